@@ -0,0 +1,143 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap::Args;
+use serde::Serialize;
+
+use octocode::config::Config;
+use octocode::indexer::api_diff::{self, ApiChange, ChangeKind};
+use octocode::indexer::git_utils::GitUtils;
+use octocode::indexer::GraphBuilder;
+
+use crate::commands::OutputFormat;
+
+#[derive(Args, Debug)]
+pub struct ApiDiffArgs {
+	/// Revisions to compare: `<rev1>..<rev2>`, or `<rev1>` to compare with HEAD
+	pub range: String,
+
+	/// Exit with an error when breaking changes are found (for release gating)
+	#[arg(long)]
+	pub check: bool,
+
+	/// Output format
+	#[arg(long, value_enum, default_value = "cli")]
+	pub format: OutputFormat,
+}
+
+#[derive(Serialize)]
+struct ApiDiffReport<'a> {
+	from: &'a str,
+	to: &'a str,
+	changes: &'a [ApiChange],
+}
+
+/// Report public symbols removed, changed or moved between two revisions,
+/// with the internal callers each change affects
+pub async fn execute(config: &Config, args: &ApiDiffArgs) -> Result<(), anyhow::Error> {
+	let current_dir = std::env::current_dir()?;
+	let repo_path = GitUtils::find_git_root(&current_dir)
+		.ok_or_else(|| anyhow::anyhow!("api-diff needs a git repository"))?;
+
+	let (from, to) = match args.range.split_once("..") {
+		Some((from, to)) => (from, if to.is_empty() { "HEAD" } else { to }),
+		None => (args.range.as_str(), "HEAD"),
+	};
+	let from_commit = GitUtils::resolve_commit(&repo_path, from)?;
+	let to_commit = GitUtils::resolve_commit(&repo_path, to)?;
+
+	let mut changes = api_diff::api_diff(&repo_path, &from_commit, &to_commit)?;
+
+	// Callers come from the current knowledge graph, i.e. the code that
+	// would break
+	if !changes.is_empty() {
+		let graph = if config.graphrag.enabled {
+			GraphBuilder::new_with_quiet(config.clone(), true)
+				.await?
+				.get_graph()
+				.await?
+		} else {
+			Default::default()
+		};
+		if graph.nodes.is_empty() && !args.format.is_json() {
+			eprintln!("Note: the GraphRAG knowledge graph is empty or disabled, so affected callers are not listed. Enable GraphRAG and run 'octocode index' first.");
+		}
+		api_diff::attach_callers(&mut changes, &graph);
+	}
+
+	if args.format.is_json() {
+		let report = ApiDiffReport {
+			from,
+			to,
+			changes: &changes,
+		};
+		println!("{}", serde_json::to_string_pretty(&report)?);
+	} else {
+		print_changes(from, to, &changes);
+	}
+
+	if args.check && !changes.is_empty() {
+		return Err(anyhow::anyhow!(
+			"{} breaking public API change(s) between {} and {}",
+			changes.len(),
+			from,
+			to
+		));
+	}
+
+	Ok(())
+}
+
+fn print_changes(from: &str, to: &str, changes: &[ApiChange]) {
+	let count = |kind: ChangeKind| changes.iter().filter(|c| c.change == kind).count();
+	println!(
+		"Public API changes {}..{}: {} removed, {} changed, {} moved",
+		from,
+		to,
+		count(ChangeKind::Removed),
+		count(ChangeKind::Changed),
+		count(ChangeKind::Moved)
+	);
+
+	for change in changes {
+		let symbol = &change.symbol;
+		println!();
+		println!(
+			"{:<8} {:<9} {}:{}  {}",
+			change.change.as_str(),
+			symbol.kind,
+			symbol.path,
+			symbol.line,
+			symbol.name
+		);
+		match (&change.new_signature, &change.new_path) {
+			(Some(new_signature), _) => {
+				println!("    - {}", symbol.signature);
+				println!("    + {}", new_signature);
+			}
+			(None, Some(new_path)) => {
+				println!("    {}", symbol.signature);
+				println!("    now in {}", new_path);
+			}
+			(None, None) => println!("    {}", symbol.signature),
+		}
+
+		if !change.affected_callers.is_empty() {
+			println!("    affected callers:");
+			for caller in &change.affected_callers {
+				println!("      {}:{}  {}", caller.path, caller.line, caller.name);
+			}
+		}
+	}
+}
@@ -0,0 +1,212 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use serde_json::json;
+
+use octocode::config::Config;
+use octocode::embedding::benchmark::{self, BenchmarkSample, ModelBenchmark};
+use octocode::embedding::count_tokens;
+use octocode::store::Store;
+
+use crate::commands::OutputFormat;
+
+#[derive(Args, Debug)]
+pub struct BenchmarkArgs {
+	#[command(subcommand)]
+	pub command: BenchmarkCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum BenchmarkCommand {
+	/// Embed a sample of indexed code with each configured model and compare speed, cost and retrieval quality
+	Embeddings {
+		/// Indexed code blocks embedded by each model
+		#[arg(long, default_value_t = 64, value_parser = clap::value_parser!(u64).range(2..=1000))]
+		sample: u64,
+
+		/// Also benchmark this model (provider:model); repeat to compare several
+		#[arg(long = "model", value_name = "PROVIDER:MODEL")]
+		models: Vec<String>,
+
+		/// Output format
+		#[arg(long, value_enum, default_value = "cli")]
+		format: OutputFormat,
+	},
+}
+
+pub async fn execute(store: &Store, config: &Config, args: &BenchmarkArgs) -> Result<()> {
+	match &args.command {
+		BenchmarkCommand::Embeddings {
+			sample,
+			models,
+			format,
+		} => embeddings(store, config, *sample as usize, models, format).await,
+	}
+}
+
+async fn embeddings(
+	store: &Store,
+	config: &Config,
+	sample_size: usize,
+	candidates: &[String],
+	format: &OutputFormat,
+) -> Result<()> {
+	let mut blocks = store.get_all_code_blocks_for_graphrag().await?;
+	if blocks.len() < 2 {
+		return Err(anyhow::anyhow!(
+			"Not enough indexed code to benchmark. Run 'octocode index' first."
+		));
+	}
+	let index_tokens = blocks
+		.iter()
+		.map(|block| count_tokens(&block.content))
+		.sum();
+
+	// Evenly spaced blocks in path order, so repeated runs compare the same sample
+	blocks.sort_by(|a, b| {
+		a.path
+			.cmp(&b.path)
+			.then_with(|| a.start_line.cmp(&b.start_line))
+	});
+	let step = (blocks.len() / sample_size).max(1);
+	let sample = BenchmarkSample::new(
+		blocks
+			.into_iter()
+			.step_by(step)
+			.take(sample_size)
+			.map(|block| block.content)
+			.collect(),
+		index_tokens,
+	);
+
+	// Every model once, with what the configuration uses it for
+	let mut models: Vec<(String, Vec<String>)> = Vec::new();
+	let configured = [
+		(&config.embedding.code_model, "code"),
+		(&config.embedding.text_model, "text"),
+	];
+	let candidates = candidates.iter().map(|model| (model, "candidate"));
+	for (model, role) in configured.into_iter().chain(candidates) {
+		match models.iter_mut().find(|(known, _)| known == model) {
+			Some((_, roles)) if !roles.iter().any(|known| known == role) => {
+				roles.push(role.to_string())
+			}
+			Some(_) => {}
+			None => models.push((model.clone(), vec![role.to_string()])),
+		}
+	}
+
+	if format.is_cli() {
+		println!(
+			"Benchmarking {} model(s) on {} code blocks ({} self-test queries)...\n",
+			models.len(),
+			sample.blocks.len(),
+			sample.queries.len()
+		);
+	}
+
+	let mut results = Vec::new();
+	let mut failed = Vec::new();
+	for (model, roles) in models {
+		// A model without credentials doesn't stop the others
+		match benchmark::run(
+			&model,
+			roles,
+			&sample,
+			config.index.embeddings_batch_size,
+			config.index.embeddings_max_tokens_per_batch,
+		)
+		.await
+		{
+			Ok(result) => results.push(result),
+			Err(e) => failed.push((model, e.to_string())),
+		}
+	}
+
+	if format.is_json() {
+		let failed: Vec<_> = failed
+			.iter()
+			.map(|(model, error)| json!({"model": model, "error": error}))
+			.collect();
+		println!(
+			"{}",
+			serde_json::to_string_pretty(&json!({
+				"sample_blocks": sample.blocks.len(),
+				"queries": sample.queries.len(),
+				"index_tokens": sample.index_tokens,
+				"models": results,
+				"failed": failed,
+			}))?
+		);
+	} else {
+		print_results(&results, &failed, sample.index_tokens);
+	}
+
+	if results.is_empty() {
+		return Err(anyhow::anyhow!("No model could be benchmarked"));
+	}
+	Ok(())
+}
+
+fn print_results(results: &[ModelBenchmark], failed: &[(String, String)], index_tokens: usize) {
+	for result in results {
+		println!("{} ({})", result.model, result.roles.join(", "));
+		println!(
+			"  Latency:    {} ms total, {} ms median batch, {} ms p95 batch ({} batches)",
+			result.total_ms, result.p50_batch_ms, result.p95_batch_ms, result.batches
+		);
+		println!(
+			"  Throughput: {:.1} blocks/s, {:.0} tokens/s, {} dimensions",
+			result.blocks_per_second, result.tokens_per_second, result.dimension
+		);
+		match (
+			result.usd_per_million_tokens,
+			result.estimated_index_cost_usd,
+		) {
+			(Some(price), Some(cost)) => println!(
+				"  Cost:       ${:.3} per 1M tokens, ~${:.4} to embed this index ({} tokens)",
+				price, cost, index_tokens
+			),
+			_ => println!("  Cost:       unknown price"),
+		}
+		println!(
+			"  Retrieval:  recall@1 {:.2}, recall@5 {:.2}, MRR {:.3} ({} queries)\n",
+			result.quality.recall_at_1,
+			result.quality.recall_at_5,
+			result.quality.mrr,
+			result.quality.queries
+		);
+	}
+	for (model, error) in failed {
+		println!("{}\n  ❌ {}\n", model, error);
+	}
+
+	if results.len() > 1 {
+		let by = |key: fn(&ModelBenchmark) -> f64| {
+			results
+				.iter()
+				.max_by(|a, b| {
+					key(a)
+						.partial_cmp(&key(b))
+						.unwrap_or(std::cmp::Ordering::Equal)
+				})
+				.map(|result| result.model.as_str())
+				.unwrap_or_default()
+		};
+		println!("Best retrieval: {}", by(|result| result.quality.mrr));
+		println!("Fastest:        {}", by(|result| result.tokens_per_second));
+	}
+}
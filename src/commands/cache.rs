@@ -0,0 +1,124 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap::{Args, Subcommand};
+
+use octocode::config::Config;
+use octocode::index_cache::IndexCache;
+use octocode::indexer::GitUtils;
+use octocode::store::Store;
+
+#[derive(Args, Debug)]
+pub struct CacheArgs {
+	#[command(subcommand)]
+	pub command: CacheCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CacheCommand {
+	/// Upload index blocks the remote cache does not have yet
+	Push {
+		/// Name to publish the index under (default: the last indexed commit)
+		#[arg(long = "ref")]
+		reference: Option<String>,
+	},
+
+	/// Download index blocks missing locally from the remote cache
+	Pull {
+		/// Manifest to pull (default: HEAD, falling back to the latest push)
+		#[arg(long = "ref")]
+		reference: Option<String>,
+	},
+
+	/// Compare the local index with the latest push
+	Status,
+}
+
+/// Share the index through the remote cache configured in `[cache]`
+pub async fn execute(store: &Store, config: &Config, args: &CacheArgs) -> anyhow::Result<()> {
+	let cache = IndexCache::from_config(config)?;
+
+	match &args.command {
+		CacheCommand::Push { reference } => {
+			let commit = match store.get_last_commit_hash().await? {
+				Some(commit) => commit,
+				None => current_commit().unwrap_or_default(),
+			};
+			let reference = reference.clone().unwrap_or_else(|| commit.clone());
+			if reference.is_empty() {
+				return Err(anyhow::anyhow!(
+					"Cannot name the manifest outside a git repository: pass --ref"
+				));
+			}
+
+			println!("Pushing index to remote cache ({})...", reference);
+			let summary = cache.push(store, &reference, &commit).await?;
+			println!(
+				"Pushed manifest '{}': {} blocks, {} uploaded, {} already cached",
+				summary.manifest,
+				summary.blocks,
+				summary.uploaded,
+				summary.blocks.saturating_sub(summary.uploaded)
+			);
+		}
+		CacheCommand::Pull { reference } => {
+			let reference = reference.clone().or_else(current_commit);
+
+			println!("Pulling index from remote cache...");
+			let summary = cache.pull(store, reference.as_deref()).await?;
+			println!(
+				"Pulled manifest '{}': {} blocks, {} downloaded, {} stale blocks removed",
+				summary.manifest, summary.blocks, summary.downloaded, summary.removed
+			);
+			if !summary.commit.is_empty() {
+				println!(
+					"Index now matches commit {}; run 'octocode index' to pick up later changes.",
+					short_commit(&summary.commit)
+				);
+			}
+		}
+		CacheCommand::Status => {
+			let status = cache.status(store).await?;
+			println!("Namespace: {}", status.namespace);
+			println!("Local blocks: {}", status.local_blocks);
+			match status.remote {
+				Some(remote) => {
+					println!(
+						"Latest push: commit {}, {} blocks",
+						short_commit(&remote.commit),
+						remote.blocks
+					);
+					println!("  Missing locally (pull): {}", remote.missing_locally);
+					println!("  Missing remotely (push): {}", remote.missing_remotely);
+				}
+				None => println!("Latest push: none"),
+			}
+		}
+	}
+
+	Ok(())
+}
+
+fn current_commit() -> Option<String> {
+	let current_dir = std::env::current_dir().ok()?;
+	GitUtils::get_current_commit_hash(&current_dir).ok()
+}
+
+fn short_commit(commit: &str) -> &str {
+	if commit.is_empty() {
+		"unknown"
+	} else {
+		&commit[..commit.len().min(12)]
+	}
+}
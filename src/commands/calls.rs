@@ -0,0 +1,139 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap::Args;
+use serde::Serialize;
+
+use octocode::config::Config;
+use octocode::indexer::graphrag::calls::{self, CallSite, FunctionRef};
+use octocode::indexer::GraphBuilder;
+
+use crate::commands::OutputFormat;
+
+#[derive(Args, Debug)]
+pub struct CallsArgs {
+	/// Function name, `Type::method`, or `path:function` to pick one definition
+	pub target: String,
+
+	/// Only show who calls the function
+	#[arg(long, conflicts_with = "callees")]
+	pub callers: bool,
+
+	/// Only show what the function calls
+	#[arg(long)]
+	pub callees: bool,
+
+	/// How many levels of the call chain to follow
+	#[arg(long, short = 'd', default_value = "1")]
+	pub depth: usize,
+
+	/// Output format
+	#[arg(long, value_enum, default_value = "cli")]
+	pub format: OutputFormat,
+}
+
+#[derive(Serialize)]
+struct CallsReport<'a> {
+	function: &'a FunctionRef,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	callers: Option<Vec<CallSite>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	callees: Option<Vec<CallSite>>,
+}
+
+/// Answer who calls a function and what it calls, from the functions of the
+/// GraphRAG knowledge graph
+pub async fn execute(config: &Config, args: &CallsArgs) -> Result<(), anyhow::Error> {
+	if args.depth == 0 {
+		return Err(anyhow::anyhow!("--depth must be at least 1"));
+	}
+	if !config.graphrag.enabled {
+		return Err(anyhow::anyhow!(
+			"Calls are read from the GraphRAG knowledge graph. Enable it with 'octocode config --graphrag-enable true' and run 'octocode index'."
+		));
+	}
+
+	let graph = GraphBuilder::new_with_quiet(config.clone(), true)
+		.await?
+		.get_graph()
+		.await?;
+	if graph.nodes.is_empty() {
+		return Err(anyhow::anyhow!(
+			"The GraphRAG knowledge graph is empty. Run 'octocode index' first."
+		));
+	}
+
+	let functions = calls::find_functions(&graph, &args.target);
+	if functions.is_empty() {
+		return Err(anyhow::anyhow!(
+			"No function definition found for '{}'",
+			args.target
+		));
+	}
+
+	let reports: Vec<CallsReport> = functions
+		.iter()
+		.map(|function| CallsReport {
+			function,
+			callers: (!args.callees).then(|| calls::callers_within(&graph, function, args.depth)),
+			callees: (!args.callers).then(|| calls::callees_within(&graph, function, args.depth)),
+		})
+		.collect();
+
+	if args.format.is_json() {
+		println!("{}", serde_json::to_string_pretty(&reports)?);
+	} else {
+		for (index, report) in reports.iter().enumerate() {
+			if index > 0 {
+				println!();
+			}
+			print_report(report);
+		}
+	}
+
+	Ok(())
+}
+
+fn print_report(report: &CallsReport) {
+	let function = report.function;
+	println!(
+		"{}  {}:{}-{}",
+		function.name, function.path, function.line, function.end_line
+	);
+
+	if let Some(callers) = &report.callers {
+		println!("  Callers:");
+		print_sites(callers);
+	}
+	if let Some(callees) = &report.callees {
+		println!("  Callees:");
+		print_sites(callees);
+	}
+}
+
+/// One function per line at its definition, indented by depth
+fn print_sites(sites: &[CallSite]) {
+	if sites.is_empty() {
+		println!("    (none found)");
+	}
+	for site in sites {
+		println!(
+			"{}{}:{}  {}",
+			"  ".repeat(site.depth + 1),
+			site.function.path,
+			site.function.line,
+			site.function.name
+		);
+	}
+}
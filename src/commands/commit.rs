@@ -17,9 +17,18 @@ use clap::Args;
 use std::io::{self, Write};
 use std::process::Command;
 
-use octocode::config::Config;
+use octocode::config::{CommitStyle, Config};
 use octocode::indexer::git_utils::GitUtils;
 
+use super::house_style;
+
+mod convention;
+
+use convention::Overrides;
+
+/// Message used when the LLM cannot be reached
+const FALLBACK_DESCRIPTION: &str = "update files";
+
 #[derive(Args, Debug)]
 pub struct CommitArgs {
 	/// Add all changes before committing
@@ -34,6 +43,15 @@ pub struct CommitArgs {
 	#[arg(short, long)]
 	pub yes: bool,
 
+	/// Commit type to use, e.g. fix (implies a conventional message)
+	#[arg(long = "type", value_name = "TYPE")]
+	pub commit_type: Option<String>,
+
+	/// Commit scope to use instead of the one derived from changed paths
+	/// (implies a conventional message)
+	#[arg(long)]
+	pub scope: Option<String>,
+
 	/// Skip pre-commit hooks and commit-msg hooks
 	/// Note: Pre-commit hooks run automatically if pre-commit binary and config are detected
 	#[arg(short, long)]
@@ -136,10 +154,24 @@ pub async fn execute(config: &Config, args: &CommitArgs) -> Result<()> {
 		}
 	}
 
+	if let Some(commit_type) = &args.commit_type {
+		if !config.commit.types.contains(commit_type) {
+			return Err(anyhow::anyhow!(
+				"❌ Unknown commit type '{}'. Allowed types: {}",
+				commit_type,
+				config.commit.types.join(", ")
+			));
+		}
+	}
+	let overrides = Overrides {
+		commit_type: args.commit_type.clone(),
+		scope: args.scope.clone(),
+	};
+
 	// Generate commit message using AI (always, but with optional context)
 	println!("\n🤖 Generating commit message...");
 	let commit_message =
-		generate_commit_message(&current_dir, config, args.message.as_deref()).await?;
+		generate_commit_message(&current_dir, config, args.message.as_deref(), &overrides).await?;
 
 	println!("\n📝 Generated commit message:");
 	println!("═══════════════════════════════════");
@@ -198,6 +230,7 @@ async fn generate_commit_message(
 	repo_path: &std::path::Path,
 	config: &Config,
 	extra_context: Option<&str>,
+	overrides: &Overrides,
 ) -> Result<String> {
 	// Get the diff of staged changes
 	let output = Command::new("git")
@@ -270,8 +303,62 @@ async fn generate_commit_message(
 		""
 	};
 
+	// Conventional style, or a type or scope forced on the command line, means
+	// the message is checked and regenerated until it conforms
+	let commit_config = &config.commit;
+	let enforce = commit_config.style == CommitStyle::Conventional
+		|| overrides.commit_type.is_some()
+		|| overrides.scope.is_some();
+	let derived_scope = convention::derive_scope(&staged_files, &commit_config.scopes);
+
+	let mut convention_section = String::new();
+	if enforce {
+		convention_section.push_str("\n\nPROJECT CONVENTIONS (MANDATORY):");
+		if let Some(commit_type) = &overrides.commit_type {
+			convention_section.push_str(&format!("\n- Type MUST be '{}'", commit_type));
+		}
+		if let Some(scope) = &overrides.scope {
+			convention_section.push_str(&format!("\n- Scope MUST be '{}'", scope));
+		} else if let Some(scope) = &derived_scope {
+			convention_section.push_str(&format!(
+				"\n- Use scope '{}' (derived from the changed paths) unless the change is clearly about something else",
+				scope
+			));
+		} else {
+			let candidates: Vec<String> =
+				convention::scope_counts(&staged_files, &commit_config.scopes)
+					.into_iter()
+					.map(|(scope, count)| format!("{} ({} files)", scope, count))
+					.collect();
+			if !candidates.is_empty() {
+				convention_section.push_str(&format!(
+					"\n- Scopes of the changed paths: {}",
+					candidates.join(", ")
+				));
+			}
+			if commit_config.require_scope {
+				convention_section.push_str("\n- A scope is REQUIRED");
+			} else {
+				convention_section
+					.push_str("\n- Omit the scope when no single one covers the change");
+			}
+		}
+		convention_section.push_str(&format!(
+			"\n- Subject line at most {} characters, no trailing period\n\
+			- Do not add emoji; they are added automatically",
+			commit_config.max_subject_length
+		));
+	}
+
+	// House style the project already follows
+	let house_style_section = house_style::prompt_section(
+		&house_style::convention_memories(repo_path, config, commit_config.convention_memories)
+			.await,
+		&house_style::recent_commit_subjects(repo_path, commit_config.history_examples),
+	);
+
 	// Prepare the enhanced prompt for the LLM
-	let prompt = format!(
+	let base_prompt = format!(
 		"Analyze this Git diff and create an appropriate commit message. Be specific and concise.\n\n\
 		STRICT FORMATTING RULES:\n\
 		- Format: type(scope): description (under 50 chars)\n\
@@ -291,7 +378,7 @@ async fn generate_commit_message(
 		- style: CODE formatting, whitespace, missing semicolons (no logic changes)\n\
 		- chore: MAINTENANCE tasks (dependencies, build, tooling, config)\n\
 		- ci: CONTINUOUS integration changes (workflows, pipelines)\n\
-		- build: BUILD system changes (Cargo.toml, package.json, Makefile){}\n\n\
+		- build: BUILD system changes (Cargo.toml, package.json, Makefile){}{}{}\n\n\
 		FEATURE vs FIX DECISION GUIDE:\n\
 		- If code was working but had bugs/errors → use 'fix' (even for new features with bugs)\n\
 		- If adding completely new functionality that didn't exist → use 'feat'\n\
@@ -323,6 +410,8 @@ async fn generate_commit_message(
 		Generate commit message:",
 		guidance_section,
 		docs_restriction,
+		convention_section,
+		house_style_section,
 		file_count,
 		additions,
 		deletions,
@@ -335,49 +424,80 @@ async fn generate_commit_message(
 		}
 	);
 
-	// Call the LLM using existing infrastructure
-	match call_llm_for_commit_message(&prompt, config).await {
-		Ok(message) => {
-			// Clean up the response but preserve multi-line structure
-			let cleaned = message
-				.trim()
-				.trim_matches('"') // Remove quotes if present
-				.trim();
-
-			// Validate the message
-			if cleaned.is_empty() {
-				Ok("chore: update files".to_string())
-			} else {
-				// Split into lines and validate subject line length
-				let lines: Vec<&str> = cleaned.lines().collect();
-				if let Some(subject) = lines.first() {
-					let subject = subject.trim();
-					if subject.len() > 72 {
-						// Truncate subject if too long but keep body if present
-						let truncated_subject = if subject.chars().count() > 69 {
-							let truncated: String = subject.chars().take(69).collect();
-							format!("{}...", truncated)
-						} else {
-							format!("{}...", subject)
-						};
-						if lines.len() > 1 {
-							let body = lines[1..].join("\n");
-							Ok(format!("{}\n{}", truncated_subject, body))
-						} else {
-							Ok(truncated_subject)
-						}
-					} else {
-						Ok(cleaned.to_string())
-					}
-				} else {
-					Ok("chore: update files".to_string())
-				}
+	let mut prompt = base_prompt.clone();
+	let mut attempt = 0;
+	loop {
+		// Call the LLM using existing infrastructure
+		let (message, llm_failed) = match call_llm_for_commit_message(&prompt, config).await {
+			Ok(message) => {
+				// Clean up the response but preserve multi-line structure
+				let cleaned = message
+					.trim()
+					.trim_matches('"') // Remove quotes if present
+					.trim();
+				(cleaned.to_string(), false)
+			}
+			Err(e) => {
+				eprintln!("Warning: LLM call failed ({}), using fallback", e);
+				(String::new(), true)
+			}
+		};
+		let message = if message.is_empty() {
+			match &derived_scope {
+				Some(scope) if enforce => format!("chore({}): {}", scope, FALLBACK_DESCRIPTION),
+				_ => format!("chore: {}", FALLBACK_DESCRIPTION),
 			}
+		} else {
+			message
+		};
+
+		if !enforce {
+			return Ok(truncate_subject(&message));
 		}
-		Err(e) => {
-			eprintln!("Warning: LLM call failed ({}), using fallback", e);
-			Ok("chore: update files".to_string())
+
+		let message = convention::apply_overrides(&message, commit_config, overrides);
+		let problems = convention::validate(&message, commit_config, overrides);
+		if problems.is_empty() {
+			return Ok(message);
 		}
+		if llm_failed || attempt >= commit_config.max_retries {
+			return Err(anyhow::anyhow!(
+				"❌ Generated commit message does not follow the commit conventions:\n  - {}\n\n{}",
+				problems.join("\n  - "),
+				message
+			));
+		}
+		attempt += 1;
+		eprintln!(
+			"⚠️  Generated message breaks the commit conventions ({}), regenerating...",
+			problems.join("; ")
+		);
+		prompt = format!(
+			"{}\n\nYour previous message was rejected:\n```\n{}\n```\n\
+			Problems:\n- {}\n\n\
+			Write a corrected commit message:",
+			base_prompt,
+			message,
+			problems.join("\n- ")
+		);
+	}
+}
+
+/// Shorten a subject line over 72 characters, keeping the body
+fn truncate_subject(message: &str) -> String {
+	let lines: Vec<&str> = message.lines().collect();
+	let Some(subject) = lines.first().map(|subject| subject.trim()) else {
+		return format!("chore: {}", FALLBACK_DESCRIPTION);
+	};
+	if subject.len() <= 72 {
+		return message.to_string();
+	}
+	let truncated: String = subject.chars().take(69).collect();
+	let truncated_subject = format!("{}...", truncated);
+	if lines.len() > 1 {
+		format!("{}\n{}", truncated_subject, lines[1..].join("\n"))
+	} else {
+		truncated_subject
 	}
 }
 
@@ -0,0 +1,340 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Conventional commit headers: scopes from changed paths, gitmoji, and the
+//! checks a generated message must pass before it is committed
+
+use std::collections::BTreeMap;
+
+use octocode::config::CommitConfig;
+
+/// Directories whose children name the component rather than themselves
+const SCOPE_ROOTS: &[&str] = &[
+	"src", "lib", "crates", "packages", "apps", "pkg", "cmd", "internal",
+];
+
+/// Gitmoji of the standard conventional types
+const GITMOJI: &[(&str, &str)] = &[
+	("feat", "✨"),
+	("fix", "🐛"),
+	("docs", "📝"),
+	("style", "🎨"),
+	("refactor", "♻️"),
+	("test", "✅"),
+	("chore", "🔧"),
+	("perf", "⚡️"),
+	("ci", "👷"),
+	("build", "📦️"),
+	("revert", "⏪️"),
+];
+
+/// `--type` and `--scope` given on the command line
+#[derive(Debug, Default, Clone)]
+pub struct Overrides {
+	pub commit_type: Option<String>,
+	pub scope: Option<String>,
+}
+
+/// Parsed `type(scope)!: description` subject line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Header {
+	pub commit_type: String,
+	pub scope: Option<String>,
+	pub breaking: bool,
+	pub description: String,
+}
+
+impl std::fmt::Display for Header {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", self.commit_type)?;
+		if let Some(scope) = &self.scope {
+			write!(f, "({})", scope)?;
+		}
+		if self.breaking {
+			write!(f, "!")?;
+		}
+		write!(f, ": {}", self.description)
+	}
+}
+
+pub fn gitmoji_for(commit_type: &str) -> Option<&'static str> {
+	GITMOJI
+		.iter()
+		.find(|(name, _)| *name == commit_type)
+		.map(|(_, emoji)| *emoji)
+}
+
+/// Drop a leading gitmoji (or `:shortcode:`) and the space after it
+fn strip_gitmoji(subject: &str) -> &str {
+	let subject = subject.trim_start();
+	if let Some(rest) = subject.strip_prefix(':') {
+		if let Some((code, rest)) = rest.split_once(':') {
+			if !code.is_empty() && !code.contains(char::is_whitespace) {
+				return rest.trim_start();
+			}
+		}
+	}
+	subject.trim_start_matches(|c: char| !c.is_ascii() || c.is_whitespace())
+}
+
+/// Parse a subject line, ignoring a leading gitmoji
+pub fn parse_header(subject: &str) -> Option<Header> {
+	let subject = strip_gitmoji(subject);
+	let (prefix, description) = subject.split_once(':')?;
+	let (prefix, breaking) = match prefix.strip_suffix('!') {
+		Some(prefix) => (prefix, true),
+		None => (prefix, false),
+	};
+	let (commit_type, scope) = match prefix.split_once('(') {
+		Some((commit_type, scope)) => (commit_type, Some(scope.strip_suffix(')')?)),
+		None => (prefix, None),
+	};
+	let is_word = |part: &str| {
+		!part.is_empty()
+			&& part
+				.chars()
+				.all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '/' | '.'))
+	};
+	if !is_word(commit_type) || scope.is_some_and(|scope| !is_word(scope)) {
+		return None;
+	}
+	Some(Header {
+		commit_type: commit_type.to_string(),
+		scope: scope.map(str::to_string),
+		breaking,
+		description: description.trim().to_string(),
+	})
+}
+
+/// Scope of one changed path: the longest matching configured prefix, else the
+/// first directory below a source root, else the top-level directory. Files
+/// directly under a source root use their stem; files at the root have none.
+fn scope_of_path(path: &str, scopes: &BTreeMap<String, String>) -> Option<String> {
+	let configured = scopes
+		.iter()
+		.filter(|(prefix, _)| {
+			let prefix = prefix.trim_end_matches('/');
+			path == prefix
+				|| path
+					.strip_prefix(prefix)
+					.is_some_and(|rest| rest.starts_with('/'))
+		})
+		.max_by_key(|(prefix, _)| prefix.len());
+	if let Some((_, scope)) = configured {
+		return Some(scope.clone());
+	}
+
+	let parts: Vec<&str> = path.split('/').collect();
+	match parts.as_slice() {
+		[_] => None,
+		[root, file] if SCOPE_ROOTS.contains(root) => {
+			let stem = file.split('.').next().unwrap_or(file);
+			(!matches!(stem, "main" | "lib" | "mod" | "index")).then(|| stem.to_string())
+		}
+		[root, directory, ..] if SCOPE_ROOTS.contains(root) => Some(directory.to_string()),
+		[directory, ..] => Some(directory.trim_start_matches('.').to_string()),
+		[] => None,
+	}
+	.filter(|scope| !scope.is_empty())
+}
+
+/// Scopes of the changed paths with the number of files in each, most files first
+pub fn scope_counts(files: &[String], scopes: &BTreeMap<String, String>) -> Vec<(String, usize)> {
+	let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+	for file in files {
+		if let Some(scope) = scope_of_path(file, scopes) {
+			*counts.entry(scope).or_default() += 1;
+		}
+	}
+	let mut counts: Vec<(String, usize)> = counts.into_iter().collect();
+	counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+	counts
+}
+
+/// The scope covering most of the changed files, when one covers more than half
+pub fn derive_scope(files: &[String], scopes: &BTreeMap<String, String>) -> Option<String> {
+	let counts = scope_counts(files, scopes);
+	let (scope, count) = counts.first()?;
+	(*count * 2 > files.len()).then(|| scope.clone())
+}
+
+/// Rewrite the subject of a message with the forced type and scope and, when
+/// configured, the gitmoji of its type. Subjects that do not parse are left
+/// alone for `validate` to report.
+pub fn apply_overrides(message: &str, config: &CommitConfig, overrides: &Overrides) -> String {
+	let (subject, body) = match message.split_once('\n') {
+		Some((subject, body)) => (subject, Some(body)),
+		None => (message, None),
+	};
+	let Some(mut header) = parse_header(subject) else {
+		return message.to_string();
+	};
+	if let Some(commit_type) = &overrides.commit_type {
+		header.commit_type = commit_type.clone();
+	}
+	if let Some(scope) = &overrides.scope {
+		header.scope = Some(scope.clone());
+	}
+	let mut subject = header.to_string();
+	if config.gitmoji {
+		if let Some(emoji) = gitmoji_for(&header.commit_type) {
+			subject = format!("{} {}", emoji, subject);
+		}
+	}
+	match body {
+		Some(body) => format!("{}\n{}", subject, body),
+		None => subject,
+	}
+}
+
+/// Problems that keep a message from being committed; empty when it conforms
+pub fn validate(message: &str, config: &CommitConfig, overrides: &Overrides) -> Vec<String> {
+	let mut problems = Vec::new();
+	let mut lines = message.lines();
+	let subject = lines.next().unwrap_or("").trim();
+	if subject.is_empty() {
+		return vec!["the message is empty".to_string()];
+	}
+	if lines.next().is_some_and(|line| !line.trim().is_empty()) {
+		problems.push("the subject must be followed by a blank line before the body".to_string());
+	}
+	let length = subject.chars().count();
+	if length > config.max_subject_length {
+		problems.push(format!(
+			"the subject is {} characters, the limit is {}",
+			length, config.max_subject_length
+		));
+	}
+
+	let Some(header) = parse_header(subject) else {
+		problems.push("the subject is not in the form type(scope): description".to_string());
+		return problems;
+	};
+	if !config.types.contains(&header.commit_type) {
+		problems.push(format!(
+			"type '{}' is not one of: {}",
+			header.commit_type,
+			config.types.join(", ")
+		));
+	}
+	if let Some(commit_type) = &overrides.commit_type {
+		if &header.commit_type != commit_type {
+			problems.push(format!("the type must be '{}'", commit_type));
+		}
+	}
+	match (&header.scope, &overrides.scope) {
+		(scope, Some(forced)) if scope.as_ref() != Some(forced) => {
+			problems.push(format!("the scope must be '{}'", forced));
+		}
+		(None, None) if config.require_scope => {
+			problems.push("a scope is required".to_string());
+		}
+		_ => {}
+	}
+	if header.description.is_empty() {
+		problems.push("the description is empty".to_string());
+	} else if header.description.ends_with('.') {
+		problems.push("the description must not end with a period".to_string());
+	}
+	problems
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse_header() {
+		let header = parse_header("✨ feat(mcp)!: add TLS support").unwrap();
+		assert_eq!(header.commit_type, "feat");
+		assert_eq!(header.scope.as_deref(), Some("mcp"));
+		assert!(header.breaking);
+		assert_eq!(header.description, "add TLS support");
+		assert_eq!(header.to_string(), "feat(mcp)!: add TLS support");
+
+		assert_eq!(
+			parse_header(":bug: fix: handle empty diff")
+				.unwrap()
+				.commit_type,
+			"fix"
+		);
+		assert!(parse_header("Add TLS support").is_none());
+		assert!(parse_header("feat(mcp: add TLS").is_none());
+		assert!(parse_header("new feature: tls").is_none());
+	}
+
+	#[test]
+	fn test_derive_scope() {
+		let mut scopes = BTreeMap::new();
+		scopes.insert("src/indexer/graphrag".to_string(), "graphrag".to_string());
+		let files =
+			|paths: &[&str]| -> Vec<String> { paths.iter().map(|p| p.to_string()).collect() };
+
+		assert_eq!(
+			derive_scope(
+				&files(&["src/indexer/mod.rs", "src/indexer/languages/rust.rs"]),
+				&scopes
+			),
+			Some("indexer".to_string())
+		);
+		assert_eq!(
+			derive_scope(&files(&["src/indexer/graphrag/ai.rs"]), &scopes),
+			Some("graphrag".to_string())
+		);
+		assert_eq!(
+			derive_scope(
+				&files(&["src/config.rs", "config-templates/default.toml"]),
+				&scopes
+			),
+			None
+		);
+		assert_eq!(
+			derive_scope(
+				&files(&["src/config.rs", "src/config.rs.orig", "Cargo.toml"]),
+				&scopes
+			),
+			Some("config".to_string())
+		);
+		assert_eq!(derive_scope(&files(&["src/main.rs"]), &scopes), None);
+		assert_eq!(
+			derive_scope(&files(&[".github/workflows/ci.yml"]), &scopes),
+			Some("github".to_string())
+		);
+	}
+
+	#[test]
+	fn test_overrides_and_validation() {
+		let config = CommitConfig {
+			require_scope: true,
+			gitmoji: true,
+			..Default::default()
+		};
+		let overrides = Overrides {
+			commit_type: Some("fix".to_string()),
+			scope: Some("cli".to_string()),
+		};
+		let message = apply_overrides("feat: add flag\n\n- details", &config, &overrides);
+		assert_eq!(message, "🐛 fix(cli): add flag\n\n- details");
+		assert!(validate(&message, &config, &overrides).is_empty());
+
+		let problems = validate("feat: Added a flag.", &config, &overrides);
+		assert_eq!(problems.len(), 3);
+		assert!(validate("update stuff", &config, &Overrides::default())[0].contains("type(scope)"));
+		assert_eq!(
+			validate("chore(x): a\nbody", &config, &Overrides::default()),
+			vec!["the subject must be followed by a blank line before the body"]
+		);
+		assert!(!validate("wip(x): a", &config, &Overrides::default()).is_empty());
+	}
+}
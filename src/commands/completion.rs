@@ -0,0 +1,222 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Shell completion scripts, and the values they complete from the live index
+//!
+//! The generated bash, zsh and fish scripts call `octocode __complete <kind>
+//! <prefix>` for the values of `--symbol`, `--language`, the memory tag flags
+//! and `--namespace`, which prints one candidate per line.
+
+use anyhow::Result;
+use clap::{Args, Command, ValueEnum};
+use clap_complete::{generate, Shell};
+
+use octocode::storage;
+use octocode::store::completion::{self, CompletionSource};
+
+/// Most candidates printed for one completion
+const MAX_CANDIDATES: usize = 500;
+
+/// Flags completed dynamically: long name, value name and what they list
+const DYNAMIC_FLAGS: &[(&str, &str, CompletionKind)] = &[
+	("symbol", "SYMBOL", CompletionKind::Symbols),
+	("language", "LANGUAGE", CompletionKind::Languages),
+	("tags", "TAGS", CompletionKind::Tags),
+	("add-tags", "ADD_TAGS", CompletionKind::Tags),
+	("remove-tags", "REMOVE_TAGS", CompletionKind::Tags),
+	("namespace", "NAMESPACE", CompletionKind::Namespaces),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CompletionKind {
+	/// Symbols declared in indexed code
+	Symbols,
+	/// Languages of indexed code
+	Languages,
+	/// Memory tags; completes the last item of a comma-separated list
+	Tags,
+	/// Memory namespaces
+	Namespaces,
+}
+
+impl CompletionKind {
+	fn source(self) -> CompletionSource {
+		match self {
+			CompletionKind::Symbols => CompletionSource::Symbols,
+			CompletionKind::Languages => CompletionSource::Languages,
+			CompletionKind::Tags => CompletionSource::Tags,
+			CompletionKind::Namespaces => CompletionSource::Namespaces,
+		}
+	}
+
+	fn name(self) -> &'static str {
+		match self {
+			CompletionKind::Symbols => "symbols",
+			CompletionKind::Languages => "languages",
+			CompletionKind::Tags => "tags",
+			CompletionKind::Namespaces => "namespaces",
+		}
+	}
+}
+
+#[derive(Args, Debug)]
+pub struct CompleteArgs {
+	/// What to complete
+	#[arg(value_enum)]
+	pub kind: CompletionKind,
+
+	/// Text typed so far
+	#[arg(default_value = "", allow_hyphen_values = true)]
+	pub prefix: String,
+}
+
+/// Print the candidates for a value being completed. Completion must never
+/// break the shell, so a missing or unreadable index prints nothing.
+pub async fn execute(args: &CompleteArgs) -> Result<()> {
+	let current_dir = std::env::current_dir()?;
+	let db_path = storage::get_project_database_path(&current_dir)?;
+
+	// Tags are given as a comma-separated list; complete its last item
+	let (listed, prefix) = match args.kind {
+		CompletionKind::Tags => split_list(&args.prefix),
+		_ => ("", args.prefix.as_str()),
+	};
+	let values = completion::values(&db_path, args.kind.source(), prefix)
+		.await
+		.unwrap_or_default();
+
+	let already: Vec<&str> = listed.split(',').map(str::trim).collect();
+	for value in values
+		.iter()
+		.filter(|value| !already.contains(&value.as_str()))
+		.take(MAX_CANDIDATES)
+	{
+		println!("{}{}", listed, value);
+	}
+	Ok(())
+}
+
+/// Items already typed (with their trailing comma) and the one being typed
+fn split_list(text: &str) -> (&str, &str) {
+	match text.rfind(',') {
+		Some(position) => text.split_at(position + 1),
+		None => ("", text),
+	}
+}
+
+/// Print the completion script of `shell`, with dynamic completion of index
+/// values for the shells that support it
+pub fn print_script(shell: Shell, command: &mut Command) {
+	let name = command.get_name().to_string();
+	let mut script = Vec::new();
+	generate(shell, command, name.clone(), &mut script);
+	let script = String::from_utf8_lossy(&script);
+	print!("{}", with_dynamic_values(shell, &script, &name));
+}
+
+fn with_dynamic_values(shell: Shell, script: &str, name: &str) -> String {
+	match shell {
+		Shell::Bash => {
+			let cases: Vec<String> = DYNAMIC_FLAGS
+				.iter()
+				.map(|(flag, _, kind)| format!("        --{}) kind={} ;;", flag, kind.name()))
+				.collect();
+			let wrapper = format!(
+				r#"
+_{name}_dynamic() {{
+    local cur="${{COMP_WORDS[COMP_CWORD]}}"
+    local prev="${{COMP_WORDS[COMP_CWORD-1]}}"
+    local kind
+    case "${{prev}}" in
+{cases}
+        *) _{name} "$@"; return ;;
+    esac
+    local IFS=$'\n'
+    COMPREPLY=( $({name} __complete "${{kind}}" "${{cur}}" 2>/dev/null) )
+}}
+"#,
+				name = name,
+				cases = cases.join("\n")
+			);
+			// Register the wrapper in place of the generated function
+			let script = script.replace(
+				&format!("complete -F _{} ", name),
+				&format!("complete -F _{}_dynamic ", name),
+			);
+			format!("{}{}", script, wrapper)
+		}
+		Shell::Zsh => {
+			let mut output = String::new();
+			for line in script.lines() {
+				let mut line = line.to_string();
+				for (_, value_name, kind) in DYNAMIC_FLAGS {
+					line = line.replace(
+						&format!(":{}:_default'", value_name),
+						&format!(":{}:_{}_values {}'", value_name, name, kind.name()),
+					);
+				}
+				// The helper must exist before the script first runs `_name`
+				if line.starts_with(&format!("if [ \"$funcstack[1]\" = \"_{}\" ]", name)) {
+					output.push_str(&format!(
+						"_{name}_values() {{\n    local -a values\n    values=(${{(f)\"$({name} __complete $1 \"$PREFIX\" 2>/dev/null)\"}})\n    compadd -a values\n}}\n\n",
+						name = name
+					));
+				}
+				output.push_str(&line);
+				output.push('\n');
+			}
+			output
+		}
+		Shell::Fish => {
+			let mut output = script.to_string();
+			for (flag, _, kind) in DYNAMIC_FLAGS {
+				output.push_str(&format!(
+					"complete -c {name} -l {flag} -r -f -a '({name} __complete {kind} (commandline -ct))'\n",
+					name = name,
+					flag = flag,
+					kind = kind.name()
+				));
+			}
+			output
+		}
+		_ => script.to_string(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_split_list() {
+		assert_eq!(split_list("bug"), ("", "bug"));
+		assert_eq!(split_list("auth,bu"), ("auth,", "bu"));
+		assert_eq!(split_list("auth,"), ("auth,", ""));
+	}
+
+	#[test]
+	fn test_dynamic_values() {
+		let zsh = "'--symbol=[Only symbols]:SYMBOL:_default' \\\n'--limit=[Limit]:LIMIT:_default' \\\nif [ \"$funcstack[1]\" = \"_octocode\" ]; then\n";
+		let script = with_dynamic_values(Shell::Zsh, zsh, "octocode");
+		assert!(script.contains(":SYMBOL:_octocode_values symbols'"));
+		assert!(script.contains(":LIMIT:_default'"));
+		assert!(script.find("_octocode_values() {").unwrap() < script.find("if [").unwrap());
+
+		let bash =
+			"_octocode() {\n}\n    complete -F _octocode -o bashdefault -o default octocode\n";
+		let script = with_dynamic_values(Shell::Bash, bash, "octocode");
+		assert!(script.contains("complete -F _octocode_dynamic -o bashdefault"));
+		assert!(script.contains("--add-tags) kind=tags ;;"));
+	}
+}
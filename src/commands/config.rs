@@ -55,12 +55,29 @@ pub struct ConfigArgs {
 	#[arg(long)]
 	pub show: bool,
 
+	/// Show every configuration value with the layer it came from: default,
+	/// user config, project .octocode/config.toml or environment variable
+	#[arg(long)]
+	pub show_origin: bool,
+
 	/// Reset configuration to defaults
 	#[arg(long)]
 	pub reset: bool,
 }
 
-pub fn execute(args: &ConfigArgs, mut config: Config) -> Result<()> {
+pub fn execute(args: &ConfigArgs, config: Config) -> Result<()> {
+	if args.show_origin {
+		return show_origin();
+	}
+
+	// Changes are saved to the user config, so they never pick up project
+	// or environment overrides
+	let mut config = if args.show {
+		config
+	} else {
+		Config::load_user()?
+	};
+
 	if args.reset {
 		config = Config::default();
 		config.save()?;
@@ -81,6 +98,13 @@ pub fn execute(args: &ConfigArgs, mut config: Config) -> Result<()> {
 				println!("   Status: ⚠️  Not found (using defaults)");
 			}
 		}
+		let project_config = std::env::current_dir()?
+			.join(".octocode")
+			.join(octocode::config::PROJECT_CONFIG_FILE);
+		if project_config.exists() {
+			println!("📄 Project overrides: {}", project_config.display());
+		}
+		println!("   Run with --show-origin to see where each value comes from");
 		println!();
 
 		// LLM Configuration
@@ -311,3 +335,29 @@ pub fn execute(args: &ConfigArgs, mut config: Config) -> Result<()> {
 
 	Ok(())
 }
+
+/// Print every value of the resolved configuration with its layer
+fn show_origin() -> Result<()> {
+	let layered = Config::load_layered()?;
+	for (key, value) in &layered.values {
+		let leaf = key.rsplit('.').next().unwrap_or(key);
+		let shown = if leaf.ends_with("api_key") || leaf.ends_with("token") {
+			"\"********\"".to_string()
+		} else {
+			let value = value.to_string();
+			// Long prompts would drown the listing
+			if value.chars().count() > 80 {
+				format!("{}...", value.chars().take(77).collect::<String>())
+			} else {
+				value
+			}
+		};
+		let origin = layered
+			.origins
+			.get(key)
+			.map(|layer| layer.to_string())
+			.unwrap_or_else(|| "default".to_string());
+		println!("{} = {}  # {}", key, shown.replace('\n', "\\n"), origin);
+	}
+	Ok(())
+}
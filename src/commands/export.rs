@@ -0,0 +1,127 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap::{Args, ValueEnum};
+use std::path::PathBuf;
+
+use octocode::config::Config;
+use octocode::export::tags::TagsFormat;
+use octocode::export::{scip, tags};
+use octocode::index_cache::archive;
+use octocode::store::Store;
+
+#[derive(Args, Debug)]
+pub struct ExportArgs {
+	/// File to write [default: octocode-index.jsonl, index.scip, tags or
+	/// TAGS, by format]
+	#[arg(short, long)]
+	pub file: Option<PathBuf>,
+
+	/// Only export blocks and graph rows of files changed between this commit
+	/// and the last indexed commit; apply with `import --apply-delta`
+	#[arg(long)]
+	pub since: Option<String>,
+
+	/// What to write
+	#[arg(long, value_enum, default_value_t = ExportFormat::Archive)]
+	pub format: ExportFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+	/// Archive for `octocode import` (JSON Lines)
+	Archive,
+	/// SCIP index of symbols, definitions and graph relationships, for code
+	/// navigation tools
+	Scip,
+	/// Vim tags file of the signatures of indexed files
+	Ctags,
+	/// Emacs TAGS file of the signatures of indexed files
+	Etags,
+}
+
+/// Write the index, or the changes since a commit, to an archive, or the
+/// index as SCIP or a tags file
+pub async fn execute(store: &Store, config: &Config, args: &ExportArgs) -> anyhow::Result<()> {
+	let current_dir = std::env::current_dir()?;
+
+	if args.format != ExportFormat::Archive && args.since.is_some() {
+		return Err(anyhow::anyhow!("--since only applies to archives"));
+	}
+
+	match args.format {
+		ExportFormat::Archive => {}
+		ExportFormat::Scip => {
+			let file = args.file.clone().unwrap_or_else(|| "index.scip".into());
+			let summary = scip::export(store, &current_dir, &file).await?;
+			println!(
+				"Exported SCIP index to {}: {} documents, {} definitions, {} relationships",
+				file.display(),
+				summary.documents,
+				summary.definitions,
+				summary.relationships
+			);
+			return Ok(());
+		}
+		ExportFormat::Ctags | ExportFormat::Etags => {
+			let (format, default_file) = if args.format == ExportFormat::Ctags {
+				(TagsFormat::Ctags, "tags")
+			} else {
+				(TagsFormat::Etags, "TAGS")
+			};
+			let file = args.file.clone().unwrap_or_else(|| default_file.into());
+			let summary = tags::export(store, &current_dir, &file, format).await?;
+			println!(
+				"Exported {} tags from {} files to {}",
+				summary.tags,
+				summary.files,
+				file.display()
+			);
+			return Ok(());
+		}
+	}
+
+	let file = args
+		.file
+		.clone()
+		.unwrap_or_else(|| "octocode-index.jsonl".into());
+	let summary =
+		archive::export(store, config, &current_dir, &file, args.since.as_deref()).await?;
+
+	match &summary.base_commit {
+		Some(base) => println!(
+			"Exported delta {}..{} to {}: {} changed files, {} blocks, {} graph nodes, {} relationships",
+			short_commit(base),
+			short_commit(&summary.commit),
+			file.display(),
+			summary.paths,
+			summary.blocks,
+			summary.nodes,
+			summary.relationships
+		),
+		None => println!(
+			"Exported index to {}: {} blocks, {} graph nodes, {} relationships",
+			file.display(),
+			summary.blocks,
+			summary.nodes,
+			summary.relationships
+		),
+	}
+
+	Ok(())
+}
+
+fn short_commit(commit: &str) -> &str {
+	&commit[..commit.len().min(12)]
+}
@@ -36,6 +36,10 @@ pub struct FormatArgs {
 	#[arg(short, long)]
 	pub commit: bool,
 
+	/// Exit non-zero when files need formatting, without changing them (for hooks and CI)
+	#[arg(long, conflicts_with = "commit")]
+	pub check: bool,
+
 	/// Specific files to format (default: all git-tracked and unstaged files)
 	pub files: Vec<PathBuf>,
 
@@ -89,6 +93,7 @@ pub async fn execute(format_args: &FormatArgs) -> Result<()> {
 		println!("Found {} files to process", files_to_format.len());
 	}
 
+	let dry_run = format_args.dry_run || format_args.check;
 	let mut formatted_files = Vec::new();
 	let mut total_changes = 0;
 
@@ -97,7 +102,7 @@ pub async fn execute(format_args: &FormatArgs) -> Result<()> {
 			println!("Processing: {}", file_path.display());
 		}
 
-		let changes = format_file(file_path, !format_args.dry_run, format_args.verbose)
+		let changes = format_file(file_path, !dry_run, format_args.verbose)
 			.with_context(|| format!("Failed to format file: {}", file_path.display()))?;
 
 		if changes > 0 {
@@ -111,6 +116,20 @@ pub async fn execute(format_args: &FormatArgs) -> Result<()> {
 		return Ok(());
 	}
 
+	if format_args.check {
+		for file in &formatted_files {
+			println!(
+				"  {}",
+				file.strip_prefix(&git_root).unwrap_or(file).display()
+			);
+		}
+		return Err(anyhow!(
+			"{} files need formatting ({} changes); run 'octocode format' to fix them",
+			formatted_files.len(),
+			total_changes
+		));
+	}
+
 	let action = if format_args.dry_run {
 		"would be applied"
 	} else {
@@ -123,7 +142,7 @@ pub async fn execute(format_args: &FormatArgs) -> Result<()> {
 		action
 	);
 
-	if !format_args.dry_run && format_args.commit {
+	if !dry_run && format_args.commit {
 		commit_changes(&formatted_files)?;
 	}
 
@@ -16,16 +16,21 @@ use clap::{Args, ValueEnum};
 
 use octocode::config::Config;
 use octocode::indexer;
+use octocode::indexer::path_aliases::PathAliases;
 use octocode::store::Store;
 
 use crate::commands::OutputFormat;
 
-#[derive(Args, Debug)]
+#[derive(Args, Debug, Clone)]
 pub struct GraphRAGArgs {
 	/// The operation to perform on the GraphRAG knowledge graph
 	#[arg(value_enum)]
 	pub operation: GraphRAGOperation,
 
+	/// Function name, `Type::method`, or `path:function` (used with callers and callees operations),
+	/// or file path (used with tests-for operation)
+	pub target: Option<String>,
+
 	/// The query to search for (used with the search operation)
 	#[arg(long)]
 	pub query: Option<String>,
@@ -46,6 +51,26 @@ pub struct GraphRAGArgs {
 	#[arg(long, default_value = "3")]
 	pub max_depth: usize,
 
+	/// Maximum number of candidates, communities, cycles, tests or files to list (used with orphans, communities, cycles, tests-for and hotspots operations)
+	#[arg(long, default_value = "50")]
+	pub limit: usize,
+
+	/// Also list unused public functions, which may be used outside the repository (used with orphans operation)
+	#[arg(long)]
+	pub include_public: bool,
+
+	/// Skip the LLM summary of each community (used with communities operation)
+	#[arg(long)]
+	pub no_summaries: bool,
+
+	/// Weight centrality by how often each file changed in git history (used with hotspots operation)
+	#[arg(long)]
+	pub churn: bool,
+
+	/// Churn window, in any form `git log --since` accepts (used with hotspots operation and --churn)
+	#[arg(long, default_value = "90 days ago")]
+	pub since: String,
+
 	/// Output format
 	#[arg(long, value_enum, default_value = "cli")]
 	pub format: OutputFormat,
@@ -63,6 +88,20 @@ pub enum GraphRAGOperation {
 	FindPath,
 	/// Get an overview of the entire graph structure
 	Overview,
+	/// List files and functions nothing uses, ranked as dead code candidates
+	Orphans,
+	/// List the functions calling a function
+	Callers,
+	/// List the functions a function calls
+	Callees,
+	/// Group files into cohesive modules with a summary of each
+	Communities,
+	/// List circular dependencies between files with their shortest cycles
+	Cycles,
+	/// List the test files exercising a file, to run before and after editing it
+	TestsFor,
+	/// Rank files by degree and betweenness centrality, optionally weighted by churn
+	Hotspots,
 }
 
 /// Execute a GraphRAG command
@@ -104,6 +143,17 @@ pub async fn execute(
 		return Ok(());
 	}
 
+	// Nodes are keyed by file path; IDs of renamed files map to their current path
+	let aliases = PathAliases::load(&std::env::current_dir()?);
+	let mut args = args.clone();
+	for id in [&mut args.node_id, &mut args.source_id, &mut args.target_id]
+		.into_iter()
+		.flatten()
+	{
+		*id = aliases.resolve_missing(id, |id| graph.nodes.contains_key(id));
+	}
+	let args = &args;
+
 	// Execute the requested operation
 	match args.operation {
 		GraphRAGOperation::Search => {
@@ -118,7 +168,9 @@ pub async fn execute(
 			};
 
 			// Search for nodes
-			println!("Searching for: {}", query);
+			if !args.format.is_json() {
+				println!("Searching for: {}", query);
+			}
 			let nodes = graph_builder.search_nodes(query).await?;
 
 			// Display results in the requested format
@@ -157,6 +209,14 @@ pub async fn execute(
 			// Get the graph
 			let graph = graph_builder.get_graph().await?;
 
+			if args.format.is_json() {
+				println!(
+					"{}",
+					serde_json::to_string_pretty(&graph.nodes.get(node_id))?
+				);
+				return Ok(());
+			}
+
 			// Get node details
 			match graph.nodes.get(node_id) {
 				Some(node) => {
@@ -194,7 +254,11 @@ pub async fn execute(
 
 			// Check if the node exists
 			if !graph.nodes.contains_key(node_id) {
-				println!("Node not found: {}", node_id);
+				if args.format.is_json() {
+					println!("null");
+				} else {
+					println!("Node not found: {}", node_id);
+				}
 				return Ok(());
 			}
 
@@ -205,6 +269,19 @@ pub async fn execute(
 				.filter(|rel| rel.source == *node_id || rel.target == *node_id)
 				.collect();
 
+			if args.format.is_json() {
+				let (outgoing, incoming): (Vec<&_>, Vec<&_>) = relationships
+					.into_iter()
+					.partition(|rel| rel.source == *node_id);
+				let output = serde_json::json!({
+					"node_id": node_id,
+					"outgoing": outgoing,
+					"incoming": incoming,
+				});
+				println!("{}", serde_json::to_string_pretty(&output)?);
+				return Ok(());
+			}
+
 			if relationships.is_empty() {
 				println!("No relationships found for node: {}", node_id);
 			} else {
@@ -281,14 +358,27 @@ pub async fn execute(
 			};
 
 			// Find paths
-			println!(
-				"Finding paths from {} to {} (max depth: {})...",
-				source_id, target_id, args.max_depth
-			);
+			if !args.format.is_json() {
+				println!(
+					"Finding paths from {} to {} (max depth: {})...",
+					source_id, target_id, args.max_depth
+				);
+			}
 			let paths = graph_builder
 				.find_paths(source_id, target_id, args.max_depth)
 				.await?;
 
+			if args.format.is_json() {
+				let output = serde_json::json!({
+					"source_id": source_id,
+					"target_id": target_id,
+					"max_depth": args.max_depth,
+					"paths": paths,
+				});
+				println!("{}", serde_json::to_string_pretty(&output)?);
+				return Ok(());
+			}
+
 			// Get the graph for node name lookup
 			let graph = graph_builder.get_graph().await?;
 
@@ -339,17 +429,28 @@ pub async fn execute(
 			let relationship_count = graph.relationships.len();
 
 			// Count node types
-			let mut node_types = std::collections::HashMap::new();
+			let mut node_types = std::collections::BTreeMap::new();
 			for node in graph.nodes.values() {
 				*node_types.entry(node.kind.clone()).or_insert(0) += 1;
 			}
 
 			// Count relationship types
-			let mut rel_types = std::collections::HashMap::new();
+			let mut rel_types = std::collections::BTreeMap::new();
 			for rel in &graph.relationships {
 				*rel_types.entry(rel.relation_type.clone()).or_insert(0) += 1;
 			}
 
+			if args.format.is_json() {
+				let output = serde_json::json!({
+					"node_count": node_count,
+					"relationship_count": relationship_count,
+					"node_types": node_types,
+					"relationship_types": rel_types,
+				});
+				println!("{}", serde_json::to_string_pretty(&output)?);
+				return Ok(());
+			}
+
 			// Display overview
 			println!("GraphRAG Knowledge Graph Overview");
 			println!("=================================\n");
@@ -371,6 +472,336 @@ pub async fn execute(
 				println!("  - {}: {} relationships", rel_type, count);
 			}
 		}
+		GraphRAGOperation::Orphans => {
+			use octocode::indexer::graphrag::orphans;
+
+			let graph = graph_builder.get_graph().await?;
+			let mut candidates = orphans::find_orphan_files(&graph);
+			candidates.extend(orphans::find_orphan_functions(&graph, args.include_public));
+			orphans::rank(&mut candidates);
+			let total = candidates.len();
+			candidates.truncate(args.limit);
+
+			if args.format.is_json() {
+				println!("{}", serde_json::to_string_pretty(&candidates)?);
+				return Ok(());
+			}
+
+			if candidates.is_empty() {
+				println!("No dead code candidates found.");
+				return Ok(());
+			}
+
+			println!(
+				"Dead code candidates ({} of {}), safest removals first:\n",
+				candidates.len(),
+				total
+			);
+			for candidate in &candidates {
+				let location = match candidate.line {
+					Some(line) => format!("{}:{}  {}", candidate.path, line, candidate.name),
+					None => candidate.path.clone(),
+				};
+				println!(
+					"  {:.2}  {:<8}  {}  ({} lines)",
+					candidate.score, candidate.kind, location, candidate.size_lines
+				);
+				println!("        {}", candidate.reasons.join("; "));
+			}
+			println!("\nCandidates are heuristics: check dynamic uses, reflection and external callers before removing.");
+		}
+		GraphRAGOperation::Communities => {
+			use octocode::indexer::graphrag::ai::AIEnhancements;
+			use octocode::indexer::graphrag::communities;
+
+			let mut result = communities::detect_communities(&graph);
+			let total = result.communities.len();
+			result.communities.truncate(args.limit);
+
+			if !args.no_summaries && !result.communities.is_empty() {
+				if config.openrouter.api_key.is_some() {
+					let ai = AIEnhancements::new(config.clone(), reqwest::Client::new(), true);
+					let count = result.communities.len();
+					for community in result.communities.iter_mut() {
+						if !args.format.is_json() {
+							eprint!("\rSummarizing communities: {}/{}", community.id, count);
+						}
+						match ai.summarize_community(community, &graph).await {
+							Ok(summary) => community.summary = Some(summary),
+							Err(e) => eprintln!(
+								"\nWarning: summary of community {} failed: {}",
+								community.id, e
+							),
+						}
+					}
+					if !args.format.is_json() {
+						eprintln!();
+					}
+				} else if !args.format.is_json() {
+					eprintln!(
+						"No OpenRouter API key configured: listing communities without summaries."
+					);
+				}
+			}
+
+			if args.format.is_json() {
+				println!("{}", serde_json::to_string_pretty(&result)?);
+				return Ok(());
+			}
+
+			if result.communities.is_empty() {
+				println!("No communities found: the knowledge graph has too few relationships between files.");
+				return Ok(());
+			}
+
+			println!(
+				"Communities ({} of {}, modularity {:.2}), largest first:\n",
+				result.communities.len(),
+				total,
+				result.modularity
+			);
+			for community in &result.communities {
+				println!(
+					"{}. {}  ({} files, cohesion {:.2})",
+					community.id,
+					community.label,
+					community.files.len(),
+					community.cohesion
+				);
+				if let Some(summary) = &community.summary {
+					for line in summary.lines().filter(|line| !line.trim().is_empty()) {
+						println!("   {}", line.trim());
+					}
+				}
+				println!("   Key files: {}", community.key_files.join(", "));
+				let others = community.files.len() - community.key_files.len();
+				if others > 0 {
+					println!("   ... and {} more", others);
+				}
+				println!();
+			}
+			if result.ungrouped > 0 {
+				println!(
+					"{} files have no relationships strong enough to join a community.",
+					result.ungrouped
+				);
+			}
+		}
+		GraphRAGOperation::Cycles => {
+			use octocode::indexer::graphrag::cycles;
+
+			let mut groups = cycles::find_cycles(&graph);
+			let total = groups.len();
+			groups.truncate(args.limit);
+
+			if args.format.is_json() {
+				println!("{}", serde_json::to_string_pretty(&groups)?);
+				return Ok(());
+			}
+
+			if groups.is_empty() {
+				println!("No circular dependencies found.");
+				return Ok(());
+			}
+
+			println!(
+				"Circular dependencies ({} of {}), largest first:\n",
+				groups.len(),
+				total
+			);
+			for (index, group) in groups.iter().enumerate() {
+				println!(
+					"{}. {} files: {}",
+					index + 1,
+					group.files.len(),
+					group.files.join(", ")
+				);
+				for cycle in &group.cycles {
+					println!("   {}", cycle.join(" -> "));
+				}
+				println!();
+			}
+			println!("Breaking one dependency of the shortest cycle is usually the cheapest fix.");
+		}
+		GraphRAGOperation::Hotspots => {
+			use crate::commands::report::ownership;
+			use octocode::indexer::git_utils::GitUtils;
+			use octocode::indexer::graphrag::hotspots;
+
+			let commits = if args.churn {
+				let current_dir = std::env::current_dir()?;
+				let repo_path = GitUtils::find_git_root(&current_dir)
+					.ok_or_else(|| anyhow::anyhow!("--churn needs a git repository"))?;
+				let churn = ownership::git_churn(&repo_path, &args.since)?;
+				Some(
+					churn
+						.into_iter()
+						.map(|(path, file)| (path, file.commits.len()))
+						.collect(),
+				)
+			} else {
+				None
+			};
+
+			let mut ranked = hotspots::find_hotspots(&graph, commits.as_ref());
+			let total = ranked.len();
+			ranked.truncate(args.limit);
+
+			if args.format.is_json() {
+				println!("{}", serde_json::to_string_pretty(&ranked)?);
+				return Ok(());
+			}
+
+			if ranked.is_empty() {
+				println!("No relationships between files in the knowledge graph.");
+				return Ok(());
+			}
+
+			println!(
+				"Hotspots ({} of {}), most critical first{}:\n",
+				ranked.len(),
+				total,
+				if args.churn {
+					format!(", weighted by commits since {}", args.since)
+				} else {
+					String::new()
+				}
+			);
+			for hotspot in &ranked {
+				let commits = hotspot
+					.commits
+					.map(|commits| format!(", {} commits", commits))
+					.unwrap_or_default();
+				println!(
+					"  {:.2}  {}  ({} dependents, {} dependencies, betweenness {:.3}{})",
+					hotspot.score,
+					hotspot.file,
+					hotspot.dependents,
+					hotspot.dependencies,
+					hotspot.betweenness,
+					commits
+				);
+			}
+		}
+		GraphRAGOperation::TestsFor => {
+			use octocode::indexer::graphrag::test_links;
+
+			let file = match &args.target {
+				Some(file) => aliases.resolve_missing(file, |id| graph.nodes.contains_key(id)),
+				None => {
+					eprintln!("Error: a file is required for the tests-for operation.");
+					eprintln!("Example: octocode graphrag tests-for src/config.rs");
+					return Ok(());
+				}
+			};
+			if !graph.nodes.contains_key(&file) {
+				eprintln!(
+					"No file '{}' in the knowledge graph. Run 'octocode index' to add it.",
+					file
+				);
+				return Ok(());
+			}
+
+			let mut links = test_links::tests_for(&graph, &file);
+			let total = links.len();
+			links.truncate(args.limit);
+
+			if args.format.is_json() {
+				let output = serde_json::json!({
+					"file": file,
+					"tests": links,
+				});
+				println!("{}", serde_json::to_string_pretty(&output)?);
+				return Ok(());
+			}
+
+			if links.is_empty() {
+				println!("No tests found for {}.", file);
+				return Ok(());
+			}
+
+			println!(
+				"Tests for {} ({} of {}), most certain first:\n",
+				file,
+				links.len(),
+				total
+			);
+			for link in &links {
+				println!("  {:.2}  {}", link.confidence, link.test);
+				println!("        {}", link.reasons);
+			}
+		}
+		GraphRAGOperation::Callers | GraphRAGOperation::Callees => {
+			use octocode::indexer::graphrag::calls;
+
+			let show_callers = matches!(args.operation, GraphRAGOperation::Callers);
+			let target = match &args.target {
+				Some(target) => target,
+				None => {
+					eprintln!("Error: a function is required for this operation.");
+					eprintln!("Example: octocode graphrag callers parse_config");
+					return Ok(());
+				}
+			};
+
+			let functions = calls::find_functions(&graph, target);
+			if functions.is_empty() {
+				eprintln!(
+					"No function '{}' in the knowledge graph. Run 'octocode index' to extract functions.",
+					target
+				);
+				return Ok(());
+			}
+
+			if args.format.is_json() {
+				let reports: Vec<serde_json::Value> = functions
+					.iter()
+					.map(|function| {
+						if show_callers {
+							serde_json::json!({
+								"function": function,
+								"callers": calls::callers(&graph, function),
+							})
+						} else {
+							let callees = calls::callees(&graph, function);
+							serde_json::json!({
+								"function": function,
+								"callees": callees.functions,
+								"external": callees.external,
+							})
+						}
+					})
+					.collect();
+				println!("{}", serde_json::to_string_pretty(&reports)?);
+				return Ok(());
+			}
+
+			for (index, function) in functions.iter().enumerate() {
+				if index > 0 {
+					println!();
+				}
+				println!(
+					"{}  {}:{}-{}",
+					function.name, function.path, function.line, function.end_line
+				);
+				let (label, related, external) = if show_callers {
+					("Callers", calls::callers(&graph, function), Vec::new())
+				} else {
+					let callees = calls::callees(&graph, function);
+					("Callees", callees.functions, callees.external)
+				};
+				println!("  {}:", label);
+				if related.is_empty() {
+					println!("    (none found)");
+				}
+				for other in &related {
+					println!("    {}:{}  {}", other.path, other.line, other.name);
+				}
+				if !external.is_empty() {
+					println!("  Outside the index: {}", external.join(", "));
+				}
+			}
+		}
 	}
 
 	Ok(())
@@ -0,0 +1,214 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{anyhow, Context, Result};
+use clap::{Args, Subcommand};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use octocode::config::{Config, HookCheck};
+
+use super::review::fail_on_severity;
+
+/// Line identifying the hooks this command wrote, so it only ever replaces
+/// or removes its own
+const MARKER: &str = "# Installed by `octocode hooks install`";
+
+#[derive(Args, Debug)]
+pub struct HooksArgs {
+	#[command(subcommand)]
+	pub command: HooksCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum HooksCommand {
+	/// Write the pre-commit and pre-push hooks configured in [hooks]
+	Install {
+		/// Replace hooks octocode did not write
+		#[arg(long)]
+		force: bool,
+	},
+
+	/// Remove the hooks octocode wrote
+	Uninstall,
+}
+
+/// Install or remove the git hooks running the checks of `[hooks]`
+pub fn execute(config: &Config, args: &HooksArgs) -> Result<()> {
+	let hooks_dir = hooks_dir()?;
+	let hooks = [
+		("pre-commit", &config.hooks.pre_commit),
+		("pre-push", &config.hooks.pre_push),
+	];
+
+	match &args.command {
+		HooksCommand::Install { force } => {
+			fail_on_severity(&config.hooks.fail_on)
+				.context("Invalid hooks.fail_on in the configuration")?;
+
+			// Check every hook first, so a conflict leaves all of them as they were
+			if !force {
+				for (name, checks) in &hooks {
+					let path = hooks_dir.join(name);
+					if !checks.is_empty() && path.exists() && !is_ours(&path) {
+						return Err(anyhow!(
+							"{} exists and was not written by octocode; pass --force to replace it",
+							path.display()
+						));
+					}
+				}
+			}
+
+			std::fs::create_dir_all(&hooks_dir)
+				.with_context(|| format!("Failed to create {}", hooks_dir.display()))?;
+			for (name, checks) in &hooks {
+				let path = hooks_dir.join(name);
+				if checks.is_empty() {
+					// A hook dropped from the configuration goes away on reinstall
+					if is_ours(&path) {
+						std::fs::remove_file(&path)?;
+						println!("Removed {} hook", name);
+					}
+					continue;
+				}
+				write_hook(&path, &hook_script(checks, &config.hooks.fail_on))?;
+				println!("Installed {} hook: {}", name, describe(checks));
+			}
+			println!(
+				"Skip the hooks once with 'git commit --no-verify' or 'git push --no-verify'."
+			);
+		}
+		HooksCommand::Uninstall => {
+			let mut removed = 0;
+			for (name, _) in &hooks {
+				let path = hooks_dir.join(name);
+				if is_ours(&path) {
+					std::fs::remove_file(&path)?;
+					println!("Removed {} hook", name);
+					removed += 1;
+				}
+			}
+			if removed == 0 {
+				println!("No hooks installed by octocode.");
+			}
+		}
+	}
+
+	Ok(())
+}
+
+/// The hooks directory git uses, honouring `core.hooksPath` and worktrees
+fn hooks_dir() -> Result<PathBuf> {
+	let output = Command::new("git")
+		.args(["rev-parse", "--git-path", "hooks"])
+		.output()
+		.context("Failed to execute git")?;
+	if !output.status.success() {
+		return Err(anyhow!("❌ Not in a git repository!"));
+	}
+	let path = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+	Ok(std::env::current_dir()?.join(path))
+}
+
+fn is_ours(path: &Path) -> bool {
+	std::fs::read_to_string(path).is_ok_and(|script| script.contains(MARKER))
+}
+
+fn write_hook(path: &Path, script: &str) -> Result<()> {
+	std::fs::write(path, script).with_context(|| format!("Failed to write {}", path.display()))?;
+	#[cfg(unix)]
+	{
+		use std::os::unix::fs::PermissionsExt;
+		std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))?;
+	}
+	Ok(())
+}
+
+/// The command a check runs
+fn check_command(check: HookCheck, fail_on: &str) -> String {
+	match check {
+		HookCheck::Review => format!("octocode review --staged --fail-on {}", fail_on),
+		HookCheck::Gate => format!("octocode review --staged --gate --fail-on {}", fail_on),
+		HookCheck::Format => "octocode format --check".to_string(),
+	}
+}
+
+/// Shell script running the checks in order, stopping at the first failure
+fn hook_script(checks: &[HookCheck], fail_on: &str) -> String {
+	let mut script = format!(
+		"#!/bin/sh\n{}: the checks come from [hooks] in the\n# octocode configuration; reinstall after changing them.\nset -e\n",
+		MARKER
+	);
+	for check in checks {
+		script.push_str(&check_command(*check, fail_on));
+		script.push('\n');
+	}
+	script
+}
+
+fn describe(checks: &[HookCheck]) -> String {
+	checks
+		.iter()
+		.map(|check| match check {
+			HookCheck::Review => "review",
+			HookCheck::Gate => "gate",
+			HookCheck::Format => "format",
+		})
+		.collect::<Vec<_>>()
+		.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_hook_script_runs_checks_in_order() {
+		let script = hook_script(&[HookCheck::Review, HookCheck::Format], "error");
+		assert!(script.starts_with("#!/bin/sh\n"));
+		assert!(script.contains(MARKER));
+		let commands: Vec<&str> = script
+			.lines()
+			.filter(|line| line.starts_with("octocode"))
+			.collect();
+		assert_eq!(
+			commands,
+			vec![
+				"octocode review --staged --fail-on error",
+				"octocode format --check",
+			]
+		);
+	}
+
+	#[test]
+	fn test_hook_commands_parse_with_the_cli() {
+		use clap::Parser;
+
+		for check in [HookCheck::Review, HookCheck::Gate, HookCheck::Format] {
+			let command = check_command(check, "error");
+			let Ok(args) = crate::OctocodeArgs::try_parse_from(command.split_whitespace()) else {
+				panic!("`{}` doesn't parse", command);
+			};
+			match (check, args.command) {
+				(HookCheck::Review | HookCheck::Gate, crate::Commands::Review(review)) => {
+					assert!(review.staged);
+					assert_eq!(review.gate, check == HookCheck::Gate);
+					assert_eq!(review.fail_on.as_deref(), Some("error"));
+				}
+				(HookCheck::Format, crate::Commands::Format(format)) => assert!(format.check),
+				_ => panic!("`{}` runs the wrong command", command),
+			}
+		}
+	}
+}
@@ -0,0 +1,169 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Project conventions learned from memories and commit history
+//!
+//! Memories tagged `convention` and the subjects of recent commits are added
+//! to the commit message and review prompts, so generated output follows the
+//! house style without it being spelled out in the config. Both are hints:
+//! when the memory store or git history cannot be read, the prompt goes
+//! without them.
+
+use std::path::Path;
+use std::process::Command;
+
+use octocode::config::Config;
+use octocode::memory::{MemoryConfig, MemoryManager};
+use octocode::storage;
+
+/// Tag marking a memory as a project convention
+pub const CONVENTION_TAG: &str = "convention";
+
+/// Longest memory content quoted in a prompt, in characters
+const MAX_CONVENTION_CHARS: usize = 600;
+
+/// Content of the most important memories tagged `convention`, at most `limit`
+pub async fn convention_memories(repo_path: &Path, config: &Config, limit: usize) -> Vec<String> {
+	if limit == 0 {
+		return Vec::new();
+	}
+	// Never create a database just to find it empty
+	let Ok(db_path) = storage::get_project_database_path(repo_path) else {
+		return Vec::new();
+	};
+	if !db_path.exists() {
+		return Vec::new();
+	}
+	let Ok(manager) = MemoryManager::open(config, MemoryConfig::default(), &db_path).await else {
+		return Vec::new();
+	};
+	let Ok(results) = manager
+		.get_memories_by_tags(vec![CONVENTION_TAG.to_string()])
+		.await
+	else {
+		return Vec::new();
+	};
+
+	results
+		.into_iter()
+		.take(limit)
+		.map(|result| {
+			let memory = result.memory;
+			let content = truncate(memory.content.trim(), MAX_CONVENTION_CHARS);
+			if content.is_empty() {
+				memory.title
+			} else {
+				format!("{}: {}", memory.title, content)
+			}
+		})
+		.collect()
+}
+
+/// Subjects of the last `limit` commits, leaving out merges and fixups
+pub fn recent_commit_subjects(repo_path: &Path, limit: usize) -> Vec<String> {
+	if limit == 0 {
+		return Vec::new();
+	}
+	// Ask for more than needed so skipped fixups do not shorten the list
+	let output = Command::new("git")
+		.args([
+			"log",
+			"--no-merges",
+			"--format=%s",
+			"-n",
+			&(limit * 2).to_string(),
+		])
+		.current_dir(repo_path)
+		.output();
+	match output {
+		Ok(output) if output.status.success() => {
+			filter_subjects(&String::from_utf8_lossy(&output.stdout), limit)
+		}
+		_ => Vec::new(),
+	}
+}
+
+fn filter_subjects(log: &str, limit: usize) -> Vec<String> {
+	log.lines()
+		.map(str::trim)
+		.filter(|subject| {
+			!subject.is_empty()
+				&& !subject.starts_with("fixup!")
+				&& !subject.starts_with("squash!")
+				&& !subject.starts_with("amend!")
+		})
+		.take(limit)
+		.map(str::to_string)
+		.collect()
+}
+
+/// Prompt section with the conventions and example subjects, empty without either
+pub fn prompt_section(conventions: &[String], subjects: &[String]) -> String {
+	let mut section = String::new();
+	if !conventions.is_empty() {
+		section.push_str("\n\nPROJECT CONVENTIONS (from team memory, follow them):");
+		for convention in conventions {
+			section.push_str(&format!("\n- {}", convention));
+		}
+	}
+	if !subjects.is_empty() {
+		section.push_str(
+			"\n\nRECENT COMMIT SUBJECTS (match their style, wording and casing; do not copy them):",
+		);
+		for subject in subjects {
+			section.push_str(&format!("\n- {}", subject));
+		}
+	}
+	section
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+	if text.chars().count() > max_chars {
+		let truncated: String = text.chars().take(max_chars).collect();
+		format!("{}...", truncated)
+	} else {
+		text.to_string()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_filter_subjects() {
+		let log = "feat(mcp): add streaming\nfixup! feat(mcp): add streaming\n\nsquash! fix: x\nfix(cli): handle empty input\ndocs: update readme\n";
+		assert_eq!(
+			filter_subjects(log, 2),
+			vec!["feat(mcp): add streaming", "fix(cli): handle empty input"]
+		);
+	}
+
+	#[test]
+	fn test_prompt_section() {
+		assert_eq!(prompt_section(&[], &[]), "");
+
+		let section = prompt_section(
+			&["Scopes: Use the crate name as scope".to_string()],
+			&["fix(store): close tables".to_string()],
+		);
+		assert!(section.contains("PROJECT CONVENTIONS"));
+		assert!(section.contains("\n- Scopes: Use the crate name as scope"));
+		assert!(section.contains("\n- fix(store): close tables"));
+		assert!(section.find("PROJECT CONVENTIONS").unwrap() < section.find("RECENT").unwrap());
+
+		let section = prompt_section(&[], &["chore: bump deps".to_string()]);
+		assert!(!section.contains("PROJECT CONVENTIONS"));
+	}
+}
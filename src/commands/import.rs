@@ -0,0 +1,59 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap::Args;
+use std::path::PathBuf;
+
+use octocode::config::Config;
+use octocode::index_cache::archive;
+use octocode::store::Store;
+
+#[derive(Args, Debug)]
+pub struct ImportArgs {
+	/// Archive file written by `octocode export`
+	pub file: PathBuf,
+
+	/// Apply a delta written by `export --since` on top of the current index
+	#[arg(long)]
+	pub apply_delta: bool,
+
+	/// Apply a delta even if the index is not at the delta's base commit
+	#[arg(long, requires = "apply_delta")]
+	pub force: bool,
+}
+
+/// Load an archive into the index
+pub async fn execute(store: &Store, config: &Config, args: &ImportArgs) -> anyhow::Result<()> {
+	let summary = archive::import(store, config, &args.file, args.apply_delta, args.force).await?;
+
+	if args.apply_delta {
+		println!(
+			"Applied delta: {} files replaced with {} blocks, {} graph nodes, {} relationships",
+			summary.paths, summary.blocks, summary.nodes, summary.relationships
+		);
+	} else {
+		println!(
+			"Imported index: {} blocks, {} graph nodes, {} relationships",
+			summary.blocks, summary.nodes, summary.relationships
+		);
+	}
+	if !summary.commit.is_empty() {
+		println!(
+			"Index now matches commit {}",
+			&summary.commit[..summary.commit.len().min(12)]
+		);
+	}
+
+	Ok(())
+}
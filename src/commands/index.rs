@@ -14,13 +14,16 @@
 
 use clap::Args;
 use parking_lot::RwLock;
-use std::io::Write;
+use std::io::{IsTerminal, Write};
 use std::sync::Arc;
 
 use octocode::config::Config;
 use octocode::indexer;
+use octocode::indexer::dry_run::{DryRunReport, FileAction};
 use octocode::state;
-use octocode::store::Store;
+use octocode::store::{preflight, EmbeddingSettings, Store};
+
+use crate::commands::OutputFormat;
 
 #[derive(Args, Debug)]
 pub struct IndexArgs {
@@ -39,6 +42,77 @@ pub struct IndexArgs {
 	/// Show GraphRAG connections for a specific file
 	#[arg(long, value_name = "FILE_PATH")]
 	pub graphrag: Option<String>,
+
+	/// Reproducible indexing: sorted file order, built-in chunking parameters
+	/// and pinned timestamps; prints the manifest hash of the resulting index
+	#[arg(long)]
+	pub deterministic: bool,
+
+	/// Report which files would be indexed, reindexed or skipped and the
+	/// embedding work it would take, without writing or calling providers
+	#[arg(long)]
+	pub dry_run: bool,
+
+	/// Output format for --dry-run
+	#[arg(long, value_enum, default_value = "cli", requires = "dry_run")]
+	pub format: OutputFormat,
+
+	/// Drop the existing index and rebuild it from scratch, e.g. after
+	/// switching embedding models
+	#[arg(long, conflicts_with = "dry_run")]
+	pub rebuild: bool,
+
+	/// Directories indexed despite .gitignore and .noindex, set by `watch --include-extra`
+	#[arg(skip)]
+	pub extra_dirs: Vec<String>,
+}
+
+/// Check the existing index against the configured embedding models before
+/// the store is opened, as opening it fails on tables of another dimension.
+/// Returns whether the index has to be rebuilt.
+pub async fn preflight(config: &Config, args: &IndexArgs) -> Result<bool, anyhow::Error> {
+	if args.list_files || args.show_file.is_some() || args.graphrag.is_some() {
+		return Ok(false);
+	}
+	if args.rebuild {
+		return Ok(true);
+	}
+	check_embedding_drift(config, !args.dry_run).await
+}
+
+/// Report embedding drift of the current project's index. With `prompt` and a
+/// terminal, ask whether to rebuild; otherwise fail with guidance. Returns
+/// whether the user chose to rebuild.
+pub async fn check_embedding_drift(config: &Config, prompt: bool) -> Result<bool, anyhow::Error> {
+	let db_path = octocode::storage::get_project_database_path(&std::env::current_dir()?)?;
+	let drift = preflight::check(config, &db_path).await?;
+	if drift.is_empty() {
+		return Ok(false);
+	}
+
+	let mut report =
+		"The existing index does not match the configured embedding models:\n".to_string();
+	for difference in &drift {
+		report.push_str(&format!("  - {}\n", difference));
+	}
+
+	if prompt && std::io::stdin().is_terminal() && std::io::stdout().is_terminal() {
+		println!("⚠️  {}", report);
+		print!("Drop the index and rebuild it with the configured models? (y/N): ");
+		std::io::stdout().flush()?;
+		let mut input = String::new();
+		std::io::stdin().read_line(&mut input)?;
+		if input.trim().to_lowercase().starts_with('y') {
+			return Ok(true);
+		}
+	}
+
+	Err(anyhow::anyhow!(
+		"{}\nRun `octocode store migrate` to re-embed the index with the configured models while it stays searchable, \
+		 `octocode index --rebuild` to drop the index and rebuild it, \
+		 or restore the embedding models it was built with (`octocode config --show-origin` shows where they are set).",
+		report
+	))
 }
 
 pub async fn execute(
@@ -67,10 +141,31 @@ pub async fn execute(
 		return Ok(());
 	}
 
+	let mut config = config.clone();
+	if args.deterministic {
+		config.index.deterministic = true;
+	}
+	let config = &config;
+	let overridden = indexer::deterministic::pin_chunker(&mut config.clone());
+	if !overridden.is_empty() {
+		println!(
+			"🔒 Deterministic mode: ignoring configured {} in favor of built-in values",
+			overridden.join(", ")
+		);
+	}
+
 	let current_dir = std::env::current_dir()?;
 
 	// Git repository validation and optimization
-	let git_repo_root = if !args.no_git && config.index.require_git {
+	let git_repo_root = if !config.index.roots.is_empty() {
+		// A workspace root holds its roots side by side; git changes only
+		// tell what changed when the workspace root is a repository itself
+		if !args.no_git && indexer::git::is_git_repo_root(&current_dir) {
+			Some(current_dir.clone())
+		} else {
+			None
+		}
+	} else if !args.no_git && config.index.require_git {
 		// Check if we're in a git repository root
 		if !indexer::git::is_git_repo_root(&current_dir) {
 			return Err(anyhow::anyhow!(
@@ -90,6 +185,27 @@ pub async fn execute(
 		None
 	};
 
+	if args.dry_run {
+		let report =
+			indexer::dry_run::plan(store, config, &current_dir, git_repo_root.as_deref()).await?;
+		if args.format.is_json() {
+			println!("{}", serde_json::to_string_pretty(&report)?);
+		} else {
+			print_dry_run(&report);
+		}
+		return Ok(());
+	}
+
+	if args.rebuild {
+		println!("🧹 Dropping the existing index to rebuild it from scratch (memories are kept)");
+		store.clear_non_memory_tables().await?;
+		store.initialize_collections().await?;
+	}
+	// The preflight passed, so the index now follows the configured models
+	store
+		.store_embedding_settings(&EmbeddingSettings::from_config(config))
+		.await?;
+
 	if let Some(ref git_root) = git_repo_root {
 		println!("✓ Git repository detected: {}", git_root.display());
 	} else if args.no_git {
@@ -99,7 +215,11 @@ pub async fn execute(
 	}
 
 	let state = state::create_shared_state();
-	state.write().current_directory = current_dir;
+	{
+		let mut state_guard = state.write();
+		state_guard.current_directory = current_dir;
+		state_guard.extra_dirs = args.extra_dirs.clone();
+	}
 
 	// Spawn the progress display task
 	let progress_handle = tokio::spawn(display_indexing_progress(state.clone()));
@@ -112,6 +232,12 @@ pub async fn execute(
 
 	// Flush index to disk
 	store.flush().await?;
+
+	if config.index.deterministic {
+		if let Some(manifest_hash) = store.get_index_manifest_hash().await? {
+			println!("🔒 Manifest hash: {}", manifest_hash);
+		}
+	}
 	Ok(())
 }
 
@@ -245,9 +371,17 @@ pub async fn display_indexing_progress(state: Arc<RwLock<state::IndexState>>) {
 	let final_total;
 	let final_graphrag_enabled;
 	let final_graphrag_blocks;
+	let secret_findings;
+	let secret_skipped_blocks;
+	let policy_violations;
+	let file_policy_skips;
 
 	{
 		let final_state = state.read();
+		secret_findings = final_state.secret_findings.clone();
+		secret_skipped_blocks = final_state.secret_skipped_blocks;
+		policy_violations = final_state.policy_violations.clone();
+		file_policy_skips = final_state.file_policy_skips;
 		final_indexed = final_state.indexed_files;
 		final_skipped = final_state.skipped_files;
 		final_total = final_state.total_files;
@@ -282,6 +416,114 @@ pub async fn display_indexing_progress(state: Arc<RwLock<state::IndexState>>) {
 			final_indexed, final_total, final_graphrag_blocks
 		);
 	}
+
+	if file_policy_skips.total() > 0 {
+		println!(
+			"Skipped {} files ({}); see max_file_size, skip_binary and include/exclude_extensions in [index]",
+			file_policy_skips.total(),
+			file_policy_skips.describe()
+		);
+	}
+
+	print_secret_report(secret_findings, secret_skipped_blocks);
+	print_policy_report(policy_violations);
+}
+
+/// Print what `index --dry-run` found, listing the files that would be processed
+fn print_dry_run(report: &DryRunReport) {
+	println!("Dry run: nothing is written to the index and no provider is called");
+	if report.up_to_date {
+		println!("✅ No commit changes since last index, indexing would skip all files");
+	}
+
+	for file in report
+		.files
+		.iter()
+		.filter(|file| file.action != FileAction::Skip)
+	{
+		let action = match file.action {
+			FileAction::Reindex => "reindex",
+			_ => "index",
+		};
+		println!(
+			"  {:<8} {}  ({} blocks, {} tokens)",
+			action, file.path, file.blocks, file.tokens
+		);
+	}
+
+	println!(
+		"\nFiles: {} to index, {} to reindex, {} unchanged",
+		report.count(FileAction::Index),
+		report.count(FileAction::Reindex),
+		report.count(FileAction::Skip)
+	);
+	if report.skipped.total() > 0 {
+		println!(
+			"Skipped by [index] file policy: {}",
+			report.skipped.describe()
+		);
+	}
+	for (name, estimate) in [
+		("Code", &report.code),
+		("Text", &report.text),
+		("Documents", &report.documents),
+	] {
+		if estimate.blocks > 0 {
+			println!(
+				"{}: {} blocks, {} tokens, {} embedding calls",
+				name, estimate.blocks, estimate.tokens, estimate.calls
+			);
+		}
+	}
+	let total = report.total();
+	println!(
+		"Total: {} blocks, {} tokens, ~{} embedding calls",
+		total.blocks, total.tokens, total.calls
+	);
+}
+
+/// Summarize credentials redacted or skipped during indexing
+fn print_secret_report(mut findings: Vec<String>, skipped_blocks: usize) {
+	// Overlapping text chunks report the same line twice
+	findings.sort();
+	findings.dedup();
+	if findings.is_empty() {
+		return;
+	}
+
+	println!(
+		"⚠ {} secrets and redacted values kept out of the index ({} blocks skipped entirely)",
+		findings.len(),
+		skipped_blocks
+	);
+	const MAX_LISTED: usize = 20;
+	for finding in findings.iter().take(MAX_LISTED) {
+		println!("  {}", finding);
+	}
+	if findings.len() > MAX_LISTED {
+		println!("  ... and {} more", findings.len() - MAX_LISTED);
+	}
+}
+
+/// Summarize blocks that embedding.path_policies kept away from remote providers
+fn print_policy_report(mut violations: Vec<String>) {
+	violations.sort();
+	violations.dedup();
+	if violations.is_empty() {
+		return;
+	}
+
+	println!(
+		"⚠ {} local-only files kept away from remote providers (see embedding.path_policies)",
+		violations.len()
+	);
+	const MAX_LISTED: usize = 20;
+	for violation in violations.iter().take(MAX_LISTED) {
+		println!("  {}", violation);
+	}
+	if violations.len() > MAX_LISTED {
+		println!("  ... and {} more", violations.len() - MAX_LISTED);
+	}
 }
 
 async fn show_graphrag_connections(store: &Store, file_path: &str) -> Result<(), anyhow::Error> {
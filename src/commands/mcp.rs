@@ -13,13 +13,19 @@
 // limitations under the License.
 
 use anyhow::Result;
-use clap::Args;
+use clap::{Args, Subcommand};
 
 use octocode::config::Config;
+use octocode::mcp::control::{self, StatusSnapshot};
 use octocode::mcp::McpServer;
 
+use crate::commands::OutputFormat;
+
 #[derive(Args, Clone)]
 pub struct McpArgs {
+	#[command(subcommand)]
+	pub command: Option<McpCommand>,
+
 	/// Enable debug logging for MCP server
 	#[arg(long)]
 	pub debug: bool,
@@ -36,9 +42,23 @@ pub struct McpArgs {
 	#[arg(long, value_name = "COMMAND")]
 	pub with_lsp: Option<String>,
 
-	/// Bind to HTTP server on host:port instead of using stdin/stdout (e.g., "0.0.0.0:12345")
-	#[arg(long, value_name = "HOST:PORT")]
+	/// Serve the streamable HTTP transport (JSON or SSE replies) on host:port instead of stdin/stdout (e.g., "0.0.0.0:12345")
+	#[arg(long, visible_alias = "listen", value_name = "HOST:PORT")]
 	pub bind: Option<String>,
+
+	/// Disable tools that modify stored data (memorize, forget) for less-trusted clients
+	#[arg(long)]
+	pub read_only: bool,
+}
+
+#[derive(Subcommand, Clone)]
+pub enum McpCommand {
+	/// Show sessions, in-flight tool calls, recent errors and index freshness of the running server
+	Status {
+		/// Output format
+		#[arg(long, value_enum, default_value = "cli")]
+		format: OutputFormat,
+	},
 }
 
 pub async fn run(args: McpArgs) -> Result<()> {
@@ -57,6 +77,16 @@ pub async fn run(args: McpArgs) -> Result<()> {
 		));
 	}
 
+	if let Some(McpCommand::Status { format }) = &args.command {
+		let snapshot = control::query_status(&working_directory).await?;
+		if format.is_json() {
+			println!("{}", serde_json::to_string_pretty(&snapshot)?);
+		} else {
+			print_status(&snapshot);
+		}
+		return Ok(());
+	}
+
 	// Note: No console output here - MCP protocol compliance requires clean stdout/stderr
 	// All debug information is logged to files via structured logging in the server
 
@@ -66,6 +96,7 @@ pub async fn run(args: McpArgs) -> Result<()> {
 		working_directory,
 		args.no_git,
 		args.with_lsp,
+		args.read_only,
 	)
 	.await?;
 
@@ -76,3 +107,79 @@ pub async fn run(args: McpArgs) -> Result<()> {
 		server.run().await
 	}
 }
+
+fn print_status(snapshot: &StatusSnapshot) {
+	let uptime = chrono::Utc::now() - snapshot.started_at;
+	println!("MCP Server Status");
+	println!("  PID: {}", snapshot.pid);
+	println!("  Directory: {}", snapshot.working_directory);
+	println!(
+		"  Uptime: {}h {}m {}s",
+		uptime.num_hours(),
+		uptime.num_minutes() % 60,
+		uptime.num_seconds() % 60
+	);
+	println!("  Total requests: {}", snapshot.total_requests);
+
+	println!();
+	println!("Sessions ({}):", snapshot.sessions.len());
+	for session in &snapshot.sessions {
+		let client = match (&session.client_name, &session.client_version) {
+			(Some(name), Some(version)) => format!("{} {}", name, version),
+			(Some(name), None) => name.clone(),
+			_ => "unknown client".to_string(),
+		};
+		println!(
+			"  - {} [{}] {} requests, last active {}",
+			client,
+			session.key,
+			session.requests,
+			session.last_activity.format("%Y-%m-%d %H:%M:%S UTC")
+		);
+	}
+
+	println!();
+	println!("In-flight tool calls ({}):", snapshot.in_flight.len());
+	for call in &snapshot.in_flight {
+		println!(
+			"  - {} from {} running for {}ms",
+			call.tool, call.session, call.elapsed_ms
+		);
+	}
+
+	println!();
+	println!("Recent errors ({}):", snapshot.recent_errors.len());
+	for error in snapshot.recent_errors.iter().rev() {
+		println!(
+			"  - {} {}{}: {}",
+			error.timestamp.format("%Y-%m-%d %H:%M:%S"),
+			error.method,
+			error
+				.tool
+				.as_ref()
+				.map(|t| format!(" ({})", t))
+				.unwrap_or_default(),
+			error.message
+		);
+	}
+
+	println!();
+	println!("Index:");
+	if snapshot.index.indexing_in_progress {
+		println!("  Reindex in progress");
+	}
+	match snapshot.index.last_indexed_at {
+		Some(at) => println!(
+			"  Last reindex: {} ({}, {}ms)",
+			at.format("%Y-%m-%d %H:%M:%S UTC"),
+			if snapshot.index.last_success == Some(true) {
+				"success"
+			} else {
+				"failed"
+			},
+			snapshot.index.last_duration_ms.unwrap_or(0)
+		),
+		None => println!("  No reindex since server start"),
+	}
+	println!("  Reindex runs: {}", snapshot.index.reindex_count);
+}
@@ -13,15 +13,30 @@
 // limitations under the License.
 
 use anyhow::Result;
-use clap::Args;
+use clap::{Args, Subcommand};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::io::AsyncReadExt;
+use tokio::sync::Semaphore;
 
 use octocode::mcp::proxy::McpProxyServer;
 
+use crate::commands::OutputFormat;
+
+/// Number of trailing stderr lines kept for a failed repository
+const FAILURE_TAIL_LINES: usize = 5;
+
 #[derive(Args, Clone)]
 pub struct McpProxyArgs {
+	#[command(subcommand)]
+	pub command: Option<McpProxyCommand>,
+
 	/// Bind to HTTP server on host:port (required for proxy mode)
 	#[arg(long, value_name = "HOST:PORT")]
-	pub bind: String,
+	pub bind: Option<String>,
 
 	/// Root path to scan for git repositories (defaults to current directory)
 	#[arg(long, default_value = ".")]
@@ -32,6 +47,29 @@ pub struct McpProxyArgs {
 	pub debug: bool,
 }
 
+#[derive(Subcommand, Clone)]
+pub enum McpProxyCommand {
+	/// Incrementally index every repository the proxy serves, in parallel
+	IndexAll {
+		/// Maximum number of repositories indexed at the same time
+		#[arg(long, default_value = "4")]
+		concurrency: usize,
+
+		/// Output format
+		#[arg(long, value_enum, default_value = "cli")]
+		format: OutputFormat,
+	},
+}
+
+/// Outcome of indexing a single repository
+#[derive(Debug, Serialize)]
+struct RepositoryIndexResult {
+	repository: String,
+	success: bool,
+	duration_ms: u64,
+	error: Option<String>,
+}
+
 pub async fn run(args: McpProxyArgs) -> Result<()> {
 	// Convert path to absolute PathBuf
 	let root_path = if args.path == "." {
@@ -51,11 +89,21 @@ pub async fn run(args: McpProxyArgs) -> Result<()> {
 		));
 	}
 
+	if let Some(McpProxyCommand::IndexAll {
+		concurrency,
+		format,
+	}) = &args.command
+	{
+		return index_all(&root_path, *concurrency, format).await;
+	}
+
 	// Parse bind address
-	let bind_addr = args
+	let bind = args
 		.bind
+		.ok_or_else(|| anyhow::anyhow!("--bind is required to run the proxy server"))?;
+	let bind_addr = bind
 		.parse::<std::net::SocketAddr>()
-		.map_err(|e| anyhow::anyhow!("Invalid bind address '{}': {}", args.bind, e))?;
+		.map_err(|e| anyhow::anyhow!("Invalid bind address '{}': {}", bind, e))?;
 
 	// Print startup info to console since this is HTTP mode
 	println!("🚀 Starting MCP Proxy Server...");
@@ -67,3 +115,229 @@ pub async fn run(args: McpProxyArgs) -> Result<()> {
 	let mut proxy_server = McpProxyServer::new(bind_addr, root_path, args.debug).await?;
 	proxy_server.run().await
 }
+
+/// Run `octocode index` in every discovered repository with bounded parallelism.
+///
+/// Each repository is indexed by a child process started in the repository
+/// directory, since the store and indexer are bound to the current directory.
+async fn index_all(root_path: &Path, concurrency: usize, format: &OutputFormat) -> Result<()> {
+	if concurrency == 0 {
+		return Err(anyhow::anyhow!("--concurrency must be at least 1"));
+	}
+
+	let repositories = McpProxyServer::discover_repositories_under(root_path)?;
+	if repositories.is_empty() {
+		if format.is_json() {
+			println!("[]");
+		} else {
+			println!(
+				"⚠️  No git repositories found under {}",
+				root_path.display()
+			);
+		}
+		return Ok(());
+	}
+
+	let exe = std::env::current_exe()
+		.map_err(|e| anyhow::anyhow!("Failed to locate octocode executable: {}", e))?;
+	let total = repositories.len();
+	if !format.is_json() {
+		println!(
+			"📚 Indexing {} repositories (concurrency {})...",
+			total, concurrency
+		);
+	}
+
+	let results = index_repositories(
+		&exe,
+		root_path,
+		repositories,
+		concurrency,
+		|done, result| {
+			if !format.is_json() {
+				println!(
+					"[{}/{}] {} {} ({:.1}s)",
+					done,
+					total,
+					if result.success { "✅" } else { "❌" },
+					result.repository,
+					result.duration_ms as f64 / 1000.0
+				);
+			}
+		},
+	)
+	.await?;
+
+	let failed: Vec<&RepositoryIndexResult> = results.iter().filter(|r| !r.success).collect();
+	if format.is_json() {
+		println!("{}", serde_json::to_string_pretty(&results)?);
+	} else {
+		println!();
+		println!("Indexed {} of {} repositories", total - failed.len(), total);
+		for result in &failed {
+			println!("\n❌ {}:", result.repository);
+			for line in result.error.as_deref().unwrap_or("unknown error").lines() {
+				println!("   {}", line);
+			}
+		}
+	}
+
+	if failed.is_empty() {
+		Ok(())
+	} else {
+		Err(anyhow::anyhow!(
+			"{} of {} repositories failed to index",
+			failed.len(),
+			total
+		))
+	}
+}
+
+/// Index `repositories` with `exe`, at most `concurrency` at a time, calling
+/// `on_done` with the running count as each finishes; results are sorted by name
+async fn index_repositories(
+	exe: &Path,
+	root_path: &Path,
+	repositories: Vec<PathBuf>,
+	concurrency: usize,
+	mut on_done: impl FnMut(usize, &RepositoryIndexResult),
+) -> Result<Vec<RepositoryIndexResult>> {
+	let semaphore = Arc::new(Semaphore::new(concurrency));
+	let mut tasks = tokio::task::JoinSet::new();
+	let total = repositories.len();
+	for repository in repositories {
+		let semaphore = semaphore.clone();
+		let exe = exe.to_path_buf();
+		let name = repository
+			.strip_prefix(root_path)
+			.unwrap_or(&repository)
+			.display()
+			.to_string();
+		tasks.spawn(async move {
+			let _permit = semaphore.acquire_owned().await;
+			index_repository(&exe, repository, name).await
+		});
+	}
+
+	let mut results = Vec::with_capacity(total);
+	while let Some(joined) = tasks.join_next().await {
+		let result = joined.map_err(|e| anyhow::anyhow!("Indexing task failed: {}", e))?;
+		on_done(results.len() + 1, &result);
+		results.push(result);
+	}
+	results.sort_by(|a, b| a.repository.cmp(&b.repository));
+	Ok(results)
+}
+
+/// Index one repository in a child process and capture the failure reason
+async fn index_repository(exe: &Path, repository: PathBuf, name: String) -> RepositoryIndexResult {
+	let started = Instant::now();
+	let outcome: Result<()> = async {
+		let mut child = tokio::process::Command::new(exe)
+			.arg("index")
+			.current_dir(&repository)
+			.stdin(Stdio::null())
+			.stdout(Stdio::null())
+			.stderr(Stdio::piped())
+			.kill_on_drop(true)
+			.spawn()
+			.map_err(|e| anyhow::anyhow!("Failed to start indexer: {}", e))?;
+
+		let mut stderr = String::new();
+		if let Some(mut pipe) = child.stderr.take() {
+			pipe.read_to_string(&mut stderr).await?;
+		}
+		let status = child.wait().await?;
+		if status.success() {
+			return Ok(());
+		}
+
+		let lines: Vec<&str> = stderr.lines().filter(|l| !l.trim().is_empty()).collect();
+		let tail = lines[lines.len().saturating_sub(FAILURE_TAIL_LINES)..].join("\n");
+		Err(anyhow::anyhow!(
+			"{}{}",
+			status,
+			if tail.is_empty() {
+				String::new()
+			} else {
+				format!("\n{}", tail)
+			}
+		))
+	}
+	.await;
+
+	RepositoryIndexResult {
+		repository: name,
+		success: outcome.is_ok(),
+		duration_ms: started.elapsed().as_millis() as u64,
+		error: outcome.err().map(|e| e.to_string()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Stand-in for the octocode executable: `index` fails with seven lines
+	/// on stderr in directories holding a `fail` file
+	#[cfg(unix)]
+	fn fake_indexer(dir: &Path) -> PathBuf {
+		use std::os::unix::fs::PermissionsExt;
+		let exe = dir.join("fake-octocode");
+		std::fs::write(
+			&exe,
+			"#!/bin/sh\n[ \"$1\" = index ] || exit 9\n[ -f fail ] || exit 0\nfor i in 1 2 3 4 5 6 7; do echo \"error line $i\" >&2; done\nexit 2\n",
+		)
+		.unwrap();
+		std::fs::set_permissions(&exe, std::fs::Permissions::from_mode(0o755)).unwrap();
+		exe
+	}
+
+	#[cfg(unix)]
+	#[tokio::test]
+	async fn test_index_repositories_reports_each_outcome() {
+		let root = std::env::temp_dir().join(format!("octocode_index_all_{}", std::process::id()));
+		for repository in ["api", "web", "libs/core", "node_modules/dep"] {
+			std::fs::create_dir_all(root.join(repository).join(".git")).unwrap();
+		}
+		std::fs::write(root.join("web/fail"), "").unwrap();
+		let bin = root.join(".bin");
+		std::fs::create_dir_all(&bin).unwrap();
+		let exe = fake_indexer(&bin);
+
+		let repositories = McpProxyServer::discover_repositories_under(&root).unwrap();
+		let mut progress = Vec::new();
+		let results = index_repositories(&exe, &root, repositories, 2, |done, result| {
+			progress.push((done, result.repository.clone()))
+		})
+		.await
+		.unwrap();
+
+		let names: Vec<&str> = results.iter().map(|r| r.repository.as_str()).collect();
+		assert_eq!(names, vec!["api", "libs/core", "web"]);
+		assert_eq!(
+			progress.iter().map(|(done, _)| *done).collect::<Vec<_>>(),
+			vec![1, 2, 3]
+		);
+		assert!(results[0].success && results[1].success);
+		assert!(results[0].error.is_none());
+
+		let failed = &results[2];
+		assert!(!failed.success);
+		let error = failed.error.as_deref().unwrap();
+		assert!(error.contains("exit status: 2"), "{}", error);
+		// Only the last lines of stderr are kept
+		assert!(error.contains("error line 3") && error.contains("error line 7"));
+		assert!(!error.contains("error line 2"));
+
+		let _ = std::fs::remove_dir_all(&root);
+	}
+
+	#[tokio::test]
+	async fn test_index_all_needs_some_concurrency() {
+		let err = index_all(&std::env::temp_dir(), 0, &OutputFormat::Json)
+			.await
+			.unwrap_err();
+		assert!(err.to_string().contains("--concurrency"));
+	}
+}
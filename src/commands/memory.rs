@@ -16,15 +16,69 @@ use anyhow::Result;
 use clap::{Args, Subcommand};
 use serde_json::Value;
 use std::io::{self, Write};
+use std::path::PathBuf;
 
 use octocode::config::Config;
 use octocode::constants::MAX_QUERIES;
-use octocode::memory::{MemoryManager, MemoryQuery, MemoryType};
+use octocode::memory::{
+	timeline, validate_namespace, ExportFormat, Memorized, MemoryExport, MemoryManager,
+	MemoryQuery, MemoryType, TimelineFilter, DEFAULT_NAMESPACE,
+};
+
+use crate::commands::OutputFormat;
 
 #[derive(Args, Debug)]
 pub struct MemoryArgs {
 	#[command(subcommand)]
 	pub command: MemoryCommand,
+
+	/// Print JSON for every subcommand (set by the global --output flag)
+	#[arg(skip)]
+	pub json_output: bool,
+}
+
+impl MemoryArgs {
+	/// Apply the global --output flag to the subcommand's own format option
+	pub fn apply_output_format(&mut self, output: &OutputFormat) {
+		self.json_output = output.is_json();
+		let value = match output {
+			OutputFormat::Json => "json",
+			OutputFormat::Text => "compact",
+			OutputFormat::Cli | OutputFormat::Md => "text",
+		};
+
+		match &mut self.command {
+			MemoryCommand::Remember { format, .. }
+			| MemoryCommand::Get { format, .. }
+			| MemoryCommand::Recent { format, .. }
+			| MemoryCommand::ByType { format, .. }
+			| MemoryCommand::ForFiles { format, .. }
+			| MemoryCommand::ByTags { format, .. }
+			| MemoryCommand::CurrentCommit { format }
+			| MemoryCommand::Timeline { format, .. }
+			| MemoryCommand::Relationships { format, .. }
+			| MemoryCommand::Related { format, .. } => *format = value.to_string(),
+			_ => {}
+		}
+	}
+
+	/// Whether output must be JSON, from --output or the subcommand's own --format
+	fn wants_json(&self) -> bool {
+		self.json_output
+			|| matches!(
+				&self.command,
+				MemoryCommand::Remember { format, .. }
+				| MemoryCommand::Get { format, .. }
+				| MemoryCommand::Recent { format, .. }
+				| MemoryCommand::ByType { format, .. }
+				| MemoryCommand::ForFiles { format, .. }
+				| MemoryCommand::ByTags { format, .. }
+				| MemoryCommand::CurrentCommit { format }
+				| MemoryCommand::Timeline { format, .. }
+				| MemoryCommand::Relationships { format, .. }
+				| MemoryCommand::Related { format, .. } if format == "json"
+			)
+	}
 }
 
 #[derive(Subcommand, Debug)]
@@ -54,6 +108,14 @@ pub enum MemoryCommand {
 		/// Related file paths (comma-separated)
 		#[arg(long)]
 		files: Option<String>,
+
+		/// Namespace of the agent or session the memory belongs to
+		#[arg(long, default_value = DEFAULT_NAMESPACE)]
+		namespace: String,
+
+		/// Store the memory even when it duplicates an existing one
+		#[arg(long)]
+		force: bool,
 	},
 
 	/// Search and retrieve stored memories using semantic search
@@ -81,6 +143,14 @@ pub enum MemoryCommand {
 		#[arg(long)]
 		min_relevance: Option<f32>,
 
+		/// Namespace to search
+		#[arg(long, default_value = DEFAULT_NAMESPACE)]
+		namespace: String,
+
+		/// Search every namespace instead of only --namespace
+		#[arg(long, conflicts_with = "namespace")]
+		all_namespaces: bool,
+
 		/// Output format: text, json, or compact
 		#[arg(short, long, default_value = "text")]
 		format: String,
@@ -104,11 +174,40 @@ pub enum MemoryCommand {
 		#[arg(long)]
 		tags: Option<String>,
 
+		/// Namespace whose memories the query may delete
+		#[arg(long, default_value = DEFAULT_NAMESPACE)]
+		namespace: String,
+
 		/// Confirm deletion without prompting
 		#[arg(short = 'y', long)]
 		yes: bool,
 	},
 
+	/// Scrub a memory's content, keeping a tombstone with its ID, type and dates
+	Redact {
+		/// Memory ID to redact
+		memory_id: String,
+
+		/// Why the memory is redacted, recorded in the audit log
+		#[arg(short, long)]
+		reason: Option<String>,
+
+		/// Confirm redaction without prompting
+		#[arg(short = 'y', long)]
+		yes: bool,
+	},
+
+	/// Show the append-only audit log of memory changes
+	Audit {
+		/// Only entries for this memory ID
+		#[arg(short, long)]
+		memory_id: Option<String>,
+
+		/// Show only the last N entries
+		#[arg(short, long)]
+		limit: Option<usize>,
+	},
+
 	/// Update an existing memory
 	Update {
 		/// Memory ID to update
@@ -154,6 +253,7 @@ pub enum MemoryCommand {
 	},
 
 	/// List recent memories
+	#[command(visible_alias = "list")]
 	Recent {
 		/// Maximum number of memories to show
 		#[arg(short, long, default_value = "20")]
@@ -163,6 +263,10 @@ pub enum MemoryCommand {
 		#[arg(short = 'm', long)]
 		memory_type: Option<String>,
 
+		/// Only memories of this namespace (default: all namespaces)
+		#[arg(long)]
+		namespace: Option<String>,
+
 		/// Output format: text, json, or compact
 		#[arg(short, long, default_value = "compact")]
 		format: String,
@@ -209,6 +313,41 @@ pub enum MemoryCommand {
 		format: String,
 	},
 
+	/// List memories chronologically, grouped by day or week
+	Timeline {
+		/// Filter by memory types (comma-separated)
+		#[arg(short = 'm', long)]
+		memory_types: Option<String>,
+
+		/// Filter by tags (comma-separated)
+		#[arg(long)]
+		tags: Option<String>,
+
+		/// Only memories with a related file under this path
+		#[arg(long)]
+		scope: Option<String>,
+
+		/// Start date (YYYY-MM-DD) or span back from now (e.g. 14d, 2w)
+		#[arg(long)]
+		since: Option<String>,
+
+		/// End date (YYYY-MM-DD, exclusive)
+		#[arg(long)]
+		until: Option<String>,
+
+		/// Group by day or week
+		#[arg(long, default_value = "week")]
+		by: String,
+
+		/// Summarize each period with the configured LLM
+		#[arg(long)]
+		digest: bool,
+
+		/// Output format: text, json, or compact
+		#[arg(short, long, default_value = "text")]
+		format: String,
+	},
+
 	/// Show memory statistics
 	Stats,
 
@@ -219,6 +358,15 @@ pub enum MemoryCommand {
 		yes: bool,
 	},
 
+	/// Archive rarely used, low-importance memories beyond memory.max_memories
+	Prune,
+
+	/// Bring an archived memory back into the store
+	Restore {
+		/// Archived memory ID
+		memory_id: String,
+	},
+
 	/// Clear ALL memory data (DANGEROUS: deletes everything)
 	ClearAll {
 		/// Confirm deletion without prompting
@@ -266,10 +414,41 @@ pub enum MemoryCommand {
 		#[arg(short, long, default_value = "text")]
 		format: String,
 	},
+
+	/// Export all memories and relationships to a JSON or Markdown file
+	Export {
+		/// File to write; prints to stdout when omitted
+		#[arg(long)]
+		file: Option<PathBuf>,
+
+		/// File format: json or md (default: from the file extension, JSON otherwise)
+		#[arg(long)]
+		format: Option<ExportFormat>,
+
+		/// Include embedding vectors (JSON only) so imports with the same model skip re-embedding
+		#[arg(long)]
+		with_embeddings: bool,
+	},
+
+	/// Import memories and relationships from an exported file
+	Import {
+		/// File written by `memory export`
+		#[arg(long)]
+		file: PathBuf,
+
+		/// File format: json or md (default: from the file extension, JSON otherwise)
+		#[arg(long)]
+		format: Option<ExportFormat>,
+
+		/// Recompute embeddings even when the file contains usable ones
+		#[arg(long)]
+		reembed: bool,
+	},
 }
 
 pub async fn execute(config: &Config, args: &MemoryArgs) -> Result<()> {
 	let mut memory_manager = MemoryManager::new(config).await?;
+	let json_output = args.wants_json();
 
 	match &args.command {
 		MemoryCommand::Memorize {
@@ -279,6 +458,8 @@ pub async fn execute(config: &Config, args: &MemoryArgs) -> Result<()> {
 			importance,
 			tags,
 			files,
+			namespace,
+			force,
 		} => {
 			// Validate input lengths
 			if title.len() < 5 || title.len() > 200 {
@@ -300,7 +481,7 @@ pub async fn execute(config: &Config, args: &MemoryArgs) -> Result<()> {
 				.as_ref()
 				.map(|f| f.split(',').map(|s| s.trim().to_string()).collect());
 
-			let memory = memory_manager
+			let memorized = memory_manager
 				.memorize(
 					mem_type,
 					title.clone(),
@@ -308,13 +489,30 @@ pub async fn execute(config: &Config, args: &MemoryArgs) -> Result<()> {
 					*importance,
 					tags_vec,
 					files_vec,
+					namespace,
+					*force,
 				)
 				.await?;
+			let memory = memorized.memory();
 
-			println!("✅ Memory stored successfully!");
+			if json_output {
+				println!("{}", serde_json::to_string_pretty(memory)?);
+				return Ok(());
+			}
+
+			match memorized {
+				Memorized::Created(_) => println!("✅ Memory stored successfully!"),
+				Memorized::Merged { distance, .. } => println!(
+					"✅ Merged into an existing memory (distance {:.3}); use --force to store it separately",
+					distance
+				),
+			}
 			println!("Memory ID: {}", memory.id);
 			println!("Type: {}", memory.memory_type);
 			println!("Title: {}", memory.title);
+			if memory.namespace != DEFAULT_NAMESPACE {
+				println!("Namespace: {}", memory.namespace);
+			}
 			if let Some(imp) = importance {
 				println!("Importance: {:.2}", imp);
 			}
@@ -327,8 +525,12 @@ pub async fn execute(config: &Config, args: &MemoryArgs) -> Result<()> {
 			files,
 			limit,
 			min_relevance,
+			namespace,
+			all_namespaces,
 			format,
 		} => {
+			validate_namespace(namespace)?;
+
 			let mem_types = memory_types.as_ref().map(|types| {
 				types
 					.split(',')
@@ -345,6 +547,7 @@ pub async fn execute(config: &Config, args: &MemoryArgs) -> Result<()> {
 				.map(|f| f.split(',').map(|s| s.trim().to_string()).collect());
 
 			let memory_query = MemoryQuery {
+				namespaces: (!all_namespaces).then(|| vec![namespace.clone()]),
 				memory_types: mem_types,
 				tags: tags_vec,
 				related_files: files_vec,
@@ -355,27 +558,30 @@ pub async fn execute(config: &Config, args: &MemoryArgs) -> Result<()> {
 
 			// Validate queries
 			if queries.is_empty() {
-				println!("❌ No queries provided.");
-				return Ok(());
+				return invalid_input(json_output, "No queries provided.".to_string());
 			}
 
 			if queries.len() > MAX_QUERIES {
-				println!(
-					"❌ Too many queries: maximum {} queries allowed, got {}.",
-					MAX_QUERIES,
-					queries.len()
+				return invalid_input(
+					json_output,
+					format!(
+						"Too many queries: maximum {} queries allowed, got {}.",
+						MAX_QUERIES,
+						queries.len()
+					),
 				);
-				return Ok(());
 			}
 
 			// Validate each query
 			for query in queries {
 				if query.len() < 3 || query.len() > 500 {
-					println!(
-						"❌ Each query must be between 3 and 500 characters. Invalid query: '{}'",
-						query
+					return invalid_input(
+						json_output,
+						format!(
+							"Each query must be between 3 and 500 characters. Invalid query: '{}'",
+							query
+						),
 					);
-					return Ok(());
 				}
 			}
 
@@ -392,8 +598,12 @@ pub async fn execute(config: &Config, args: &MemoryArgs) -> Result<()> {
 			};
 
 			if results.is_empty() {
-				println!("❌ No memories found matching your query.");
-				println!("Try using different search terms or removing filters.");
+				if json_output {
+					println!("[]");
+				} else {
+					println!("❌ No memories found matching your query.");
+					println!("Try using different search terms or removing filters.");
+				}
 				return Ok(());
 			}
 
@@ -406,6 +616,7 @@ pub async fn execute(config: &Config, args: &MemoryArgs) -> Result<()> {
 								"memory_id": r.memory.id,
 								"title": r.memory.title,
 								"memory_type": r.memory.memory_type.to_string(),
+								"namespace": r.memory.namespace,
 								"relevance_score": r.relevance_score,
 								"content": r.memory.content,
 								"created_at": r.memory.created_at.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
@@ -438,6 +649,9 @@ pub async fn execute(config: &Config, args: &MemoryArgs) -> Result<()> {
 						println!("{}. Memory ID: {}", i + 1, result.memory.id);
 						println!("   Title: {}", result.memory.title);
 						println!("   Type: {}", result.memory.memory_type);
+						if result.memory.namespace != DEFAULT_NAMESPACE {
+							println!("   Namespace: {}", result.memory.namespace);
+						}
 						println!("   Relevance: {:.2}", result.relevance_score);
 						println!("   Importance: {:.2}", result.memory.metadata.importance);
 						println!(
@@ -466,8 +680,15 @@ pub async fn execute(config: &Config, args: &MemoryArgs) -> Result<()> {
 			query,
 			memory_types,
 			tags,
+			namespace,
 			yes,
 		} => {
+			if json_output && !yes {
+				return Err(anyhow::anyhow!(
+					"--yes is required to forget memories with --output json"
+				));
+			}
+
 			if let Some(id) = memory_id {
 				if !yes {
 					print!("Are you sure you want to delete memory '{}'? (y/N): ", id);
@@ -481,7 +702,11 @@ pub async fn execute(config: &Config, args: &MemoryArgs) -> Result<()> {
 				}
 
 				memory_manager.forget(id).await?;
-				println!("✅ Memory '{}' deleted successfully.", id);
+				if json_output {
+					println!("{}", serde_json::json!({ "deleted": [id] }));
+				} else {
+					println!("✅ Memory '{}' deleted successfully.", id);
+				}
 			} else if let Some(q) = query {
 				if q.len() < 3 || q.len() > 500 {
 					return Err(anyhow::anyhow!(
@@ -500,8 +725,11 @@ pub async fn execute(config: &Config, args: &MemoryArgs) -> Result<()> {
 					.as_ref()
 					.map(|t| t.split(',').map(|s| s.trim().to_string()).collect());
 
+				validate_namespace(namespace)?;
+
 				let memory_query = MemoryQuery {
 					query_text: Some(q.clone()),
+					namespaces: Some(vec![namespace.clone()]),
 					memory_types: mem_types,
 					tags: tags_vec,
 					..Default::default()
@@ -539,7 +767,11 @@ pub async fn execute(config: &Config, args: &MemoryArgs) -> Result<()> {
 				}
 
 				let deleted_count = memory_manager.forget_matching(memory_query).await?;
-				println!("✅ {} memories deleted successfully.", deleted_count);
+				if json_output {
+					println!("{}", serde_json::json!({ "deleted_count": deleted_count }));
+				} else {
+					println!("✅ {} memories deleted successfully.", deleted_count);
+				}
 			} else {
 				return Err(anyhow::anyhow!(
 					"Either --memory-id or --query must be provided"
@@ -563,7 +795,11 @@ pub async fn execute(config: &Config, args: &MemoryArgs) -> Result<()> {
 				.await?;
 
 			if updated_memory.is_none() {
-				println!("❌ Memory '{}' not found.", memory_id);
+				if json_output {
+					println!("null");
+				} else {
+					println!("❌ Memory '{}' not found.", memory_id);
+				}
 				return Ok(());
 			}
 
@@ -597,7 +833,12 @@ pub async fn execute(config: &Config, args: &MemoryArgs) -> Result<()> {
 				}
 			}
 
-			println!("✅ Memory '{}' updated successfully.", memory_id);
+			if json_output {
+				let memory = memory_manager.get_memory(memory_id).await?;
+				println!("{}", serde_json::to_string_pretty(&memory)?);
+			} else {
+				println!("✅ Memory '{}' updated successfully.", memory_id);
+			}
 		}
 
 		MemoryCommand::Get { memory_id, format } => {
@@ -628,6 +869,8 @@ pub async fn execute(config: &Config, args: &MemoryArgs) -> Result<()> {
 						println!("Content:\n{}", memory.content);
 					}
 				}
+			} else if json_output {
+				println!("null");
 			} else {
 				println!("❌ Memory '{}' not found.", memory_id);
 			}
@@ -636,19 +879,22 @@ pub async fn execute(config: &Config, args: &MemoryArgs) -> Result<()> {
 		MemoryCommand::Recent {
 			limit,
 			memory_type,
+			namespace,
 			format,
 		} => {
 			let memories = if let Some(mem_type) = memory_type {
 				let parsed_type = MemoryType::from(mem_type.clone());
 				memory_manager
-					.get_memories_by_type(parsed_type, Some(*limit))
+					.get_memories_by_type(parsed_type, Some(*limit), namespace.as_deref())
 					.await?
 			} else {
-				memory_manager.get_recent_memories(*limit).await?
+				memory_manager
+					.get_recent_memories(*limit, namespace.as_deref())
+					.await?
 			};
 
 			if memories.is_empty() {
-				println!("❌ No recent memories found.");
+				print_empty(json_output, "❌ No recent memories found.");
 				return Ok(());
 			}
 
@@ -662,11 +908,14 @@ pub async fn execute(config: &Config, args: &MemoryArgs) -> Result<()> {
 		} => {
 			let parsed_type = MemoryType::from(memory_type.clone());
 			let memories = memory_manager
-				.get_memories_by_type(parsed_type, Some(*limit))
+				.get_memories_by_type(parsed_type, Some(*limit), None)
 				.await?;
 
 			if memories.is_empty() {
-				println!("❌ No memories found for type '{}'.", memory_type);
+				print_empty(
+					json_output,
+					&format!("❌ No memories found for type '{}'.", memory_type),
+				);
 				return Ok(());
 			}
 
@@ -678,7 +927,7 @@ pub async fn execute(config: &Config, args: &MemoryArgs) -> Result<()> {
 			let results = memory_manager.get_memories_for_files(file_paths).await?;
 
 			if results.is_empty() {
-				println!("❌ No memories found for the specified files.");
+				print_empty(json_output, "❌ No memories found for the specified files.");
 				return Ok(());
 			}
 
@@ -690,7 +939,7 @@ pub async fn execute(config: &Config, args: &MemoryArgs) -> Result<()> {
 			let results = memory_manager.get_memories_by_tags(tag_list).await?;
 
 			if results.is_empty() {
-				println!("❌ No memories found for the specified tags.");
+				print_empty(json_output, "❌ No memories found for the specified tags.");
 				return Ok(());
 			}
 
@@ -701,19 +950,89 @@ pub async fn execute(config: &Config, args: &MemoryArgs) -> Result<()> {
 			let memories = memory_manager.get_memories_for_current_commit().await?;
 
 			if memories.is_empty() {
-				println!("❌ No memories found for the current Git commit.");
+				print_empty(
+					json_output,
+					"❌ No memories found for the current Git commit.",
+				);
 				return Ok(());
 			}
 
 			format_memories(&memories, format);
 		}
 
+		MemoryCommand::Timeline {
+			memory_types,
+			tags,
+			scope,
+			since,
+			until,
+			by,
+			digest,
+			format,
+		} => {
+			let now = chrono::Utc::now();
+			let filter = TimelineFilter {
+				memory_types: memory_types.as_ref().map(|types| {
+					types
+						.split(',')
+						.map(|s| MemoryType::from(s.trim().to_string()))
+						.collect()
+				}),
+				tags: tags
+					.as_ref()
+					.map(|t| t.split(',').map(|s| s.trim().to_string()).collect()),
+				scope: scope.clone(),
+				since: since
+					.as_deref()
+					.map(|since| timeline::parse_since(since, now))
+					.transpose()?,
+				until: until
+					.as_deref()
+					.map(|until| timeline::parse_since(until, now))
+					.transpose()?,
+			};
+
+			let mut groups = memory_manager.timeline(&filter, by.parse()?).await?;
+			if *digest {
+				for group in &mut groups {
+					match timeline::digest(config, group).await {
+						Ok(summary) => group.digest = Some(summary),
+						Err(e) => {
+							eprintln!("Warning: digest for {} failed: {}", group.period, e);
+						}
+					}
+				}
+			}
+
+			if format == "json" {
+				println!("{}", serde_json::to_string_pretty(&groups)?);
+			} else {
+				print!(
+					"{}",
+					octocode::memory::formatting::format_timeline_as_text(
+						&groups,
+						format == "compact"
+					)
+				);
+				println!();
+			}
+		}
+
 		MemoryCommand::Stats => {
 			let stats = memory_manager.get_memory_stats().await?;
-			print!("{}", stats.format());
+			if json_output {
+				println!("{}", serde_json::to_string_pretty(&stats)?);
+			} else {
+				print!("{}", stats.format());
+			}
 		}
 
 		MemoryCommand::Cleanup { yes } => {
+			if json_output && !yes {
+				return Err(anyhow::anyhow!(
+					"--yes is required to clean up memories with --output json"
+				));
+			}
 			if !yes {
 				print!("Are you sure you want to clean up old memories? (y/N): ");
 				io::stdout().flush()?;
@@ -726,10 +1045,116 @@ pub async fn execute(config: &Config, args: &MemoryArgs) -> Result<()> {
 			}
 
 			let cleaned_count = memory_manager.cleanup().await?;
-			println!("✅ Cleaned up {} old memories.", cleaned_count);
+			if json_output {
+				println!("{}", serde_json::json!({ "cleaned_count": cleaned_count }));
+			} else {
+				println!("✅ Cleaned up {} old memories.", cleaned_count);
+			}
+		}
+
+		MemoryCommand::Redact {
+			memory_id,
+			reason,
+			yes,
+		} => {
+			if json_output && !yes {
+				return Err(anyhow::anyhow!(
+					"--yes is required to redact memories with --output json"
+				));
+			}
+			if !yes {
+				println!(
+					"⚠️  This permanently removes the memory's title, content, tags and files."
+				);
+				print!("Redact memory '{}'? (y/N): ", memory_id);
+				io::stdout().flush()?;
+				let mut input = String::new();
+				io::stdin().read_line(&mut input)?;
+				if !input.trim().to_lowercase().starts_with('y') {
+					println!("Redaction cancelled.");
+					return Ok(());
+				}
+			}
+
+			let redacted = memory_manager.redact(memory_id, reason.clone()).await?;
+			if json_output {
+				println!("{}", serde_json::to_string_pretty(&redacted)?);
+			} else if redacted.is_some() {
+				println!("✅ Memory '{}' redacted; its tombstone remains.", memory_id);
+			} else {
+				println!("❌ Memory '{}' not found.", memory_id);
+			}
+		}
+
+		MemoryCommand::Audit { memory_id, limit } => {
+			let mut entries = memory_manager.audit_log(memory_id.as_deref())?;
+			if let Some(limit) = limit {
+				entries.drain(..entries.len().saturating_sub(*limit));
+			}
+
+			if json_output {
+				println!("{}", serde_json::to_string_pretty(&entries)?);
+			} else if entries.is_empty() {
+				println!("No audit log entries.");
+			} else {
+				for entry in &entries {
+					let mut line = format!(
+						"{}  {:<8}  {}",
+						entry.timestamp.format("%Y-%m-%d %H:%M:%S"),
+						entry.action,
+						entry.memory_id
+					);
+					if let Some(memory_type) = &entry.memory_type {
+						line.push_str(&format!("  [{}]", memory_type));
+					}
+					if let Some(actor) = &entry.actor {
+						line.push_str(&format!("  by {}", actor));
+					}
+					if let Some(details) = &entry.details {
+						line.push_str(&format!("  ({})", details));
+					}
+					println!("{}", line);
+				}
+			}
+		}
+
+		MemoryCommand::Prune => {
+			let archived_count = memory_manager.prune().await?;
+			if json_output {
+				println!(
+					"{}",
+					serde_json::json!({ "archived_count": archived_count })
+				);
+			} else if archived_count == 0 {
+				println!("Nothing to prune.");
+			} else {
+				println!(
+					"✅ Archived {} rarely used memories. Restore one with `octocode memory restore <id>`.",
+					archived_count
+				);
+			}
+		}
+
+		MemoryCommand::Restore { memory_id } => {
+			let restored = memory_manager.restore(memory_id).await?;
+			if json_output {
+				println!(
+					"{}",
+					serde_json::json!({ "memory_id": memory_id, "restored": restored })
+				);
+			} else if restored {
+				println!("✅ Restored memory {}", memory_id);
+			} else {
+				println!("❌ No archived memory with ID {}", memory_id);
+			}
 		}
 
 		MemoryCommand::ClearAll { yes } => {
+			if json_output && !yes {
+				return Err(anyhow::anyhow!(
+					"--yes is required to clear memories with --output json"
+				));
+			}
 			if !yes {
 				println!(
 					"⚠️  WARNING: This will delete ALL memories and relationships permanently!"
@@ -745,10 +1170,14 @@ pub async fn execute(config: &Config, args: &MemoryArgs) -> Result<()> {
 			}
 
 			let deleted_count = memory_manager.clear_all().await?;
-			println!(
-				"✅ Cleared all memory data. {} records deleted.",
-				deleted_count
-			);
+			if json_output {
+				println!("{}", serde_json::json!({ "deleted_count": deleted_count }));
+			} else {
+				println!(
+					"✅ Cleared all memory data. {} records deleted.",
+					deleted_count
+				);
+			}
 		}
 
 		MemoryCommand::Relate {
@@ -779,6 +1208,11 @@ pub async fn execute(config: &Config, args: &MemoryArgs) -> Result<()> {
 				)
 				.await?;
 
+			if json_output {
+				println!("{}", serde_json::to_string_pretty(&relationship)?);
+				return Ok(());
+			}
+
 			println!("✅ Relationship created successfully!");
 			println!("Relationship ID: {}", relationship.id);
 			println!("Type: {}", relationship.relationship_type);
@@ -789,7 +1223,10 @@ pub async fn execute(config: &Config, args: &MemoryArgs) -> Result<()> {
 			let relationships = memory_manager.get_relationships(memory_id).await?;
 
 			if relationships.is_empty() {
-				println!("❌ No relationships found for memory '{}'.", memory_id);
+				print_empty(
+					json_output,
+					&format!("❌ No relationships found for memory '{}'.", memory_id),
+				);
 				return Ok(());
 			}
 
@@ -831,12 +1268,96 @@ pub async fn execute(config: &Config, args: &MemoryArgs) -> Result<()> {
 			let related_memories = memory_manager.get_related_memories(memory_id).await?;
 
 			if related_memories.is_empty() {
-				println!("❌ No related memories found for memory '{}'.", memory_id);
+				print_empty(
+					json_output,
+					&format!("❌ No related memories found for memory '{}'.", memory_id),
+				);
 				return Ok(());
 			}
 
 			format_memories(&related_memories, format);
 		}
+
+		MemoryCommand::Export {
+			file,
+			format,
+			with_embeddings,
+		} => {
+			let format = format.unwrap_or_else(|| {
+				file.as_deref()
+					.map(ExportFormat::from_path)
+					.unwrap_or(ExportFormat::Json)
+			});
+			if *with_embeddings && format == ExportFormat::Md {
+				return Err(anyhow::anyhow!(
+					"--with-embeddings requires the JSON export format"
+				));
+			}
+
+			let export = memory_manager.export(*with_embeddings).await?;
+			let contents = export.render(format)?;
+			let Some(file) = file else {
+				print!("{}", contents);
+				if !contents.ends_with('\n') {
+					println!();
+				}
+				return Ok(());
+			};
+
+			std::fs::write(file, contents)
+				.map_err(|e| anyhow::anyhow!("Failed to write {}: {}", file.display(), e))?;
+			if json_output {
+				println!(
+					"{}",
+					serde_json::json!({
+						"file": file,
+						"memories": export.memories.len(),
+						"relationships": export.relationships.len(),
+						"embeddings": export.embedding_model.is_some(),
+					})
+				);
+			} else {
+				println!(
+					"✅ Exported {} memories and {} relationships to {}",
+					export.memories.len(),
+					export.relationships.len(),
+					file.display()
+				);
+			}
+		}
+
+		MemoryCommand::Import {
+			file,
+			format,
+			reembed,
+		} => {
+			let format = format.unwrap_or_else(|| ExportFormat::from_path(file));
+			let contents = std::fs::read_to_string(file)
+				.map_err(|e| anyhow::anyhow!("Failed to read {}: {}", file.display(), e))?;
+			let export = MemoryExport::parse(&contents, format)?;
+
+			let summary = memory_manager.import(export, *reembed).await?;
+			if json_output {
+				println!("{}", serde_json::to_string_pretty(&summary)?);
+			} else {
+				println!(
+					"✅ Imported {} memories ({} with exported embeddings) and {} relationships",
+					summary.memories, summary.reused_embeddings, summary.relationships
+				);
+				if summary.skipped_relationships > 0 {
+					println!(
+						"⚠️  Skipped {} relationships that reference missing memories",
+						summary.skipped_relationships
+					);
+				}
+				if summary.archived > 0 {
+					println!(
+						"📦 Archived {} rarely used memories to stay within max_memories",
+						summary.archived
+					);
+				}
+			}
+		}
 	}
 
 	Ok(())
@@ -851,3 +1372,22 @@ fn format_search_results(results: &[octocode::memory::MemorySearchResult], forma
 	// Use shared formatting function
 	octocode::memory::format_memories_for_cli(results, format);
 }
+
+/// Report an empty result: `[]` in JSON mode, a human message otherwise
+fn print_empty(json_output: bool, message: &str) {
+	if json_output {
+		println!("[]");
+	} else {
+		println!("{}", message);
+	}
+}
+
+/// Reject invalid input: an error in JSON mode so stdout stays parseable
+fn invalid_input(json_output: bool, message: String) -> Result<()> {
+	if json_output {
+		Err(anyhow::anyhow!(message))
+	} else {
+		println!("❌ {}", message);
+		Ok(())
+	}
+}
@@ -12,39 +12,66 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod api_diff;
+pub mod benchmark;
+pub mod cache;
+pub mod calls;
 pub mod clear;
 pub mod commit;
+pub mod completion;
 pub mod config;
+pub mod export;
 pub mod format;
 pub mod graphrag;
+pub mod hooks;
+pub mod house_style;
+pub mod import;
 pub mod index;
 pub mod logs;
 pub mod mcp;
 pub mod mcp_proxy;
 pub mod memory;
 pub mod models;
+pub mod open;
 pub mod output_format;
 pub mod release;
+pub mod report;
 pub mod review;
 pub mod search;
+pub mod serve;
+pub mod stats;
+pub mod store;
 pub mod view;
 pub mod watch;
 
 // Re-export all the command structs and enums
+pub use api_diff::ApiDiffArgs;
+pub use benchmark::BenchmarkArgs;
+pub use cache::CacheArgs;
+pub use calls::CallsArgs;
 pub use clear::ClearArgs;
 pub use commit::CommitArgs;
+pub use completion::CompleteArgs;
 pub use config::ConfigArgs;
+pub use export::ExportArgs;
 pub use format::FormatArgs;
 pub use graphrag::GraphRAGArgs;
+pub use hooks::HooksArgs;
+pub use import::ImportArgs;
 pub use index::IndexArgs;
 pub use logs::LogsArgs;
 pub use mcp::McpArgs;
 pub use mcp_proxy::McpProxyArgs;
 pub use memory::MemoryArgs;
 pub use models::ModelsCommand;
+pub use open::OpenArgs;
 pub use output_format::OutputFormat;
 pub use release::ReleaseArgs;
+pub use report::ReportArgs;
 pub use review::ReviewArgs;
 pub use search::SearchArgs;
+pub use serve::ServeArgs;
+pub use stats::StatsArgs;
+pub use store::StoreArgs;
 pub use view::ViewArgs;
 pub use watch::WatchArgs;
@@ -74,6 +74,7 @@ async fn list_models(provider_filter: Option<String>) -> Result<()> {
 			EmbeddingProviderType::Voyage,
 			EmbeddingProviderType::Google,
 			EmbeddingProviderType::OpenAI,
+			EmbeddingProviderType::Mistral,
 		]
 	};
 
@@ -166,6 +167,18 @@ async fn list_models(provider_filter: Option<String>) -> Result<()> {
 				}
 				println!("  Use 'info' command for real-time API validation");
 			}
+			EmbeddingProviderType::Mistral => {
+				let mistral_models = [
+					("codestral-embed", 1536),
+					("codestral-embed-2505", 1536),
+					("mistral-embed", 1024),
+				];
+				println!("Found {} models:", mistral_models.len());
+				for (i, (model, dim)) in mistral_models.iter().enumerate() {
+					println!("  {}. {} ({}d)", i + 1, model, dim);
+				}
+				println!("  Use 'info' command for real-time API validation");
+			}
 		}
 	}
 
@@ -232,8 +245,9 @@ fn parse_provider(provider_str: &str) -> Result<EmbeddingProviderType> {
 		"voyage" => Ok(EmbeddingProviderType::Voyage),
 		"google" => Ok(EmbeddingProviderType::Google),
 		"openai" => Ok(EmbeddingProviderType::OpenAI),
+		"mistral" => Ok(EmbeddingProviderType::Mistral),
 		_ => Err(anyhow::anyhow!(
-			"Unknown provider '{}'. Supported: fastembed, huggingface, jina, voyage, google, openai",
+			"Unknown provider '{}'. Supported: fastembed, huggingface, jina, voyage, google, openai, mistral",
 			provider_str
 		)),
 	}
@@ -0,0 +1,78 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap::Args;
+use std::io::{BufRead, Write};
+
+use octocode::editor::{self, Location};
+
+#[derive(Args, Debug)]
+pub struct OpenArgs {
+	/// Number of the hit to open; lists the hits to pick from when omitted
+	pub number: Option<usize>,
+}
+
+/// Open a hit of the last search in the editor
+pub fn execute(args: &OpenArgs) -> anyhow::Result<()> {
+	let current_dir = std::env::current_dir()?;
+	let locations = editor::load_last_results(&current_dir)?;
+	if locations.is_empty() {
+		return Err(anyhow::anyhow!("The last search returned no results."));
+	}
+
+	let number = match args.number {
+		Some(number) => number,
+		None => match pick(&locations)? {
+			Some(number) => number,
+			None => return Ok(()),
+		},
+	};
+
+	let location = select(&locations, number)?;
+	editor::open_location(&current_dir, location)
+}
+
+/// Hit `number` (1-indexed) of a result list
+pub fn select(locations: &[Location], number: usize) -> anyhow::Result<&Location> {
+	number
+		.checked_sub(1)
+		.and_then(|index| locations.get(index))
+		.ok_or_else(|| {
+			anyhow::anyhow!(
+				"No result {}: the last search has {} results",
+				number,
+				locations.len()
+			)
+		})
+}
+
+/// Ask which hit to open; `None` when the user enters nothing
+fn pick(locations: &[Location]) -> anyhow::Result<Option<usize>> {
+	for (index, location) in locations.iter().enumerate() {
+		println!("{:>3}. {}", index + 1, location);
+	}
+	print!("Open result [1-{}]: ", locations.len());
+	std::io::stdout().flush()?;
+
+	let mut input = String::new();
+	std::io::stdin().lock().read_line(&mut input)?;
+	let input = input.trim();
+	if input.is_empty() {
+		return Ok(None);
+	}
+	input
+		.parse()
+		.map(Some)
+		.map_err(|_| anyhow::anyhow!("Invalid result number '{}'", input))
+}
@@ -23,6 +23,12 @@ use std::process::Command;
 use octocode::config::Config;
 use octocode::indexer::git_utils::GitUtils;
 
+mod publish;
+mod workspace;
+
+use publish::{PublishTarget, Publisher};
+use workspace::{WorkspaceKind, WorkspacePackage};
+
 #[derive(Args, Debug)]
 pub struct ReleaseArgs {
 	/// Changelog file path (default: CHANGELOG.md)
@@ -40,6 +46,40 @@ pub struct ReleaseArgs {
 	/// Force a specific version instead of AI calculation
 	#[arg(short, long)]
 	pub force_version: Option<String>,
+
+	/// Release only this workspace package (can be repeated)
+	#[arg(short, long = "package", value_name = "NAME")]
+	pub package: Vec<String>,
+
+	/// Release every workspace package changed since its last release
+	#[arg(short, long, conflicts_with = "package")]
+	pub workspace: bool,
+
+	/// Push the release and create it on GitHub (token in GITHUB_TOKEN) or
+	/// GitLab (token in GITLAB_TOKEN), with the changelog entry as notes
+	#[arg(long, value_enum)]
+	pub publish: Option<PublishTarget>,
+
+	/// File to attach to the published release (can be repeated)
+	#[arg(long, value_name = "PATH", requires = "publish")]
+	pub asset: Vec<PathBuf>,
+
+	/// Publish the release as a draft (GitHub only)
+	#[arg(long, requires = "publish")]
+	pub draft: bool,
+
+	/// Mark the published release as a prerelease (GitHub only)
+	#[arg(long, requires = "publish")]
+	pub prerelease: bool,
+}
+
+impl ReleaseArgs {
+	/// Publisher for `--publish`, checked before anything is changed
+	fn publisher(&self, root: &Path) -> Result<Option<Publisher>> {
+		self.publish
+			.map(|target| Publisher::new(target, root, &self.asset, self.draft, self.prerelease))
+			.transpose()
+	}
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -92,6 +132,26 @@ pub async fn execute(config: &Config, args: &ReleaseArgs) -> Result<()> {
 
 	println!("🚀 Starting release process...\n");
 
+	let workspace = workspace::detect_workspace(&current_dir)?;
+	if args.workspace || !args.package.is_empty() {
+		let (kind, packages) = workspace.ok_or_else(|| {
+			anyhow::anyhow!(
+				"❌ No Cargo, npm or pnpm workspace found in {}",
+				current_dir.display()
+			)
+		})?;
+		return execute_workspace(config, args, &current_dir, kind, packages).await;
+	}
+	if let Some((kind, packages)) = &workspace {
+		if !packages.is_empty() {
+			println!(
+				"💡 {} with {} packages detected; use --workspace or --package to release them independently",
+				kind.as_str(),
+				packages.len()
+			);
+		}
+	}
+
 	// Detect project type
 	let project_type = detect_project_type(&current_dir)?;
 	println!(
@@ -104,7 +164,7 @@ pub async fn execute(config: &Config, args: &ReleaseArgs) -> Result<()> {
 	println!("📌 Current version: {}", current_version);
 
 	// Get latest tag to determine commit range
-	let last_tag = get_latest_tag().await?;
+	let last_tag = get_latest_tag(None).await?;
 	let commit_range = if let Some(ref tag) = last_tag {
 		format!("{}..HEAD", tag)
 	} else {
@@ -117,7 +177,7 @@ pub async fn execute(config: &Config, args: &ReleaseArgs) -> Result<()> {
 	);
 
 	// Analyze commits since last tag
-	let commit_analysis = analyze_commits(&commit_range).await?;
+	let commit_analysis = analyze_commits(&commit_range, None).await?;
 
 	if commit_analysis.commits.is_empty() {
 		println!("✅ No new commits since last release. Nothing to release.");
@@ -130,22 +190,9 @@ pub async fn execute(config: &Config, args: &ReleaseArgs) -> Result<()> {
 	);
 
 	// Calculate new version using AI
-	let version_calculation = if let Some(forced_version) = &args.force_version {
-		VersionCalculation {
-			current_version: current_version.clone(),
-			new_version: forced_version.clone(),
-			version_type: "forced".to_string(),
-			reasoning: "Version forced by user".to_string(),
-		}
-	} else {
-		calculate_version_with_ai(config, &current_version, &commit_analysis).await?
-	};
-
-	println!("\n🎯 Version calculation:");
-	println!("   Current: {}", version_calculation.current_version);
-	println!("   New:     {}", version_calculation.new_version);
-	println!("   Type:    {}", version_calculation.version_type);
-	println!("   Reason:  {}", version_calculation.reasoning);
+	let version_calculation =
+		calculate_version(config, args, &current_version, &commit_analysis).await?;
+	print_version_calculation(&version_calculation);
 
 	// Generate changelog content with AI enhancement
 	let changelog_content = generate_enhanced_changelog_with_ai(
@@ -154,6 +201,7 @@ pub async fn execute(config: &Config, args: &ReleaseArgs) -> Result<()> {
 		&commit_analysis,
 		&project_type,
 		&commit_range,
+		None,
 	)
 	.await?;
 
@@ -162,8 +210,16 @@ pub async fn execute(config: &Config, args: &ReleaseArgs) -> Result<()> {
 	println!("{}", changelog_content);
 	println!("═══════════════════════════════════");
 
+	let publisher = args.publisher(&current_dir)?;
+
 	if args.dry_run {
 		println!("\n🔍 DRY RUN - No changes would be made");
+		if let Some(target) = args.publish {
+			println!(
+				"🔍 Would publish {} as a {:?} release",
+				version_calculation.new_version, target
+			);
+		}
 		return Ok(());
 	}
 
@@ -215,11 +271,243 @@ pub async fn execute(config: &Config, args: &ReleaseArgs) -> Result<()> {
 		"\n🎉 Release {} created successfully!",
 		version_calculation.new_version
 	);
-	println!("💡 Don't forget to push with: git push origin main --tags");
+	match publisher {
+		Some(publisher) => {
+			let tags = [version_calculation.new_version.clone()];
+			publish_releases(&publisher, &tags, &[changelog_content.as_str()]).await?
+		}
+		None => println!("💡 Don't forget to push with: git push origin main --tags"),
+	}
 
 	Ok(())
 }
 
+/// Release of one workspace package
+struct PackageRelease {
+	package: WorkspacePackage,
+	version: VersionCalculation,
+	changelog_content: String,
+}
+
+/// Release workspace packages independently: each package gets a version
+/// calculated from the commits touching its directory, its own changelog and
+/// a `<name>-vX.Y.Z` tag. All packages are bumped in a single release commit.
+async fn execute_workspace(
+	config: &Config,
+	args: &ReleaseArgs,
+	root: &Path,
+	kind: WorkspaceKind,
+	packages: Vec<WorkspacePackage>,
+) -> Result<()> {
+	println!(
+		"📦 {} detected with {} packages",
+		kind.as_str(),
+		packages.len()
+	);
+
+	for name in &args.package {
+		if !packages.iter().any(|package| &package.name == name) {
+			let available: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+			return Err(anyhow::anyhow!(
+				"❌ Unknown workspace package '{}'. Available: {}",
+				name,
+				available.join(", ")
+			));
+		}
+	}
+	let selected: Vec<WorkspacePackage> = packages
+		.into_iter()
+		.filter(|package| args.package.is_empty() || args.package.contains(&package.name))
+		.collect();
+
+	if args.force_version.is_some() && selected.len() != 1 {
+		return Err(anyhow::anyhow!(
+			"❌ --force-version can only be used when releasing a single --package"
+		));
+	}
+
+	let mut releases = Vec::new();
+	for package in selected {
+		let package_dir = root.join(&package.path);
+		println!("\n📦 {} ({})", package.name, package.path.display());
+		println!("📌 Current version: {}", package.version);
+
+		let last_tag = get_latest_tag(Some(&package.tag_prefix())).await?;
+		let commit_range = if let Some(ref tag) = last_tag {
+			format!("{}..HEAD", tag)
+		} else {
+			"HEAD".to_string()
+		};
+		println!(
+			"📋 Analyzing commits since: {}",
+			last_tag.as_deref().unwrap_or("initial commit")
+		);
+
+		let commit_analysis = analyze_commits(&commit_range, Some(&package_dir)).await?;
+		if commit_analysis.commits.is_empty() {
+			println!("✅ No changes since last release, skipping");
+			continue;
+		}
+		println!(
+			"📊 Found {} commits touching {}",
+			commit_analysis.commits.len(),
+			package.path.display()
+		);
+
+		let version_calculation =
+			calculate_version(config, args, &package.version, &commit_analysis).await?;
+		print_version_calculation(&version_calculation);
+
+		let changelog_content = generate_enhanced_changelog_with_ai(
+			config,
+			&version_calculation,
+			&commit_analysis,
+			&package.project_type,
+			&commit_range,
+			Some(&package_dir),
+		)
+		.await?;
+
+		println!(
+			"\n📝 Generated changelog entry for {}:",
+			package.path.join(&args.changelog).display()
+		);
+		println!("═══════════════════════════════════");
+		println!("{}", changelog_content);
+		println!("═══════════════════════════════════");
+
+		releases.push(PackageRelease {
+			package,
+			version: version_calculation,
+			changelog_content,
+		});
+	}
+
+	if releases.is_empty() {
+		println!("\n✅ No package changed since its last release. Nothing to release.");
+		return Ok(());
+	}
+
+	let tags: Vec<String> = releases
+		.iter()
+		.map(|release| release.package.tag_name(&release.version.new_version))
+		.collect();
+
+	let publisher = args.publisher(root)?;
+
+	if args.dry_run {
+		println!(
+			"\n🔍 DRY RUN - No changes would be made (would tag {})",
+			tags.join(", ")
+		);
+		if let Some(target) = args.publish {
+			println!(
+				"🔍 Would publish {} as {:?} releases",
+				tags.join(", "),
+				target
+			);
+		}
+		return Ok(());
+	}
+
+	if !args.yes {
+		print!("\nProceed with release of {}? [y/N] ", tags.join(", "));
+		io::stdout().flush()?;
+
+		let mut input = String::new();
+		io::stdin().read_line(&mut input)?;
+
+		if !input.trim().to_lowercase().starts_with('y') {
+			println!("❌ Release cancelled.");
+			return Ok(());
+		}
+	}
+
+	println!("\n🔄 Creating release...");
+
+	for release in &releases {
+		update_project_version(&release.package.project_type, &release.version.new_version).await?;
+	}
+	println!("✅ Updated package manifests");
+
+	// Lock files live at the workspace root
+	update_lock_files(&detect_project_type(root)?).await?;
+	println!("✅ Updated lock files");
+
+	for release in &releases {
+		let changelog = root.join(&release.package.path).join(&args.changelog);
+		let changelog = changelog.to_string_lossy();
+		update_changelog(&changelog, &release.changelog_content).await?;
+		stage_release_files(&changelog, &release.package.project_type).await?;
+		println!("✅ Updated {}", changelog);
+	}
+	println!("✅ Staged release files");
+
+	let released: Vec<String> = releases
+		.iter()
+		.map(|release| format!("{}@{}", release.package.name, release.version.new_version))
+		.collect();
+	create_commit(&format!("chore(release): {}", released.join(", "))).await?;
+	println!("✅ Created release commit");
+
+	for (release, tag) in releases.iter().zip(&tags) {
+		create_tag(tag, &release.changelog_content).await?;
+		println!("✅ Created git tag: {}", tag);
+	}
+
+	println!("\n🎉 Released {} successfully!", released.join(", "));
+	match publisher {
+		Some(publisher) => {
+			let notes: Vec<&str> = releases
+				.iter()
+				.map(|release| release.changelog_content.as_str())
+				.collect();
+			publish_releases(&publisher, &tags, &notes).await?
+		}
+		None => println!("💡 Don't forget to push with: git push origin main --tags"),
+	}
+
+	Ok(())
+}
+
+/// Push the release commit and tags, then create a remote release per tag
+async fn publish_releases(publisher: &Publisher, tags: &[String], notes: &[&str]) -> Result<()> {
+	println!("\n📤 Publishing...");
+	publisher.push(tags)?;
+	println!("✅ Pushed release commit and tags");
+	for (tag, notes) in tags.iter().zip(notes) {
+		let url = publisher.publish(tag, notes).await?;
+		println!("✅ Published release {}: {}", tag, url);
+	}
+	Ok(())
+}
+
+async fn calculate_version(
+	config: &Config,
+	args: &ReleaseArgs,
+	current_version: &str,
+	analysis: &CommitAnalysis,
+) -> Result<VersionCalculation> {
+	if let Some(forced_version) = &args.force_version {
+		Ok(VersionCalculation {
+			current_version: current_version.to_string(),
+			new_version: forced_version.clone(),
+			version_type: "forced".to_string(),
+			reasoning: "Version forced by user".to_string(),
+		})
+	} else {
+		calculate_version_with_ai(config, current_version, analysis).await
+	}
+}
+
+fn print_version_calculation(version_calculation: &VersionCalculation) {
+	println!("\n🎯 Version calculation:");
+	println!("   Current: {}", version_calculation.current_version);
+	println!("   New:     {}", version_calculation.new_version);
+	println!("   Type:    {}", version_calculation.version_type);
+	println!("   Reason:  {}", version_calculation.reasoning);
+}
+
 fn detect_project_type(dir: &Path) -> Result<ProjectType> {
 	if dir.join("Cargo.toml").exists() {
 		Ok(ProjectType::Rust(dir.join("Cargo.toml")))
@@ -284,7 +572,7 @@ async fn get_current_version(project_type: &ProjectType) -> Result<String> {
 	}
 
 	// Fallback to git tags
-	if let Ok(Some(tag)) = get_latest_tag().await {
+	if let Ok(Some(tag)) = get_latest_tag(None).await {
 		// Remove 'v' prefix if present
 		let version = tag.strip_prefix('v').unwrap_or(&tag);
 		Ok(version.to_string())
@@ -309,10 +597,14 @@ fn extract_version_from_line(line: &str) -> Option<String> {
 	None
 }
 
-async fn get_latest_tag() -> Result<Option<String>> {
-	let output = Command::new("git")
-		.args(["describe", "--tags", "--abbrev=0"])
-		.output()?;
+/// Latest tag reachable from HEAD, limited to tags starting with `prefix`
+async fn get_latest_tag(prefix: Option<&str>) -> Result<Option<String>> {
+	let mut command = Command::new("git");
+	command.args(["describe", "--tags", "--abbrev=0"]);
+	if let Some(prefix) = prefix {
+		command.arg(format!("--match={}*", prefix));
+	}
+	let output = command.output()?;
 
 	if output.status.success() {
 		let tag = String::from_utf8(output.stdout)?;
@@ -322,10 +614,14 @@ async fn get_latest_tag() -> Result<Option<String>> {
 	}
 }
 
-async fn analyze_commits(commit_range: &str) -> Result<CommitAnalysis> {
-	let output = Command::new("git")
-		.args(["log", "--format=%H|%an|%ad|%s", "--date=iso", commit_range])
-		.output()?;
+/// Commits in `commit_range`, limited to those touching `path` when given
+async fn analyze_commits(commit_range: &str, path: Option<&Path>) -> Result<CommitAnalysis> {
+	let mut command = Command::new("git");
+	command.args(["log", "--format=%H|%an|%ad|%s", "--date=iso", commit_range]);
+	if let Some(path) = path {
+		command.arg("--").arg(path);
+	}
+	let output = command.output()?;
 
 	if !output.status.success() {
 		return Err(anyhow::anyhow!(
@@ -792,13 +1088,16 @@ async fn generate_enhanced_changelog_with_ai(
 	analysis: &CommitAnalysis,
 	project_type: &ProjectType,
 	commit_range: &str,
+	path: Option<&Path>,
 ) -> Result<String> {
 	// First generate the standard changelog
 	let standard_changelog = generate_changelog_content(version, analysis).await?;
 
 	// Try to enhance with AI summary if API key is available
 	if config.openrouter.api_key.is_some() || std::env::var("OPENROUTER_API_KEY").is_ok() {
-		match generate_ai_changelog_summary(config, analysis, project_type, commit_range).await {
+		match generate_ai_changelog_summary(config, analysis, project_type, commit_range, path)
+			.await
+		{
 			Ok(ai_summary) => {
 				let mut enhanced = String::new();
 				let date = chrono::Utc::now().format("%Y-%m-%d").to_string();
@@ -923,10 +1222,13 @@ fn extract_field_from_toml(content: &str, field: &str) -> Option<String> {
 	None
 }
 
-async fn analyze_file_changes(commit_range: &str) -> Result<String> {
-	let output = Command::new("git")
-		.args(["diff", "--name-only", commit_range])
-		.output()?;
+async fn analyze_file_changes(commit_range: &str, path: Option<&Path>) -> Result<String> {
+	let mut command = Command::new("git");
+	command.args(["diff", "--name-only", commit_range]);
+	if let Some(path) = path {
+		command.arg("--").arg(path);
+	}
+	let output = command.output()?;
 
 	if !output.status.success() {
 		return Ok("Unable to analyze file changes".to_string());
@@ -1000,10 +1302,11 @@ async fn generate_ai_changelog_summary(
 	analysis: &CommitAnalysis,
 	project_type: &ProjectType,
 	commit_range: &str,
+	path: Option<&Path>,
 ) -> Result<String> {
 	// Gather enhanced context
 	let (project_name, project_description) = gather_project_context(project_type).await?;
-	let file_changes = analyze_file_changes(commit_range).await?;
+	let file_changes = analyze_file_changes(commit_range, path).await?;
 
 	// Group commits by type for better summary context
 	let mut breaking_msgs = Vec::new();
@@ -1151,7 +1454,18 @@ async fn update_lock_files(project_type: &ProjectType) -> Result<()> {
 
 			// Check if using yarn or npm
 			let current_dir = std::env::current_dir()?;
-			if current_dir.join("yarn.lock").exists() {
+			if current_dir.join("pnpm-lock.yaml").exists() {
+				let output = Command::new("pnpm")
+					.args(["install", "--lockfile-only"])
+					.output()?;
+
+				if !output.status.success() {
+					return Err(anyhow::anyhow!(
+						"Failed to update pnpm-lock.yaml: {}",
+						String::from_utf8_lossy(&output.stderr)
+					));
+				}
+			} else if current_dir.join("yarn.lock").exists() {
 				let output = Command::new("yarn")
 					.args(["install", "--frozen-lockfile"])
 					.output()?;
@@ -1409,14 +1723,12 @@ async fn stage_release_files(changelog_path: &str, project_type: &ProjectType) -
 		}
 		ProjectType::Node(path) => {
 			files_to_stage.push(path.to_string_lossy().to_string());
-			// Add package-lock.json or yarn.lock if they exist
-			let package_lock = current_dir.join("package-lock.json");
-			let yarn_lock = current_dir.join("yarn.lock");
-			if package_lock.exists() {
-				files_to_stage.push(package_lock.to_string_lossy().to_string());
-			}
-			if yarn_lock.exists() {
-				files_to_stage.push(yarn_lock.to_string_lossy().to_string());
+			// Add package-lock.json, yarn.lock or pnpm-lock.yaml if they exist
+			for lock_file in ["package-lock.json", "yarn.lock", "pnpm-lock.yaml"] {
+				let lock_path = current_dir.join(lock_file);
+				if lock_path.exists() {
+					files_to_stage.push(lock_path.to_string_lossy().to_string());
+				}
 			}
 		}
 		ProjectType::Php(path) => {
@@ -0,0 +1,356 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Workspace detection for monorepo releases
+//!
+//! Packages of Cargo, npm and pnpm workspaces are versioned independently:
+//! each one gets its own version, changelog and `<name>-vX.Y.Z` tags.
+
+use anyhow::Result;
+use globset::Glob;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::ProjectType;
+
+/// Kind of workspace a repository root declares
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkspaceKind {
+	Cargo,
+	Npm,
+	Pnpm,
+}
+
+impl WorkspaceKind {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::Cargo => "Cargo workspace",
+			Self::Npm => "npm workspaces",
+			Self::Pnpm => "pnpm workspace",
+		}
+	}
+}
+
+/// Independently versioned package of a workspace
+#[derive(Debug, Clone)]
+pub struct WorkspacePackage {
+	pub name: String,
+	/// Package directory relative to the repository root
+	pub path: PathBuf,
+	pub version: String,
+	pub project_type: ProjectType,
+}
+
+impl WorkspacePackage {
+	/// Prefix of the package's release tags
+	pub fn tag_prefix(&self) -> String {
+		format!("{}-v", self.name)
+	}
+
+	pub fn tag_name(&self, version: &str) -> String {
+		format!("{}{}", self.tag_prefix(), version)
+	}
+}
+
+/// Workspace declared at `root`, with its member packages sorted by path.
+/// Returns `None` for single-package repositories.
+pub fn detect_workspace(root: &Path) -> Result<Option<(WorkspaceKind, Vec<WorkspacePackage>)>> {
+	let (kind, members, excludes) = if let Some((members, excludes)) = cargo_members(root)? {
+		(WorkspaceKind::Cargo, members, excludes)
+	} else if let Some(members) = pnpm_members(root)? {
+		let (excludes, members) = split_negations(members);
+		(WorkspaceKind::Pnpm, members, excludes)
+	} else if let Some(members) = npm_members(root)? {
+		let (excludes, members) = split_negations(members);
+		(WorkspaceKind::Npm, members, excludes)
+	} else {
+		return Ok(None);
+	};
+
+	let excluded: Vec<PathBuf> = excludes
+		.iter()
+		.flat_map(|pattern| expand_pattern(root, pattern))
+		.collect();
+
+	let mut packages = Vec::new();
+	for pattern in &members {
+		for dir in expand_pattern(root, pattern) {
+			if excluded.contains(&dir) || packages.iter().any(|p: &WorkspacePackage| p.path == dir)
+			{
+				continue;
+			}
+			if let Some(package) = read_package(root, &dir, kind)? {
+				packages.push(package);
+			}
+		}
+	}
+	packages.sort_by(|a, b| a.path.cmp(&b.path));
+
+	Ok(Some((kind, packages)))
+}
+
+/// `[workspace] members` and `exclude` of the root Cargo.toml
+fn cargo_members(root: &Path) -> Result<Option<(Vec<String>, Vec<String>)>> {
+	let manifest = root.join("Cargo.toml");
+	if !manifest.exists() {
+		return Ok(None);
+	}
+	let value: toml::Value = toml::from_str(&fs::read_to_string(&manifest)?)?;
+	let Some(workspace) = value.get("workspace") else {
+		return Ok(None);
+	};
+
+	let strings = |key: &str| -> Vec<String> {
+		workspace
+			.get(key)
+			.and_then(|v| v.as_array())
+			.map(|items| {
+				items
+					.iter()
+					.filter_map(|item| item.as_str().map(String::from))
+					.collect()
+			})
+			.unwrap_or_default()
+	};
+	Ok(Some((strings("members"), strings("exclude"))))
+}
+
+/// `workspaces` of the root package.json, as an array or `{ "packages": [...] }`
+fn npm_members(root: &Path) -> Result<Option<Vec<String>>> {
+	let manifest = root.join("package.json");
+	if !manifest.exists() {
+		return Ok(None);
+	}
+	let package: serde_json::Value = serde_json::from_str(&fs::read_to_string(&manifest)?)?;
+	let workspaces = match package.get("workspaces") {
+		Some(serde_json::Value::Array(items)) => items,
+		Some(serde_json::Value::Object(config)) => match config.get("packages") {
+			Some(serde_json::Value::Array(items)) => items,
+			_ => return Ok(None),
+		},
+		_ => return Ok(None),
+	};
+
+	Ok(Some(
+		workspaces
+			.iter()
+			.filter_map(|item| item.as_str().map(String::from))
+			.collect(),
+	))
+}
+
+/// `packages` of pnpm-workspace.yaml
+fn pnpm_members(root: &Path) -> Result<Option<Vec<String>>> {
+	let manifest = root.join("pnpm-workspace.yaml");
+	if !manifest.exists() {
+		return Ok(None);
+	}
+	Ok(Some(parse_pnpm_packages(&fs::read_to_string(&manifest)?)))
+}
+
+/// Entries of the top-level `packages:` list. Only the block list form pnpm
+/// documents is supported, which avoids pulling in a YAML parser.
+pub fn parse_pnpm_packages(content: &str) -> Vec<String> {
+	let mut packages = Vec::new();
+	let mut in_packages = false;
+
+	for line in content.lines() {
+		let trimmed = line.trim();
+		if trimmed.is_empty() || trimmed.starts_with('#') {
+			continue;
+		}
+		if !line.starts_with(char::is_whitespace) && !trimmed.starts_with('-') {
+			in_packages = trimmed == "packages:";
+			continue;
+		}
+		if !in_packages {
+			continue;
+		}
+		if let Some(item) = trimmed.strip_prefix('-') {
+			let item = item.split(" #").next().unwrap_or_default().trim();
+			let item = item.trim_matches(|c| c == '"' || c == '\'');
+			if !item.is_empty() {
+				packages.push(item.to_string());
+			}
+		}
+	}
+
+	packages
+}
+
+/// Split `!pattern` exclusions from member patterns
+fn split_negations(patterns: Vec<String>) -> (Vec<String>, Vec<String>) {
+	let (negated, members): (Vec<String>, Vec<String>) =
+		patterns.into_iter().partition(|p| p.starts_with('!'));
+	let negated = negated
+		.into_iter()
+		.map(|p| p.trim_start_matches('!').to_string())
+		.collect();
+	(negated, members)
+}
+
+/// Directories (relative to `root`) matching a member pattern such as
+/// `crates/*` or `packages/**`.
+fn expand_pattern(root: &Path, pattern: &str) -> Vec<PathBuf> {
+	let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+	let mut dirs = vec![PathBuf::new()];
+
+	for segment in pattern.split('/').filter(|s| !s.is_empty() && *s != ".") {
+		if segment == "**" {
+			dirs = dirs
+				.iter()
+				.flat_map(|dir| {
+					let mut nested = vec![dir.clone()];
+					collect_subdirs(root, dir, &mut nested);
+					nested
+				})
+				.collect();
+		} else if segment.contains(['*', '?', '[', '{']) {
+			let Ok(glob) = Glob::new(segment) else {
+				return Vec::new();
+			};
+			let matcher = glob.compile_matcher();
+			dirs = dirs
+				.iter()
+				.flat_map(|dir| {
+					child_dirs(root, dir).into_iter().filter(|child| {
+						child.file_name().is_some_and(|name| matcher.is_match(name))
+					})
+				})
+				.collect();
+		} else {
+			dirs = dirs
+				.iter()
+				.map(|dir| dir.join(segment))
+				.filter(|dir| root.join(dir).is_dir())
+				.collect();
+		}
+	}
+
+	dirs.retain(|dir| !dir.as_os_str().is_empty());
+	dirs
+}
+
+fn child_dirs(root: &Path, dir: &Path) -> Vec<PathBuf> {
+	let Ok(entries) = fs::read_dir(root.join(dir)) else {
+		return Vec::new();
+	};
+	let mut children: Vec<PathBuf> = entries
+		.flatten()
+		.filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+		.map(|entry| entry.file_name())
+		.filter(|name| {
+			let name = name.to_string_lossy();
+			!name.starts_with('.') && name != "node_modules" && name != "target"
+		})
+		.map(|name| dir.join(name))
+		.collect();
+	children.sort();
+	children
+}
+
+fn collect_subdirs(root: &Path, dir: &Path, out: &mut Vec<PathBuf>) {
+	for child in child_dirs(root, dir) {
+		out.push(child.clone());
+		collect_subdirs(root, &child, out);
+	}
+}
+
+/// Package in `dir`, when it has a manifest with a name and its own version
+fn read_package(root: &Path, dir: &Path, kind: WorkspaceKind) -> Result<Option<WorkspacePackage>> {
+	let (manifest, name, version) = match kind {
+		WorkspaceKind::Cargo => {
+			let manifest = root.join(dir).join("Cargo.toml");
+			if !manifest.exists() {
+				return Ok(None);
+			}
+			let value: toml::Value = toml::from_str(&fs::read_to_string(&manifest)?)?;
+			let package = value.get("package");
+			let name = package.and_then(|p| p.get("name")).and_then(|v| v.as_str());
+			let version = package
+				.and_then(|p| p.get("version"))
+				.and_then(|v| v.as_str());
+			(manifest, name.map(String::from), version.map(String::from))
+		}
+		WorkspaceKind::Npm | WorkspaceKind::Pnpm => {
+			let manifest = root.join(dir).join("package.json");
+			if !manifest.exists() {
+				return Ok(None);
+			}
+			let package: serde_json::Value = serde_json::from_str(&fs::read_to_string(&manifest)?)?;
+			let name = package.get("name").and_then(|v| v.as_str());
+			let version = package.get("version").and_then(|v| v.as_str());
+			(manifest, name.map(String::from), version.map(String::from))
+		}
+	};
+
+	let Some(name) = name else {
+		return Ok(None);
+	};
+	let Some(version) = version else {
+		// `version.workspace = true` and friends share one version, so the
+		// package cannot be released on its own
+		eprintln!(
+			"⚠️  Skipping {} ({}): no version of its own",
+			name,
+			dir.display()
+		);
+		return Ok(None);
+	};
+
+	let project_type = match kind {
+		WorkspaceKind::Cargo => ProjectType::Rust(manifest),
+		WorkspaceKind::Npm | WorkspaceKind::Pnpm => ProjectType::Node(manifest),
+	};
+
+	Ok(Some(WorkspacePackage {
+		name,
+		path: dir.to_path_buf(),
+		version,
+		project_type,
+	}))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse_pnpm_packages() {
+		let yaml = "# monorepo\npackages:\n  - 'packages/*'\n  - \"apps/web\" # site\n  - '!**/test/**'\n\ncatalog:\n  react: ^18\n";
+		assert_eq!(
+			parse_pnpm_packages(yaml),
+			vec!["packages/*", "apps/web", "!**/test/**"]
+		);
+		assert!(parse_pnpm_packages("catalog:\n  - x\n").is_empty());
+	}
+
+	#[test]
+	fn test_split_negations_and_tag_names() {
+		let (excluded, members) = split_negations(vec![
+			"packages/*".to_string(),
+			"!packages/internal".to_string(),
+		]);
+		assert_eq!(members, vec!["packages/*"]);
+		assert_eq!(excluded, vec!["packages/internal"]);
+
+		let package = WorkspacePackage {
+			name: "@acme/ui".to_string(),
+			path: PathBuf::from("packages/ui"),
+			version: "1.2.0".to_string(),
+			project_type: ProjectType::Unknown,
+		};
+		assert_eq!(package.tag_name("1.3.0"), "@acme/ui-v1.3.0");
+	}
+}
@@ -0,0 +1,175 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::Result;
+use clap::{Args, Subcommand, ValueEnum};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use octocode::config::Config;
+use octocode::indexer;
+use octocode::indexer::git_utils::GitUtils;
+use octocode::indexer::graphrag::orphans;
+use octocode::store::Store;
+
+use crate::commands::OutputFormat;
+
+pub(crate) mod ownership;
+
+use ownership::{CodeOwners, IndexedFile};
+
+#[derive(Args, Debug)]
+pub struct ReportArgs {
+	#[command(subcommand)]
+	pub command: ReportCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ReportCommand {
+	/// Modules by owner with recent churn and GraphRAG centrality
+	Ownership {
+		/// Report format
+		#[arg(long, value_enum, default_value = "html")]
+		format: ReportFormat,
+
+		/// Write the report to a file instead of stdout
+		#[arg(long)]
+		out: Option<PathBuf>,
+
+		/// Churn window, in any form `git log --since` accepts
+		#[arg(long, default_value = "90 days ago")]
+		since: String,
+
+		/// Directory levels that make up a module (e.g. 2 groups by `src/indexer`)
+		#[arg(long, default_value = "2")]
+		depth: usize,
+	},
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ReportFormat {
+	/// Static, self-contained HTML page
+	Html,
+	Json,
+	Md,
+	Text,
+}
+
+impl ReportArgs {
+	/// Apply the global --output flag to the subcommand's own format option
+	pub fn apply_output_format(&mut self, output: &OutputFormat) {
+		let ReportCommand::Ownership { format, .. } = &mut self.command;
+		*format = match output {
+			OutputFormat::Json => ReportFormat::Json,
+			OutputFormat::Md => ReportFormat::Md,
+			OutputFormat::Cli | OutputFormat::Text => ReportFormat::Text,
+		};
+	}
+}
+
+pub async fn execute(store: &Store, config: &Config, args: &ReportArgs) -> Result<()> {
+	match &args.command {
+		ReportCommand::Ownership {
+			format,
+			out,
+			since,
+			depth,
+		} => ownership_report(store, config, *format, out.as_ref(), since, *depth).await,
+	}
+}
+
+async fn ownership_report(
+	store: &Store,
+	config: &Config,
+	format: ReportFormat,
+	out: Option<&PathBuf>,
+	since: &str,
+	depth: usize,
+) -> Result<()> {
+	if depth == 0 {
+		return Err(anyhow::anyhow!("--depth must be at least 1"));
+	}
+
+	let current_dir = std::env::current_dir()?;
+	let repo_path = GitUtils::find_git_root(&current_dir)
+		.ok_or_else(|| anyhow::anyhow!("The ownership report needs a git repository"))?;
+
+	// Files and their sizes come from the index
+	let blocks = store.get_all_code_blocks_for_graphrag().await?;
+	if blocks.is_empty() {
+		return Err(anyhow::anyhow!(
+			"No indexed code found. Run 'octocode index' first."
+		));
+	}
+	let mut sizes: HashMap<&str, usize> = HashMap::new();
+	for block in &blocks {
+		let size = sizes.entry(block.path.as_str()).or_default();
+		*size = (*size).max(block.end_line + 1);
+	}
+	let files: Vec<IndexedFile> = sizes
+		.into_iter()
+		.map(|(path, lines)| IndexedFile {
+			path: path.to_string(),
+			lines,
+		})
+		.collect();
+
+	// Centrality needs the knowledge graph; the report is still useful without it
+	let mut dependents = None;
+	if config.graphrag.enabled {
+		let graph = indexer::GraphBuilder::new(config.clone())
+			.await?
+			.get_graph()
+			.await?;
+		if !graph.nodes.is_empty() {
+			let mut counts: HashMap<String, usize> = HashMap::new();
+			for node in graph.relationships.iter().filter_map(orphans::used_node) {
+				*counts.entry(node.to_string()).or_default() += 1;
+			}
+			dependents = Some(counts);
+		}
+	}
+
+	let codeowners = CodeOwners::load(&repo_path);
+	let churn = ownership::git_churn(&repo_path, since)?;
+	let report = ownership::build_report(
+		&files,
+		dependents.as_ref(),
+		codeowners.as_ref(),
+		&churn,
+		depth,
+		since,
+	);
+
+	let rendered = match format {
+		ReportFormat::Html => ownership::render_html(&report),
+		ReportFormat::Json => serde_json::to_string_pretty(&report)?,
+		ReportFormat::Md => ownership::render_markdown(&report),
+		ReportFormat::Text => ownership::render_text(&report),
+	};
+
+	match out {
+		Some(path) => {
+			std::fs::write(path, rendered)?;
+			eprintln!(
+				"Ownership report for {} modules written to {}",
+				report.modules.len(),
+				path.display()
+			);
+		}
+		None => println!("{}", rendered),
+	}
+
+	Ok(())
+}
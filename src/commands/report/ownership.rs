@@ -0,0 +1,548 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Code ownership by module: owners, recent churn and GraphRAG centrality
+
+use anyhow::Result;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Write as _;
+use std::path::Path;
+use std::process::Command;
+
+/// Where GitHub and GitLab look for CODEOWNERS, in order
+const CODEOWNERS_LOCATIONS: &[&str] = &[".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+/// Owners listed per module
+const MAX_OWNERS: usize = 3;
+
+/// Owner label of modules nobody claims
+pub const UNOWNED: &str = "(unowned)";
+
+/// Ownership rules of a CODEOWNERS file; the last matching rule wins
+pub struct CodeOwners {
+	rules: Vec<(GlobSet, Vec<String>)>,
+}
+
+impl CodeOwners {
+	/// CODEOWNERS of the repository, if it has one
+	pub fn load(repo_path: &Path) -> Option<Self> {
+		CODEOWNERS_LOCATIONS
+			.iter()
+			.find_map(|location| std::fs::read_to_string(repo_path.join(location)).ok())
+			.map(|content| Self::parse(&content))
+	}
+
+	pub fn parse(content: &str) -> Self {
+		let rules = content
+			.lines()
+			.map(|line| line.split('#').next().unwrap_or_default().trim())
+			.filter(|line| !line.is_empty())
+			.filter_map(|line| {
+				let mut fields = line.split_whitespace();
+				let pattern = fields.next()?;
+				let owners = fields.map(|owner| owner.to_string()).collect();
+				Some((pattern_globs(pattern)?, owners))
+			})
+			.collect();
+		Self { rules }
+	}
+
+	/// Owners of a repository-relative path; empty when a rule unassigns it
+	pub fn owners(&self, path: &str) -> Option<&[String]> {
+		self.rules
+			.iter()
+			.rev()
+			.find(|(globs, _)| globs.is_match(path))
+			.map(|(_, owners)| owners.as_slice())
+	}
+}
+
+/// Globs for a gitignore-style CODEOWNERS pattern. Patterns without an inner
+/// slash match at any depth; any pattern also matches everything below it.
+fn pattern_globs(pattern: &str) -> Option<GlobSet> {
+	let anchored = pattern.trim_end_matches('/').contains('/');
+	let mut base = pattern
+		.trim_start_matches('/')
+		.trim_end_matches('/')
+		.to_string();
+	if base.is_empty() || base == "*" {
+		base = "**".to_string();
+	} else if !anchored {
+		base = format!("**/{}", base);
+	}
+
+	let mut builder = GlobSetBuilder::new();
+	if !pattern.ends_with('/') {
+		builder.add(Glob::new(&base).ok()?);
+	}
+	builder.add(Glob::new(&format!("{}/**", base)).ok()?);
+	builder.build().ok()
+}
+
+/// Changes to one file within the churn window
+#[derive(Debug, Default)]
+pub struct FileChurn {
+	pub commits: HashSet<String>,
+	/// Lines added plus lines deleted
+	pub lines: usize,
+	/// Changed lines per author
+	pub authors: HashMap<String, usize>,
+}
+
+/// Per-file churn from `git log` since a date such as "90 days ago"
+pub fn git_churn(repo_path: &Path, since: &str) -> Result<HashMap<String, FileChurn>> {
+	let output = Command::new("git")
+		.args([
+			"log",
+			&format!("--since={}", since),
+			"--no-renames",
+			"--no-merges",
+			"--numstat",
+			"--format=commit %H%x09%an",
+		])
+		.current_dir(repo_path)
+		.output()?;
+
+	if !output.status.success() {
+		return Err(anyhow::anyhow!(
+			"Failed to read git history: {}",
+			String::from_utf8_lossy(&output.stderr).trim()
+		));
+	}
+
+	Ok(parse_numstat(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Parse `git log --numstat --format="commit %H%x09%an"` output
+pub fn parse_numstat(log: &str) -> HashMap<String, FileChurn> {
+	let mut churn: HashMap<String, FileChurn> = HashMap::new();
+	let mut commit = ("", "");
+
+	for line in log.lines() {
+		if let Some(header) = line.strip_prefix("commit ") {
+			commit = header.split_once('\t').unwrap_or((header, ""));
+			continue;
+		}
+		let mut fields = line.splitn(3, '\t');
+		let (Some(added), Some(deleted), Some(path)) =
+			(fields.next(), fields.next(), fields.next())
+		else {
+			continue;
+		};
+		// Binary files show "-" for both counts
+		let lines = added.parse::<usize>().unwrap_or(0) + deleted.parse::<usize>().unwrap_or(0);
+
+		let file = churn.entry(path.to_string()).or_default();
+		file.commits.insert(commit.0.to_string());
+		file.lines += lines;
+		*file.authors.entry(commit.1.to_string()).or_default() += lines;
+	}
+
+	churn
+}
+
+/// Module of a file: its first `depth` directories
+pub fn module_of(path: &str, depth: usize) -> String {
+	let directories: Vec<&str> = path.split('/').collect();
+	let directories = &directories[..directories.len() - 1];
+	if directories.is_empty() {
+		return ".".to_string();
+	}
+	directories[..depth.min(directories.len())].join("/")
+}
+
+/// Ownership and activity of one module
+#[derive(Debug, Clone, Serialize)]
+pub struct ModuleOwnership {
+	pub module: String,
+	/// Most significant owners first
+	pub owners: Vec<String>,
+	/// "codeowners", "git" or "none"
+	pub owner_source: String,
+	pub files: usize,
+	pub lines: usize,
+	pub commits: usize,
+	/// Lines added plus deleted within the churn window
+	pub churn: usize,
+	pub contributors: usize,
+	/// Usage relationships pointing into the module, relative to the most
+	/// depended-on module (0.0-1.0)
+	pub centrality: f32,
+}
+
+impl ModuleOwnership {
+	pub fn primary_owner(&self) -> &str {
+		self.owners.first().map(String::as_str).unwrap_or(UNOWNED)
+	}
+}
+
+#[derive(Debug, Serialize)]
+pub struct OwnershipReport {
+	pub generated_at: String,
+	pub since: String,
+	/// Whether centrality comes from a GraphRAG graph
+	pub has_graph: bool,
+	pub modules: Vec<ModuleOwnership>,
+}
+
+/// Indexed file with its size in lines
+pub struct IndexedFile {
+	pub path: String,
+	pub lines: usize,
+}
+
+/// Group indexed files into modules and attach owners, churn and centrality.
+/// `dependents` counts usage relationships pointing at each file.
+pub fn build_report(
+	files: &[IndexedFile],
+	dependents: Option<&HashMap<String, usize>>,
+	codeowners: Option<&CodeOwners>,
+	churn: &HashMap<String, FileChurn>,
+	depth: usize,
+	since: &str,
+) -> OwnershipReport {
+	let mut grouped: BTreeMap<String, Vec<&IndexedFile>> = BTreeMap::new();
+	for file in files {
+		grouped
+			.entry(module_of(&file.path, depth))
+			.or_default()
+			.push(file);
+	}
+
+	let mut modules: Vec<ModuleOwnership> = Vec::new();
+	let mut incoming: Vec<usize> = Vec::new();
+	for (module, files) in grouped {
+		let mut declared: HashMap<&str, usize> = HashMap::new();
+		let mut authors: HashMap<&str, usize> = HashMap::new();
+		let mut commits: HashSet<&str> = HashSet::new();
+		let mut changed_lines = 0;
+		let mut used = 0;
+
+		for file in &files {
+			if let Some(owners) = codeowners.and_then(|codeowners| codeowners.owners(&file.path)) {
+				for owner in owners {
+					*declared.entry(owner.as_str()).or_default() += 1;
+				}
+			}
+			if let Some(file_churn) = churn.get(&file.path) {
+				changed_lines += file_churn.lines;
+				commits.extend(file_churn.commits.iter().map(String::as_str));
+				for (author, lines) in &file_churn.authors {
+					*authors.entry(author.as_str()).or_default() += lines;
+				}
+			}
+			used += dependents
+				.and_then(|dependents| dependents.get(&file.path))
+				.copied()
+				.unwrap_or(0);
+		}
+
+		let (owners, owner_source) = if !declared.is_empty() {
+			(top(&declared), "codeowners")
+		} else if !authors.is_empty() {
+			(top(&authors), "git")
+		} else {
+			(Vec::new(), "none")
+		};
+
+		modules.push(ModuleOwnership {
+			module,
+			owners,
+			owner_source: owner_source.to_string(),
+			files: files.len(),
+			lines: files.iter().map(|file| file.lines).sum(),
+			commits: commits.len(),
+			churn: changed_lines,
+			contributors: authors.len(),
+			centrality: 0.0,
+		});
+		incoming.push(used);
+	}
+
+	let most_used = incoming.iter().copied().max().unwrap_or(0);
+	if most_used > 0 {
+		for (module, used) in modules.iter_mut().zip(incoming) {
+			module.centrality = used as f32 / most_used as f32;
+		}
+	}
+
+	// Grouped by owner, busiest modules first
+	modules.sort_by(|a, b| {
+		a.primary_owner()
+			.cmp(b.primary_owner())
+			.then(b.churn.cmp(&a.churn))
+			.then(a.module.cmp(&b.module))
+	});
+
+	OwnershipReport {
+		generated_at: chrono::Utc::now().format("%Y-%m-%d %H:%M UTC").to_string(),
+		since: since.to_string(),
+		has_graph: dependents.is_some(),
+		modules,
+	}
+}
+
+/// Names with the highest weight, ties by name
+fn top(weights: &HashMap<&str, usize>) -> Vec<String> {
+	let mut ranked: Vec<(&str, usize)> = weights
+		.iter()
+		.filter(|(name, _)| !name.is_empty())
+		.map(|(name, weight)| (*name, *weight))
+		.collect();
+	ranked.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+	ranked
+		.into_iter()
+		.take(MAX_OWNERS)
+		.map(|(name, _)| name.to_string())
+		.collect()
+}
+
+/// Modules of each owner, in report order
+fn by_owner(report: &OwnershipReport) -> Vec<(&str, Vec<&ModuleOwnership>)> {
+	let mut owners: Vec<(&str, Vec<&ModuleOwnership>)> = Vec::new();
+	for module in &report.modules {
+		if let Some((_, modules)) = owners
+			.last_mut()
+			.filter(|(owner, _)| *owner == module.primary_owner())
+		{
+			modules.push(module);
+			continue;
+		}
+		owners.push((module.primary_owner(), vec![module]));
+	}
+	owners
+}
+
+fn escape_html(text: &str) -> String {
+	text.replace('&', "&amp;")
+		.replace('<', "&lt;")
+		.replace('>', "&gt;")
+		.replace('"', "&quot;")
+}
+
+/// Self-contained HTML page: an owner summary and a heatmap table per owner
+pub fn render_html(report: &OwnershipReport) -> String {
+	let max_churn = report
+		.modules
+		.iter()
+		.map(|m| m.churn)
+		.max()
+		.unwrap_or(0)
+		.max(1);
+	let mut html = String::new();
+
+	html.push_str(
+		"<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+		<title>Code ownership report</title>\n<style>\n\
+		body { font-family: -apple-system, BlinkMacSystemFont, \"Segoe UI\", sans-serif; margin: 2rem; color: #1f2328; }\n\
+		h1 { margin-bottom: 0.2rem; }\n\
+		.meta { color: #656d76; margin-bottom: 2rem; }\n\
+		table { border-collapse: collapse; margin-bottom: 2rem; min-width: 60%; }\n\
+		th, td { border: 1px solid #d0d7de; padding: 0.35rem 0.7rem; text-align: right; }\n\
+		th:first-child, td:first-child { text-align: left; }\n\
+		th { background: #f6f8fa; }\n\
+		code { font-size: 0.9em; }\n\
+		</style>\n</head>\n<body>\n",
+	);
+	let _ = writeln!(html, "<h1>Code ownership report</h1>");
+	let _ = writeln!(
+		html,
+		"<p class=\"meta\">Generated {} &middot; churn since {} &middot; {} modules{}</p>",
+		escape_html(&report.generated_at),
+		escape_html(&report.since),
+		report.modules.len(),
+		if report.has_graph {
+			""
+		} else {
+			" &middot; centrality unavailable (GraphRAG disabled or empty)"
+		}
+	);
+
+	let owners = by_owner(report);
+	html.push_str("<h2>Owners</h2>\n<table>\n<tr><th>Owner</th><th>Modules</th><th>Files</th><th>Lines</th><th>Churn</th></tr>\n");
+	for (owner, modules) in &owners {
+		let _ = writeln!(
+			html,
+			"<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+			escape_html(owner),
+			modules.len(),
+			modules.iter().map(|m| m.files).sum::<usize>(),
+			modules.iter().map(|m| m.lines).sum::<usize>(),
+			modules.iter().map(|m| m.churn).sum::<usize>()
+		);
+	}
+	html.push_str("</table>\n");
+
+	for (owner, modules) in &owners {
+		let _ = writeln!(html, "<h2>{}</h2>", escape_html(owner));
+		html.push_str("<table>\n<tr><th>Module</th><th>Owners</th><th>Files</th><th>Lines</th><th>Commits</th><th>Churn</th><th>Contributors</th><th>Centrality</th></tr>\n");
+		for module in modules {
+			let heat = module.churn as f32 / max_churn as f32;
+			let _ = writeln!(
+				html,
+				"<tr><td><code>{}</code></td><td>{} <small>({})</small></td><td>{}</td><td>{}</td><td>{}</td>\
+				<td style=\"background: rgba(207, 34, 46, {:.2})\">{}</td><td>{}</td>\
+				<td style=\"background: rgba(9, 105, 218, {:.2})\">{:.2}</td></tr>",
+				escape_html(&module.module),
+				escape_html(&module.owners.join(", ")),
+				module.owner_source,
+				module.files,
+				module.lines,
+				module.commits,
+				heat * 0.6,
+				module.churn,
+				module.contributors,
+				module.centrality * 0.6,
+				module.centrality
+			);
+		}
+		html.push_str("</table>\n");
+	}
+
+	html.push_str("</body>\n</html>\n");
+	html
+}
+
+pub fn render_markdown(report: &OwnershipReport) -> String {
+	let mut md = String::new();
+	let _ = writeln!(md, "# Code ownership report\n");
+	let _ = writeln!(
+		md,
+		"Generated {}, churn since {}.\n",
+		report.generated_at, report.since
+	);
+	for (owner, modules) in by_owner(report) {
+		let _ = writeln!(md, "## {}\n", owner);
+		let _ = writeln!(
+			md,
+			"| Module | Owners | Files | Lines | Commits | Churn | Contributors | Centrality |"
+		);
+		let _ = writeln!(md, "|---|---|---:|---:|---:|---:|---:|---:|");
+		for module in modules {
+			let _ = writeln!(
+				md,
+				"| `{}` | {} ({}) | {} | {} | {} | {} | {} | {:.2} |",
+				module.module,
+				module.owners.join(", "),
+				module.owner_source,
+				module.files,
+				module.lines,
+				module.commits,
+				module.churn,
+				module.contributors,
+				module.centrality
+			);
+		}
+		md.push('\n');
+	}
+	md
+}
+
+pub fn render_text(report: &OwnershipReport) -> String {
+	let mut text = String::new();
+	let _ = writeln!(
+		text,
+		"Code ownership ({} modules, churn since {})",
+		report.modules.len(),
+		report.since
+	);
+	for (owner, modules) in by_owner(report) {
+		let _ = writeln!(text, "\n{}", owner);
+		for module in modules {
+			let _ = writeln!(
+				text,
+				"  {:<32} files {:>4}  lines {:>6}  commits {:>4}  churn {:>6}  centrality {:.2}",
+				module.module,
+				module.files,
+				module.lines,
+				module.commits,
+				module.churn,
+				module.centrality
+			);
+		}
+	}
+	text
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_codeowners_matching() {
+		let codeowners = CodeOwners::parse(
+			"# default owners\n\
+			* @core\n\
+			*.md @docs\n\
+			/src/indexer/ @search-team @alice\n\
+			src/indexer/languages/sql.rs @db\n\
+			vendor\n",
+		);
+
+		assert_eq!(codeowners.owners("Cargo.toml").unwrap(), ["@core"]);
+		assert_eq!(codeowners.owners("doc/COMMANDS.md").unwrap(), ["@docs"]);
+		assert_eq!(
+			codeowners.owners("src/indexer/mod.rs").unwrap(),
+			["@search-team", "@alice"]
+		);
+		assert_eq!(
+			codeowners.owners("src/indexer/languages/sql.rs").unwrap(),
+			["@db"]
+		);
+		assert!(codeowners.owners("third/vendor/lib.rs").unwrap().is_empty());
+	}
+
+	#[test]
+	fn test_parse_numstat_and_report() {
+		let log = "commit aaa\tAlice\n\n10\t2\tsrc/store/mod.rs\n-\t-\tassets/logo.png\n\
+			commit bbb\tBob\n\n3\t1\tsrc/store/mod.rs\n5\t0\tsrc/main.rs\n";
+		let churn = parse_numstat(log);
+		let store = &churn["src/store/mod.rs"];
+		assert_eq!(store.commits.len(), 2);
+		assert_eq!(store.lines, 16);
+		assert_eq!(store.authors["Alice"], 12);
+		assert_eq!(churn["assets/logo.png"].lines, 0);
+
+		assert_eq!(module_of("src/store/mod.rs", 1), "src");
+		assert_eq!(module_of("src/indexer/graphrag/mod.rs", 2), "src/indexer");
+		assert_eq!(module_of("build.rs", 2), ".");
+
+		let files = [
+			IndexedFile {
+				path: "src/store/mod.rs".to_string(),
+				lines: 100,
+			},
+			IndexedFile {
+				path: "src/main.rs".to_string(),
+				lines: 40,
+			},
+		];
+		let dependents = HashMap::from([("src/store/mod.rs".to_string(), 4)]);
+		let report = build_report(&files, Some(&dependents), None, &churn, 2, "90 days ago");
+
+		let store = report
+			.modules
+			.iter()
+			.find(|m| m.module == "src/store")
+			.unwrap();
+		assert_eq!(store.owners, ["Alice", "Bob"]);
+		assert_eq!(store.owner_source, "git");
+		assert_eq!(store.centrality, 1.0);
+		let src = report.modules.iter().find(|m| m.module == "src").unwrap();
+		assert_eq!(src.owners, ["Bob"]);
+		assert_eq!(src.centrality, 0.0);
+	}
+}
@@ -13,13 +13,21 @@
 // limitations under the License.
 
 use anyhow::Result;
-use clap::Args;
+use clap::{Args, ValueEnum};
+use serde_json::json;
 use std::collections::HashMap;
 use std::process::Command;
 
 use octocode::config::Config;
 use octocode::indexer::git_utils::GitUtils;
 
+use super::house_style;
+
+mod gate;
+mod rules;
+
+use rules::ReviewRule;
+
 #[derive(Args, Debug)]
 pub struct ReviewArgs {
 	/// Add all changes before reviewing
@@ -34,13 +42,66 @@ pub struct ReviewArgs {
 	#[arg(long)]
 	pub json: bool,
 
+	/// Output format: 'text', 'json', or 'sarif' (SARIF 2.1.0 for code scanning uploads)
+	#[arg(long, value_enum, conflicts_with = "json")]
+	pub format: Option<ReviewFormat>,
+
 	/// Severity level filter: all, critical, high, medium, low
 	#[arg(long, default_value = "medium")]
 	pub severity: String,
+
+	/// Review only the staged changes (the default), for hook scripts
+	#[arg(long, conflicts_with = "all")]
+	pub staged: bool,
+
+	/// Pre-commit gate: run the fast built-in rules, print a short summary and
+	/// fail when an issue at or above --fail-on (default: high) is found
+	#[arg(long, conflicts_with_all = ["json", "format", "focus"])]
+	pub gate: bool,
+
+	/// With --gate, also run the LLM review within --budget seconds
+	#[arg(long, requires = "gate")]
+	pub llm: bool,
+
+	/// Seconds the LLM review may take in gate mode before it is skipped
+	#[arg(long, default_value_t = 20, requires = "llm")]
+	pub budget: u64,
+
+	/// Exit non-zero when an issue at or above this severity is found:
+	/// critical, high, medium, low, or a SARIF level (error, warning, note)
+	#[arg(long, value_parser = parse_fail_on)]
+	pub fail_on: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ReviewFormat {
+	Text,
+	Json,
+	Sarif,
+}
+
+impl ReviewArgs {
+	fn output_format(&self) -> ReviewFormat {
+		match self.format {
+			Some(format) => format,
+			None if self.json => ReviewFormat::Json,
+			None => ReviewFormat::Text,
+		}
+	}
 }
 
 pub async fn execute(config: &Config, args: &ReviewArgs) -> Result<()> {
 	let current_dir = std::env::current_dir()?;
+	let format = args.output_format();
+
+	// Keep stdout to the report itself for machine-readable formats
+	let progress = |message: &str| {
+		if format == ReviewFormat::Text {
+			println!("{}", message);
+		} else {
+			eprintln!("{}", message);
+		}
+	};
 
 	// Find git repository root
 	let git_root = GitUtils::find_git_root(&current_dir)
@@ -48,10 +109,13 @@ pub async fn execute(config: &Config, args: &ReviewArgs) -> Result<()> {
 
 	// Use git root as working directory for all operations
 	let current_dir = git_root;
+	let rules = rules::load(&current_dir)?;
+	let fail_on = args.fail_on.as_deref().map(fail_on_severity).transpose()?;
+	let gate_fail_on = fail_on.unwrap_or("high");
 
 	// Add all files if requested
 	if args.all {
-		println!("📂 Adding all changes for review...");
+		progress("📂 Adding all changes for review...");
 		let output = Command::new("git")
 			.args(["add", "."])
 			.current_dir(&current_dir)
@@ -79,31 +143,169 @@ pub async fn execute(config: &Config, args: &ReviewArgs) -> Result<()> {
 	}
 
 	let staged_files = String::from_utf8(output.stdout)?;
+	if args.gate {
+		return run_gate(
+			&current_dir,
+			config,
+			args,
+			&rules,
+			gate_fail_on,
+			staged_files.trim().is_empty(),
+		)
+		.await;
+	}
 	if staged_files.trim().is_empty() {
 		return Err(anyhow::anyhow!(
 			"❌ No staged changes to review. Use 'git add' or --all flag."
 		));
 	}
 
-	println!("🔍 Reviewing staged files:");
+	progress("🔍 Reviewing staged files:");
 	for file in staged_files.lines() {
-		println!("  • {}", file);
+		progress(&format!("  • {}", file));
 	}
 
 	// Perform the code review
-	println!("\n🤖 Analyzing changes for best practices and potential issues...");
-	let review_result = perform_code_review(&current_dir, config, args).await?;
+	progress("\n🤖 Analyzing changes for best practices and potential issues...");
+	let review_result = perform_code_review(&current_dir, config, args, &rules, true).await?;
 
 	// Output the results
-	if args.json {
-		println!("{}", serde_json::to_string_pretty(&review_result)?);
-	} else {
-		display_review_results(&review_result, &args.severity);
+	match format {
+		ReviewFormat::Json => println!("{}", serde_json::to_string_pretty(&review_result)?),
+		ReviewFormat::Sarif => println!(
+			"{}",
+			serde_json::to_string_pretty(&to_sarif(&review_result, &rules, &args.severity))?
+		),
+		ReviewFormat::Text => display_review_results(&review_result, &args.severity),
+	}
+
+	if let Some(fail_on) = fail_on {
+		let failing = review_result
+			.issues
+			.iter()
+			.filter(|issue| should_show_issue(&issue.severity.to_uppercase(), fail_on))
+			.count();
+		if failing > 0 {
+			return Err(anyhow::anyhow!(
+				"Review found {} issue(s) at or above {}; fix them or commit with --no-verify",
+				failing,
+				args.fail_on.as_deref().unwrap_or(fail_on)
+			));
+		}
 	}
 
 	Ok(())
 }
 
+/// Severity `--fail-on` stands for; SARIF levels map as in `sarif_level`
+pub(super) fn fail_on_severity(fail_on: &str) -> Result<&'static str> {
+	match fail_on.to_lowercase().as_str() {
+		"critical" => Ok("critical"),
+		"high" | "error" => Ok("high"),
+		"medium" | "warning" => Ok("medium"),
+		"low" | "note" => Ok("low"),
+		_ => Err(anyhow::anyhow!(
+			"Invalid --fail-on '{}': use critical, high, medium, low, error, warning or note",
+			fail_on
+		)),
+	}
+}
+
+/// Clap value parser for `--fail-on`, so a typo fails before the review runs
+fn parse_fail_on(fail_on: &str) -> Result<String> {
+	fail_on_severity(fail_on).map(|_| fail_on.to_string())
+}
+
+/// `--gate`: built-in rules, then the LLM review if asked for and in time.
+/// Prints issues at or above `fail_on` one per line and fails when there are any.
+async fn run_gate(
+	repo_path: &std::path::Path,
+	config: &Config,
+	args: &ReviewArgs,
+	rules: &[ReviewRule],
+	fail_on: &str,
+	nothing_staged: bool,
+) -> Result<()> {
+	if nothing_staged {
+		println!("✅ Review gate passed: nothing staged");
+		return Ok(());
+	}
+
+	let output = Command::new("git")
+		.args(["diff", "--cached", "-U0", "--no-color", "--no-ext-diff"])
+		.current_dir(repo_path)
+		.output()?;
+	if !output.status.success() {
+		return Err(anyhow::anyhow!(
+			"Failed to get diff: {}",
+			String::from_utf8_lossy(&output.stderr)
+		));
+	}
+	let mut issues = gate::check(&gate::added_lines(&String::from_utf8_lossy(&output.stdout)));
+
+	// The LLM is advisory here: a slow or failed call never blocks a commit
+	if args.llm {
+		let budget = std::time::Duration::from_secs(args.budget);
+		match tokio::time::timeout(
+			budget,
+			perform_code_review(repo_path, config, args, rules, false),
+		)
+		.await
+		{
+			Ok(Ok(review)) => issues.extend(review.issues),
+			Ok(Err(e)) => eprintln!("⚠️  LLM review skipped: {}", e),
+			Err(_) => eprintln!("⚠️  LLM review skipped: no answer within {}s", args.budget),
+		}
+	}
+
+	let (blocking, other): (Vec<&ReviewIssue>, Vec<&ReviewIssue>) = issues
+		.iter()
+		.partition(|issue| should_show_issue(&issue.severity.to_uppercase(), fail_on));
+
+	if blocking.is_empty() {
+		println!(
+			"✅ Review gate passed{}",
+			if other.is_empty() {
+				String::new()
+			} else {
+				format!(" ({} non-blocking issue(s))", other.len())
+			}
+		);
+		return Ok(());
+	}
+
+	println!(
+		"❌ Review gate failed: {} blocking issue(s)",
+		blocking.len()
+	);
+	for issue in &blocking {
+		let location = match (issue.file.is_empty(), issue.line) {
+			(true, _) => String::new(),
+			(false, 0) => format!("{}: ", issue.file),
+			(false, line) => format!("{}:{}: ", issue.file, line),
+		};
+		let rule = if issue.rule.is_empty() {
+			String::new()
+		} else {
+			format!(" [{}]", issue.rule)
+		};
+		println!(
+			"  {}[{}]{} {} - {}",
+			location,
+			issue.severity.to_uppercase(),
+			rule,
+			issue.title,
+			issue.description
+		);
+	}
+	if !other.is_empty() {
+		println!("  ...and {} non-blocking issue(s)", other.len());
+	}
+	Err(anyhow::anyhow!(
+		"Commit blocked by review; fix the issues or commit with --no-verify"
+	))
+}
+
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 struct ReviewResult {
 	summary: ReviewSummary,
@@ -124,12 +326,24 @@ struct ReviewIssue {
 	category: String,
 	title: String,
 	description: String,
+	/// Repository-relative path of the affected file, empty when not file-specific
+	#[serde(default)]
+	file: String,
+	/// 1-indexed line in the new version of the file, 0 when unknown
+	#[serde(default)]
+	line: usize,
+	/// Id of the project rule the issue breaks, empty for other issues
+	#[serde(default, skip_serializing_if = "String::is_empty")]
+	rule: String,
 }
 
 async fn perform_code_review(
 	repo_path: &std::path::Path,
 	config: &Config,
 	args: &ReviewArgs,
+	rules: &[ReviewRule],
+	// Stand in a placeholder review when the LLM fails, instead of an error
+	fallback: bool,
 ) -> Result<ReviewResult> {
 	// Get the diff of staged changes
 	let output = Command::new("git")
@@ -195,6 +409,13 @@ async fn perform_code_review(
 		String::new()
 	};
 
+	// Conventions the team recorded, so they are checked like style rules
+	let conventions_context = house_style::prompt_section(
+		&house_style::convention_memories(repo_path, config, config.commit.convention_memories)
+			.await,
+		&[],
+	);
+
 	// Prepare the enhanced prompt for code review
 	let prompt = format!(
 		"You are an expert code reviewer. Analyze the following git diff and provide a comprehensive code review focusing on best practices, potential issues, and maintainability.\n\n\
@@ -229,7 +450,11 @@ async fn perform_code_review(
 		File Statistics:\n\
 		{}\n\n\
 		Git Diff:\n\
-		```\n{}\n```{}\n\n\
+		```\n{}\n```{}{}{}\n\n\
+		LOCATIONS:\n\
+		For every issue set 'file' to the path exactly as it appears in the diff header (without the a/ or b/ prefix) \
+		and 'line' to the line number in the new version of the file, taken from the hunk headers. \
+		Use an empty file and line 0 for issues that do not belong to a specific place.\n\n\
 		Provide a structured analysis. Focus on actionable feedback and be specific about issues. Provide clear suggestions for improvements. Be thorough but concise.",
 		file_count,
 		additions,
@@ -243,7 +468,9 @@ async fn perform_code_review(
 		} else {
 			diff
 		},
-		focus_context
+		focus_context,
+		conventions_context,
+		rules::prompt_section(rules)
 	);
 
 	// Call the LLM for code review
@@ -251,7 +478,11 @@ async fn perform_code_review(
 		Ok(response) => {
 			// Parse the JSON response (should be valid due to structured output)
 			match serde_json::from_str::<ReviewResult>(&response) {
-				Ok(review_result) => Ok(review_result),
+				Ok(mut review_result) => {
+					apply_rules(&mut review_result, rules);
+					Ok(review_result)
+				}
+				Err(e) if !fallback => Err(anyhow::anyhow!("invalid LLM response ({})", e)),
 				Err(e) => {
 					eprintln!(
 						"Warning: Failed to parse LLM response as JSON ({}), creating fallback",
@@ -262,6 +493,7 @@ async fn perform_code_review(
 				}
 			}
 		}
+		Err(e) if !fallback => Err(e),
 		Err(e) => {
 			eprintln!("Warning: LLM call failed ({}), creating basic review", e);
 			create_fallback_review(file_count, &changed_files, "LLM analysis failed")
@@ -269,6 +501,21 @@ async fn perform_code_review(
 	}
 }
 
+/// Drop rule ids the project doesn't define and give issues the severity of
+/// their rule
+fn apply_rules(review: &mut ReviewResult, rules: &[ReviewRule]) {
+	for issue in &mut review.issues {
+		match rules::find(rules, &issue.rule) {
+			Some(rule) => {
+				if let Some(severity) = &rule.severity {
+					issue.severity = severity.to_uppercase();
+				}
+			}
+			None => issue.rule.clear(),
+		}
+	}
+}
+
 fn analyze_file_types(files: &[String]) -> String {
 	let mut type_counts: HashMap<String, usize> = HashMap::new();
 
@@ -305,6 +552,9 @@ fn create_fallback_review(
 			description:
 				"The automated review could not complete fully. Manual review recommended."
 					.to_string(),
+			file: String::new(),
+			line: 0,
+			rule: String::new(),
 		}],
 		recommendations: vec![
 			"Consider running the review again".to_string(),
@@ -342,6 +592,16 @@ fn display_review_results(review: &ReviewResult, severity_filter: &str) {
 
 			println!("\n{} {} [{}]", severity_emoji, issue.title, issue.severity);
 			println!("   Category: {}", issue.category);
+			if !issue.rule.is_empty() {
+				println!("   Rule: {}", issue.rule);
+			}
+			if !issue.file.is_empty() {
+				if issue.line > 0 {
+					println!("   Location: {}:{}", issue.file, issue.line);
+				} else {
+					println!("   Location: {}", issue.file);
+				}
+			}
 			println!("   Description: {}", issue.description);
 		}
 	}
@@ -366,6 +626,110 @@ fn display_review_results(review: &ReviewResult, severity_filter: &str) {
 	}
 }
 
+/// Map review issues to a SARIF 2.1.0 log, one rule per project rule broken
+/// and per category of the other issues
+fn to_sarif(
+	review: &ReviewResult,
+	project_rules: &[ReviewRule],
+	severity_filter: &str,
+) -> serde_json::Value {
+	let mut rule_ids: Vec<String> = Vec::new();
+	let mut rules = Vec::new();
+	let mut results = Vec::new();
+
+	for issue in review
+		.issues
+		.iter()
+		.filter(|issue| should_show_issue(&issue.severity, severity_filter))
+	{
+		let project_rule = rules::find(project_rules, &issue.rule);
+		let rule_id = match project_rule {
+			Some(rule) => format!("project/{}", rule.id),
+			None => sarif_rule_id(&issue.category),
+		};
+		let rule_index = match rule_ids.iter().position(|id| id == &rule_id) {
+			Some(index) => index,
+			None => {
+				rules.push(match project_rule {
+					Some(rule) => json!({
+						"id": rule_id,
+						"name": rule.id,
+						"shortDescription": {
+							"text": if rule.title.is_empty() { &rule.id } else { &rule.title }
+						},
+						"fullDescription": { "text": rule.description },
+						"properties": { "tags": ["project-rule"] }
+					}),
+					None => json!({
+						"id": rule_id,
+						"name": issue.category,
+						"shortDescription": { "text": format!("{} issues", issue.category) },
+						"properties": { "tags": [issue.category.to_lowercase()] }
+					}),
+				});
+				rule_ids.push(rule_id.clone());
+				rule_ids.len() - 1
+			}
+		};
+
+		let mut result = json!({
+			"ruleId": rule_id,
+			"ruleIndex": rule_index,
+			"level": sarif_level(&issue.severity),
+			"message": { "text": format!("{}: {}", issue.title, issue.description) },
+			"properties": { "severity": issue.severity }
+		});
+		if !issue.file.is_empty() {
+			let mut location = json!({
+				"artifactLocation": { "uri": issue.file, "uriBaseId": "%SRCROOT%" }
+			});
+			if issue.line > 0 {
+				location["region"] = json!({ "startLine": issue.line });
+			}
+			result["locations"] = json!([{ "physicalLocation": location }]);
+		}
+		results.push(result);
+	}
+
+	json!({
+		"$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+		"version": "2.1.0",
+		"runs": [{
+			"tool": {
+				"driver": {
+					"name": "octocode",
+					"version": env!("CARGO_PKG_VERSION"),
+					"informationUri": "https://github.com/muvon/octocode",
+					"rules": rules
+				}
+			},
+			"results": results
+		}]
+	})
+}
+
+/// Stable rule ID for an issue category, e.g. "Code Quality" -> "octocode/code-quality"
+fn sarif_rule_id(category: &str) -> String {
+	let slug = category
+		.to_lowercase()
+		.split(|c: char| !c.is_ascii_alphanumeric())
+		.filter(|part| !part.is_empty())
+		.collect::<Vec<_>>()
+		.join("-");
+	format!(
+		"octocode/{}",
+		if slug.is_empty() { "general" } else { &slug }
+	)
+}
+
+fn sarif_level(severity: &str) -> &'static str {
+	match severity.to_uppercase().as_str() {
+		"CRITICAL" | "HIGH" => "error",
+		"MEDIUM" => "warning",
+		_ => "note",
+	}
+}
+
 fn should_show_issue(issue_severity: &str, filter: &str) -> bool {
 	let severity_levels = ["CRITICAL", "HIGH", "MEDIUM", "LOW"];
 	let filter_index = severity_levels
@@ -431,9 +795,12 @@ async fn call_llm_for_review(prompt: &str, config: &Config) -> Result<String> {
 									"severity": {"type": "string"},
 									"category": {"type": "string"},
 									"title": {"type": "string"},
-									"description": {"type": "string"}
+									"description": {"type": "string"},
+									"file": {"type": "string"},
+									"line": {"type": "integer"},
+									"rule": {"type": "string"}
 								},
-								"required": ["severity", "category", "title", "description"],
+								"required": ["severity", "category", "title", "description", "file", "line", "rule"],
 								"additionalProperties": false
 							}
 						},
@@ -480,3 +847,143 @@ async fn call_llm_for_review(prompt: &str, config: &Config) -> Result<String> {
 
 	Ok(message.to_string())
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn issue(severity: &str, category: &str, file: &str, line: usize) -> ReviewIssue {
+		ReviewIssue {
+			severity: severity.to_string(),
+			category: category.to_string(),
+			title: "Title".to_string(),
+			description: "Description".to_string(),
+			file: file.to_string(),
+			line,
+			rule: String::new(),
+		}
+	}
+
+	#[test]
+	fn test_sarif_maps_rules_levels_and_locations() {
+		let review = ReviewResult {
+			summary: ReviewSummary {
+				total_files: 1,
+				total_issues: 3,
+				overall_score: 70,
+			},
+			issues: vec![
+				issue("CRITICAL", "Security", "src/auth.rs", 42),
+				issue("MEDIUM", "Code Quality", "", 0),
+				issue("HIGH", "Security", "src/db.rs", 0),
+				issue("LOW", "Documentation", "README.md", 3),
+			],
+			recommendations: Vec::new(),
+		};
+
+		let sarif = to_sarif(&review, &[], "medium");
+		let run = &sarif["runs"][0];
+		assert_eq!(sarif["version"], "2.1.0");
+		assert_eq!(run["tool"]["driver"]["rules"].as_array().unwrap().len(), 2);
+
+		let results = run["results"].as_array().unwrap();
+		assert_eq!(results.len(), 3); // LOW is below the severity filter
+		assert_eq!(results[0]["ruleId"], "octocode/security");
+		assert_eq!(results[0]["level"], "error");
+		let location = &results[0]["locations"][0]["physicalLocation"];
+		assert_eq!(location["artifactLocation"]["uri"], "src/auth.rs");
+		assert_eq!(location["region"]["startLine"], 42);
+
+		assert_eq!(results[1]["ruleId"], "octocode/code-quality");
+		assert_eq!(results[1]["level"], "warning");
+		assert!(results[1].get("locations").is_none());
+
+		assert_eq!(results[2]["ruleIndex"], 0);
+		assert!(results[2]["locations"][0]["physicalLocation"]
+			.get("region")
+			.is_none());
+	}
+
+	#[test]
+	fn test_fail_on_accepts_sarif_levels() {
+		assert_eq!(fail_on_severity("error").unwrap(), "high");
+		assert_eq!(fail_on_severity("Warning").unwrap(), "medium");
+		assert_eq!(fail_on_severity("critical").unwrap(), "critical");
+		assert!(fail_on_severity("fatal").is_err());
+		assert!(should_show_issue(
+			"CRITICAL",
+			fail_on_severity("error").unwrap()
+		));
+		assert!(!should_show_issue(
+			"MEDIUM",
+			fail_on_severity("error").unwrap()
+		));
+	}
+
+	#[test]
+	fn test_gate_takes_validated_fail_on() {
+		use clap::Parser;
+		let parse = |args: &[&str]| crate::OctocodeArgs::try_parse_from(args);
+
+		let Ok(crate::OctocodeArgs {
+			command: crate::Commands::Review(args),
+			..
+		}) = parse(&[
+			"octocode",
+			"review",
+			"--staged",
+			"--gate",
+			"--fail-on",
+			"critical",
+		])
+		else {
+			panic!("--gate --fail-on critical was rejected");
+		};
+		assert!(args.gate);
+		assert_eq!(args.fail_on.as_deref(), Some("critical"));
+
+		assert!(parse(&["octocode", "review", "--fail-on", "fatal"]).is_err());
+		assert!(parse(&["octocode", "review", "--gate", "--block-on", "critical"]).is_err());
+	}
+
+	#[test]
+	fn test_project_rules_map_to_issues() {
+		let rules = vec![ReviewRule {
+			id: "no-unwrap".to_string(),
+			title: "No unwrap in library code".to_string(),
+			severity: Some("high".to_string()),
+			description: "Use `?`.".to_string(),
+		}];
+		let mut review = ReviewResult {
+			summary: ReviewSummary {
+				total_files: 1,
+				total_issues: 2,
+				overall_score: 80,
+			},
+			issues: vec![
+				ReviewIssue {
+					rule: "no-unwrap".to_string(),
+					..issue("LOW", "Code Quality", "src/lib.rs", 7)
+				},
+				ReviewIssue {
+					rule: "made-up".to_string(),
+					..issue("LOW", "Style", "src/lib.rs", 9)
+				},
+			],
+			recommendations: Vec::new(),
+		};
+
+		apply_rules(&mut review, &rules);
+		assert_eq!(review.issues[0].severity, "HIGH");
+		assert!(review.issues[1].rule.is_empty());
+
+		let sarif = to_sarif(&review, &rules, "low");
+		let run = &sarif["runs"][0];
+		assert_eq!(run["results"][0]["ruleId"], "project/no-unwrap");
+		assert_eq!(run["results"][1]["ruleId"], "octocode/style");
+		assert_eq!(
+			run["tool"]["driver"]["rules"][0]["shortDescription"]["text"],
+			"No unwrap in library code"
+		);
+	}
+}
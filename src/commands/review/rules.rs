@@ -0,0 +1,176 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Project review rules from `.octocode/review-rules.toml` or
+//! `.octocode/review-rules.md`
+//!
+//! The rules are handed to the LLM with the diff, and issues it attributes to
+//! a rule carry the rule's id. In TOML each rule is a `[[rule]]` table:
+//!
+//! ```toml
+//! [[rule]]
+//! id = "no-unwrap"
+//! title = "No unwrap in library code"
+//! severity = "high"
+//! description = "Propagate errors with `?` or use `expect` with a reason."
+//! ```
+//!
+//! In Markdown each `## id: title` heading starts a rule whose description is
+//! the text under it; a `Severity: high` line sets its severity.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ReviewRule {
+	pub id: String,
+	#[serde(default)]
+	pub title: String,
+	/// Severity of violations, when the rule sets one
+	#[serde(default)]
+	pub severity: Option<String>,
+	#[serde(default)]
+	pub description: String,
+}
+
+#[derive(Deserialize)]
+struct RulesFile {
+	#[serde(default)]
+	rule: Vec<ReviewRule>,
+}
+
+/// Rules of the repository, none when it has no rules file
+pub fn load(repo_path: &Path) -> Result<Vec<ReviewRule>> {
+	let dir = repo_path.join(".octocode");
+	let toml_path = dir.join("review-rules.toml");
+	let markdown_path = dir.join("review-rules.md");
+
+	let rules = if toml_path.exists() {
+		let text = std::fs::read_to_string(&toml_path)?;
+		toml::from_str::<RulesFile>(&text)
+			.with_context(|| format!("Invalid review rules in {}", toml_path.display()))?
+			.rule
+	} else if markdown_path.exists() {
+		parse_markdown(&std::fs::read_to_string(&markdown_path)?)
+	} else {
+		return Ok(Vec::new());
+	};
+
+	let mut ids = std::collections::HashSet::new();
+	for rule in &rules {
+		if rule.id.trim().is_empty() || rule.id.contains(char::is_whitespace) {
+			return Err(anyhow::anyhow!(
+				"Review rule ids must be non-empty and without spaces: '{}'",
+				rule.id
+			));
+		}
+		if !ids.insert(rule.id.as_str()) {
+			return Err(anyhow::anyhow!("Duplicate review rule id '{}'", rule.id));
+		}
+	}
+	Ok(rules)
+}
+
+fn parse_markdown(text: &str) -> Vec<ReviewRule> {
+	let mut rules: Vec<ReviewRule> = Vec::new();
+	for line in text.lines() {
+		if let Some(heading) = line.strip_prefix("## ") {
+			let (id, title) = heading.split_once(':').unwrap_or((heading, ""));
+			rules.push(ReviewRule {
+				id: id.trim().to_string(),
+				title: title.trim().to_string(),
+				severity: None,
+				description: String::new(),
+			});
+			continue;
+		}
+		// Text before the first rule introduces the file
+		let Some(rule) = rules.last_mut() else {
+			continue;
+		};
+		match line.trim().strip_prefix("Severity:") {
+			Some(severity) => rule.severity = Some(severity.trim().to_uppercase()),
+			None => {
+				rule.description.push_str(line);
+				rule.description.push('\n');
+			}
+		}
+	}
+	for rule in &mut rules {
+		rule.description = rule.description.trim().to_string();
+	}
+	rules
+}
+
+/// The rules as a prompt section, empty without rules
+pub fn prompt_section(rules: &[ReviewRule]) -> String {
+	if rules.is_empty() {
+		return String::new();
+	}
+	let mut section = String::from(
+		"\n\nPROJECT RULES:\n\
+		Check the changes against these project rules. For an issue that breaks one, set 'rule' to the rule id \
+		(and use the rule's severity when it has one); leave 'rule' empty for other issues.\n",
+	);
+	for rule in rules {
+		section.push_str(&format!("\n[{}] {}", rule.id, rule.title));
+		if let Some(severity) = &rule.severity {
+			section.push_str(&format!(" (severity: {})", severity.to_uppercase()));
+		}
+		section.push('\n');
+		if !rule.description.is_empty() {
+			section.push_str(&rule.description);
+			section.push('\n');
+		}
+	}
+	section
+}
+
+pub fn find<'a>(rules: &'a [ReviewRule], id: &str) -> Option<&'a ReviewRule> {
+	rules.iter().find(|rule| rule.id == id)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse_markdown() {
+		let rules = parse_markdown(
+			"# Review rules\n\nIntro.\n\n## no-unwrap: No unwrap in library code\nSeverity: high\nUse `?` instead.\n\n## log-context\nLog with the request id.\n",
+		);
+		assert_eq!(
+			rules,
+			vec![
+				ReviewRule {
+					id: "no-unwrap".to_string(),
+					title: "No unwrap in library code".to_string(),
+					severity: Some("HIGH".to_string()),
+					description: "Use `?` instead.".to_string(),
+				},
+				ReviewRule {
+					id: "log-context".to_string(),
+					title: String::new(),
+					severity: None,
+					description: "Log with the request id.".to_string(),
+				},
+			]
+		);
+		assert!(prompt_section(&rules)
+			.contains("[no-unwrap] No unwrap in library code (severity: HIGH)"));
+		assert!(prompt_section(&[]).is_empty());
+	}
+}
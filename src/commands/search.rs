@@ -12,14 +12,18 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use clap::Args;
+use clap::{Args, ValueEnum};
 
 use octocode::config::Config;
 use octocode::constants::MAX_QUERIES;
+use octocode::editor::{self, Location};
 use octocode::indexer;
+use octocode::indexer::blame;
+use octocode::indexer::refine::CachedResults;
+use octocode::indexer::similar::CodeRange;
 
 use octocode::storage;
-use octocode::store::Store;
+use octocode::store::{relevance, ContentFilter, ContentScope, PathFilter, Store, SymbolFilter};
 
 use crate::commands::OutputFormat;
 
@@ -33,6 +37,12 @@ fn validate_detail_level(s: &str) -> Result<String, String> {
 	}
 }
 
+fn validate_scope(s: &str) -> Result<String, String> {
+	ContentScope::parse(s)
+		.map(|scope| scope.as_str().to_string())
+		.map_err(|e| e.to_string())
+}
+
 fn validate_queries(queries: &[String]) -> Result<(), anyhow::Error> {
 	if queries.is_empty() {
 		return Err(anyhow::anyhow!("At least one query is required"));
@@ -65,21 +75,51 @@ fn validate_queries(queries: &[String]) -> Result<(), anyhow::Error> {
 	Ok(())
 }
 
+/// Order of the results of each content type
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SearchSort {
+	/// Most similar first
+	#[default]
+	Relevance,
+	/// Most recently changed first, by git blame (needs index.blame)
+	Recency,
+}
+
 #[derive(Debug, Args)]
 pub struct SearchArgs {
-	/// The search queries
-	#[arg(required = true)]
+	/// The search queries (optional with --symbol, which then ranks by the symbol name)
+	#[arg(required_unless_present_any = ["symbol", "refine", "like"])]
 	pub queries: Vec<String>,
 
+	/// Narrow down the results of the last search with a new query instead of searching the index again
+	#[arg(
+		long,
+		value_name = "QUERY",
+		conflicts_with_all = ["queries", "symbol", "scope", "language", "threshold"]
+	)]
+	pub refine: Option<String>,
+
+	/// Find code similar to these lines, e.g. 'src/main.rs:10-40', to spot duplicates and copy-paste drift
+	#[arg(
+		long,
+		value_name = "FILE:START-END",
+		conflicts_with_all = ["queries", "refine", "symbol", "scope", "language", "expand_query"]
+	)]
+	pub like: Option<String>,
+
 	/// Search mode: 'all' (default), 'code', 'docs', or 'text'
-	#[arg(short, long, default_value = "all")]
-	pub mode: String,
+	#[arg(short, long)]
+	pub mode: Option<String>,
+
+	/// Use the defaults of the [search.profiles.<NAME>] config section; flags given here still win
+	#[arg(long, value_name = "NAME")]
+	pub profile: Option<String>,
 
 	/// Output format: 'cli', 'json', 'md', or 'text'
 	#[arg(short, long, default_value = "cli")]
 	pub format: OutputFormat,
 
-	/// Similarity threshold (0.0-1.0). Higher values = more similar results only. Defaults to config.search.similarity_threshold
+	/// Smallest relevance score (0.0-1.0) of the results; higher values trade recall for precision. Defaults to config.search.similarity_threshold
 	#[arg(short, long)]
 	pub threshold: Option<f32>,
 
@@ -94,6 +134,96 @@ pub struct SearchArgs {
 	/// Filter by programming language (only affects code blocks)
 	#[arg(short = 'l', long)]
 	pub language: Option<String>,
+
+	/// Only return code blocks declaring this exact symbol; end with '*' for prefix matching (e.g. 'parse_*')
+	#[arg(long)]
+	pub symbol: Option<String>,
+
+	/// Only return code blocks mentioning a query in 'comments', 'strings' (string literals), or 'code'
+	#[arg(long = "in", value_name = "SCOPE", value_parser = validate_scope)]
+	pub scope: Option<String>,
+
+	/// Only return results from files at or under this path, relative to the project root (repeatable)
+	#[arg(long = "path", value_name = "PATH", conflicts_with = "refine")]
+	pub paths: Vec<String>,
+
+	/// Maximum results per content type (default: config.search.max_results)
+	#[arg(long)]
+	pub limit: Option<usize>,
+
+	/// Skip this many results per content type, to page through long result lists
+	#[arg(long, default_value_t = 0)]
+	pub offset: usize,
+
+	/// Open hit N of this search in $EDITOR (or VS Code); 'octocode open' picks from the last search later
+	#[arg(long, value_name = "N")]
+	pub open: Option<usize>,
+
+	/// Leave out test files and inline test code (default: config.search.exclude_tests)
+	#[arg(long, conflicts_with = "include_tests")]
+	pub exclude_tests: bool,
+
+	/// Keep test code in the results even when config.search.exclude_tests is set
+	#[arg(long)]
+	pub include_tests: bool,
+
+	/// Also search LLM reformulations of a single query and fuse the rankings (default: config.search.query_expansion)
+	#[arg(long, conflicts_with_all = ["no_expand_query", "refine"])]
+	pub expand_query: bool,
+
+	/// Search a single query as typed even when config.search.query_expansion is set
+	#[arg(long)]
+	pub no_expand_query: bool,
+
+	/// Rerank results by how well their text, symbols and path match the queries
+	#[arg(long, conflicts_with = "refine")]
+	pub rerank: bool,
+
+	/// Order results by relevance or by when they last changed
+	#[arg(long, value_enum, default_value_t = SearchSort::Relevance)]
+	pub sort: SearchSort,
+
+	/// Only blocks last changed by an author whose name or email contains this (needs index.blame)
+	#[arg(long, value_name = "NAME")]
+	pub author: Option<String>,
+}
+
+/// Search settings shared by flags, the selected profile and `[search]`
+#[derive(Debug, PartialEq)]
+struct Settings {
+	mode: String,
+	language: Option<String>,
+	threshold: Option<f32>,
+	rerank: bool,
+	exclude_tests: bool,
+	limit: usize,
+}
+
+// Command line flags take precedence over the profile, and the profile
+// over the configured defaults
+fn resolve_settings(args: &SearchArgs, config: &Config) -> anyhow::Result<Settings> {
+	indexer::search::check_offset(args.offset, config.search.max_offset)?;
+	let profile = match &args.profile {
+		Some(name) => config.search.profile(name)?.clone(),
+		None => Default::default(),
+	};
+	Ok(Settings {
+		mode: args
+			.mode
+			.clone()
+			.or(profile.mode)
+			.unwrap_or_else(|| "all".to_string()),
+		language: args.language.clone().or(profile.language),
+		threshold: args.threshold.or(profile.threshold),
+		rerank: args.rerank || profile.rerank.unwrap_or(false),
+		exclude_tests: args.exclude_tests
+			|| (profile.exclude_tests.unwrap_or(config.search.exclude_tests)
+				&& !args.include_tests),
+		limit: args
+			.limit
+			.or(profile.limit)
+			.unwrap_or(config.search.max_results),
+	})
 }
 
 pub async fn execute(
@@ -113,102 +243,317 @@ pub async fn execute(
 		));
 	}
 
-	// Validate queries
-	validate_queries(&args.queries)?;
+	// Validate detail_level is only used with compatible formats
+	if args.detail_level.is_some() {
+		if args.format.is_json() {
+			return Err(anyhow::anyhow!(
+				"--detail-level is not supported with JSON format. Use --format=cli or --format=text instead."
+			));
+		}
+		if args.format.is_md() {
+			return Err(anyhow::anyhow!(
+				"--detail-level is not supported with Markdown format. Use --format=cli or --format=text instead."
+			));
+		}
+	}
 
-	// Use config default threshold if not provided via CLI
-	let threshold = args.threshold.unwrap_or(config.search.similarity_threshold);
+	// Get effective detail level (default to "partial" for cli/text formats)
+	let effective_detail_level = args.detail_level.as_deref().unwrap_or("partial");
 
-	// Validate similarity threshold
-	if !(0.0..=1.0).contains(&threshold) {
-		return Err(anyhow::anyhow!(
-			"Similarity threshold must be between 0.0 and 1.0, got: {}",
-			threshold
-		));
+	let Settings {
+		mode,
+		language,
+		threshold: threshold_override,
+		rerank,
+		exclude_tests,
+		limit,
+	} = resolve_settings(args, config)?;
+	if limit == 0 {
+		return Err(anyhow::anyhow!("--limit must be at least 1"));
 	}
 
-	// Validate search mode
-	let search_mode = match args.mode.as_str() {
-		"all" | "code" | "docs" | "text" => args.mode.as_str(),
-		_ => {
+	let (search_mode, results, distance_threshold) = if let Some(query) = &args.refine {
+		let (search_mode, results) =
+			refine_last_results(&current_dir, query, &mode, exclude_tests)?;
+		// Refined hits already passed the threshold of the original search
+		(search_mode, results, f32::MAX)
+	} else if let Some(like) = &args.like {
+		let range = CodeRange::parse(like)?;
+		if !matches!(mode.as_str(), "all" | "code") {
 			return Err(anyhow::anyhow!(
-				"Invalid search mode '{}'. Use 'all', 'code', 'docs', or 'text'.",
-				args.mode
+				"--like only finds code. Use --mode code or --mode all."
 			));
 		}
-	};
-
-	// Validate language filter if provided
-	if let Some(ref language) = args.language {
-		use octocode::indexer::languages;
-		if languages::get_language(language).is_none() {
+		let threshold = threshold_override.unwrap_or(config.search.similarity_threshold);
+		if !(0.0..=1.0).contains(&threshold) {
 			return Err(anyhow::anyhow!(
-				"Invalid language '{}'. Supported languages: rust, javascript, typescript, python, go, cpp, php, bash, ruby, json, svelte, css",
-				language
+				"Similarity threshold must be between 0.0 and 1.0, got: {}",
+				threshold
 			));
 		}
-	}
+		let path_filter = if args.paths.is_empty() {
+			None
+		} else {
+			Some(PathFilter::parse(&args.paths)?)
+		};
+		let store = &store.with_path_filter(path_filter);
+
+		// One extra result tells whether another page follows
+		let mut code_blocks = indexer::similar::find_similar(
+			store,
+			&range,
+			&current_dir,
+			args.offset.saturating_add(limit).saturating_add(1),
+			threshold,
+			config,
+		)
+		.await?;
+		if exclude_tests {
+			indexer::search::drop_test_results(&mut code_blocks, &mut Vec::new(), &mut Vec::new());
+		}
 
-	// Validate detail_level is only used with compatible formats
-	if args.detail_level.is_some() {
-		if args.format.is_json() {
-			return Err(anyhow::anyhow!(
-				"--detail-level is not supported with JSON format. Use --format=cli or --format=text instead."
-			));
+		let results = CachedResults {
+			queries: vec![range.to_string()],
+			mode: "code".to_string(),
+			code_blocks,
+			doc_blocks: Vec::new(),
+			text_blocks: Vec::new(),
+		};
+		if let Err(e) = results.save(&current_dir) {
+			eprintln!("Warning: failed to remember search results: {}", e);
 		}
-		if args.format.is_md() {
+		("code", results, 1.0 - threshold)
+	} else {
+		let symbol_filter = args
+			.symbol
+			.as_deref()
+			.map(SymbolFilter::parse)
+			.transpose()?;
+
+		// Validate queries; a bare --symbol search ranks by the symbol name itself
+		let queries = if args.queries.is_empty() {
+			match &symbol_filter {
+				Some(symbol) => vec![symbol.name().to_string()],
+				None => return Err(anyhow::anyhow!("At least one query is required")),
+			}
+		} else {
+			validate_queries(&args.queries)?;
+			args.queries.clone()
+		};
+
+		// Path filters also pick the shards searched when blocks are sharded
+		let path_filter = if args.paths.is_empty() {
+			None
+		} else {
+			Some(PathFilter::parse(&args.paths)?)
+		};
+		let store = &store.with_path_filter(path_filter);
+
+		// Scoped searches look for the queries literally in comments, strings or code
+		let content_filter = match args.scope.as_deref() {
+			Some(scope) => {
+				if args.queries.is_empty() {
+					return Err(anyhow::anyhow!("--in requires a search term"));
+				}
+				Some(ContentFilter::new(ContentScope::parse(scope)?, &queries)?)
+			}
+			None => None,
+		};
+
+		// Use config default threshold if not provided via CLI. Symbol and scoped
+		// matches are already exact, so vector similarity only orders them unless
+		// asked otherwise.
+		let exact_match = symbol_filter.is_some() || content_filter.is_some();
+		let threshold = threshold_override.unwrap_or(if exact_match {
+			0.0
+		} else {
+			config.search.similarity_threshold
+		});
+
+		// Validate similarity threshold
+		if !(0.0..=1.0).contains(&threshold) {
 			return Err(anyhow::anyhow!(
-				"--detail-level is not supported with Markdown format. Use --format=cli or --format=text instead."
+				"Similarity threshold must be between 0.0 and 1.0, got: {}",
+				threshold
 			));
 		}
-	}
 
-	// Convert similarity threshold to distance threshold
-	let distance_threshold = 1.0 - threshold;
+		// Validate search mode
+		let search_mode = match mode.as_str() {
+			"all" | "code" | "docs" | "text" => mode.as_str(),
+			_ => {
+				return Err(anyhow::anyhow!(
+					"Invalid search mode '{}'. Use 'all', 'code', 'docs', or 'text'.",
+					mode
+				));
+			}
+		};
+
+		// Symbols and comment/string spans only exist on code blocks
+		let search_mode = match (search_mode, exact_match) {
+			("docs" | "text", true) => {
+				return Err(anyhow::anyhow!(
+					"--symbol and --in only apply to code search. Use --mode code or --mode all."
+				));
+			}
+			("all", true) => "code",
+			(mode, _) => mode,
+		};
+
+		// Validate language filter if provided
+		if let Some(ref language) = language {
+			use octocode::indexer::languages;
+			if languages::get_language(language).is_none() {
+				return Err(anyhow::anyhow!(
+				"Invalid language '{}'. Supported languages: rust, javascript, typescript, python, go, cpp, php, bash, ruby, json, svelte, css, hcl, sql, zig, proto, haskell, lua, dart",
+				language
+			));
+			}
+		}
 
-	// Get effective detail level (default to "partial" for cli/text formats)
-	let effective_detail_level = args.detail_level.as_deref().unwrap_or("partial");
+		// Convert similarity threshold to distance threshold
+		let distance_threshold = 1.0 - threshold;
+
+		// A single query may be searched together with LLM reformulations;
+		// symbol and scoped searches match exact text, so they never are
+		let expand = args.expand_query || (config.search.query_expansion && !args.no_expand_query);
+		let mut search_queries = queries.clone();
+		if expand && args.queries.len() == 1 && !exact_match {
+			match indexer::query_expansion::expand_query(&queries[0], config).await {
+				Ok(expansions) => {
+					if args.format.is_cli() && !expansions.is_empty() {
+						println!("Also searching for: {}\n", expansions.join(" | "));
+					}
+					search_queries.extend(expansions);
+				}
+				Err(e) => eprintln!("Warning: query expansion failed, searching as typed: {}", e),
+			}
+		}
+		let fuse = search_queries.len() > queries.len();
+
+		// Generate batch embeddings for all queries
+		let embeddings = indexer::search::generate_batch_embeddings_for_queries(
+			&search_queries,
+			search_mode,
+			config,
+		)
+		.await?;
+
+		// Zip queries with embeddings
+		let query_embeddings: Vec<_> = search_queries
+			.iter()
+			.cloned()
+			.zip(embeddings.into_iter())
+			.collect();
+
+		// Execute parallel searches - FIXED: Use distance_threshold instead of args.threshold.
+		// Pages are cut from the merged ranking, so fetch everything up to the page
+		// plus one result to tell whether another page follows.
+		let search_results = indexer::search::execute_parallel_searches(
+			store,
+			query_embeddings,
+			search_mode,
+			args.offset.saturating_add(limit).saturating_add(1),
+			distance_threshold, // FIXED: Was args.threshold, now distance_threshold
+			language.as_deref(),
+			symbol_filter.as_ref(),
+			content_filter.as_ref(),
+			exclude_tests,
+		)
+		.await?;
+
+		let (mut code_blocks, mut doc_blocks, mut text_blocks) = if fuse {
+			// Reformulations of one query are merged by reciprocal rank fusion
+			indexer::search::fuse_results(search_results, distance_threshold)
+		} else {
+			// Deduplicate and merge with multi-query bonuses
+			indexer::search::deduplicate_and_merge_results(
+				search_results,
+				&queries,
+				distance_threshold,
+			)
+		};
+
+		if rerank {
+			use octocode::reranker::{CrossEncoder, Reranker};
+			let query = queries.join(" ");
+			match &config.search.rerank_model {
+				Some(model) => {
+					let encoder = CrossEncoder::load(model)?;
+					code_blocks = encoder.rerank_code_blocks(code_blocks, &query)?;
+					doc_blocks = encoder.rerank_document_blocks(doc_blocks, &query)?;
+					text_blocks = encoder.rerank_text_blocks(text_blocks, &query)?;
+				}
+				None => {
+					code_blocks = Reranker::rerank_code_blocks(code_blocks, &query);
+					Reranker::tf_idf_boost(&mut code_blocks, &query);
+					doc_blocks = Reranker::rerank_document_blocks(doc_blocks, &query);
+					text_blocks = Reranker::rerank_text_blocks(text_blocks, &query);
+				}
+			}
+		}
 
-	// Generate batch embeddings for all queries
-	let embeddings =
-		indexer::search::generate_batch_embeddings_for_queries(&args.queries, search_mode, config)
-			.await?;
-
-	// Zip queries with embeddings
-	let query_embeddings: Vec<_> = args
-		.queries
-		.iter()
-		.cloned()
-		.zip(embeddings.into_iter())
-		.collect();
-
-	// Execute parallel searches - FIXED: Use distance_threshold instead of args.threshold
-	let search_results = indexer::search::execute_parallel_searches(
-		store,
-		query_embeddings,
-		search_mode,
-		config.search.max_results,
-		distance_threshold, // FIXED: Was args.threshold, now distance_threshold
-		args.language.as_deref(),
-	)
-	.await?;
-
-	// Deduplicate and merge with multi-query bonuses
-	let (mut code_blocks, mut doc_blocks, mut text_blocks) =
-		indexer::search::deduplicate_and_merge_results(
-			search_results,
-			&args.queries,
-			distance_threshold,
+		// Remember the whole result set so `--refine` can narrow it down later
+		let results = CachedResults {
+			queries,
+			mode: search_mode.to_string(),
+			code_blocks,
+			doc_blocks,
+			text_blocks,
+		};
+		if let Err(e) = results.save(&current_dir) {
+			eprintln!("Warning: failed to remember search results: {}", e);
+		}
+		(search_mode, results, distance_threshold)
+	};
+	let CachedResults {
+		mut code_blocks,
+		mut doc_blocks,
+		mut text_blocks,
+		..
+	} = results;
+
+	// Blame filters work on whatever the search ranked, before paging
+	if let Some(author) = &args.author {
+		blame::retain_author(&mut code_blocks, author);
+		blame::retain_author(&mut doc_blocks, author);
+		blame::retain_author(&mut text_blocks, author);
+	}
+	if args.sort == SearchSort::Recency {
+		blame::sort_by_recency(&mut code_blocks);
+		blame::sort_by_recency(&mut doc_blocks);
+		blame::sort_by_recency(&mut text_blocks);
+	}
+	if (args.author.is_some() || args.sort == SearchSort::Recency) && !config.index.blame {
+		eprintln!(
+			"Warning: --sort recency and --author use blame recorded at index time; set index.blame = true and reindex"
 		);
+	}
+
+	// Apply global result limits to the requested page
+	let code_more = indexer::search::take_page(&mut code_blocks, args.offset, limit);
+	let doc_more = indexer::search::take_page(&mut doc_blocks, args.offset, limit);
+	let text_more = indexer::search::take_page(&mut text_blocks, args.offset, limit);
+	let has_more = match search_mode {
+		"code" => code_more,
+		"docs" => doc_more,
+		"text" => text_more,
+		_ => code_more || doc_more || text_more,
+	};
 
-	// Apply global result limits
-	code_blocks.truncate(config.search.max_results);
-	doc_blocks.truncate(config.search.max_results);
-	text_blocks.truncate(config.search.max_results);
+	// Remember the hits so `octocode open` can jump to them later
+	let locations = result_locations(search_mode, &code_blocks, &doc_blocks, &text_blocks);
+	if let Err(e) = editor::save_last_results(&current_dir, &locations) {
+		if args.open.is_none() {
+			eprintln!("Warning: failed to remember search results: {}", e);
+		}
+	}
 
 	// Symbol expansion if requested
 	if args.expand && !code_blocks.is_empty() {
-		println!("Expanding symbols...");
+		if !args.format.is_json() {
+			println!("Expanding symbols...");
+		}
 		code_blocks = indexer::expand_symbols(store, code_blocks).await?;
 	}
 
@@ -237,7 +582,8 @@ pub async fn execute(
 		}
 		"docs" => {
 			if args.format.is_json() {
-				let json = serde_json::to_string_pretty(&doc_blocks)?;
+				let json =
+					serde_json::to_string_pretty(&indexer::search::scored_json(&doc_blocks)?)?;
 				println!("{}", json);
 			} else if args.format.is_md() {
 				let markdown =
@@ -256,7 +602,8 @@ pub async fn execute(
 		}
 		"text" => {
 			if args.format.is_json() {
-				let json = serde_json::to_string_pretty(&text_blocks)?;
+				let json =
+					serde_json::to_string_pretty(&indexer::search::scored_json(&text_blocks)?)?;
 				println!("{}", json);
 			} else if args.format.is_md() {
 				let markdown = indexer::text_blocks_to_markdown_with_config(&text_blocks, config);
@@ -298,15 +645,17 @@ pub async fn execute(
 
 			let mut final_code_results = code_blocks;
 			if args.expand {
-				println!("Expanding symbols...");
+				if !args.format.is_json() {
+					println!("Expanding symbols...");
+				}
 				final_code_results = indexer::expand_symbols(store, final_code_results).await?;
 			}
 
 			if args.format.is_json() {
 				let combined = serde_json::json!({
-					"code_blocks": final_code_results,
-					"document_blocks": doc_blocks,
-					"text_blocks": text_blocks
+					"code_blocks": indexer::search::scored_json(&final_code_results)?,
+					"document_blocks": indexer::search::scored_json(&doc_blocks)?,
+					"text_blocks": indexer::search::scored_json(&text_blocks)?
 				});
 				println!("{}", serde_json::to_string_pretty(&combined)?);
 			} else if args.format.is_md() {
@@ -383,9 +732,107 @@ pub async fn execute(
 		_ => unreachable!(),
 	}
 
+	if has_more && !args.format.is_json() {
+		println!(
+			"\nMore results available: rerun with --offset {}",
+			args.offset.saturating_add(limit)
+		);
+	}
+
+	if let Some(number) = args.open {
+		let location = crate::commands::open::select(&locations, number)?;
+		editor::open_location(&current_dir, location)?;
+	}
+
 	Ok(())
 }
 
+/// Narrow the cached results of the last search down to those matching
+/// `query`; `mode` limits a mixed result set to one content type.
+fn refine_last_results(
+	current_dir: &std::path::Path,
+	query: &str,
+	mode: &str,
+	exclude_tests: bool,
+) -> Result<(&'static str, CachedResults), anyhow::Error> {
+	let query = query.trim();
+	if query.len() < 3 {
+		return Err(anyhow::anyhow!(
+			"Refine query must be at least 3 characters long"
+		));
+	}
+
+	let mut refined = CachedResults::load(current_dir)?.refine(query);
+	if exclude_tests {
+		indexer::search::drop_test_results(
+			&mut refined.code_blocks,
+			&mut refined.doc_blocks,
+			&mut refined.text_blocks,
+		);
+	}
+	if let Err(e) = refined.save(current_dir) {
+		eprintln!("Warning: failed to remember refined results: {}", e);
+	}
+
+	let search_mode = match (refined.mode.as_str(), mode) {
+		(_, "code") => "code",
+		(_, "docs") => "docs",
+		(_, "text") => "text",
+		("code", "all") => "code",
+		("docs", "all") => "docs",
+		("text", "all") => "text",
+		(_, "all") => "all",
+		_ => {
+			return Err(anyhow::anyhow!(
+				"Invalid search mode '{}'. Use 'all', 'code', 'docs', or 'text'.",
+				mode
+			));
+		}
+	};
+	if refined.mode != "all" && refined.mode != search_mode {
+		return Err(anyhow::anyhow!(
+			"The last search only returned {} results; refine it with --mode {} or 'all'",
+			refined.mode,
+			refined.mode
+		));
+	}
+
+	Ok((search_mode, refined))
+}
+
+/// Hits in the order `octocode open` numbers them: code, then docs, then text
+fn result_locations(
+	search_mode: &str,
+	code_blocks: &[octocode::store::CodeBlock],
+	doc_blocks: &[octocode::store::DocumentBlock],
+	text_blocks: &[octocode::store::TextBlock],
+) -> Vec<Location> {
+	let mut locations = Vec::new();
+	// Code and document blocks store 0-indexed lines, text chunks 1-indexed ones
+	if matches!(search_mode, "code" | "all") {
+		locations.extend(code_blocks.iter().map(|block| Location {
+			path: block.path.clone(),
+			line: block.start_line + 1,
+			label: block.symbols.first().cloned().unwrap_or_default(),
+		}));
+	}
+	if matches!(search_mode, "docs" | "all") {
+		locations.extend(doc_blocks.iter().map(|block| Location {
+			path: block.path.clone(),
+			line: block.start_line + 1,
+			label: block.title.clone(),
+		}));
+	}
+	if matches!(search_mode, "text" | "all") {
+		locations.extend(text_blocks.iter().map(|block| Location {
+			path: block.path.clone(),
+			line: block.start_line.max(1),
+			label: String::new(),
+		}));
+	}
+	locations
+}
+
 fn render_text_blocks_with_config(
 	blocks: &[octocode::store::TextBlock],
 	_config: &Config,
@@ -420,7 +867,7 @@ fn render_text_blocks_with_config(
 
 			// Show similarity score if available
 			if let Some(distance) = block.distance {
-				println!("║ Similarity: {:.4}", 1.0 - distance);
+				println!("║ Similarity: {:.4}", relevance(distance));
 			}
 
 			println!("║");
@@ -544,7 +991,7 @@ fn render_document_blocks_with_config(
 
 			// Show similarity score if available
 			if let Some(distance) = block.distance {
-				println!("║ Similarity: {:.4}", 1.0 - distance);
+				println!("║ Similarity: {:.4}", relevance(distance));
 			}
 
 			println!("║");
@@ -638,3 +1085,96 @@ fn render_document_blocks_with_config(
 		println!("╚══════════════════════════════════════════════\n");
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use clap::Parser;
+	use octocode::config::SearchProfile;
+
+	#[derive(Parser)]
+	struct Cli {
+		#[command(flatten)]
+		search: SearchArgs,
+	}
+
+	fn settings(config: &Config, args: &[&str]) -> anyhow::Result<Settings> {
+		let cli = Cli::try_parse_from(["search", "auth"].iter().chain(args)).unwrap();
+		resolve_settings(&cli.search, config)
+	}
+
+	#[test]
+	fn test_profile_precedence() {
+		let mut config = Config::load_from_template().unwrap();
+		config.search.max_results = 20;
+		config.search.exclude_tests = false;
+		config.search.profiles.insert(
+			"review".to_string(),
+			SearchProfile {
+				mode: Some("code".to_string()),
+				limit: Some(5),
+				threshold: Some(0.6),
+				language: Some("rust".to_string()),
+				exclude_tests: Some(true),
+				rerank: Some(true),
+			},
+		);
+
+		// Without a profile, [search] applies
+		let defaults = settings(&config, &[]).unwrap();
+		assert_eq!(defaults.mode, "all");
+		assert_eq!(defaults.limit, 20);
+		assert_eq!(defaults.threshold, None);
+		assert!(!defaults.exclude_tests && !defaults.rerank);
+
+		// The profile overrides [search]
+		let profile = settings(&config, &["--profile", "review"]).unwrap();
+		assert_eq!(
+			profile,
+			Settings {
+				mode: "code".to_string(),
+				language: Some("rust".to_string()),
+				threshold: Some(0.6),
+				rerank: true,
+				exclude_tests: true,
+				limit: 5,
+			}
+		);
+
+		// Flags override the profile
+		let flags = settings(
+			&config,
+			&[
+				"--profile",
+				"review",
+				"--mode",
+				"docs",
+				"--limit",
+				"8",
+				"--threshold",
+				"0.3",
+				"--include-tests",
+			],
+		)
+		.unwrap();
+		assert_eq!(flags.mode, "docs");
+		assert_eq!(flags.limit, 8);
+		assert_eq!(flags.threshold, Some(0.3));
+		assert!(!flags.exclude_tests);
+		assert_eq!(flags.language.as_deref(), Some("rust"));
+
+		let unknown = settings(&config, &["--profile", "missing"]).unwrap_err();
+		assert!(unknown.to_string().contains("Configured profiles: review"));
+	}
+
+	#[test]
+	fn test_offset_is_capped() {
+		let mut config = Config::load_from_template().unwrap();
+		config.search.max_offset = 100;
+
+		assert!(settings(&config, &["--offset", "100"]).is_ok());
+		let err = settings(&config, &["--offset", "101"]).unwrap_err();
+		assert!(err.to_string().contains("search.max_offset"), "{}", err);
+		assert!(settings(&config, &["--offset", &usize::MAX.to_string()]).is_err());
+	}
+}
@@ -0,0 +1,108 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::Result;
+use clap::Args;
+use std::net::{IpAddr, SocketAddr};
+
+use octocode::config::Config;
+use octocode::mcp::logging::init_mcp_logging;
+use octocode::mcp::rest::RestServer;
+
+#[derive(Args, Clone)]
+pub struct ServeArgs {
+	/// Port to listen on
+	#[arg(long, default_value = "8080")]
+	pub port: u16,
+
+	/// Address (IPv4, IPv6 or host name) to listen on; use 0.0.0.0 or :: to
+	/// accept connections from other hosts
+	#[arg(long, default_value = "127.0.0.1")]
+	pub host: String,
+
+	/// Path to the directory to serve (defaults to current directory)
+	#[arg(long, default_value = ".")]
+	pub path: String,
+
+	/// Reject requests that modify stored data (POST and DELETE /memory)
+	#[arg(long)]
+	pub read_only: bool,
+
+	/// Enable debug logging
+	#[arg(long)]
+	pub debug: bool,
+}
+
+pub async fn run(config: Config, args: &ServeArgs) -> Result<()> {
+	let working_directory = std::path::Path::new(&args.path)
+		.canonicalize()
+		.map_err(|e| anyhow::anyhow!("Invalid path '{}': {}", args.path, e))?;
+	if !working_directory.is_dir() {
+		return Err(anyhow::anyhow!("Path '{}' is not a directory", args.path));
+	}
+
+	let bind_addr = bind_address(&args.host, args.port).await?;
+
+	init_mcp_logging(working_directory.clone(), args.debug)?;
+
+	RestServer::new(config, working_directory, args.read_only)
+		.await
+		.run(bind_addr)
+		.await
+}
+
+/// Address to listen on: an IPv4 or IPv6 address, bracketed or not, or a host
+/// name resolving to one
+async fn bind_address(host: &str, port: u16) -> Result<SocketAddr> {
+	let host = host
+		.strip_prefix('[')
+		.and_then(|host| host.strip_suffix(']'))
+		.unwrap_or(host);
+	if let Ok(ip) = host.parse::<IpAddr>() {
+		return Ok(SocketAddr::new(ip, port));
+	}
+	tokio::net::lookup_host((host, port))
+		.await
+		.map_err(|e| anyhow::anyhow!("Invalid address '{}': {}", host, e))?
+		.next()
+		.ok_or_else(|| anyhow::anyhow!("Address '{}' did not resolve", host))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn test_bind_address() {
+		assert_eq!(
+			bind_address("127.0.0.1", 8080).await.unwrap(),
+			"127.0.0.1:8080".parse::<SocketAddr>().unwrap()
+		);
+		for host in ["::1", "[::1]"] {
+			assert_eq!(
+				bind_address(host, 8080).await.unwrap(),
+				"[::1]:8080".parse::<SocketAddr>().unwrap()
+			);
+		}
+		assert_eq!(
+			bind_address("::", 9000).await.unwrap(),
+			"[::]:9000".parse::<SocketAddr>().unwrap()
+		);
+		assert!(bind_address("localhost", 8080)
+			.await
+			.unwrap()
+			.ip()
+			.is_loopback());
+	}
+}
@@ -0,0 +1,109 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap::Args;
+
+use octocode::store::{IndexStatistics, Store};
+
+use crate::commands::OutputFormat;
+
+#[derive(Args, Debug)]
+pub struct StatsArgs {
+	/// Output format
+	#[arg(long, value_enum, default_value = "cli")]
+	pub format: OutputFormat,
+}
+
+/// Report index health: table sizes, files, embeddings, commits and languages
+pub async fn execute(store: &Store, args: &StatsArgs) -> Result<(), anyhow::Error> {
+	let stats = store.get_index_statistics().await?;
+
+	if args.format.is_json() {
+		println!("{}", serde_json::to_string_pretty(&stats)?);
+	} else {
+		print_stats(&stats);
+	}
+
+	Ok(())
+}
+
+fn print_stats(stats: &IndexStatistics) {
+	println!("Index Statistics");
+	println!("================\n");
+
+	println!("Tables:");
+	if stats.tables.is_empty() {
+		println!("  (no tables - run 'octocode index' first)");
+	}
+	for table in &stats.tables {
+		let files = table
+			.unique_files
+			.map(|count| format!(", {} files", count))
+			.unwrap_or_default();
+		println!(
+			"  {:<24} {:>8} rows{}  {}",
+			table.name,
+			table.rows,
+			files,
+			format_size(table.size_bytes)
+		);
+	}
+	println!();
+
+	println!("Unique files: {}", stats.unique_files);
+	println!("Size on disk: {}", format_size(stats.total_size_bytes));
+	println!(
+		"Embedding dimensions: code {}, text {}",
+		stats.code_vector_dim, stats.text_vector_dim
+	);
+	println!(
+		"Last indexed commit: {}",
+		stats.last_indexed_commit.as_deref().unwrap_or("none")
+	);
+	println!();
+
+	println!("GraphRAG:");
+	println!("  Nodes: {}", stats.graphrag_nodes);
+	println!("  Relationships: {}", stats.graphrag_relationships);
+	println!(
+		"  Last commit: {}",
+		stats.graphrag_last_commit.as_deref().unwrap_or("none")
+	);
+	println!();
+
+	println!("Code blocks by language:");
+	if stats.languages.is_empty() {
+		println!("  (none)");
+	}
+	let mut languages: Vec<_> = stats.languages.iter().collect();
+	languages.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+	for (language, count) in languages {
+		println!("  {:<16} {}", language, count);
+	}
+}
+
+pub fn format_size(bytes: u64) -> String {
+	const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
+	let mut size = bytes as f64;
+	let mut unit = 0;
+	while size >= 1024.0 && unit < UNITS.len() - 1 {
+		size /= 1024.0;
+		unit += 1;
+	}
+	if unit == 0 {
+		format!("{} {}", bytes, UNITS[0])
+	} else {
+		format!("{:.1} {}", size, UNITS[unit])
+	}
+}
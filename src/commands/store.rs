@@ -0,0 +1,485 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap::{Args, Subcommand};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use octocode::config::Config;
+use octocode::store::compaction::TableCompaction;
+use octocode::store::migration::{self, Migration, TableProgress};
+use octocode::store::tuning::{TableTuning, TuneOptions};
+use octocode::store::vector_optimizer::{IndexBuild, IndexHealth};
+use octocode::store::{preflight, EmbeddingSettings, Store};
+
+use crate::commands::stats::format_size;
+use crate::commands::OutputFormat;
+
+/// Versions `store optimize` keeps by default: a week, as lance does
+const DEFAULT_KEEP_MINUTES: i64 = 7 * 24 * 60;
+
+fn validate_recall(s: &str) -> Result<f32, String> {
+	match s.parse::<f32>() {
+		Ok(recall) if (0.0..=1.0).contains(&recall) => Ok(recall),
+		_ => Err(format!(
+			"Invalid recall '{}'. Use a number between 0.0 and 1.0.",
+			s
+		)),
+	}
+}
+
+#[derive(Args, Debug)]
+pub struct StoreArgs {
+	#[command(subcommand)]
+	pub command: StoreCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum StoreCommand {
+	/// Measure vector index recall at several nprobes/refine settings and keep the fastest one reaching the target
+	Tune {
+		/// Stored vectors used as sample queries per table
+		#[arg(long, default_value_t = 20)]
+		samples: usize,
+
+		/// Neighbours compared per sample query
+		#[arg(long, default_value_t = 10)]
+		top_k: usize,
+
+		/// Smallest acceptable recall (0.0-1.0)
+		#[arg(long, default_value_t = 0.95, value_parser = validate_recall)]
+		target_recall: f32,
+
+		/// Forget the recorded settings and go back to the size-based defaults
+		#[arg(long, conflicts_with_all = ["samples", "top_k", "target_recall"])]
+		reset: bool,
+
+		/// Output format
+		#[arg(long, value_enum, default_value = "cli")]
+		format: OutputFormat,
+	},
+
+	/// Build missing vector indexes and add newly stored rows to existing ones
+	BuildIndex {
+		/// Return only once every index covers all rows of its table
+		#[arg(long)]
+		wait: bool,
+
+		/// Seconds to wait with --wait before failing
+		#[arg(long, default_value_t = 600, requires = "wait", value_parser = clap::value_parser!(u64).range(1..=7200))]
+		timeout: u64,
+
+		/// Output format
+		#[arg(long, value_enum, default_value = "cli")]
+		format: OutputFormat,
+	},
+
+	/// Compact tables, prune old versions and update vector indexes, reclaiming the space of deleted rows
+	Optimize {
+		/// Keep the versions of the last N minutes, for searches running meanwhile
+		/// and for checking out recent versions; 0 prunes every old version
+		#[arg(long, default_value_t = DEFAULT_KEEP_MINUTES)]
+		keep_minutes: i64,
+
+		/// Output format
+		#[arg(long, value_enum, default_value = "cli")]
+		format: OutputFormat,
+	},
+
+	/// Re-embed the index with the configured embedding models while it stays searchable, then switch to it
+	Migrate {
+		/// Run detached, logging to the project's logs directory
+		#[arg(long, conflicts_with_all = ["status", "abort"])]
+		background: bool,
+
+		/// Show the migration in progress
+		#[arg(long, conflicts_with = "abort")]
+		status: bool,
+
+		/// Discard the migration in progress and keep the index as it is
+		#[arg(long)]
+		abort: bool,
+
+		/// Output format
+		#[arg(long, value_enum, default_value = "cli")]
+		format: OutputFormat,
+	},
+}
+
+/// Maintain the vector store of the current project
+pub async fn execute(store: &Store, args: &StoreArgs) -> anyhow::Result<()> {
+	match &args.command {
+		StoreCommand::Migrate { .. } => return migrate(&args.command).await,
+		StoreCommand::Tune {
+			samples,
+			top_k,
+			target_recall,
+			reset,
+			format,
+		} => {
+			if *reset {
+				store.reset_search_tuning().await?;
+				println!("Search tuning cleared; searches use the size-based defaults.");
+				return Ok(());
+			}
+			if *samples == 0 || *top_k == 0 {
+				return Err(anyhow::anyhow!("--samples and --top-k must be at least 1"));
+			}
+
+			let options = TuneOptions {
+				samples: *samples,
+				top_k: *top_k,
+				target_recall: *target_recall,
+			};
+			if !format.is_json() {
+				println!("Measuring recall against exact search...");
+			}
+			let tunings = store.tune_search(&options).await?;
+
+			if format.is_json() {
+				println!("{}", serde_json::to_string_pretty(&tunings)?);
+			} else {
+				print_tunings(&tunings, *target_recall);
+			}
+		}
+		StoreCommand::BuildIndex {
+			wait,
+			timeout,
+			format,
+		} => {
+			if !format.is_json() {
+				println!("Building vector indexes...");
+			}
+			let builds = store
+				.build_indexes(wait.then_some(std::time::Duration::from_secs(*timeout)))
+				.await?;
+
+			if format.is_json() {
+				println!("{}", serde_json::to_string_pretty(&builds)?);
+			} else {
+				print_builds(&builds);
+			}
+		}
+		StoreCommand::Optimize {
+			keep_minutes,
+			format,
+		} => {
+			if *keep_minutes < 0 {
+				return Err(anyhow::anyhow!("--keep-minutes can't be negative"));
+			}
+			if !format.is_json() {
+				println!("Compacting tables...");
+			}
+			let (compactions, builds) = store
+				.optimize(chrono::Duration::minutes(*keep_minutes))
+				.await?;
+
+			if format.is_json() {
+				println!(
+					"{}",
+					serde_json::to_string_pretty(&serde_json::json!({
+						"tables": compactions,
+						"indexes": builds,
+					}))?
+				);
+			} else {
+				print_compactions(&compactions);
+				if !builds.is_empty() {
+					println!();
+					print_builds(&builds);
+				}
+			}
+		}
+	}
+
+	Ok(())
+}
+
+/// Re-embed the index with the configured models. Runs without opening the
+/// store, which refuses tables of another dimension.
+pub async fn migrate(command: &StoreCommand) -> anyhow::Result<()> {
+	let StoreCommand::Migrate {
+		background,
+		status,
+		abort,
+		format,
+	} = command
+	else {
+		return Ok(());
+	};
+	let project_dir = std::env::current_dir()?;
+
+	if *abort {
+		if migration::abort(&project_dir)? {
+			println!("Migration discarded; the index keeps the models it was built with.");
+		} else {
+			println!("No migration in progress.");
+		}
+		return Ok(());
+	}
+
+	// The configured models, not those the index is searched with meanwhile
+	let config = Config::load_layered()?.config;
+	let configured = EmbeddingSettings::from_config(&config);
+	let pending = migration::pending(&project_dir)?;
+
+	if *status {
+		let Some(migration) = pending else {
+			if format.is_json() {
+				println!("null");
+			} else {
+				println!("No migration in progress.");
+			}
+			return Ok(());
+		};
+		let progress = migration::status(&project_dir, &migration).await?;
+		if format.is_json() {
+			println!(
+				"{}",
+				serde_json::to_string_pretty(&serde_json::json!({
+					"migration": migration,
+					"tables": progress,
+				}))?
+			);
+		} else {
+			print_migration(&migration);
+			print_progress(&progress);
+		}
+		return Ok(());
+	}
+
+	let migration = match pending {
+		Some(migration) if migration.to != configured => {
+			return Err(anyhow::anyhow!(
+				"The embedding models were changed again during the migration to {} and {}. \
+				 Run `octocode store migrate --abort`, then start the migration again.",
+				migration.to.code_model,
+				migration.to.text_model
+			));
+		}
+		Some(migration) => migration,
+		None => {
+			let db_path = octocode::storage::get_project_database_path(&project_dir)?;
+			let recorded = preflight::recorded(&db_path).await?.ok_or_else(|| {
+				anyhow::anyhow!(
+					"The index has no record of the embedding models it was built with. \
+					 Run `octocode index --rebuild` to rebuild it with the configured models."
+				)
+			})?;
+			if recorded == configured {
+				println!("The index already uses the configured embedding models.");
+				return Ok(());
+			}
+			migration::start(&project_dir, recorded, configured)?
+		}
+	};
+
+	if *background {
+		return spawn_migration(&project_dir);
+	}
+
+	if !format.is_json() {
+		print_migration(&migration);
+	}
+	let batch_size = config.index.embeddings_batch_size;
+	let max_tokens = config.index.embeddings_max_tokens_per_batch;
+	let progress = migration::run(&project_dir, &migration, batch_size, max_tokens, |table| {
+		if !format.is_json() {
+			print!(
+				"\r  {}: {} of {} rows",
+				table.table, table.migrated, table.rows
+			);
+			let _ = std::io::stdout().flush();
+		}
+	})
+	.await?;
+	if !format.is_json() {
+		println!("\nSwitching to the re-embedded index...");
+	}
+	migration::switch(&project_dir, &migration, batch_size, max_tokens).await?;
+
+	// Searches fall back to exact search until the new vectors are indexed
+	let builds = Store::new().await?.build_indexes(None).await?;
+	if format.is_json() {
+		println!(
+			"{}",
+			serde_json::to_string_pretty(&serde_json::json!({
+				"migration": migration,
+				"tables": progress,
+				"indexes": builds,
+			}))?
+		);
+	} else {
+		print_builds(&builds);
+		println!(
+			"\nThe index now uses {} and {}. The knowledge graph is rebuilt by the next `octocode index`.",
+			migration.to.code_model, migration.to.text_model
+		);
+	}
+	Ok(())
+}
+
+/// Continue the recorded migration in a detached `octocode store migrate`
+fn spawn_migration(project_dir: &std::path::Path) -> anyhow::Result<()> {
+	let log_dir = octocode::storage::ensure_project_storage_exists(project_dir)?.join("logs");
+	std::fs::create_dir_all(&log_dir)?;
+	let log_path = log_dir.join("migration.log");
+	let log_file = std::fs::OpenOptions::new()
+		.create(true)
+		.append(true)
+		.open(&log_path)?;
+
+	let mut command = Command::new(std::env::current_exe()?);
+	command
+		.args(["store", "migrate"])
+		.current_dir(project_dir)
+		.stdin(Stdio::null())
+		.stdout(log_file.try_clone()?)
+		.stderr(log_file);
+	#[cfg(unix)]
+	{
+		use std::os::unix::process::CommandExt;
+		command.process_group(0);
+	}
+	#[cfg(windows)]
+	{
+		use std::os::windows::process::CommandExt;
+		const DETACHED_PROCESS: u32 = 0x0000_0008;
+		const CREATE_NEW_PROCESS_GROUP: u32 = 0x0000_0200;
+		command.creation_flags(DETACHED_PROCESS | CREATE_NEW_PROCESS_GROUP);
+	}
+	let child = command.spawn()?;
+
+	println!("Migrating in the background (PID {})", child.id());
+	println!(
+		"The index is searched with the models it was built with until the migration completes."
+	);
+	println!("Progress: octocode store migrate --status");
+	println!("Log file: {}", log_path.display());
+	Ok(())
+}
+
+fn print_migration(migration: &Migration) {
+	println!(
+		"Migrating the index from {} / {} to {} / {} (started {})",
+		migration.from.code_model,
+		migration.from.text_model,
+		migration.to.code_model,
+		migration.to.text_model,
+		migration.started_at.format("%Y-%m-%d %H:%M UTC")
+	);
+}
+
+fn print_progress(progress: &[TableProgress]) {
+	for table in progress {
+		println!(
+			"  {}: {} of {} rows",
+			table.table, table.migrated, table.rows
+		);
+	}
+}
+
+fn print_tunings(tunings: &[TableTuning], target_recall: f32) {
+	if tunings.is_empty() {
+		println!(
+			"No table has a vector index yet; small tables are searched exactly and need no tuning."
+		);
+		return;
+	}
+
+	for tuning in tunings {
+		let chosen = &tuning.chosen;
+		println!(
+			"{} ({} rows): nprobes={}, refine_factor={}, recall {:.1}%, {:.2} ms/query",
+			tuning.table,
+			tuning.rows,
+			chosen.nprobes,
+			chosen
+				.refine_factor
+				.map_or("none".to_string(), |factor| factor.to_string()),
+			chosen.recall * 100.0,
+			chosen.latency_ms
+		);
+		if chosen.recall < target_recall {
+			println!(
+				"  no setting reached {:.1}% recall; the one with the best recall is used",
+				target_recall * 100.0
+			);
+		}
+	}
+	println!("\nSettings recorded; rerun after the index grows or shrinks by more than half.");
+}
+
+fn print_compactions(compactions: &[TableCompaction]) {
+	for compaction in compactions {
+		println!(
+			"{}: {} fragments merged into {}, {} old versions pruned, {} reclaimed",
+			compaction.table,
+			compaction.fragments_removed,
+			compaction.fragments_added,
+			compaction.versions_pruned,
+			format_size(compaction.bytes_reclaimed)
+		);
+	}
+	let reclaimed: u64 = compactions.iter().map(|c| c.bytes_reclaimed).sum();
+	println!("Reclaimed {} in total.", format_size(reclaimed));
+}
+
+fn print_builds(builds: &[IndexBuild]) {
+	if builds.is_empty() {
+		println!("Nothing is indexed yet; run `octocode index` first.");
+		return;
+	}
+
+	for build in builds {
+		let state = match build.health {
+			IndexHealth::NotNeeded => "searched exactly, too small for an index".to_string(),
+			IndexHealth::Missing => "no index".to_string(),
+			IndexHealth::Partial { indexed, unindexed }
+			| IndexHealth::Ready { indexed, unindexed } => {
+				format!("{} of {} rows indexed", indexed, indexed + unindexed)
+			}
+		};
+		println!(
+			"{} ({} rows): {}, {}",
+			build.table, build.rows, build.action, state
+		);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use clap::Parser;
+
+	#[derive(Parser)]
+	struct Cli {
+		#[command(flatten)]
+		store: StoreArgs,
+	}
+
+	fn keep_minutes(args: &[&str]) -> i64 {
+		let cli = Cli::try_parse_from(["store", "optimize"].iter().chain(args)).unwrap();
+		match cli.store.command {
+			StoreCommand::Optimize { keep_minutes, .. } => keep_minutes,
+			_ => unreachable!("parsed optimize"),
+		}
+	}
+
+	#[test]
+	fn test_optimize_keeps_a_week_by_default() {
+		assert_eq!(keep_minutes(&[]), 7 * 24 * 60);
+		assert_eq!(keep_minutes(&["--keep-minutes", "0"]), 0);
+	}
+}
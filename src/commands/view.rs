@@ -27,15 +27,29 @@ pub struct ViewArgs {
 	/// Output format
 	#[arg(long, value_enum, default_value = "cli")]
 	pub format: OutputFormat,
+
+	/// Only show symbols whose name matches (substring, or glob like `parse_*`)
+	#[arg(long, value_name = "PATTERN")]
+	pub symbol: Option<String>,
+
+	/// Only show symbols overlapping a 1-based line range: `10:40`, `10:`, `:40` or `25`
+	#[arg(long, value_name = "A:B", value_parser = indexer::parse_line_range)]
+	pub lines: Option<(usize, usize)>,
+
+	/// Show full bodies of the shown symbols instead of their first and last lines
+	#[arg(long)]
+	pub expand: bool,
 }
 
 pub async fn execute(args: &ViewArgs) -> Result<(), anyhow::Error> {
+	let filter = indexer::SignatureFilter::new(args.symbol.as_deref(), args.lines)?;
+
 	// Get current directory
 	let current_dir = std::env::current_dir()?;
 
 	// Note: View command doesn't require an index as it parses files directly
 	let index_path = storage::get_project_database_path(&current_dir)?;
-	if !index_path.exists() {
+	if !index_path.exists() && !args.format.is_json() {
 		println!("Note: No index found. The view command works without an index, but you can run 'octocode index' to create one if needed for other commands.");
 	}
 
@@ -93,25 +107,29 @@ pub async fn execute(args: &ViewArgs) -> Result<(), anyhow::Error> {
 	}
 
 	// Extract signatures from matching files
-	let signatures = indexer::extract_file_signatures(&matching_files)?;
+	let signatures = filter.apply(indexer::extract_file_signatures(&matching_files)?);
+	if signatures.is_empty() && !filter.is_empty() && !args.format.is_json() {
+		println!("No symbols match the given filters.");
+		return Ok(());
+	}
 
 	// Display results in the requested format
 	if args.format.is_json() {
 		indexer::render_signatures_json(&signatures)?
 	} else if args.format.is_md() {
 		// Use markdown format
-		let markdown = indexer::signatures_to_markdown(&signatures);
+		let markdown = indexer::signatures_to_markdown(&signatures, args.expand);
 		println!("{}", markdown);
 	} else if args.format.is_text() {
 		// Use minimal text format for token efficiency
-		let text_output = indexer::render_signatures_text(&signatures);
+		let text_output = indexer::render_signatures_text(&signatures, args.expand);
 		println!("{}", text_output);
 	} else if args.format.is_cli() {
 		// CLI format - use the nice format with tab spacing
-		indexer::render_signatures_cli(&signatures);
+		indexer::render_signatures_cli(&signatures, args.expand);
 	} else {
 		// Fallback to CLI format
-		indexer::render_signatures_cli(&signatures);
+		indexer::render_signatures_cli(&signatures, args.expand);
 	}
 
 	Ok(())
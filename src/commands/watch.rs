@@ -13,6 +13,10 @@
 // limitations under the License.
 
 use clap::Args;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
 
 use octocode::config::Config;
 use octocode::indexer;
@@ -24,6 +28,7 @@ use octocode::watcher_config::{
 };
 
 use super::index::IndexArgs;
+use super::OutputFormat;
 
 #[derive(Args, Debug)]
 pub struct WatchArgs {
@@ -42,6 +47,416 @@ pub struct WatchArgs {
 	/// Skip git repository requirement and git-based optimizations
 	#[arg(long)]
 	pub no_git: bool,
+
+	/// Also watch and index this directory although .gitignore or .noindex
+	/// excludes it (repeatable); its files are removed from the index once
+	/// indexing runs without it
+	#[arg(long, value_name = "PATH")]
+	pub include_extra: Vec<PathBuf>,
+
+	/// Run the watcher in the background and write its PID to .octocode/watch.pid
+	#[arg(long, conflicts_with_all = ["stop", "status"])]
+	pub daemon: bool,
+
+	/// Stop the background watcher started with --daemon
+	#[arg(long, conflicts_with = "status")]
+	pub stop: bool,
+
+	/// Show whether a background watcher is running for this directory
+	#[arg(long)]
+	pub status: bool,
+}
+
+impl WatchArgs {
+	/// Whether this invocation manages a background watcher instead of watching
+	pub fn is_daemon_action(&self) -> bool {
+		self.daemon || self.stop || self.status
+	}
+}
+
+/// PID file name inside the project's .octocode directory
+const PID_FILE_NAME: &str = "watch.pid";
+
+/// Handle --daemon, --stop and --status without running a watcher in this process
+pub fn execute_daemon_action(args: &WatchArgs) -> Result<(), anyhow::Error> {
+	let current_dir = std::env::current_dir()?;
+	let pid_path = pid_file_path(&current_dir)?;
+
+	if args.stop {
+		println!("{}", stop_daemon(&pid_path)?);
+		return Ok(());
+	}
+	if args.status {
+		println!("{}", daemon_status(&pid_path));
+		return Ok(());
+	}
+	start_daemon(args, &current_dir, &pid_path)
+}
+
+fn pid_file_path(current_dir: &Path) -> Result<PathBuf, anyhow::Error> {
+	let config_dir = octocode::storage::get_project_config_path(current_dir)?;
+	Ok(config_dir.join(PID_FILE_NAME))
+}
+
+/// Background watcher recorded in the PID file: its PID on the first line and
+/// the file name of its executable on the second, so a recycled PID is never
+/// mistaken for the watcher
+#[derive(Debug, PartialEq)]
+struct DaemonRecord {
+	pid: u32,
+	exe: Option<String>,
+}
+
+impl DaemonRecord {
+	fn read(pid_path: &Path) -> Option<Self> {
+		let content = std::fs::read_to_string(pid_path).ok()?;
+		let mut lines = content.lines();
+		let pid = lines.next()?.trim().parse().ok()?;
+		let exe = lines
+			.next()
+			.map(str::trim)
+			.filter(|exe| !exe.is_empty())
+			.map(String::from);
+		Some(Self { pid, exe })
+	}
+
+	fn write(&self, pid_path: &Path) -> Result<(), anyhow::Error> {
+		let exe = self.exe.as_deref().unwrap_or_default();
+		std::fs::write(pid_path, format!("{}\n{}\n", self.pid, exe))?;
+		Ok(())
+	}
+
+	/// Whether the PID still belongs to the executable that wrote the record;
+	/// records from before the executable was stored are never trusted
+	fn is_running(&self) -> bool {
+		let Some(exe) = &self.exe else {
+			return false;
+		};
+		process_image(self.pid).is_some_and(|image| same_image(exe, &image))
+	}
+}
+
+/// File name of the running executable, as recorded in the PID file
+fn current_exe_name() -> Result<String, anyhow::Error> {
+	let exe = std::env::current_exe()?;
+	exe.file_name()
+		.map(|name| name.to_string_lossy().to_string())
+		.ok_or_else(|| anyhow::anyhow!("Can't determine the executable name"))
+}
+
+/// Compare a recorded executable name with the image name the OS reports;
+/// Linux truncates process names to 15 bytes
+fn same_image(exe: &str, image: &str) -> bool {
+	const LINUX_COMM_LEN: usize = 15;
+	exe.eq_ignore_ascii_case(image) || (image.len() == LINUX_COMM_LEN && exe.starts_with(image))
+}
+
+fn start_daemon(
+	args: &WatchArgs,
+	current_dir: &Path,
+	pid_path: &Path,
+) -> Result<(), anyhow::Error> {
+	if let Some(record) = DaemonRecord::read(pid_path) {
+		if record.is_running() {
+			return Err(anyhow::anyhow!(
+				"Watcher is already running (PID {}). Use 'octocode watch --stop' first.",
+				record.pid
+			));
+		}
+	}
+
+	// Daemon output goes to project storage so it never triggers the watcher itself
+	let log_dir = octocode::storage::ensure_project_storage_exists(current_dir)?.join("logs");
+	std::fs::create_dir_all(&log_dir)?;
+	let log_path = log_dir.join("watch_daemon.log");
+	let log_file = std::fs::OpenOptions::new()
+		.create(true)
+		.append(true)
+		.open(&log_path)?;
+
+	// Re-launch ourselves in quiet foreground mode as a detached child process
+	let mut command = Command::new(std::env::current_exe()?);
+	command.arg("watch").arg("--quiet");
+	if let Some(debounce) = args.debounce {
+		command.arg("--debounce").arg(debounce.to_string());
+	}
+	if let Some(additional_delay) = args.additional_delay {
+		command
+			.arg("--additional-delay")
+			.arg(additional_delay.to_string());
+	}
+	if args.no_git {
+		command.arg("--no-git");
+	}
+	for path in &args.include_extra {
+		command.arg("--include-extra").arg(current_dir.join(path));
+	}
+	command
+		.current_dir(current_dir)
+		.stdin(Stdio::null())
+		.stdout(log_file.try_clone()?)
+		.stderr(log_file);
+
+	// Detach from the terminal's process group so Ctrl+C in the shell doesn't reach it
+	#[cfg(unix)]
+	{
+		use std::os::unix::process::CommandExt;
+		command.process_group(0);
+	}
+	#[cfg(windows)]
+	{
+		use std::os::windows::process::CommandExt;
+		const DETACHED_PROCESS: u32 = 0x0000_0008;
+		const CREATE_NEW_PROCESS_GROUP: u32 = 0x0000_0200;
+		command.creation_flags(DETACHED_PROCESS | CREATE_NEW_PROCESS_GROUP);
+	}
+
+	let child = command.spawn()?;
+	let pid = child.id();
+
+	if let Some(parent) = pid_path.parent() {
+		std::fs::create_dir_all(parent)?;
+	}
+	DaemonRecord {
+		pid,
+		exe: Some(current_exe_name()?),
+	}
+	.write(pid_path)?;
+
+	println!("Started background watcher (PID {})", pid);
+	println!("PID file: {}", pid_path.display());
+	println!("Log file: {}", log_path.display());
+	Ok(())
+}
+
+fn stop_daemon(pid_path: &Path) -> Result<String, anyhow::Error> {
+	let Some(record) = DaemonRecord::read(pid_path) else {
+		return Ok("No background watcher is running for this directory.".to_string());
+	};
+
+	let message = if record.is_running() {
+		terminate_process(record.pid)?;
+		format!("Stopped background watcher (PID {})", record.pid)
+	} else {
+		format!(
+			"Background watcher (PID {}) was not running; removing stale PID file",
+			record.pid
+		)
+	};
+
+	std::fs::remove_file(pid_path)?;
+	Ok(message)
+}
+
+fn daemon_status(pid_path: &Path) -> String {
+	match DaemonRecord::read(pid_path) {
+		Some(record) if record.is_running() => {
+			format!("Background watcher is running (PID {})", record.pid)
+		}
+		Some(record) => format!(
+			"Background watcher is not running (stale PID file for PID {})",
+			record.pid
+		),
+		None => "Background watcher is not running".to_string(),
+	}
+}
+
+/// Executable name of a running process, or `None` if there is no such process
+#[cfg(unix)]
+fn process_image(pid: u32) -> Option<String> {
+	let output = Command::new("ps")
+		.args(["-p", &pid.to_string(), "-o", "comm="])
+		.stderr(Stdio::null())
+		.output()
+		.ok()?;
+	let comm = String::from_utf8_lossy(&output.stdout).trim().to_string();
+	if !output.status.success() || comm.is_empty() {
+		return None;
+	}
+	// macOS reports the full path
+	Path::new(&comm)
+		.file_name()
+		.map(|name| name.to_string_lossy().to_string())
+}
+
+#[cfg(windows)]
+fn process_image(pid: u32) -> Option<String> {
+	let output = Command::new("tasklist")
+		.args(["/FI", &format!("PID eq {}", pid), "/FO", "CSV", "/NH"])
+		.output()
+		.ok()?;
+	parse_tasklist_csv(&String::from_utf8_lossy(&output.stdout), pid)
+}
+
+/// Image name of the row whose PID field is exactly `pid` in `tasklist /FO CSV`
+/// output; a filter without matches prints an informational line instead
+#[cfg(any(windows, test))]
+fn parse_tasklist_csv(output: &str, pid: u32) -> Option<String> {
+	let pid = pid.to_string();
+	output.lines().find_map(|line| {
+		let fields: Vec<&str> = line
+			.trim()
+			.strip_prefix('"')?
+			.strip_suffix('"')?
+			.split("\",\"")
+			.collect();
+		(fields.len() >= 2 && fields[1] == pid).then(|| fields[0].to_string())
+	})
+}
+
+#[cfg(unix)]
+fn terminate_process(pid: u32) -> Result<(), anyhow::Error> {
+	let status = Command::new("kill").arg(pid.to_string()).status()?;
+	if !status.success() {
+		return Err(anyhow::anyhow!("Failed to stop watcher process {}", pid));
+	}
+	Ok(())
+}
+
+#[cfg(windows)]
+fn terminate_process(pid: u32) -> Result<(), anyhow::Error> {
+	let status = Command::new("taskkill")
+		.args(["/PID", &pid.to_string(), "/F"])
+		.status()?;
+	if !status.success() {
+		return Err(anyhow::anyhow!("Failed to stop watcher process {}", pid));
+	}
+	Ok(())
+}
+
+/// Arguments for the plain incremental index runs the watcher performs
+fn index_args(args: &WatchArgs, extra_dirs: &[String]) -> IndexArgs {
+	IndexArgs {
+		no_git: args.no_git,
+		list_files: false,
+		show_file: None,
+		graphrag: None,
+		deterministic: false,
+		dry_run: false,
+		format: OutputFormat::Cli,
+		rebuild: false,
+		extra_dirs: extra_dirs.to_vec(),
+	}
+}
+
+/// Seconds an `on_index_complete` hook may take before it is given up on
+const HOOK_TIMEOUT_SECS: u64 = 30;
+
+/// JSON payload handed to the `watch.on_index_complete` hook
+#[derive(Debug, Serialize)]
+struct IndexCompletePayload {
+	event: &'static str,
+	directory: String,
+	/// Paths relative to `directory`, sorted
+	changed_files: Vec<String>,
+	duration_ms: u64,
+	completed_at: String,
+}
+
+/// Run the `watch.on_index_complete` hook in the background
+fn notify_index_complete(hook: &str, current_dir: &Path, payload: &IndexCompletePayload) {
+	let body = match serde_json::to_string(payload) {
+		Ok(body) => body,
+		Err(e) => {
+			eprintln!("on_index_complete hook failed: {}", e);
+			return;
+		}
+	};
+	let hook = hook.to_string();
+	let current_dir = current_dir.to_path_buf();
+	let env = hook_env(payload);
+
+	tokio::spawn(async move {
+		let timeout = std::time::Duration::from_secs(HOOK_TIMEOUT_SECS);
+		let result = if hook.starts_with("http://") || hook.starts_with("https://") {
+			post_webhook(&hook, body, timeout).await
+		} else {
+			run_hook_command(&hook, &current_dir, body, &env, timeout).await
+		};
+		if let Err(e) = result {
+			eprintln!("on_index_complete hook failed: {}", e);
+		}
+	});
+}
+
+/// Environment variables a hook command gets besides the payload on stdin
+fn hook_env(payload: &IndexCompletePayload) -> Vec<(&'static str, String)> {
+	vec![
+		("OCTOCODE_EVENT", payload.event.to_string()),
+		("OCTOCODE_DIRECTORY", payload.directory.clone()),
+		(
+			"OCTOCODE_CHANGED_FILES",
+			payload.changed_files.len().to_string(),
+		),
+	]
+}
+
+async fn post_webhook(
+	url: &str,
+	body: String,
+	timeout: std::time::Duration,
+) -> Result<(), anyhow::Error> {
+	let response = reqwest::Client::new()
+		.post(url)
+		.header("Content-Type", "application/json")
+		.body(body)
+		.timeout(timeout)
+		.send()
+		.await?;
+	if !response.status().is_success() {
+		return Err(anyhow::anyhow!("{} returned {}", url, response.status()));
+	}
+	Ok(())
+}
+
+async fn run_hook_command(
+	command: &str,
+	current_dir: &Path,
+	body: String,
+	env: &[(&str, String)],
+	timeout: std::time::Duration,
+) -> Result<(), anyhow::Error> {
+	use tokio::io::AsyncWriteExt;
+
+	#[cfg(unix)]
+	let mut hook = tokio::process::Command::new("sh");
+	#[cfg(unix)]
+	hook.arg("-c");
+	#[cfg(windows)]
+	let mut hook = tokio::process::Command::new("cmd");
+	#[cfg(windows)]
+	hook.arg("/C");
+
+	let mut child = hook
+		.arg(command)
+		.current_dir(current_dir)
+		.envs(env.iter().cloned())
+		.stdin(Stdio::piped())
+		.kill_on_drop(true)
+		.spawn()?;
+	let stdin = child.stdin.take();
+	let run = async {
+		if let Some(mut stdin) = stdin {
+			// A hook that ignores its input may exit before reading it
+			let _ = stdin.write_all(body.as_bytes()).await;
+		}
+		child.wait().await
+	};
+	let status = match tokio::time::timeout(timeout, run).await {
+		Ok(status) => status?,
+		Err(_) => {
+			let _ = child.kill().await;
+			return Err(anyhow::anyhow!(
+				"`{}` did not finish within {}s and was stopped",
+				command,
+				timeout.as_secs_f32()
+			));
+		}
+	};
+	if !status.success() {
+		return Err(anyhow::anyhow!("`{}` exited with {}", command, status));
+	}
+	Ok(())
 }
 
 pub async fn execute(
@@ -50,6 +465,7 @@ pub async fn execute(
 	args: &WatchArgs,
 ) -> Result<(), anyhow::Error> {
 	let current_dir = std::env::current_dir()?;
+	let extra_dirs = indexer::extra_dirs::resolve(&current_dir, &args.include_extra)?;
 
 	// Get the debounce time from args or use default, with bounds checking
 	let debounce_secs = args
@@ -71,27 +487,24 @@ pub async fn execute(
 			"Configuration: debounce={}s, additional_delay={}ms",
 			debounce_secs, additional_delay_ms
 		);
+		if !extra_dirs.is_empty() {
+			println!("Including ignored directories: {}", extra_dirs.join(", "));
+		}
 		println!("Initial indexing...");
 	}
 
 	// Do initial indexing
 	if !args.quiet {
 		// If not in quiet mode, use the regular indexing with progress display
-		super::index::execute(
-			store,
-			config,
-			&IndexArgs {
-				no_git: args.no_git,
-				list_files: false,
-				show_file: None,
-				graphrag: None,
-			},
-		)
-		.await?
+		super::index::execute(store, config, &index_args(args, &extra_dirs)).await?
 	} else {
 		// In quiet mode, just do the indexing without progress display
 		let state = state::create_shared_state();
-		state.write().current_directory = current_dir.clone();
+		{
+			let mut state_guard = state.write();
+			state_guard.current_directory = current_dir.clone();
+			state_guard.extra_dirs = extra_dirs.clone();
+		}
 
 		// Get git root for optimization
 		let git_repo_root = if !args.no_git {
@@ -120,7 +533,8 @@ pub async fn execute(
 	let quiet_mode = args.quiet;
 
 	// Create ignore patterns manager
-	let ignore_patterns = IgnorePatterns::new(current_dir.clone());
+	let ignore_patterns =
+		IgnorePatterns::new(current_dir.clone()).with_extra_dirs(extra_dirs.clone());
 
 	// Create a debounced watcher to call our tx sender when files change
 	let mut debouncer = new_debouncer(
@@ -129,13 +543,14 @@ pub async fn execute(
 			match res {
 				Ok(events) => {
 					// Filter out events from irrelevant paths using ignore patterns
-					let relevant_events = events
-						.iter()
+					let relevant_paths: Vec<PathBuf> = events
+						.into_iter()
 						.filter(|event| !ignore_patterns.should_ignore_path(&event.path))
-						.count();
+						.map(|event| event.path)
+						.collect();
 
-					if relevant_events > 0 {
-						let _ = tx.send(());
+					if !relevant_paths.is_empty() {
+						let _ = tx.send(relevant_paths);
 					}
 				}
 				Err(e) => {
@@ -154,7 +569,11 @@ pub async fn execute(
 
 	// Create shared state for reindexing
 	let state = state::create_shared_state();
-	state.write().current_directory = current_dir;
+	{
+		let mut state_guard = state.write();
+		state_guard.current_directory = current_dir.clone();
+		state_guard.extra_dirs = extra_dirs.clone();
+	}
 
 	// Keep a copy of the config for reindexing
 	let config = config.clone();
@@ -162,16 +581,22 @@ pub async fn execute(
 	loop {
 		// Wait for changes
 		match rx.recv() {
-			Ok(()) => {
+			Ok(paths) => {
 				if !args.quiet {
 					println!("\nDetected file changes, reindexing...");
 				}
+				let started = std::time::Instant::now();
+				let mut changed_files: BTreeSet<PathBuf> = paths.into_iter().collect();
 
 				// Reset the indexing state
 				{
 					let mut state_guard = state.write();
 					state_guard.indexed_files = 0;
 					state_guard.indexing_complete = false;
+					state_guard.secret_findings.clear();
+					state_guard.secret_skipped_blocks = 0;
+					state_guard.policy_violations.clear();
+					state_guard.file_policy_skips = Default::default();
 				}
 
 				// Additional delay to ensure all file operations are complete
@@ -180,19 +605,14 @@ pub async fn execute(
 						.await;
 				}
 
+				// Changes reported meanwhile are picked up by this reindex
+				while let Ok(paths) = rx.try_recv() {
+					changed_files.extend(paths);
+				}
+
 				if !args.quiet {
 					// Use regular indexing with progress in non-quiet mode
-					super::index::execute(
-						store,
-						&config,
-						&IndexArgs {
-							no_git: args.no_git,
-							list_files: false,
-							show_file: None,
-							graphrag: None,
-						},
-					)
-					.await?
+					super::index::execute(store, &config, &index_args(args, &extra_dirs)).await?
 				} else {
 					// In quiet mode, just do the indexing without progress display
 					let git_repo_root = if !args.no_git {
@@ -203,6 +623,25 @@ pub async fn execute(
 					indexer::index_files(store, state.clone(), &config, git_repo_root.as_deref())
 						.await?;
 				}
+
+				if let Some(hook) = &config.watch.on_index_complete {
+					let payload = IndexCompletePayload {
+						event: "index_complete",
+						directory: current_dir.to_string_lossy().to_string(),
+						changed_files: changed_files
+							.iter()
+							.map(|path| {
+								path.strip_prefix(&current_dir)
+									.unwrap_or(path)
+									.to_string_lossy()
+									.to_string()
+							})
+							.collect(),
+						duration_ms: started.elapsed().as_millis() as u64,
+						completed_at: chrono::Utc::now().to_rfc3339(),
+					};
+					notify_index_complete(hook, &current_dir, &payload);
+				}
 			}
 			Err(e) => {
 				if !args.quiet {
@@ -215,3 +654,206 @@ pub async fn execute(
 
 	Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn pid_path(name: &str) -> PathBuf {
+		let dir =
+			std::env::temp_dir().join(format!("octocode_watch_{}_{}", name, std::process::id()));
+		std::fs::create_dir_all(&dir).unwrap();
+		dir.join(PID_FILE_NAME)
+	}
+
+	fn record(pid_path: &Path, pid: u32, exe: Option<&str>) {
+		DaemonRecord {
+			pid,
+			exe: exe.map(String::from),
+		}
+		.write(pid_path)
+		.unwrap();
+	}
+
+	#[test]
+	fn test_missing_pid_file() {
+		let pid_path = pid_path("missing");
+		assert_eq!(
+			daemon_status(&pid_path),
+			"Background watcher is not running"
+		);
+		assert_eq!(
+			stop_daemon(&pid_path).unwrap(),
+			"No background watcher is running for this directory."
+		);
+	}
+
+	#[test]
+	fn test_stale_pid_file() {
+		let pid_path = pid_path("stale");
+		let exe = current_exe_name().unwrap();
+
+		// A watcher that has exited
+		let mut child = Command::new(std::env::current_exe().unwrap())
+			.arg("--list")
+			.stdout(Stdio::null())
+			.spawn()
+			.unwrap();
+		let exited = child.id();
+		child.wait().unwrap();
+
+		// This process is alive, but isn't what the record names; stopping
+		// it would end the test run
+		let recycled = std::process::id();
+
+		for (pid, exe) in [
+			(exited, Some(exe.as_str())),
+			(recycled, Some("octocode-watch-other")),
+			(recycled, None),
+		] {
+			record(&pid_path, pid, exe);
+			assert_eq!(
+				daemon_status(&pid_path),
+				format!(
+					"Background watcher is not running (stale PID file for PID {})",
+					pid
+				)
+			);
+			assert_eq!(
+				stop_daemon(&pid_path).unwrap(),
+				format!(
+					"Background watcher (PID {}) was not running; removing stale PID file",
+					pid
+				)
+			);
+			assert!(!pid_path.exists());
+		}
+	}
+
+	#[test]
+	fn test_status_of_running_watcher() {
+		let pid_path = pid_path("running");
+		record(
+			&pid_path,
+			std::process::id(),
+			Some(&current_exe_name().unwrap()),
+		);
+		assert_eq!(
+			daemon_status(&pid_path),
+			format!("Background watcher is running (PID {})", std::process::id())
+		);
+		assert_eq!(
+			DaemonRecord::read(&pid_path),
+			Some(DaemonRecord {
+				pid: std::process::id(),
+				exe: Some(current_exe_name().unwrap()),
+			})
+		);
+		std::fs::remove_file(&pid_path).unwrap();
+	}
+
+	#[test]
+	fn test_parse_tasklist_csv() {
+		let output = "\"octocode.exe\",\"1234\",\"Console\",\"1\",\"10,000 K\"\r\n\
+			\"other.exe\",\"12345\",\"Console\",\"1\",\"2,000 K\"\r\n";
+		assert_eq!(
+			parse_tasklist_csv(output, 1234).as_deref(),
+			Some("octocode.exe")
+		);
+		assert_eq!(
+			parse_tasklist_csv(output, 12345).as_deref(),
+			Some("other.exe")
+		);
+		assert_eq!(parse_tasklist_csv(output, 123), None);
+		assert_eq!(
+			parse_tasklist_csv(
+				"INFO: No tasks are running which match the specified criteria.\r\n",
+				1234
+			),
+			None
+		);
+	}
+
+	fn payload(directory: &Path) -> IndexCompletePayload {
+		IndexCompletePayload {
+			event: "index_complete",
+			directory: directory.to_string_lossy().to_string(),
+			changed_files: vec!["src/lib.rs".to_string(), "src/main.rs".to_string()],
+			duration_ms: 42,
+			completed_at: "2025-06-01T12:00:00Z".to_string(),
+		}
+	}
+
+	async fn run_hook(
+		command: &str,
+		directory: &Path,
+		timeout_ms: u64,
+	) -> Result<(), anyhow::Error> {
+		let payload = payload(directory);
+		run_hook_command(
+			command,
+			directory,
+			serde_json::to_string(&payload).unwrap(),
+			&hook_env(&payload),
+			std::time::Duration::from_millis(timeout_ms),
+		)
+		.await
+	}
+
+	#[cfg(unix)]
+	#[tokio::test]
+	async fn test_hook_command_gets_payload_and_environment() {
+		let directory = pid_path("hook_ok").parent().unwrap().to_path_buf();
+		run_hook(
+			"cat > payload.json && echo \"$OCTOCODE_EVENT $OCTOCODE_CHANGED_FILES $OCTOCODE_DIRECTORY\" > env.txt",
+			&directory,
+			10_000,
+		)
+		.await
+		.unwrap();
+
+		let payload: serde_json::Value =
+			serde_json::from_str(&std::fs::read_to_string(directory.join("payload.json")).unwrap())
+				.unwrap();
+		assert_eq!(payload["event"], "index_complete");
+		assert_eq!(payload["changed_files"][1], "src/main.rs");
+		assert_eq!(payload["duration_ms"], 42);
+		assert_eq!(
+			std::fs::read_to_string(directory.join("env.txt")).unwrap(),
+			format!("index_complete 2 {}\n", directory.display())
+		);
+		let _ = std::fs::remove_dir_all(&directory);
+	}
+
+	#[cfg(unix)]
+	#[tokio::test]
+	async fn test_hook_command_failure_and_timeout() {
+		let directory = pid_path("hook_fail").parent().unwrap().to_path_buf();
+
+		let err = run_hook("exit 3", &directory, 10_000).await.unwrap_err();
+		assert!(err.to_string().contains("exited with"), "{}", err);
+		assert!(err.to_string().contains('3'), "{}", err);
+
+		// A hook that ignores its input still succeeds
+		run_hook("true", &directory, 10_000).await.unwrap();
+
+		let started = std::time::Instant::now();
+		let err = run_hook("sleep 30", &directory, 200).await.unwrap_err();
+		assert!(
+			err.to_string().contains("did not finish within 0.2s"),
+			"{}",
+			err
+		);
+		assert!(started.elapsed() < std::time::Duration::from_secs(10));
+		let _ = std::fs::remove_dir_all(&directory);
+	}
+
+	#[test]
+	fn test_same_image() {
+		assert!(same_image("octocode", "octocode"));
+		assert!(same_image("octocode.exe", "OCTOCODE.EXE"));
+		assert!(same_image("octocode-4d2f19ab77c1e0f3", "octocode-4d2f19"));
+		assert!(!same_image("octocode", "bash"));
+		assert!(!same_image("octocode", "octo"));
+	}
+}
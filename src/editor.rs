@@ -0,0 +1,162 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Opening search hits in the user's editor
+//!
+//! The last search of a project is remembered, so `octocode open` can jump to
+//! one of its hits without searching again.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::indexer::path_aliases::PathAliases;
+use crate::storage;
+
+const LAST_RESULTS_FILE: &str = "last_search.json";
+
+/// Search hit to jump to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Location {
+	/// Path relative to the project root
+	pub path: String,
+	/// 1-indexed line
+	pub line: usize,
+	/// Short description shown when picking a hit (symbol, heading, ...)
+	#[serde(default, skip_serializing_if = "String::is_empty")]
+	pub label: String,
+}
+
+impl std::fmt::Display for Location {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}:{}", self.path, self.line)?;
+		if !self.label.is_empty() {
+			write!(f, "  {}", self.label)?;
+		}
+		Ok(())
+	}
+}
+
+/// Remember the hits of the latest search in a project
+pub fn save_last_results(project_path: &Path, locations: &[Location]) -> Result<()> {
+	let path = last_results_path(project_path)?;
+	std::fs::write(path, serde_json::to_string(locations)?)?;
+	Ok(())
+}
+
+/// Hits of the latest search in a project
+pub fn load_last_results(project_path: &Path) -> Result<Vec<Location>> {
+	let path = last_results_path(project_path)?;
+	if !path.exists() {
+		return Err(anyhow::anyhow!(
+			"No search results to open yet. Run 'octocode search' first."
+		));
+	}
+	Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+}
+
+fn last_results_path(project_path: &Path) -> Result<PathBuf> {
+	Ok(storage::ensure_project_storage_exists(project_path)?.join(LAST_RESULTS_FILE))
+}
+
+/// Open a hit in `$VISUAL`, `$EDITOR` or, when neither is set, VS Code
+pub fn open_location(project_path: &Path, location: &Location) -> Result<()> {
+	let editor = std::env::var("VISUAL")
+		.or_else(|_| std::env::var("EDITOR"))
+		.ok()
+		.filter(|editor| !editor.trim().is_empty())
+		.unwrap_or_else(|| "code".to_string());
+
+	// The hit may point at a file renamed since the search
+	let aliases = PathAliases::load(project_path);
+	let path = aliases.resolve_missing(&location.path, |path| project_path.join(path).exists());
+	let file = project_path.join(path);
+	let command = editor_command(&editor, &file.to_string_lossy(), location.line);
+	let status = Command::new(&command[0])
+		.args(&command[1..])
+		.status()
+		.map_err(|e| {
+			anyhow::anyhow!(
+				"Failed to start editor '{}': {}. Set $EDITOR to your editor command.",
+				command[0],
+				e
+			)
+		})?;
+
+	if !status.success() {
+		return Err(anyhow::anyhow!(
+			"Editor '{}' exited with {}",
+			command[0],
+			status
+		));
+	}
+	Ok(())
+}
+
+/// Command line opening `path` at `line` with the given editor command.
+/// Editors are told about the line the way each one expects it.
+pub fn editor_command(editor: &str, path: &str, line: usize) -> Vec<String> {
+	let mut command: Vec<String> = editor.split_whitespace().map(String::from).collect();
+	let program = command
+		.first()
+		.and_then(|program| Path::new(program).file_stem())
+		.map(|stem| stem.to_string_lossy().to_lowercase())
+		.unwrap_or_default();
+
+	match program.as_str() {
+		"code" | "code-insiders" | "codium" | "cursor" | "windsurf" => {
+			command.push("-g".to_string());
+			command.push(format!("{}:{}", path, line));
+		}
+		"subl" | "zed" | "hx" | "helix" => command.push(format!("{}:{}", path, line)),
+		"idea" | "pycharm" | "webstorm" | "goland" | "clion" | "rustrover" | "phpstorm" => {
+			command.push("--line".to_string());
+			command.push(line.to_string());
+			command.push(path.to_string());
+		}
+		// vi, vim, nvim, nano, emacs, micro, kak and most terminal editors
+		_ => {
+			command.push(format!("+{}", line));
+			command.push(path.to_string());
+		}
+	}
+	command
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_editor_command_line_conventions() {
+		assert_eq!(
+			editor_command("code", "src/main.rs", 42),
+			vec!["code", "-g", "src/main.rs:42"]
+		);
+		assert_eq!(
+			editor_command("/usr/local/bin/code --wait", "a.rs", 3),
+			vec!["/usr/local/bin/code", "--wait", "-g", "a.rs:3"]
+		);
+		assert_eq!(
+			editor_command("nvim", "a.rs", 7),
+			vec!["nvim", "+7", "a.rs"]
+		);
+		assert_eq!(editor_command("subl", "a.rs", 7), vec!["subl", "a.rs:7"]);
+		assert_eq!(
+			editor_command("idea", "a.rs", 7),
+			vec!["idea", "--line", "7", "a.rs"]
+		);
+	}
+}
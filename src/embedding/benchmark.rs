@@ -0,0 +1,382 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Benchmarking embedding models on a sample of indexed blocks
+//!
+//! Each model embeds the same blocks, batched as indexing would batch them,
+//! which gives its latency and throughput. A retrieval self-test then turns
+//! the first line of each block (a signature, mostly) into a query of plain
+//! words, e.g. "load layered config" for `fn load_layered() -> LayeredConfig`,
+//! and checks how high the block ranks for it among the sample. Costs use
+//! list prices per million tokens, counted with the cl100k tokenizer.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::time::Instant;
+
+use super::types::{parse_provider_model, EmbeddingProviderType, InputType};
+use super::{
+	count_tokens, create_embedding_provider_from_parts, split_texts_into_token_limited_batches,
+};
+
+/// Words of a signature that say nothing about what the code does
+const KEYWORDS: &[&str] = &[
+	"fn",
+	"pub",
+	"crate",
+	"async",
+	"def",
+	"func",
+	"function",
+	"class",
+	"struct",
+	"enum",
+	"impl",
+	"trait",
+	"interface",
+	"let",
+	"const",
+	"var",
+	"static",
+	"return",
+	"self",
+	"this",
+	"public",
+	"private",
+	"protected",
+	"void",
+	"export",
+	"mut",
+	"where",
+	"new",
+];
+
+/// Words kept in a self-test query
+const MAX_QUERY_WORDS: usize = 8;
+
+/// List prices in USD per million input tokens; local models cost nothing
+const PRICES: &[(&str, f64)] = &[
+	("openai:text-embedding-3-small", 0.02),
+	("openai:text-embedding-3-large", 0.13),
+	("openai:text-embedding-ada-002", 0.10),
+	("voyage:voyage-code-3", 0.18),
+	("voyage:voyage-code-2", 0.12),
+	("voyage:voyage-3-large", 0.18),
+	("voyage:voyage-3.5", 0.06),
+	("voyage:voyage-3.5-lite", 0.02),
+	("voyage:voyage-3", 0.06),
+	("voyage:voyage-3-lite", 0.02),
+	("jina:jina-embeddings-v3", 0.02),
+	("jina:jina-embeddings-v2-base-code", 0.02),
+	("mistral:mistral-embed", 0.10),
+	("mistral:codestral-embed", 0.15),
+	("google:gemini-embedding-001", 0.15),
+];
+
+/// Results of one model
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelBenchmark {
+	/// "provider:model"
+	pub model: String,
+	/// What the configuration uses the model for: "code", "text" or "candidate"
+	pub roles: Vec<String>,
+	pub dimension: usize,
+	pub blocks: usize,
+	pub tokens: usize,
+	pub batches: usize,
+	pub total_ms: u64,
+	/// Median and slowest-5% batch latency
+	pub p50_batch_ms: u64,
+	pub p95_batch_ms: u64,
+	pub blocks_per_second: f64,
+	pub tokens_per_second: f64,
+	/// `None` for models without a known price
+	pub usd_per_million_tokens: Option<f64>,
+	/// Cost of embedding every code block of the index once
+	pub estimated_index_cost_usd: Option<f64>,
+	pub quality: RetrievalQuality,
+}
+
+/// How well self-test queries find the block they were made from
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RetrievalQuality {
+	pub queries: usize,
+	/// Share of queries ranking their block first
+	pub recall_at_1: f64,
+	/// Share of queries ranking their block among the first five
+	pub recall_at_5: f64,
+	/// Mean reciprocal rank of the block
+	pub mrr: f64,
+}
+
+/// Sample blocks and the self-test queries made from them
+pub struct BenchmarkSample {
+	pub blocks: Vec<String>,
+	/// Query and the index of the block it should find
+	pub queries: Vec<(String, usize)>,
+	/// Tokens of every code block in the index, for the cost estimate
+	pub index_tokens: usize,
+}
+
+impl BenchmarkSample {
+	pub fn new(blocks: Vec<String>, index_tokens: usize) -> Self {
+		let queries = blocks
+			.iter()
+			.enumerate()
+			.filter_map(|(idx, block)| self_test_query(block).map(|query| (query, idx)))
+			.collect();
+		Self {
+			blocks,
+			queries,
+			index_tokens,
+		}
+	}
+}
+
+/// Price of a model in USD per million tokens, if known
+pub fn price_per_million_tokens(model: &str) -> Option<f64> {
+	let (provider, name) = parse_provider_model(model);
+	match provider {
+		EmbeddingProviderType::FastEmbed | EmbeddingProviderType::HuggingFace => Some(0.0),
+		_ => PRICES
+			.iter()
+			.find(|(priced, _)| {
+				priced
+					.split_once(':')
+					.is_some_and(|(_, priced)| priced.eq_ignore_ascii_case(&name))
+					&& parse_provider_model(priced).0 == provider
+			})
+			.map(|(_, price)| *price),
+	}
+}
+
+/// Embed the sample with `model` and run the self-test
+pub async fn run(
+	model: &str,
+	roles: Vec<String>,
+	sample: &BenchmarkSample,
+	batch_size: usize,
+	max_tokens_per_batch: usize,
+) -> Result<ModelBenchmark> {
+	let (provider, name) = parse_provider_model(model);
+	let provider_impl = create_embedding_provider_from_parts(&provider, &name)?;
+
+	let tokens: usize = sample.blocks.iter().map(|block| count_tokens(block)).sum();
+	let batches = split_texts_into_token_limited_batches(
+		sample.blocks.clone(),
+		batch_size.max(1),
+		max_tokens_per_batch,
+	);
+	let batch_count = batches.len();
+
+	let started = Instant::now();
+	let mut latencies = Vec::with_capacity(batch_count);
+	let mut embeddings = Vec::with_capacity(sample.blocks.len());
+	for batch in batches {
+		let batch_started = Instant::now();
+		embeddings.extend(
+			provider_impl
+				.generate_embeddings_batch(batch, InputType::Document)
+				.await?,
+		);
+		latencies.push(batch_started.elapsed().as_millis() as u64);
+	}
+	let total = started.elapsed();
+
+	let queries: Vec<String> = sample
+		.queries
+		.iter()
+		.map(|(query, _)| query.clone())
+		.collect();
+	let mut query_embeddings = Vec::with_capacity(queries.len());
+	for batch in
+		split_texts_into_token_limited_batches(queries, batch_size.max(1), max_tokens_per_batch)
+	{
+		query_embeddings.extend(
+			provider_impl
+				.generate_embeddings_batch(batch, InputType::Query)
+				.await?,
+		);
+	}
+	let expected: Vec<usize> = sample.queries.iter().map(|(_, idx)| *idx).collect();
+	let quality = retrieval_quality(&query_embeddings, &embeddings, &expected);
+
+	let seconds = total.as_secs_f64().max(f64::EPSILON);
+	let price = price_per_million_tokens(model);
+	latencies.sort_unstable();
+	Ok(ModelBenchmark {
+		model: model.to_string(),
+		roles,
+		dimension: provider_impl.get_dimension(),
+		blocks: sample.blocks.len(),
+		tokens,
+		batches: batch_count,
+		total_ms: total.as_millis() as u64,
+		p50_batch_ms: percentile(&latencies, 0.5),
+		p95_batch_ms: percentile(&latencies, 0.95),
+		blocks_per_second: sample.blocks.len() as f64 / seconds,
+		tokens_per_second: tokens as f64 / seconds,
+		usd_per_million_tokens: price,
+		estimated_index_cost_usd: price.map(|price| price * sample.index_tokens as f64 / 1e6),
+		quality,
+	})
+}
+
+/// Plain-word query for a block from its first line of code, or `None`
+/// when that line has too few words to ask about
+pub fn self_test_query(content: &str) -> Option<String> {
+	let line = content.lines().map(str::trim).find(|line| {
+		!line.is_empty()
+			&& !["//", "#", "/*", "*", "--", "@", "\"\"\""]
+				.iter()
+				.any(|prefix| line.starts_with(prefix))
+	})?;
+
+	let mut words: Vec<String> = Vec::new();
+	for token in line.split(|c: char| !c.is_alphanumeric()) {
+		for word in split_identifier(token) {
+			if word.len() > 1
+				&& !KEYWORDS.contains(&word.as_str())
+				&& !word.chars().all(|c| c.is_ascii_digit())
+				&& !words.contains(&word)
+			{
+				words.push(word);
+			}
+		}
+	}
+	words.truncate(MAX_QUERY_WORDS);
+	(words.len() >= 2).then(|| words.join(" "))
+}
+
+/// Lowercase words of a camelCase or PascalCase identifier
+fn split_identifier(identifier: &str) -> Vec<String> {
+	let mut words = Vec::new();
+	let mut current = String::new();
+	let chars: Vec<char> = identifier.chars().collect();
+	for (idx, &c) in chars.iter().enumerate() {
+		let next_is_lower = chars.get(idx + 1).is_some_and(|next| next.is_lowercase());
+		let prev_is_lower = idx > 0 && chars[idx - 1].is_lowercase();
+		// "parseHTTPRequest" is "parse", "http", "request"
+		if c.is_uppercase() && !current.is_empty() && (prev_is_lower || next_is_lower) {
+			words.push(std::mem::take(&mut current));
+		}
+		current.extend(c.to_lowercase());
+	}
+	if !current.is_empty() {
+		words.push(current);
+	}
+	words
+}
+
+/// Recall and mean reciprocal rank of `expected[i]` among `blocks` for
+/// `queries[i]`, ranked by cosine similarity
+pub fn retrieval_quality(
+	queries: &[Vec<f32>],
+	blocks: &[Vec<f32>],
+	expected: &[usize],
+) -> RetrievalQuality {
+	if queries.is_empty() {
+		return RetrievalQuality::default();
+	}
+	let (mut top1, mut top5, mut reciprocal_ranks) = (0, 0, 0.0);
+	for (query, &expected) in queries.iter().zip(expected) {
+		let Some(target) = blocks.get(expected) else {
+			continue;
+		};
+		let score = cosine_similarity(query, target);
+		let rank = 1 + blocks
+			.iter()
+			.enumerate()
+			.filter(|(idx, block)| *idx != expected && cosine_similarity(query, block) > score)
+			.count();
+		if rank == 1 {
+			top1 += 1;
+		}
+		if rank <= 5 {
+			top5 += 1;
+		}
+		reciprocal_ranks += 1.0 / rank as f64;
+	}
+	let count = queries.len() as f64;
+	RetrievalQuality {
+		queries: queries.len(),
+		recall_at_1: top1 as f64 / count,
+		recall_at_5: top5 as f64 / count,
+		mrr: reciprocal_ranks / count,
+	}
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+	let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+	let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+	let norms = norm(a) * norm(b);
+	if norms == 0.0 {
+		0.0
+	} else {
+		dot / norms
+	}
+}
+
+/// Value at `fraction` of sorted `values`
+fn percentile(values: &[u64], fraction: f64) -> u64 {
+	if values.is_empty() {
+		return 0;
+	}
+	let idx = ((values.len() - 1) as f64 * fraction).round() as usize;
+	values[idx.min(values.len() - 1)]
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_self_test_query() {
+		assert_eq!(
+			self_test_query("/// Loads config\npub fn load_layered() -> Result<LayeredConfig> {")
+				.as_deref(),
+			Some("load layered result config")
+		);
+		assert_eq!(
+			self_test_query("async function parseHTTPRequest(req) {").as_deref(),
+			Some("parse http request req")
+		);
+		assert_eq!(self_test_query("}\n"), None);
+	}
+
+	#[test]
+	fn test_retrieval_quality() {
+		let blocks = vec![vec![1.0, 0.0], vec![0.0, 1.0], vec![0.7, 0.7]];
+		let queries = vec![vec![0.9, 0.1], vec![0.9, 0.2]];
+		// The first query finds block 0 first, the second ranks block 1 third
+		let quality = retrieval_quality(&queries, &blocks, &[0, 1]);
+		assert_eq!(quality.queries, 2);
+		assert_eq!(quality.recall_at_1, 0.5);
+		assert_eq!(quality.recall_at_5, 1.0);
+		assert!((quality.mrr - (1.0 + 1.0 / 3.0) / 2.0).abs() < 1e-9);
+	}
+
+	#[test]
+	fn test_prices() {
+		assert_eq!(
+			price_per_million_tokens("openai:text-embedding-3-small"),
+			Some(0.02)
+		);
+		assert_eq!(
+			price_per_million_tokens("fastembed:jina-embeddings-v2-base-code"),
+			Some(0.0)
+		);
+		assert_eq!(price_per_million_tokens("voyage:unknown-model"), None);
+	}
+}
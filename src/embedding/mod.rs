@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod benchmark;
+pub mod policy;
 pub mod provider;
 #[cfg(test)]
 mod tests;
@@ -64,12 +66,11 @@ pub fn truncate_output(output: &str, max_tokens: usize) -> String {
 
 	// Simple truncation - cut at character boundary
 	// Estimate roughly where to cut (tokens are ~4 chars average)
-	let estimated_chars = max_tokens * 3; // Conservative estimate
-	let truncated = if output.len() > estimated_chars {
-		&output[..estimated_chars]
-	} else {
-		output
-	};
+	let mut estimated_chars = (max_tokens * 3).min(output.len()); // Conservative estimate
+	while !output.is_char_boundary(estimated_chars) {
+		estimated_chars -= 1;
+	}
+	let truncated = &output[..estimated_chars];
 
 	// Find last newline to avoid cutting mid-line
 	let last_newline = truncated.rfind('\n').unwrap_or(truncated.len());
@@ -0,0 +1,157 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Path policies for embedding providers
+//!
+//! `[[embedding.path_policies]]` entries mark paths whose content must never
+//! leave the machine. Blocks from those paths are only embedded when the model
+//! that would embed them runs locally; otherwise they are kept out of the index
+//! and reported as violations.
+
+use anyhow::Result;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+
+use super::types::{parse_provider_model, EmbeddingProviderType};
+use crate::config::Config;
+
+/// Whether a `provider:model` string embeds on this machine
+pub fn is_local_model(model: &str) -> bool {
+	matches!(
+		parse_provider_model(model).0,
+		EmbeddingProviderType::FastEmbed | EmbeddingProviderType::HuggingFace
+	)
+}
+
+/// Compiled `embedding.path_policies`
+pub struct PathGuard {
+	local_only: GlobSet,
+	code_model: String,
+	text_model: String,
+	/// Remote destination of GraphRAG file descriptions, if any
+	graph_destination: Option<String>,
+}
+
+impl PathGuard {
+	pub fn from_config(config: &Config) -> Result<Self> {
+		let mut builder = GlobSetBuilder::new();
+		for policy in config
+			.embedding
+			.path_policies
+			.iter()
+			.filter(|policy| policy.local_only)
+		{
+			for pattern in &policy.paths {
+				let glob = Glob::new(pattern.trim_start_matches("./")).map_err(|e| {
+					anyhow::anyhow!(
+						"Invalid pattern '{}' in embedding.path_policies: {}",
+						pattern,
+						e
+					)
+				})?;
+				builder.add(glob);
+			}
+		}
+		let graph_destination = if config.graphrag.use_llm {
+			Some(config.graphrag.llm.description_model.clone())
+		} else if !is_local_model(&config.embedding.text_model) {
+			Some(config.embedding.text_model.clone())
+		} else {
+			None
+		};
+		Ok(Self {
+			local_only: builder.build()?,
+			code_model: config.embedding.code_model.clone(),
+			text_model: config.embedding.text_model.clone(),
+			graph_destination,
+		})
+	}
+
+	/// Whether the path is restricted to local models
+	pub fn is_local_only(&self, path: &str) -> bool {
+		!self.local_only.is_empty() && self.local_only.is_match(path.trim_start_matches("./"))
+	}
+
+	/// The remote model a block of `path` would be sent to against policy,
+	/// or `None` when it may be embedded
+	pub fn violation(&self, path: &str, is_code: bool) -> Option<&str> {
+		let model = if is_code {
+			&self.code_model
+		} else {
+			&self.text_model
+		};
+		(self.is_local_only(path) && !is_local_model(model)).then_some(model.as_str())
+	}
+
+	/// Like [`Self::violation`] for GraphRAG, which describes files with the
+	/// text model and optionally an LLM
+	pub fn graph_violation(&self, path: &str) -> Option<&str> {
+		if !self.is_local_only(path) {
+			return None;
+		}
+		self.graph_destination.as_deref()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::embedding::types::PathPolicy;
+
+	fn config(code_model: &str, text_model: &str) -> Config {
+		let mut config = Config::load_from_template().unwrap();
+		config.embedding.code_model = code_model.to_string();
+		config.embedding.text_model = text_model.to_string();
+		config.embedding.path_policies = vec![
+			PathPolicy {
+				paths: vec!["internal/secrets/**".to_string(), "*.pem".to_string()],
+				local_only: true,
+			},
+			PathPolicy {
+				paths: vec!["docs/**".to_string()],
+				local_only: false,
+			},
+		];
+		config
+	}
+
+	#[test]
+	fn test_local_only_paths_need_local_models() {
+		let guard = PathGuard::from_config(&config(
+			"voyage:voyage-code-3",
+			"fastembed:sentence-transformers/all-MiniLM-L6-v2-quantized",
+		))
+		.unwrap();
+
+		assert_eq!(
+			guard.violation("internal/secrets/vault.rs", true),
+			Some("voyage:voyage-code-3")
+		);
+		assert_eq!(
+			guard.violation("./internal/secrets/keys.rs", true),
+			Some("voyage:voyage-code-3")
+		);
+		assert_eq!(guard.violation("internal/secrets/README.md", false), None);
+		assert_eq!(guard.violation("src/main.rs", true), None);
+		// Policies without local_only restrict nothing
+		assert_eq!(guard.violation("docs/guide.md", false), None);
+		assert_eq!(guard.graph_violation("internal/secrets/vault.rs"), None);
+	}
+
+	#[test]
+	fn test_invalid_policy_pattern() {
+		let mut config = config("voyage:voyage-code-3", "voyage:voyage-3.5-lite");
+		config.embedding.path_policies[0].paths = vec!["internal/[secrets".to_string()];
+		assert!(PathGuard::from_config(&config).is_err());
+	}
+}
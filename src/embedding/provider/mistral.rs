@@ -0,0 +1,179 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Mistral embedding provider implementation (Codestral Embed and Mistral Embed)
+
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+
+use super::super::types::InputType;
+use super::{EmbeddingProvider, HTTP_CLIENT};
+
+/// Mistral provider implementation for trait
+pub struct MistralProviderImpl {
+	model_name: String,
+	dimension: usize,
+}
+
+impl MistralProviderImpl {
+	pub fn new(model: &str) -> Result<Self> {
+		// Validate model first - fail fast if unsupported
+		let supported_models = ["codestral-embed", "codestral-embed-2505", "mistral-embed"];
+
+		if !supported_models.contains(&model) {
+			return Err(anyhow::anyhow!(
+				"Unsupported Mistral model: '{}'. Supported models: {:?}",
+				model,
+				supported_models
+			));
+		}
+
+		let dimension = Self::get_model_dimension(model);
+		Ok(Self {
+			model_name: model.to_string(),
+			dimension,
+		})
+	}
+
+	fn get_model_dimension(model: &str) -> usize {
+		match model {
+			// Codestral Embed defaults to 1536 dimensions when output_dimension is not set
+			"codestral-embed" | "codestral-embed-2505" => 1536,
+			"mistral-embed" => 1024,
+			_ => {
+				// This should never be reached due to validation in new()
+				panic!(
+					"Invalid Mistral model '{}' passed to get_model_dimension",
+					model
+				);
+			}
+		}
+	}
+}
+
+#[async_trait::async_trait]
+impl EmbeddingProvider for MistralProviderImpl {
+	async fn generate_embedding(&self, text: &str) -> Result<Vec<f32>> {
+		MistralProvider::generate_embeddings(text, &self.model_name).await
+	}
+
+	async fn generate_embeddings_batch(
+		&self,
+		texts: Vec<String>,
+		input_type: InputType,
+	) -> Result<Vec<Vec<f32>>> {
+		MistralProvider::generate_embeddings_batch(texts, &self.model_name, input_type).await
+	}
+
+	fn get_dimension(&self) -> usize {
+		self.dimension
+	}
+
+	fn is_model_supported(&self) -> bool {
+		matches!(
+			self.model_name.as_str(),
+			"codestral-embed" | "codestral-embed-2505" | "mistral-embed"
+		)
+	}
+}
+
+/// Mistral provider implementation
+pub struct MistralProvider;
+
+impl MistralProvider {
+	pub async fn generate_embeddings(contents: &str, model: &str) -> Result<Vec<f32>> {
+		let result =
+			Self::generate_embeddings_batch(vec![contents.to_string()], model, InputType::None)
+				.await?;
+		result
+			.first()
+			.cloned()
+			.ok_or_else(|| anyhow::anyhow!("No embeddings found"))
+	}
+
+	pub async fn generate_embeddings_batch(
+		texts: Vec<String>,
+		model: &str,
+		input_type: InputType,
+	) -> Result<Vec<Vec<f32>>> {
+		let mistral_api_key = std::env::var("MISTRAL_API_KEY")
+			.context("MISTRAL_API_KEY environment variable not set")?;
+
+		// Apply input type prefixes since Mistral doesn't have native input_type support
+		let processed_texts: Vec<String> = texts
+			.into_iter()
+			.map(|text| input_type.apply_prefix(&text))
+			.collect();
+
+		let request_body = json!({
+			"input": processed_texts,
+			"model": model,
+		});
+
+		let response = HTTP_CLIENT
+			.post("https://api.mistral.ai/v1/embeddings")
+			.header("Authorization", format!("Bearer {}", mistral_api_key))
+			.header("Content-Type", "application/json")
+			.json(&request_body)
+			.send()
+			.await?;
+
+		if !response.status().is_success() {
+			let error_text = response.text().await?;
+			return Err(anyhow::anyhow!("Mistral API error: {}", error_text));
+		}
+
+		let response_json: Value = response.json().await?;
+
+		let embeddings = response_json["data"]
+			.as_array()
+			.context("Failed to get embeddings array")?
+			.iter()
+			.map(|data| {
+				data["embedding"]
+					.as_array()
+					.unwrap_or(&Vec::new())
+					.iter()
+					.map(|v| v.as_f64().unwrap_or_default() as f32)
+					.collect()
+			})
+			.collect();
+
+		Ok(embeddings)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_mistral_provider_creation() {
+		assert!(MistralProviderImpl::new("codestral-embed").is_ok());
+		assert!(MistralProviderImpl::new("codestral-embed-2505").is_ok());
+		assert!(MistralProviderImpl::new("mistral-embed").is_ok());
+
+		assert!(MistralProviderImpl::new("invalid-model").is_err());
+	}
+
+	#[test]
+	fn test_model_dimensions() {
+		let codestral = MistralProviderImpl::new("codestral-embed").unwrap();
+		assert_eq!(codestral.get_dimension(), 1536);
+		assert!(codestral.is_model_supported());
+
+		let mistral = MistralProviderImpl::new("mistral-embed").unwrap();
+		assert_eq!(mistral.get_dimension(), 1024);
+	}
+}
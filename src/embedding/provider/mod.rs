@@ -44,6 +44,7 @@ pub mod huggingface;
 // Always available provider modules
 pub mod google;
 pub mod jina;
+pub mod mistral;
 pub mod openai;
 pub mod voyage;
 
@@ -56,6 +57,7 @@ pub use huggingface::{HuggingFaceProvider, HuggingFaceProviderImpl};
 // Always available provider re-exports
 pub use google::{GoogleProvider, GoogleProviderImpl};
 pub use jina::{JinaProvider, JinaProviderImpl};
+pub use mistral::{MistralProvider, MistralProviderImpl};
 pub use openai::{OpenAIProvider, OpenAIProviderImpl};
 pub use voyage::{VoyageProvider, VoyageProviderImpl};
 
@@ -98,6 +100,7 @@ pub fn create_embedding_provider_from_parts(
 		EmbeddingProviderType::Voyage => Ok(Box::new(VoyageProviderImpl::new(model)?)),
 		EmbeddingProviderType::Google => Ok(Box::new(GoogleProviderImpl::new(model)?)),
 		EmbeddingProviderType::OpenAI => Ok(Box::new(OpenAIProviderImpl::new(model)?)),
+		EmbeddingProviderType::Mistral => Ok(Box::new(MistralProviderImpl::new(model)?)),
 		EmbeddingProviderType::HuggingFace => {
 			#[cfg(feature = "huggingface")]
 			{
@@ -82,6 +82,12 @@ mod embedding_tests {
 			"voyage-code-3",
 		));
 
+		test_cases.push((
+			"mistral:codestral-embed",
+			EmbeddingProviderType::Mistral,
+			"codestral-embed",
+		));
+
 		for (input, expected_provider, expected_model) in test_cases {
 			let (provider, model) = parse_provider_model(input);
 			assert_eq!(
@@ -128,6 +134,7 @@ mod embedding_tests {
 		let config = EmbeddingConfig {
 			code_model: "huggingface:microsoft/codebert-base".to_string(),
 			text_model: "huggingface:sentence-transformers/all-mpnet-base-v2".to_string(),
+			path_policies: Vec::new(),
 		};
 
 		// Test getting active provider
@@ -154,6 +161,7 @@ mod embedding_tests {
 		let config = EmbeddingConfig {
 			code_model: "voyage:voyage-code-3".to_string(),
 			text_model: "voyage:voyage-3.5-lite".to_string(),
+			path_policies: Vec::new(),
 		};
 
 		// Test getting active provider
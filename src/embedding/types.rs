@@ -70,6 +70,7 @@ pub enum EmbeddingProviderType {
 	Google,
 	HuggingFace,
 	OpenAI,
+	Mistral,
 }
 
 impl Default for EmbeddingProviderType {
@@ -93,6 +94,22 @@ pub struct EmbeddingConfig {
 
 	/// Text embedding model (format: "provider:model")
 	pub text_model: String,
+
+	/// Restrictions on which providers may embed specific paths
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub path_policies: Vec<PathPolicy>,
+}
+
+/// Embedding restriction for files matching `paths`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PathPolicy {
+	/// Glob patterns relative to the project root, e.g. "internal/secrets/**"
+	pub paths: Vec<String>,
+
+	/// Only embed matching files with local models (FastEmbed, HuggingFace);
+	/// with a remote model configured their blocks are skipped
+	#[serde(default)]
+	pub local_only: bool,
 }
 
 impl Default for EmbeddingConfig {
@@ -104,6 +121,7 @@ impl Default for EmbeddingConfig {
 				code_model: "fastembed:jinaai/jina-embeddings-v2-base-code".to_string(),
 				text_model: "fastembed:sentence-transformers/all-MiniLM-L6-v2-quantized"
 					.to_string(),
+				path_policies: Vec::new(),
 			}
 		}
 		#[cfg(not(feature = "fastembed"))]
@@ -111,6 +129,7 @@ impl Default for EmbeddingConfig {
 			Self {
 				code_model: "voyage:voyage-code-3".to_string(),
 				text_model: "voyage:voyage-3.5-lite".to_string(),
+				path_policies: Vec::new(),
 			}
 		}
 	}
@@ -126,6 +145,7 @@ pub fn parse_provider_model(input: &str) -> (EmbeddingProviderType, String) {
 			"google" => EmbeddingProviderType::Google,
 			"huggingface" | "hf" => EmbeddingProviderType::HuggingFace,
 			"openai" => EmbeddingProviderType::OpenAI,
+			"mistral" => EmbeddingProviderType::Mistral,
 			_ => {
 				// Default fallback - use FastEmbed if available, otherwise Voyage
 				#[cfg(feature = "fastembed")]
@@ -165,6 +185,7 @@ impl EmbeddingConfig {
 			EmbeddingProviderType::Jina => std::env::var("JINA_API_KEY").ok(),
 			EmbeddingProviderType::Voyage => std::env::var("VOYAGE_API_KEY").ok(),
 			EmbeddingProviderType::Google => std::env::var("GOOGLE_API_KEY").ok(),
+			EmbeddingProviderType::Mistral => std::env::var("MISTRAL_API_KEY").ok(),
 			_ => None, // FastEmbed and SentenceTransformer don't need API keys
 		}
 	}
@@ -0,0 +1,22 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Index exports for other tools
+//!
+//! `octocode export` writes the index in formats that code navigation tools
+//! read. The JSONL archive shared with the index cache lives in
+//! [`crate::index_cache::archive`].
+
+pub mod scip;
+pub mod tags;
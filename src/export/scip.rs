@@ -0,0 +1,427 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! SCIP export
+//!
+//! `octocode export --format scip` writes the index as a SCIP index
+//! (<https://github.com/sourcegraph/scip>) that Sourcegraph-style navigation
+//! tools can load. Each indexed code file is a document:
+//!
+//! - functions GraphRAG extracted are method definitions, documented with
+//!   their signature and referencing the functions they call
+//! - other symbols of the file's blocks are term definitions, located at
+//!   their first mention in the block
+//! - a namespace symbol stands for the file and references the files it
+//!   imports or calls, from the GraphRAG relationships
+//!
+//! Only the few message types used are encoded, directly as protobuf.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+
+use anyhow::Result;
+
+use crate::indexer::file_summary::OVERVIEW_PREFIX;
+use crate::indexer::graphrag::database::DatabaseOperations;
+use crate::indexer::graphrag::{CodeGraph, FunctionInfo};
+use crate::store::{CodeBlock, Store};
+
+/// `Occurrence.symbol_roles` bit of a definition
+const ROLE_DEFINITION: u64 = 1;
+
+/// `TextEncoding.UTF8` and `PositionEncoding.UTF8CodeUnitOffsetFromLineStart`
+const UTF8: u64 = 1;
+
+#[derive(Debug, Default)]
+pub struct ScipSummary {
+	pub documents: usize,
+	pub definitions: usize,
+	pub relationships: usize,
+}
+
+/// Write the code blocks and GraphRAG graph of the index to `output` as SCIP
+pub async fn export(store: &Store, repo_path: &Path, output: &Path) -> Result<ScipSummary> {
+	let blocks = store.get_all_code_blocks_for_graphrag().await?;
+	let graph = DatabaseOperations::new(store)
+		.load_graph(repo_path, true)
+		.await?;
+
+	let mut files: BTreeMap<&str, Vec<&CodeBlock>> = BTreeMap::new();
+	for block in &blocks {
+		if !block.content.starts_with(OVERVIEW_PREFIX) {
+			files.entry(block.path.as_str()).or_default().push(block);
+		}
+	}
+	for blocks in files.values_mut() {
+		blocks.sort_by_key(|block| (block.start_line, block.end_line));
+	}
+
+	let symbols = Symbols::new(repo_path, &graph);
+	let mut summary = ScipSummary::default();
+	let mut index = ProtoWriter::default();
+	index.message(1, metadata(repo_path));
+	for (path, blocks) in &files {
+		index.message(2, document(path, blocks, &graph, &symbols, &mut summary));
+		summary.documents += 1;
+	}
+
+	std::fs::write(output, index.buf)?;
+	Ok(summary)
+}
+
+fn metadata(repo_path: &Path) -> ProtoWriter {
+	let mut tool = ProtoWriter::default();
+	tool.string(1, "octocode");
+	tool.string(2, env!("CARGO_PKG_VERSION"));
+
+	let mut metadata = ProtoWriter::default();
+	metadata.message(2, tool);
+	let root = repo_path.canonicalize().unwrap_or(repo_path.to_path_buf());
+	metadata.string(3, &format!("file://{}", root.display()));
+	metadata.uint(4, UTF8);
+	metadata
+}
+
+fn document(
+	path: &str,
+	blocks: &[&CodeBlock],
+	graph: &CodeGraph,
+	symbols: &Symbols,
+	summary: &mut ScipSummary,
+) -> ProtoWriter {
+	let mut document = ProtoWriter::default();
+	document.string(1, path);
+	document.string(4, scip_language(&blocks[0].language));
+	document.uint(6, UTF8);
+
+	// Indexed lines of the file, 0-indexed like block ranges
+	let mut lines: BTreeMap<usize, &str> = BTreeMap::new();
+	for block in blocks {
+		for (offset, line) in block.content.lines().enumerate() {
+			lines.entry(block.start_line + offset).or_insert(line);
+		}
+	}
+
+	let mut defined = BTreeSet::new();
+	let functions = symbols.functions.get(path).cloned().unwrap_or_default();
+	for function in &functions {
+		let symbol = symbols.method(path, &function.name);
+		if !defined.insert(symbol.clone()) {
+			continue;
+		}
+		if let Some(column) = lines
+			.get(&(function.start_line as usize))
+			.and_then(|line| find_word(line, &function.name))
+		{
+			document.message(
+				2,
+				occurrence(
+					function.start_line as usize,
+					column,
+					&function.name,
+					&symbol,
+				),
+			);
+		}
+
+		let mut information = ProtoWriter::default();
+		information.string(1, &symbol);
+		information.string(3, &format!("```\n{}\n```", function.signature.trim()));
+		let mut callees = BTreeSet::new();
+		for name in &function.calls {
+			callees.extend(symbols.resolve_call(path, name));
+		}
+		for callee in &callees {
+			information.message(4, relationship(callee, false));
+			summary.relationships += 1;
+		}
+		information.string(6, &function.name);
+		document.message(3, information);
+		summary.definitions += 1;
+	}
+
+	for block in blocks {
+		for name in &block.symbols {
+			let symbol = symbols.term(path, name);
+			if functions.iter().any(|function| &function.name == name)
+				|| !defined.insert(symbol.clone())
+			{
+				continue;
+			}
+			let found = (block.start_line..=block.end_line).find_map(|line| {
+				lines
+					.get(&line)
+					.and_then(|text| find_word(text, name))
+					.map(|column| (line, column))
+			});
+			if let Some((line, column)) = found {
+				document.message(2, occurrence(line, column, name, &symbol));
+			}
+
+			let mut information = ProtoWriter::default();
+			information.string(1, &symbol);
+			information.string(6, name);
+			document.message(3, information);
+			summary.definitions += 1;
+		}
+	}
+
+	// The file itself, referencing the files it depends on
+	let mut targets: BTreeMap<&str, bool> = BTreeMap::new();
+	for relationship in graph.relationships.iter().filter(|r| r.source == path) {
+		let implementation = match relationship.relation_type.as_str() {
+			"imports" | "imports_direct" | "calls" | "calls_service" => false,
+			"implements_service" => true,
+			_ => continue,
+		};
+		*targets.entry(relationship.target.as_str()).or_default() |= implementation;
+	}
+	if !targets.is_empty() {
+		let mut information = ProtoWriter::default();
+		information.string(1, &symbols.file(path));
+		for (target, implementation) in targets {
+			information.message(4, relationship(&symbols.file(target), implementation));
+			summary.relationships += 1;
+		}
+		information.string(6, path);
+		document.message(3, information);
+	}
+
+	document
+}
+
+fn occurrence(line: usize, column: usize, name: &str, symbol: &str) -> ProtoWriter {
+	let mut occurrence = ProtoWriter::default();
+	occurrence.packed(
+		1,
+		&[line as u64, column as u64, (column + name.len()) as u64],
+	);
+	occurrence.string(2, symbol);
+	occurrence.uint(3, ROLE_DEFINITION);
+	occurrence
+}
+
+fn relationship(symbol: &str, implementation: bool) -> ProtoWriter {
+	let mut relationship = ProtoWriter::default();
+	relationship.string(1, symbol);
+	relationship.bool(2, !implementation);
+	relationship.bool(3, implementation);
+	relationship
+}
+
+/// SCIP symbols of the project, and the functions of each file
+struct Symbols<'a> {
+	/// `octocode . <project> .`, the scheme and package of every symbol
+	package: String,
+	functions: HashMap<&'a str, Vec<&'a FunctionInfo>>,
+}
+
+impl<'a> Symbols<'a> {
+	fn new(repo_path: &Path, graph: &'a CodeGraph) -> Self {
+		let project = repo_path
+			.canonicalize()
+			.ok()
+			.and_then(|root| {
+				root.file_name()
+					.map(|name| name.to_string_lossy().to_string())
+			})
+			.unwrap_or_else(|| "project".to_string());
+		let mut functions: HashMap<&str, Vec<&FunctionInfo>> = HashMap::new();
+		for node in graph.nodes.values() {
+			functions
+				.entry(node.path.as_str())
+				.or_default()
+				.extend(&node.functions);
+		}
+		Self {
+			// Spaces separate the fields of a symbol and are doubled inside them
+			package: format!("octocode . {} .", project.replace(' ', "  ")),
+			functions,
+		}
+	}
+
+	/// Namespace symbol standing for a file: one namespace per path component
+	fn file(&self, path: &str) -> String {
+		let namespaces: String = path
+			.split('/')
+			.map(|component| format!("{}/", descriptor_name(component)))
+			.collect();
+		format!("{} {}", self.package, namespaces)
+	}
+
+	fn method(&self, path: &str, name: &str) -> String {
+		format!("{}{}().", self.file(path), descriptor_name(name))
+	}
+
+	fn term(&self, path: &str, name: &str) -> String {
+		format!("{}{}.", self.file(path), descriptor_name(name))
+	}
+
+	/// Functions a call may reach: the one of that name in the calling file,
+	/// or else every function of that name
+	fn resolve_call(&self, path: &str, name: &str) -> Vec<String> {
+		let defines = |functions: &[&FunctionInfo]| functions.iter().any(|f| f.name == name);
+		if self.functions.get(path).is_some_and(|f| defines(f)) {
+			return vec![self.method(path, name)];
+		}
+		self.functions
+			.iter()
+			.filter(|(_, functions)| defines(functions))
+			.map(|(file, _)| self.method(file, name))
+			.collect()
+	}
+}
+
+/// A descriptor name, in backticks unless it is a simple identifier
+fn descriptor_name(name: &str) -> String {
+	if !name.is_empty()
+		&& name
+			.chars()
+			.all(|c| c.is_alphanumeric() || matches!(c, '_' | '+' | '-' | '$'))
+	{
+		name.to_string()
+	} else {
+		format!("`{}`", name.replace('`', "``"))
+	}
+}
+
+/// Byte column of the first mention of `name` not inside a longer identifier
+fn find_word(line: &str, name: &str) -> Option<usize> {
+	let is_identifier = |c: char| c.is_alphanumeric() || c == '_';
+	line.match_indices(name).map(|(i, _)| i).find(|&i| {
+		!line[..i].chars().next_back().is_some_and(is_identifier)
+			&& !line[i + name.len()..]
+				.chars()
+				.next()
+				.is_some_and(is_identifier)
+	})
+}
+
+/// Language names SCIP uses, for the languages octocode indexes
+fn scip_language(language: &str) -> &str {
+	match language {
+		"rust" => "Rust",
+		"javascript" => "JavaScript",
+		"typescript" => "TypeScript",
+		"python" => "Python",
+		"go" => "Go",
+		"cpp" => "CPP",
+		"php" => "PHP",
+		"bash" => "ShellScript",
+		"ruby" => "Ruby",
+		"json" => "JSON",
+		"svelte" => "Svelte",
+		"css" => "CSS",
+		"hcl" => "HCL",
+		"sql" => "SQL",
+		"zig" => "Zig",
+		"proto" => "Protobuf",
+		"haskell" => "Haskell",
+		"lua" => "Lua",
+		"dart" => "Dart",
+		other => other,
+	}
+}
+
+/// Protobuf message encoder; fields holding their default value are omitted,
+/// as in proto3
+#[derive(Default)]
+struct ProtoWriter {
+	buf: Vec<u8>,
+}
+
+impl ProtoWriter {
+	fn varint(&mut self, mut value: u64) {
+		while value >= 0x80 {
+			self.buf.push(value as u8 | 0x80);
+			value >>= 7;
+		}
+		self.buf.push(value as u8);
+	}
+
+	fn key(&mut self, field: u32, wire_type: u8) {
+		self.varint(((field as u64) << 3) | wire_type as u64);
+	}
+
+	fn uint(&mut self, field: u32, value: u64) {
+		if value != 0 {
+			self.key(field, 0);
+			self.varint(value);
+		}
+	}
+
+	fn bool(&mut self, field: u32, value: bool) {
+		self.uint(field, value as u64);
+	}
+
+	fn bytes(&mut self, field: u32, bytes: &[u8]) {
+		self.key(field, 2);
+		self.varint(bytes.len() as u64);
+		self.buf.extend_from_slice(bytes);
+	}
+
+	fn string(&mut self, field: u32, value: &str) {
+		if !value.is_empty() {
+			self.bytes(field, value.as_bytes());
+		}
+	}
+
+	fn message(&mut self, field: u32, message: ProtoWriter) {
+		self.bytes(field, &message.buf);
+	}
+
+	/// A packed repeated integer field
+	fn packed(&mut self, field: u32, values: &[u64]) {
+		let mut packed = ProtoWriter::default();
+		for &value in values {
+			packed.varint(value);
+		}
+		self.bytes(field, &packed.buf);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_proto_writer() {
+		let mut writer = ProtoWriter::default();
+		writer.uint(3, 300);
+		writer.string(1, "ab");
+		writer.string(2, "");
+		writer.bool(4, false);
+		writer.packed(1, &[1, 2]);
+		assert_eq!(
+			writer.buf,
+			vec![0x18, 0xac, 0x02, 0x0a, 0x02, b'a', b'b', 0x0a, 0x02, 0x01, 0x02]
+		);
+	}
+
+	#[test]
+	fn test_symbols() {
+		assert_eq!(descriptor_name("parse_config"), "parse_config");
+		assert_eq!(descriptor_name("main.rs"), "`main.rs`");
+		assert_eq!(descriptor_name("test `x`"), "`test ``x```");
+		assert_eq!(find_word("fn parse(parser: Parser)", "parse"), Some(3));
+		assert_eq!(find_word("let parser = 1;", "parse"), None);
+
+		let graph = CodeGraph::default();
+		let symbols = Symbols::new(Path::new("/nonexistent/my app"), &graph);
+		assert_eq!(
+			symbols.method("src/main.rs", "run"),
+			"octocode . project . src/`main.rs`/run()."
+		);
+	}
+}
@@ -0,0 +1,200 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tags files for Vim (ctags) and Emacs (etags)
+//!
+//! Tags are the signatures `octocode view` shows, extracted from the current
+//! contents of every indexed file. Paths are relative to the repository root,
+//! where editors expect the tags file.
+
+use std::fmt::Write as _;
+use std::path::Path;
+
+use anyhow::Result;
+
+use crate::indexer::signature_extractor::{extract_signatures_from_contents, FileSignature};
+use crate::store::Store;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TagsFormat {
+	/// `tags`, sorted by name, with line number addresses
+	Ctags,
+	/// `TAGS`, one section per file
+	Etags,
+}
+
+#[derive(Debug, Default)]
+pub struct TagsSummary {
+	pub files: usize,
+	pub tags: usize,
+}
+
+/// Write a tags file for the files of the index to `output`
+pub async fn export(
+	store: &Store,
+	repo_path: &Path,
+	output: &Path,
+	format: TagsFormat,
+) -> Result<TagsSummary> {
+	let mut paths: Vec<String> = store
+		.get_all_indexed_file_paths()
+		.await?
+		.into_iter()
+		.collect();
+	paths.sort();
+
+	let mut files = Vec::new();
+	for path in paths {
+		// Files removed since indexing have nothing to jump to
+		let Ok(contents) = std::fs::read_to_string(repo_path.join(&path)) else {
+			continue;
+		};
+		if let Some(signature) = extract_signatures_from_contents(&path, &contents)? {
+			if !signature.signatures.is_empty() {
+				files.push((signature, contents));
+			}
+		}
+	}
+
+	let (text, tags) = match format {
+		TagsFormat::Ctags => ctags(&files),
+		TagsFormat::Etags => etags(&files),
+	};
+	std::fs::write(output, text)?;
+	Ok(TagsSummary {
+		files: files.len(),
+		tags,
+	})
+}
+
+/// Names can't hold the separators of either format
+fn is_taggable(name: &str) -> bool {
+	!name.is_empty() && !name.contains(['\t', '\n', '\r', '\x7f', '\x01'])
+}
+
+fn ctags(files: &[(FileSignature, String)]) -> (String, usize) {
+	let mut lines: Vec<String> = files
+		.iter()
+		.flat_map(|(file, _)| {
+			file.signatures
+				.iter()
+				.filter(|item| is_taggable(&item.name))
+				.map(move |item| {
+					format!(
+						"{}\t{}\t{};\"\tkind:{}",
+						item.name,
+						file.path,
+						item.start_line + 1,
+						item.kind
+					)
+				})
+		})
+		.collect();
+	// Sorted by bytes, so editors can binary search
+	lines.sort();
+	lines.dedup();
+
+	let mut text = String::from(
+		"!_TAG_FILE_FORMAT\t2\t/extended format/\n!_TAG_FILE_SORTED\t1\t/0=unsorted, 1=sorted/\n",
+	);
+	let _ = writeln!(
+		text,
+		"!_TAG_PROGRAM_NAME\toctocode\t//\n!_TAG_PROGRAM_VERSION\t{}\t//",
+		env!("CARGO_PKG_VERSION")
+	);
+	for line in &lines {
+		text.push_str(line);
+		text.push('\n');
+	}
+	(text, lines.len())
+}
+
+fn etags(files: &[(FileSignature, String)]) -> (String, usize) {
+	let mut text = String::new();
+	let mut tags = 0;
+	for (file, contents) in files {
+		// Byte offset of each line start
+		let offsets: Vec<usize> = std::iter::once(0)
+			.chain(contents.match_indices('\n').map(|(i, _)| i + 1))
+			.collect();
+		let lines: Vec<&str> = contents.lines().collect();
+
+		let mut section = String::new();
+		for item in file
+			.signatures
+			.iter()
+			.filter(|item| is_taggable(&item.name))
+		{
+			let line = lines.get(item.start_line).copied().unwrap_or_default();
+			let _ = writeln!(
+				section,
+				"{}\x7f{}\x01{},{}",
+				line.trim_end().replace('\x7f', ""),
+				item.name,
+				item.start_line + 1,
+				offsets.get(item.start_line).copied().unwrap_or_default()
+			);
+			tags += 1;
+		}
+		let _ = write!(text, "\x0c\n{},{}\n{}", file.path, section.len(), section);
+	}
+	(text, tags)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::indexer::signature_extractor::SignatureItem;
+
+	fn item(kind: &str, name: &str, start_line: usize) -> SignatureItem {
+		SignatureItem {
+			kind: kind.to_string(),
+			name: name.to_string(),
+			signature: String::new(),
+			description: None,
+			start_line,
+			end_line: start_line,
+		}
+	}
+
+	#[test]
+	fn test_tags_formats() {
+		let file = FileSignature {
+			path: "src/lib.rs".to_string(),
+			language: "rust".to_string(),
+			file_comment: None,
+			signatures: vec![item("struct", "Store", 0), item("function", "open", 2)],
+		};
+		let files = vec![(file, "pub struct Store;\n\npub fn open() {}\n".to_string())];
+
+		let (text, tags) = ctags(&files);
+		assert_eq!(tags, 2);
+		let entries: Vec<&str> = text.lines().filter(|l| !l.starts_with("!_")).collect();
+		assert_eq!(
+			entries,
+			vec![
+				"Store\tsrc/lib.rs\t1;\"\tkind:struct",
+				"open\tsrc/lib.rs\t3;\"\tkind:function",
+			]
+		);
+
+		let (text, tags) = etags(&files);
+		assert_eq!(tags, 2);
+		let section = "pub struct Store;\x7fStore\x011,0\npub fn open() {}\x7fopen\x013,19\n";
+		assert_eq!(
+			text,
+			format!("\x0c\nsrc/lib.rs,{}\n{}", section.len(), section)
+		);
+	}
+}
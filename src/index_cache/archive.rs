@@ -0,0 +1,439 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Index export archives
+//!
+//! An archive is a JSON Lines file: a header followed by one record per
+//! block (with its embedding), GraphRAG node and relationship. A full archive
+//! replaces the whole index on import. A delta archive covers only the files
+//! changed between two commits: importing it drops everything indexed for those
+//! files and adds the shipped rows, which moves an index built at the base
+//! commit to the delta's commit without re-embedding anything.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use super::CACHED_TABLES;
+use crate::config::Config;
+use crate::indexer::graphrag::database::DatabaseOperations;
+use crate::indexer::graphrag::{CodeNode, CodeRelationship};
+use crate::indexer::GitUtils;
+use crate::store::{AnchoredBlock, CodeBlock, DocumentBlock, Store, TextBlock};
+
+const ARCHIVE_VERSION: u32 = 1;
+
+/// Blocks read from or written to the store at a time
+const BLOCK_BATCH_SIZE: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchiveKind {
+	Full,
+	Delta,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveHeader {
+	pub version: u32,
+	pub kind: ArchiveKind,
+	/// Commit a delta applies on top of
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub base_commit: Option<String>,
+	/// Commit the archived content corresponds to (empty outside git)
+	pub commit: String,
+	pub code_model: String,
+	pub text_model: String,
+	/// Whether GraphRAG rows are included
+	pub graph: bool,
+	/// Files whose rows a delta replaces; empty for full archives
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub paths: Vec<String>,
+	/// Manifest hash of the exported index; full archives only
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub manifest_hash: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "record", rename_all = "snake_case")]
+enum Record {
+	Header(ArchiveHeader),
+	Code {
+		block: CodeBlock,
+		embedding: Vec<f32>,
+	},
+	Text {
+		block: TextBlock,
+		embedding: Vec<f32>,
+	},
+	Document {
+		block: DocumentBlock,
+		embedding: Vec<f32>,
+	},
+	Node(CodeNode),
+	Relationship(CodeRelationship),
+}
+
+#[derive(Debug, Default)]
+pub struct ArchiveSummary {
+	pub commit: String,
+	pub base_commit: Option<String>,
+	pub paths: usize,
+	pub blocks: usize,
+	pub nodes: usize,
+	pub relationships: usize,
+}
+
+/// Write the index to `output`; with `since`, only what changed between that
+/// commit and the last indexed commit
+pub async fn export(
+	store: &Store,
+	config: &Config,
+	repo_path: &Path,
+	output: &Path,
+	since: Option<&str>,
+) -> Result<ArchiveSummary> {
+	let commit = store.get_last_commit_hash().await?.unwrap_or_default();
+	let since = since
+		.map(|since| GitUtils::resolve_commit(repo_path, since))
+		.transpose()?;
+	let since = since.as_deref();
+	let paths = match since {
+		Some(since) => {
+			if commit.is_empty() {
+				return Err(anyhow::anyhow!(
+					"The index has no recorded commit: run 'octocode index' in a git repository first"
+				));
+			}
+			Some(GitUtils::get_changed_files_between_commits(
+				repo_path, since, &commit,
+			)?)
+		}
+		None => None,
+	};
+	let path_filter: Option<HashSet<&str>> = paths
+		.as_ref()
+		.map(|paths| paths.iter().map(|path| path.as_str()).collect());
+	let graph = store
+		.tables_exist(&["graphrag_nodes", "graphrag_relationships"])
+		.await?;
+
+	let header = ArchiveHeader {
+		version: ARCHIVE_VERSION,
+		kind: if since.is_some() {
+			ArchiveKind::Delta
+		} else {
+			ArchiveKind::Full
+		},
+		base_commit: since.map(|since| since.to_string()),
+		commit: commit.clone(),
+		code_model: config.embedding.code_model.clone(),
+		text_model: config.embedding.text_model.clone(),
+		graph,
+		paths: paths.clone().unwrap_or_default(),
+		manifest_hash: match since {
+			Some(_) => None,
+			None => Some(super::manifest_hash(store).await?),
+		},
+	};
+
+	let mut writer = BufWriter::new(std::fs::File::create(output)?);
+	write_record(&mut writer, &Record::Header(header))?;
+
+	let mut summary = ArchiveSummary {
+		commit,
+		base_commit: since.map(|since| since.to_string()),
+		paths: paths.as_ref().map_or(0, |paths| paths.len()),
+		..Default::default()
+	};
+
+	for table in CACHED_TABLES {
+		let mut hashes = match &paths {
+			Some(paths) => {
+				let mut hashes = Vec::new();
+				for path in paths {
+					hashes.extend(store.get_file_blocks_metadata(path, table).await?);
+				}
+				hashes
+			}
+			None => store.get_all_block_hashes(table).await?,
+		};
+		hashes.sort();
+		hashes.dedup();
+
+		for batch in hashes.chunks(BLOCK_BATCH_SIZE) {
+			// Rows come back in storage order; sort them so equal indexes
+			// export identical archives
+			let mut blocks = store.get_blocks_with_embeddings(table, batch).await?;
+			blocks.sort_by(|(a, _), (b, _)| (a.hash(), a.lines()).cmp(&(b.hash(), b.lines())));
+
+			let mut seen = HashSet::new();
+			for (block, embedding) in blocks {
+				if !seen.insert(block.hash().to_string()) {
+					continue;
+				}
+				let record = match block {
+					AnchoredBlock::Code(mut block) => {
+						block.distance = None;
+						Record::Code { block, embedding }
+					}
+					AnchoredBlock::Text(mut block) => {
+						block.distance = None;
+						Record::Text { block, embedding }
+					}
+					AnchoredBlock::Document(mut block) => {
+						block.distance = None;
+						Record::Document { block, embedding }
+					}
+				};
+				write_record(&mut writer, &record)?;
+				summary.blocks += 1;
+			}
+		}
+	}
+
+	if graph {
+		let graph = DatabaseOperations::new(store)
+			.load_graph(repo_path, true)
+			.await?;
+		let included = |path: &str| path_filter.as_ref().is_none_or(|set| set.contains(path));
+
+		let mut nodes: Vec<CodeNode> = graph
+			.nodes
+			.into_values()
+			.filter(|node| included(&node.path))
+			.collect();
+		nodes.sort_by(|a, b| a.id.cmp(&b.id));
+		for node in nodes {
+			write_record(&mut writer, &Record::Node(node))?;
+			summary.nodes += 1;
+		}
+
+		let mut relationships = graph.relationships;
+		relationships.sort_by(|a, b| {
+			(&a.source, &a.target, &a.relation_type).cmp(&(&b.source, &b.target, &b.relation_type))
+		});
+		for relationship in relationships {
+			if included(&relationship.source) || included(&relationship.target) {
+				write_record(&mut writer, &Record::Relationship(relationship))?;
+				summary.relationships += 1;
+			}
+		}
+	}
+
+	writer.flush()?;
+	Ok(summary)
+}
+
+/// Load an archive. A full archive replaces the index (memories are kept); a
+/// delta is only accepted with `apply_delta`, and only on top of its base
+/// commit unless `force` is set.
+pub async fn import(
+	store: &Store,
+	config: &Config,
+	input: &Path,
+	apply_delta: bool,
+	force: bool,
+) -> Result<ArchiveSummary> {
+	let mut lines = BufReader::new(std::fs::File::open(input)?).lines();
+
+	let header = match lines.next().transpose()? {
+		Some(line) => match serde_json::from_str(&line)? {
+			Record::Header(header) => header,
+			_ => return Err(anyhow::anyhow!("Archive does not start with a header")),
+		},
+		None => return Err(anyhow::anyhow!("Archive is empty")),
+	};
+	check_header(&header, config, apply_delta)?;
+
+	if header.kind == ArchiveKind::Delta {
+		let base = header.base_commit.as_deref().unwrap_or_default();
+		let local = store.get_last_commit_hash().await?.unwrap_or_default();
+		if local != base && !force {
+			return Err(anyhow::anyhow!(
+				"Delta applies on top of commit {} but the index is at {}; pass --force to apply anyway",
+				short_commit(base),
+				short_commit(&local)
+			));
+		}
+		for path in &header.paths {
+			store.remove_blocks_by_path(path).await?;
+		}
+	} else {
+		store.clear_code_table().await?;
+		store.clear_text_table().await?;
+		store.clear_docs_table().await?;
+		store.clear_graph_nodes().await?;
+		store.clear_graph_relationships().await?;
+		store.initialize_collections().await?;
+	}
+
+	let mut summary = ArchiveSummary {
+		commit: header.commit.clone(),
+		base_commit: header.base_commit.clone(),
+		paths: header.paths.len(),
+		..Default::default()
+	};
+	let mut blocks = Vec::new();
+	let mut nodes = Vec::new();
+	let mut relationships = Vec::new();
+
+	for (index, line) in lines.enumerate() {
+		let line = line?;
+		if line.trim().is_empty() {
+			continue;
+		}
+		let record: Record = serde_json::from_str(&line)
+			.map_err(|e| anyhow::anyhow!("Invalid archive record on line {}: {}", index + 2, e))?;
+		match record {
+			Record::Header(_) => {
+				return Err(anyhow::anyhow!(
+					"Unexpected header on line {} of the archive",
+					index + 2
+				))
+			}
+			Record::Code { block, embedding } => {
+				blocks.push((AnchoredBlock::Code(block), embedding))
+			}
+			Record::Text { block, embedding } => {
+				blocks.push((AnchoredBlock::Text(block), embedding))
+			}
+			Record::Document { block, embedding } => {
+				blocks.push((AnchoredBlock::Document(block), embedding))
+			}
+			Record::Node(node) => nodes.push(node),
+			Record::Relationship(relationship) => relationships.push(relationship),
+		}
+
+		if blocks.len() >= BLOCK_BATCH_SIZE {
+			summary.blocks += blocks.len();
+			store
+				.store_blocks_with_embeddings(std::mem::take(&mut blocks))
+				.await?;
+		}
+	}
+	summary.blocks += blocks.len();
+	store.store_blocks_with_embeddings(blocks).await?;
+
+	summary.nodes = nodes.len();
+	summary.relationships = relationships.len();
+	DatabaseOperations::new(store)
+		.save_graph_incremental(&nodes, &relationships)
+		.await?;
+
+	if !header.commit.is_empty() {
+		store.store_git_metadata(&header.commit).await?;
+		if header.graph {
+			store.store_graphrag_commit_hash(&header.commit).await?;
+		}
+	}
+
+	Ok(summary)
+}
+
+fn check_header(header: &ArchiveHeader, config: &Config, apply_delta: bool) -> Result<()> {
+	if header.version != ARCHIVE_VERSION {
+		return Err(anyhow::anyhow!(
+			"Unsupported archive version {}",
+			header.version
+		));
+	}
+	if header.code_model != config.embedding.code_model
+		|| header.text_model != config.embedding.text_model
+	{
+		return Err(anyhow::anyhow!(
+			"Archive was built with embedding models {} / {} but this project uses {} / {}",
+			header.code_model,
+			header.text_model,
+			config.embedding.code_model,
+			config.embedding.text_model
+		));
+	}
+	match (header.kind, apply_delta) {
+		(ArchiveKind::Delta, false) => Err(anyhow::anyhow!(
+			"Archive is a delta: import it with --apply-delta"
+		)),
+		(ArchiveKind::Full, true) => Err(anyhow::anyhow!(
+			"Archive is a full export: import it without --apply-delta"
+		)),
+		_ => Ok(()),
+	}
+}
+
+fn write_record(writer: &mut impl Write, record: &Record) -> Result<()> {
+	serde_json::to_writer(&mut *writer, record)?;
+	writer.write_all(b"\n")?;
+	Ok(())
+}
+
+fn short_commit(commit: &str) -> &str {
+	if commit.is_empty() {
+		"none"
+	} else {
+		&commit[..commit.len().min(12)]
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_records_round_trip_with_tags() {
+		let record = Record::Text {
+			block: TextBlock {
+				path: "README.md".to_string(),
+				language: "text".to_string(),
+				content: "hello".to_string(),
+				start_line: 0,
+				end_line: 1,
+				hash: "abc".to_string(),
+				provenance: None,
+				distance: None,
+			},
+			embedding: vec![0.5, 1.0],
+		};
+		let line = serde_json::to_string(&record).unwrap();
+		assert!(line.starts_with(r#"{"record":"text""#));
+		match serde_json::from_str(&line).unwrap() {
+			Record::Text { block, embedding } => {
+				assert_eq!(block.path, "README.md");
+				assert_eq!(embedding, vec![0.5, 1.0]);
+			}
+			_ => panic!("expected a text record"),
+		}
+
+		let header = ArchiveHeader {
+			version: ARCHIVE_VERSION,
+			kind: ArchiveKind::Delta,
+			base_commit: Some("a1".to_string()),
+			commit: "b2".to_string(),
+			code_model: "c".to_string(),
+			text_model: "t".to_string(),
+			graph: false,
+			paths: vec!["src/lib.rs".to_string()],
+			manifest_hash: None,
+		};
+		let line = serde_json::to_string(&Record::Header(header)).unwrap();
+		match serde_json::from_str(&line).unwrap() {
+			Record::Header(header) => {
+				assert_eq!(header.kind, ArchiveKind::Delta);
+				assert_eq!(header.paths, vec!["src/lib.rs".to_string()]);
+			}
+			_ => panic!("expected a header"),
+		}
+	}
+}
@@ -0,0 +1,128 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Binary encoding of one indexed block and its embedding
+//!
+//! Layout: `OCC1`, little-endian u32 length of the JSON block, the JSON block,
+//! then the embedding as little-endian f32 values.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::store::{AnchoredBlock, CodeBlock, DocumentBlock, TextBlock};
+
+const CHUNK_MAGIC: &[u8] = b"OCC1";
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum ChunkBlock {
+	Code(CodeBlock),
+	Text(TextBlock),
+	Document(DocumentBlock),
+}
+
+/// Table a block is stored in
+pub fn table_for(block: &AnchoredBlock) -> &'static str {
+	match block {
+		AnchoredBlock::Code(_) => "code_blocks",
+		AnchoredBlock::Text(_) => "text_blocks",
+		AnchoredBlock::Document(_) => "document_blocks",
+	}
+}
+
+pub fn encode(block: &AnchoredBlock, embedding: &[f32]) -> Result<Vec<u8>> {
+	let block = match block.clone() {
+		AnchoredBlock::Code(mut block) => {
+			block.distance = None;
+			ChunkBlock::Code(block)
+		}
+		AnchoredBlock::Text(mut block) => {
+			block.distance = None;
+			ChunkBlock::Text(block)
+		}
+		AnchoredBlock::Document(mut block) => {
+			block.distance = None;
+			ChunkBlock::Document(block)
+		}
+	};
+	let json = serde_json::to_vec(&block)?;
+
+	let mut data = Vec::with_capacity(CHUNK_MAGIC.len() + 4 + json.len() + embedding.len() * 4);
+	data.extend_from_slice(CHUNK_MAGIC);
+	data.extend_from_slice(&(json.len() as u32).to_le_bytes());
+	data.extend_from_slice(&json);
+	for value in embedding {
+		data.extend_from_slice(&value.to_le_bytes());
+	}
+	Ok(data)
+}
+
+pub fn decode(data: &[u8]) -> Result<(AnchoredBlock, Vec<f32>)> {
+	let invalid = || anyhow::anyhow!("Invalid cache chunk");
+
+	let rest = data.strip_prefix(CHUNK_MAGIC).ok_or_else(invalid)?;
+	if rest.len() < 4 {
+		return Err(invalid());
+	}
+	let (len, rest) = rest.split_at(4);
+	let len = u32::from_le_bytes(len.try_into().map_err(|_| invalid())?) as usize;
+	if rest.len() < len || (rest.len() - len) % 4 != 0 {
+		return Err(invalid());
+	}
+	let (json, embedding) = rest.split_at(len);
+
+	let block = match serde_json::from_slice(json)? {
+		ChunkBlock::Code(block) => AnchoredBlock::Code(block),
+		ChunkBlock::Text(block) => AnchoredBlock::Text(block),
+		ChunkBlock::Document(block) => AnchoredBlock::Document(block),
+	};
+	let embedding = embedding
+		.chunks_exact(4)
+		.map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+		.collect();
+	Ok((block, embedding))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_chunk_round_trip() {
+		let block = AnchoredBlock::Text(TextBlock {
+			path: "notes.txt".to_string(),
+			language: "text".to_string(),
+			content: "hello".to_string(),
+			start_line: 3,
+			end_line: 4,
+			hash: "abc123".to_string(),
+			provenance: None,
+			distance: Some(0.5),
+		});
+		let data = encode(&block, &[0.25, -1.5, 3.0]).unwrap();
+		let (decoded, embedding) = decode(&data).unwrap();
+
+		assert_eq!(table_for(&decoded), "text_blocks");
+		assert_eq!(decoded.content(), "hello");
+		assert_eq!(decoded.lines(), (3, 4));
+		assert_eq!(embedding, vec![0.25, -1.5, 3.0]);
+		match decoded {
+			AnchoredBlock::Text(block) => assert!(block.distance.is_none()),
+			_ => panic!("expected a text block"),
+		}
+
+		assert!(decode(&data[..data.len() - 1]).is_err());
+		assert!(decode(b"nope").is_err());
+	}
+}
@@ -0,0 +1,158 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Client-side encryption of cache objects
+//!
+//! Chunks and manifests are sealed with ChaCha20-Poly1305 under a key derived
+//! from a passphrase, so the remote only ever sees ciphertext. Chunk names are
+//! keyed hashes, which keeps block hashes (and thus which files exist) private
+//! while still letting every machine with the passphrase compute the same name.
+
+use anyhow::Result;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use ring::{hmac, pbkdf2};
+use std::num::NonZeroU32;
+
+/// Prefix of every sealed object
+const SEALED_MAGIC: &[u8] = b"OCE1";
+
+const KDF_SALT: &[u8] = b"octocode-index-cache";
+const KDF_ITERATIONS: u32 = 100_000;
+
+pub struct Cipher {
+	key: LessSafeKey,
+	name_key: hmac::Key,
+	rng: SystemRandom,
+}
+
+impl Cipher {
+	pub fn from_passphrase(passphrase: &str) -> Result<Self> {
+		if passphrase.is_empty() {
+			return Err(anyhow::anyhow!("Cache encryption passphrase is empty"));
+		}
+
+		let mut material = [0u8; 64];
+		pbkdf2::derive(
+			pbkdf2::PBKDF2_HMAC_SHA256,
+			NonZeroU32::new(KDF_ITERATIONS).expect("non-zero iterations"),
+			KDF_SALT,
+			passphrase.as_bytes(),
+			&mut material,
+		);
+		let (aead_key, name_key) = material.split_at(32);
+
+		let key = UnboundKey::new(&CHACHA20_POLY1305, aead_key)
+			.map_err(|_| anyhow::anyhow!("Failed to derive cache encryption key"))?;
+		Ok(Self {
+			key: LessSafeKey::new(key),
+			name_key: hmac::Key::new(hmac::HMAC_SHA256, name_key),
+			rng: SystemRandom::new(),
+		})
+	}
+
+	/// Encrypt and authenticate an object
+	pub fn seal(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+		let mut nonce = [0u8; NONCE_LEN];
+		self.rng
+			.fill(&mut nonce)
+			.map_err(|_| anyhow::anyhow!("Failed to generate nonce"))?;
+
+		let mut in_out = plaintext.to_vec();
+		self.key
+			.seal_in_place_append_tag(
+				Nonce::assume_unique_for_key(nonce),
+				Aad::empty(),
+				&mut in_out,
+			)
+			.map_err(|_| anyhow::anyhow!("Failed to encrypt cache object"))?;
+
+		let mut sealed = Vec::with_capacity(SEALED_MAGIC.len() + NONCE_LEN + in_out.len());
+		sealed.extend_from_slice(SEALED_MAGIC);
+		sealed.extend_from_slice(&nonce);
+		sealed.extend_from_slice(&in_out);
+		Ok(sealed)
+	}
+
+	/// Decrypt an object produced by [`Self::seal`]
+	pub fn open(&self, sealed: &[u8]) -> Result<Vec<u8>> {
+		if !is_sealed(sealed) || sealed.len() < SEALED_MAGIC.len() + NONCE_LEN {
+			return Err(anyhow::anyhow!(
+				"Cache object is not encrypted; disable [cache] encrypt to read this cache"
+			));
+		}
+		let (nonce, ciphertext) = sealed[SEALED_MAGIC.len()..].split_at(NONCE_LEN);
+		let nonce = Nonce::try_assume_unique_for_key(nonce)
+			.map_err(|_| anyhow::anyhow!("Invalid nonce in cache object"))?;
+
+		let mut in_out = ciphertext.to_vec();
+		let plaintext = self
+			.key
+			.open_in_place(nonce, Aad::empty(), &mut in_out)
+			.map_err(|_| {
+				anyhow::anyhow!(
+					"Failed to decrypt cache object: wrong passphrase or corrupted data"
+				)
+			})?;
+		Ok(plaintext.to_vec())
+	}
+
+	/// Keyed, deterministic name for an object
+	pub fn object_name(&self, name: &str) -> String {
+		to_hex(hmac::sign(&self.name_key, name.as_bytes()).as_ref())
+	}
+}
+
+/// Whether an object was written by an encrypting cache
+pub fn is_sealed(data: &[u8]) -> bool {
+	data.starts_with(SEALED_MAGIC)
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+	bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_seal_round_trip_and_wrong_passphrase() {
+		let cipher = Cipher::from_passphrase("correct horse").unwrap();
+		let sealed = cipher.seal(b"chunk payload").unwrap();
+		assert!(is_sealed(&sealed));
+		assert!(!sealed.windows(13).any(|w| w == b"chunk payload"));
+		assert_eq!(cipher.open(&sealed).unwrap(), b"chunk payload");
+
+		let other = Cipher::from_passphrase("battery staple").unwrap();
+		assert!(other.open(&sealed).is_err());
+		assert!(cipher.open(b"plain chunk").is_err());
+	}
+
+	#[test]
+	fn test_object_names_are_deterministic_per_passphrase() {
+		let a = Cipher::from_passphrase("secret").unwrap();
+		let b = Cipher::from_passphrase("secret").unwrap();
+		let c = Cipher::from_passphrase("other").unwrap();
+		assert_eq!(
+			a.object_name("code_blocks:abc"),
+			b.object_name("code_blocks:abc")
+		);
+		assert_ne!(
+			a.object_name("code_blocks:abc"),
+			c.object_name("code_blocks:abc")
+		);
+		assert_eq!(a.object_name("x").len(), 64);
+	}
+}
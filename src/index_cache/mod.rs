@@ -0,0 +1,454 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Remote index cache
+//!
+//! The index is shared as content-addressed chunks, one per block with its
+//! embedding, plus a manifest per commit listing the chunks that make up the
+//! index at that commit. Pushing uploads only chunks the previous manifest does
+//! not already reference; pulling downloads only blocks missing locally, so CI
+//! runs and teammates never transfer or re-embed content they already have.
+//!
+//! Remote layout, under a namespace derived from the embedding models (vectors
+//! from different models must never mix):
+//!
+//! ```text
+//! <namespace>/chunks/<chunk id>
+//! <namespace>/manifests/<commit or ref>
+//! <namespace>/latest
+//! ```
+
+pub mod archive;
+pub mod chunk;
+pub mod crypto;
+pub mod remote;
+pub mod s3;
+
+use anyhow::Result;
+use futures::stream::{self, StreamExt, TryStreamExt};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+
+use crate::config::Config;
+use crate::store::Store;
+use crypto::Cipher;
+use remote::Remote;
+
+/// Block tables shared through the cache. GraphRAG is rebuilt locally and
+/// memories never leave the machine.
+pub const CACHED_TABLES: [&str; 3] = ["code_blocks", "text_blocks", "document_blocks"];
+
+const MANIFEST_VERSION: u32 = 1;
+
+/// Blocks read from or written to the store at a time
+const TRANSFER_BATCH_SIZE: usize = 500;
+
+/// Hash identifying the blocks in the index regardless of row order, ids and
+/// vectors; equal hashes mean two indexes hold the same blocks
+pub async fn manifest_hash(store: &Store) -> Result<String> {
+	let mut hasher = Sha256::new();
+	for table in CACHED_TABLES {
+		let mut hashes = store.get_all_block_hashes(table).await?;
+		hashes.sort();
+		hashes.dedup();
+		for hash in hashes {
+			hasher.update(format!("{}:{}\n", table, hash).as_bytes());
+		}
+	}
+	Ok(format!("{:x}", hasher.finalize()))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Manifest {
+	pub version: u32,
+	/// Commit the indexed content corresponds to
+	pub commit: String,
+	pub entries: Vec<ManifestEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ManifestEntry {
+	pub table: String,
+	pub hash: String,
+	pub chunk: String,
+}
+
+#[derive(Debug, Default)]
+pub struct PushSummary {
+	pub manifest: String,
+	pub blocks: usize,
+	pub uploaded: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct PullSummary {
+	pub manifest: String,
+	pub commit: String,
+	pub blocks: usize,
+	pub downloaded: usize,
+	pub removed: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct CacheStatus {
+	pub namespace: String,
+	pub local_blocks: usize,
+	/// `None` when nothing was pushed yet
+	pub remote: Option<RemoteStatus>,
+}
+
+#[derive(Debug, Default)]
+pub struct RemoteStatus {
+	pub commit: String,
+	pub blocks: usize,
+	/// Remote blocks a pull would download
+	pub missing_locally: usize,
+	/// Local blocks a push would upload
+	pub missing_remotely: usize,
+}
+
+pub struct IndexCache {
+	remote: Remote,
+	cipher: Option<Cipher>,
+	namespace: String,
+	concurrency: usize,
+}
+
+impl IndexCache {
+	pub fn from_config(config: &Config) -> Result<Self> {
+		let url = config.cache.url.as_deref().ok_or_else(|| {
+			anyhow::anyhow!("No remote cache configured: set [cache] url in the config")
+		})?;
+
+		let cipher = if config.cache.encrypt {
+			let passphrase = std::env::var("OCTOCODE_CACHE_KEY").map_err(|_| {
+				anyhow::anyhow!("OCTOCODE_CACHE_KEY is required when [cache] encrypt is enabled")
+			})?;
+			Some(Cipher::from_passphrase(&passphrase)?)
+		} else {
+			None
+		};
+
+		Ok(Self {
+			remote: Remote::open(url)?,
+			cipher,
+			namespace: namespace(&config.embedding.code_model, &config.embedding.text_model),
+			concurrency: config.cache.concurrency.max(1),
+		})
+	}
+
+	pub fn namespace(&self) -> &str {
+		&self.namespace
+	}
+
+	/// Upload the local index as the manifest for `reference`, which also
+	/// becomes the latest manifest
+	pub async fn push(&self, store: &Store, reference: &str, commit: &str) -> Result<PushSummary> {
+		let local = self.local_entries(store).await?;
+
+		let known: HashSet<String> = match self.read_manifest(&self.latest_key()).await? {
+			Some(previous) => previous.entries.into_iter().map(|e| e.chunk).collect(),
+			None => HashSet::new(),
+		};
+
+		let mut uploaded = 0;
+		for table in CACHED_TABLES {
+			let missing: Vec<&ManifestEntry> = local
+				.iter()
+				.filter(|entry| entry.table == table && !known.contains(&entry.chunk))
+				.collect();
+
+			for batch in missing.chunks(TRANSFER_BATCH_SIZE) {
+				let hashes: Vec<String> = batch.iter().map(|entry| entry.hash.clone()).collect();
+				let chunk_ids: HashMap<&str, &str> = batch
+					.iter()
+					.map(|entry| (entry.hash.as_str(), entry.chunk.as_str()))
+					.collect();
+
+				let mut seen = HashSet::new();
+				let mut objects = Vec::new();
+				for (block, embedding) in store.get_blocks_with_embeddings(table, &hashes).await? {
+					let hash = block.hash().to_string();
+					let Some(chunk_id) = chunk_ids.get(hash.as_str()) else {
+						continue;
+					};
+					if !seen.insert(hash) {
+						continue;
+					}
+					let data = self.seal(chunk::encode(&block, &embedding)?)?;
+					objects.push((self.chunk_key(chunk_id), data));
+				}
+
+				uploaded += objects.len();
+				stream::iter(objects)
+					.map(|(key, data)| async move { self.remote.put(&key, data).await })
+					.buffer_unordered(self.concurrency)
+					.try_collect::<Vec<_>>()
+					.await?;
+			}
+		}
+
+		let manifest = Manifest {
+			version: MANIFEST_VERSION,
+			commit: commit.to_string(),
+			entries: local,
+		};
+		let data = self.seal(serde_json::to_vec(&manifest)?)?;
+		self.remote
+			.put(&self.manifest_key(reference), data.clone())
+			.await?;
+		self.remote.put(&self.latest_key(), data).await?;
+
+		Ok(PushSummary {
+			manifest: reference.to_string(),
+			blocks: manifest.entries.len(),
+			uploaded,
+		})
+	}
+
+	/// Make the local index match a manifest: download missing blocks, drop
+	/// blocks the manifest does not list, and record the manifest's commit so
+	/// the next `octocode index` only processes files changed since then.
+	///
+	/// Without a reference, or when its manifest does not exist, the latest
+	/// manifest is used.
+	pub async fn pull(&self, store: &Store, reference: Option<&str>) -> Result<PullSummary> {
+		let (name, manifest) = self.find_manifest(reference).await?.ok_or_else(|| {
+			anyhow::anyhow!("Remote cache has no index yet: run 'octocode cache push' first")
+		})?;
+
+		let mut local: HashMap<&str, HashSet<String>> = HashMap::new();
+		for table in CACHED_TABLES {
+			local.insert(
+				table,
+				store
+					.get_all_block_hashes(table)
+					.await?
+					.into_iter()
+					.collect(),
+			);
+		}
+
+		let missing: Vec<&ManifestEntry> = manifest
+			.entries
+			.iter()
+			.filter(|entry| {
+				local
+					.get(entry.table.as_str())
+					.is_some_and(|hashes| !hashes.contains(&entry.hash))
+			})
+			.collect();
+
+		let mut downloaded = 0;
+		for batch in missing.chunks(TRANSFER_BATCH_SIZE) {
+			let blocks = stream::iter(batch.iter().copied())
+				.map(|entry| async move { self.fetch_chunk(entry).await })
+				.buffer_unordered(self.concurrency)
+				.try_collect::<Vec<_>>()
+				.await?;
+			downloaded += blocks.len();
+			store.store_blocks_with_embeddings(blocks).await?;
+		}
+
+		let mut removed = 0;
+		for table in CACHED_TABLES {
+			let wanted: HashSet<&str> = manifest
+				.entries
+				.iter()
+				.filter(|entry| entry.table == table)
+				.map(|entry| entry.hash.as_str())
+				.collect();
+			let stale: Vec<String> = local[table]
+				.iter()
+				.filter(|hash| !wanted.contains(hash.as_str()))
+				.cloned()
+				.collect();
+			if !stale.is_empty() {
+				removed += stale.len();
+				store.remove_blocks_by_hashes(&stale, table).await?;
+			}
+		}
+
+		if !manifest.commit.is_empty() {
+			store.store_git_metadata(&manifest.commit).await?;
+		}
+
+		Ok(PullSummary {
+			manifest: name,
+			commit: manifest.commit,
+			blocks: manifest.entries.len(),
+			downloaded,
+			removed,
+		})
+	}
+
+	/// Compare the local index with the latest manifest
+	pub async fn status(&self, store: &Store) -> Result<CacheStatus> {
+		let local = self.local_entries(store).await?;
+		let remote = self
+			.read_manifest(&self.latest_key())
+			.await?
+			.map(|manifest| {
+				let remote: HashSet<&ManifestEntry> = manifest.entries.iter().collect();
+				let local_set: HashSet<&ManifestEntry> = local.iter().collect();
+				RemoteStatus {
+					commit: manifest.commit.clone(),
+					blocks: manifest.entries.len(),
+					missing_locally: remote.difference(&local_set).count(),
+					missing_remotely: local_set.difference(&remote).count(),
+				}
+			});
+
+		Ok(CacheStatus {
+			namespace: self.namespace.clone(),
+			local_blocks: local.len(),
+			remote,
+		})
+	}
+
+	async fn local_entries(&self, store: &Store) -> Result<Vec<ManifestEntry>> {
+		let mut entries = Vec::new();
+		for table in CACHED_TABLES {
+			let hashes: HashSet<String> = store
+				.get_all_block_hashes(table)
+				.await?
+				.into_iter()
+				.collect();
+			let mut hashes: Vec<String> = hashes.into_iter().collect();
+			hashes.sort();
+			entries.extend(hashes.into_iter().map(|hash| ManifestEntry {
+				table: table.to_string(),
+				chunk: self.chunk_id(table, &hash),
+				hash,
+			}));
+		}
+		Ok(entries)
+	}
+
+	async fn find_manifest(&self, reference: Option<&str>) -> Result<Option<(String, Manifest)>> {
+		if let Some(reference) = reference {
+			if let Some(manifest) = self.read_manifest(&self.manifest_key(reference)).await? {
+				return Ok(Some((reference.to_string(), manifest)));
+			}
+		}
+		Ok(self
+			.read_manifest(&self.latest_key())
+			.await?
+			.map(|manifest| ("latest".to_string(), manifest)))
+	}
+
+	async fn read_manifest(&self, key: &str) -> Result<Option<Manifest>> {
+		let Some(data) = self.remote.get(key).await? else {
+			return Ok(None);
+		};
+		let manifest: Manifest = serde_json::from_slice(&self.open(data)?)
+			.map_err(|e| anyhow::anyhow!("Invalid cache manifest {}: {}", key, e))?;
+		if manifest.version != MANIFEST_VERSION {
+			return Err(anyhow::anyhow!(
+				"Cache manifest {} has unsupported version {}",
+				key,
+				manifest.version
+			));
+		}
+		Ok(Some(manifest))
+	}
+
+	async fn fetch_chunk(
+		&self,
+		entry: &ManifestEntry,
+	) -> Result<(crate::store::AnchoredBlock, Vec<f32>)> {
+		let data = self
+			.remote
+			.get(&self.chunk_key(&entry.chunk))
+			.await?
+			.ok_or_else(|| anyhow::anyhow!("Cache chunk {} is missing", entry.chunk))?;
+		let (block, embedding) = chunk::decode(&self.open(data)?)?;
+
+		if chunk::table_for(&block) != entry.table || block.hash() != entry.hash {
+			return Err(anyhow::anyhow!(
+				"Cache chunk {} does not match its manifest entry",
+				entry.chunk
+			));
+		}
+		Ok((block, embedding))
+	}
+
+	/// Deterministic chunk name; keyed when encrypting so names reveal nothing
+	fn chunk_id(&self, table: &str, hash: &str) -> String {
+		let name = format!("{}:{}", table, hash);
+		match &self.cipher {
+			Some(cipher) => cipher.object_name(&name),
+			None => format!("{:x}", Sha256::digest(name.as_bytes())),
+		}
+	}
+
+	fn seal(&self, data: Vec<u8>) -> Result<Vec<u8>> {
+		match &self.cipher {
+			Some(cipher) => cipher.seal(&data),
+			None => Ok(data),
+		}
+	}
+
+	fn open(&self, data: Vec<u8>) -> Result<Vec<u8>> {
+		match &self.cipher {
+			Some(cipher) => cipher.open(&data),
+			None if crypto::is_sealed(&data) => Err(anyhow::anyhow!(
+				"Remote cache is encrypted: enable [cache] encrypt and set OCTOCODE_CACHE_KEY"
+			)),
+			None => Ok(data),
+		}
+	}
+
+	fn chunk_key(&self, chunk_id: &str) -> String {
+		format!("{}/chunks/{}", self.namespace, chunk_id)
+	}
+
+	fn manifest_key(&self, reference: &str) -> String {
+		format!("{}/manifests/{}", self.namespace, reference)
+	}
+
+	fn latest_key(&self) -> String {
+		format!("{}/latest", self.namespace)
+	}
+}
+
+/// Cache namespace for a pair of embedding models
+fn namespace(code_model: &str, text_model: &str) -> String {
+	let digest = Sha256::digest(format!("{}|{}", code_model, text_model).as_bytes());
+	format!("{:x}", digest)[..16].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_namespace_depends_on_both_models() {
+		let a = namespace("voyage:voyage-code-3", "voyage:voyage-3.5-lite");
+		assert_eq!(a.len(), 16);
+		assert_eq!(
+			a,
+			namespace("voyage:voyage-code-3", "voyage:voyage-3.5-lite")
+		);
+		assert_ne!(
+			a,
+			namespace("voyage:voyage-code-3", "jina:jina-embeddings-v3")
+		);
+		assert_ne!(
+			a,
+			namespace("voyage:voyage-3.5-lite", "voyage:voyage-code-3")
+		);
+	}
+}
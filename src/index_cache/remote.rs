@@ -0,0 +1,140 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Object storage backends for the index cache
+
+use anyhow::Result;
+use std::path::PathBuf;
+
+use super::s3::S3Bucket;
+
+enum Backend {
+	File(PathBuf),
+	/// Plain HTTP object store (GET/PUT), also used for the GCS XML API
+	Http {
+		base: String,
+		token: Option<String>,
+	},
+	S3(S3Bucket),
+}
+
+/// Key/value object store behind a cache URL
+pub struct Remote {
+	backend: Backend,
+	client: reqwest::Client,
+}
+
+impl Remote {
+	/// Open `s3://bucket/prefix`, `gs://bucket/prefix`, `http(s)://host/prefix`
+	/// or `file:///path`
+	pub fn open(url: &str) -> Result<Self> {
+		let (scheme, rest) = url
+			.split_once("://")
+			.ok_or_else(|| anyhow::anyhow!("Invalid cache URL '{}'", url))?;
+		let (bucket, prefix) = rest.split_once('/').unwrap_or((rest, ""));
+
+		let backend = match scheme {
+			"file" => Backend::File(PathBuf::from(rest)),
+			"http" | "https" => Backend::Http {
+				base: url.trim_end_matches('/').to_string(),
+				token: std::env::var("OCTOCODE_CACHE_TOKEN").ok(),
+			},
+			"gs" => {
+				let token = std::env::var("GOOGLE_OAUTH_ACCESS_TOKEN").map_err(|_| {
+					anyhow::anyhow!("GOOGLE_OAUTH_ACCESS_TOKEN is required for a gs:// cache")
+				})?;
+				Backend::Http {
+					base: format!(
+						"https://storage.googleapis.com/{}/{}",
+						bucket,
+						prefix.trim_matches('/')
+					)
+					.trim_end_matches('/')
+					.to_string(),
+					token: Some(token),
+				}
+			}
+			"s3" => Backend::S3(S3Bucket::from_env(bucket, prefix)?),
+			_ => {
+				return Err(anyhow::anyhow!(
+					"Unsupported cache URL scheme '{}': use s3://, gs://, https:// or file://",
+					scheme
+				))
+			}
+		};
+		if bucket.is_empty() && scheme != "file" {
+			return Err(anyhow::anyhow!("Cache URL '{}' has no bucket or host", url));
+		}
+
+		Ok(Self {
+			backend,
+			client: reqwest::Client::new(),
+		})
+	}
+
+	/// Read an object; `None` when it does not exist
+	pub async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+		match &self.backend {
+			Backend::File(root) => match tokio::fs::read(root.join(key)).await {
+				Ok(data) => Ok(Some(data)),
+				Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+				Err(e) => Err(e.into()),
+			},
+			Backend::Http { base, token } => {
+				let mut request = self.client.get(format!("{}/{}", base, key));
+				if let Some(token) = token {
+					request = request.bearer_auth(token);
+				}
+				let response = request.send().await?;
+				if response.status() == reqwest::StatusCode::NOT_FOUND {
+					return Ok(None);
+				}
+				if !response.status().is_success() {
+					return Err(anyhow::anyhow!("GET {} failed: {}", key, response.status()));
+				}
+				Ok(Some(response.bytes().await?.to_vec()))
+			}
+			Backend::S3(bucket) => bucket.get(&self.client, key).await,
+		}
+	}
+
+	/// Write (or overwrite) an object
+	pub async fn put(&self, key: &str, data: Vec<u8>) -> Result<()> {
+		match &self.backend {
+			Backend::File(root) => {
+				let path = root.join(key);
+				if let Some(parent) = path.parent() {
+					tokio::fs::create_dir_all(parent).await?;
+				}
+				// Write then rename so readers never see a partial object
+				let tmp = path.with_extension("tmp");
+				tokio::fs::write(&tmp, data).await?;
+				tokio::fs::rename(&tmp, &path).await?;
+				Ok(())
+			}
+			Backend::Http { base, token } => {
+				let mut request = self.client.put(format!("{}/{}", base, key)).body(data);
+				if let Some(token) = token {
+					request = request.bearer_auth(token);
+				}
+				let response = request.send().await?;
+				if !response.status().is_success() {
+					return Err(anyhow::anyhow!("PUT {} failed: {}", key, response.status()));
+				}
+				Ok(())
+			}
+			Backend::S3(bucket) => bucket.put(&self.client, key, data).await,
+		}
+	}
+}
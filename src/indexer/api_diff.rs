@@ -0,0 +1,389 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Public API differences between two revisions
+//!
+//! Signatures are extracted from both sides of every file changed between the
+//! revisions. Public symbols that disappeared, changed their signature or
+//! moved to another file are breaking changes; the call graph of the current
+//! index tells which internal functions call them.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use crate::indexer::git_utils::GitUtils;
+use crate::indexer::graphrag::calls::{self, FunctionRef};
+use crate::indexer::graphrag::types::CodeGraph;
+use crate::indexer::signature_extractor::{
+	extract_signatures_from_contents, FileSignature, SignatureItem,
+};
+
+/// Languages with a notion of public API the detector understands
+const API_LANGUAGES: &[&str] = &[
+	"rust",
+	"go",
+	"python",
+	"javascript",
+	"typescript",
+	"php",
+	"cpp",
+	"ruby",
+];
+
+/// Public symbol at one revision
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ApiSymbol {
+	pub path: String,
+	pub kind: String,
+	pub name: String,
+	/// Declaration with whitespace collapsed; functions stop before their body
+	pub signature: String,
+	/// 1-indexed line
+	pub line: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+	Removed,
+	Changed,
+	Moved,
+}
+
+impl ChangeKind {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::Removed => "removed",
+			Self::Changed => "changed",
+			Self::Moved => "moved",
+		}
+	}
+}
+
+/// Breaking change to a public symbol
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiChange {
+	pub change: ChangeKind,
+	/// The symbol as it was at the older revision
+	pub symbol: ApiSymbol,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub new_signature: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub new_path: Option<String>,
+	/// Functions of the current index calling the symbol
+	pub affected_callers: Vec<FunctionRef>,
+}
+
+/// Breaking public API changes between two revisions of the repository
+pub fn api_diff(repo_path: &Path, from: &str, to: &str) -> Result<Vec<ApiChange>> {
+	let mut old_symbols = Vec::new();
+	let mut new_symbols = Vec::new();
+
+	for file in GitUtils::get_changed_files_between_commits(repo_path, from, to)? {
+		for (revision, symbols) in [(from, &mut old_symbols), (to, &mut new_symbols)] {
+			let Some(contents) = GitUtils::get_file_at_commit(repo_path, revision, &file)? else {
+				continue;
+			};
+			if let Some(signatures) = extract_signatures_from_contents(&file, &contents)? {
+				symbols.extend(public_symbols(&signatures, &contents));
+			}
+		}
+	}
+
+	Ok(diff_symbols(&old_symbols, &new_symbols))
+}
+
+/// Public symbols among a file's signatures
+pub fn public_symbols(file: &FileSignature, contents: &str) -> Vec<ApiSymbol> {
+	if !API_LANGUAGES.contains(&file.language.as_str()) {
+		return Vec::new();
+	}
+	let lines: Vec<&str> = contents.lines().collect();
+
+	file.signatures
+		.iter()
+		.filter(|item| {
+			!["import", "export_statement", "use_declaration", "comment"]
+				.iter()
+				.any(|skipped| item.kind.contains(skipped))
+		})
+		.filter(|item| {
+			let first_line = lines.get(item.start_line).copied().unwrap_or_default();
+			is_public(&file.language, item, first_line)
+		})
+		.map(|item| ApiSymbol {
+			path: file.path.clone(),
+			kind: item.kind.clone(),
+			name: item.name.clone(),
+			signature: declaration(&file.language, item),
+			line: item.start_line + 1,
+		})
+		.collect()
+}
+
+/// Whether a declaration is visible outside its module or package
+fn is_public(language: &str, item: &SignatureItem, first_line: &str) -> bool {
+	is_public_declaration(language, &item.name, first_line)
+}
+
+/// Whether the declaration of `name`, starting on `first_line`, is part of
+/// the public API by the language's visibility rules
+pub fn is_public_declaration(language: &str, name: &str, first_line: &str) -> bool {
+	let line = first_line.trim_start();
+	match language {
+		"rust" => line.starts_with("pub ") || line.starts_with("pub\t"),
+		"go" => name.starts_with(|c: char| c.is_uppercase()),
+		"python" => !name.starts_with('_') || (name.starts_with("__") && name.ends_with("__")),
+		"javascript" | "typescript" => line.starts_with("export"),
+		"php" | "cpp" => {
+			// Modifiers come before the name
+			let before_name = line.split(name).next().unwrap_or_default();
+			!before_name
+				.split_whitespace()
+				.any(|word| matches!(word, "private" | "protected" | "private:" | "protected:"))
+		}
+		_ => true,
+	}
+}
+
+/// Comparable declaration text. Functions, classes and modules are compared by
+/// their header so body edits don't count; data types by their whole text.
+fn declaration(language: &str, item: &SignatureItem) -> String {
+	let text = item.signature.as_str();
+	let header_only = matches!(
+		item.kind.as_str(),
+		"function" | "method" | "class" | "module"
+	);
+
+	let text = if !header_only {
+		text
+	} else if language == "python" {
+		// Up to the colon closing the `def`/`class` line, even across lines
+		let mut end = text.len();
+		let mut offset = 0;
+		for line in text.split_inclusive('\n') {
+			offset += line.len();
+			if line.trim_end().ends_with(':') {
+				end = offset;
+				break;
+			}
+		}
+		&text[..end]
+	} else {
+		text.split(['{', ';']).next().unwrap_or(text)
+	};
+
+	text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Removed, changed and moved symbols between the old and new public API
+pub fn diff_symbols(old: &[ApiSymbol], new: &[ApiSymbol]) -> Vec<ApiChange> {
+	type Key<'a> = (&'a str, &'a str, &'a str);
+	fn group(symbols: &[ApiSymbol]) -> HashMap<Key<'_>, Vec<&ApiSymbol>> {
+		let mut groups: HashMap<Key<'_>, Vec<&ApiSymbol>> = HashMap::new();
+		for symbol in symbols {
+			groups
+				.entry((
+					symbol.path.as_str(),
+					symbol.kind.as_str(),
+					symbol.name.as_str(),
+				))
+				.or_default()
+				.push(symbol);
+		}
+		groups
+	}
+	let old_groups = group(old);
+	let new_groups = group(new);
+
+	let mut changes = Vec::new();
+	for (key, old_group) in &old_groups {
+		let record = |change, symbol: &ApiSymbol, new_signature, new_path| ApiChange {
+			change,
+			symbol: symbol.clone(),
+			new_signature,
+			new_path,
+			affected_callers: Vec::new(),
+		};
+
+		if let Some(new_group) = new_groups.get(key) {
+			let old_signatures: HashSet<&str> =
+				old_group.iter().map(|s| s.signature.as_str()).collect();
+			let new_signatures: HashSet<&str> =
+				new_group.iter().map(|s| s.signature.as_str()).collect();
+			// The closest replacement: a signature the old side didn't have
+			let replacement = new_group
+				.iter()
+				.find(|s| !old_signatures.contains(s.signature.as_str()))
+				.unwrap_or(&new_group[0]);
+
+			for symbol in old_group {
+				if !new_signatures.contains(symbol.signature.as_str()) {
+					changes.push(record(
+						ChangeKind::Changed,
+						symbol,
+						Some(replacement.signature.clone()),
+						None,
+					));
+				}
+			}
+			continue;
+		}
+
+		// Same kind and name showing up in a file that didn't have it before
+		let (path, kind, name) = *key;
+		let moved_to =
+			new.iter()
+				.find(|s| {
+					s.path != path
+						&& s.kind == kind && s.name == name
+						&& !old_groups.contains_key(&(s.path.as_str(), kind, name))
+				})
+				.map(|s| s.path.clone());
+
+		for symbol in old_group {
+			changes.push(match &moved_to {
+				Some(new_path) => record(ChangeKind::Moved, symbol, None, Some(new_path.clone())),
+				None => record(ChangeKind::Removed, symbol, None, None),
+			});
+		}
+	}
+
+	changes.sort_by(|a, b| {
+		a.symbol
+			.path
+			.cmp(&b.symbol.path)
+			.then(a.symbol.line.cmp(&b.symbol.line))
+	});
+	changes
+}
+
+/// Fill in the internal callers of changed functions from the functions of
+/// the GraphRAG graph
+pub fn attach_callers(changes: &mut [ApiChange], graph: &CodeGraph) {
+	for change in changes {
+		if !matches!(change.symbol.kind.as_str(), "function" | "method") {
+			continue;
+		}
+
+		let path = change.new_path.as_deref().unwrap_or(&change.symbol.path);
+		let functions = match change.change {
+			ChangeKind::Removed => Vec::new(),
+			ChangeKind::Changed | ChangeKind::Moved => {
+				calls::find_functions(graph, &format!("{}:{}", path, change.symbol.name))
+			}
+		};
+
+		change.affected_callers = if functions.is_empty() {
+			calls::callers_of_name(graph, &change.symbol.name)
+		} else {
+			let mut callers: Vec<FunctionRef> = functions
+				.iter()
+				.flat_map(|function| calls::callers(graph, function))
+				.collect();
+			callers.dedup();
+			callers
+		};
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn symbol(path: &str, name: &str, signature: &str) -> ApiSymbol {
+		ApiSymbol {
+			path: path.to_string(),
+			kind: "function".to_string(),
+			name: name.to_string(),
+			signature: signature.to_string(),
+			line: 1,
+		}
+	}
+
+	fn item(kind: &str, name: &str, signature: &str) -> SignatureItem {
+		SignatureItem {
+			kind: kind.to_string(),
+			name: name.to_string(),
+			signature: signature.to_string(),
+			description: None,
+			start_line: 0,
+			end_line: 0,
+		}
+	}
+
+	#[test]
+	fn test_diff_symbols() {
+		let old = vec![
+			symbol("src/a.rs", "parse", "pub fn parse(s: &str)"),
+			symbol("src/a.rs", "load", "pub fn load()"),
+			symbol("src/a.rs", "keep", "pub fn keep()"),
+			symbol("src/a.rs", "gone", "pub fn gone()"),
+		];
+		let new = vec![
+			symbol("src/a.rs", "parse", "pub fn parse(s: &str, strict: bool)"),
+			symbol("src/b.rs", "load", "pub fn load()"),
+			symbol("src/a.rs", "keep", "pub fn keep()"),
+		];
+
+		let changes = diff_symbols(&old, &new);
+		let summary: Vec<(&str, &str)> = changes
+			.iter()
+			.map(|c| (c.change.as_str(), c.symbol.name.as_str()))
+			.collect();
+		assert_eq!(changes.len(), 3);
+		assert!(summary.contains(&("changed", "parse")));
+		assert!(summary.contains(&("moved", "load")));
+		assert!(summary.contains(&("removed", "gone")));
+
+		let parse = changes.iter().find(|c| c.symbol.name == "parse").unwrap();
+		assert_eq!(
+			parse.new_signature.as_deref(),
+			Some("pub fn parse(s: &str, strict: bool)")
+		);
+	}
+
+	#[test]
+	fn test_visibility_and_declarations() {
+		let function = item(
+			"function",
+			"run",
+			"pub fn run(\n\targs: Args,\n) -> Result<()> {\n\tOk(())\n}",
+		);
+		assert!(is_public("rust", &function, "pub fn run("));
+		assert!(!is_public("rust", &function, "pub(crate) fn run("));
+		assert_eq!(
+			declaration("rust", &function),
+			"pub fn run( args: Args, ) -> Result<()>"
+		);
+
+		let method = item("function", "_helper", "def _helper(self):\n    pass");
+		assert!(!is_public("python", &method, "def _helper(self):"));
+		assert_eq!(declaration("python", &method), "def _helper(self):");
+
+		let private = item("method", "reset", "private function reset() {}");
+		assert!(!is_public(
+			"php",
+			&private,
+			"    private function reset() {}"
+		));
+		assert!(is_public(
+			"go",
+			&item("function", "Serve", ""),
+			"func Serve() {"
+		));
+	}
+}
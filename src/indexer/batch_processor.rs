@@ -19,16 +19,84 @@
 
 use crate::config::Config;
 use crate::embedding::count_tokens;
+use crate::embedding::policy::PathGuard;
+use crate::indexer::write_behind::PendingWrite;
+use crate::indexer::{blame, deterministic};
 use crate::mcp::logging::log_performance_metrics;
-use crate::store::{CodeBlock, DocumentBlock, Store, TextBlock};
+use crate::state::SharedState;
+use crate::store::{CodeBlock, DocumentBlock, Provenance, Store, TextBlock};
 use anyhow::Result;
 
+/// Blocks that `embedding.path_policies` allows sending to the configured model;
+/// the others are recorded as "path (model)" in the shared state
+fn apply_path_policies<T: Clone>(
+	blocks: &[T],
+	path: impl Fn(&T) -> &str,
+	is_code: bool,
+	config: &Config,
+	state: &SharedState,
+) -> Result<Vec<T>> {
+	if config.embedding.path_policies.is_empty() {
+		return Ok(blocks.to_vec());
+	}
+
+	let guard = PathGuard::from_config(config)?;
+	let mut violations = Vec::new();
+	let allowed = blocks
+		.iter()
+		.filter(|block| {
+			let path = path(block);
+			match guard.violation(path, is_code) {
+				Some(model) => {
+					violations.push(format!("{} ({})", path, model));
+					false
+				}
+				None => true,
+			}
+		})
+		.cloned()
+		.collect();
+
+	if !violations.is_empty() {
+		state.write().policy_violations.extend(violations);
+	}
+	Ok(allowed)
+}
+
 /// Process a batch of code blocks for embedding and storage
 pub async fn process_code_blocks_batch(
 	store: &Store,
 	blocks: &[CodeBlock],
 	config: &Config,
+	state: &SharedState,
 ) -> Result<()> {
+	embed_code_blocks_batch(blocks, config, state)
+		.await?
+		.persist(store)
+		.await
+}
+
+/// Embed a batch of code blocks, leaving storage to the caller
+pub async fn embed_code_blocks_batch(
+	blocks: &[CodeBlock],
+	config: &Config,
+	state: &SharedState,
+) -> Result<PendingWrite> {
+	let mut blocks = apply_path_policies(blocks, |b| &b.path, true, config, state)?;
+	if blocks.is_empty() {
+		return Ok(PendingWrite::Code(blocks, Vec::new()));
+	}
+	let provenance = Provenance::new(
+		&config.embedding.code_model,
+		deterministic::indexed_at(config),
+	);
+	for block in &mut blocks {
+		block.provenance = Some(provenance.clone());
+	}
+	if config.index.blame {
+		let project_dir = state.read().current_directory.clone();
+		blame::annotate(&mut blocks, &project_dir);
+	}
 	let start_time = std::time::Instant::now();
 	let contents: Vec<String> = blocks.iter().map(|b| b.content.clone()).collect();
 	let embeddings = crate::embedding::generate_embeddings_batch(
@@ -38,12 +106,40 @@ pub async fn process_code_blocks_batch(
 		crate::embedding::types::InputType::Document,
 	)
 	.await?;
-	store.store_code_blocks(blocks, &embeddings).await?;
 
 	let duration_ms = start_time.elapsed().as_millis() as u64;
 	log_performance_metrics("code_blocks_batch", duration_ms, blocks.len(), None);
 
-	Ok(())
+	Ok(PendingWrite::Code(blocks, embeddings))
+}
+
+/// Embed overview blocks of long code files with the code model and store them
+pub async fn process_file_summaries_batch(
+	store: &Store,
+	blocks: &[CodeBlock],
+	config: &Config,
+	state: &SharedState,
+) -> Result<()> {
+	let mut blocks = apply_path_policies(blocks, |b| &b.path, true, config, state)?;
+	if blocks.is_empty() {
+		return Ok(());
+	}
+	let provenance = Provenance::new(
+		&config.embedding.code_model,
+		deterministic::indexed_at(config),
+	);
+	for block in &mut blocks {
+		block.provenance = Some(provenance.clone());
+	}
+	let contents: Vec<String> = blocks.iter().map(|b| b.content.clone()).collect();
+	let embeddings = crate::embedding::generate_embeddings_batch(
+		contents,
+		true,
+		config,
+		crate::embedding::types::InputType::Document,
+	)
+	.await?;
+	store.store_file_summaries(&blocks, &embeddings).await
 }
 
 /// Process a batch of text blocks for embedding and storage
@@ -51,7 +147,35 @@ pub async fn process_text_blocks_batch(
 	store: &Store,
 	blocks: &[TextBlock],
 	config: &Config,
+	state: &SharedState,
 ) -> Result<()> {
+	embed_text_blocks_batch(blocks, config, state)
+		.await?
+		.persist(store)
+		.await
+}
+
+/// Embed a batch of text blocks, leaving storage to the caller
+pub async fn embed_text_blocks_batch(
+	blocks: &[TextBlock],
+	config: &Config,
+	state: &SharedState,
+) -> Result<PendingWrite> {
+	let mut blocks = apply_path_policies(blocks, |b| &b.path, false, config, state)?;
+	if blocks.is_empty() {
+		return Ok(PendingWrite::Text(blocks, Vec::new()));
+	}
+	let provenance = Provenance::new(
+		&config.embedding.text_model,
+		deterministic::indexed_at(config),
+	);
+	for block in &mut blocks {
+		block.provenance = Some(provenance.clone());
+	}
+	if config.index.blame {
+		let project_dir = state.read().current_directory.clone();
+		blame::annotate(&mut blocks, &project_dir);
+	}
 	let start_time = std::time::Instant::now();
 	let contents: Vec<String> = blocks.iter().map(|b| b.content.clone()).collect();
 	let embeddings = crate::embedding::generate_embeddings_batch(
@@ -61,12 +185,11 @@ pub async fn process_text_blocks_batch(
 		crate::embedding::types::InputType::Document,
 	)
 	.await?;
-	store.store_text_blocks(blocks, &embeddings).await?;
 
 	let duration_ms = start_time.elapsed().as_millis() as u64;
 	log_performance_metrics("text_blocks_batch", duration_ms, blocks.len(), None);
 
-	Ok(())
+	Ok(PendingWrite::Text(blocks, embeddings))
 }
 
 /// Process a batch of document blocks for embedding and storage
@@ -74,7 +197,35 @@ pub async fn process_document_blocks_batch(
 	store: &Store,
 	blocks: &[DocumentBlock],
 	config: &Config,
+	state: &SharedState,
 ) -> Result<()> {
+	embed_document_blocks_batch(blocks, config, state)
+		.await?
+		.persist(store)
+		.await
+}
+
+/// Embed a batch of document blocks, leaving storage to the caller
+pub async fn embed_document_blocks_batch(
+	blocks: &[DocumentBlock],
+	config: &Config,
+	state: &SharedState,
+) -> Result<PendingWrite> {
+	let mut blocks = apply_path_policies(blocks, |b| &b.path, false, config, state)?;
+	if blocks.is_empty() {
+		return Ok(PendingWrite::Document(blocks, Vec::new()));
+	}
+	let provenance = Provenance::new(
+		&config.embedding.text_model,
+		deterministic::indexed_at(config),
+	);
+	for block in &mut blocks {
+		block.provenance = Some(provenance.clone());
+	}
+	if config.index.blame {
+		let project_dir = state.read().current_directory.clone();
+		blame::annotate(&mut blocks, &project_dir);
+	}
 	let start_time = std::time::Instant::now();
 	let contents: Vec<String> = blocks
 		.iter()
@@ -93,12 +244,11 @@ pub async fn process_document_blocks_batch(
 		crate::embedding::types::InputType::Document,
 	)
 	.await?;
-	store.store_document_blocks(blocks, &embeddings).await?;
 
 	let duration_ms = start_time.elapsed().as_millis() as u64;
 	log_performance_metrics("document_blocks_batch", duration_ms, blocks.len(), None);
 
-	Ok(())
+	Ok(PendingWrite::Document(blocks, embeddings))
 }
 
 /// Check if a batch should be processed based on size and token limits
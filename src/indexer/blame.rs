@@ -0,0 +1,261 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Git blame enrichment of indexed blocks
+//!
+//! With `index.blame` on, each block's provenance records the most recent
+//! commit among those that last changed its lines, so searches can prefer
+//! actively maintained code (`--sort recency`) or one author's (`--author`).
+//! `git blame` runs once per file and batch. Files outside a repository, and
+//! lines not committed yet, have no blame.
+
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+
+use crate::store::{Blame, CodeBlock, DocumentBlock, Provenance, TextBlock};
+
+/// Commit `git blame` reports for lines not committed yet
+const UNCOMMITTED: &str = "0000000000000000000000000000000000000000";
+
+/// Blocks blame is recorded on and searched by
+pub trait Blamable {
+	fn path(&self) -> &str;
+	/// First and last line of the block, counting from 0
+	fn line_range(&self) -> (usize, usize);
+	fn provenance(&self) -> Option<&Provenance>;
+	fn provenance_mut(&mut self) -> Option<&mut Provenance>;
+
+	fn blame(&self) -> Option<&Blame> {
+		self.provenance().and_then(|p| p.blame.as_ref())
+	}
+}
+
+impl Blamable for CodeBlock {
+	fn path(&self) -> &str {
+		&self.path
+	}
+	fn line_range(&self) -> (usize, usize) {
+		(self.start_line, self.end_line)
+	}
+	fn provenance(&self) -> Option<&Provenance> {
+		self.provenance.as_ref()
+	}
+	fn provenance_mut(&mut self) -> Option<&mut Provenance> {
+		self.provenance.as_mut()
+	}
+}
+
+impl Blamable for TextBlock {
+	fn path(&self) -> &str {
+		&self.path
+	}
+	// Text chunks count lines from 1
+	fn line_range(&self) -> (usize, usize) {
+		(
+			self.start_line.saturating_sub(1),
+			self.end_line.saturating_sub(1),
+		)
+	}
+	fn provenance(&self) -> Option<&Provenance> {
+		self.provenance.as_ref()
+	}
+	fn provenance_mut(&mut self) -> Option<&mut Provenance> {
+		self.provenance.as_mut()
+	}
+}
+
+impl Blamable for DocumentBlock {
+	fn path(&self) -> &str {
+		&self.path
+	}
+	fn line_range(&self) -> (usize, usize) {
+		(self.start_line, self.end_line)
+	}
+	fn provenance(&self) -> Option<&Provenance> {
+		self.provenance.as_ref()
+	}
+	fn provenance_mut(&mut self) -> Option<&mut Provenance> {
+		self.provenance.as_mut()
+	}
+}
+
+/// Record the last commit changing each block in its provenance
+pub fn annotate<T: Blamable>(blocks: &mut [T], project_dir: &Path) {
+	let mut files: HashMap<String, Option<Vec<Option<Blame>>>> = HashMap::new();
+	for block in blocks.iter_mut() {
+		let lines = files
+			.entry(block.path().to_string())
+			.or_insert_with_key(|path| blame_file(project_dir, path));
+		let Some(lines) = lines else {
+			continue;
+		};
+		let (first, last) = block.line_range();
+		let blame = latest_change(lines, first, last);
+		if let Some(provenance) = block.provenance_mut() {
+			provenance.blame = blame;
+		}
+	}
+}
+
+/// Keep the blocks last changed by an author whose name or email contains
+/// `author`, ignoring case
+pub fn retain_author<T: Blamable>(blocks: &mut Vec<T>, author: &str) {
+	let author = author.to_lowercase();
+	blocks.retain(|block| {
+		block
+			.blame()
+			.is_some_and(|blame| blame.author.to_lowercase().contains(&author))
+	});
+}
+
+/// Most recently changed blocks first; blocks without blame follow in their
+/// current order
+pub fn sort_by_recency<T: Blamable>(blocks: &mut [T]) {
+	blocks.sort_by_key(|block| std::cmp::Reverse(block.blame().map(|blame| blame.committed_at)));
+}
+
+/// Blame of every line of a file, `None` where the file has no history
+fn blame_file(project_dir: &Path, path: &str) -> Option<Vec<Option<Blame>>> {
+	let file = project_dir.join(path);
+	// Run from the file's directory, so files of nested repositories resolve
+	let output = Command::new("git")
+		.args(["blame", "--line-porcelain", "--"])
+		.arg(file.file_name()?)
+		.current_dir(file.parent()?)
+		.output()
+		.ok()?;
+	if !output.status.success() {
+		return None;
+	}
+	Some(parse_line_porcelain(&String::from_utf8_lossy(
+		&output.stdout,
+	)))
+}
+
+/// Blame of each line from `git blame --line-porcelain` output
+fn parse_line_porcelain(output: &str) -> Vec<Option<Blame>> {
+	let mut lines = Vec::new();
+	let mut commit: Option<&str> = None;
+	let mut author = "";
+	let mut mail = "";
+	let mut committed_at: Option<DateTime<Utc>> = None;
+
+	for line in output.lines() {
+		if line.starts_with('\t') {
+			// The line's content ends its entry
+			let blame = commit
+				.filter(|commit| *commit != UNCOMMITTED)
+				.zip(committed_at)
+				.map(|(commit, committed_at)| Blame {
+					commit: commit.to_string(),
+					author: if mail.is_empty() {
+						author.to_string()
+					} else {
+						format!("{} {}", author, mail)
+					},
+					committed_at,
+				});
+			lines.push(blame);
+			commit = None;
+		} else if commit.is_none() {
+			commit = line.split_whitespace().next();
+			author = "";
+			mail = "";
+			committed_at = None;
+		} else if let Some(name) = line.strip_prefix("author ") {
+			author = name;
+		} else if let Some(value) = line.strip_prefix("author-mail ") {
+			mail = value;
+		} else if let Some(time) = line.strip_prefix("committer-time ") {
+			committed_at = time
+				.parse()
+				.ok()
+				.and_then(|secs| DateTime::from_timestamp(secs, 0));
+		}
+	}
+	lines
+}
+
+/// The most recent change among lines `first..=last`
+fn latest_change(lines: &[Option<Blame>], first: usize, last: usize) -> Option<Blame> {
+	lines
+		.get(first..=last.min(lines.len().checked_sub(1)?))?
+		.iter()
+		.flatten()
+		.max_by_key(|blame| blame.committed_at)
+		.cloned()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const PORCELAIN: &str = "\
+1111111111111111111111111111111111111111 1 1 2
+author Ada
+author-mail <ada@example.com>
+author-time 1700000000
+committer Ada
+committer-time 1700000000
+summary First
+filename src/lib.rs
+\tfn one() {}
+1111111111111111111111111111111111111111 2 2
+author Ada
+author-mail <ada@example.com>
+author-time 1700000000
+committer Ada
+committer-time 1700000000
+summary First
+filename src/lib.rs
+\tfn two() {}
+2222222222222222222222222222222222222222 3 3 1
+author Grace
+author-mail <grace@example.com>
+author-time 1710000000
+committer Grace
+committer-time 1710000000
+summary Second
+filename src/lib.rs
+\tfn three() {}
+0000000000000000000000000000000000000000 4 4 1
+author Not Committed Yet
+author-mail <not.committed.yet>
+author-time 1720000000
+committer Not Committed Yet
+committer-time 1720000000
+summary Version of src/lib.rs from src/lib.rs
+filename src/lib.rs
+\tfn four() {}
+";
+
+	#[test]
+	fn test_latest_change_of_block() {
+		let lines = parse_line_porcelain(PORCELAIN);
+		assert_eq!(lines.len(), 4);
+		assert!(lines[3].is_none());
+
+		let blame = latest_change(&lines, 0, 1).unwrap();
+		assert_eq!(blame.commit, "1".repeat(40));
+		assert_eq!(blame.author, "Ada <ada@example.com>");
+
+		// Uncommitted lines and lines past the end don't count
+		let blame = latest_change(&lines, 1, 10).unwrap();
+		assert_eq!(blame.author, "Grace <grace@example.com>");
+		assert!(latest_change(&lines, 3, 3).is_none());
+		assert!(latest_change(&lines, 7, 9).is_none());
+	}
+}
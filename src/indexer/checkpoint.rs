@@ -0,0 +1,123 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Resuming an interrupted index run
+//!
+//! A processed file waits with the batch holding its blocks. Once that batch
+//! is written, the file's modification time is recorded and the file is added
+//! to the checkpoint, in that order behind the batch on the write-behind
+//! queue, so an interruption never records a file whose blocks are missing.
+//! The next run for the same commit leaves checkpointed files alone instead
+//! of clearing and embedding them again; a completed run clears the
+//! checkpoint.
+
+use std::collections::HashSet;
+
+use anyhow::Result;
+
+use crate::indexer::write_behind::PendingWrite;
+use crate::store::Store;
+
+/// Files written by an unfinished run, and files waiting for their batch
+#[derive(Debug, Default)]
+pub struct Checkpoint {
+	/// Commit the run indexes; empty outside git repositories
+	commit: String,
+	/// Files finished by the interrupted run being resumed
+	done: HashSet<String>,
+	/// Processed files with their modification times, per batch kind
+	code: Vec<(String, u64)>,
+	text: Vec<(String, u64)>,
+	documents: Vec<(String, u64)>,
+}
+
+/// The batch a file's blocks go to
+#[derive(Debug, Clone, Copy)]
+pub enum BatchKind {
+	Code,
+	Text,
+	Document,
+}
+
+impl Checkpoint {
+	/// Checkpoint of an unfinished run indexing `commit`. A forced run, or
+	/// one for another commit, starts over.
+	pub async fn load(store: &Store, commit: &str, force_reindex: bool) -> Result<Self> {
+		let done = if force_reindex {
+			HashSet::new()
+		} else {
+			store.get_checkpoint_files(commit).await?
+		};
+		if done.is_empty() {
+			store.clear_checkpoint().await?;
+		}
+		Ok(Self {
+			commit: commit.to_string(),
+			done,
+			..Default::default()
+		})
+	}
+
+	/// Files the interrupted run already indexed
+	pub fn resumed(&self) -> usize {
+		self.done.len()
+	}
+
+	pub fn is_done(&self, path: &str) -> bool {
+		self.done.contains(path)
+	}
+
+	/// Hold a processed file until the batch of its blocks is written
+	pub fn processed(&mut self, kind: BatchKind, path: String, mtime: u64) {
+		self.pending(kind).push((path, mtime));
+	}
+
+	/// The write recording the files of a batch of `kind`, queued right after
+	/// the batch itself
+	pub fn written(&mut self, kind: BatchKind) -> PendingWrite {
+		PendingWrite::Files {
+			commit: self.commit.clone(),
+			files: std::mem::take(self.pending(kind)),
+		}
+	}
+
+	fn pending(&mut self, kind: BatchKind) -> &mut Vec<(String, u64)> {
+		match kind {
+			BatchKind::Code => &mut self.code,
+			BatchKind::Text => &mut self.text,
+			BatchKind::Document => &mut self.documents,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_written_takes_pending_files_of_kind() {
+		let mut checkpoint = Checkpoint::default();
+		checkpoint.processed(BatchKind::Code, "src/main.rs".to_string(), 10);
+		checkpoint.processed(BatchKind::Document, "README.md".to_string(), 20);
+
+		match checkpoint.written(BatchKind::Code) {
+			PendingWrite::Files { files, .. } => {
+				assert_eq!(files, vec![("src/main.rs".to_string(), 10)])
+			}
+			_ => panic!("expected a files write"),
+		}
+		assert!(checkpoint.written(BatchKind::Code).is_empty());
+		assert!(!checkpoint.written(BatchKind::Document).is_empty());
+	}
+}
@@ -19,7 +19,8 @@
 //! for indexing and embedding.
 
 use crate::indexer::languages;
-use tree_sitter::Node;
+use crate::store::{ContentSpan, SpanKind};
+use tree_sitter::{Node, Parser};
 
 /// Represents a meaningful code block/region with tree-sitter node information.
 #[derive(Clone)]
@@ -329,3 +330,43 @@ pub fn combine_with_preceding_comments(node: Node, contents: &str) -> (String, u
 	snippet.push_str(&contents[node.start_byte()..node.end_byte()]);
 	(snippet, combined_start)
 }
+
+/// Records the comments and string literals of a block's final content.
+///
+/// The block is parsed on its own, so the spans line up with the stored
+/// content even after merging or secret redaction.
+pub fn extract_content_spans(parser: &mut Parser, content: &str) -> Vec<ContentSpan> {
+	let mut spans = Vec::new();
+	if let Some(tree) = parser.parse(content, None) {
+		collect_content_spans(tree.root_node(), &mut spans);
+	}
+	spans
+}
+
+fn collect_content_spans(node: Node, spans: &mut Vec<ContentSpan>) {
+	let kind = node.kind();
+	let span_kind = if kind.contains("comment") {
+		Some(SpanKind::Comment)
+	} else if kind.contains("string") || kind.starts_with("heredoc") {
+		Some(SpanKind::String)
+	} else {
+		None
+	};
+
+	// Nested nodes (escapes, interpolations) belong to the outermost literal
+	if let Some(kind) = span_kind {
+		if node.end_byte() > node.start_byte() {
+			spans.push(ContentSpan {
+				kind,
+				start: node.start_byte(),
+				end: node.end_byte(),
+			});
+		}
+		return;
+	}
+
+	let mut cursor = node.walk();
+	for child in node.children(&mut cursor) {
+		collect_content_spans(child, spans);
+	}
+}
@@ -0,0 +1,121 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Deterministic indexing
+//!
+//! With `index.deterministic`, two machines indexing the same commit produce
+//! the same index: files are walked in sorted order, chunking ignores local
+//! overrides and uses the built-in parameters, and provenance timestamps come
+//! from `SOURCE_DATE_EPOCH` (or the Unix epoch) instead of the clock. Row ids
+//! are always derived from block content, so they need no switch here.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::borrow::Cow;
+
+use crate::config::{Config, IndexConfig};
+use crate::store::Store;
+
+/// `config` as indexing sees it: with chunking pinned in deterministic mode
+pub fn effective_config(config: &Config) -> Cow<'_, Config> {
+	if !config.index.deterministic {
+		return Cow::Borrowed(config);
+	}
+	let mut pinned = config.clone();
+	pin_chunker(&mut pinned);
+	Cow::Owned(pinned)
+}
+
+/// Apply the built-in chunking parameters when deterministic mode is on;
+/// returns the settings whose configured values were overridden
+pub fn pin_chunker(config: &mut Config) -> Vec<&'static str> {
+	if !config.index.deterministic {
+		return Vec::new();
+	}
+
+	let pinned = IndexConfig::default();
+	let index = &mut config.index;
+	let mut overridden = Vec::new();
+	if index.chunk_size != pinned.chunk_size {
+		index.chunk_size = pinned.chunk_size;
+		overridden.push("chunk_size");
+	}
+	if index.chunk_overlap != pinned.chunk_overlap {
+		index.chunk_overlap = pinned.chunk_overlap;
+		overridden.push("chunk_overlap");
+	}
+	if index.chunk_strategy != pinned.chunk_strategy {
+		index.chunk_strategy = pinned.chunk_strategy;
+		overridden.push("chunk_strategy");
+	}
+	overridden
+}
+
+/// Timestamp recorded as `indexed_at` for blocks indexed now
+pub fn indexed_at(config: &Config) -> DateTime<Utc> {
+	if !config.index.deterministic {
+		return Utc::now();
+	}
+	reproducible_time(std::env::var("SOURCE_DATE_EPOCH").ok().as_deref())
+}
+
+/// Record the manifest hash of the index after a deterministic run
+pub async fn record_manifest_hash(store: &Store, config: &Config) -> Result<()> {
+	if !config.index.deterministic {
+		return Ok(());
+	}
+	let manifest_hash = crate::index_cache::manifest_hash(store).await?;
+	store.store_index_manifest_hash(&manifest_hash).await
+}
+
+/// `SOURCE_DATE_EPOCH` as a timestamp, the Unix epoch when unset or invalid
+fn reproducible_time(source_date_epoch: Option<&str>) -> DateTime<Utc> {
+	source_date_epoch
+		.and_then(|secs| secs.trim().parse::<i64>().ok())
+		.and_then(|secs| DateTime::from_timestamp(secs, 0))
+		.unwrap_or(DateTime::UNIX_EPOCH)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::config::ChunkStrategy;
+
+	#[test]
+	fn test_pin_chunker_restores_defaults() {
+		let mut config = Config::load_from_template().unwrap();
+		config.index.chunk_size = 500;
+		config.index.chunk_strategy = ChunkStrategy::Sentence;
+		assert!(pin_chunker(&mut config.clone()).is_empty());
+
+		config.index.deterministic = true;
+		assert_eq!(
+			pin_chunker(&mut config),
+			vec!["chunk_size", "chunk_strategy"]
+		);
+		assert_eq!(config.index.chunk_size, IndexConfig::default().chunk_size);
+		assert_eq!(config.index.chunk_strategy, ChunkStrategy::Fixed);
+		assert!(pin_chunker(&mut config).is_empty());
+	}
+
+	#[test]
+	fn test_reproducible_time() {
+		assert_eq!(
+			reproducible_time(Some("1700000000")).timestamp(),
+			1_700_000_000
+		);
+		assert_eq!(reproducible_time(None), DateTime::UNIX_EPOCH);
+		assert_eq!(reproducible_time(Some("yesterday")), DateTime::UNIX_EPOCH);
+	}
+}
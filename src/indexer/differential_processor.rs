@@ -20,8 +20,9 @@
 
 use crate::config::Config;
 use crate::embedding::{calculate_content_hash_with_lines, calculate_unique_content_hash};
-use crate::indexer::code_region_extractor::extract_meaningful_regions;
-use crate::indexer::file_processor::chunk_text;
+use crate::indexer::code_region_extractor::{extract_content_spans, extract_meaningful_regions};
+use crate::indexer::file_processor::{chunk_text, redact_document_block, redact_secrets};
+use crate::indexer::file_summary::{update_file_summary, OutlineEntry};
 use crate::indexer::languages;
 use crate::indexer::markdown_processor::parse_markdown_content;
 use crate::state::SharedState;
@@ -85,16 +86,33 @@ pub async fn process_file_differential(
 	// Create set of new hashes for this file
 	let mut new_hashes = HashSet::new();
 	let mut graphrag_blocks_added = 0;
+	let mut outline = Vec::new();
 
 	for region in code_regions {
+		let content = match redact_secrets(
+			&region.content,
+			file_path,
+			region.start_line,
+			ctx.config,
+			&ctx.state,
+		) {
+			Some(content) => content,
+			None => continue,
+		};
+
 		// Use a hash that includes content, path, and line ranges
 		let content_hash = calculate_content_hash_with_lines(
-			&region.content,
+			&content,
 			file_path,
 			region.start_line,
 			region.end_line,
 		);
 		new_hashes.insert(content_hash.clone());
+		outline.push(OutlineEntry::new(
+			&content,
+			region.start_line,
+			region.end_line,
+		));
 
 		// Skip the check if force_reindex is true
 		let exists = !force_reindex
@@ -103,14 +121,17 @@ pub async fn process_file_differential(
 				.content_exists(&content_hash, "code_blocks")
 				.await?;
 		if !exists {
+			let spans = extract_content_spans(&mut parser, &content);
 			let code_block = CodeBlock {
 				path: file_path.to_string(),
 				hash: content_hash.clone(),
 				language: lang_impl.name().to_string(),
-				content: region.content.clone(),
+				content: content.into_owned(),
 				symbols: region.symbols.clone(),
 				start_line: region.start_line,
 				end_line: region.end_line,
+				spans,
+				provenance: None,
 				distance: None, // No relevance score when indexing
 			};
 
@@ -146,6 +167,9 @@ pub async fn process_file_differential(
 		}
 	}
 
+	// Long files also get an overview block
+	update_file_summary(ctx, contents, file_path, lang_impl.name(), &outline).await?;
+
 	// Update GraphRAG state if enabled and blocks were added
 	if ctx.config.graphrag.enabled && graphrag_blocks_added > 0 {
 		let mut state_guard = ctx.state.write();
@@ -177,20 +201,25 @@ pub async fn process_text_file_differential(
 			.await?
 	};
 
-	// Split content into chunks using configuration values
-	let chunks = chunk_text(
-		contents,
-		config.index.chunk_size,
-		config.index.chunk_overlap,
-	);
+	// Split content into chunks with the configured strategy
+	let chunks = chunk_text(contents, &config.index);
 	let mut new_hashes = HashSet::new();
 
 	for (chunk_idx, chunk_with_lines) in chunks.iter().enumerate() {
-		// Use chunk index in hash for uniqueness but keep path clean
-		let chunk_hash = calculate_unique_content_hash(
+		let content = match redact_secrets(
 			&chunk_with_lines.content,
-			&format!("{}#{}", file_path, chunk_idx),
-		);
+			file_path,
+			chunk_with_lines.start_line,
+			config,
+			&state,
+		) {
+			Some(content) => content,
+			None => continue,
+		};
+
+		// Use chunk index in hash for uniqueness but keep path clean
+		let chunk_hash =
+			calculate_unique_content_hash(&content, &format!("{}#{}", file_path, chunk_idx));
 		new_hashes.insert(chunk_hash.clone());
 
 		// Skip the check if force_reindex is true
@@ -199,10 +228,11 @@ pub async fn process_text_file_differential(
 			text_blocks_batch.push(TextBlock {
 				path: file_path.to_string(),
 				language: "text".to_string(),
-				content: chunk_with_lines.content.clone(),
+				content: content.into_owned(),
 				start_line: chunk_with_lines.start_line, // Use directly from TextChunkWithLines
 				end_line: chunk_with_lines.end_line,     // Use directly from TextChunkWithLines
 				hash: chunk_hash,
+				provenance: None,
 				distance: None,
 			});
 		}
@@ -251,6 +281,10 @@ pub async fn process_markdown_file_differential(
 	let mut new_hashes = HashSet::new();
 
 	for doc_block in document_blocks {
+		let doc_block = match redact_document_block(doc_block, config, &state) {
+			Some(doc_block) => doc_block,
+			None => continue,
+		};
 		new_hashes.insert(doc_block.hash.clone());
 
 		// Check if this document block already exists (unless force reindex)
@@ -321,9 +355,20 @@ pub async fn process_file(
 	let mut graphrag_blocks_added = 0;
 
 	for region in code_regions {
+		let content = match redact_secrets(
+			&region.content,
+			file_path,
+			region.start_line,
+			ctx.config,
+			&ctx.state,
+		) {
+			Some(content) => content,
+			None => continue,
+		};
+
 		// Use a hash that includes content, path, and line ranges
 		let content_hash = calculate_content_hash_with_lines(
-			&region.content,
+			&content,
 			file_path,
 			region.start_line,
 			region.end_line,
@@ -336,14 +381,17 @@ pub async fn process_file(
 				.content_exists(&content_hash, "code_blocks")
 				.await?;
 		if !exists {
+			let spans = extract_content_spans(&mut parser, &content);
 			let code_block = CodeBlock {
 				path: file_path.to_string(),
 				hash: content_hash,
 				language: lang_impl.name().to_string(),
-				content: region.content.clone(),
+				content: content.into_owned(),
 				symbols: region.symbols.clone(),
 				start_line: region.start_line,
 				end_line: region.end_line,
+				spans,
+				provenance: None,
 				distance: None, // No relevance score when indexing
 			};
 
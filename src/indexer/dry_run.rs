@@ -0,0 +1,421 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Indexing dry run
+//!
+//! Walks the project the way [`index_files`](super::index_files) does and
+//! chunks every file that would be processed, but only reads from the store:
+//! nothing is written and no embedding provider is called. Block hashes are
+//! checked against the store so unchanged blocks of a changed file are not
+//! counted, and embedding calls are estimated with the same batch limits.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::Path;
+use tree_sitter::Parser;
+
+use crate::config::Config;
+use crate::embedding::{
+	calculate_content_hash_with_lines, calculate_unique_content_hash, count_tokens,
+};
+use crate::indexer::code_region_extractor::extract_meaningful_regions;
+use crate::indexer::file_policy::{FilePolicy, SkipReason, SkippedFiles};
+use crate::indexer::file_processor::{
+	chunk_text, is_allowed_text_extension, is_markdown_file, redact_document_block, redact_secrets,
+};
+use crate::indexer::markdown_processor::parse_markdown_content;
+use crate::indexer::{
+	detect_language, deterministic, get_file_mtime, git, languages, NoindexWalker, PathUtils,
+};
+use crate::state::{self, SharedState};
+use crate::store::Store;
+
+/// What indexing would do with a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileAction {
+	/// Not indexed before
+	Index,
+	/// Indexed before and changed since
+	Reindex,
+	/// Unchanged since it was last indexed
+	Skip,
+}
+
+/// A file indexing would visit, with the blocks it would embed
+#[derive(Debug, Clone, Serialize)]
+pub struct PlannedFile {
+	pub path: String,
+	pub action: FileAction,
+	/// "code", "text" or "document"
+	pub kind: &'static str,
+	/// Blocks not yet in the store
+	pub blocks: usize,
+	pub tokens: usize,
+}
+
+/// Embedding work for one block table
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EmbeddingEstimate {
+	pub blocks: usize,
+	pub tokens: usize,
+	pub calls: usize,
+}
+
+/// Everything an indexing run would do
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DryRunReport {
+	pub files: Vec<PlannedFile>,
+	pub code: EmbeddingEstimate,
+	pub text: EmbeddingEstimate,
+	pub documents: EmbeddingEstimate,
+	/// Files the `[index]` file policy keeps out
+	pub skipped: SkippedFiles,
+	/// Set when git shows no commits since the last index, so nothing runs
+	pub up_to_date: bool,
+}
+
+impl DryRunReport {
+	pub fn count(&self, action: FileAction) -> usize {
+		self.files.iter().filter(|f| f.action == action).count()
+	}
+
+	/// Totals over all block tables
+	pub fn total(&self) -> EmbeddingEstimate {
+		let tables = [&self.code, &self.text, &self.documents];
+		EmbeddingEstimate {
+			blocks: tables.iter().map(|t| t.blocks).sum(),
+			tokens: tables.iter().map(|t| t.tokens).sum(),
+			calls: tables.iter().map(|t| t.calls).sum(),
+		}
+	}
+}
+
+/// Groups blocks into embedding calls like indexing does: a batch is sent
+/// once, after a file, it reaches the batch size or the token limit
+struct BatchEstimator<'a> {
+	config: &'a Config,
+	estimate: EmbeddingEstimate,
+	pending_blocks: usize,
+	pending_tokens: usize,
+}
+
+impl<'a> BatchEstimator<'a> {
+	fn new(config: &'a Config) -> Self {
+		Self {
+			config,
+			estimate: EmbeddingEstimate::default(),
+			pending_blocks: 0,
+			pending_tokens: 0,
+		}
+	}
+
+	fn add_file(&mut self, block_tokens: &[usize]) {
+		let tokens: usize = block_tokens.iter().sum();
+		self.estimate.blocks += block_tokens.len();
+		self.estimate.tokens += tokens;
+		self.pending_blocks += block_tokens.len();
+		self.pending_tokens += tokens;
+
+		if self.pending_blocks > 0
+			&& (self.pending_blocks >= self.config.index.embeddings_batch_size
+				|| self.pending_tokens >= self.config.index.embeddings_max_tokens_per_batch)
+		{
+			self.flush();
+		}
+	}
+
+	fn flush(&mut self) {
+		if self.pending_blocks > 0 {
+			self.estimate.calls += 1;
+			self.pending_blocks = 0;
+			self.pending_tokens = 0;
+		}
+	}
+
+	fn finish(mut self) -> EmbeddingEstimate {
+		self.flush();
+		self.estimate
+	}
+}
+
+/// Files git reports changed since the last indexed commit
+enum GitChanges {
+	/// No usable commit to compare against: every file is checked
+	Unknown,
+	/// Nothing committed since the last index
+	None,
+	Files(HashSet<String>),
+}
+
+/// Plan an indexing run of `current_dir` without touching the store or providers
+pub async fn plan(
+	store: &Store,
+	config: &Config,
+	current_dir: &Path,
+	git_repo_root: Option<&Path>,
+) -> Result<DryRunReport> {
+	let config = deterministic::effective_config(config);
+	let config = config.as_ref();
+	// Redaction findings are recorded here and dropped
+	let state = state::create_shared_state();
+
+	let git_changes = match git_repo_root {
+		Some(git_root) => git_changes(store, git_root).await,
+		None => GitChanges::Unknown,
+	};
+	let file_metadata = store.get_all_file_metadata().await?;
+
+	let file_policy = FilePolicy::new(&config.index);
+
+	let roots = super::roots::resolve(current_dir, &config.index.roots)?;
+	let mut walker = NoindexWalker::create_roots_walker(current_dir, &roots);
+	walker.sort_by_file_name(|a, b| a.cmp(b));
+
+	let mut report = DryRunReport {
+		up_to_date: matches!(git_changes, GitChanges::None),
+		..Default::default()
+	};
+	let mut code = BatchEstimator::new(config);
+	let mut text = BatchEstimator::new(config);
+	let mut documents = BatchEstimator::new(config);
+
+	for entry in walker.build().flatten() {
+		if !entry.file_type().is_some_and(|ft| ft.is_file()) {
+			continue;
+		}
+		let path = entry.path();
+		let language = detect_language(path);
+		let kind = match language {
+			Some("markdown") => "document",
+			Some(_) => "code",
+			None if is_allowed_text_extension(path) && !is_markdown_file(path) => "text",
+			None => continue,
+		};
+		let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+		if let Some(reason) = file_policy.check_path(path, size) {
+			report.skipped.record(reason);
+			continue;
+		}
+		let file_path = PathUtils::to_relative_string(path, current_dir);
+
+		let indexed_before = file_metadata.contains_key(&file_path);
+		let changed = match &git_changes {
+			GitChanges::None => false,
+			GitChanges::Files(files) => files.contains(&file_path),
+			GitChanges::Unknown => match (get_file_mtime(path), file_metadata.get(&file_path)) {
+				(Ok(actual), Some(stored)) => actual > *stored,
+				_ => true,
+			},
+		};
+		let action = match (changed, indexed_before) {
+			(false, _) => FileAction::Skip,
+			(true, true) => FileAction::Reindex,
+			(true, false) => FileAction::Index,
+		};
+
+		let mut planned = PlannedFile {
+			path: file_path,
+			action,
+			kind,
+			blocks: 0,
+			tokens: 0,
+		};
+		if action != FileAction::Skip {
+			let contents = match file_policy.read(path) {
+				Ok(Ok(contents)) => contents,
+				Ok(Err(reason)) => {
+					report.skipped.record(reason);
+					continue;
+				}
+				Err(_) => continue,
+			};
+			// Git-changed files lose all their blocks before they are indexed
+			let check_store = !matches!(git_changes, GitChanges::Files(_));
+			let block_tokens = match (kind, language) {
+				("code", Some(language)) => {
+					code_blocks(
+						store,
+						config,
+						&state,
+						&contents,
+						&planned.path,
+						language,
+						check_store,
+					)
+					.await?
+				}
+				("document", _) => {
+					document_blocks(store, config, &state, &contents, &planned.path, check_store)
+						.await?
+				}
+				_ if file_policy.is_text(&contents) => {
+					text_blocks(store, config, &state, &contents, &planned.path, check_store)
+						.await?
+				}
+				_ => {
+					report.skipped.record(SkipReason::Binary);
+					continue;
+				}
+			};
+			planned.blocks = block_tokens.len();
+			planned.tokens = block_tokens.iter().sum();
+			match kind {
+				"code" => code.add_file(&block_tokens),
+				"document" => documents.add_file(&block_tokens),
+				_ => text.add_file(&block_tokens),
+			}
+		}
+		report.files.push(planned);
+	}
+
+	report.code = code.finish();
+	report.text = text.finish();
+	report.documents = documents.finish();
+	Ok(report)
+}
+
+async fn git_changes(store: &Store, git_root: &Path) -> GitChanges {
+	let Ok(Some(last_commit)) = store.get_last_commit_hash().await else {
+		return GitChanges::Unknown;
+	};
+	let Ok(current_commit) = git::get_current_commit_hash(git_root) else {
+		return GitChanges::Unknown;
+	};
+	if last_commit == current_commit {
+		return GitChanges::None;
+	}
+	match git::get_changed_files_since_commit(git_root, &last_commit) {
+		Ok(files) => GitChanges::Files(files.into_iter().collect()),
+		Err(_) => GitChanges::Unknown,
+	}
+}
+
+/// Whether indexing would embed a block with this hash
+async fn is_new(store: &Store, check_store: bool, hash: &str, table: &str) -> Result<bool> {
+	Ok(!check_store || !store.content_exists(hash, table).await?)
+}
+
+/// Token counts of the code blocks of a file that are not in the store yet
+async fn code_blocks(
+	store: &Store,
+	config: &Config,
+	state: &SharedState,
+	contents: &str,
+	file_path: &str,
+	language: &str,
+	check_store: bool,
+) -> Result<Vec<usize>> {
+	let Some(lang_impl) = languages::get_language(language) else {
+		return Ok(Vec::new());
+	};
+	let mut parser = Parser::new();
+	parser.set_language(&lang_impl.get_ts_language())?;
+	let Some(tree) = parser.parse(contents, None) else {
+		return Ok(Vec::new());
+	};
+	let mut regions = Vec::new();
+	extract_meaningful_regions(tree.root_node(), contents, lang_impl.as_ref(), &mut regions);
+
+	let mut tokens = Vec::new();
+	for region in regions {
+		let Some(content) =
+			redact_secrets(&region.content, file_path, region.start_line, config, state)
+		else {
+			continue;
+		};
+		let hash = calculate_content_hash_with_lines(
+			&content,
+			file_path,
+			region.start_line,
+			region.end_line,
+		);
+		if is_new(store, check_store, &hash, "code_blocks").await? {
+			tokens.push(count_tokens(&content));
+		}
+	}
+	Ok(tokens)
+}
+
+/// Token counts of the text chunks of a file that are not in the store yet
+async fn text_blocks(
+	store: &Store,
+	config: &Config,
+	state: &SharedState,
+	contents: &str,
+	file_path: &str,
+	check_store: bool,
+) -> Result<Vec<usize>> {
+	let mut tokens = Vec::new();
+	for (chunk_idx, chunk) in chunk_text(contents, &config.index).iter().enumerate() {
+		let Some(content) =
+			redact_secrets(&chunk.content, file_path, chunk.start_line, config, state)
+		else {
+			continue;
+		};
+		let hash = calculate_unique_content_hash(&content, &format!("{}#{}", file_path, chunk_idx));
+		if is_new(store, check_store, &hash, "text_blocks").await? {
+			tokens.push(count_tokens(&content));
+		}
+	}
+	Ok(tokens)
+}
+
+/// Token counts of the markdown sections of a file that are not in the store yet
+async fn document_blocks(
+	store: &Store,
+	config: &Config,
+	state: &SharedState,
+	contents: &str,
+	file_path: &str,
+	check_store: bool,
+) -> Result<Vec<usize>> {
+	let mut tokens = Vec::new();
+	for block in parse_markdown_content(contents, file_path, config) {
+		let Some(block) = redact_document_block(block, config, state) else {
+			continue;
+		};
+		if is_new(store, check_store, &block.hash, "document_blocks").await? {
+			tokens.push(count_tokens(&block.content));
+		}
+	}
+	Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_batch_estimator_follows_batch_limits() {
+		let mut config = Config::load_from_template().unwrap();
+		config.index.embeddings_batch_size = 3;
+		config.index.embeddings_max_tokens_per_batch = 100;
+
+		let mut estimator = BatchEstimator::new(&config);
+		// Two files of two blocks: the batch is sent once it holds four
+		estimator.add_file(&[10, 10]);
+		estimator.add_file(&[10, 10]);
+		// One large block reaches the token limit on its own
+		estimator.add_file(&[150]);
+		estimator.add_file(&[]);
+		estimator.add_file(&[5]);
+		let estimate = estimator.finish();
+
+		assert_eq!(estimate.blocks, 6);
+		assert_eq!(estimate.tokens, 195);
+		assert_eq!(estimate.calls, 3);
+	}
+}
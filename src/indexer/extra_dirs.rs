@@ -0,0 +1,139 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Directories indexed despite .gitignore and .noindex
+//!
+//! `octocode watch --include-extra <dir>` indexes a directory the ignore
+//! rules keep out, such as generated code, for as long as the watcher runs
+//! with the flag. The directories of the last indexing run are recorded in
+//! the project storage; when a later run no longer includes one, the files
+//! indexed from it that the ignore rules exclude are removed again.
+
+use anyhow::Result;
+use std::path::{Component, Path, PathBuf};
+
+use crate::storage;
+
+const EXTRA_DIRS_FILE: &str = "extra_dirs.json";
+
+/// Check `--include-extra` arguments and turn them into directories relative to
+/// `current_dir`, with `/` separators, sorted and without duplicates
+pub fn resolve(current_dir: &Path, paths: &[PathBuf]) -> Result<Vec<String>> {
+	let mut dirs = Vec::new();
+	for path in paths {
+		let absolute = if path.is_absolute() {
+			path.clone()
+		} else {
+			current_dir.join(path)
+		};
+		if !absolute.is_dir() {
+			return Err(anyhow::anyhow!(
+				"--include-extra: {} is not a directory",
+				path.display()
+			));
+		}
+		let relative = absolute
+			.canonicalize()?
+			.strip_prefix(current_dir.canonicalize()?)
+			.map(Path::to_path_buf)
+			.map_err(|_| {
+				anyhow::anyhow!(
+					"--include-extra: {} is outside {}",
+					path.display(),
+					current_dir.display()
+				)
+			})?;
+		let relative: Vec<String> = relative
+			.components()
+			.filter_map(|component| match component {
+				Component::Normal(part) => Some(part.to_string_lossy().to_string()),
+				_ => None,
+			})
+			.collect();
+		if relative.is_empty() {
+			return Err(anyhow::anyhow!(
+				"--include-extra: {} is the project directory itself",
+				path.display()
+			));
+		}
+		dirs.push(relative.join("/"));
+	}
+	dirs.sort();
+	dirs.dedup();
+	Ok(dirs)
+}
+
+/// Whether a relative file path lies in one of `dirs`
+pub fn contains(dirs: &[String], file_path: &str) -> bool {
+	dirs.iter().any(|dir| {
+		file_path
+			.strip_prefix(dir.as_str())
+			.is_some_and(|rest| rest.starts_with('/'))
+	})
+}
+
+/// Directories included by the last indexing run
+pub fn load(project_path: &Path) -> Vec<String> {
+	storage::get_project_storage_path(project_path)
+		.ok()
+		.and_then(|storage_dir| std::fs::read_to_string(storage_dir.join(EXTRA_DIRS_FILE)).ok())
+		.and_then(|content| serde_json::from_str(&content).ok())
+		.unwrap_or_default()
+}
+
+/// Record the directories of the current indexing run
+pub fn save(project_path: &Path, dirs: &[String]) -> Result<()> {
+	let storage_dir = storage::ensure_project_storage_exists(project_path)?;
+	let path = storage_dir.join(EXTRA_DIRS_FILE);
+	if dirs.is_empty() {
+		if path.exists() {
+			std::fs::remove_file(path)?;
+		}
+		return Ok(());
+	}
+	std::fs::write(path, serde_json::to_string_pretty(dirs)?)?;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_resolve_and_contains() {
+		let root = std::env::temp_dir().join(format!("octocode_extra_{}", std::process::id()));
+		let _ = std::fs::remove_dir_all(&root);
+		std::fs::create_dir_all(root.join("build/gen")).unwrap();
+		std::fs::create_dir_all(root.join("vendor")).unwrap();
+
+		let dirs = resolve(
+			&root,
+			&[
+				PathBuf::from("vendor"),
+				root.join("build/gen"),
+				PathBuf::from("./vendor/"),
+			],
+		)
+		.unwrap();
+		assert_eq!(dirs, vec!["build/gen", "vendor"]);
+		assert!(resolve(&root, &[PathBuf::from("missing")]).is_err());
+		assert!(resolve(&root, &[PathBuf::from(".")]).is_err());
+
+		assert!(contains(&dirs, "build/gen/api.rs"));
+		assert!(!contains(&dirs, "build/generated.rs"));
+		assert!(!contains(&dirs, "src/main.rs"));
+
+		let _ = std::fs::remove_dir_all(&root);
+	}
+}
@@ -0,0 +1,185 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Which files indexing reads at all
+//!
+//! `index.max_file_size`, `index.skip_binary` and the
+//! `index.include_extensions`/`index.exclude_extensions` lists are checked
+//! for every file the walker yields that indexing would otherwise process.
+//! Files turned away are counted per reason for the indexing summary.
+
+use serde::Serialize;
+use std::path::Path;
+
+use super::file_utils::FileUtils;
+use crate::config::IndexConfig;
+
+/// Bytes inspected for NUL bytes when detecting binary files, as git does
+const BINARY_SNIFF_LEN: usize = 8000;
+
+/// Why a file was not indexed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipReason {
+	TooLarge,
+	Binary,
+	Extension,
+}
+
+/// Files kept out of the index by the file policy, per reason
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct SkippedFiles {
+	pub too_large: usize,
+	pub binary: usize,
+	pub excluded_extension: usize,
+}
+
+impl SkippedFiles {
+	pub fn record(&mut self, reason: SkipReason) {
+		match reason {
+			SkipReason::TooLarge => self.too_large += 1,
+			SkipReason::Binary => self.binary += 1,
+			SkipReason::Extension => self.excluded_extension += 1,
+		}
+	}
+
+	pub fn total(&self) -> usize {
+		self.too_large + self.binary + self.excluded_extension
+	}
+
+	/// "2 larger than max_file_size, 1 binary" for summaries
+	pub fn describe(&self) -> String {
+		let mut parts = Vec::new();
+		if self.too_large > 0 {
+			parts.push(format!("{} larger than max_file_size", self.too_large));
+		}
+		if self.binary > 0 {
+			parts.push(format!("{} binary", self.binary));
+		}
+		if self.excluded_extension > 0 {
+			parts.push(format!("{} excluded by extension", self.excluded_extension));
+		}
+		parts.join(", ")
+	}
+}
+
+/// The `[index]` file policy
+#[derive(Debug, Clone)]
+pub struct FilePolicy {
+	max_file_size: u64,
+	skip_binary: bool,
+	include_extensions: Vec<String>,
+	exclude_extensions: Vec<String>,
+}
+
+impl FilePolicy {
+	pub fn new(config: &IndexConfig) -> Self {
+		let normalize = |extensions: &[String]| -> Vec<String> {
+			extensions
+				.iter()
+				.map(|ext| ext.trim().trim_start_matches('.').to_lowercase())
+				.filter(|ext| !ext.is_empty())
+				.collect()
+		};
+		Self {
+			max_file_size: config.max_file_size,
+			skip_binary: config.skip_binary,
+			include_extensions: normalize(&config.include_extensions),
+			exclude_extensions: normalize(&config.exclude_extensions),
+		}
+	}
+
+	/// Check a file by its extension and size, before reading it
+	pub fn check_path(&self, path: &Path, size: u64) -> Option<SkipReason> {
+		let extension = path
+			.extension()
+			.and_then(|ext| ext.to_str())
+			.map(|ext| ext.to_lowercase())
+			.unwrap_or_default();
+		if !self.include_extensions.is_empty() && !self.include_extensions.contains(&extension) {
+			return Some(SkipReason::Extension);
+		}
+		if self.exclude_extensions.contains(&extension) {
+			return Some(SkipReason::Extension);
+		}
+		if self.max_file_size > 0 && size > self.max_file_size {
+			return Some(SkipReason::TooLarge);
+		}
+		None
+	}
+
+	/// Check a file by its path and size on disk
+	pub fn check_file(&self, path: &Path) -> Option<SkipReason> {
+		let size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+		self.check_path(path, size)
+	}
+
+	/// Read a file as UTF-8, or the reason to skip it when it is binary
+	///
+	/// Files that are not valid UTF-8 fail with `InvalidData`, as
+	/// `read_to_string` does.
+	pub fn read(&self, path: &Path) -> std::io::Result<Result<String, SkipReason>> {
+		let bytes = std::fs::read(path)?;
+		if self.skip_binary && bytes[..bytes.len().min(BINARY_SNIFF_LEN)].contains(&0) {
+			return Ok(Err(SkipReason::Binary));
+		}
+		String::from_utf8(bytes)
+			.map(Ok)
+			.map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+	}
+
+	/// Whether text read from a file without a language is worth indexing as
+	/// text; with `skip_binary` off every readable file is
+	pub fn is_text(&self, contents: &str) -> bool {
+		!self.skip_binary || FileUtils::is_text_file(contents)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_extension_and_size_checks() {
+		let config = IndexConfig {
+			max_file_size: 100,
+			include_extensions: vec![".RS".to_string(), "toml".to_string()],
+			exclude_extensions: vec!["toml".to_string()],
+			..Default::default()
+		};
+		let policy = FilePolicy::new(&config);
+
+		assert_eq!(policy.check_path(Path::new("src/lib.rs"), 10), None);
+		assert_eq!(
+			policy.check_path(Path::new("src/lib.rs"), 101),
+			Some(SkipReason::TooLarge)
+		);
+		assert_eq!(
+			policy.check_path(Path::new("Cargo.toml"), 10),
+			Some(SkipReason::Extension)
+		);
+		assert_eq!(
+			policy.check_path(Path::new("README.md"), 10),
+			Some(SkipReason::Extension)
+		);
+
+		let mut skipped = SkippedFiles::default();
+		skipped.record(SkipReason::TooLarge);
+		skipped.record(SkipReason::Extension);
+		assert_eq!(skipped.total(), 2);
+		assert_eq!(
+			skipped.describe(),
+			"1 larger than max_file_size, 1 excluded by extension"
+		);
+	}
+}
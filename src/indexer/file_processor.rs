@@ -17,14 +17,17 @@
 //! This module handles the processing of text and markdown files, including
 //! chunking, content validation, and block creation for indexing.
 
-use crate::config::Config;
-use crate::embedding::calculate_unique_content_hash;
+use crate::config::{Config, IndexConfig};
+use crate::embedding::{calculate_content_hash_with_lines, calculate_unique_content_hash};
+use crate::indexer::chunk_processors;
 use crate::indexer::file_utils::FileUtils;
 use crate::indexer::markdown_processor::parse_markdown_content;
+use crate::indexer::secret_scanner;
 use crate::indexer::text_processing::{TextChunkWithLines, TextProcessor};
 use crate::state::SharedState;
 use crate::store::{DocumentBlock, Store, TextBlock};
 use anyhow::Result;
+use std::borrow::Cow;
 
 /// Check if a file extension is allowed for text indexing
 pub fn is_allowed_text_extension(path: &std::path::Path) -> bool {
@@ -47,9 +50,92 @@ pub fn is_text_file(contents: &str) -> bool {
 	FileUtils::is_text_file(contents)
 }
 
-/// Chunk text content using configuration parameters
-pub fn chunk_text(content: &str, chunk_size: usize, overlap: usize) -> Vec<TextChunkWithLines> {
-	TextProcessor::chunk_text(content, chunk_size, overlap)
+/// Chunk text content with the configured strategy
+pub fn chunk_text(content: &str, config: &IndexConfig) -> Vec<TextChunkWithLines> {
+	TextProcessor::chunk_text_with_strategy(
+		content,
+		config.chunk_strategy,
+		config.chunk_size,
+		config.chunk_overlap,
+	)
+}
+
+/// Scan a block for credentials and run the chunk processors before it is
+/// hashed and embedded.
+///
+/// Returns the content to index with secrets redacted, or `None` when the block
+/// is mostly credentials and must stay out of the index. Findings are recorded
+/// in the shared state for the report at the end of indexing.
+pub fn redact_secrets<'a>(
+	content: &'a str,
+	file_path: &str,
+	start_line: usize,
+	config: &Config,
+	state: &SharedState,
+) -> Option<Cow<'a, str>> {
+	// Validated before indexing starts; should it still fail, keep the block out
+	let pipeline = match chunk_processors::pipeline(config) {
+		Ok(pipeline) => pipeline,
+		Err(e) => {
+			tracing::warn!("Skipping {} block: {}", file_path, e);
+			return None;
+		}
+	};
+	let mut content = Cow::Borrowed(content);
+
+	if config.index.skip_secrets {
+		if let Some(scan) = secret_scanner::scan(&content) {
+			let mut state_guard = state.write();
+			for finding in &scan.findings {
+				state_guard.secret_findings.push(format!(
+					"{}:{} ({})",
+					file_path,
+					start_line + finding.line + 1,
+					finding.kind
+				));
+			}
+			if scan.skip {
+				state_guard.secret_skipped_blocks += 1;
+				return None;
+			}
+			content = Cow::Owned(scan.redacted);
+		}
+	}
+
+	for processor in pipeline.processors() {
+		let Some(processed) = processor.process_in(file_path, &content) else {
+			continue;
+		};
+		let mut state_guard = state.write();
+		for line in processed.lines {
+			state_guard.secret_findings.push(format!(
+				"{}:{} ({})",
+				file_path,
+				start_line + line + 1,
+				processor.name()
+			));
+		}
+		content = Cow::Owned(processed.content);
+	}
+
+	Some(content)
+}
+
+/// Apply [`redact_secrets`] to a parsed markdown block, rehashing redacted content
+pub fn redact_document_block(
+	mut block: DocumentBlock,
+	config: &Config,
+	state: &SharedState,
+) -> Option<DocumentBlock> {
+	let redacted =
+		match redact_secrets(&block.content, &block.path, block.start_line, config, state)? {
+			Cow::Borrowed(_) => return Some(block),
+			Cow::Owned(redacted) => redacted,
+		};
+	block.hash =
+		calculate_content_hash_with_lines(&redacted, &block.path, block.start_line, block.end_line);
+	block.content = redacted;
+	Some(block)
 }
 
 /// Process an unsupported file as chunked text blocks
@@ -68,19 +154,24 @@ pub async fn process_text_file(
 ) -> Result<()> {
 	let force_reindex = state.read().force_reindex;
 
-	// Split content into chunks using configuration values
-	let chunks = chunk_text(
-		contents,
-		config.index.chunk_size,
-		config.index.chunk_overlap,
-	);
+	// Split content into chunks with the configured strategy
+	let chunks = chunk_text(contents, &config.index);
 
 	for (chunk_idx, chunk_with_lines) in chunks.iter().enumerate() {
-		// Use chunk index in hash for uniqueness but keep path clean
-		let chunk_hash = calculate_unique_content_hash(
+		let content = match redact_secrets(
 			&chunk_with_lines.content,
-			&format!("{}#{}", file_path, chunk_idx),
-		);
+			file_path,
+			chunk_with_lines.start_line,
+			config,
+			&state,
+		) {
+			Some(content) => content,
+			None => continue,
+		};
+
+		// Use chunk index in hash for uniqueness but keep path clean
+		let chunk_hash =
+			calculate_unique_content_hash(&content, &format!("{}#{}", file_path, chunk_idx));
 
 		// Skip the check if force_reindex is true
 		let exists = !force_reindex && store.content_exists(&chunk_hash, "text_blocks").await?;
@@ -88,10 +179,11 @@ pub async fn process_text_file(
 			text_blocks_batch.push(TextBlock {
 				path: file_path.to_string(),
 				language: "text".to_string(),
-				content: chunk_with_lines.content.clone(),
+				content: content.into_owned(),
 				start_line: chunk_with_lines.start_line, // Use directly from TextChunkWithLines
 				end_line: chunk_with_lines.end_line,     // Use directly from TextChunkWithLines
 				hash: chunk_hash,
+				provenance: None,
 				distance: None,
 			});
 		}
@@ -116,6 +208,11 @@ pub async fn process_markdown_file(
 	let document_blocks = parse_markdown_content(contents, file_path, config);
 
 	for doc_block in document_blocks {
+		let doc_block = match redact_document_block(doc_block, config, &state) {
+			Some(doc_block) => doc_block,
+			None => continue,
+		};
+
 		// Check if this document block already exists (unless force reindex)
 		let exists = !force_reindex
 			&& store
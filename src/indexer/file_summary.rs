@@ -0,0 +1,378 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Overview blocks of long code files
+//!
+//! A long file answers "what does this file do" with a handful of unrelated
+//! fragments. Files split into at least `index.file_summary_min_blocks` code
+//! blocks therefore also get one overview block in the `file_summaries` table:
+//! the file's leading comment and the first line of each of its blocks,
+//! optionally with a description written by the GraphRAG description model.
+//! Overviews are embedded with the code model and searched with code blocks.
+
+use crate::embedding::calculate_content_hash_with_lines;
+use crate::indexer::batch_processor::process_file_summaries_batch;
+use crate::indexer::differential_processor::ProcessFileContext;
+use crate::indexer::file_processor::redact_secrets;
+use crate::indexer::graphrag::ai::AIEnhancements;
+use crate::store::CodeBlock;
+use anyhow::Result;
+
+/// Start of every overview's content, telling overviews apart from code
+/// blocks in search results
+pub const OVERVIEW_PREFIX: &str = "File overview: ";
+
+/// Blocks listed in an overview; longer files end with a count of the rest
+const MAX_OUTLINE_ENTRIES: usize = 40;
+
+/// Longest first line of a block quoted in an overview, in characters
+const MAX_SIGNATURE_CHARS: usize = 120;
+
+/// Lines of the file's leading comment quoted in an overview
+const MAX_COMMENT_LINES: usize = 6;
+
+/// Code block of a file as listed in its overview
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutlineEntry {
+	/// 0-indexed, like the lines of code blocks
+	pub start_line: usize,
+	pub end_line: usize,
+	pub signature: String,
+}
+
+impl OutlineEntry {
+	pub fn new(content: &str, start_line: usize, end_line: usize) -> Self {
+		Self {
+			start_line,
+			end_line,
+			signature: signature_line(content),
+		}
+	}
+}
+
+/// Create, replace or remove the overview of a code file after its blocks
+/// were processed
+pub async fn update_file_summary(
+	ctx: &ProcessFileContext<'_>,
+	contents: &str,
+	file_path: &str,
+	language: &str,
+	entries: &[OutlineEntry],
+) -> Result<()> {
+	let force_reindex = ctx.state.read().force_reindex;
+	let min_blocks = ctx.config.index.file_summary_min_blocks;
+
+	let existing_hashes = if force_reindex {
+		Vec::new()
+	} else {
+		ctx.store
+			.get_file_blocks_metadata(file_path, "file_summaries")
+			.await?
+	};
+
+	let mut current_hash = None;
+	if min_blocks > 0 && entries.len() >= min_blocks {
+		let (first, last) = (&entries[0], &entries[entries.len() - 1]);
+		let comment = leading_comment(contents).and_then(|comment| {
+			redact_secrets(&comment, file_path, 0, ctx.config, &ctx.state)
+				.map(|comment| comment.into_owned())
+		});
+		let overview = build_overview(file_path, language, comment.as_deref(), entries, None);
+
+		// The description is only asked for when the outline changed, and
+		// turning it on or off rebuilds the overview
+		let use_llm = ctx.config.index.file_summary_llm;
+		let keyed = format!("{}\n{}", if use_llm { "llm" } else { "local" }, overview);
+		let hash =
+			calculate_content_hash_with_lines(&keyed, file_path, first.start_line, last.end_line);
+
+		let exists = !force_reindex && ctx.store.content_exists(&hash, "file_summaries").await?;
+		if !exists {
+			let description = if use_llm {
+				describe(ctx, &overview, file_path, language).await
+			} else {
+				None
+			};
+			let content = match description {
+				Some(description) => build_overview(
+					file_path,
+					language,
+					comment.as_deref(),
+					entries,
+					Some(&description),
+				),
+				None => overview,
+			};
+			let block = CodeBlock {
+				path: file_path.to_string(),
+				hash: hash.clone(),
+				language: language.to_string(),
+				content,
+				symbols: Vec::new(),
+				start_line: first.start_line,
+				end_line: last.end_line,
+				spans: Vec::new(),
+				provenance: None,
+				distance: None,
+			};
+			process_file_summaries_batch(ctx.store, &[block], ctx.config, &ctx.state).await?;
+		}
+		current_hash = Some(hash);
+	}
+
+	// Replaced overviews, and those of files that became short
+	let stale: Vec<String> = existing_hashes
+		.into_iter()
+		.filter(|hash| Some(hash) != current_hash.as_ref())
+		.collect();
+	if !stale.is_empty() {
+		ctx.store
+			.remove_blocks_by_hashes(&stale, "file_summaries")
+			.await?;
+	}
+
+	Ok(())
+}
+
+/// Description of the file by the GraphRAG description model; the overview
+/// is stored without one when the call fails
+async fn describe(
+	ctx: &ProcessFileContext<'_>,
+	overview: &str,
+	file_path: &str,
+	language: &str,
+) -> Option<String> {
+	let ai = AIEnhancements::new(ctx.config.clone(), reqwest::Client::new(), true);
+	match ai
+		.extract_ai_description(overview, file_path, language, &[])
+		.await
+	{
+		Ok(description) if !description.is_empty() => Some(description),
+		Ok(_) => None,
+		Err(e) => {
+			tracing::warn!("File overview description failed for {}: {}", file_path, e);
+			None
+		}
+	}
+}
+
+/// Overview text of a file from its leading comment and blocks
+pub fn build_overview(
+	file_path: &str,
+	language: &str,
+	comment: Option<&str>,
+	entries: &[OutlineEntry],
+	description: Option<&str>,
+) -> String {
+	let mut overview = format!(
+		"{}{} ({}, {} blocks",
+		OVERVIEW_PREFIX,
+		file_path,
+		language,
+		entries.len()
+	);
+	if let (Some(first), Some(last)) = (entries.first(), entries.last()) {
+		overview.push_str(&format!(
+			", lines {}-{}",
+			first.start_line + 1,
+			last.end_line + 1
+		));
+	}
+	overview.push(')');
+
+	if let Some(description) = description {
+		overview.push_str(&format!("\n\n{}", description.trim()));
+	}
+	if let Some(comment) = comment {
+		overview.push_str(&format!("\n\n{}", comment));
+	}
+
+	overview.push_str("\n\nContents:");
+	for entry in entries.iter().take(MAX_OUTLINE_ENTRIES) {
+		overview.push_str(&format!(
+			"\n- L{}-{} {}",
+			entry.start_line + 1,
+			entry.end_line + 1,
+			entry.signature
+		));
+	}
+	if entries.len() > MAX_OUTLINE_ENTRIES {
+		overview.push_str(&format!(
+			"\n- ... and {} more blocks",
+			entries.len() - MAX_OUTLINE_ENTRIES
+		));
+	}
+	overview
+}
+
+/// First line of a block that is not a comment, attribute or decorator
+fn signature_line(content: &str) -> String {
+	let mut lines = content
+		.lines()
+		.map(str::trim)
+		.filter(|line| !line.is_empty());
+	let first = lines.clone().next().unwrap_or_default();
+	let line = lines
+		.find(|line| {
+			comment_text(line).is_none() && !line.starts_with("#[") && !line.starts_with('@')
+		})
+		.unwrap_or(first);
+
+	if line.chars().count() > MAX_SIGNATURE_CHARS {
+		let truncated: String = line.chars().take(MAX_SIGNATURE_CHARS).collect();
+		format!("{}...", truncated)
+	} else {
+		line.to_string()
+	}
+}
+
+/// Comment at the top of a file, or the module docstring, leaving out license
+/// headers
+fn leading_comment(contents: &str) -> Option<String> {
+	// Comment paragraphs, split by blank lines
+	let mut paragraphs: Vec<Vec<&str>> = vec![Vec::new()];
+	let mut docstring: Option<&str> = None;
+
+	for line in contents.lines().map(str::trim) {
+		let text = if let Some(delimiter) = docstring {
+			match line.find(delimiter) {
+				Some(end) => {
+					docstring = None;
+					&line[..end]
+				}
+				None => line,
+			}
+		} else if line.is_empty() {
+			if paragraphs
+				.last()
+				.is_some_and(|paragraph| !paragraph.is_empty())
+			{
+				paragraphs.push(Vec::new());
+			}
+			continue;
+		} else if line.starts_with("#!") {
+			// Shebangs and inner attributes such as `#![allow(...)]`
+			continue;
+		} else if let Some(delimiter) = ["\"\"\"", "'''"]
+			.into_iter()
+			.find(|delimiter| line.starts_with(delimiter))
+		{
+			let rest = &line[delimiter.len()..];
+			match rest.find(delimiter) {
+				Some(end) => &rest[..end],
+				None => {
+					docstring = Some(delimiter);
+					rest
+				}
+			}
+		} else {
+			match comment_text(line) {
+				Some(text) => text,
+				None => break,
+			}
+		};
+
+		let text = text.trim();
+		if !text.is_empty() {
+			if let Some(paragraph) = paragraphs.last_mut() {
+				paragraph.push(text);
+			}
+		}
+	}
+
+	let lines: Vec<&str> = paragraphs
+		.into_iter()
+		.filter(|paragraph| {
+			let text = paragraph.join(" ").to_lowercase();
+			!text.contains("license") && !text.contains("copyright")
+		})
+		.flatten()
+		.take(MAX_COMMENT_LINES)
+		.collect();
+	if lines.is_empty() {
+		None
+	} else {
+		Some(lines.join("\n"))
+	}
+}
+
+/// Text of a comment line, or None when the line is not a comment
+fn comment_text(line: &str) -> Option<&str> {
+	for marker in ["//!", "///", "//", "/**", "/*", "*/", "--", ";;"] {
+		if let Some(text) = line.strip_prefix(marker) {
+			return Some(text.trim_end_matches("*/"));
+		}
+	}
+	// `#` comments, but not `#include` or `#[derive]`
+	if let Some(text) = line.strip_prefix('#') {
+		if text.is_empty() || text.starts_with(' ') || text.starts_with('#') {
+			return Some(text.trim_start_matches('#'));
+		}
+	}
+	// Continuation lines of block comments
+	if line == "*" || line.starts_with("* ") {
+		return Some(&line[1..]);
+	}
+	None
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_overview() {
+		let contents = "#!/usr/bin/env python3\n\
+			# Copyright 2025 Example Corp\n\
+			# Licensed under the MIT License\n\
+			\n\
+			\"\"\"Payment processing service.\n\
+			\n\
+			Charges cards and issues refunds.\n\
+			\"\"\"\n\
+			\n\
+			import stripe\n";
+		assert_eq!(
+			leading_comment(contents).as_deref(),
+			Some("Payment processing service.\nCharges cards and issues refunds.")
+		);
+		assert_eq!(leading_comment("fn main() {}\n// not leading\n"), None);
+
+		let entries = vec![
+			OutlineEntry::new(
+				"# Charges a card\n@retry\ndef charge(card, amount):\n    pass",
+				11,
+				20,
+			),
+			OutlineEntry::new("class Refund:\n    pass", 22, 40),
+		];
+		assert_eq!(entries[0].signature, "def charge(card, amount):");
+
+		let overview = build_overview(
+			"payments/service.py",
+			"python",
+			leading_comment(contents).as_deref(),
+			&entries,
+			None,
+		);
+		assert_eq!(
+			overview,
+			"File overview: payments/service.py (python, 2 blocks, lines 12-41)\n\n\
+			Payment processing service.\nCharges cards and issues refunds.\n\n\
+			Contents:\n\
+			- L12-21 def charge(card, amount):\n\
+			- L23-41 class Refund:"
+		);
+	}
+}
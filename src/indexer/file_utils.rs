@@ -110,6 +110,20 @@ impl FileUtils {
 			"rb" => Some("ruby"),
 			// JSON
 			"json" => Some("json"),
+			// Terraform/HCL
+			"tf" | "tfvars" | "hcl" => Some("hcl"),
+			// SQL
+			"sql" => Some("sql"),
+			// Zig
+			"zig" => Some("zig"),
+			// Protocol Buffers
+			"proto" => Some("proto"),
+			// Haskell
+			"hs" => Some("haskell"),
+			// Lua
+			"lua" => Some("lua"),
+			// Dart
+			"dart" => Some("dart"),
 			// Svelte
 			"svelte" => Some("svelte"),
 			// CSS
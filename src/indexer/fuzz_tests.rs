@@ -0,0 +1,157 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Property tests for text and code chunking
+//!
+//! Files are generated from adversarial fragments (huge lines, unicode,
+//! unbalanced brackets and quotes, stray blank lines) mixed with arbitrary
+//! text. Chunking must never panic and must only produce line ranges that
+//! exist in the file and match the chunk content.
+
+use proptest::prelude::*;
+use tree_sitter::Parser;
+
+use crate::config::ChunkStrategy;
+use crate::indexer::code_region_extractor::extract_meaningful_regions;
+use crate::indexer::languages::get_language;
+use crate::indexer::text_processing::{TextChunkWithLines, TextProcessor};
+
+const STRATEGIES: [ChunkStrategy; 4] = [
+	ChunkStrategy::Fixed,
+	ChunkStrategy::Sentence,
+	ChunkStrategy::Paragraph,
+	ChunkStrategy::Tokens,
+];
+
+fn fragment() -> impl Strategy<Value = String> {
+	prop_oneof![
+		4 => prop::sample::select(vec![
+			"",
+			"   ",
+			"\t\t",
+			"fn main() {",
+			"}",
+			"{{{{",
+			"))]]",
+			"\"unterminated string",
+			"/* open comment",
+			"*/",
+			"// comment",
+			"#[derive(Debug)]",
+			"def broken(:",
+			"    return x",
+			"class A:",
+			"function f() { return `${",
+			"=> {",
+			"key: value",
+			"  nested: [1, 2",
+			"[section]",
+			"End of a sentence.",
+			"Ünïcödé ✓ 日本語 🚀 \u{200b}",
+			"\r",
+		])
+		.prop_map(str::to_string),
+		2 => "\\PC{0,80}",
+		1 => (1usize..6000, prop::sample::select(vec!["x", "é", "🚀", "{", " "]))
+			.prop_map(|(count, unit)| unit.repeat(count)),
+	]
+}
+
+fn source_file() -> impl Strategy<Value = String> {
+	prop::collection::vec(fragment(), 0..80).prop_map(|lines| lines.join("\n"))
+}
+
+/// Chunk ranges are 1-based, within the file and match their content
+fn assert_chunk_lines(content: &str, chunks: &[TextChunkWithLines]) {
+	let lines: Vec<&str> = content.lines().collect();
+	for chunk in chunks {
+		assert!(chunk.start_line >= 1, "line numbers are 1-based");
+		assert!(chunk.start_line <= chunk.end_line);
+		assert!(chunk.end_line <= lines.len());
+		assert_eq!(
+			chunk.content,
+			lines[chunk.start_line - 1..chunk.end_line].join("\n")
+		);
+	}
+}
+
+/// Every line with content ends up in at least one chunk
+fn assert_covers_content(content: &str, chunks: &[TextChunkWithLines]) {
+	for (idx, line) in content.lines().enumerate() {
+		if !line.trim().is_empty() {
+			assert!(
+				chunks
+					.iter()
+					.any(|chunk| chunk.start_line <= idx + 1 && idx < chunk.end_line),
+				"line {} is in no chunk",
+				idx + 1
+			);
+		}
+	}
+}
+
+proptest! {
+	#![proptest_config(ProptestConfig::with_cases(64))]
+
+	#[test]
+	fn prop_text_chunking_keeps_line_ranges_valid(
+		content in source_file(),
+		chunk_size in 0usize..400,
+		overlap in 0usize..120,
+	) {
+		for strategy in STRATEGIES {
+			let chunks =
+				TextProcessor::chunk_text_with_strategy(&content, strategy, chunk_size, overlap);
+			assert_chunk_lines(&content, &chunks);
+			assert_covers_content(&content, &chunks);
+
+			for pair in chunks.windows(2) {
+				if strategy == ChunkStrategy::Fixed {
+					// Overlapping windows, but always moving forward
+					prop_assert!(pair[0].start_line < pair[1].start_line);
+				} else {
+					prop_assert!(pair[0].end_line < pair[1].start_line);
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn prop_code_regions_stay_in_file(
+		content in source_file(),
+		language in prop::sample::select(vec!["rust", "python", "javascript", "json"]),
+	) {
+		let lang_impl = get_language(language).unwrap();
+		let mut parser = Parser::new();
+		parser.set_language(&lang_impl.get_ts_language()).unwrap();
+		let Some(tree) = parser.parse(&content, None) else {
+			return Ok(());
+		};
+
+		let mut regions = Vec::new();
+		extract_meaningful_regions(tree.root_node(), &content, lang_impl.as_ref(), &mut regions);
+
+		// Rows are 0-based; the last row is the number of line breaks
+		let last_row = content.matches('\n').count();
+		for region in &regions {
+			prop_assert!(region.start_line <= region.end_line);
+			prop_assert!(region.end_line <= last_row);
+			prop_assert!(!region.symbols.is_empty());
+		}
+		// Regions follow the file and never nest; neighbours may share a line
+		for pair in regions.windows(2) {
+			prop_assert!(pair[0].end_line <= pair[1].start_line);
+		}
+	}
+}
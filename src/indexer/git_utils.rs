@@ -80,6 +80,107 @@ impl GitUtils {
 		Ok(changed_files.into_iter().collect())
 	}
 
+	/// Get files renamed between a commit and HEAD as `(old path, new path)` pairs
+	pub fn get_renamed_files_since_commit(
+		repo_path: &Path,
+		since_commit: &str,
+	) -> Result<Vec<(String, String)>> {
+		let output = Command::new("git")
+			.args(["diff", "--name-status", "-M", since_commit, "HEAD"])
+			.current_dir(repo_path)
+			.output()?;
+
+		if !output.status.success() {
+			return Err(anyhow::anyhow!(
+				"Failed to detect renames since {}: {}",
+				since_commit,
+				String::from_utf8_lossy(&output.stderr).trim()
+			));
+		}
+
+		// Rename lines look like `R087\told/path\tnew/path`
+		Ok(String::from_utf8(output.stdout)?
+			.lines()
+			.filter_map(|line| {
+				let mut fields = line.split('\t');
+				let status = fields.next()?;
+				if !status.starts_with('R') {
+					return None;
+				}
+				Some((fields.next()?.to_string(), fields.next()?.to_string()))
+			})
+			.collect())
+	}
+
+	/// Resolve a revision (branch, tag, short hash) to a full commit hash
+	pub fn resolve_commit(repo_path: &Path, revision: &str) -> Result<String> {
+		let output = Command::new("git")
+			.args(["rev-parse", "--verify", &format!("{}^{{commit}}", revision)])
+			.current_dir(repo_path)
+			.output()?;
+
+		if !output.status.success() {
+			return Err(anyhow::anyhow!("Unknown commit '{}'", revision));
+		}
+
+		Ok(String::from_utf8(output.stdout)?.trim().to_string())
+	}
+
+	/// Get files changed between two commits, listing both sides of a rename
+	pub fn get_changed_files_between_commits(
+		repo_path: &Path,
+		from_commit: &str,
+		to_commit: &str,
+	) -> Result<Vec<String>> {
+		let output = Command::new("git")
+			.args([
+				"diff",
+				"--name-only",
+				"--no-renames",
+				from_commit,
+				to_commit,
+			])
+			.current_dir(repo_path)
+			.output()?;
+
+		if !output.status.success() {
+			return Err(anyhow::anyhow!(
+				"Failed to diff {}..{}: {}",
+				from_commit,
+				to_commit,
+				String::from_utf8_lossy(&output.stderr).trim()
+			));
+		}
+
+		let mut files: Vec<String> = String::from_utf8(output.stdout)?
+			.lines()
+			.map(|line| line.trim())
+			.filter(|line| !line.is_empty())
+			.map(|line| line.to_string())
+			.collect();
+		files.sort();
+		files.dedup();
+		Ok(files)
+	}
+
+	/// Contents of a file at a commit; `None` when the file doesn't exist there
+	pub fn get_file_at_commit(
+		repo_path: &Path,
+		commit: &str,
+		file_path: &str,
+	) -> Result<Option<String>> {
+		let output = Command::new("git")
+			.args(["show", &format!("{}:{}", commit, file_path)])
+			.current_dir(repo_path)
+			.output()?;
+
+		if !output.status.success() {
+			return Ok(None);
+		}
+
+		Ok(Some(String::from_utf8_lossy(&output.stdout).into_owned()))
+	}
+
 	/// Get only staged files (files in git index)
 	pub fn get_staged_files(repo_path: &Path) -> Result<Vec<String>> {
 		let mut staged_files = Vec::new();
@@ -15,13 +15,23 @@
 // GraphRAG AI-powered enhancements
 
 use crate::config::Config;
-use crate::indexer::graphrag::types::{CodeNode, CodeRelationship};
+use crate::indexer::graphrag::communities::Community;
+use crate::indexer::graphrag::types::{CodeGraph, CodeNode, CodeRelationship};
 use anyhow::Result;
 use reqwest::Client;
 use serde::Deserialize;
 use serde_json::json;
 use std::collections::HashMap;
 
+const COMMUNITY_SYSTEM_PROMPT: &str =
+	"You describe a module of a codebase to a developer who is new to it. \
+You get a group of files that depend on each other, with a short description of each. \
+In 2-4 sentences, say what the module is responsible for and which files to read first. \
+Do not list every file and do not invent behaviour that the descriptions do not mention.";
+
+/// Files described in a community summary prompt
+const COMMUNITY_PROMPT_FILES: usize = 30;
+
 pub struct AIEnhancements {
 	config: Config,
 	client: Client,
@@ -563,9 +573,14 @@ impl AIEnhancements {
 		prompt: String,
 		json_schema: Option<serde_json::Value>,
 	) -> Result<String> {
-		// Check if we have an API key configured
+		// Check if we have an API key configured, otherwise try the MCP client's model
 		let api_key = match &self.config.openrouter.api_key {
 			Some(key) => key.clone(),
+			None if crate::mcp::sampling::is_available() => {
+				return self
+					.call_llm_via_sampling(model_name, system, prompt, json_schema)
+					.await;
+			}
 			None => return Err(anyhow::anyhow!("OpenRouter API key not configured")),
 		};
 
@@ -630,6 +645,32 @@ impl AIEnhancements {
 		}
 	}
 
+	// Route an LLM request through MCP client sampling (no server-side API key needed)
+	async fn call_llm_via_sampling(
+		&self,
+		model_name: &str,
+		system: String,
+		prompt: String,
+		json_schema: Option<serde_json::Value>,
+	) -> Result<String> {
+		// Sampling has no structured output support, so the schema goes into the prompt
+		let system = match json_schema {
+			Some(schema) => format!(
+				"{}\n\nRespond ONLY with JSON that matches this schema, without markdown fences:\n{}",
+				system, schema
+			),
+			None => system,
+		};
+
+		crate::mcp::sampling::create_message(
+			&system,
+			&prompt,
+			Some(model_name),
+			crate::mcp::sampling::SAMPLING_DEFAULT_MAX_TOKENS,
+		)
+		.await
+	}
+
 	// Extract AI-powered description for complex files (legacy single-file method)
 	pub async fn extract_ai_description(
 		&self,
@@ -683,4 +724,47 @@ impl AIEnhancements {
 			}
 		}
 	}
+
+	// Summarize what a community of files does together, for onboarding
+	pub async fn summarize_community(
+		&self,
+		community: &Community,
+		graph: &CodeGraph,
+	) -> Result<String> {
+		let mut prompt = format!(
+			"Module: {} ({} files, most connected first)\n\n",
+			community.label,
+			community.files.len()
+		);
+		let ordered = community.key_files.iter().chain(
+			community
+				.files
+				.iter()
+				.filter(|file| !community.key_files.contains(file)),
+		);
+		for file in ordered.take(COMMUNITY_PROMPT_FILES) {
+			match graph.nodes.get(file) {
+				Some(node) if !node.description.is_empty() => {
+					prompt.push_str(&format!("- {}: {}\n", file, node.description))
+				}
+				_ => prompt.push_str(&format!("- {}\n", file)),
+			}
+		}
+		if community.files.len() > COMMUNITY_PROMPT_FILES {
+			prompt.push_str(&format!(
+				"- ... and {} more files\n",
+				community.files.len() - COMMUNITY_PROMPT_FILES
+			));
+		}
+
+		let summary = self
+			.call_llm(
+				&self.config.graphrag.llm.description_model,
+				COMMUNITY_SYSTEM_PROMPT.to_string(),
+				prompt,
+				None,
+			)
+			.await?;
+		Ok(summary.trim().to_string())
+	}
 }
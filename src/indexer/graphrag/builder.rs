@@ -24,6 +24,7 @@ use crate::indexer::graphrag::database::DatabaseOperations;
 use crate::indexer::graphrag::relationships::RelationshipDiscovery;
 use crate::indexer::graphrag::types::{CodeGraph, CodeNode, CodeRelationship};
 use crate::indexer::graphrag::utils::{cosine_similarity, detect_project_root, to_relative_path};
+use crate::indexer::signature_extractor::extract_function_calls;
 use crate::state::SharedState;
 use crate::store::{CodeBlock, Store};
 use anyhow::{Context, Result};
@@ -177,111 +178,28 @@ impl GraphBuilder {
 					}
 				}
 
-				// Extract file information efficiently
-				let file_name = Path::new(&file_path)
-					.file_stem()
-					.and_then(|s| s.to_str())
-					.unwrap_or("unknown")
-					.to_string();
-
-				// Determine file kind based on path patterns
-				let kind = RelationshipDiscovery::determine_file_kind(&relative_path);
-
-				// Extract language from the first block (should be consistent)
-				let language = file_blocks
-					.first()
-					.map(|b| b.language.clone())
-					.unwrap_or_else(|| "unknown".to_string());
-
-				// Collect all symbols from all blocks
-				let mut all_symbols = HashSet::new();
-				let mut all_functions = Vec::new();
-				let mut total_lines = 0;
-
-				for block in &file_blocks {
-					all_symbols.extend(block.symbols.iter().cloned());
-					total_lines = total_lines.max(block.end_line);
-
-					// Extract function information from this block
-					if let Ok(functions) =
-						RelationshipDiscovery::extract_functions_from_block(block)
-					{
-						all_functions.extend(functions);
-					}
-				}
-
-				let symbols: Vec<String> = all_symbols.into_iter().collect();
-
-				// Extract imports and exports using language-specific AST parsing
-				let (imports, exports) = self
-					.extract_imports_exports_from_file(&file_path, &language)
-					.await
-					.unwrap_or_else(|e| {
-						if !self.quiet {
-							eprintln!(
-								"⚠️  Import/export extraction failed for {}: {}",
-								relative_path, e
-							);
-						}
-						// Fallback to old method if AST parsing fails
-						RelationshipDiscovery::extract_imports_exports_efficient(
-							&symbols,
-							&language,
-							&relative_path,
-						)
-					});
-
-				if !self.quiet && (!imports.is_empty() || !exports.is_empty()) {
-					eprintln!(
-						"📦 Found {} imports, {} exports in {}",
-						imports.len(),
-						exports.len(),
-						relative_path
-					);
-					if !imports.is_empty() {
-						eprintln!("  Imports: {:?}", imports);
-					}
-					if !exports.is_empty() {
-						eprintln!("  Exports: {:?}", exports);
-					}
-				}
-
-				// Generate description - collect for batch AI processing when enabled
-				let description = if self.llm_enabled()
-					&& self.should_use_ai_for_description(&symbols, total_lines as u32, &language)
-				{
+				let node = self
+					.build_file_node(&file_path, &relative_path, &file_blocks, content_hash)
+					.await;
+
+				// Collect for batch AI processing when enabled
+				if self.llm_enabled()
+					&& self.should_use_ai_for_description(
+						&node.symbols,
+						node.size_lines,
+						&node.language,
+					) {
 					if !self.quiet {
 						eprintln!(
 							"🤖 Collecting for AI batch: {} ({} lines, {} symbols)",
 							relative_path,
-							total_lines,
-							symbols.len()
+							node.size_lines,
+							node.symbols.len()
 						);
 					}
 
 					// Collect file for batch processing
-					let content_sample = self.build_content_sample_for_ai(&file_blocks);
-					let file_for_ai = crate::indexer::graphrag::ai::FileForAI {
-						file_id: file_path.clone(),
-						file_path: file_path.clone(),
-						language: language.clone(),
-						symbols: symbols.clone(),
-						content_sample,
-						function_count: symbols
-							.iter()
-							.filter(|s| {
-								s.contains("fn ") || s.contains("function ") || s.contains("def ")
-							})
-							.count(),
-						class_count: symbols
-							.iter()
-							.filter(|s| {
-								s.contains("class ")
-									|| s.contains("struct ") || s.contains("interface ")
-							})
-							.count(),
-					};
-					ai_batch_queue.push(file_for_ai);
+					ai_batch_queue.push(self.build_file_for_ai(&file_path, &node, &file_blocks));
 
 					// Process AI batch when it reaches configured size
 					if ai_batch_queue.len() >= self.config.graphrag.llm.ai_batch_size {
@@ -338,52 +256,15 @@ impl GraphBuilder {
 							}
 						}
 					}
-
-					// For now, use simple description - will be replaced after batch processing
-					RelationshipDiscovery::generate_simple_description(
-						&file_name,
-						&language,
-						&symbols,
-						total_lines as u32,
-					)
-				} else {
-					if !self.quiet && self.llm_enabled() {
-						eprintln!(
-							"📝 Using simple description for: {} (AI criteria not met)",
-							relative_path
-						);
-					}
-					RelationshipDiscovery::generate_simple_description(
-						&file_name,
-						&language,
-						&symbols,
-						total_lines as u32,
-					)
-				};
-
-				// Generate summary text for embedding (much lighter than full content)
-				let summary_text =
-					format!("{} {} symbols: {}", file_name, language, symbols.join(" "));
+				} else if !self.quiet && self.llm_enabled() {
+					eprintln!(
+						"📝 Using simple description for: {} (AI criteria not met)",
+						relative_path
+					);
+				}
 
 				// Store summary text for batch embedding generation
-				pending_embeddings.push(summary_text);
-
-				// Create the file node without embedding (will be added later)
-				let node = CodeNode {
-					id: relative_path.clone(),
-					name: file_name,
-					kind,
-					path: relative_path.clone(),
-					description,
-					symbols,
-					imports,
-					exports,
-					functions: all_functions,
-					hash: content_hash,
-					embedding: Vec::new(), // Will be filled after batch embedding
-					size_lines: total_lines as u32,
-					language,
-				};
+				pending_embeddings.push(Self::node_summary_text(&node));
 
 				new_nodes.push(node);
 				processed_count += 1;
@@ -629,6 +510,285 @@ impl GraphBuilder {
 		}
 	}
 
+	// Build a file node from its code blocks (description is the simple one, embedding is empty)
+	async fn build_file_node(
+		&self,
+		file_path: &str,
+		relative_path: &str,
+		file_blocks: &[&CodeBlock],
+		content_hash: String,
+	) -> CodeNode {
+		// Extract file information efficiently
+		let file_name = Path::new(file_path)
+			.file_stem()
+			.and_then(|s| s.to_str())
+			.unwrap_or("unknown")
+			.to_string();
+
+		// Determine file kind based on path patterns
+		let kind = RelationshipDiscovery::determine_file_kind(relative_path);
+
+		// Extract language from the first block (should be consistent)
+		let language = file_blocks
+			.first()
+			.map(|b| b.language.clone())
+			.unwrap_or_else(|| "unknown".to_string());
+
+		// Collect all symbols from all blocks
+		let mut all_symbols = HashSet::new();
+		let mut all_functions = Vec::new();
+		let mut total_lines = 0;
+
+		for block in file_blocks {
+			all_symbols.extend(block.symbols.iter().cloned());
+			total_lines = total_lines.max(block.end_line);
+
+			// Extract function information from this block
+			if let Ok(functions) = RelationshipDiscovery::extract_functions_from_block(block) {
+				all_functions.extend(functions);
+			}
+		}
+
+		let symbols: Vec<String> = all_symbols.into_iter().collect();
+
+		// Functions with the calls they make, from the syntax tree; the block
+		// symbols above remain for languages without call extraction
+		if let Some(functions) = std::fs::read_to_string(file_path)
+			.ok()
+			.and_then(|contents| extract_function_calls(relative_path, &contents).ok())
+			.filter(|functions| !functions.is_empty())
+		{
+			all_functions = functions
+				.into_iter()
+				.map(RelationshipDiscovery::function_info)
+				.collect();
+		}
+
+		// Extract imports and exports using language-specific AST parsing
+		let (imports, exports) = self
+			.extract_imports_exports_from_file(file_path, &language)
+			.await
+			.unwrap_or_else(|e| {
+				if !self.quiet {
+					eprintln!(
+						"⚠️  Import/export extraction failed for {}: {}",
+						relative_path, e
+					);
+				}
+				// Fallback to old method if AST parsing fails
+				RelationshipDiscovery::extract_imports_exports_efficient(
+					&symbols,
+					&language,
+					relative_path,
+				)
+			});
+
+		if !self.quiet && (!imports.is_empty() || !exports.is_empty()) {
+			eprintln!(
+				"📦 Found {} imports, {} exports in {}",
+				imports.len(),
+				exports.len(),
+				relative_path
+			);
+			if !imports.is_empty() {
+				eprintln!("  Imports: {:?}", imports);
+			}
+			if !exports.is_empty() {
+				eprintln!("  Exports: {:?}", exports);
+			}
+		}
+
+		// AI descriptions, when enabled, replace this after batch processing
+		let description = RelationshipDiscovery::generate_simple_description(
+			&file_name,
+			&language,
+			&symbols,
+			total_lines as u32,
+		);
+
+		CodeNode {
+			id: relative_path.to_string(),
+			name: file_name,
+			kind,
+			path: relative_path.to_string(),
+			description,
+			symbols,
+			imports,
+			exports,
+			functions: all_functions,
+			hash: content_hash,
+			embedding: Vec::new(), // Will be filled after batch embedding
+			size_lines: total_lines as u32,
+			language,
+		}
+	}
+
+	// Describe a file node for AI description extraction
+	fn build_file_for_ai(
+		&self,
+		file_path: &str,
+		node: &CodeNode,
+		file_blocks: &[&CodeBlock],
+	) -> crate::indexer::graphrag::ai::FileForAI {
+		crate::indexer::graphrag::ai::FileForAI {
+			file_id: file_path.to_string(),
+			file_path: file_path.to_string(),
+			language: node.language.clone(),
+			symbols: node.symbols.clone(),
+			content_sample: self.build_content_sample_for_ai(file_blocks),
+			function_count: node
+				.symbols
+				.iter()
+				.filter(|s| s.contains("fn ") || s.contains("function ") || s.contains("def "))
+				.count(),
+			class_count: node
+				.symbols
+				.iter()
+				.filter(|s| {
+					s.contains("class ") || s.contains("struct ") || s.contains("interface ")
+				})
+				.count(),
+		}
+	}
+
+	// Summary text used for node embeddings (much lighter than full content)
+	fn node_summary_text(node: &CodeNode) -> String {
+		format!(
+			"{} {} symbols: {}",
+			node.name,
+			node.language,
+			node.symbols.join(" ")
+		)
+	}
+
+	// Update the graph for a single changed file without touching the rest of the graph.
+	// Only the file's node and its outgoing relationships are recomputed; edges from other
+	// nodes into this file are kept. Passing no code blocks removes the file from the graph.
+	pub async fn update_file_differential(
+		&self,
+		file_path: &str,
+		code_blocks: &[CodeBlock],
+	) -> Result<()> {
+		let relative_path = self.to_relative_path(file_path)?;
+
+		if code_blocks.is_empty() {
+			return self.remove_file_from_graph(&relative_path).await;
+		}
+
+		let file_blocks: Vec<&CodeBlock> = code_blocks.iter().collect();
+		let combined_content: String = file_blocks
+			.iter()
+			.map(|b| b.content.as_str())
+			.collect::<Vec<_>>()
+			.join("\n");
+		let content_hash = calculate_unique_content_hash(&combined_content, file_path);
+
+		if is_unchanged(&*self.graph.read().await, &relative_path, &content_hash) {
+			return Ok(());
+		}
+
+		let mut node = self
+			.build_file_node(file_path, &relative_path, &file_blocks, content_hash)
+			.await;
+
+		if self.llm_enabled()
+			&& self.should_use_ai_for_description(&node.symbols, node.size_lines, &node.language)
+		{
+			if let Some(ref ai_enhancements) = self.ai_enhancements {
+				let file_for_ai = self.build_file_for_ai(file_path, &node, &file_blocks);
+				match ai_enhancements
+					.extract_ai_descriptions_batch(&[file_for_ai])
+					.await
+				{
+					Ok(descriptions) => {
+						if let Some(description) = descriptions.into_values().next() {
+							node.description = description;
+						}
+					}
+					Err(e) => {
+						if !self.quiet {
+							eprintln!("⚠️  AI description failed for {}: {}", relative_path, e);
+						}
+					}
+				}
+			}
+		}
+
+		// Replace the stored node and drop its outgoing edges; incoming edges stay valid
+		self.store
+			.remove_graph_nodes_by_path(&relative_path)
+			.await?;
+		self.store
+			.remove_graph_relationships_by_source(&relative_path)
+			.await?;
+
+		let mut pending_embeddings = vec![Self::node_summary_text(&node)];
+		let mut nodes = vec![node.clone()];
+		let mut batches_processed = 0;
+		self.process_nodes_batch(&mut nodes, &mut pending_embeddings, &mut batches_processed)
+			.await?;
+
+		// Recompute relationships for the updated node only
+		let discovered = if self.llm_enabled() {
+			self.discover_relationships_with_ai_enhancement(std::slice::from_ref(&node))
+				.await?
+		} else {
+			self.discover_relationships_efficiently(std::slice::from_ref(&node))
+				.await?
+		};
+
+		let (new_relationships, missing) = replace_outgoing_relationships(
+			&mut *self.graph.write().await,
+			&relative_path,
+			discovered,
+		);
+
+		let db_ops = DatabaseOperations::new(&self.store);
+		db_ops
+			.save_graph_incremental(&[], &new_relationships)
+			.await?;
+
+		self.remove_stored_relationships(&missing).await?;
+		self.store.flush().await?;
+
+		if !self.quiet {
+			eprintln!(
+				"GraphRAG: Updated {} ({} new relationships)",
+				relative_path,
+				new_relationships.len()
+			);
+		}
+
+		Ok(())
+	}
+
+	// Remove a file node and every relationship that touches it
+	async fn remove_file_from_graph(&self, relative_path: &str) -> Result<()> {
+		let (removed, missing) = remove_file(&mut *self.graph.write().await, relative_path);
+
+		self.store.remove_graph_nodes_by_path(relative_path).await?;
+		self.store
+			.remove_graph_relationships_by_path(relative_path)
+			.await?;
+		self.remove_stored_relationships(&missing).await?;
+		self.store.flush().await?;
+
+		if removed && !self.quiet {
+			eprintln!("GraphRAG: Removed {}", relative_path);
+		}
+
+		Ok(())
+	}
+
+	// Drop the stored relationships of nodes that are gone from the graph
+	async fn remove_stored_relationships(&self, missing: &HashSet<String>) -> Result<usize> {
+		let mut removed = 0;
+		for id in missing {
+			removed += self.store.remove_graph_relationships_by_path(id).await?;
+		}
+		Ok(removed)
+	}
+
 	// Legacy method for backward compatibility - now uses efficient code block processing
 	pub async fn process_code_blocks(
 		&self,
@@ -1014,3 +1174,72 @@ fn extract_imports_exports_recursive(
 		extract_imports_exports_recursive(child, contents, lang_impl, all_imports, all_exports);
 	}
 }
+
+// Whether the stored node of a file already has the given content hash
+pub(crate) fn is_unchanged(graph: &CodeGraph, relative_path: &str, content_hash: &str) -> bool {
+	graph
+		.nodes
+		.get(relative_path)
+		.is_some_and(|node| node.hash == content_hash)
+}
+
+// Replace the outgoing relationships of an updated file with the discovered ones.
+// Returns the relationships that are new to the graph and the ids of nodes that
+// dangling relationships pointed to.
+pub(crate) fn replace_outgoing_relationships(
+	graph: &mut CodeGraph,
+	relative_path: &str,
+	discovered: Vec<CodeRelationship>,
+) -> (Vec<CodeRelationship>, HashSet<String>) {
+	graph
+		.relationships
+		.retain(|rel| rel.source != relative_path);
+
+	// Edges discovered from the other side (e.g. hierarchical ones) may already exist
+	let mut fresh = Vec::new();
+	for rel in discovered {
+		let exists = graph.relationships.iter().any(|existing| {
+			existing.source == rel.source
+				&& existing.target == rel.target
+				&& existing.relation_type == rel.relation_type
+		});
+		if !exists {
+			graph.relationships.push(rel.clone());
+			fresh.push(rel);
+		}
+	}
+
+	let missing = prune_dangling_relationships(graph);
+	(fresh, missing)
+}
+
+// Remove a file node and every relationship that touches it. Returns whether the
+// node existed and the ids of nodes that dangling relationships pointed to.
+pub(crate) fn remove_file(graph: &mut CodeGraph, relative_path: &str) -> (bool, HashSet<String>) {
+	let removed = graph.nodes.remove(relative_path).is_some();
+	graph
+		.relationships
+		.retain(|rel| rel.source != relative_path && rel.target != relative_path);
+	(removed, prune_dangling_relationships(graph))
+}
+
+// Drop relationships whose source or target node no longer exists and return those ids
+fn prune_dangling_relationships(graph: &mut CodeGraph) -> HashSet<String> {
+	let CodeGraph {
+		nodes,
+		relationships,
+	} = graph;
+
+	let mut missing = HashSet::new();
+	relationships.retain(|rel| {
+		let mut keep = true;
+		for id in [&rel.source, &rel.target] {
+			if !nodes.contains_key(id) {
+				missing.insert(id.clone());
+				keep = false;
+			}
+		}
+		keep
+	});
+	missing
+}
@@ -0,0 +1,400 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Function-level call graph over GraphRAG nodes
+//
+// Every file node lists its functions with the names each one calls, as found
+// by the language's tree-sitter call query. Names resolve to the functions of
+// that name, preferring the caller's own file. `called_by` is derived from
+// all nodes' calls when the graph is loaded rather than stored, since a change
+// to one file alters the callers of functions in others.
+
+use serde::Serialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::indexer::graphrag::types::{CodeGraph, CodeNode, FunctionInfo};
+
+/// A function of a graph node
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FunctionRef {
+	pub path: String,
+	pub name: String,
+	pub signature: String,
+	/// 1-indexed first line
+	pub line: u32,
+	/// 1-indexed last line
+	pub end_line: u32,
+}
+
+impl FunctionRef {
+	fn new(node: &CodeNode, function: &FunctionInfo) -> Self {
+		Self {
+			path: node.path.clone(),
+			name: function.name.clone(),
+			signature: function.signature.clone(),
+			line: function.start_line + 1,
+			end_line: function.end_line + 1,
+		}
+	}
+}
+
+/// What a function calls: functions defined in the graph, and other names
+/// (library or built-in functions)
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Callees {
+	pub functions: Vec<FunctionRef>,
+	pub external: Vec<String>,
+}
+
+/// Caller or callee reached while following calls
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CallSite {
+	/// 1 for direct callers/callees, 2 for theirs, ...
+	pub depth: usize,
+	pub function: FunctionRef,
+}
+
+/// Functions of the graph by name, as (node id, function index)
+pub(crate) struct FunctionIndex<'a> {
+	by_name: HashMap<&'a str, Vec<(&'a str, usize)>>,
+}
+
+impl<'a> FunctionIndex<'a> {
+	pub fn new(nodes: impl IntoIterator<Item = &'a CodeNode>) -> Self {
+		let mut by_name: HashMap<&str, Vec<(&str, usize)>> = HashMap::new();
+		for node in nodes {
+			for (index, function) in node.functions.iter().enumerate() {
+				by_name
+					.entry(function.name.as_str())
+					.or_default()
+					.push((node.id.as_str(), index));
+			}
+		}
+		for functions in by_name.values_mut() {
+			functions.sort();
+			functions.dedup();
+		}
+		Self { by_name }
+	}
+
+	/// Functions a call to `name` from `caller_node` may reach: those of the
+	/// caller's file when it defines the name, otherwise all of that name
+	pub fn resolve(&self, caller_node: &str, name: &str) -> Vec<(&'a str, usize)> {
+		let Some(candidates) = self.by_name.get(name) else {
+			return Vec::new();
+		};
+		let local: Vec<(&str, usize)> = candidates
+			.iter()
+			.copied()
+			.filter(|(node, _)| *node == caller_node)
+			.collect();
+		if local.is_empty() {
+			candidates.clone()
+		} else {
+			local
+		}
+	}
+}
+
+/// Recompute `called_by` of every function as `path:function` of its callers
+pub fn link_calls(graph: &mut CodeGraph) {
+	let mut called_by: HashMap<(String, usize), Vec<String>> = HashMap::new();
+	{
+		let index = FunctionIndex::new(graph.nodes.values());
+		for node in graph.nodes.values() {
+			for (caller, function) in node.functions.iter().enumerate() {
+				for name in &function.calls {
+					for (target, callee) in index.resolve(&node.id, name) {
+						if target == node.id && callee == caller {
+							continue;
+						}
+						called_by
+							.entry((target.to_string(), callee))
+							.or_default()
+							.push(format!("{}:{}", node.path, function.name));
+					}
+				}
+			}
+		}
+	}
+
+	for node in graph.nodes.values_mut() {
+		for (index, function) in node.functions.iter_mut().enumerate() {
+			let mut callers = called_by
+				.remove(&(node.id.clone(), index))
+				.unwrap_or_default();
+			callers.sort();
+			callers.dedup();
+			function.called_by = callers;
+		}
+	}
+}
+
+/// Functions matching `name`, `Type::name`, `object.name` or `path:name`
+/// (any path ending in the given one)
+pub fn find_functions(graph: &CodeGraph, target: &str) -> Vec<FunctionRef> {
+	let (path, name) = match target.rsplit_once(':') {
+		Some((path, name)) if !path.is_empty() && !path.ends_with(':') && !name.is_empty() => {
+			(Some(path), name)
+		}
+		_ => (None, target),
+	};
+	let name = name.rsplit(['.', ':']).next().unwrap_or(name).trim();
+
+	let mut functions: Vec<FunctionRef> = graph
+		.nodes
+		.values()
+		.filter(|node| path.is_none_or(|path| node.path.ends_with(path)))
+		.flat_map(|node| {
+			node.functions
+				.iter()
+				.filter(|function| function.name == name)
+				.map(move |function| FunctionRef::new(node, function))
+		})
+		.collect();
+	functions.sort_by(|a, b| a.path.cmp(&b.path).then(a.line.cmp(&b.line)));
+	functions
+}
+
+/// Direct callers of `function`; requires [`link_calls`] to have run
+pub fn callers(graph: &CodeGraph, function: &FunctionRef) -> Vec<FunctionRef> {
+	let Some(info) = lookup(graph, function) else {
+		return Vec::new();
+	};
+
+	let mut callers: Vec<FunctionRef> = info
+		.called_by
+		.iter()
+		.filter_map(|caller| caller.rsplit_once(':'))
+		.filter_map(|(path, name)| graph.nodes.get(path).map(|node| (node, name)))
+		.flat_map(|(node, name)| {
+			node.functions
+				.iter()
+				.filter(move |function| function.name == name)
+				.map(move |function| FunctionRef::new(node, function))
+		})
+		.filter(|caller| caller != function)
+		.collect();
+	callers.sort_by(|a, b| a.path.cmp(&b.path).then(a.line.cmp(&b.line)));
+	callers.dedup();
+	callers
+}
+
+/// Functions `function` calls directly, in call order
+pub fn callees(graph: &CodeGraph, function: &FunctionRef) -> Callees {
+	let Some(info) = lookup(graph, function) else {
+		return Callees::default();
+	};
+	let index = FunctionIndex::new(graph.nodes.values());
+
+	let mut callees = Callees::default();
+	for name in &info.calls {
+		let resolved = index.resolve(&function.path, name);
+		if resolved.is_empty() {
+			callees.external.push(name.clone());
+			continue;
+		}
+		for (node, callee) in resolved {
+			let node = &graph.nodes[node];
+			let callee = FunctionRef::new(node, &node.functions[callee]);
+			if callee != *function && !callees.functions.contains(&callee) {
+				callees.functions.push(callee);
+			}
+		}
+	}
+	callees
+}
+
+/// Functions calling `function`, up to `depth` levels away
+pub fn callers_within(graph: &CodeGraph, function: &FunctionRef, depth: usize) -> Vec<CallSite> {
+	walk(graph, function, depth, callers)
+}
+
+/// Functions defined in the graph that `function` calls, up to `depth`
+/// levels away
+pub fn callees_within(graph: &CodeGraph, function: &FunctionRef, depth: usize) -> Vec<CallSite> {
+	walk(graph, function, depth, |graph, function| {
+		callees(graph, function).functions
+	})
+}
+
+/// Functions calling `name` directly, whether or not the graph still defines
+/// it; used for functions that no longer exist
+pub fn callers_of_name(graph: &CodeGraph, name: &str) -> Vec<FunctionRef> {
+	let mut callers: Vec<FunctionRef> = graph
+		.nodes
+		.values()
+		.flat_map(|node| {
+			node.functions
+				.iter()
+				.filter(|function| {
+					function.name != name && function.calls.iter().any(|call| call == name)
+				})
+				.map(move |function| FunctionRef::new(node, function))
+		})
+		.collect();
+	callers.sort_by(|a, b| a.path.cmp(&b.path).then(a.line.cmp(&b.line)));
+	callers
+}
+
+/// Breadth-first walk along `next`, listing each function once, at the
+/// depth it is first reached
+fn walk(
+	graph: &CodeGraph,
+	start: &FunctionRef,
+	depth: usize,
+	next: impl Fn(&CodeGraph, &FunctionRef) -> Vec<FunctionRef>,
+) -> Vec<CallSite> {
+	let key =
+		|function: &FunctionRef| (function.path.clone(), function.name.clone(), function.line);
+	let mut sites = Vec::new();
+	let mut visited = HashSet::from([key(start)]);
+	let mut queue = VecDeque::from([(start.clone(), 0)]);
+
+	while let Some((current, level)) = queue.pop_front() {
+		if level >= depth {
+			continue;
+		}
+		for reached in next(graph, &current) {
+			if !visited.insert(key(&reached)) {
+				continue;
+			}
+			sites.push(CallSite {
+				depth: level + 1,
+				function: reached.clone(),
+			});
+			queue.push_back((reached, level + 1));
+		}
+	}
+	sites
+}
+
+fn lookup<'a>(graph: &'a CodeGraph, function: &FunctionRef) -> Option<&'a FunctionInfo> {
+	graph
+		.nodes
+		.get(&function.path)?
+		.functions
+		.iter()
+		.find(|info| info.name == function.name && info.start_line + 1 == function.line)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn function(name: &str, start_line: u32, calls: &[&str]) -> FunctionInfo {
+		FunctionInfo {
+			name: name.to_string(),
+			signature: format!("fn {}()", name),
+			start_line,
+			end_line: start_line + 3,
+			calls: calls.iter().map(|s| s.to_string()).collect(),
+			called_by: Vec::new(),
+			parameters: Vec::new(),
+			return_type: None,
+		}
+	}
+
+	fn node(path: &str, functions: Vec<FunctionInfo>) -> CodeNode {
+		CodeNode {
+			id: path.to_string(),
+			name: path.to_string(),
+			kind: "source_file".to_string(),
+			path: path.to_string(),
+			description: String::new(),
+			symbols: Vec::new(),
+			hash: String::new(),
+			embedding: Vec::new(),
+			imports: Vec::new(),
+			exports: Vec::new(),
+			functions,
+			size_lines: 20,
+			language: "rust".to_string(),
+		}
+	}
+
+	#[test]
+	fn test_callers_and_callees() {
+		let mut graph = CodeGraph::default();
+		for node in [
+			node(
+				"src/main.rs",
+				vec![
+					function("main", 0, &["load", "run", "println"]),
+					function("load", 5, &[]),
+				],
+			),
+			node("src/run.rs", vec![function("run", 0, &["load", "run"])]),
+			node("src/config.rs", vec![function("load", 0, &[])]),
+		] {
+			graph.nodes.insert(node.id.clone(), node);
+		}
+		link_calls(&mut graph);
+
+		let main = &find_functions(&graph, "main")[0];
+		let callees = callees(&graph, main);
+		let names: Vec<(&str, &str)> = callees
+			.functions
+			.iter()
+			.map(|f| (f.path.as_str(), f.name.as_str()))
+			.collect();
+		// The load of main's own file wins over src/config.rs
+		assert_eq!(names, vec![("src/main.rs", "load"), ("src/run.rs", "run")]);
+		assert_eq!(callees.external, vec!["println".to_string()]);
+
+		let loads = find_functions(&graph, "load");
+		assert_eq!(loads.len(), 2);
+		let config_load = &find_functions(&graph, "config.rs:load")[0];
+		assert_eq!(config_load.path, "src/config.rs");
+		let callers: Vec<String> = callers(&graph, config_load)
+			.iter()
+			.map(|f| format!("{}:{}", f.path, f.name))
+			.collect();
+		assert_eq!(callers, vec!["src/run.rs:run".to_string()]);
+
+		// Recursion is not listed as its own caller
+		let run = &find_functions(&graph, "Runner::run")[0];
+		assert_eq!(
+			graph.nodes["src/run.rs"].functions[0].called_by,
+			vec!["src/main.rs:main".to_string()]
+		);
+		assert_eq!(super::callers(&graph, run).len(), 1);
+
+		// Two levels down, main reaches nothing beyond its direct callees;
+		// one level up from config.rs's load is run, two levels up is main
+		let sites: Vec<(usize, String)> = callees_within(&graph, main, 2)
+			.into_iter()
+			.map(|site| (site.depth, site.function.name))
+			.collect();
+		assert_eq!(
+			sites,
+			vec![
+				(1, "load".to_string()),
+				(1, "run".to_string()),
+				(2, "load".to_string())
+			]
+		);
+		let sites: Vec<(usize, String)> = callers_within(&graph, config_load, 2)
+			.into_iter()
+			.map(|site| (site.depth, site.function.name))
+			.collect();
+		assert_eq!(sites, vec![(1, "run".to_string()), (2, "main".to_string())]);
+
+		let removed: Vec<String> = callers_of_name(&graph, "load")
+			.into_iter()
+			.map(|f| format!("{}:{}", f.path, f.name))
+			.collect();
+		assert_eq!(removed, vec!["src/main.rs:main", "src/run.rs:run"]);
+	}
+}
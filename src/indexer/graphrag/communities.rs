@@ -0,0 +1,327 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Community detection: groups of files more connected to each other than to the rest
+
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::indexer::graphrag::orphans::STRUCTURAL_RELATIONS;
+use crate::indexer::graphrag::types::{CodeGraph, CodeRelationship};
+
+/// Weight factor for relationships derived from file layout, so that sharing
+/// a directory pulls files together less than actually using each other
+const STRUCTURAL_FACTOR: f64 = 0.3;
+
+/// Upper bound of a single relationship's weight (call counts can be large)
+const MAX_EDGE_WEIGHT: f64 = 5.0;
+
+/// Files listed as the most connected members of a community
+const KEY_FILES: usize = 5;
+
+/// A cohesive group of files
+#[derive(Debug, Clone, Serialize)]
+pub struct Community {
+	/// 1-based, largest community first
+	pub id: usize,
+	/// Common directory of the files, or the directory of the best connected file
+	pub label: String,
+	pub files: Vec<String>,
+	/// Files with the strongest connections inside the community
+	pub key_files: Vec<String>,
+	/// Relationships between files of the community
+	pub internal_edges: usize,
+	/// Relationships between a file of the community and one outside it
+	pub external_edges: usize,
+	/// Share of the community's relationship weight that stays inside it, 0.0 to 1.0
+	pub cohesion: f32,
+	/// LLM summary of what the files do together, when requested
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub summary: Option<String>,
+}
+
+/// Result of clustering a graph
+#[derive(Debug, Clone, Serialize)]
+pub struct Communities {
+	pub communities: Vec<Community>,
+	/// Files without relationships strong enough to join any community
+	pub ungrouped: usize,
+	/// Newman modularity of the partition; above 0.3 means clear structure
+	pub modularity: f32,
+}
+
+fn edge_weight(relationship: &CodeRelationship) -> f64 {
+	let weight = (relationship.weight as f64).clamp(0.1, MAX_EDGE_WEIGHT)
+		* (relationship.confidence as f64).clamp(0.0, 1.0);
+	if STRUCTURAL_RELATIONS.contains(&relationship.relation_type.as_str()) {
+		weight * STRUCTURAL_FACTOR
+	} else {
+		weight
+	}
+}
+
+/// Symmetric weighted adjacency; a self-loop of weight w is stored as 2w so
+/// that row sums are node degrees
+type Adjacency = Vec<BTreeMap<usize, f64>>;
+
+/// One Louvain local moving phase: nodes move to the neighbouring community
+/// with the best modularity gain until none does. Returns the community of
+/// each node and whether any node moved.
+fn local_moving(adjacency: &Adjacency) -> (Vec<usize>, bool) {
+	let degrees: Vec<f64> = adjacency.iter().map(|row| row.values().sum()).collect();
+	let total: f64 = degrees.iter().sum();
+	let mut community: Vec<usize> = (0..adjacency.len()).collect();
+	if total <= 0.0 {
+		return (community, false);
+	}
+	let mut community_degree = degrees.clone();
+	let mut moved_any = false;
+
+	loop {
+		let mut moved = false;
+		for node in 0..adjacency.len() {
+			let current = community[node];
+			community_degree[current] -= degrees[node];
+
+			let mut links: BTreeMap<usize, f64> = BTreeMap::new();
+			links.insert(current, 0.0);
+			for (&neighbour, &weight) in &adjacency[node] {
+				if neighbour != node {
+					*links.entry(community[neighbour]).or_default() += weight;
+				}
+			}
+
+			let gain =
+				|target: usize, link: f64| link - community_degree[target] * degrees[node] / total;
+			let mut best = current;
+			let mut best_gain = gain(current, links[&current]);
+			for (&target, &link) in &links {
+				let target_gain = gain(target, link);
+				if target_gain > best_gain + 1e-12 {
+					best = target;
+					best_gain = target_gain;
+				}
+			}
+
+			community_degree[best] += degrees[node];
+			if best != current {
+				community[node] = best;
+				moved = true;
+				moved_any = true;
+			}
+		}
+		if !moved {
+			break;
+		}
+	}
+
+	// Renumber communities densely in order of first appearance
+	let mut renumbered: HashMap<usize, usize> = HashMap::new();
+	for id in community.iter_mut() {
+		let next = renumbered.len();
+		*id = *renumbered.entry(*id).or_insert(next);
+	}
+	(community, moved_any)
+}
+
+/// Collapse each community into a single node
+fn aggregate(adjacency: &Adjacency, community: &[usize]) -> Adjacency {
+	let count = community.iter().max().map_or(0, |max| max + 1);
+	let mut aggregated: Adjacency = vec![BTreeMap::new(); count];
+	for (node, row) in adjacency.iter().enumerate() {
+		for (&neighbour, &weight) in row {
+			*aggregated[community[node]]
+				.entry(community[neighbour])
+				.or_default() += weight;
+		}
+	}
+	aggregated
+}
+
+fn modularity(adjacency: &Adjacency, community: &[usize]) -> f64 {
+	let total: f64 = adjacency.iter().flat_map(|row| row.values()).sum();
+	if total <= 0.0 {
+		return 0.0;
+	}
+	let mut internal: HashMap<usize, f64> = HashMap::new();
+	let mut degree: HashMap<usize, f64> = HashMap::new();
+	for (node, row) in adjacency.iter().enumerate() {
+		for (&neighbour, &weight) in row {
+			if community[node] == community[neighbour] {
+				*internal.entry(community[node]).or_default() += weight;
+			}
+			*degree.entry(community[node]).or_default() += weight;
+		}
+	}
+	degree
+		.iter()
+		.map(|(id, degree)| {
+			internal.get(id).copied().unwrap_or(0.0) / total - (degree / total).powi(2)
+		})
+		.sum()
+}
+
+/// Longest directory prefix shared by all paths
+fn common_directory(paths: &[String]) -> String {
+	let mut prefix: Vec<&str> = match paths.first() {
+		Some(path) => path.split('/').collect(),
+		None => return String::new(),
+	};
+	// The last component of a path is the file name
+	prefix.pop();
+	for path in &paths[1..] {
+		let parts: Vec<&str> = path.split('/').collect();
+		let directory = &parts[..parts.len().saturating_sub(1)];
+		let shared = prefix
+			.iter()
+			.zip(directory)
+			.take_while(|(a, b)| a == b)
+			.count();
+		prefix.truncate(shared);
+	}
+	prefix.join("/")
+}
+
+fn directory_of(path: &str) -> String {
+	path.rsplit_once('/')
+		.map(|(directory, _)| directory.to_string())
+		.unwrap_or_default()
+}
+
+/// Group the files of a graph into communities with the Louvain method
+///
+/// Relationships are treated as undirected and weighted by strength and
+/// confidence. Files ending up alone are counted as ungrouped rather than
+/// listed. The result is deterministic for a given graph.
+pub fn detect_communities(graph: &CodeGraph) -> Communities {
+	let mut ids: Vec<&String> = graph.nodes.keys().collect();
+	ids.sort();
+	let index: HashMap<&str, usize> = ids
+		.iter()
+		.enumerate()
+		.map(|(i, id)| (id.as_str(), i))
+		.collect();
+
+	let mut adjacency: Adjacency = vec![BTreeMap::new(); ids.len()];
+	for relationship in &graph.relationships {
+		let (Some(&source), Some(&target)) = (
+			index.get(relationship.source.as_str()),
+			index.get(relationship.target.as_str()),
+		) else {
+			continue;
+		};
+		if source == target {
+			continue;
+		}
+		let weight = edge_weight(relationship);
+		*adjacency[source].entry(target).or_default() += weight;
+		*adjacency[target].entry(source).or_default() += weight;
+	}
+	let original = adjacency.clone();
+
+	// Community of each file, refined level by level
+	let mut membership: Vec<usize> = (0..ids.len()).collect();
+	loop {
+		let (community, moved) = local_moving(&adjacency);
+		if !moved {
+			break;
+		}
+		for id in membership.iter_mut() {
+			*id = community[*id];
+		}
+		adjacency = aggregate(&adjacency, &community);
+	}
+
+	let mut groups: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+	for (node, &id) in membership.iter().enumerate() {
+		groups.entry(id).or_default().push(node);
+	}
+
+	let mut ungrouped = 0;
+	let mut communities = Vec::new();
+	for members in groups.values() {
+		if members.len() < 2 {
+			ungrouped += members.len();
+			continue;
+		}
+		let group = membership[members[0]];
+		let mut internal_edges = 0;
+		let mut external_edges = 0;
+		let mut internal_weight = 0.0;
+		let mut external_weight = 0.0;
+		let mut strength: Vec<(f64, &String)> = Vec::new();
+		for &node in members {
+			let mut node_strength = 0.0;
+			for (&neighbour, &weight) in &original[node] {
+				if membership[neighbour] == group {
+					node_strength += weight;
+					// Each internal pair is seen from both ends
+					if node < neighbour {
+						internal_edges += 1;
+						internal_weight += weight;
+					}
+				} else {
+					external_edges += 1;
+					external_weight += weight;
+				}
+			}
+			strength.push((node_strength, ids[node]));
+		}
+		strength.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| a.1.cmp(b.1)));
+
+		let files: Vec<String> = members.iter().map(|&node| ids[node].clone()).collect();
+		let mut label = common_directory(&files);
+		if label.is_empty() {
+			label = directory_of(strength[0].1);
+		}
+		if label.is_empty() {
+			label = ".".to_string();
+		}
+
+		communities.push(Community {
+			id: 0,
+			label,
+			key_files: strength
+				.iter()
+				.take(KEY_FILES)
+				.map(|(_, path)| (*path).clone())
+				.collect(),
+			files,
+			internal_edges,
+			external_edges,
+			cohesion: if internal_weight + external_weight > 0.0 {
+				(internal_weight / (internal_weight + external_weight)) as f32
+			} else {
+				0.0
+			},
+			summary: None,
+		});
+	}
+
+	communities.sort_by(|a, b| {
+		b.files
+			.len()
+			.cmp(&a.files.len())
+			.then_with(|| a.label.cmp(&b.label))
+	});
+	for (i, community) in communities.iter_mut().enumerate() {
+		community.id = i + 1;
+	}
+
+	Communities {
+		communities,
+		ungrouped,
+		modularity: modularity(&original, &membership) as f32,
+	}
+}
@@ -0,0 +1,189 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Circular dependencies: strongly connected components of the import graph
+
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+use crate::indexer::graphrag::types::CodeGraph;
+
+/// Relationships a file depends on its target through
+const DEPENDENCY_RELATIONS: &[&str] = &["imports", "imports_direct", "contains"];
+
+/// Shortest cycles listed per group of files
+const MAX_CYCLES: usize = 5;
+
+/// Files that all depend on each other, directly or through one another
+#[derive(Debug, Clone, Serialize)]
+pub struct DependencyCycle {
+	pub files: Vec<String>,
+	/// Shortest cycles through the files, shortest first; each starts and
+	/// ends with the same file
+	pub cycles: Vec<Vec<String>>,
+}
+
+/// Groups of mutually dependent files, largest first
+pub fn find_cycles(graph: &CodeGraph) -> Vec<DependencyCycle> {
+	// Sorted ids and targets, so results don't depend on hash order
+	let ids: Vec<&str> = graph
+		.nodes
+		.keys()
+		.map(String::as_str)
+		.collect::<BTreeSet<_>>()
+		.into_iter()
+		.collect();
+	let index: HashMap<&str, usize> = ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+	let mut edges: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); ids.len()];
+	for relationship in &graph.relationships {
+		if !DEPENDENCY_RELATIONS.contains(&relationship.relation_type.as_str()) {
+			continue;
+		}
+		if let (Some(&source), Some(&target)) = (
+			index.get(relationship.source.as_str()),
+			index.get(relationship.target.as_str()),
+		) {
+			if source != target {
+				edges[source].insert(target);
+			}
+		}
+	}
+
+	let mut cycles: Vec<DependencyCycle> = strongly_connected(&edges)
+		.into_iter()
+		.filter(|component| component.len() > 1)
+		.map(|component| {
+			let cycles = shortest_cycles(&edges, &component)
+				.into_iter()
+				.map(|cycle| cycle.into_iter().map(|i| ids[i].to_string()).collect())
+				.collect();
+			DependencyCycle {
+				files: component.iter().map(|&i| ids[i].to_string()).collect(),
+				cycles,
+			}
+		})
+		.collect();
+	cycles.sort_by(|a, b| {
+		b.files
+			.len()
+			.cmp(&a.files.len())
+			.then(a.files.cmp(&b.files))
+	});
+	cycles
+}
+
+/// Tarjan's algorithm without recursion, as import chains can be long.
+/// Each component is sorted.
+fn strongly_connected(edges: &[BTreeSet<usize>]) -> Vec<Vec<usize>> {
+	let mut order = vec![usize::MAX; edges.len()];
+	let mut low = vec![0; edges.len()];
+	let mut on_stack = vec![false; edges.len()];
+	let mut stack = Vec::new();
+	let mut components = Vec::new();
+	let mut next = 0;
+
+	for root in 0..edges.len() {
+		if order[root] != usize::MAX {
+			continue;
+		}
+		// Nodes being visited, with the targets left to look at
+		let mut visiting = vec![(root, edges[root].iter())];
+		order[root] = next;
+		low[root] = next;
+		next += 1;
+		stack.push(root);
+		on_stack[root] = true;
+
+		while let Some((node, targets)) = visiting.last_mut() {
+			let node = *node;
+			if let Some(&target) = targets.next() {
+				if order[target] == usize::MAX {
+					order[target] = next;
+					low[target] = next;
+					next += 1;
+					stack.push(target);
+					on_stack[target] = true;
+					visiting.push((target, edges[target].iter()));
+				} else if on_stack[target] {
+					low[node] = low[node].min(order[target]);
+				}
+				continue;
+			}
+
+			visiting.pop();
+			if let Some((parent, _)) = visiting.last() {
+				low[*parent] = low[*parent].min(low[node]);
+			}
+			if low[node] == order[node] {
+				let mut component = Vec::new();
+				while let Some(member) = stack.pop() {
+					on_stack[member] = false;
+					component.push(member);
+					if member == node {
+						break;
+					}
+				}
+				component.sort_unstable();
+				components.push(component);
+			}
+		}
+	}
+	components
+}
+
+/// The shortest cycle through each file of a component, found with a
+/// breadth-first search that stays inside it, without duplicates
+fn shortest_cycles(edges: &[BTreeSet<usize>], component: &[usize]) -> Vec<Vec<usize>> {
+	let members: BTreeSet<usize> = component.iter().copied().collect();
+	let mut found: BTreeSet<(usize, Vec<usize>)> = BTreeSet::new();
+
+	for &start in component {
+		let mut previous: HashMap<usize, usize> = HashMap::new();
+		let mut queue = VecDeque::from([start]);
+		let mut closing = None;
+		'search: while let Some(node) = queue.pop_front() {
+			for &target in &edges[node] {
+				if target == start {
+					closing = Some(node);
+					break 'search;
+				}
+				if members.contains(&target) && !previous.contains_key(&target) {
+					previous.insert(target, node);
+					queue.push_back(target);
+				}
+			}
+		}
+		let Some(mut node) = closing else {
+			continue;
+		};
+
+		let mut cycle = vec![node];
+		while node != start {
+			node = previous[&node];
+			cycle.push(node);
+		}
+		cycle.reverse();
+		// Rotate to start at the smallest id, so one cycle is kept once
+		let smallest = (0..cycle.len()).min_by_key(|&i| cycle[i]).unwrap_or(0);
+		cycle.rotate_left(smallest);
+		cycle.push(cycle[0]);
+		found.insert((cycle.len(), cycle));
+	}
+
+	found
+		.into_iter()
+		.map(|(_, cycle)| cycle)
+		.take(MAX_CYCLES)
+		.collect()
+}
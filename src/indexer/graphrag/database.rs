@@ -14,6 +14,7 @@
 
 // GraphRAG database operations
 
+use crate::indexer::graphrag::calls;
 use crate::indexer::graphrag::types::{CodeGraph, CodeNode, CodeRelationship};
 use crate::indexer::graphrag::utils::cosine_similarity;
 use crate::store::Store;
@@ -120,6 +121,10 @@ impl<'a> DatabaseOperations<'a> {
 			.as_any()
 			.downcast_ref::<arrow::array::StringArray>()
 			.unwrap();
+		// Absent from tables created before functions were stored
+		let functions_array = node_batch
+			.column_by_name("functions")
+			.and_then(|column| column.as_any().downcast_ref::<arrow::array::StringArray>());
 
 		// Get the embedding fixed size list array
 		let embedding_array = node_batch
@@ -167,6 +172,11 @@ impl<'a> DatabaseOperations<'a> {
 
 			let hash = hash_array.value(i).to_string();
 
+			let functions = functions_array
+				.filter(|functions| !functions.is_null(i))
+				.and_then(|functions| serde_json::from_str(functions.value(i)).ok())
+				.unwrap_or_default();
+
 			// Extract the embedding for this node
 			let embedding_offset = i * embedding_array.value_length() as usize;
 			let embedding_len = embedding_array.value_length() as usize;
@@ -186,8 +196,8 @@ impl<'a> DatabaseOperations<'a> {
 				symbols,
 				imports,
 				exports,
-				functions: Vec::new(), // Default empty for nodes loaded from old schema
-				size_lines: 0,         // Default for nodes loaded from old schema
+				functions,
+				size_lines: 0,                   // Default for nodes loaded from old schema
 				language: "unknown".to_string(), // Default for nodes loaded from old schema
 				hash,
 				embedding,
@@ -255,6 +265,8 @@ impl<'a> DatabaseOperations<'a> {
 			}
 		}
 
+		calls::link_calls(&mut graph);
+
 		if !graph.nodes.is_empty() && !quiet {
 			println!(
 				"Loaded GraphRAG knowledge graph with {} nodes and {} relationships",
@@ -607,10 +619,12 @@ impl<'a> DatabaseOperations<'a> {
 			Field::new("weight", DataType::Float32, false),
 		]));
 
-		// Generate unique IDs
+		// Ids derived from the relationship itself, identical across machines
 		let ids: Vec<String> = relationships
 			.iter()
-			.map(|_| uuid::Uuid::new_v4().to_string())
+			.map(|r| {
+				crate::store::batch_converter::row_id(&[&r.source, &r.target, &r.relation_type])
+			})
 			.collect();
 		let sources: Vec<&str> = relationships.iter().map(|r| r.source.as_str()).collect();
 		let targets: Vec<&str> = relationships.iter().map(|r| r.target.as_str()).collect();
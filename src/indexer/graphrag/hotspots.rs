@@ -0,0 +1,172 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Hotspots: files central to the dependency graph, optionally weighted by churn
+
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+use crate::indexer::graphrag::orphans::used_node;
+use crate::indexer::graphrag::types::CodeGraph;
+
+/// A file and how much of the codebase depends on it
+#[derive(Debug, Clone, Serialize)]
+pub struct Hotspot {
+	pub file: String,
+	/// Files using it
+	pub dependents: usize,
+	/// Files it uses
+	pub dependencies: usize,
+	/// Share of the possible usage relationships with the other files, in
+	/// either direction, that it has (0.0-1.0)
+	pub degree: f32,
+	/// Share of the shortest dependency paths between other files that pass
+	/// through it (0.0-1.0)
+	pub betweenness: f32,
+	/// Commits changing it within the churn window, when weighted by churn
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub commits: Option<usize>,
+	/// 0.0-1.0, higher is more critical
+	pub score: f32,
+}
+
+/// Files ranked by degree and betweenness centrality over usage
+/// relationships, most critical first. With `commits` per file, the score
+/// also weighs how often each file changes, so central files nobody touches
+/// rank below central files that change all the time. Files without
+/// relationships are left out.
+pub fn find_hotspots(graph: &CodeGraph, commits: Option<&HashMap<String, usize>>) -> Vec<Hotspot> {
+	// Sorted ids, so results don't depend on hash order
+	let ids: Vec<&str> = graph
+		.nodes
+		.keys()
+		.map(String::as_str)
+		.collect::<BTreeSet<_>>()
+		.into_iter()
+		.collect();
+	let index: HashMap<&str, usize> = ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+
+	// Edges from the using file to the used one
+	let mut edges: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); ids.len()];
+	for relationship in &graph.relationships {
+		let Some(used) = used_node(relationship) else {
+			continue;
+		};
+		let user = if used == relationship.target {
+			&relationship.source
+		} else {
+			&relationship.target
+		};
+		if let (Some(&user), Some(&used)) = (index.get(user.as_str()), index.get(used)) {
+			edges[user].insert(used);
+		}
+	}
+	let mut dependents = vec![0; ids.len()];
+	for targets in &edges {
+		for &target in targets {
+			dependents[target] += 1;
+		}
+	}
+
+	let betweenness = betweenness(&edges);
+	let possible = (2 * ids.len().saturating_sub(1)).max(1) as f32;
+	// Ordered pairs of other files a path can connect
+	let pairs = (ids.len().saturating_sub(1) * ids.len().saturating_sub(2)).max(1) as f64;
+
+	let mut hotspots: Vec<Hotspot> = (0..ids.len())
+		.filter(|&i| dependents[i] + edges[i].len() > 0)
+		.map(|i| Hotspot {
+			file: ids[i].to_string(),
+			dependents: dependents[i],
+			dependencies: edges[i].len(),
+			degree: (dependents[i] + edges[i].len()) as f32 / possible,
+			betweenness: (betweenness[i] / pairs) as f32,
+			commits: commits.map(|commits| commits.get(ids[i]).copied().unwrap_or(0)),
+			score: 0.0,
+		})
+		.collect();
+
+	// Each measure relative to the file scoring highest on it
+	let max = |value: fn(&Hotspot) -> f32| {
+		hotspots
+			.iter()
+			.map(value)
+			.fold(0.0f32, f32::max)
+			.max(f32::EPSILON)
+	};
+	let max_degree = max(|hotspot| hotspot.degree);
+	let max_betweenness = max(|hotspot| hotspot.betweenness);
+	// Logarithmic, so one file changed in every commit doesn't flatten the rest
+	let max_churn = max(|hotspot| churn(hotspot.commits));
+	for hotspot in &mut hotspots {
+		let structure = (hotspot.degree / max_degree + hotspot.betweenness / max_betweenness) / 2.0;
+		hotspot.score = match hotspot.commits {
+			Some(_) => (structure * churn(hotspot.commits) / max_churn).sqrt(),
+			None => structure,
+		};
+	}
+
+	hotspots.sort_by(|a, b| {
+		b.score
+			.partial_cmp(&a.score)
+			.unwrap_or(std::cmp::Ordering::Equal)
+			.then_with(|| a.file.cmp(&b.file))
+	});
+	hotspots
+}
+
+fn churn(commits: Option<usize>) -> f32 {
+	commits.map_or(0.0, |commits| (commits as f32).ln_1p())
+}
+
+/// Brandes' algorithm: for each file, the number of shortest paths between
+/// other files passing through it, each pair's paths counting as one
+fn betweenness(edges: &[BTreeSet<usize>]) -> Vec<f64> {
+	let mut centrality = vec![0.0; edges.len()];
+	for source in 0..edges.len() {
+		let mut order = Vec::new();
+		let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); edges.len()];
+		let mut paths = vec![0.0f64; edges.len()];
+		let mut distance = vec![usize::MAX; edges.len()];
+		paths[source] = 1.0;
+		distance[source] = 0;
+
+		let mut queue = VecDeque::from([source]);
+		while let Some(node) = queue.pop_front() {
+			order.push(node);
+			for &target in &edges[node] {
+				if distance[target] == usize::MAX {
+					distance[target] = distance[node] + 1;
+					queue.push_back(target);
+				}
+				if distance[target] == distance[node] + 1 {
+					paths[target] += paths[node];
+					predecessors[target].push(node);
+				}
+			}
+		}
+
+		let mut dependency = vec![0.0f64; edges.len()];
+		while let Some(node) = order.pop() {
+			for &predecessor in &predecessors[node] {
+				dependency[predecessor] +=
+					paths[predecessor] / paths[node] * (1.0 + dependency[node]);
+			}
+			if node != source {
+				centrality[node] += dependency[node];
+			}
+		}
+	}
+	centrality
+}
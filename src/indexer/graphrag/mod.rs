@@ -16,8 +16,14 @@
 
 pub mod ai;
 pub mod builder;
+pub mod calls;
+pub mod communities;
+pub mod cycles;
 pub mod database;
+pub mod hotspots;
+pub mod orphans;
 pub mod relationships;
+pub mod test_links;
 pub mod types;
 pub mod utils;
 
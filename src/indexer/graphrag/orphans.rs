@@ -0,0 +1,240 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Dead code detection: files nothing depends on and functions nothing calls
+
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::indexer::api_diff::is_public_declaration;
+use crate::indexer::graphrag::types::{CodeGraph, CodeRelationship};
+use crate::indexer::test_classifier;
+
+/// Languages whose files reference each other through imports the graph sees
+const CODE_LANGUAGES: &[&str] = &[
+	"rust",
+	"go",
+	"python",
+	"javascript",
+	"typescript",
+	"php",
+	"cpp",
+	"ruby",
+	"svelte",
+];
+
+/// Relationships derived from file layout; they say nothing about usage
+pub(crate) const STRUCTURAL_RELATIONS: &[&str] = &[
+	"sibling_module",
+	"contains",
+	"mod_declaration",
+	"crate_root",
+	"index_module",
+	"package_init",
+	"same_package",
+	"same_namespace",
+];
+
+/// File stems that programs, packages and build tools start from
+const ENTRYPOINT_STEMS: &[&str] = &[
+	"main", "lib", "mod", "index", "__init__", "__main__", "build", "setup", "manage", "app", "cli",
+];
+
+/// Directories whose files are run directly rather than imported
+const ENTRYPOINT_DIRS: &[&str] = &["bin", "cmd", "scripts", "examples", "benches", "migrations"];
+
+/// Functions invoked by a runtime, framework or trait rather than by name
+const IMPLICIT_FUNCTIONS: &[&str] = &[
+	"main",
+	"init",
+	"setup",
+	"teardown",
+	"new",
+	"default",
+	"fmt",
+	"from",
+	"try_from",
+	"from_str",
+	"into",
+	"drop",
+	"deref",
+	"deref_mut",
+	"as_ref",
+	"as_mut",
+	"borrow",
+	"clone",
+	"eq",
+	"cmp",
+	"partial_cmp",
+	"hash",
+	"next",
+	"index",
+	"serialize",
+	"deserialize",
+	"constructor",
+	"render",
+	"toString",
+	"__init__",
+	"__str__",
+	"__repr__",
+	"__call__",
+];
+
+/// Code that looks unused, ranked for removal
+#[derive(Debug, Clone, Serialize)]
+pub struct OrphanCandidate {
+	/// "file" or "function"
+	pub kind: String,
+	pub path: String,
+	pub name: String,
+	/// 1-indexed definition line of a function
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub line: Option<usize>,
+	/// Lines removing it would delete
+	pub size_lines: usize,
+	/// 0.0-1.0, higher is a safer removal
+	pub score: f32,
+	pub reasons: Vec<String>,
+}
+
+/// Node a relationship shows to be used: the imported or called side, or
+/// the exporting side of `exports_to`
+pub fn used_node(relationship: &CodeRelationship) -> Option<&str> {
+	if relationship.source == relationship.target
+		|| STRUCTURAL_RELATIONS.contains(&relationship.relation_type.as_str())
+	{
+		return None;
+	}
+	match relationship.relation_type.as_str() {
+		"exports_to" => Some(&relationship.source),
+		_ => Some(&relationship.target),
+	}
+}
+
+/// Whether a file is started directly (binaries, scripts, package roots)
+pub fn is_entrypoint_path(path: &str) -> bool {
+	let path = Path::new(path);
+	let stem = path
+		.file_stem()
+		.map(|stem| stem.to_string_lossy())
+		.unwrap_or_default();
+	ENTRYPOINT_STEMS.contains(&stem.as_ref())
+		|| path.components().any(|component| {
+			ENTRYPOINT_DIRS.contains(&component.as_os_str().to_string_lossy().as_ref())
+		})
+}
+
+/// Files no usage relationship points to. Entrypoints, tests, docs and
+/// config are never candidates; files exporting symbols rank lower as they
+/// may be used from outside the repository.
+pub fn find_orphan_files(graph: &CodeGraph) -> Vec<OrphanCandidate> {
+	let used: HashSet<&str> = graph.relationships.iter().filter_map(used_node).collect();
+
+	let mut candidates: Vec<OrphanCandidate> = graph
+		.nodes
+		.values()
+		.filter(|node| !used.contains(node.id.as_str()))
+		.filter(|node| CODE_LANGUAGES.contains(&node.language.as_str()))
+		.filter(|node| {
+			!matches!(
+				node.kind.as_str(),
+				"test_file" | "documentation" | "config_file" | "example_file"
+			)
+		})
+		.filter(|node| {
+			!test_classifier::is_test_path(&node.path) && !is_entrypoint_path(&node.path)
+		})
+		.map(|node| {
+			let mut score = 1.0;
+			let mut reasons = vec!["no file imports or uses it".to_string()];
+			if !node.exports.is_empty() {
+				score -= 0.3;
+				reasons.push(format!(
+					"exports {} symbol(s) that may be used outside the repository",
+					node.exports.len()
+				));
+			}
+			OrphanCandidate {
+				kind: "file".to_string(),
+				path: node.path.clone(),
+				name: node.name.clone(),
+				line: None,
+				size_lines: node.size_lines as usize,
+				score,
+				reasons,
+			}
+		})
+		.collect();
+
+	rank(&mut candidates);
+	candidates
+}
+
+/// Functions of the graph nothing calls; requires
+/// [`crate::indexer::graphrag::calls::link_calls`] to have run. Public
+/// functions are part of the library API and only listed, with a lower
+/// score, when `include_public` is set.
+pub fn find_orphan_functions(graph: &CodeGraph, include_public: bool) -> Vec<OrphanCandidate> {
+	let mut candidates = Vec::new();
+
+	for node in graph.nodes.values() {
+		if test_classifier::is_test_path(&node.path) {
+			continue;
+		}
+		for function in &node.functions {
+			if !function.called_by.is_empty()
+				|| IMPLICIT_FUNCTIONS.contains(&function.name.as_str())
+				|| function.name.starts_with("test")
+			{
+				continue;
+			}
+
+			let mut score = 0.9;
+			let mut reasons = vec!["never called in the index".to_string()];
+			let definition_line = function.signature.lines().next().unwrap_or_default();
+			if is_public_declaration(&node.language, &function.name, definition_line) {
+				if !include_public {
+					continue;
+				}
+				score -= 0.4;
+				reasons.push("public: may be used outside the repository".to_string());
+			}
+
+			candidates.push(OrphanCandidate {
+				kind: "function".to_string(),
+				path: node.path.clone(),
+				name: function.name.clone(),
+				line: Some(function.start_line as usize + 1),
+				size_lines: function.end_line.saturating_sub(function.start_line) as usize + 1,
+				score,
+				reasons,
+			});
+		}
+	}
+
+	rank(&mut candidates);
+	candidates
+}
+
+/// Safest removals first; among equals, the ones freeing the most code
+pub fn rank(candidates: &mut [OrphanCandidate]) {
+	candidates.sort_by(|a, b| {
+		b.score
+			.total_cmp(&a.score)
+			.then(b.size_lines.cmp(&a.size_lines))
+			.then(a.path.cmp(&b.path))
+			.then(a.line.cmp(&b.line))
+	});
+}
@@ -14,14 +14,39 @@
 
 // GraphRAG relationship discovery logic
 
+use crate::indexer::graphrag::calls::FunctionIndex;
 use crate::indexer::graphrag::types::{CodeNode, CodeRelationship, FunctionInfo};
 use crate::indexer::graphrag::utils::{is_parent_child_relationship, symbols_match};
+use crate::indexer::signature_extractor::FunctionCalls;
 use crate::store::CodeBlock;
 use anyhow::Result;
 use std::path::Path;
 
 pub struct RelationshipDiscovery;
 
+// Names protoc plugins generate for the server side of a service: Go,
+// Python, Java, C#, TypeScript and Rust (tonic)
+fn grpc_server_names(service: &str) -> Vec<String> {
+	vec![
+		format!("{}Server", service),
+		format!("Unimplemented{}Server", service),
+		format!("Register{}Server", service),
+		format!("{}Servicer", service),
+		format!("add_{}Servicer_to_server", service),
+		format!("{}ImplBase", service),
+		format!("{}Base", service),
+	]
+}
+
+// Names generated for the client side of a service
+fn grpc_client_names(service: &str) -> Vec<String> {
+	vec![
+		format!("{}Client", service),
+		format!("New{}Client", service),
+		format!("{}Stub", service),
+	]
+}
+
 impl RelationshipDiscovery {
 	// Discover relationships efficiently without AI for most cases
 	pub async fn discover_relationships_efficiently(
@@ -29,6 +54,7 @@ impl RelationshipDiscovery {
 		all_nodes: &[CodeNode],
 	) -> Result<Vec<CodeRelationship>> {
 		let mut relationships = Vec::new();
+		let functions = FunctionIndex::new(all_nodes.iter().chain(new_files));
 
 		for source_file in new_files {
 			// 1. Import/Export relationships (high confidence)
@@ -114,12 +140,22 @@ impl RelationshipDiscovery {
 				}
 			}
 
-			// 4. Language-specific pattern relationships
+			// 4. Function call relationships (high confidence)
+			Self::discover_call_relationships(source_file, &functions, &mut relationships);
+
+			// 5. Language-specific pattern relationships
 			Self::discover_language_specific_relationships(
 				source_file,
 				all_nodes,
 				&mut relationships,
 			);
+
+			// 6. Test files and the files they exercise
+			crate::indexer::graphrag::test_links::discover_test_relationships(
+				source_file,
+				all_nodes,
+				&mut relationships,
+			);
 		}
 
 		// Deduplicate relationships
@@ -137,6 +173,39 @@ impl RelationshipDiscovery {
 		Ok(relationships)
 	}
 
+	// One "calls" relationship per file whose functions source_file calls
+	fn discover_call_relationships(
+		source_file: &CodeNode,
+		functions: &FunctionIndex,
+		relationships: &mut Vec<CodeRelationship>,
+	) {
+		let mut called: std::collections::BTreeMap<&str, Vec<&str>> =
+			std::collections::BTreeMap::new();
+		for function in &source_file.functions {
+			for name in &function.calls {
+				for (target, _) in functions.resolve(&source_file.id, name) {
+					if target != source_file.id {
+						let names = called.entry(target).or_default();
+						if !names.contains(&name.as_str()) {
+							names.push(name);
+						}
+					}
+				}
+			}
+		}
+
+		for (target, names) in called {
+			relationships.push(CodeRelationship {
+				source: source_file.id.clone(),
+				target: target.to_string(),
+				relation_type: "calls".to_string(),
+				description: format!("Calls {}", names.join(", ")),
+				confidence: 0.85,
+				weight: names.len() as f32,
+			});
+		}
+	}
+
 	// Discover language-specific relationships with import resolution
 	fn discover_language_specific_relationships(
 		source_file: &CodeNode,
@@ -146,6 +215,9 @@ impl RelationshipDiscovery {
 		// First, resolve imports to create semantic relationships
 		Self::discover_import_relationships(source_file, all_nodes, relationships);
 
+		// gRPC services and the code generated from them span languages
+		Self::discover_grpc_relationships(source_file, all_nodes, relationships);
+
 		// Then add language-specific patterns as fallback
 		match source_file.language.as_str() {
 			"rust" => {
@@ -228,6 +300,65 @@ impl RelationshipDiscovery {
 		}
 	}
 
+	// Link gRPC services to the files implementing or calling them through
+	// the code generated from the .proto file, whichever side is new
+	pub fn discover_grpc_relationships(
+		source_file: &CodeNode,
+		all_nodes: &[CodeNode],
+		relationships: &mut Vec<CodeRelationship>,
+	) {
+		let source_is_proto = source_file.language == "proto";
+		for other_file in all_nodes {
+			if other_file.id == source_file.id
+				|| (other_file.language == "proto") == source_is_proto
+			{
+				continue;
+			}
+			let (proto, code) = if source_is_proto {
+				(source_file, other_file)
+			} else {
+				(other_file, source_file)
+			};
+
+			let names = Self::referenced_names(code);
+			for service in crate::indexer::languages::Proto::services(&proto.symbols) {
+				let (relation_type, description, confidence) =
+					if grpc_server_names(service).iter().any(|n| names.contains(n)) {
+						("implements_service", "Implements gRPC service", 0.85)
+					} else if grpc_client_names(service).iter().any(|n| names.contains(n)) {
+						("calls_service", "Calls gRPC service", 0.8)
+					} else {
+						continue;
+					};
+				relationships.push(CodeRelationship {
+					source: code.id.clone(),
+					target: proto.id.clone(),
+					relation_type: relation_type.to_string(),
+					description: format!("{} {}", description, service),
+					confidence,
+					weight: 0.9,
+				});
+			}
+		}
+	}
+
+	// Last segments of the names a file defines, exports, imports and calls:
+	// `pb.UnimplementedUserServiceServer` is matched as its type name
+	fn referenced_names(node: &CodeNode) -> std::collections::HashSet<String> {
+		node.symbols
+			.iter()
+			.chain(&node.exports)
+			.chain(&node.imports)
+			.chain(node.functions.iter().flat_map(|function| &function.calls))
+			.map(|name| {
+				name.rsplit(['.', ':', '\\', '/'])
+					.next()
+					.unwrap_or(name)
+					.to_string()
+			})
+			.collect()
+	}
+
 	// Rust-specific relationship patterns
 	fn discover_rust_relationships(
 		source_file: &CodeNode,
@@ -433,6 +564,21 @@ impl RelationshipDiscovery {
 		Ok(functions)
 	}
 
+	// FunctionInfo for a function found by tree-sitter call extraction;
+	// called_by is filled in when the graph is loaded
+	pub fn function_info(function: FunctionCalls) -> FunctionInfo {
+		FunctionInfo {
+			name: function.name,
+			signature: function.signature,
+			start_line: function.start_line as u32,
+			end_line: function.end_line as u32,
+			calls: function.calls,
+			called_by: Vec::new(),
+			parameters: Vec::new(),
+			return_type: None,
+		}
+	}
+
 	// Parse function symbol to create FunctionInfo
 	fn parse_function_symbol(symbol: &str, block: &CodeBlock) -> Option<FunctionInfo> {
 		// Simple pattern matching for common function symbol formats
@@ -472,15 +618,11 @@ impl RelationshipDiscovery {
 		(Vec::new(), exports)
 	}
 	// Determine file kind based on path patterns
-	// Determine file kind based on path patterns
 	pub fn determine_file_kind(relative_path: &str) -> String {
-		if relative_path.contains("/src/") || relative_path.contains("/lib/") {
-			"source_file".to_string()
-		} else if relative_path.contains("/test")
-			|| relative_path.contains("_test.")
-			|| relative_path.contains(".test.")
-		{
+		if crate::indexer::test_classifier::is_test_path(relative_path) {
 			"test_file".to_string()
+		} else if relative_path.contains("/src/") || relative_path.contains("/lib/") {
+			"source_file".to_string()
 		} else if relative_path.ends_with(".md")
 			|| relative_path.ends_with(".txt")
 			|| relative_path.ends_with(".rst")
@@ -0,0 +1,271 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Test-to-source linkage: "tests" relationships from test files to the files
+// they exercise
+//
+// A test file is linked to a source file by three kinds of evidence: the
+// naming convention pairing them (`foo_test.go` and `foo.go`, `test_foo.py`
+// and `foo.py`, `Foo.test.ts` and `Foo.ts`, `tests/foo.rs` and `src/foo.rs`),
+// an import of the source file, and calls to functions it defines.
+
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use crate::indexer::graphrag::types::{CodeGraph, CodeNode, CodeRelationship};
+use crate::indexer::graphrag::utils::symbols_match;
+use crate::indexer::test_classifier::is_test_path;
+
+/// Relationship from a test file to a file it exercises
+pub const TESTS_RELATION: &str = "tests";
+
+/// Called function names listed in a relationship's description
+const MAX_LISTED_CALLS: usize = 5;
+
+/// Names defined by more files than this (`new`, `run`, ...) don't tell
+/// which one a test calls
+const MAX_DEFINING_FILES: usize = 3;
+
+/// Name endings of test files; the rest is the name of the file under test
+const TEST_SUFFIXES: &[&str] = &[
+	"_unittest",
+	"_tests",
+	"_test",
+	"_spec",
+	".test",
+	".spec",
+	"Tests",
+	"Test",
+	"IT",
+];
+
+/// A test file exercising a source file
+#[derive(Debug, Clone, Serialize)]
+pub struct TestLink {
+	pub test: String,
+	pub confidence: f32,
+	/// Why the test is linked, e.g. "naming convention; calls parse"
+	pub reasons: String,
+}
+
+/// Link `source_file` to the files it tests, or the test files to it,
+/// whichever side is new
+pub fn discover_test_relationships(
+	source_file: &CodeNode,
+	all_nodes: &[CodeNode],
+	relationships: &mut Vec<CodeRelationship>,
+) {
+	let source_is_test = is_test_path(&source_file.path);
+	for other_file in all_nodes {
+		if other_file.id == source_file.id || is_test_path(&other_file.path) == source_is_test {
+			continue;
+		}
+		let (test, code) = if source_is_test {
+			(source_file, other_file)
+		} else {
+			(other_file, source_file)
+		};
+		if let Some(relationship) = link(test, code, all_nodes) {
+			relationships.push(relationship);
+		}
+	}
+}
+
+/// Test files linked to `path` by "tests" relationships, most certain first
+pub fn tests_for(graph: &CodeGraph, path: &str) -> Vec<TestLink> {
+	let mut links: Vec<TestLink> = graph
+		.relationships
+		.iter()
+		.filter(|rel| rel.relation_type == TESTS_RELATION && rel.target == path)
+		.map(|rel| TestLink {
+			test: rel.source.clone(),
+			confidence: rel.confidence,
+			reasons: rel.description.clone(),
+		})
+		.collect();
+	links.sort_by(|a, b| {
+		b.confidence
+			.partial_cmp(&a.confidence)
+			.unwrap_or(std::cmp::Ordering::Equal)
+			.then_with(|| a.test.cmp(&b.test))
+	});
+	links.dedup_by(|a, b| a.test == b.test);
+	links
+}
+
+/// The "tests" relationship from `test` to `code`, if any evidence links them
+fn link(test: &CodeNode, code: &CodeNode, all_nodes: &[CodeNode]) -> Option<CodeRelationship> {
+	let mut reasons = Vec::new();
+	let mut confidence: f32 = 0.0;
+
+	if test.language == code.language && is_named_after(test, code, all_nodes) {
+		reasons.push("naming convention".to_string());
+		confidence = confidence.max(0.9);
+	}
+
+	if imports(test, code) {
+		reasons.push(format!("imports {}", code.name));
+		confidence = confidence.max(0.85);
+	}
+
+	let calls = called_functions(test, code, all_nodes);
+	if !calls.is_empty() {
+		let mut listed: Vec<&str> = calls.iter().take(MAX_LISTED_CALLS).copied().collect();
+		if calls.len() > MAX_LISTED_CALLS {
+			listed.push("...");
+		}
+		reasons.push(format!("calls {}", listed.join(", ")));
+		confidence = confidence.max(0.7);
+	}
+
+	if reasons.is_empty() {
+		return None;
+	}
+	// Each further kind of evidence makes the link more certain
+	confidence = (confidence + 0.05 * (reasons.len() - 1) as f32).min(0.99);
+
+	Some(CodeRelationship {
+		source: test.id.clone(),
+		target: code.id.clone(),
+		relation_type: TESTS_RELATION.to_string(),
+		description: format!("Tests {}: {}", code.name, reasons.join("; ")),
+		confidence,
+		weight: reasons.len() as f32,
+	})
+}
+
+/// Name of the file a test file is named after: `foo` for `foo_test.go`,
+/// `test_foo.py`, `foo.spec.ts`, `FooTest.java` and `tests/foo.rs`. Rust's
+/// `store/tests.rs` tests its parent module, `store`.
+fn tested_name(test_path: &str) -> Option<String> {
+	let path = Path::new(test_path);
+	let stem = path.file_stem()?.to_str()?;
+
+	if stem == "tests" || stem == "test" {
+		let parent = path.parent()?.file_name()?.to_str()?;
+		return Some(parent.to_string());
+	}
+	if let Some(name) = stem.strip_prefix("test_") {
+		return Some(name.to_string());
+	}
+	let name = TEST_SUFFIXES
+		.iter()
+		.find_map(|suffix| stem.strip_suffix(suffix))
+		.filter(|name| !name.is_empty())
+		.unwrap_or(stem);
+	Some(name.to_string())
+}
+
+/// Name a source file is tested under: its stem, or its directory for module
+/// entrypoints such as `mod.rs`, `index.ts` and `__init__.py`
+fn module_name(code_path: &str) -> Option<String> {
+	let path = Path::new(code_path);
+	let stem = path.file_stem()?.to_str()?;
+	if matches!(stem, "mod" | "index" | "__init__") {
+		return path
+			.parent()?
+			.file_name()?
+			.to_str()
+			.map(|name| name.to_string());
+	}
+	Some(stem.to_string())
+}
+
+/// Whether `test` follows the naming convention for `code`. When several
+/// files share the name, only the ones closest to the test count.
+fn is_named_after(test: &CodeNode, code: &CodeNode, all_nodes: &[CodeNode]) -> bool {
+	let Some(name) = tested_name(&test.path) else {
+		return false;
+	};
+	let matches = |node: &CodeNode| {
+		node.language == test.language
+			&& !is_test_path(&node.path)
+			&& module_name(&node.path).is_some_and(|module| module.eq_ignore_ascii_case(&name))
+	};
+	if !matches(code) {
+		return false;
+	}
+
+	let closest = all_nodes
+		.iter()
+		.filter(|node| matches(node))
+		.map(|node| shared_directories(&test.path, &node.path))
+		.max()
+		.unwrap_or(0);
+	shared_directories(&test.path, &code.path) >= closest
+}
+
+/// Number of leading directories two paths have in common
+fn shared_directories(a: &str, b: &str) -> usize {
+	let a = Path::new(a).parent().into_iter().flat_map(Path::components);
+	let b: Vec<_> = Path::new(b)
+		.parent()
+		.into_iter()
+		.flat_map(Path::components)
+		.collect();
+	a.zip(b).take_while(|(a, b)| a == b).count()
+}
+
+/// Whether an import of `test` resolves to `code` or names what it exports
+fn imports(test: &CodeNode, code: &CodeNode) -> bool {
+	if test.imports.is_empty() {
+		return false;
+	}
+	if let Some(lang_impl) = crate::indexer::languages::get_language(&test.language) {
+		let files = [code.path.clone()];
+		if test.imports.iter().any(|import| {
+			lang_impl
+				.resolve_import(import, &test.path, &files)
+				.as_ref() == Some(&code.path)
+		}) {
+			return true;
+		}
+	}
+	test.imports.iter().any(|import| {
+		let imported = import.rsplit(['.', ':', '/']).next().unwrap_or(import);
+		code.exports
+			.iter()
+			.any(|export| symbols_match(imported, export))
+	})
+}
+
+/// Functions of `code` the test calls, except names the test defines itself
+/// and names too common to point at `code`
+fn called_functions<'a>(
+	test: &CodeNode,
+	code: &'a CodeNode,
+	all_nodes: &[CodeNode],
+) -> Vec<&'a str> {
+	let own: BTreeSet<&str> = test.functions.iter().map(|f| f.name.as_str()).collect();
+	let calls: BTreeSet<&str> = test
+		.functions
+		.iter()
+		.flat_map(|function| &function.calls)
+		.map(String::as_str)
+		.filter(|name| !own.contains(name))
+		.collect();
+	let defined: BTreeSet<&str> = code.functions.iter().map(|f| f.name.as_str()).collect();
+	defined
+		.into_iter()
+		.filter(|name| calls.contains(name))
+		.filter(|name| {
+			all_nodes
+				.iter()
+				.filter(|node| !is_test_path(&node.path))
+				.filter(|node| node.functions.iter().any(|f| f.name == *name))
+				.count() <= MAX_DEFINING_FILES
+		})
+		.collect()
+}
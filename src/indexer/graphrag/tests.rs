@@ -612,6 +612,164 @@ def _private_function():
 		);
 	}
 
+	/// Test Zig @import path resolution
+	#[tokio::test]
+	async fn test_zig_import_resolution() {
+		let zig_lang = get_language("zig").expect("Zig language should be available");
+
+		let all_files = vec![
+			"src/main.zig".to_string(),
+			"src/math.zig".to_string(),
+			"src/geometry/shapes.zig".to_string(),
+			"build.zig".to_string(),
+		];
+
+		let resolved = zig_lang.resolve_import("math.zig", "src/main.zig", &all_files);
+		assert_eq!(
+			resolved,
+			Some("src/math.zig".to_string()),
+			"Should resolve sibling file import"
+		);
+
+		let resolved =
+			zig_lang.resolve_import("../math.zig", "src/geometry/shapes.zig", &all_files);
+		assert_eq!(
+			resolved,
+			Some("src/math.zig".to_string()),
+			"Should resolve parent directory import"
+		);
+
+		let resolved = zig_lang.resolve_import("geometry/shapes.zig", "src/main.zig", &all_files);
+		assert_eq!(
+			resolved,
+			Some("src/geometry/shapes.zig".to_string()),
+			"Should resolve subdirectory import"
+		);
+	}
+
+	/// Test Protobuf import resolution and linking of gRPC services
+	#[tokio::test]
+	async fn test_proto_import_resolution() {
+		let proto_lang = get_language("proto").expect("Protobuf language should be available");
+
+		let all_files = vec![
+			"proto/users/v1/users.proto".to_string(),
+			"proto/common/pagination.proto".to_string(),
+			"server/users.go".to_string(),
+		];
+
+		let resolved = proto_lang.resolve_import(
+			"proto/common/pagination.proto",
+			"proto/users/v1/users.proto",
+			&all_files,
+		);
+		assert_eq!(
+			resolved,
+			Some("proto/common/pagination.proto".to_string()),
+			"Should resolve import from the project root"
+		);
+
+		let resolved = proto_lang.resolve_import(
+			"../../common/pagination.proto",
+			"proto/users/v1/users.proto",
+			&all_files,
+		);
+		assert_eq!(
+			resolved,
+			Some("proto/common/pagination.proto".to_string()),
+			"Should resolve import relative to the importing file"
+		);
+
+		let node = |path: &str, language: &str, symbols: &[&str]| CodeNode {
+			id: path.to_string(),
+			name: path.to_string(),
+			kind: "file".to_string(),
+			path: path.to_string(),
+			description: String::new(),
+			symbols: symbols.iter().map(|s| s.to_string()).collect(),
+			imports: vec![],
+			exports: vec![],
+			functions: vec![],
+			hash: String::new(),
+			embedding: vec![],
+			size_lines: 10,
+			language: language.to_string(),
+		};
+		let proto = node(
+			"proto/users/v1/users.proto",
+			"proto",
+			&[
+				"UserService",
+				"UserService/GetUser",
+				"GetUserRequest",
+				"User",
+			],
+		);
+		let server = node(
+			"server/users.go",
+			"go",
+			&["server", "pb.UnimplementedUserServiceServer", "GetUser"],
+		);
+		let client = node("client/users.py", "python", &["stub", "UserServiceStub"]);
+		let other = node("server/health.go", "go", &["HealthServer"]);
+		let all_nodes = vec![proto.clone(), server.clone(), client.clone(), other];
+
+		// From a new .proto file to the code generated from it
+		let mut relationships = Vec::new();
+		RelationshipDiscovery::discover_grpc_relationships(&proto, &all_nodes, &mut relationships);
+		let relations: Vec<(&str, &str)> = relationships
+			.iter()
+			.map(|r| (r.source.as_str(), r.relation_type.as_str()))
+			.collect();
+		assert_eq!(
+			relations,
+			vec![
+				("server/users.go", "implements_service"),
+				("client/users.py", "calls_service"),
+			]
+		);
+
+		// From new code to the .proto file
+		let mut relationships = Vec::new();
+		RelationshipDiscovery::discover_grpc_relationships(&server, &all_nodes, &mut relationships);
+		assert_eq!(relationships.len(), 1);
+		assert_eq!(relationships[0].target, "proto/users/v1/users.proto");
+		assert_eq!(
+			relationships[0].description,
+			"Implements gRPC service UserService"
+		);
+	}
+
+	/// Test Terraform module source resolution
+	#[tokio::test]
+	async fn test_hcl_import_resolution() {
+		let hcl_lang = get_language("hcl").expect("HCL language should be available");
+
+		let all_files = vec![
+			"main.tf".to_string(),
+			"modules/vpc/variables.tf".to_string(),
+			"modules/vpc/main.tf".to_string(),
+			"modules/vpc/nested/main.tf".to_string(),
+			"envs/prod/main.tf".to_string(),
+		];
+
+		// Module sources are directories; their main.tf represents them
+		let resolved = hcl_lang.resolve_import("./modules/vpc", "main.tf", &all_files);
+		assert_eq!(
+			resolved,
+			Some("modules/vpc/main.tf".to_string()),
+			"Should resolve ./modules/vpc module source"
+		);
+
+		let resolved =
+			hcl_lang.resolve_import("../../modules/vpc", "envs/prod/main.tf", &all_files);
+		assert_eq!(
+			resolved,
+			Some("modules/vpc/main.tf".to_string()),
+			"Should resolve ../../modules/vpc module source"
+		);
+	}
+
 	/// Test Svelte import resolution
 	#[tokio::test]
 	async fn test_svelte_import_resolution() {
@@ -769,6 +927,703 @@ def _private_function():
 		}
 	}
 
+	/// Test dead file detection over usage relationships
+	#[test]
+	fn test_orphan_files() {
+		use crate::indexer::graphrag::orphans::find_orphan_files;
+		use crate::indexer::graphrag::types::{CodeGraph, CodeRelationship};
+
+		let node = |path: &str, exports: &[&str]| CodeNode {
+			id: path.to_string(),
+			name: path.rsplit('/').next().unwrap().to_string(),
+			kind: RelationshipDiscovery::determine_file_kind(path),
+			path: path.to_string(),
+			description: String::new(),
+			symbols: vec![],
+			imports: vec![],
+			exports: exports.iter().map(|e| e.to_string()).collect(),
+			functions: vec![],
+			hash: path.to_string(),
+			embedding: vec![],
+			size_lines: 10,
+			language: "rust".to_string(),
+		};
+		let relationship = |source: &str, target: &str, relation_type: &str| CodeRelationship {
+			source: source.to_string(),
+			target: target.to_string(),
+			relation_type: relation_type.to_string(),
+			description: String::new(),
+			confidence: 0.9,
+			weight: 1.0,
+		};
+
+		let mut graph = CodeGraph::default();
+		for (path, exports) in [
+			("src/main.rs", vec![]),
+			("src/config.rs", vec!["Config"]),
+			("src/legacy.rs", vec![]),
+			("src/compat.rs", vec!["shim"]),
+			("tests/integration.rs", vec![]),
+		] {
+			graph.nodes.insert(path.to_string(), node(path, &exports));
+		}
+		graph.relationships = vec![
+			relationship("src/main.rs", "src/config.rs", "imports_direct"),
+			// Layout relationships don't make a file used
+			relationship("src/main.rs", "src/legacy.rs", "sibling_module"),
+		];
+
+		let orphans = find_orphan_files(&graph);
+		let paths: Vec<&str> = orphans.iter().map(|o| o.path.as_str()).collect();
+		// Files exporting symbols rank after the ones that don't
+		assert_eq!(paths, vec!["src/legacy.rs", "src/compat.rs"]);
+		assert!(orphans[0].score > orphans[1].score);
+	}
+
+	/// Test dead function detection over the calls of the graph
+	#[test]
+	fn test_orphan_functions() {
+		use crate::indexer::graphrag::calls::link_calls;
+		use crate::indexer::graphrag::orphans::find_orphan_functions;
+		use crate::indexer::graphrag::types::{CodeGraph, FunctionInfo};
+
+		let function = |signature: &str, name: &str, calls: &[&str]| FunctionInfo {
+			name: name.to_string(),
+			signature: signature.to_string(),
+			start_line: 0,
+			end_line: 4,
+			calls: calls.iter().map(|c| c.to_string()).collect(),
+			called_by: vec![],
+			parameters: vec![],
+			return_type: None,
+		};
+		let node = |path: &str, functions: Vec<FunctionInfo>| CodeNode {
+			id: path.to_string(),
+			name: path.rsplit('/').next().unwrap().to_string(),
+			kind: RelationshipDiscovery::determine_file_kind(path),
+			path: path.to_string(),
+			description: String::new(),
+			symbols: vec![],
+			imports: vec![],
+			exports: vec![],
+			functions,
+			hash: path.to_string(),
+			embedding: vec![],
+			size_lines: 10,
+			language: "rust".to_string(),
+		};
+
+		let mut graph = CodeGraph::default();
+		for node in [
+			node(
+				"src/main.rs",
+				vec![function("fn main() {", "main", &["load"])],
+			),
+			node(
+				"src/config.rs",
+				vec![
+					function("fn load() {", "load", &[]),
+					function("fn unused() {", "unused", &[]),
+					function("pub fn exported() {", "exported", &[]),
+				],
+			),
+			node(
+				"tests/config.rs",
+				vec![function("fn helper() {", "helper", &[])],
+			),
+		] {
+			graph.nodes.insert(node.id.clone(), node);
+		}
+		link_calls(&mut graph);
+
+		let names = |include_public: bool| -> Vec<String> {
+			find_orphan_functions(&graph, include_public)
+				.into_iter()
+				.map(|orphan| orphan.name)
+				.collect()
+		};
+		// Called, implicit and test functions are never listed
+		assert_eq!(names(false), vec!["unused"]);
+		assert_eq!(names(true), vec!["unused", "exported"]);
+	}
+
+	/// Test that tightly connected files cluster together
+	#[test]
+	fn test_communities() {
+		use crate::indexer::graphrag::communities::detect_communities;
+		use crate::indexer::graphrag::types::{CodeGraph, CodeRelationship};
+
+		let node = |path: &str| CodeNode {
+			id: path.to_string(),
+			name: path.rsplit('/').next().unwrap().to_string(),
+			kind: RelationshipDiscovery::determine_file_kind(path),
+			path: path.to_string(),
+			description: String::new(),
+			symbols: vec![],
+			imports: vec![],
+			exports: vec![],
+			functions: vec![],
+			hash: path.to_string(),
+			embedding: vec![],
+			size_lines: 10,
+			language: "rust".to_string(),
+		};
+		let relationship = |source: &str, target: &str, confidence: f32| CodeRelationship {
+			source: source.to_string(),
+			target: target.to_string(),
+			relation_type: "imports_direct".to_string(),
+			description: String::new(),
+			confidence,
+			weight: 1.0,
+		};
+
+		let mut graph = CodeGraph::default();
+		for path in [
+			"src/auth/login.rs",
+			"src/auth/session.rs",
+			"src/auth/token.rs",
+			"src/store/db.rs",
+			"src/store/query.rs",
+			"src/store/schema.rs",
+			"README.md",
+		] {
+			graph.nodes.insert(path.to_string(), node(path));
+		}
+		graph.relationships = vec![
+			relationship("src/auth/login.rs", "src/auth/session.rs", 0.9),
+			relationship("src/auth/login.rs", "src/auth/token.rs", 0.9),
+			relationship("src/auth/session.rs", "src/auth/token.rs", 0.9),
+			relationship("src/store/db.rs", "src/store/query.rs", 0.9),
+			relationship("src/store/db.rs", "src/store/schema.rs", 0.9),
+			relationship("src/store/query.rs", "src/store/schema.rs", 0.9),
+			// A single weak link between the clusters doesn't merge them
+			relationship("src/auth/session.rs", "src/store/db.rs", 0.2),
+		];
+
+		let result = detect_communities(&graph);
+		let labels: Vec<&str> = result
+			.communities
+			.iter()
+			.map(|c| c.label.as_str())
+			.collect();
+		assert_eq!(labels, vec!["src/auth", "src/store"]);
+		assert_eq!(result.ungrouped, 1);
+		assert_eq!(result.communities[0].files.len(), 3);
+		assert_eq!(result.communities[0].internal_edges, 3);
+		assert_eq!(result.communities[0].external_edges, 1);
+		assert_eq!(result.communities[0].key_files.len(), 3);
+		assert!(result.communities[0].cohesion > 0.9);
+		assert!(result.modularity > 0.3);
+	}
+
+	/// Test that circular imports are reported with their shortest cycles
+	#[test]
+	fn test_cycles() {
+		use crate::indexer::graphrag::cycles::find_cycles;
+		use crate::indexer::graphrag::types::{CodeGraph, CodeRelationship};
+
+		let node = |path: &str| CodeNode {
+			id: path.to_string(),
+			name: path.rsplit('/').next().unwrap().to_string(),
+			kind: RelationshipDiscovery::determine_file_kind(path),
+			path: path.to_string(),
+			description: String::new(),
+			symbols: vec![],
+			imports: vec![],
+			exports: vec![],
+			functions: vec![],
+			hash: path.to_string(),
+			embedding: vec![],
+			size_lines: 10,
+			language: "rust".to_string(),
+		};
+		let relationship = |source: &str, target: &str, relation_type: &str| CodeRelationship {
+			source: source.to_string(),
+			target: target.to_string(),
+			relation_type: relation_type.to_string(),
+			description: String::new(),
+			confidence: 0.9,
+			weight: 1.0,
+		};
+
+		let mut graph = CodeGraph::default();
+		for path in ["a.rs", "b.rs", "c.rs", "d.rs", "e.rs", "f.rs"] {
+			graph.nodes.insert(path.to_string(), node(path));
+		}
+		graph.relationships = vec![
+			relationship("a.rs", "b.rs", "imports_direct"),
+			relationship("b.rs", "c.rs", "imports_direct"),
+			relationship("c.rs", "a.rs", "imports"),
+			// A shortcut back makes a shorter cycle through b.rs
+			relationship("b.rs", "a.rs", "imports_direct"),
+			relationship("c.rs", "d.rs", "imports_direct"),
+			relationship("e.rs", "f.rs", "contains"),
+			relationship("f.rs", "e.rs", "imports_direct"),
+			// Calls and layout relationships are not dependencies
+			relationship("d.rs", "c.rs", "calls"),
+			relationship("d.rs", "c.rs", "sibling_module"),
+		];
+
+		let groups = find_cycles(&graph);
+		assert_eq!(groups.len(), 2);
+		assert_eq!(groups[0].files, vec!["a.rs", "b.rs", "c.rs"]);
+		assert_eq!(
+			groups[0].cycles,
+			vec![
+				vec!["a.rs", "b.rs", "a.rs"],
+				vec!["a.rs", "b.rs", "c.rs", "a.rs"],
+			]
+		);
+		assert_eq!(groups[1].files, vec!["e.rs", "f.rs"]);
+		assert_eq!(groups[1].cycles, vec![vec!["e.rs", "f.rs", "e.rs"]]);
+	}
+
+	/// Test that files bridging the graph rank first, and that churn reorders them
+	#[test]
+	fn test_hotspots() {
+		use crate::indexer::graphrag::hotspots::find_hotspots;
+		use crate::indexer::graphrag::types::{CodeGraph, CodeRelationship};
+
+		let node = |path: &str| CodeNode {
+			id: path.to_string(),
+			name: path.to_string(),
+			kind: RelationshipDiscovery::determine_file_kind(path),
+			path: path.to_string(),
+			description: String::new(),
+			symbols: vec![],
+			imports: vec![],
+			exports: vec![],
+			functions: vec![],
+			hash: path.to_string(),
+			embedding: vec![],
+			size_lines: 10,
+			language: "rust".to_string(),
+		};
+		let relationship = |source: &str, target: &str, relation_type: &str| CodeRelationship {
+			source: source.to_string(),
+			target: target.to_string(),
+			relation_type: relation_type.to_string(),
+			description: String::new(),
+			confidence: 0.9,
+			weight: 1.0,
+		};
+
+		let mut graph = CodeGraph::default();
+		for path in ["app.rs", "cli.rs", "core.rs", "db.rs", "log.rs", "idle.rs"] {
+			graph.nodes.insert(path.to_string(), node(path));
+		}
+		// Everything reaches db.rs and log.rs through core.rs
+		graph.relationships = vec![
+			relationship("app.rs", "core.rs", "imports"),
+			relationship("cli.rs", "core.rs", "calls"),
+			relationship("core.rs", "db.rs", "imports_direct"),
+			relationship("core.rs", "log.rs", "imports"),
+			// Layout relationships are not usage
+			relationship("idle.rs", "core.rs", "sibling_module"),
+		];
+
+		let hotspots = find_hotspots(&graph, None);
+		assert_eq!(hotspots.len(), 5);
+		assert_eq!(hotspots[0].file, "core.rs");
+		assert_eq!(hotspots[0].dependents, 2);
+		assert_eq!(hotspots[0].dependencies, 2);
+		assert!((hotspots[0].betweenness - 0.2).abs() < 1e-6);
+		assert!(hotspots.iter().skip(1).all(|h| h.betweenness == 0.0));
+
+		// A central file nobody changes ranks below one changed often
+		let commits = std::collections::HashMap::from([
+			("db.rs".to_string(), 40),
+			("core.rs".to_string(), 0),
+		]);
+		let hotspots = find_hotspots(&graph, Some(&commits));
+		assert_eq!(hotspots[0].file, "db.rs");
+		assert_eq!(hotspots[0].commits, Some(40));
+	}
+
+	/// Test that test files link to the files they exercise
+	#[tokio::test]
+	async fn test_tests_relationships() {
+		use crate::indexer::graphrag::test_links::tests_for;
+		use crate::indexer::graphrag::types::{CodeGraph, FunctionInfo};
+
+		let function = |name: &str, calls: &[&str]| FunctionInfo {
+			name: name.to_string(),
+			signature: String::new(),
+			start_line: 0,
+			end_line: 1,
+			calls: calls.iter().map(|c| c.to_string()).collect(),
+			called_by: vec![],
+			parameters: vec![],
+			return_type: None,
+		};
+		let node = |path: &str, language: &str, functions: Vec<FunctionInfo>| CodeNode {
+			id: path.to_string(),
+			name: path.rsplit('/').next().unwrap().to_string(),
+			kind: RelationshipDiscovery::determine_file_kind(path),
+			path: path.to_string(),
+			description: String::new(),
+			symbols: vec![],
+			imports: vec![],
+			exports: vec![],
+			functions,
+			hash: path.to_string(),
+			embedding: vec![],
+			size_lines: 10,
+			language: language.to_string(),
+		};
+
+		let nodes = vec![
+			node("pkg/server/handler.go", "go", vec![function("Handle", &[])]),
+			node(
+				"pkg/server/handler_test.go",
+				"go",
+				vec![function("TestHandle", &["Handle", "ParseConfig"])],
+			),
+			node(
+				"pkg/config/config.go",
+				"go",
+				vec![function("ParseConfig", &[])],
+			),
+			node("pkg/client/handler.go", "go", vec![]),
+			node("app/models.py", "python", vec![]),
+			node("tests/test_models.py", "python", vec![]),
+		];
+		let mut graph = CodeGraph::default();
+		for node in &nodes {
+			graph.nodes.insert(node.id.clone(), node.clone());
+		}
+		graph.relationships =
+			RelationshipDiscovery::discover_relationships_efficiently(&nodes, &nodes)
+				.await
+				.unwrap();
+
+		let handler = tests_for(&graph, "pkg/server/handler.go");
+		assert_eq!(handler.len(), 1);
+		assert_eq!(handler[0].test, "pkg/server/handler_test.go");
+		// Naming convention and a call back each other up
+		assert!(handler[0].confidence > 0.9);
+		assert!(handler[0].reasons.contains("calls Handle"));
+
+		// The other handler.go is further from the test
+		assert!(tests_for(&graph, "pkg/client/handler.go").is_empty());
+
+		let config = tests_for(&graph, "pkg/config/config.go");
+		assert_eq!(config.len(), 1);
+		assert!(config[0].confidence < handler[0].confidence);
+
+		let models = tests_for(&graph, "app/models.py");
+		assert_eq!(models.len(), 1);
+		assert_eq!(models[0].test, "tests/test_models.py");
+	}
+
+	/// Test the graph changes of a differential single-file update
+	#[test]
+	fn test_differential_update() {
+		use crate::indexer::graphrag::builder::{
+			is_unchanged, remove_file, replace_outgoing_relationships,
+		};
+		use crate::indexer::graphrag::types::{CodeGraph, CodeRelationship};
+
+		let node = |path: &str| CodeNode {
+			id: path.to_string(),
+			name: path.rsplit('/').next().unwrap().to_string(),
+			kind: "file".to_string(),
+			path: path.to_string(),
+			description: String::new(),
+			symbols: vec![],
+			imports: vec![],
+			exports: vec![],
+			functions: vec![],
+			hash: format!("{}-hash", path),
+			embedding: vec![],
+			size_lines: 10,
+			language: "rust".to_string(),
+		};
+		let rel = |source: &str, target: &str| CodeRelationship {
+			source: source.to_string(),
+			target: target.to_string(),
+			relation_type: "imports".to_string(),
+			description: String::new(),
+			confidence: 0.9,
+			weight: 1.0,
+		};
+		let edges = |graph: &CodeGraph| {
+			let mut edges: Vec<(String, String)> = graph
+				.relationships
+				.iter()
+				.map(|rel| (rel.source.clone(), rel.target.clone()))
+				.collect();
+			edges.sort();
+			edges
+		};
+		let edge = |source: &str, target: &str| (source.to_string(), target.to_string());
+
+		let mut graph = CodeGraph::default();
+		for path in ["src/a.rs", "src/b.rs", "src/c.rs"] {
+			graph.nodes.insert(path.to_string(), node(path));
+		}
+		graph.relationships = vec![
+			rel("src/a.rs", "src/c.rs"),
+			rel("src/c.rs", "src/a.rs"),
+			rel("src/b.rs", "src/gone.rs"),
+		];
+
+		// Unchanged hash: the update is skipped
+		assert!(is_unchanged(&graph, "src/a.rs", "src/a.rs-hash"));
+		assert!(!is_unchanged(&graph, "src/a.rs", "other"));
+		assert!(!is_unchanged(&graph, "src/new.rs", "src/new.rs-hash"));
+
+		// Modified file: its outgoing edges are replaced, incoming ones stay, an
+		// edge that already exists isn't added twice and dangling edges go
+		let (fresh, missing) = replace_outgoing_relationships(
+			&mut graph,
+			"src/a.rs",
+			vec![rel("src/a.rs", "src/b.rs"), rel("src/c.rs", "src/a.rs")],
+		);
+		assert_eq!(fresh.len(), 1);
+		assert_eq!(fresh[0].target, "src/b.rs");
+		assert_eq!(missing.into_iter().collect::<Vec<_>>(), vec!["src/gone.rs"]);
+		assert_eq!(
+			edges(&graph),
+			vec![edge("src/a.rs", "src/b.rs"), edge("src/c.rs", "src/a.rs")]
+		);
+
+		// Removed file: the node and every edge touching it go
+		let (removed, missing) = remove_file(&mut graph, "src/a.rs");
+		assert!(removed);
+		assert!(missing.is_empty());
+		assert!(!graph.nodes.contains_key("src/a.rs"));
+		assert!(edges(&graph).is_empty());
+
+		let (removed, _) = remove_file(&mut graph, "src/a.rs");
+		assert!(!removed);
+	}
+
+	/// Test Haskell import/export extraction, symbols and module resolution
+	#[tokio::test]
+	async fn test_haskell_extraction_and_resolution() {
+		let haskell_code = r#"module Geometry.Shapes (Shape(..), area) where
+
+import qualified Data.Map.Strict as Map
+import Geometry.Vector (Vector, norm)
+
+data Shape = Circle Double | Rect { width :: Double, height :: Double }
+
+area :: Shape -> Double
+area (Circle r) = pi * r * r
+area (Rect w h) = w * h
+"#;
+		let haskell_lang = get_language("haskell").expect("Haskell language should be available");
+		let (imports, exports) = extract_imports_exports(haskell_lang.as_ref(), haskell_code);
+		assert_eq!(imports, vec!["Data.Map.Strict", "Geometry.Vector"]);
+		assert!(exports.contains(&"area".to_string()), "{:?}", exports);
+		assert!(exports.contains(&"Shape".to_string()), "{:?}", exports);
+
+		let symbols = symbols_of_kind(haskell_lang.as_ref(), haskell_code, "data_type");
+		for symbol in ["Shape", "Circle", "Rect", "width", "height"] {
+			assert!(symbols.contains(&symbol.to_string()), "{:?}", symbols);
+		}
+
+		let all_files = vec![
+			"src/Geometry/Shapes.hs".to_string(),
+			"src/Geometry/Vector.hs".to_string(),
+			"app/Main.hs".to_string(),
+		];
+		assert_eq!(
+			haskell_lang.resolve_import("Geometry.Vector", "src/Geometry/Shapes.hs", &all_files),
+			Some("src/Geometry/Vector.hs".to_string()),
+			"Should resolve a module under a source directory"
+		);
+		assert_eq!(
+			haskell_lang.resolve_import("Data.Map.Strict", "src/Geometry/Shapes.hs", &all_files),
+			None,
+			"Modules of other packages should not resolve"
+		);
+	}
+
+	/// Test Lua require extraction, symbols and module resolution
+	#[tokio::test]
+	async fn test_lua_extraction_and_resolution() {
+		let lua_code = r#"local config = require("plugin.config")
+local util = require "plugin.util"
+
+local M = {}
+
+function M.setup(opts)
+  config.apply(opts)
+end
+
+local function helper()
+  return util.noop()
+end
+
+return M
+"#;
+		let lua_lang = get_language("lua").expect("Lua language should be available");
+		let (imports, exports) = extract_imports_exports(lua_lang.as_ref(), lua_code);
+		assert_eq!(imports, vec!["plugin.config", "plugin.util"]);
+		assert_eq!(exports, vec!["M.setup"], "Local functions are not exported");
+
+		let symbols = symbols_of_kind(lua_lang.as_ref(), lua_code, "function_declaration");
+		assert!(symbols.iter().any(|s| s.contains("setup")), "{:?}", symbols);
+		assert!(symbols.iter().any(|s| s == "helper"), "{:?}", symbols);
+
+		let all_files = vec![
+			"lua/plugin/config.lua".to_string(),
+			"lua/plugin/util/init.lua".to_string(),
+			"init.lua".to_string(),
+		];
+		assert_eq!(
+			lua_lang.resolve_import("plugin.config", "init.lua", &all_files),
+			Some("lua/plugin/config.lua".to_string()),
+			"Should resolve a module file under a package.path root"
+		);
+		assert_eq!(
+			lua_lang.resolve_import("plugin.util", "init.lua", &all_files),
+			Some("lua/plugin/util/init.lua".to_string()),
+			"Should resolve a module directory to its init.lua"
+		);
+	}
+
+	/// Test Dart import/export extraction, symbols and import resolution
+	#[tokio::test]
+	async fn test_dart_extraction_and_resolution() {
+		let dart_code = r#"import 'dart:async';
+import 'package:app/src/widgets.dart' show Button;
+import '../models/user.dart';
+
+class UserCard {
+  final String name;
+  UserCard(this.name);
+}
+
+class _Cache {}
+
+String greet(String name) {
+  return 'Hello $name';
+}
+"#;
+		let dart_lang = get_language("dart").expect("Dart language should be available");
+		let (imports, exports) = extract_imports_exports(dart_lang.as_ref(), dart_code);
+		assert_eq!(
+			imports,
+			vec![
+				"dart:async",
+				"package:app/src/widgets.dart",
+				"../models/user.dart"
+			]
+		);
+		assert!(exports.contains(&"UserCard".to_string()), "{:?}", exports);
+		assert!(exports.contains(&"greet".to_string()), "{:?}", exports);
+		assert!(
+			!exports.contains(&"_Cache".to_string()),
+			"Private names are not exported"
+		);
+
+		let symbols = symbols_of_kind(dart_lang.as_ref(), dart_code, "class_definition");
+		assert!(symbols.contains(&"UserCard".to_string()), "{:?}", symbols);
+
+		let all_files = vec![
+			"lib/src/widgets.dart".to_string(),
+			"lib/src/screens/home.dart".to_string(),
+			"lib/src/models/user.dart".to_string(),
+		];
+		let source = "lib/src/screens/home.dart";
+		assert_eq!(
+			dart_lang.resolve_import("package:app/src/widgets.dart", source, &all_files),
+			Some("lib/src/widgets.dart".to_string()),
+			"Should resolve a package: import to lib/"
+		);
+		assert_eq!(
+			dart_lang.resolve_import("../models/user.dart", source, &all_files),
+			Some("lib/src/models/user.dart".to_string()),
+			"Should resolve a relative import"
+		);
+		assert_eq!(
+			dart_lang.resolve_import("dart:async", source, &all_files),
+			None,
+			"SDK libraries should not resolve"
+		);
+	}
+
+	/// Test SQL symbols; SQL files don't import each other
+	#[tokio::test]
+	async fn test_sql_extraction_and_resolution() {
+		let sql_code = r#"CREATE TABLE public.orders (
+    id BIGSERIAL PRIMARY KEY,
+    account_id BIGINT NOT NULL REFERENCES accounts (id),
+    placed_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+"#;
+		let sql_lang = get_language("sql").expect("SQL language should be available");
+		let (imports, exports) = extract_imports_exports(sql_lang.as_ref(), sql_code);
+		assert!(imports.is_empty() && exports.is_empty());
+
+		let symbols = symbols_of_kind(sql_lang.as_ref(), sql_code, "create_table");
+		for symbol in [
+			"public.orders",
+			"orders",
+			"accounts",
+			"id",
+			"account_id",
+			"placed_at",
+			"postgresql",
+		] {
+			assert!(symbols.contains(&symbol.to_string()), "{:?}", symbols);
+		}
+
+		let all_files = vec!["schema/orders.sql".to_string()];
+		assert_eq!(
+			sql_lang.resolve_import("orders.sql", "schema/accounts.sql", &all_files),
+			None
+		);
+	}
+
+	/// Imports and exports of a whole file
+	fn extract_imports_exports(lang_impl: &dyn Language, code: &str) -> (Vec<String>, Vec<String>) {
+		let mut parser = Parser::new();
+		parser.set_language(&lang_impl.get_ts_language()).unwrap();
+		let tree = parser.parse(code, None).unwrap();
+
+		let mut imports = Vec::new();
+		let mut exports = Vec::new();
+		extract_imports_exports_recursive(
+			tree.root_node(),
+			code,
+			lang_impl,
+			&mut imports,
+			&mut exports,
+		);
+		(imports, exports)
+	}
+
+	/// Symbols of every node of one kind in a file
+	fn symbols_of_kind(lang_impl: &dyn Language, code: &str, kind: &str) -> Vec<String> {
+		fn collect(
+			node: tree_sitter::Node,
+			code: &str,
+			lang_impl: &dyn Language,
+			kind: &str,
+			symbols: &mut Vec<String>,
+		) {
+			if node.kind() == kind {
+				symbols.extend(lang_impl.extract_symbols(node, code));
+			}
+			let mut cursor = node.walk();
+			for child in node.children(&mut cursor) {
+				collect(child, code, lang_impl, kind, symbols);
+			}
+		}
+
+		let mut parser = Parser::new();
+		parser.set_language(&lang_impl.get_ts_language()).unwrap();
+		let tree = parser.parse(code, None).unwrap();
+		let mut symbols = Vec::new();
+		collect(tree.root_node(), code, lang_impl, kind, &mut symbols);
+		assert!(!symbols.is_empty(), "no {} node found", kind);
+		symbols
+	}
+
 	/// Helper function to extract imports/exports recursively (same as in builder.rs)
 	fn extract_imports_exports_recursive(
 		node: tree_sitter::Node,
@@ -42,8 +42,8 @@ pub struct FunctionInfo {
 	pub signature: String,           // Function signature
 	pub start_line: u32,             // Starting line number
 	pub end_line: u32,               // Ending line number
-	pub calls: Vec<String>,          // Functions this function calls
-	pub called_by: Vec<String>,      // Functions that call this function
+	pub calls: Vec<String>,          // Names of the functions this function calls
+	pub called_by: Vec<String>,      // Callers as `path:function`, derived when the graph loads
 	pub parameters: Vec<String>,     // Function parameters
 	pub return_type: Option<String>, // Return type if available
 }
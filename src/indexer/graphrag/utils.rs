@@ -15,6 +15,7 @@
 // GraphRAG utility functions
 
 use crate::indexer::graphrag::types::CodeNode;
+use crate::store::Anchor;
 use anyhow::Result;
 use std::path::{Path, PathBuf};
 
@@ -143,6 +144,10 @@ pub fn graphrag_nodes_to_text(nodes: &[CodeNode]) -> String {
 		for node in file_nodes {
 			output.push_str(&format!("  {} {}\n", node.kind, node.name));
 			output.push_str(&format!("  ID: {}\n", node.id));
+			output.push_str(&format!(
+				"  Anchor: {}\n",
+				Anchor::for_node(&node.path, &node.hash)
+			));
 			output.push_str(&format!("  Description: {}\n", node.description));
 
 			if !node.symbols.is_empty() {
@@ -196,6 +201,10 @@ pub fn graphrag_nodes_to_markdown(nodes: &[CodeNode]) -> String {
 		for node in file_nodes {
 			markdown.push_str(&format!("### {} `{}`\n", node.kind, node.name));
 			markdown.push_str(&format!("**ID:** {}  \n", node.id));
+			markdown.push_str(&format!(
+				"**Anchor:** `{}`  \n",
+				Anchor::for_node(&node.path, &node.hash)
+			));
 			markdown.push_str(&format!("**Description:** {}  \n", node.description));
 
 			if !node.symbols.is_empty() {
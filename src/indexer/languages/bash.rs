@@ -153,6 +153,14 @@ impl Language for Bash {
 		}
 	}
 
+	fn call_query(&self) -> Option<&'static str> {
+		Some(
+			r#"
+			(command name: (command_name (word) @callee))
+			"#,
+		)
+	}
+
 	fn get_file_extensions(&self) -> Vec<&'static str> {
 		vec!["sh", "bash"]
 	}
@@ -215,6 +215,16 @@ impl Language for Cpp {
 		}
 	}
 
+	fn call_query(&self) -> Option<&'static str> {
+		Some(
+			r#"
+			(call_expression function: (identifier) @callee)
+			(call_expression function: (field_expression field: (field_identifier) @callee))
+			(call_expression function: (qualified_identifier name: (identifier) @callee))
+			"#,
+		)
+	}
+
 	fn get_file_extensions(&self) -> Vec<&'static str> {
 		vec!["cpp", "cc", "cxx", "c++", "c", "h", "hpp"]
 	}
@@ -0,0 +1,275 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Dart language implementation for the indexer
+//!
+//! Classes, mixins, extensions, enums and type aliases are indexed as blocks
+//! with their members, found as `Class.member` and `member`. Classes with a
+//! `build` method (Flutter widgets and their `State`s) also carry the
+//! `widget_build` symbol, so widgets can be searched for as such.
+//!
+//! The grammar puts a top-level function's signature and body side by side
+//! rather than in one node (or, for typed functions, wraps both in a lambda
+//! expression), so top-level functions are indexed by their signature.
+
+use crate::indexer::languages::Language;
+use tree_sitter::Node;
+
+/// Symbol of classes that build a widget tree
+const WIDGET_BUILD_SYMBOL: &str = "widget_build";
+
+/// Signature kinds of class members
+const MEMBER_SIGNATURES: &[&str] = &[
+	"function_signature",
+	"getter_signature",
+	"setter_signature",
+	"constructor_signature",
+	"factory_constructor_signature",
+];
+
+pub struct Dart {}
+
+impl Language for Dart {
+	fn name(&self) -> &'static str {
+		"dart"
+	}
+
+	fn get_ts_language(&self) -> tree_sitter::Language {
+		tree_sitter_dart::language()
+	}
+
+	fn get_meaningful_kinds(&self) -> Vec<&'static str> {
+		vec![
+			"class_definition",
+			"mixin_declaration",
+			"extension_declaration",
+			"enum_declaration",
+			"type_alias",
+			"function_signature",
+		]
+	}
+
+	fn extract_symbols(&self, node: Node, contents: &str) -> Vec<String> {
+		let mut symbols = Vec::new();
+
+		match node.kind() {
+			"class_definition" | "mixin_declaration" | "extension_declaration" => {
+				let name = Self::declaration_name(node, contents);
+				// `extension StringX on String` is found by the type it extends
+				if node.kind() == "extension_declaration" {
+					symbols.extend(
+						node.child_by_field_name("class")
+							.and_then(|class| class.utf8_text(contents.as_bytes()).ok())
+							.map(|class| class.trim().to_string()),
+					);
+				}
+
+				let mut members = Vec::new();
+				if let Some(body) = Self::body(node) {
+					Self::collect_members(body, contents, &mut members);
+				}
+				if members.iter().any(|member| member == "build") {
+					symbols.push(WIDGET_BUILD_SYMBOL.to_string());
+				}
+				for member in members {
+					// Constructors are named after their class
+					if let Some(name) = name.as_ref().filter(|name| **name != member) {
+						symbols.push(format!("{}.{}", name, member));
+					}
+					symbols.push(member);
+				}
+				symbols.extend(name);
+			}
+			"enum_declaration" => {
+				symbols.extend(Self::declaration_name(node, contents));
+				if let Some(body) = Self::body(node) {
+					for constant in body
+						.children(&mut body.walk())
+						.filter(|child| child.kind() == "enum_constant")
+					{
+						symbols.extend(Self::declaration_name(constant, contents));
+					}
+				}
+			}
+			"type_alias" | "function_signature" => {
+				symbols.extend(Self::declaration_name(node, contents));
+			}
+			_ => self.extract_identifiers(node, contents, &mut symbols),
+		}
+
+		// Deduplicate symbols before returning
+		symbols.sort();
+		symbols.dedup();
+
+		symbols
+	}
+
+	fn extract_identifiers(&self, node: Node, contents: &str, symbols: &mut Vec<String>) {
+		if node.kind() == "identifier" || node.kind() == "type_identifier" {
+			if let Ok(text) = node.utf8_text(contents.as_bytes()) {
+				let text = text.trim();
+				if !text.is_empty() && !symbols.contains(&text.to_string()) {
+					symbols.push(text.to_string());
+				}
+			}
+		}
+
+		// Continue with recursive traversal
+		let mut cursor = node.walk();
+		if cursor.goto_first_child() {
+			loop {
+				self.extract_identifiers(cursor.node(), contents, symbols);
+				if !cursor.goto_next_sibling() {
+					break;
+				}
+			}
+		}
+	}
+
+	fn extract_imports_exports(&self, node: Node, contents: &str) -> (Vec<String>, Vec<String>) {
+		let mut imports = Vec::new();
+		let mut exports = Vec::new();
+
+		match node.kind() {
+			// `import 'package:app/src/widgets.dart' show Button;`
+			"import_specification" => {
+				imports.extend(Self::first_string(node, contents));
+			}
+			// Top-level names without a leading underscore are public
+			"class_definition"
+			| "mixin_declaration"
+			| "extension_declaration"
+			| "enum_declaration"
+			| "type_alias"
+			| "function_signature" => {
+				// A typed top-level function parses as a lambda expression
+				// wrapping its signature
+				let container = match node.parent() {
+					Some(parent) if parent.kind() == "lambda_expression" => parent.parent(),
+					parent => parent,
+				};
+				let is_top_level = container.is_some_and(|parent| parent.kind() == "program");
+				if is_top_level {
+					exports.extend(
+						Self::declaration_name(node, contents)
+							.filter(|name| !name.starts_with('_')),
+					);
+				}
+			}
+			_ => {}
+		}
+
+		(imports, exports)
+	}
+
+	fn are_node_types_equivalent(&self, type1: &str, type2: &str) -> bool {
+		type1 == type2
+			|| matches!(
+				(type1, type2),
+				("enum_declaration", "type_alias") | ("type_alias", "enum_declaration")
+			)
+	}
+
+	fn get_node_type_description(&self, node_type: &str) -> &'static str {
+		match node_type {
+			"class_definition" => "class declarations",
+			"mixin_declaration" => "mixin declarations",
+			"extension_declaration" => "extension declarations",
+			"enum_declaration" | "type_alias" => "type definitions",
+			"function_signature" => "function declarations",
+			"comment" | "documentation_comment" => "comments",
+			_ => "declarations",
+		}
+	}
+
+	fn resolve_import(
+		&self,
+		import_path: &str,
+		source_file: &str,
+		all_files: &[String],
+	) -> Option<String> {
+		use super::resolution_utils::{resolve_relative_path, FileRegistry};
+
+		// SDK libraries are not part of the project
+		if import_path.starts_with("dart:") {
+			return None;
+		}
+
+		let registry = FileRegistry::new(all_files);
+		match import_path.strip_prefix("package:") {
+			// `package:app/src/x.dart` is `lib/src/x.dart` of package `app`;
+			// other packages are dependencies and don't resolve
+			Some(package_path) => {
+				let (_, path) = package_path.split_once('/')?;
+				let target = format!("lib/{}", path);
+				registry
+					.get_files_with_extensions(&["dart"])
+					.into_iter()
+					.filter(|file| file == &target || file.ends_with(&format!("/{}", target)))
+					.min_by_key(|file| file.len())
+			}
+			None => {
+				let target = resolve_relative_path(source_file, import_path)?;
+				registry.find_exact_file(&target.to_string_lossy())
+			}
+		}
+	}
+
+	fn get_file_extensions(&self) -> Vec<&'static str> {
+		vec!["dart"]
+	}
+}
+
+impl Dart {
+	fn declaration_name(node: Node, contents: &str) -> Option<String> {
+		node.child_by_field_name("name")
+			.or_else(|| {
+				node.children(&mut node.walk())
+					.find(|child| child.kind() == "identifier")
+			})
+			.and_then(|name| name.utf8_text(contents.as_bytes()).ok())
+			.map(|name| name.trim().to_string())
+			.filter(|name| !name.is_empty())
+	}
+
+	/// Body of a class, mixin, extension or enum
+	fn body(node: Node) -> Option<Node> {
+		node.child_by_field_name("body").or_else(|| {
+			node.children(&mut node.walk())
+				.find(|child| child.kind().ends_with("_body"))
+		})
+	}
+
+	/// Names of the methods, getters, setters and constructors of a body,
+	/// leaving out functions declared inside them
+	fn collect_members(node: Node, contents: &str, members: &mut Vec<String>) {
+		for child in node.children(&mut node.walk()) {
+			if MEMBER_SIGNATURES.contains(&child.kind()) {
+				members.extend(Self::declaration_name(child, contents));
+			} else if child.kind() != "function_body" {
+				Self::collect_members(child, contents, members);
+			}
+		}
+	}
+
+	/// The URI of an import, without its quotes
+	fn first_string(node: Node, contents: &str) -> Option<String> {
+		if node.kind() == "string_literal" {
+			let text = node.utf8_text(contents.as_bytes()).ok()?;
+			return Some(text.trim_matches(|c| c == '"' || c == '\'').to_string());
+		}
+		node.children(&mut node.walk())
+			.find_map(|child| Self::first_string(child, contents))
+	}
+}
@@ -224,6 +224,15 @@ impl Language for Go {
 		}
 	}
 
+	fn call_query(&self) -> Option<&'static str> {
+		Some(
+			r#"
+			(call_expression function: (identifier) @callee)
+			(call_expression function: (selector_expression field: (field_identifier) @callee))
+			"#,
+		)
+	}
+
 	fn get_file_extensions(&self) -> Vec<&'static str> {
 		vec!["go"]
 	}
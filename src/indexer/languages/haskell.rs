@@ -0,0 +1,250 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Haskell language implementation for the indexer
+//!
+//! The module header, top-level functions with their type signatures, data
+//! types, newtypes, type synonyms and families, type classes and instances
+//! are indexed as blocks. Data types are also found by their constructors and
+//! record fields, classes by their methods.
+
+use crate::indexer::languages::Language;
+use tree_sitter::Node;
+
+pub struct Haskell {}
+
+impl Language for Haskell {
+	fn name(&self) -> &'static str {
+		"haskell"
+	}
+
+	fn get_ts_language(&self) -> tree_sitter::Language {
+		tree_sitter_haskell::LANGUAGE.into()
+	}
+
+	fn get_meaningful_kinds(&self) -> Vec<&'static str> {
+		vec![
+			"header",
+			"signature",
+			"function",
+			"bind",
+			"data_type",
+			"newtype",
+			// The grammar's spelling
+			"type_synomym",
+			"type_family",
+			"data_family",
+			"class",
+			"instance",
+		]
+	}
+
+	fn extract_symbols(&self, node: Node, contents: &str) -> Vec<String> {
+		let mut symbols = Vec::new();
+
+		match node.kind() {
+			"header" => {
+				symbols.extend(Self::field_text(node, "module", contents));
+			}
+			"signature" | "function" | "bind" => {
+				symbols.extend(Self::declaration_name(node, contents));
+			}
+			"data_type" | "newtype" | "data_family" => {
+				symbols.extend(Self::declaration_name(node, contents));
+				// Constructors and record fields lead to their type
+				Self::collect_kinds(node, &["constructor", "field_name"], contents, &mut symbols);
+			}
+			"type_synomym" | "type_family" => {
+				symbols.extend(Self::declaration_name(node, contents));
+			}
+			"class" => {
+				symbols.extend(Self::declaration_name(node, contents));
+				if let Some(methods) = node.child_by_field_name("declarations") {
+					for method in methods
+						.children(&mut methods.walk())
+						.filter(|child| child.kind() == "signature")
+					{
+						symbols.extend(Self::declaration_name(method, contents));
+					}
+				}
+			}
+			"instance" => {
+				// The class and the types of the instance head, e.g. `Show Color`
+				for child in node
+					.children(&mut node.walk())
+					.filter(|child| child.kind() != "instance_declarations")
+				{
+					Self::collect_kinds(child, &["name"], contents, &mut symbols);
+				}
+			}
+			_ => self.extract_identifiers(node, contents, &mut symbols),
+		}
+
+		// Deduplicate symbols before returning
+		symbols.sort();
+		symbols.dedup();
+
+		symbols
+	}
+
+	fn extract_identifiers(&self, node: Node, contents: &str, symbols: &mut Vec<String>) {
+		if matches!(node.kind(), "variable" | "name" | "constructor") {
+			if let Ok(text) = node.utf8_text(contents.as_bytes()) {
+				let text = text.trim();
+				if !text.is_empty() && !symbols.contains(&text.to_string()) {
+					symbols.push(text.to_string());
+				}
+			}
+		}
+
+		// Continue with recursive traversal
+		let mut cursor = node.walk();
+		if cursor.goto_first_child() {
+			loop {
+				self.extract_identifiers(cursor.node(), contents, symbols);
+				if !cursor.goto_next_sibling() {
+					break;
+				}
+			}
+		}
+	}
+
+	fn extract_imports_exports(&self, node: Node, contents: &str) -> (Vec<String>, Vec<String>) {
+		let mut imports = Vec::new();
+		let mut exports = Vec::new();
+
+		match node.kind() {
+			// `import qualified Data.Map.Strict as Map`
+			"import" => {
+				imports.extend(Self::field_text(node, "module", contents));
+			}
+			// `module Foo (bar, Baz(..)) where` exports what it lists
+			"header" => {
+				if let Some(list) = node.child_by_field_name("exports") {
+					Self::collect_kinds(list, &["variable", "name"], contents, &mut exports);
+				}
+			}
+			// Without an export list every top-level declaration is exported
+			"function" | "bind" | "data_type" | "newtype" | "type_synomym" | "class" => {
+				let is_top_level = node
+					.parent()
+					.and_then(|parent| parent.parent())
+					.is_some_and(|root| root.parent().is_none());
+				if is_top_level && !Self::has_export_list(node) {
+					exports.extend(Self::declaration_name(node, contents));
+				}
+			}
+			_ => {}
+		}
+
+		(imports, exports)
+	}
+
+	fn are_node_types_equivalent(&self, type1: &str, type2: &str) -> bool {
+		if type1 == type2 {
+			return true;
+		}
+
+		// A signature belongs with the equations that follow it
+		let semantic_groups = [
+			&["signature", "function", "bind"] as &[&str],
+			&["data_type", "newtype", "type_synomym"],
+			&["type_family", "data_family"],
+		];
+		semantic_groups
+			.iter()
+			.any(|group| group.contains(&type1) && group.contains(&type2))
+	}
+
+	fn get_node_type_description(&self, node_type: &str) -> &'static str {
+		match node_type {
+			"header" => "module declarations",
+			"signature" | "function" | "bind" => "function declarations",
+			"data_type" | "newtype" | "type_synomym" => "type definitions",
+			"type_family" | "data_family" => "type family declarations",
+			"class" => "type class declarations",
+			"instance" => "instance declarations",
+			"comment" => "comments",
+			_ => "declarations",
+		}
+	}
+
+	fn resolve_import(
+		&self,
+		import_path: &str,
+		_source_file: &str,
+		all_files: &[String],
+	) -> Option<String> {
+		use super::resolution_utils::FileRegistry;
+
+		// `Data.Map.Strict` lives in `Data/Map/Strict.hs` under a source
+		// directory such as `src/`; modules of other packages don't resolve
+		let module_path = format!("{}.hs", import_path.replace('.', "/"));
+		let registry = FileRegistry::new(all_files);
+		registry
+			.get_files_with_extensions(&["hs"])
+			.into_iter()
+			.filter(|file| file == &module_path || file.ends_with(&format!("/{}", module_path)))
+			.min_by_key(|file| file.len())
+	}
+
+	fn get_file_extensions(&self) -> Vec<&'static str> {
+		vec!["hs"]
+	}
+}
+
+impl Haskell {
+	fn field_text(node: Node, field: &str, contents: &str) -> Option<String> {
+		node.child_by_field_name(field)
+			.and_then(|child| child.utf8_text(contents.as_bytes()).ok())
+			.map(|text| text.trim().to_string())
+	}
+
+	/// Name of a declaration; operators defined in prefix form lose their
+	/// parentheses
+	fn declaration_name(node: Node, contents: &str) -> Option<String> {
+		Self::field_text(node, "name", contents)
+			.map(|name| {
+				name.trim_start_matches('(')
+					.trim_end_matches(')')
+					.trim()
+					.to_string()
+			})
+			.filter(|name| !name.is_empty())
+	}
+
+	/// Text of every descendant of the given kinds
+	fn collect_kinds(node: Node, kinds: &[&str], contents: &str, symbols: &mut Vec<String>) {
+		if kinds.contains(&node.kind()) {
+			if let Ok(text) = node.utf8_text(contents.as_bytes()) {
+				symbols.push(text.trim().to_string());
+			}
+			return;
+		}
+		for child in node.children(&mut node.walk()) {
+			Self::collect_kinds(child, kinds, contents, symbols);
+		}
+	}
+
+	/// Whether the module of a top-level declaration lists its exports
+	fn has_export_list(node: Node) -> bool {
+		let mut root = node;
+		while let Some(parent) = root.parent() {
+			root = parent;
+		}
+		root.children(&mut root.walk())
+			.find(|child| child.kind() == "header")
+			.is_some_and(|header| header.child_by_field_name("exports").is_some())
+	}
+}
@@ -0,0 +1,230 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! HCL (Terraform) language implementation for the indexer
+
+use crate::indexer::languages::Language;
+use tree_sitter::Node;
+
+pub struct Hcl {}
+
+impl Language for Hcl {
+	fn name(&self) -> &'static str {
+		"hcl"
+	}
+
+	fn get_ts_language(&self) -> tree_sitter::Language {
+		tree_sitter_hcl::LANGUAGE.into()
+	}
+
+	fn get_meaningful_kinds(&self) -> Vec<&'static str> {
+		// Blocks are resources, data sources, modules, variables, outputs,
+		// locals, providers...; top-level attributes are .tfvars assignments
+		vec!["block", "attribute"]
+	}
+
+	fn extract_symbols(&self, node: Node, contents: &str) -> Vec<String> {
+		let mut symbols = Vec::new();
+
+		match node.kind() {
+			"block" => {
+				if let Some((block_type, labels)) = Self::block_header(node, contents) {
+					symbols.extend(Self::block_references(&block_type, &labels));
+
+					// Each local value is referenced on its own
+					if block_type == "locals" {
+						for name in Self::attribute_names(node, contents) {
+							symbols.push(format!("local.{}", name));
+						}
+					}
+				}
+			}
+			"attribute" => {
+				if let Some(name) = Self::attribute_name(node, contents) {
+					symbols.push(name);
+				}
+			}
+			_ => self.extract_identifiers(node, contents, &mut symbols),
+		}
+
+		// Deduplicate symbols before returning
+		symbols.sort();
+		symbols.dedup();
+
+		symbols
+	}
+
+	fn extract_identifiers(&self, node: Node, contents: &str, symbols: &mut Vec<String>) {
+		// Attribute names are the closest thing HCL has to identifiers
+		if node.kind() == "attribute" {
+			if let Some(name) = Self::attribute_name(node, contents) {
+				if !symbols.contains(&name) {
+					symbols.push(name);
+				}
+			}
+		}
+
+		// Continue with recursive traversal
+		let mut cursor = node.walk();
+		if cursor.goto_first_child() {
+			loop {
+				self.extract_identifiers(cursor.node(), contents, symbols);
+				if !cursor.goto_next_sibling() {
+					break;
+				}
+			}
+		}
+	}
+
+	fn extract_imports_exports(&self, node: Node, contents: &str) -> (Vec<String>, Vec<String>) {
+		let mut imports = Vec::new();
+		let mut exports = Vec::new();
+
+		if node.kind() == "block" {
+			if let Some((block_type, labels)) = Self::block_header(node, contents) {
+				match block_type.as_str() {
+					// Local modules are imported by their source directory
+					"module" => {
+						if let Some(source) = Self::attribute_value(node, contents, "source") {
+							if source.starts_with("./") || source.starts_with("../") {
+								imports.push(source);
+							}
+						}
+					}
+					"output" => exports.extend(labels.first().cloned()),
+					_ => {}
+				}
+			}
+		}
+
+		(imports, exports)
+	}
+
+	fn get_node_type_description(&self, node_type: &str) -> &'static str {
+		match node_type {
+			"block" => "Terraform blocks",
+			"attribute" => "variable assignments",
+			"comment" => "comments",
+			_ => "declarations",
+		}
+	}
+
+	fn resolve_import(
+		&self,
+		import_path: &str,
+		source_file: &str,
+		all_files: &[String],
+	) -> Option<String> {
+		use super::resolution_utils::{
+			find_files_in_directory, resolve_relative_path, FileRegistry,
+		};
+
+		// A module source is a directory; point at its main.tf, or its first file
+		let registry = FileRegistry::new(all_files);
+		let module_dir = resolve_relative_path(source_file, import_path)?;
+		let mut files: Vec<String> = find_files_in_directory(&module_dir, &registry, &["tf"])
+			.into_iter()
+			.filter(|file| std::path::Path::new(file).parent() == Some(module_dir.as_path()))
+			.collect();
+		files.sort();
+
+		files
+			.iter()
+			.find(|file| file.ends_with("/main.tf"))
+			.or_else(|| files.first())
+			.cloned()
+	}
+
+	fn get_file_extensions(&self) -> Vec<&'static str> {
+		vec!["tf", "tfvars", "hcl"]
+	}
+}
+
+impl Hcl {
+	/// Block type and labels: `resource "aws_s3_bucket" "logs" {` gives
+	/// `("resource", ["aws_s3_bucket", "logs"])`
+	fn block_header(node: Node, contents: &str) -> Option<(String, Vec<String>)> {
+		let mut block_type = None;
+		let mut labels = Vec::new();
+
+		for child in node.children(&mut node.walk()) {
+			let text = || child.utf8_text(contents.as_bytes()).ok();
+			match child.kind() {
+				"identifier" if block_type.is_none() => block_type = text().map(String::from),
+				"identifier" => labels.extend(text().map(String::from)),
+				"string_lit" => labels.extend(text().map(|t| t.trim_matches('"').to_string())),
+				"block_start" => break,
+				_ => {}
+			}
+		}
+
+		block_type.map(|block_type| (block_type, labels))
+	}
+
+	/// Names Terraform code uses to refer to a block, e.g. `aws_s3_bucket.logs`,
+	/// `module.vpc` or `var.region`. Resources and data sources are also found
+	/// by their type alone.
+	fn block_references(block_type: &str, labels: &[String]) -> Vec<String> {
+		match (block_type, labels) {
+			("resource", [kind, name, ..]) => vec![format!("{}.{}", kind, name), kind.clone()],
+			("data", [kind, name, ..]) => vec![format!("data.{}.{}", kind, name), kind.clone()],
+			("variable", [name, ..]) => vec![format!("var.{}", name)],
+			("module" | "output" | "provider", [name, ..]) => {
+				vec![format!("{}.{}", block_type, name)]
+			}
+			(_, []) => vec![block_type.to_string()],
+			(_, labels) => vec![format!("{}.{}", block_type, labels.join("."))],
+		}
+	}
+
+	/// Name of an attribute (`name = value`)
+	fn attribute_name(node: Node, contents: &str) -> Option<String> {
+		node.children(&mut node.walk())
+			.find(|child| child.kind() == "identifier")
+			.and_then(|child| child.utf8_text(contents.as_bytes()).ok())
+			.map(String::from)
+	}
+
+	/// Names of the attributes directly inside a block's body
+	fn attribute_names(block: Node, contents: &str) -> Vec<String> {
+		Self::body_attributes(block)
+			.into_iter()
+			.filter_map(|attribute| Self::attribute_name(attribute, contents))
+			.collect()
+	}
+
+	/// Literal value of a block attribute, without quotes
+	fn attribute_value(block: Node, contents: &str, name: &str) -> Option<String> {
+		let attribute = Self::body_attributes(block).into_iter().find(|attribute| {
+			Self::attribute_name(*attribute, contents).as_deref() == Some(name)
+		})?;
+		let value = attribute
+			.children(&mut attribute.walk())
+			.find(|child| child.kind() == "expression")?;
+		let text = value.utf8_text(contents.as_bytes()).ok()?.trim();
+		Some(text.trim_matches('"').to_string())
+	}
+
+	fn body_attributes(block: Node) -> Vec<Node> {
+		block
+			.children(&mut block.walk())
+			.filter(|child| child.kind() == "body")
+			.flat_map(|body| {
+				body.children(&mut body.walk())
+					.filter(|child| child.kind() == "attribute")
+					.collect::<Vec<_>>()
+			})
+			.collect()
+	}
+}
@@ -224,6 +224,15 @@ impl Language for JavaScript {
 		}
 	}
 
+	fn call_query(&self) -> Option<&'static str> {
+		Some(
+			r#"
+			(call_expression function: (identifier) @callee)
+			(call_expression function: (member_expression property: (property_identifier) @callee))
+			"#,
+		)
+	}
+
 	fn get_file_extensions(&self) -> Vec<&'static str> {
 		vec!["js", "jsx", "mjs"]
 	}
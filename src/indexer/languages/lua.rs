@@ -0,0 +1,252 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Lua language implementation for the indexer
+//!
+//! Function declarations and top-level local and global assignments are
+//! indexed as blocks. Lua modules are tables, so `function M.setup()` is found
+//! as both `M.setup` and `setup`, and the fields of a table constructor
+//! (`local M = { setup = ... }`) as their names. `require` calls are imports.
+
+use crate::indexer::languages::Language;
+use tree_sitter::Node;
+
+pub struct Lua {}
+
+impl Language for Lua {
+	fn name(&self) -> &'static str {
+		"lua"
+	}
+
+	fn get_ts_language(&self) -> tree_sitter::Language {
+		tree_sitter_lua::LANGUAGE.into()
+	}
+
+	fn get_meaningful_kinds(&self) -> Vec<&'static str> {
+		// `local function` parses as a function_declaration too, and
+		// `local x = ...` as a variable_declaration
+		vec![
+			"function_declaration",
+			"variable_declaration",
+			"assignment_statement",
+		]
+	}
+
+	fn extract_symbols(&self, node: Node, contents: &str) -> Vec<String> {
+		let mut symbols = Vec::new();
+
+		match node.kind() {
+			"function_declaration" => {
+				if let Some(name) = node
+					.child_by_field_name("name")
+					.and_then(|name| name.utf8_text(contents.as_bytes()).ok())
+				{
+					Self::push_name(name, &mut symbols);
+				}
+			}
+			"variable_declaration" | "assignment_statement" => {
+				Self::collect_assignment(node, contents, &mut symbols);
+			}
+			_ => self.extract_identifiers(node, contents, &mut symbols),
+		}
+
+		// Deduplicate symbols before returning
+		symbols.sort();
+		symbols.dedup();
+
+		symbols
+	}
+
+	fn extract_identifiers(&self, node: Node, contents: &str, symbols: &mut Vec<String>) {
+		if node.kind() == "identifier" {
+			if let Ok(text) = node.utf8_text(contents.as_bytes()) {
+				let text = text.trim();
+				if !text.is_empty() && !symbols.contains(&text.to_string()) {
+					symbols.push(text.to_string());
+				}
+			}
+		}
+
+		// Continue with recursive traversal
+		let mut cursor = node.walk();
+		if cursor.goto_first_child() {
+			loop {
+				self.extract_identifiers(cursor.node(), contents, symbols);
+				if !cursor.goto_next_sibling() {
+					break;
+				}
+			}
+		}
+	}
+
+	fn extract_imports_exports(&self, node: Node, contents: &str) -> (Vec<String>, Vec<String>) {
+		let mut imports = Vec::new();
+		let mut exports = Vec::new();
+
+		match node.kind() {
+			// `require("plugin.config")`, `require "plugin.config"`
+			"function_call" => {
+				let is_require = node
+					.child_by_field_name("name")
+					.and_then(|name| name.utf8_text(contents.as_bytes()).ok())
+					.is_some_and(|name| name == "require");
+				if is_require {
+					imports.extend(
+						node.child_by_field_name("arguments")
+							.and_then(|arguments| Self::first_string(arguments, contents)),
+					);
+				}
+			}
+			// Globals and members of module tables are reachable by other
+			// files; locals are not
+			"function_declaration" => {
+				let is_local = node.child(0).is_some_and(|first| first.kind() == "local");
+				if !is_local {
+					exports.extend(
+						node.child_by_field_name("name")
+							.and_then(|name| name.utf8_text(contents.as_bytes()).ok())
+							.map(String::from),
+					);
+				}
+			}
+			_ => {}
+		}
+
+		(imports, exports)
+	}
+
+	fn are_node_types_equivalent(&self, type1: &str, type2: &str) -> bool {
+		type1 == type2
+			|| matches!(
+				(type1, type2),
+				("variable_declaration", "assignment_statement")
+					| ("assignment_statement", "variable_declaration")
+			)
+	}
+
+	fn get_node_type_description(&self, node_type: &str) -> &'static str {
+		match node_type {
+			"function_declaration" => "function declarations",
+			"variable_declaration" | "assignment_statement" => "variable declarations",
+			"comment" => "comments",
+			_ => "declarations",
+		}
+	}
+
+	fn call_query(&self) -> Option<&'static str> {
+		Some(
+			r#"
+			(function_call name: (identifier) @callee)
+			(function_call name: (dot_index_expression field: (identifier) @callee))
+			(function_call name: (method_index_expression method: (identifier) @callee))
+			"#,
+		)
+	}
+
+	fn resolve_import(
+		&self,
+		import_path: &str,
+		_source_file: &str,
+		all_files: &[String],
+	) -> Option<String> {
+		use super::resolution_utils::FileRegistry;
+
+		// `require("a.b")` loads `a/b.lua` or `a/b/init.lua` from any
+		// package.path root, e.g. `lua/` in Neovim plugins
+		let module = import_path.replace('.', "/");
+		let candidates = [format!("{}.lua", module), format!("{}/init.lua", module)];
+		let registry = FileRegistry::new(all_files);
+		registry
+			.get_files_with_extensions(&["lua"])
+			.into_iter()
+			.filter(|file| {
+				candidates.iter().any(|candidate| {
+					file == candidate || file.ends_with(&format!("/{}", candidate))
+				})
+			})
+			.min_by_key(|file| file.len())
+	}
+
+	fn get_file_extensions(&self) -> Vec<&'static str> {
+		vec!["lua"]
+	}
+}
+
+impl Lua {
+	/// A function or variable name; members (`M.setup`, `M:render`) are
+	/// also found by their own name
+	fn push_name(name: &str, symbols: &mut Vec<String>) {
+		let name = name.trim();
+		if name.is_empty() {
+			return;
+		}
+		symbols.push(name.to_string());
+		if let Some((_, member)) = name.rsplit_once(['.', ':']) {
+			symbols.push(member.to_string());
+		}
+	}
+
+	/// Assigned names, and the field names of assigned table constructors
+	fn collect_assignment(node: Node, contents: &str, symbols: &mut Vec<String>) {
+		for child in node.children(&mut node.walk()) {
+			match child.kind() {
+				"variable_list" => {
+					for variable in child
+						.children(&mut child.walk())
+						.filter(|variable| variable.is_named() && variable.kind() != "attribute")
+					{
+						if let Ok(name) = variable.utf8_text(contents.as_bytes()) {
+							Self::push_name(name, symbols);
+						}
+					}
+				}
+				"expression_list" => {
+					for table in child
+						.children(&mut child.walk())
+						.filter(|value| value.kind() == "table_constructor")
+					{
+						for field in table.children(&mut table.walk()) {
+							if let Some(name) = field
+								.child_by_field_name("name")
+								.filter(|name| name.kind() == "identifier")
+								.and_then(|name| name.utf8_text(contents.as_bytes()).ok())
+							{
+								symbols.push(name.to_string());
+							}
+						}
+					}
+				}
+				// `local x = ...` wraps its assignment
+				"assignment_statement" => Self::collect_assignment(child, contents, symbols),
+				_ => {}
+			}
+		}
+	}
+
+	/// Contents of the first string literal among the arguments
+	fn first_string(node: Node, contents: &str) -> Option<String> {
+		if node.kind() == "string" {
+			let text = match node.child_by_field_name("content") {
+				Some(content) => content.utf8_text(contents.as_bytes()).ok()?,
+				None => node
+					.utf8_text(contents.as_bytes())
+					.ok()?
+					.trim_matches(|c| c == '"' || c == '\''),
+			};
+			return Some(text.to_string());
+		}
+		node.children(&mut node.walk())
+			.find_map(|child| Self::first_string(child, contents))
+	}
+}
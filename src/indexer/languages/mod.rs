@@ -21,32 +21,48 @@ use tree_sitter::Node;
 mod bash;
 mod cpp;
 mod css;
+mod dart;
 mod go;
+mod haskell;
+mod hcl;
 mod javascript;
 mod json;
+mod lua;
 mod markdown;
 mod php;
+mod proto;
 mod python;
 pub mod resolution_utils;
 mod ruby;
 mod rust;
+#[cfg(test)]
+mod snapshots;
+mod sql;
 mod svelte;
 mod typescript;
+mod zig;
 
 // Re-export language modules
 pub use bash::Bash;
 pub use cpp::Cpp;
 pub use css::Css;
+pub use dart::Dart;
 pub use go::Go;
+pub use haskell::Haskell;
+pub use hcl::Hcl;
 pub use javascript::JavaScript;
 pub use json::Json;
+pub use lua::Lua;
 pub use markdown::Markdown;
 pub use php::Php;
+pub use proto::Proto;
 pub use python::Python;
 pub use ruby::Ruby;
 pub use rust::Rust;
+pub use sql::Sql;
 pub use svelte::Svelte;
 pub use typescript::TypeScript;
+pub use zig::Zig;
 
 /// Common trait for all language parsers
 pub trait Language {
@@ -102,6 +118,12 @@ pub trait Language {
 		}
 	}
 
+	/// Tree-sitter query capturing the called name of every call as `@callee`,
+	/// or `None` when calls are not extracted for this language
+	fn call_query(&self) -> Option<&'static str> {
+		None
+	}
+
 	/// Resolve import paths to actual file paths
 	/// Returns the resolved file path if found, None otherwise
 	fn resolve_import(
@@ -131,6 +153,13 @@ pub fn get_language(name: &str) -> Option<Box<dyn Language>> {
 		"svelte" => Some(Box::new(Svelte {})),
 		"css" => Some(Box::new(Css {})),
 		"markdown" => Some(Box::new(Markdown {})),
+		"hcl" => Some(Box::new(Hcl {})),
+		"sql" => Some(Box::new(Sql {})),
+		"zig" => Some(Box::new(Zig {})),
+		"proto" => Some(Box::new(Proto {})),
+		"haskell" => Some(Box::new(Haskell {})),
+		"lua" => Some(Box::new(Lua {})),
+		"dart" => Some(Box::new(Dart {})),
 		_ => None,
 	}
 }
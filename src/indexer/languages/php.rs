@@ -234,6 +234,17 @@ impl Language for Php {
 		None
 	}
 
+	fn call_query(&self) -> Option<&'static str> {
+		Some(
+			r#"
+			(function_call_expression function: (name) @callee)
+			(function_call_expression function: (qualified_name (name) @callee))
+			(member_call_expression name: (name) @callee)
+			(scoped_call_expression name: (name) @callee)
+			"#,
+		)
+	}
+
 	fn get_file_extensions(&self) -> Vec<&'static str> {
 		vec!["php"]
 	}
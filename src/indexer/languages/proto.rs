@@ -0,0 +1,221 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Protocol Buffers language implementation for the indexer
+//!
+//! Messages, enums and gRPC services are indexed as blocks. The methods of a
+//! service are also recorded as `Service/Method`, the form gRPC uses for
+//! method paths, which is how GraphRAG tells services apart from messages.
+
+use crate::indexer::languages::Language;
+use tree_sitter::Node;
+
+pub struct Proto {}
+
+impl Language for Proto {
+	fn name(&self) -> &'static str {
+		"proto"
+	}
+
+	fn get_ts_language(&self) -> tree_sitter::Language {
+		tree_sitter_proto::LANGUAGE.into()
+	}
+
+	fn get_meaningful_kinds(&self) -> Vec<&'static str> {
+		// Methods are indexed with their service; `rpc` is listed for the
+		// signatures of services spread over several blocks
+		vec!["message", "enum", "service", "rpc"]
+	}
+
+	fn extract_symbols(&self, node: Node, contents: &str) -> Vec<String> {
+		let mut symbols = Vec::new();
+
+		match node.kind() {
+			"message" | "enum" => {
+				if let Some(name) = Self::declaration_name(node, contents) {
+					symbols.push(name);
+				}
+				// Nested messages and enums are found by their own names too
+				Self::collect_nested_names(node, contents, &mut symbols);
+			}
+			"service" => {
+				if let Some(service) = Self::declaration_name(node, contents) {
+					for rpc in node
+						.children(&mut node.walk())
+						.filter(|child| child.kind() == "rpc")
+					{
+						Self::push_rpc_symbols(rpc, &service, contents, &mut symbols);
+					}
+					symbols.push(service);
+				}
+			}
+			"rpc" => {
+				let service = node
+					.parent()
+					.filter(|parent| parent.kind() == "service")
+					.and_then(|parent| Self::declaration_name(parent, contents))
+					.unwrap_or_default();
+				Self::push_rpc_symbols(node, &service, contents, &mut symbols);
+			}
+			_ => self.extract_identifiers(node, contents, &mut symbols),
+		}
+
+		// Deduplicate symbols before returning
+		symbols.sort();
+		symbols.dedup();
+
+		symbols
+	}
+
+	fn extract_identifiers(&self, node: Node, contents: &str, symbols: &mut Vec<String>) {
+		if node.kind() == "identifier" {
+			if let Ok(text) = node.utf8_text(contents.as_bytes()) {
+				let text = text.trim();
+				if !text.is_empty() && !symbols.contains(&text.to_string()) {
+					symbols.push(text.to_string());
+				}
+			}
+		}
+
+		// Continue with recursive traversal
+		let mut cursor = node.walk();
+		if cursor.goto_first_child() {
+			loop {
+				self.extract_identifiers(cursor.node(), contents, symbols);
+				if !cursor.goto_next_sibling() {
+					break;
+				}
+			}
+		}
+	}
+
+	fn extract_imports_exports(&self, node: Node, contents: &str) -> (Vec<String>, Vec<String>) {
+		let mut imports = Vec::new();
+		let mut exports = Vec::new();
+
+		match node.kind() {
+			// `import "path/to/file.proto";`, also `import public` and `import weak`
+			"import" => {
+				if let Some(path) = node
+					.children(&mut node.walk())
+					.find(|child| child.kind() == "string")
+					.and_then(|child| child.utf8_text(contents.as_bytes()).ok())
+				{
+					imports.push(path.trim_matches(|c| c == '"' || c == '\'').to_string());
+				}
+			}
+			// Every top-level definition can be used by importing files
+			"message" | "enum" | "service" => {
+				if node
+					.parent()
+					.is_some_and(|parent| parent.kind() == "source_file")
+				{
+					exports.extend(Self::declaration_name(node, contents));
+				}
+			}
+			_ => {}
+		}
+
+		(imports, exports)
+	}
+
+	fn get_node_type_description(&self, node_type: &str) -> &'static str {
+		match node_type {
+			"message" => "message definitions",
+			"enum" => "enum definitions",
+			"service" => "service definitions",
+			"rpc" => "service methods",
+			"comment" => "comments",
+			_ => "declarations",
+		}
+	}
+
+	fn resolve_import(
+		&self,
+		import_path: &str,
+		source_file: &str,
+		all_files: &[String],
+	) -> Option<String> {
+		use super::resolution_utils::{resolve_relative_path, FileRegistry};
+
+		// protoc resolves imports against its include paths, most often the
+		// project root; fall back to the importing file's directory
+		let registry = FileRegistry::new(all_files);
+		registry.find_exact_file(import_path).or_else(|| {
+			let target = resolve_relative_path(source_file, import_path)?;
+			registry.find_exact_file(&target.to_string_lossy())
+		})
+	}
+
+	fn get_file_extensions(&self) -> Vec<&'static str> {
+		vec!["proto"]
+	}
+}
+
+impl Proto {
+	/// Name of a message, enum, service or rpc
+	fn declaration_name(node: Node, contents: &str) -> Option<String> {
+		let name_kind = format!("{}_name", node.kind());
+		node.children(&mut node.walk())
+			.find(|child| child.kind() == name_kind)
+			.and_then(|child| child.utf8_text(contents.as_bytes()).ok())
+			.map(String::from)
+	}
+
+	/// Names of messages and enums defined inside a message body
+	fn collect_nested_names(node: Node, contents: &str, symbols: &mut Vec<String>) {
+		for child in node.children(&mut node.walk()) {
+			match child.kind() {
+				"message" | "enum" => {
+					symbols.extend(Self::declaration_name(child, contents));
+					Self::collect_nested_names(child, contents, symbols);
+				}
+				"message_body" | "enum_body" => {
+					Self::collect_nested_names(child, contents, symbols)
+				}
+				_ => {}
+			}
+		}
+	}
+
+	/// The method as `Service/Method`, and its request and response types
+	fn push_rpc_symbols(rpc: Node, service: &str, contents: &str, symbols: &mut Vec<String>) {
+		if let Some(method) = Self::declaration_name(rpc, contents) {
+			if service.is_empty() {
+				symbols.push(method);
+			} else {
+				symbols.push(format!("{}/{}", service, method));
+			}
+		}
+		for child in rpc.children(&mut rpc.walk()) {
+			if child.kind() == "message_or_enum_type" {
+				if let Ok(name) = child.utf8_text(contents.as_bytes()) {
+					symbols.push(name.trim_start_matches('.').to_string());
+				}
+			}
+		}
+	}
+
+	/// Services a file defines, from the `Service/Method` symbols of its
+	/// blocks
+	pub fn services(symbols: &[String]) -> Vec<&str> {
+		let mut services: Vec<&str> = symbols
+			.iter()
+			.filter_map(|symbol| symbol.split_once('/').map(|(service, _)| service))
+			.collect();
+		services.sort();
+		services.dedup();
+		services
+	}
+}
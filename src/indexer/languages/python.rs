@@ -203,6 +203,15 @@ impl Language for Python {
 		}
 	}
 
+	fn call_query(&self) -> Option<&'static str> {
+		Some(
+			r#"
+			(call function: (identifier) @callee)
+			(call function: (attribute attribute: (identifier) @callee))
+			"#,
+		)
+	}
+
 	fn get_file_extensions(&self) -> Vec<&'static str> {
 		vec!["py"]
 	}
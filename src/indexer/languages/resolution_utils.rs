@@ -233,6 +233,13 @@ pub fn detect_language_from_path(file_path: &str) -> Option<String> {
 		"css" | "scss" | "sass" => Some("css".to_string()),
 		"md" | "markdown" => Some("markdown".to_string()),
 		"svelte" => Some("svelte".to_string()),
+		"tf" | "tfvars" | "hcl" => Some("hcl".to_string()),
+		"sql" => Some("sql".to_string()),
+		"zig" => Some("zig".to_string()),
+		"proto" => Some("proto".to_string()),
+		"hs" => Some("haskell".to_string()),
+		"lua" => Some("lua".to_string()),
+		"dart" => Some("dart".to_string()),
 		_ => None,
 	}
 }
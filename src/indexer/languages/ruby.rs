@@ -169,6 +169,14 @@ impl Language for Ruby {
 		}
 	}
 
+	fn call_query(&self) -> Option<&'static str> {
+		Some(
+			r#"
+			(call method: (identifier) @callee)
+			"#,
+		)
+	}
+
 	fn get_file_extensions(&self) -> Vec<&'static str> {
 		vec!["rb"]
 	}
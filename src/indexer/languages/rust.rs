@@ -228,6 +228,19 @@ impl Language for Rust {
 		}
 	}
 
+	fn call_query(&self) -> Option<&'static str> {
+		Some(
+			r#"
+			(call_expression function: (identifier) @callee)
+			(call_expression function: (field_expression field: (field_identifier) @callee))
+			(call_expression function: (scoped_identifier name: (identifier) @callee))
+			(call_expression function: (generic_function function: (identifier) @callee))
+			(call_expression function: (generic_function function: (field_expression field: (field_identifier) @callee)))
+			(call_expression function: (generic_function function: (scoped_identifier name: (identifier) @callee)))
+			"#,
+		)
+	}
+
 	fn get_file_extensions(&self) -> Vec<&'static str> {
 		vec!["rs"]
 	}
@@ -0,0 +1,197 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Snapshot tests for code chunking
+//!
+//! Every file in `tests/fixtures/languages` is chunked exactly as indexing
+//! does it, and the resulting blocks (line range, node kind, symbols) are
+//! compared with the `.snap` file next to it. A grammar upgrade or a change
+//! to a language's meaningful kinds then shows up as a snapshot diff.
+//!
+//! Snapshots are committed; a missing or outdated one fails the test. To
+//! record a new fixture's snapshot or accept an intended change, rerun with
+//! `OCTOCODE_UPDATE_SNAPSHOTS=1` and review the `.snap` files like any other
+//! diff.
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use tree_sitter::Parser;
+
+use crate::indexer::code_region_extractor::extract_meaningful_regions;
+use crate::indexer::file_utils::FileUtils;
+use crate::indexer::languages::get_language;
+
+/// Languages chunked with tree-sitter; each needs at least one fixture.
+/// Markdown is chunked by headings in the markdown processor instead.
+const SNAPSHOT_LANGUAGES: &[&str] = &[
+	"rust",
+	"javascript",
+	"typescript",
+	"python",
+	"go",
+	"cpp",
+	"php",
+	"bash",
+	"ruby",
+	"json",
+	"svelte",
+	"css",
+	"hcl",
+	"sql",
+	"zig",
+	"proto",
+	"haskell",
+	"lua",
+	"dart",
+];
+
+const UPDATE_ENV: &str = "OCTOCODE_UPDATE_SNAPSHOTS";
+
+fn fixtures_dir() -> PathBuf {
+	Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/languages")
+}
+
+/// Fixture files with the language indexing would give them
+fn fixtures() -> Vec<(PathBuf, &'static str)> {
+	let mut fixtures: Vec<(PathBuf, &'static str)> = std::fs::read_dir(fixtures_dir())
+		.expect("fixtures directory should exist")
+		.filter_map(|entry| entry.ok().map(|entry| entry.path()))
+		.filter(|path| path.extension().is_some_and(|ext| ext != "snap"))
+		.filter_map(|path| {
+			let language = FileUtils::detect_language(&path)?;
+			Some((path, language))
+		})
+		.collect();
+	fixtures.sort();
+	fixtures
+}
+
+/// Blocks of a file, one line each: `L<start>-L<end> <node kind> [symbols]`
+pub fn render_snapshot(language: &str, contents: &str) -> String {
+	let lang_impl = get_language(language).expect("fixture language should be supported");
+	let mut parser = Parser::new();
+	parser
+		.set_language(&lang_impl.get_ts_language())
+		.expect("grammar should load");
+	let tree = parser.parse(contents, None).expect("fixture should parse");
+
+	let mut regions = Vec::new();
+	extract_meaningful_regions(tree.root_node(), contents, lang_impl.as_ref(), &mut regions);
+
+	let mut snapshot = format!("# {} blocks: {}\n", language, regions.len());
+	for region in &regions {
+		let _ = writeln!(
+			snapshot,
+			"L{}-L{} {} [{}]",
+			region.start_line + 1,
+			region.end_line + 1,
+			region.node_kind,
+			region.symbols.join(", ")
+		);
+	}
+	snapshot
+}
+
+/// First differing line between two snapshots, for the failure message
+fn first_difference(expected: &str, actual: &str) -> String {
+	let mut expected_lines = expected.lines();
+	let mut actual_lines = actual.lines();
+	for line in 1.. {
+		match (expected_lines.next(), actual_lines.next()) {
+			(Some(e), Some(a)) if e == a => continue,
+			(None, None) => break,
+			(e, a) => {
+				return format!(
+					"line {}:\n    expected: {}\n    actual:   {}",
+					line,
+					e.unwrap_or("<end of snapshot>"),
+					a.unwrap_or("<end of snapshot>")
+				)
+			}
+		}
+	}
+	"whitespace differs".to_string()
+}
+
+/// Compare `actual` with the stored snapshot, recording or updating it when allowed
+fn check_snapshot(snapshot_path: &Path, actual: &str) -> Result<(), String> {
+	let update = std::env::var(UPDATE_ENV).is_ok_and(|value| value == "1");
+	let name = snapshot_path.display();
+
+	match std::fs::read_to_string(snapshot_path) {
+		Ok(expected) if expected == actual => Ok(()),
+		Ok(expected) if !update => Err(format!(
+			"{} is out of date, {}",
+			name,
+			first_difference(&expected, actual)
+		)),
+		Err(_) if !update => Err(format!("{} is missing; record it and commit it", name)),
+		_ => {
+			std::fs::write(snapshot_path, actual).map_err(|e| format!("{}: {}", name, e))?;
+			eprintln!("Recorded snapshot {}", name);
+			Ok(())
+		}
+	}
+}
+
+#[test]
+fn test_language_chunking_snapshots() {
+	let failures: Vec<String> = fixtures()
+		.into_iter()
+		.filter_map(|(path, language)| {
+			let contents = std::fs::read_to_string(&path).expect("fixture should be readable");
+			let mut snapshot_path = path.into_os_string();
+			snapshot_path.push(".snap");
+			check_snapshot(
+				Path::new(&snapshot_path),
+				&render_snapshot(language, &contents),
+			)
+			.err()
+		})
+		.collect();
+
+	assert!(
+		failures.is_empty(),
+		"{} snapshot(s) differ; if the change is intended, rerun with {}=1 and review the diff:\n{}",
+		failures.len(),
+		UPDATE_ENV,
+		failures.join("\n")
+	);
+}
+
+#[test]
+fn test_every_language_has_a_fixture() {
+	let covered: Vec<&str> = fixtures().iter().map(|(_, language)| *language).collect();
+	let missing: Vec<&&str> = SNAPSHOT_LANGUAGES
+		.iter()
+		.filter(|language| !covered.contains(*language))
+		.collect();
+	assert!(
+		missing.is_empty(),
+		"languages without a fixture in tests/fixtures/languages: {:?}",
+		missing
+	);
+}
+
+#[test]
+fn test_first_difference() {
+	assert_eq!(
+		first_difference("a\nb\n", "a\nc\n"),
+		"line 2:\n    expected: b\n    actual:   c"
+	);
+	assert_eq!(
+		first_difference("a\n", "a\nb\n"),
+		"line 2:\n    expected: <end of snapshot>\n    actual:   b"
+	);
+}
@@ -0,0 +1,202 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! SQL language implementation for the indexer
+
+use crate::indexer::languages::Language;
+use tree_sitter::Node;
+
+pub struct Sql {}
+
+/// SQL dialect recognized from dialect-specific syntax
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SqlDialect {
+	PostgreSql,
+	MySql,
+	Sqlite,
+	SqlServer,
+}
+
+impl SqlDialect {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::PostgreSql => "postgresql",
+			Self::MySql => "mysql",
+			Self::Sqlite => "sqlite",
+			Self::SqlServer => "sqlserver",
+		}
+	}
+}
+
+impl Language for Sql {
+	fn name(&self) -> &'static str {
+		"sql"
+	}
+
+	fn get_ts_language(&self) -> tree_sitter::Language {
+		// One grammar covers the common dialects
+		tree_sitter_sequel::LANGUAGE.into()
+	}
+
+	fn get_meaningful_kinds(&self) -> Vec<&'static str> {
+		vec![
+			"create_table",
+			"create_view",
+			"create_materialized_view",
+			"create_function",
+			"create_index",
+			"create_trigger",
+			"create_type",
+			"alter_table",
+		]
+	}
+
+	fn extract_symbols(&self, node: Node, contents: &str) -> Vec<String> {
+		let mut symbols = Vec::new();
+
+		// Tables, views and functions the statement defines or references,
+		// plus the columns it declares
+		self.extract_identifiers(node, contents, &mut symbols);
+
+		// Dialect-specific syntax tells which database the schema targets
+		if let Some(dialect) = node
+			.utf8_text(contents.as_bytes())
+			.ok()
+			.and_then(detect_dialect)
+		{
+			symbols.push(dialect.as_str().to_string());
+		}
+
+		// Deduplicate symbols before returning
+		symbols.sort();
+		symbols.dedup();
+
+		symbols
+	}
+
+	fn extract_identifiers(&self, node: Node, contents: &str, symbols: &mut Vec<String>) {
+		match node.kind() {
+			"object_reference" => {
+				if let Ok(text) = node.utf8_text(contents.as_bytes()) {
+					let parts: Vec<&str> = text.split('.').map(unquote).collect();
+					// `public.users` is found as both `public.users` and `users`
+					if parts.len() > 1 {
+						symbols.push(parts.join("."));
+					}
+					if let Some(name) = parts.last().filter(|name| !name.is_empty()) {
+						symbols.push(name.to_string());
+					}
+				}
+				return;
+			}
+			"column_definition" => {
+				let name = node.child_by_field_name("name").or_else(|| {
+					node.children(&mut node.walk())
+						.find(|child| child.kind() == "identifier")
+				});
+				if let Some(Ok(name)) = name.map(|name| name.utf8_text(contents.as_bytes())) {
+					let name = unquote(name);
+					if !name.is_empty() {
+						symbols.push(name.to_string());
+					}
+				}
+			}
+			_ => {}
+		}
+
+		// Continue with recursive traversal
+		let mut cursor = node.walk();
+		if cursor.goto_first_child() {
+			loop {
+				self.extract_identifiers(cursor.node(), contents, symbols);
+				if !cursor.goto_next_sibling() {
+					break;
+				}
+			}
+		}
+	}
+
+	fn get_node_type_description(&self, node_type: &str) -> &'static str {
+		match node_type {
+			"create_table" | "alter_table" => "table definitions",
+			"create_view" | "create_materialized_view" => "view definitions",
+			"create_function" | "create_trigger" => "function declarations",
+			"create_index" => "index definitions",
+			"create_type" => "type declarations",
+			_ => "declarations",
+		}
+	}
+
+	fn resolve_import(
+		&self,
+		_import_path: &str,
+		_source_file: &str,
+		_all_files: &[String],
+	) -> Option<String> {
+		// SQL files don't import each other
+		None
+	}
+
+	fn get_file_extensions(&self) -> Vec<&'static str> {
+		vec!["sql"]
+	}
+}
+
+/// Identifier without its `"`, `` ` `` or `[]` quoting
+fn unquote(identifier: &str) -> &str {
+	identifier
+		.trim()
+		.trim_matches(|c| c == '"' || c == '`' || c == '[' || c == ']')
+}
+
+/// Dialect of a statement, when it uses syntax only one dialect accepts.
+/// Portable SQL yields `None`.
+pub fn detect_dialect(sql: &str) -> Option<SqlDialect> {
+	let upper = sql.to_uppercase();
+	let words: Vec<&str> = upper
+		.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+		.filter(|word| !word.is_empty())
+		.collect();
+	let has_word = |candidates: &[&str]| words.iter().any(|word| candidates.contains(word));
+
+	if upper.contains("$$")
+		|| upper.contains("::")
+		|| has_word(&[
+			"SERIAL",
+			"BIGSERIAL",
+			"SMALLSERIAL",
+			"PLPGSQL",
+			"JSONB",
+			"BYTEA",
+			"TIMESTAMPTZ",
+		]) {
+		Some(SqlDialect::PostgreSql)
+	} else if upper.contains('`')
+		|| has_word(&[
+			"AUTO_INCREMENT",
+			"ENGINE",
+			"UNSIGNED",
+			"TINYINT",
+			"MEDIUMTEXT",
+			"LONGTEXT",
+		]) {
+		Some(SqlDialect::MySql)
+	} else if has_word(&["AUTOINCREMENT", "ROWID"]) {
+		Some(SqlDialect::Sqlite)
+	} else if has_word(&["NVARCHAR", "GETDATE", "DBO", "UNIQUEIDENTIFIER"]) {
+		Some(SqlDialect::SqlServer)
+	} else {
+		None
+	}
+}
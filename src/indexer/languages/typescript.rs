@@ -246,6 +246,15 @@ impl Language for TypeScript {
 		None
 	}
 
+	fn call_query(&self) -> Option<&'static str> {
+		Some(
+			r#"
+			(call_expression function: (identifier) @callee)
+			(call_expression function: (member_expression property: (property_identifier) @callee))
+			"#,
+		)
+	}
+
 	fn get_file_extensions(&self) -> Vec<&'static str> {
 		vec!["ts", "tsx"]
 	}
@@ -0,0 +1,234 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Zig language implementation for the indexer
+
+use crate::indexer::languages::Language;
+use tree_sitter::Node;
+
+/// Type expressions that open a container: `const Point = struct { ... };`
+const CONTAINER_KINDS: &[&str] = &[
+	"struct_declaration",
+	"enum_declaration",
+	"union_declaration",
+	"opaque_declaration",
+	"error_set_declaration",
+];
+
+pub struct Zig {}
+
+impl Language for Zig {
+	fn name(&self) -> &'static str {
+		"zig"
+	}
+
+	fn get_ts_language(&self) -> tree_sitter::Language {
+		tree_sitter_zig::LANGUAGE.into()
+	}
+
+	fn get_meaningful_kinds(&self) -> Vec<&'static str> {
+		// Structs, enums and unions are `const` declarations in Zig, so a
+		// container is indexed together with its fields and methods
+		vec![
+			"function_declaration",
+			"variable_declaration",
+			"test_declaration",
+			"comptime_declaration",
+		]
+	}
+
+	fn extract_symbols(&self, node: Node, contents: &str) -> Vec<String> {
+		let mut symbols = Vec::new();
+
+		match node.kind() {
+			"function_declaration" | "variable_declaration" => {
+				if let Some(name) = Self::declaration_name(node, contents) {
+					symbols.push(name);
+				}
+				// Members of a container are found by their own names too
+				if let Some(container) = Self::container(node) {
+					symbols.extend(Self::member_names(container, contents));
+				}
+			}
+			"test_declaration" => {
+				// `test "name" { }` or a decltest `test add { }`
+				for child in node.children(&mut node.walk()) {
+					if matches!(child.kind(), "string" | "identifier") {
+						if let Ok(name) = child.utf8_text(contents.as_bytes()) {
+							symbols.push(format!("test {}", name.trim_matches('"')));
+						}
+						break;
+					}
+				}
+			}
+			_ => self.extract_identifiers(node, contents, &mut symbols),
+		}
+
+		// Deduplicate symbols before returning
+		symbols.sort();
+		symbols.dedup();
+
+		symbols
+	}
+
+	fn extract_identifiers(&self, node: Node, contents: &str, symbols: &mut Vec<String>) {
+		if node.kind() == "identifier" {
+			if let Ok(text) = node.utf8_text(contents.as_bytes()) {
+				let text = text.trim();
+				// Skip the discard identifier and duplicates
+				if !text.is_empty() && text != "_" && !symbols.contains(&text.to_string()) {
+					symbols.push(text.to_string());
+				}
+			}
+		}
+
+		// Continue with recursive traversal
+		let mut cursor = node.walk();
+		if cursor.goto_first_child() {
+			loop {
+				self.extract_identifiers(cursor.node(), contents, symbols);
+				if !cursor.goto_next_sibling() {
+					break;
+				}
+			}
+		}
+	}
+
+	fn extract_imports_exports(&self, node: Node, contents: &str) -> (Vec<String>, Vec<String>) {
+		let mut imports = Vec::new();
+		let mut exports = Vec::new();
+
+		match node.kind() {
+			// Files are imported with `@import("path.zig")`; package imports
+			// such as `@import("std")` do not name a file of the project
+			"builtin_function" => {
+				if let Some(path) = Self::import_path(node, contents) {
+					if path.ends_with(".zig") {
+						imports.push(path);
+					}
+				}
+			}
+			"function_declaration" | "variable_declaration" if Self::is_public(node) => {
+				exports.extend(Self::declaration_name(node, contents));
+			}
+			_ => {}
+		}
+
+		(imports, exports)
+	}
+
+	fn get_node_type_description(&self, node_type: &str) -> &'static str {
+		match node_type {
+			"function_declaration" => "function declarations",
+			"variable_declaration" => "constant and container declarations",
+			"test_declaration" => "tests",
+			"comptime_declaration" => "comptime blocks",
+			"comment" => "comments",
+			_ => "declarations",
+		}
+	}
+
+	fn call_query(&self) -> Option<&'static str> {
+		Some(
+			r#"
+			(call_expression function: (identifier) @callee)
+			(call_expression function: (field_expression member: (identifier) @callee))
+			"#,
+		)
+	}
+
+	fn resolve_import(
+		&self,
+		import_path: &str,
+		source_file: &str,
+		all_files: &[String],
+	) -> Option<String> {
+		use super::resolution_utils::{resolve_relative_path, FileRegistry};
+
+		// Import paths are relative to the importing file
+		let registry = FileRegistry::new(all_files);
+		let target = resolve_relative_path(source_file, import_path)?;
+		registry.find_exact_file(&target.to_string_lossy())
+	}
+
+	fn get_file_extensions(&self) -> Vec<&'static str> {
+		vec!["zig"]
+	}
+}
+
+impl Zig {
+	/// Name of a function or of the `const`/`var` being declared
+	fn declaration_name(node: Node, contents: &str) -> Option<String> {
+		node.child_by_field_name("name")
+			.or_else(|| {
+				node.children(&mut node.walk())
+					.find(|child| child.kind() == "identifier")
+			})
+			.and_then(|child| child.utf8_text(contents.as_bytes()).ok())
+			.map(String::from)
+	}
+
+	/// `pub` declarations are visible to importing files
+	fn is_public(node: Node) -> bool {
+		node.children(&mut node.walk())
+			.take_while(|child| child.kind() != "identifier")
+			.any(|child| child.kind() == "pub")
+			|| node
+				.prev_sibling()
+				.is_some_and(|sibling| sibling.kind() == "pub")
+	}
+
+	/// Container a `const` declaration defines, if any
+	fn container(node: Node) -> Option<Node> {
+		node.children(&mut node.walk())
+			.find(|child| CONTAINER_KINDS.contains(&child.kind()))
+	}
+
+	/// Fields, error names and declarations directly inside a container
+	fn member_names(container: Node, contents: &str) -> Vec<String> {
+		let mut names = Vec::new();
+		for child in container.children(&mut container.walk()) {
+			let name = match child.kind() {
+				"container_field" | "function_declaration" | "variable_declaration" => {
+					Self::declaration_name(child, contents)
+				}
+				// Members of `error{ NotFound, AccessDenied }`
+				"identifier" if container.kind() == "error_set_declaration" => {
+					child.utf8_text(contents.as_bytes()).ok().map(String::from)
+				}
+				_ => None,
+			};
+			names.extend(name);
+		}
+		names
+	}
+
+	/// Argument of `@import("...")`, without quotes
+	fn import_path(node: Node, contents: &str) -> Option<String> {
+		let builtin = node
+			.children(&mut node.walk())
+			.find(|child| child.kind() == "builtin_identifier")?;
+		if builtin.utf8_text(contents.as_bytes()).ok()? != "@import" {
+			return None;
+		}
+		let arguments = node
+			.children(&mut node.walk())
+			.find(|child| child.kind() == "arguments")?;
+		let path = arguments
+			.children(&mut arguments.walk())
+			.find(|child| child.kind() == "string")?;
+		let text = path.utf8_text(contents.as_bytes()).ok()?;
+		Some(text.trim_matches('"').to_string())
+	}
+}
@@ -446,6 +446,7 @@ pub fn parse_markdown_content(
 				start_line: chunk.start_line,
 				end_line: chunk.end_line,
 				hash: content_hash,
+				provenance: None,
 				distance: None,
 			}
 		})
@@ -15,16 +15,34 @@
 // Indexer module for Octocode
 // Handles code indexing, embedding, and search functionality
 
+pub mod api_diff; // Breaking public API changes between revisions
 pub mod batch_processor; // Batch processing utilities for embedding operations
+pub mod blame; // Last commit, author and age of each block from git blame
+pub mod checkpoint; // Resuming interrupted index runs
+pub mod chunk_processors; // Redaction and other transformations applied before embedding
 pub mod code_region_extractor; // Code region extraction and smart merging utilities
+pub mod deterministic; // Reproducible indexing: sorted walks, pinned chunking and timestamps
 pub mod differential_processor; // Differential processing utilities for incremental updates
+pub mod dry_run; // Planning an indexing run without writing or embedding
+pub mod extra_dirs; // Ignored directories indexed on demand by watch --include-extra
+pub mod file_policy; // Size, binary and extension limits on indexed files
 pub mod file_processor; // File processing utilities for text and markdown files
+pub mod file_summary; // Overview blocks of long code files
 pub mod graph_optimization;
 pub mod graphrag; // GraphRAG generation for code relationships (modular implementation)
 pub mod languages; // Language-specific processors
 pub mod markdown_processor; // Markdown document processing utilities
+pub mod path_aliases; // Rename aliases for paths referenced before a move
+pub mod progress_file; // Machine-readable progress of index runs for editors and CI
+pub mod query_expansion; // LLM rewriting of short search queries
+pub mod refine; // Refining cached results of the last search
+pub mod roots; // Several root directories indexed into one workspace store
 pub mod search; // Search functionality // Task-focused graph extraction and optimization
+pub mod secret_scanner; // Credential detection and redaction for indexed content
 pub mod signature_extractor; // Code signature extraction utilities
+pub mod similar; // Code similar to an existing block
+pub mod test_classifier; // Test file and inline test code detection
+pub mod write_behind; // Flush policy and write-behind queue for indexing writes
 
 pub mod render_utils;
 pub use batch_processor::*;
@@ -39,12 +57,13 @@ pub use search::*;
 pub use signature_extractor::*;
 
 use crate::config::Config;
+use crate::embedding::policy::PathGuard;
 use crate::mcp::logging::{log_file_processing_error, log_indexing_progress};
 use crate::state;
 use crate::state::SharedState;
 #[cfg(test)]
 use crate::store::DocumentBlock;
-use crate::store::Store;
+use crate::store::{CodeBlock, Store};
 pub use render_utils::*;
 
 // Import the new modular utilities
@@ -53,12 +72,15 @@ pub mod git_utils;
 mod path_utils;
 mod text_processing;
 
+#[cfg(test)]
+mod fuzz_tests; // Property tests for chunking adversarial input
+
+use self::checkpoint::BatchKind;
 use self::file_utils::FileUtils;
 
 // Re-export for external use
 pub use self::git_utils::GitUtils;
 pub use self::path_utils::PathUtils;
-use std::fs;
 // We're using ignore::WalkBuilder instead of walkdir::WalkDir
 use anyhow::Result;
 use ignore;
@@ -90,6 +112,19 @@ impl NoindexWalker {
 		builder
 	}
 
+	/// Like [`create_walker`](Self::create_walker), walking only `roots`
+	/// (directories relative to `current_dir`) when there are any
+	pub fn create_roots_walker(current_dir: &Path, roots: &[String]) -> ignore::WalkBuilder {
+		let Some((first, rest)) = roots.split_first() else {
+			return Self::create_walker(current_dir);
+		};
+		let mut builder = Self::create_walker(&current_dir.join(first));
+		for root in rest {
+			builder.add(current_dir.join(root));
+		}
+		builder
+	}
+
 	/// Creates a GitignoreBuilder for checking individual files against both .gitignore and .noindex
 	/// ENHANCED: Better error handling and debugging
 	pub fn create_matcher(current_dir: &Path, quiet: bool) -> Result<ignore::gitignore::Gitignore> {
@@ -148,6 +183,14 @@ pub mod git {
 		GitUtils::get_changed_files_since_commit(repo_path, since_commit)
 	}
 
+	/// Get files renamed between a commit and HEAD as `(old path, new path)` pairs
+	pub fn get_renamed_files_since_commit(
+		repo_path: &Path,
+		since_commit: &str,
+	) -> Result<Vec<(String, String)>> {
+		GitUtils::get_renamed_files_since_commit(repo_path, since_commit)
+	}
+
 	/// Get all working directory changes (staged + unstaged + untracked)
 	/// Note: This is used for non-git optimization scenarios only
 	pub fn get_all_changed_files(repo_path: &Path) -> Result<Vec<String>> {
@@ -180,6 +223,8 @@ pub fn detect_language(path: &std::path::Path) -> Option<&str> {
 async fn cleanup_deleted_files_optimized(
 	store: &Store,
 	current_dir: &std::path::Path,
+	roots: &[String],
+	extra: &[String],
 	quiet: bool,
 ) -> Result<()> {
 	// Get all indexed file paths from the database
@@ -206,10 +251,13 @@ async fn cleanup_deleted_files_optimized(
 			// Always treat indexed paths as relative to current directory
 			let absolute_path = current_dir.join(indexed_file);
 
-			// Check if file was deleted
-			if !absolute_path.exists() {
+			// Check if file was deleted, or its root dropped from index.roots
+			if !absolute_path.exists()
+				|| !(roots::contains(roots, indexed_file)
+					|| extra_dirs::contains(extra, indexed_file))
+			{
 				files_to_remove.push(indexed_file.clone());
-			} else {
+			} else if !extra_dirs::contains(extra, indexed_file) {
 				// Check if file is now ignored by .noindex or .gitignore patterns
 				let is_ignored = ignore_matcher
 					.matched(&absolute_path, absolute_path.is_dir())
@@ -256,21 +304,56 @@ async fn cleanup_deleted_files_optimized(
 	Ok(())
 }
 
-/// Helper function to perform intelligent flushing based on configuration
-/// Returns true if a flush was performed
-async fn flush_if_needed(
+/// Remove files indexed from `--include-extra` directories that the current run
+/// no longer includes, unless the ignore rules would index them anyway
+async fn cleanup_dropped_extra_dirs(
 	store: &Store,
-	batches_processed: &mut usize,
-	config: &Config,
-	force: bool,
-) -> Result<bool> {
-	if force || *batches_processed >= config.index.flush_frequency {
+	current_dir: &std::path::Path,
+	extra: &[String],
+	quiet: bool,
+) -> Result<()> {
+	let previous = extra_dirs::load(current_dir);
+	if previous == extra {
+		return Ok(());
+	}
+	let dropped: Vec<String> = previous
+		.into_iter()
+		.filter(|dir| !extra.contains(dir))
+		.collect();
+
+	if !dropped.is_empty() {
+		let ignore_matcher = NoindexWalker::create_matcher(current_dir, quiet)?;
+		let mut removed = 0;
+		for indexed_file in store.get_all_indexed_file_paths().await? {
+			if !extra_dirs::contains(&dropped, &indexed_file)
+				|| extra_dirs::contains(extra, &indexed_file)
+			{
+				continue;
+			}
+			// The walker skips hidden directories as well as ignored paths
+			let hidden = indexed_file.split('/').any(|part| part.starts_with('.'));
+			let ignored = ignore_matcher
+				.matched_path_or_any_parents(current_dir.join(&indexed_file), false)
+				.is_ignore();
+			if !hidden && !ignored {
+				continue;
+			}
+			store.remove_blocks_by_path(&indexed_file).await?;
+			// Index the file again should its directory be included later
+			store.store_file_metadata(&indexed_file, 0).await?;
+			removed += 1;
+		}
 		store.flush().await?;
-		*batches_processed = 0; // Reset counter
-		Ok(true)
-	} else {
-		Ok(false)
+		if !quiet && removed > 0 {
+			println!(
+				"🧹 Removed {} files of no longer included directories: {}",
+				removed,
+				dropped.join(", ")
+			);
+		}
 	}
+
+	extra_dirs::save(current_dir, extra)
 }
 
 /// Render signatures and search results as markdown output (more efficient for AI tools)
@@ -292,16 +375,49 @@ pub async fn index_files_with_quiet(
 	git_repo_root: Option<&Path>,
 	quiet: bool,
 ) -> Result<()> {
+	let progress = if config.index.progress_file {
+		let current_dir = state.read().current_directory.clone();
+		progress_file::ProgressFile::start(state.clone(), &current_dir)
+	} else {
+		None
+	};
+	let result = run_index(store, state, config, git_repo_root, quiet).await;
+	if let Some(progress) = progress {
+		progress.finish(result.as_ref().err()).await;
+	}
+	result
+}
+
+/// Record the phase an index run entered, for the progress file
+fn enter_phase(state: &SharedState, phase: &str) {
+	let mut state_guard = state.write();
+	state_guard.phase = phase.to_string();
+	state_guard.current_file = None;
+}
+
+async fn run_index(
+	store: &Store,
+	state: SharedState,
+	config: &Config,
+	git_repo_root: Option<&Path>,
+	quiet: bool,
+) -> Result<()> {
+	let config = deterministic::effective_config(config);
+	let config = config.as_ref();
+	chunk_processors::check_redaction_policy(config)?;
+	let path_guard = PathGuard::from_config(config)?;
+
 	let current_dir = state.read().current_directory.clone();
+	let roots = roots::resolve(&current_dir, &config.index.roots)?;
 	let mut code_blocks_batch = Vec::new();
 	let mut text_blocks_batch = Vec::new();
 	let mut document_blocks_batch = Vec::new();
 	let mut all_code_blocks = Vec::new(); // Store all code blocks for GraphRAG
 
 	let mut embedding_calls = 0;
-	let mut batches_processed = 0; // Track batches for intelligent flushing
 
 	// Log indexing start
+	enter_phase(&state, "indexing_start");
 	log_indexing_progress(
 		"indexing_start",
 		0,
@@ -323,6 +439,26 @@ pub async fn index_files_with_quiet(
 	// Get force_reindex flag from state
 	let force_reindex = state.read().force_reindex;
 
+	// Directories from `watch --include-extra`; ones dropped since the last run are cleaned up
+	let extra = state.read().extra_dirs.clone();
+	if let Err(e) = cleanup_dropped_extra_dirs(store, &current_dir, &extra, quiet).await {
+		if !quiet {
+			eprintln!("Warning: Cleanup of extra directories failed: {}", e);
+		}
+	}
+
+	// Files written by an interrupted run for the same commit are not redone
+	let run_commit = git_repo_root
+		.and_then(|git_root| git::get_current_commit_hash(git_root).ok())
+		.unwrap_or_default();
+	let mut checkpoint = checkpoint::Checkpoint::load(store, &run_commit, force_reindex).await?;
+	if checkpoint.resumed() > 0 && !quiet {
+		println!(
+			"⏯️  Resuming interrupted indexing: {} files already indexed",
+			checkpoint.resumed()
+		);
+	}
+
 	// Git-based optimization: Get changed files if we have a git repository
 	let git_changed_files = if let Some(git_root) = git_repo_root {
 		if !force_reindex {
@@ -333,7 +469,8 @@ pub async fn index_files_with_quiet(
 					if last_commit != current_commit {
 						// Commit hash changed - get files changed since last indexed commit
 						match git::get_changed_files_since_commit(git_root, &last_commit) {
-							Ok(changed_files) => {
+							Ok(mut changed_files) => {
+								changed_files.retain(|path| !checkpoint.is_done(path));
 								if !quiet {
 									println!(
 										"🚀 Git optimization: Commit changed, found {} files to reindex",
@@ -341,8 +478,32 @@ pub async fn index_files_with_quiet(
 									);
 								}
 
+								// Remember renames so references to old paths still resolve,
+								// and drop the blocks indexed under the old paths
+								let renames =
+									git::get_renamed_files_since_commit(git_root, &last_commit)
+										.unwrap_or_default();
+								if !renames.is_empty() {
+									let mut aliases = path_aliases::PathAliases::load(&current_dir);
+									for (old_path, new_path) in &renames {
+										aliases.record(old_path, new_path);
+									}
+									if let Err(e) = aliases.save(&current_dir) {
+										if !quiet {
+											eprintln!(
+												"Warning: Failed to save path aliases: {}",
+												e
+											);
+										}
+									}
+								}
+
 								// Clean up existing data for changed files (includes GraphRAG cleanup)
-								for file_path in &changed_files {
+								for file_path in renames
+									.iter()
+									.map(|(old_path, _)| old_path)
+									.chain(&changed_files)
+								{
 									if let Err(e) = store.remove_blocks_by_path(file_path).await {
 										if !quiet {
 											eprintln!(
@@ -369,6 +530,9 @@ pub async fn index_files_with_quiet(
 								None
 							}
 						}
+					} else if !extra.is_empty() {
+						// Same commit hash - only the extra directories may have changed
+						Some(std::collections::HashSet::new())
 					} else {
 						// Same commit hash - skip indexing entirely (ignore unstaged changes)
 						if !quiet {
@@ -383,6 +547,7 @@ pub async fn index_files_with_quiet(
 								if !quiet {
 									println!("🔗 Building GraphRAG from existing database...");
 								}
+								enter_phase(&state, "graphrag_build");
 								log_indexing_progress("graphrag_build", 0, 0, None, 0);
 								let graph_builder =
 									graphrag::GraphBuilder::new_with_quiet(config.clone(), quiet)
@@ -393,6 +558,7 @@ pub async fn index_files_with_quiet(
 							}
 						}
 
+						deterministic::record_manifest_hash(store, config).await?;
 						{
 							let mut state_guard = state.write();
 							state_guard.indexing_complete = true;
@@ -435,10 +601,13 @@ pub async fn index_files_with_quiet(
 		}
 
 		// Log cleanup phase start
+		enter_phase(&state, "cleanup");
 		log_indexing_progress("cleanup", 0, 0, None, 0);
 
 		// Optimized cleanup: Get indexed files and check them efficiently
-		if let Err(e) = cleanup_deleted_files_optimized(store, &current_dir, quiet).await {
+		if let Err(e) =
+			cleanup_deleted_files_optimized(store, &current_dir, &roots, &extra, quiet).await
+		{
 			if !quiet {
 				eprintln!("Warning: Cleanup failed: {}", e);
 			}
@@ -475,16 +644,41 @@ pub async fn index_files_with_quiet(
 
 	// Single pass: progressive counting + processing combined
 	// Use NoindexWalker to respect both .gitignore and .noindex files
-	let walker = NoindexWalker::create_walker(&current_dir).build();
+	let file_policy = file_policy::FilePolicy::new(&config.index);
+
+	let mut walker = NoindexWalker::create_roots_walker(&current_dir, &roots);
+	if config.index.deterministic {
+		// Directory listing order differs between filesystems
+		walker.sort_by_file_name(|a, b| a.cmp(b));
+	}
+	let walker = walker.build();
+
+	// Extra directories are walked without ignore rules and indexed by modification time
+	let extra_walkers = extra.iter().map(|dir| {
+		let mut builder = ignore::WalkBuilder::new(current_dir.join(dir));
+		builder.standard_filters(false).hidden(true);
+		if config.index.deterministic {
+			builder.sort_by_file_name(|a, b| a.cmp(b));
+		}
+		builder.build()
+	});
+	let mut extra_files_seen = std::collections::HashSet::new();
+
+	// Embedded batches are written directly or through the write-behind queue
+	let mut writer = write_behind::BlockWriter::new(store, &config.index);
 
 	// Progressive counting variables
 	let mut total_files_found = 0;
 	let mut files_processed = 0;
 
 	// Log file processing phase start
+	enter_phase(&state, "file_processing");
 	log_indexing_progress("file_processing", 0, 0, None, 0);
 
-	for result in walker {
+	for (result, from_extra) in walker
+		.map(|result| (result, false))
+		.chain(extra_walkers.flat_map(|walker| walker.map(|result| (result, true))))
+	{
 		let entry = match result {
 			Ok(entry) => entry,
 			Err(_) => continue,
@@ -498,10 +692,15 @@ pub async fn index_files_with_quiet(
 		// Create relative path from the current directory using our utility
 		let file_path = path_utils::PathUtils::to_relative_string(entry.path(), &current_dir);
 
+		// Files of an extra directory the ignore rules don't exclude are walked twice
+		if extra_dirs::contains(&extra, &file_path) && !extra_files_seen.insert(file_path.clone()) {
+			continue;
+		}
+
 		// Check if this file would be indexed (for progressive counting)
 		let is_indexable = if let Some(ref changed_files) = git_changed_files {
 			// Git optimization: only count changed files that are indexable
-			changed_files.contains(&file_path)
+			(from_extra || changed_files.contains(&file_path))
 				&& (detect_language(entry.path()).is_some()
 					|| is_allowed_text_extension(entry.path()))
 		} else {
@@ -525,12 +724,21 @@ pub async fn index_files_with_quiet(
 
 		// GIT OPTIMIZATION: Skip files not in the changed set (if git optimization is active)
 		if let Some(ref changed_files) = git_changed_files {
-			if !changed_files.contains(&file_path) {
+			if !from_extra && !changed_files.contains(&file_path) {
 				// File not in git changes, skip processing entirely
 				continue;
 			}
 		}
 
+		// Size and extension limits, counted only for files indexing would process
+		if detect_language(entry.path()).is_some() || is_allowed_text_extension(entry.path()) {
+			let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+			if let Some(reason) = file_policy.check_path(entry.path(), size) {
+				state.write().file_policy_skips.record(reason);
+				continue;
+			}
+		}
+
 		// PERFORMANCE OPTIMIZATION: Fast file modification time check using preloaded metadata
 		// This replaces individual database queries with HashMap lookup
 		let force_reindex = state.read().force_reindex;
@@ -550,11 +758,18 @@ pub async fn index_files_with_quiet(
 			}
 		}
 
+		if is_indexable {
+			state.write().current_file = Some(file_path.clone());
+		}
+
 		if let Some(language) = detect_language(entry.path()) {
-			match fs::read_to_string(entry.path()) {
-				Ok(contents) => {
-					// Store the file modification time after successful processing
-					let file_processed;
+			match file_policy.read(entry.path()) {
+				Ok(Err(reason)) => {
+					state.write().file_policy_skips.record(reason);
+				}
+				Ok(Ok(contents)) => {
+					// The batch the file's blocks go to
+					let batch_kind;
 
 					if language == "markdown" {
 						// Handle markdown files specially - index as document blocks
@@ -567,7 +782,7 @@ pub async fn index_files_with_quiet(
 							state.clone(),
 						)
 						.await?;
-						file_processed = true;
+						batch_kind = BatchKind::Document;
 					} else {
 						// Handle code files - index as semantic code blocks only
 						let ctx = ProcessFileContext {
@@ -585,14 +800,12 @@ pub async fn index_files_with_quiet(
 							&mut all_code_blocks,
 						)
 						.await?;
-						file_processed = true;
+						batch_kind = BatchKind::Code;
 					}
 
-					// Store file modification time after successful processing
-					if file_processed {
-						if let Ok(actual_mtime) = get_file_mtime(entry.path()) {
-							let _ = store.store_file_metadata(&file_path, actual_mtime).await;
-						}
+					// Record the file modification time once its blocks are written
+					if let Ok(actual_mtime) = get_file_mtime(entry.path()) {
+						checkpoint.processed(batch_kind, file_path.clone(), actual_mtime);
 					}
 
 					files_processed += 1;
@@ -624,29 +837,35 @@ pub async fn index_files_with_quiet(
 					// Process batches when they reach the batch size or token limit
 					if should_process_batch(&code_blocks_batch, |b| &b.content, config) {
 						embedding_calls += code_blocks_batch.len();
-						process_code_blocks_batch(store, &code_blocks_batch, config).await?;
+						let write =
+							embed_code_blocks_batch(&code_blocks_batch, config, &state).await?;
+						writer.write(store, write).await?;
+						writer
+							.write(store, checkpoint.written(BatchKind::Code))
+							.await?;
 						code_blocks_batch.clear();
-						batches_processed += 1;
-						// Intelligent flush based on configuration
-						flush_if_needed(store, &mut batches_processed, config, false).await?;
 					}
 					// Only process text_blocks_batch if we have any (from unsupported files)
 					if should_process_batch(&text_blocks_batch, |b| &b.content, config) {
 						embedding_calls += text_blocks_batch.len();
-						process_text_blocks_batch(store, &text_blocks_batch, config).await?;
+						let write =
+							embed_text_blocks_batch(&text_blocks_batch, config, &state).await?;
+						writer.write(store, write).await?;
+						writer
+							.write(store, checkpoint.written(BatchKind::Text))
+							.await?;
 						text_blocks_batch.clear();
-						batches_processed += 1;
-						// Intelligent flush based on configuration
-						flush_if_needed(store, &mut batches_processed, config, false).await?;
 					}
 					if should_process_batch(&document_blocks_batch, |b| &b.content, config) {
 						embedding_calls += document_blocks_batch.len();
-						process_document_blocks_batch(store, &document_blocks_batch, config)
+						let write =
+							embed_document_blocks_batch(&document_blocks_batch, config, &state)
+								.await?;
+						writer.write(store, write).await?;
+						writer
+							.write(store, checkpoint.written(BatchKind::Document))
 							.await?;
 						document_blocks_batch.clear();
-						batches_processed += 1;
-						// Intelligent flush based on configuration
-						flush_if_needed(store, &mut batches_processed, config, false).await?;
 					}
 				}
 				Err(e) => {
@@ -659,58 +878,73 @@ pub async fn index_files_with_quiet(
 			// First check if the file extension is in our whitelist
 			// BUT exclude markdown files since they're already processed as documents
 			if is_allowed_text_extension(entry.path()) && !is_markdown_file(entry.path()) {
-				if let Ok(contents) = fs::read_to_string(entry.path()) {
+				if let Ok(read) = file_policy.read(entry.path()) {
 					// Only process files that are likely to contain readable text
-					if is_text_file(&contents) {
-						process_text_file_differential(
-							store,
-							&contents,
-							&file_path,
-							&mut text_blocks_batch,
-							config,
-							state.clone(),
-						)
-						.await?;
+					match read {
+						Ok(contents) if file_policy.is_text(&contents) => {
+							process_text_file_differential(
+								store,
+								&contents,
+								&file_path,
+								&mut text_blocks_batch,
+								config,
+								state.clone(),
+							)
+							.await?;
 
-						// Store file modification time after successful processing
-						if let Ok(actual_mtime) = get_file_mtime(entry.path()) {
-							let _ = store.store_file_metadata(&file_path, actual_mtime).await;
-						}
+							// Record the file modification time once its blocks are written
+							if let Ok(actual_mtime) = get_file_mtime(entry.path()) {
+								checkpoint.processed(
+									BatchKind::Text,
+									file_path.clone(),
+									actual_mtime,
+								);
+							}
 
-						files_processed += 1;
-						state.write().indexed_files = files_processed;
+							files_processed += 1;
+							state.write().indexed_files = files_processed;
+
+							// Update counting phase status
+							{
+								let mut state_guard = state.write();
+								if state_guard.counting_files && total_files_found > 50 {
+									// Switch from counting to processing mode
+									state_guard.counting_files = false;
+									state_guard.total_files = total_files_found;
+									state_guard.status_message = "".to_string();
+								}
+							}
 
-						// Update counting phase status
-						{
-							let mut state_guard = state.write();
-							if state_guard.counting_files && total_files_found > 50 {
-								// Switch from counting to processing mode
-								state_guard.counting_files = false;
-								state_guard.total_files = total_files_found;
-								state_guard.status_message = "".to_string();
+							// Log progress periodically for text files
+							if files_processed % 50 == 0 {
+								let current_total = state.read().total_files;
+								log_indexing_progress(
+									"file_processing",
+									files_processed,
+									current_total,
+									Some(&file_path),
+									embedding_calls,
+								);
 							}
-						}
 
-						// Log progress periodically for text files
-						if files_processed % 50 == 0 {
-							let current_total = state.read().total_files;
-							log_indexing_progress(
-								"file_processing",
-								files_processed,
-								current_total,
-								Some(&file_path),
-								embedding_calls,
-							);
+							// Process batch when it reaches the batch size or token limit
+							if should_process_batch(&text_blocks_batch, |b| &b.content, config) {
+								embedding_calls += text_blocks_batch.len();
+								let write =
+									embed_text_blocks_batch(&text_blocks_batch, config, &state)
+										.await?;
+								writer.write(store, write).await?;
+								writer
+									.write(store, checkpoint.written(BatchKind::Text))
+									.await?;
+								text_blocks_batch.clear();
+							}
 						}
-
-						// Process batch when it reaches the batch size or token limit
-						if should_process_batch(&text_blocks_batch, |b| &b.content, config) {
-							embedding_calls += text_blocks_batch.len();
-							process_text_blocks_batch(store, &text_blocks_batch, config).await?;
-							text_blocks_batch.clear();
-							batches_processed += 1;
-							// Intelligent flush based on configuration
-							flush_if_needed(store, &mut batches_processed, config, false).await?;
+						_ => {
+							state
+								.write()
+								.file_policy_skips
+								.record(file_policy::SkipReason::Binary);
 						}
 					}
 				}
@@ -720,29 +954,38 @@ pub async fn index_files_with_quiet(
 
 	// Process remaining batches
 	if !code_blocks_batch.is_empty() {
-		process_code_blocks_batch(store, &code_blocks_batch, config).await?;
+		let write = embed_code_blocks_batch(&code_blocks_batch, config, &state).await?;
+		writer.write(store, write).await?;
 		embedding_calls += code_blocks_batch.len();
-		batches_processed += 1;
 	}
 	// Only process text_blocks_batch if we have any (from unsupported files)
 	if !text_blocks_batch.is_empty() {
-		process_text_blocks_batch(store, &text_blocks_batch, config).await?;
+		let write = embed_text_blocks_batch(&text_blocks_batch, config, &state).await?;
+		writer.write(store, write).await?;
 		embedding_calls += text_blocks_batch.len();
-		batches_processed += 1;
 	}
 	if !document_blocks_batch.is_empty() {
-		process_document_blocks_batch(store, &document_blocks_batch, config).await?;
+		let write = embed_document_blocks_batch(&document_blocks_batch, config, &state).await?;
+		writer.write(store, write).await?;
 		embedding_calls += document_blocks_batch.len();
-		batches_processed += 1;
+	}
+	// Files whose blocks were all written already, or that had no new blocks
+	for kind in [BatchKind::Code, BatchKind::Text, BatchKind::Document] {
+		writer.write(store, checkpoint.written(kind)).await?;
 	}
 
-	// Force flush any remaining data after processing all batches
-	flush_if_needed(store, &mut batches_processed, config, true).await?;
+	// Wait for queued writes and flush (and fsync) the remaining data
+	writer.finish(store).await?;
 
 	// Build GraphRAG if enabled
 	if config.graphrag.enabled {
+		retain_graph_allowed(&mut all_code_blocks, &path_guard, &state);
+
 		// Check if we have new blocks from this indexing run OR if GraphRAG needs initial indexing
-		let needs_graphrag_from_existing = if all_code_blocks.is_empty() {
+		let needs_graphrag_from_existing = if checkpoint.resumed() > 0 {
+			// Files indexed by the interrupted run never reached the graph
+			true
+		} else if all_code_blocks.is_empty() {
 			// No new blocks, check if GraphRAG needs indexing from existing database
 			store.graphrag_needs_indexing().await.unwrap_or(false)
 		} else {
@@ -761,6 +1004,7 @@ pub async fn index_files_with_quiet(
 			}
 
 			// Log GraphRAG phase start
+			enter_phase(&state, "graphrag_build");
 			log_indexing_progress(
 				"graphrag_build",
 				state.read().indexed_files,
@@ -841,14 +1085,24 @@ pub async fn index_files_with_quiet(
 		}
 	}
 
+	// The run is complete; the next one goes by the stored metadata again
+	store.clear_checkpoint().await?;
+
 	// Flush the store to ensure all data is persisted
 	store.flush().await?;
 
+	deterministic::record_manifest_hash(store, config).await?;
+
 	Ok(())
 }
 
 // Function to handle file changes (for watch mode)
 pub async fn handle_file_change(store: &Store, file_path: &str, config: &Config) -> Result<()> {
+	let config = deterministic::effective_config(config);
+	let config = config.as_ref();
+	chunk_processors::check_redaction_policy(config)?;
+	let path_guard = PathGuard::from_config(config)?;
+
 	// Create a state for tracking changes
 	let state = state::create_shared_state();
 	{
@@ -857,8 +1111,15 @@ pub async fn handle_file_change(store: &Store, file_path: &str, config: &Config)
 		state_guard.graphrag_blocks = 0;
 	}
 
-	// First, let's remove any existing code blocks for this file path
-	store.remove_blocks_by_path(file_path).await?;
+	// First, let's remove any existing blocks for this file path. With GraphRAG enabled the
+	// graph is updated differentially below so edges from other files survive the change.
+	if config.graphrag.enabled {
+		store.remove_content_blocks_by_path(file_path).await?;
+	} else {
+		store.remove_blocks_by_path(file_path).await?;
+	}
+
+	let mut graph_updated = false;
 
 	// Now, if the file still exists, check if it should be indexed based on ignore rules
 	let path = std::path::Path::new(file_path);
@@ -882,13 +1143,22 @@ pub async fn handle_file_change(store: &Store, file_path: &str, config: &Config)
 				.is_ignore()
 			{
 				// File is in ignore patterns, so don't index it
-				return Ok(());
+				return update_graph_for_file(file_path, &[], config).await;
+			}
+		}
+
+		// Size and extension limits apply to files indexing would process
+		let file_policy = file_policy::FilePolicy::new(&config.index);
+		if detect_language(&absolute_path).is_some() || is_allowed_text_extension(&absolute_path) {
+			if let Some(reason) = file_policy.check_file(&absolute_path) {
+				state.write().file_policy_skips.record(reason);
+				return update_graph_for_file(file_path, &[], config).await;
 			}
 		}
 
 		// File is not ignored, so proceed with indexing
 		if let Some(language) = detect_language(&absolute_path) {
-			if let Ok(contents) = fs::read_to_string(&absolute_path) {
+			if let Ok(Ok(contents)) = file_policy.read(&absolute_path) {
 				// Ensure we use relative path for storage
 				let relative_file_path =
 					path_utils::PathUtils::to_relative_string(&absolute_path, &current_dir);
@@ -907,8 +1177,13 @@ pub async fn handle_file_change(store: &Store, file_path: &str, config: &Config)
 					.await?;
 
 					if !document_blocks_batch.is_empty() {
-						process_document_blocks_batch(store, &document_blocks_batch, config)
-							.await?;
+						process_document_blocks_batch(
+							store,
+							&document_blocks_batch,
+							config,
+							&state,
+						)
+						.await?;
 					}
 				} else {
 					// Handle code files
@@ -933,17 +1208,17 @@ pub async fn handle_file_change(store: &Store, file_path: &str, config: &Config)
 					.await?;
 
 					if !code_blocks_batch.is_empty() {
-						process_code_blocks_batch(store, &code_blocks_batch, config).await?;
+						process_code_blocks_batch(store, &code_blocks_batch, config, &state)
+							.await?;
 					}
 					// No need to process text_blocks_batch since it will be empty for code files
 
-					// Update GraphRAG if enabled and we have new blocks
-					if config.graphrag.enabled && !all_code_blocks.is_empty() {
-						let graph_builder = graphrag::GraphBuilder::new(config.clone()).await?;
-						graph_builder
-							.process_code_blocks(&all_code_blocks, Some(state.clone()))
-							.await?;
+					// Update only this file's node and relationships in GraphRAG
+					if config.graphrag.enabled {
+						retain_graph_allowed(&mut all_code_blocks, &path_guard, &state);
 					}
+					update_graph_for_file(&relative_file_path, &all_code_blocks, config).await?;
+					graph_updated = true;
 				}
 
 				// Explicitly flush to ensure all data is persisted
@@ -953,8 +1228,8 @@ pub async fn handle_file_change(store: &Store, file_path: &str, config: &Config)
 			// Handle unsupported file types as chunked text
 			// First check if the file extension is in our whitelist
 			if is_allowed_text_extension(&absolute_path) {
-				if let Ok(contents) = fs::read_to_string(&absolute_path) {
-					if is_text_file(&contents) {
+				if let Ok(Ok(contents)) = file_policy.read(&absolute_path) {
+					if file_policy.is_text(&contents) {
 						// Ensure we use relative path for storage
 						let relative_file_path =
 							path_utils::PathUtils::to_relative_string(&absolute_path, &current_dir);
@@ -971,7 +1246,8 @@ pub async fn handle_file_change(store: &Store, file_path: &str, config: &Config)
 						.await?;
 
 						if !text_blocks_batch.is_empty() {
-							process_text_blocks_batch(store, &text_blocks_batch, config).await?;
+							process_text_blocks_batch(store, &text_blocks_batch, config, &state)
+								.await?;
 						}
 
 						// Explicitly flush to ensure all data is persisted
@@ -982,9 +1258,53 @@ pub async fn handle_file_change(store: &Store, file_path: &str, config: &Config)
 		}
 	}
 
+	// Deleted files and files that no longer produce code blocks leave the graph
+	if !graph_updated {
+		update_graph_for_file(file_path, &[], config).await?;
+	}
+
+	let violations = std::mem::take(&mut state.write().policy_violations);
+	if !violations.is_empty() {
+		tracing::warn!(
+			?violations,
+			"Blocks from local-only paths not sent to remote providers"
+		);
+	}
+
 	Ok(())
 }
 
+/// Drop blocks that GraphRAG would send to a remote provider against `embedding.path_policies`
+fn retain_graph_allowed(blocks: &mut Vec<CodeBlock>, guard: &PathGuard, state: &SharedState) {
+	let mut violations = Vec::new();
+	blocks.retain(|block| match guard.graph_violation(&block.path) {
+		Some(destination) => {
+			violations.push(format!("{} (GraphRAG: {})", block.path, destination));
+			false
+		}
+		None => true,
+	});
+	if !violations.is_empty() {
+		state.write().policy_violations.extend(violations);
+	}
+}
+
+// Differentially update GraphRAG for a single file; no code blocks removes its node
+async fn update_graph_for_file(
+	file_path: &str,
+	code_blocks: &[CodeBlock],
+	config: &Config,
+) -> Result<()> {
+	if !config.graphrag.enabled {
+		return Ok(());
+	}
+
+	let graph_builder = graphrag::GraphBuilder::new(config.clone()).await?;
+	graph_builder
+		.update_file_differential(file_path, code_blocks)
+		.await
+}
+
 // ProcessFileContext and process_file function moved to differential_processor module
 
 // Code region extraction logic moved to code_region_extractor module
@@ -1017,6 +1337,7 @@ mod context_optimization_tests {
 			start_line: 10,
 			end_line: 15,
 			hash: "test_hash".to_string(),
+			provenance: None,
 			distance: None,
 		};
 
@@ -1061,6 +1382,7 @@ mod context_optimization_tests {
 			start_line: 0,
 			end_line: 5,
 			hash: "test_hash".to_string(),
+			provenance: None,
 			distance: None,
 		};
 
@@ -0,0 +1,132 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Rename aliases for paths that moved since they were referenced
+//!
+//! Indexing records the renames git detects between the last indexed commit
+//! and HEAD. Memories, anchors, graph node IDs and remembered search hits keep
+//! the path they were created with; lookups that miss consult this table to
+//! find where the file lives now.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::storage;
+
+const ALIASES_FILE: &str = "path_aliases.json";
+
+/// Old path -> path it was renamed to
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct PathAliases {
+	renames: HashMap<String, String>,
+}
+
+impl PathAliases {
+	/// Aliases recorded for a project; empty when none were recorded yet
+	pub fn load(project_path: &Path) -> Self {
+		match storage::get_project_storage_path(project_path) {
+			Ok(storage_dir) => Self::load_from_dir(&storage_dir),
+			Err(_) => Self::default(),
+		}
+	}
+
+	/// Aliases stored in a project storage directory
+	pub fn load_from_dir(storage_dir: &Path) -> Self {
+		std::fs::read_to_string(storage_dir.join(ALIASES_FILE))
+			.ok()
+			.and_then(|content| serde_json::from_str(&content).ok())
+			.unwrap_or_default()
+	}
+
+	pub fn save(&self, project_path: &Path) -> Result<()> {
+		let storage_dir = storage::ensure_project_storage_exists(project_path)?;
+		std::fs::write(
+			storage_dir.join(ALIASES_FILE),
+			serde_json::to_string_pretty(self)?,
+		)?;
+		Ok(())
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.renames.is_empty()
+	}
+
+	/// Record that `from` was renamed to `to`
+	pub fn record(&mut self, from: &str, to: &str) {
+		if from == to {
+			return;
+		}
+		// `to` exists again, so it no longer stands for another path
+		self.renames.remove(to);
+		self.renames.insert(from.to_string(), to.to_string());
+	}
+
+	/// Current path of a file referenced as `path`, following chains of renames.
+	/// Paths that were never renamed resolve to themselves.
+	pub fn resolve<'a>(&'a self, path: &'a str) -> &'a str {
+		self.renamed(path).unwrap_or(path)
+	}
+
+	/// Path a file referenced as `path` was renamed to; `None` when it never was
+	pub fn renamed(&self, path: &str) -> Option<&str> {
+		let mut current = self.renames.get(path)?.as_str();
+		// A rename chain can't be longer than the table; the bound guards cycles
+		for _ in 0..self.renames.len() {
+			match self.renames.get(current) {
+				Some(next) => current = next,
+				None => break,
+			}
+		}
+		Some(current)
+	}
+
+	/// `path` when a lookup finds it, otherwise the path it was renamed to
+	pub fn resolve_missing(&self, path: &str, exists: impl Fn(&str) -> bool) -> String {
+		if exists(path) {
+			path.to_string()
+		} else {
+			self.resolve(path).to_string()
+		}
+	}
+
+	/// Whether two references point at the same file today
+	pub fn same_file(&self, a: &str, b: &str) -> bool {
+		a == b || self.resolve(a) == self.resolve(b)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_resolve_follows_rename_chains() {
+		let mut aliases = PathAliases::default();
+		aliases.record("src/utils.rs", "src/util/mod.rs");
+		aliases.record("src/util/mod.rs", "src/helpers.rs");
+
+		assert_eq!(aliases.resolve("src/utils.rs"), "src/helpers.rs");
+		assert_eq!(aliases.resolve("src/main.rs"), "src/main.rs");
+		assert_eq!(aliases.renamed("src/main.rs"), None);
+		assert!(aliases.same_file("src/utils.rs", "src/helpers.rs"));
+
+		// Renaming back makes the original path current again
+		aliases.record("src/helpers.rs", "src/utils.rs");
+		assert_eq!(aliases.resolve("src/utils.rs"), "src/utils.rs");
+		assert_eq!(aliases.resolve("src/util/mod.rs"), "src/utils.rs");
+	}
+}
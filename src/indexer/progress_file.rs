@@ -0,0 +1,225 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Machine-readable indexing progress
+//!
+//! While an index run is going, `progress.json` in the project storage
+//! directory is rewritten with the phase, the file counts and the file being
+//! indexed, so editor status bars and CI log streamers can show progress
+//! without parsing console output. Every write goes to a temporary file that
+//! is renamed over the previous one, so readers never see a partial document.
+//! The last write has `status` set to `complete` or `failed`.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+use crate::state::{IndexState, SharedState};
+use crate::storage;
+
+pub const PROGRESS_FILE: &str = "progress.json";
+
+/// How often the file is rewritten while something changes
+const WRITE_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Contents of the progress file
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProgressSnapshot {
+	/// `running`, `complete` or `failed`
+	pub status: &'static str,
+	/// `indexing_start`, `cleanup`, `file_processing`, `graphrag_build` or `indexing_complete`
+	pub phase: String,
+	pub current_file: Option<String>,
+	pub indexed_files: usize,
+	pub skipped_files: usize,
+	/// Indexable files found so far; grows while files are still being counted
+	pub total_files: usize,
+	pub counting_files: bool,
+	pub graphrag_blocks: usize,
+	pub embedding_calls: usize,
+	pub message: String,
+	pub error: Option<String>,
+	pub pid: u32,
+	pub started_at: String,
+	pub updated_at: String,
+}
+
+impl ProgressSnapshot {
+	fn capture(state: &IndexState, started_at: &str) -> Self {
+		Self {
+			status: if state.indexing_complete {
+				"complete"
+			} else {
+				"running"
+			},
+			phase: if state.indexing_complete {
+				"indexing_complete".to_string()
+			} else {
+				state.phase.clone()
+			},
+			current_file: state.current_file.clone(),
+			indexed_files: state.indexed_files,
+			skipped_files: state.skipped_files,
+			total_files: state.total_files,
+			counting_files: state.counting_files,
+			graphrag_blocks: state.graphrag_blocks,
+			embedding_calls: state.embedding_calls,
+			message: state.status_message.clone(),
+			error: None,
+			pid: std::process::id(),
+			started_at: started_at.to_string(),
+			updated_at: chrono::Utc::now().to_rfc3339(),
+		}
+	}
+
+	/// Everything but the timestamp, to skip writes when nothing changed
+	fn same_progress(&self, other: &Self) -> bool {
+		Self {
+			updated_at: String::new(),
+			..self.clone()
+		} == Self {
+			updated_at: String::new(),
+			..other.clone()
+		}
+	}
+}
+
+/// Replace `path` with the snapshot through a temporary file in the same directory
+pub fn write_atomic(path: &Path, snapshot: &ProgressSnapshot) -> Result<()> {
+	let tmp = path.with_extension(format!("json.{}.tmp", std::process::id()));
+	std::fs::write(&tmp, serde_json::to_vec_pretty(snapshot)?)?;
+	if let Err(e) = std::fs::rename(&tmp, path) {
+		let _ = std::fs::remove_file(&tmp);
+		return Err(e.into());
+	}
+	Ok(())
+}
+
+/// Background task keeping the progress file of one index run up to date
+pub struct ProgressFile {
+	stop: oneshot::Sender<()>,
+	handle: JoinHandle<()>,
+	path: PathBuf,
+	state: SharedState,
+	started_at: String,
+}
+
+impl ProgressFile {
+	/// Start writing progress for the project in `project_path`. Failing to
+	/// create the storage directory only disables the file.
+	pub fn start(state: SharedState, project_path: &Path) -> Option<Self> {
+		let path = storage::ensure_project_storage_exists(project_path)
+			.ok()?
+			.join(PROGRESS_FILE);
+		let started_at = chrono::Utc::now().to_rfc3339();
+		let (stop, mut stopped) = oneshot::channel();
+
+		let handle = {
+			let path = path.clone();
+			let state = state.clone();
+			let started_at = started_at.clone();
+			tokio::spawn(async move {
+				let mut last: Option<ProgressSnapshot> = None;
+				loop {
+					let snapshot = ProgressSnapshot::capture(&state.read(), &started_at);
+					if last
+						.as_ref()
+						.is_none_or(|last| !last.same_progress(&snapshot))
+						&& write_atomic(&path, &snapshot).is_ok()
+					{
+						last = Some(snapshot);
+					}
+					tokio::select! {
+						_ = &mut stopped => break,
+						_ = tokio::time::sleep(WRITE_INTERVAL) => {}
+					}
+				}
+			})
+		};
+
+		Some(Self {
+			stop,
+			handle,
+			path,
+			state,
+			started_at,
+		})
+	}
+
+	/// Stop the task and write the outcome of the run
+	pub async fn finish(self, error: Option<&anyhow::Error>) {
+		let _ = self.stop.send(());
+		let _ = self.handle.await;
+
+		let mut snapshot = ProgressSnapshot::capture(&self.state.read(), &self.started_at);
+		if let Some(error) = error {
+			snapshot.status = "failed";
+			snapshot.error = Some(error.to_string());
+		} else {
+			snapshot.status = "complete";
+			snapshot.phase = "indexing_complete".to_string();
+		}
+		snapshot.current_file = None;
+		let _ = write_atomic(&self.path, &snapshot);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_snapshot_and_atomic_write() {
+		let mut state = IndexState {
+			phase: "file_processing".to_string(),
+			current_file: Some("src/main.rs".to_string()),
+			indexed_files: 3,
+			total_files: 10,
+			..Default::default()
+		};
+		let snapshot = ProgressSnapshot::capture(&state, "2025-01-01T00:00:00+00:00");
+		assert_eq!(snapshot.status, "running");
+		assert_eq!(snapshot.phase, "file_processing");
+
+		let mut later = snapshot.clone();
+		later.updated_at = "2025-01-01T00:00:01+00:00".to_string();
+		assert!(snapshot.same_progress(&later));
+
+		state.indexing_complete = true;
+		let complete = ProgressSnapshot::capture(&state, &snapshot.started_at);
+		assert_eq!(complete.status, "complete");
+		assert_eq!(complete.phase, "indexing_complete");
+		assert!(!snapshot.same_progress(&complete));
+
+		let dir = std::env::temp_dir().join(format!("octocode_progress_{}", std::process::id()));
+		let _ = std::fs::remove_dir_all(&dir);
+		std::fs::create_dir_all(&dir).unwrap();
+		let path = dir.join(PROGRESS_FILE);
+		write_atomic(&path, &snapshot).unwrap();
+		write_atomic(&path, &complete).unwrap();
+
+		let written: serde_json::Value =
+			serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+		assert_eq!(written["status"], "complete");
+		assert_eq!(written["indexed_files"], 3);
+		assert_eq!(written["current_file"], "src/main.rs");
+		// Only the progress file is left behind
+		assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+		let _ = std::fs::remove_dir_all(&dir);
+	}
+}
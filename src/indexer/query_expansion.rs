@@ -0,0 +1,150 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! LLM rewriting of short search queries
+//!
+//! A query like "auth bug" shares few words with the code it is looking for.
+//! With `search.query_expansion` enabled, a single query is sent to the
+//! configured LLM for a few reformulations, all of them are searched in
+//! parallel, and the rankings are merged with reciprocal rank fusion
+//! (`search::fuse_results`).
+
+use anyhow::Result;
+
+use crate::config::Config;
+
+/// Reformulations asked for and searched besides the original query
+pub const MAX_EXPANSIONS: usize = 3;
+
+const EXPANSION_SYSTEM_PROMPT: &str = "You rewrite search queries for a semantic \
+code search engine. Given a short query, write up to 3 alternative phrasings that \
+a developer might use for the same intent: spell out abbreviations, name the likely \
+functions, types or concepts involved, and use the vocabulary found in code and \
+documentation. Reply with one reformulation per line and nothing else.";
+
+/// Reformulations of `query`, without the query itself
+pub async fn expand_query(query: &str, config: &Config) -> Result<Vec<String>> {
+	let reply = call_llm(config, EXPANSION_SYSTEM_PROMPT, query).await?;
+	Ok(parse_expansions(&reply, query))
+}
+
+/// Reformulations listed in an LLM reply, one per line, with list markers
+/// and quotes removed and repeats of the query dropped
+fn parse_expansions(reply: &str, query: &str) -> Vec<String> {
+	let mut expansions: Vec<String> = Vec::new();
+	for line in reply.lines() {
+		let line = line
+			.trim()
+			.trim_start_matches(|c: char| c.is_ascii_digit())
+			.trim_start_matches(['.', ')', '-', '*', '•'])
+			.trim()
+			.trim_matches(['"', '\'', '`'])
+			.trim();
+		// Same length limits as queries typed by the user
+		if line.len() < 3 || line.len() > 500 || line.ends_with(':') {
+			continue;
+		}
+		let seen = line.eq_ignore_ascii_case(query.trim())
+			|| expansions
+				.iter()
+				.any(|expansion| expansion.eq_ignore_ascii_case(line));
+		if !seen {
+			expansions.push(line.to_string());
+		}
+		if expansions.len() == MAX_EXPANSIONS {
+			break;
+		}
+	}
+	expansions
+}
+
+async fn call_llm(config: &Config, system: &str, prompt: &str) -> Result<String> {
+	use serde_json::{json, Value};
+
+	// Without an API key, fall back to the MCP client's model when serving
+	let api_key = match &config.openrouter.api_key {
+		Some(key) => key.clone(),
+		None => match std::env::var("OPENROUTER_API_KEY") {
+			Ok(key) => key,
+			Err(_) if crate::mcp::sampling::is_available() => {
+				return crate::mcp::sampling::create_message(
+					system,
+					prompt,
+					Some(&config.openrouter.model),
+					crate::mcp::sampling::SAMPLING_DEFAULT_MAX_TOKENS,
+				)
+				.await;
+			}
+			Err(_) => return Err(anyhow::anyhow!("No OpenRouter API key found")),
+		},
+	};
+
+	let payload = json!({
+		"model": config.openrouter.model,
+		"messages": [
+			{ "role": "system", "content": system },
+			{ "role": "user", "content": prompt }
+		],
+		"temperature": 0.3,
+		"max_tokens": 200
+	});
+
+	let response = reqwest::Client::new()
+		.post(format!(
+			"{}/chat/completions",
+			config.openrouter.base_url.trim_end_matches('/')
+		))
+		.header("Authorization", format!("Bearer {}", api_key))
+		.header("HTTP-Referer", "https://github.com/muvon/octocode")
+		.header("X-Title", "Octocode")
+		.json(&payload)
+		.timeout(std::time::Duration::from_secs(config.openrouter.timeout))
+		.send()
+		.await?;
+
+	if !response.status().is_success() {
+		let error_text = response.text().await?;
+		return Err(anyhow::anyhow!("LLM API error: {}", error_text));
+	}
+
+	let response_json: Value = response.json().await?;
+	response_json["choices"][0]["message"]["content"]
+		.as_str()
+		.map(|content| content.trim().to_string())
+		.ok_or_else(|| anyhow::anyhow!("Invalid response format from LLM"))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse_expansions() {
+		let reply = "Here are some reformulations:\n\
+			1. authentication failure handling\n\
+			2) \"login token validation error\"\n\
+			- Auth Bug\n\
+			* session expiry check\n\
+			- verify credentials\n";
+		assert_eq!(
+			parse_expansions(reply, "auth bug"),
+			vec![
+				"authentication failure handling",
+				"login token validation error",
+				"session expiry check"
+			]
+		);
+		assert!(parse_expansions("", "auth bug").is_empty());
+	}
+}
@@ -0,0 +1,167 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Refining the last search without querying the index again
+//!
+//! Every search remembers its full result set; `octocode search --refine`
+//! keeps the cached hits that mention the new query and reranks them.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::reranker::Reranker;
+use crate::storage;
+use crate::store::{CodeBlock, DocumentBlock, TextBlock};
+
+const CACHED_RESULTS_FILE: &str = "last_search_results.json";
+
+/// Result set of the latest search (or refinement) in a project
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CachedResults {
+	/// Queries that narrowed the set down, in order
+	pub queries: Vec<String>,
+	pub mode: String,
+	pub code_blocks: Vec<CodeBlock>,
+	pub doc_blocks: Vec<DocumentBlock>,
+	pub text_blocks: Vec<TextBlock>,
+}
+
+impl CachedResults {
+	pub fn save(&self, project_path: &Path) -> Result<()> {
+		std::fs::write(cache_path(project_path)?, serde_json::to_string(self)?)?;
+		Ok(())
+	}
+
+	pub fn load(project_path: &Path) -> Result<Self> {
+		let path = cache_path(project_path)?;
+		if !path.exists() {
+			return Err(anyhow::anyhow!(
+				"No previous search to refine. Run 'octocode search' first."
+			));
+		}
+		Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+	}
+
+	/// Hits that mention a word of `query`, reranked for it. The refined set
+	/// remembers `query` so it can be narrowed down further.
+	pub fn refine(self, query: &str) -> Self {
+		let terms = query_terms(query);
+		let mentions = |texts: &[&str]| {
+			texts.iter().any(|text| {
+				let text = text.to_lowercase();
+				terms.iter().any(|term| text.contains(term.as_str()))
+			})
+		};
+
+		let code_blocks: Vec<CodeBlock> = self
+			.code_blocks
+			.into_iter()
+			.filter(|block| {
+				let mut texts = vec![block.content.as_str(), block.path.as_str()];
+				texts.extend(block.symbols.iter().map(String::as_str));
+				mentions(&texts)
+			})
+			.collect();
+		let doc_blocks: Vec<DocumentBlock> = self
+			.doc_blocks
+			.into_iter()
+			.filter(|block| {
+				mentions(&[
+					block.title.as_str(),
+					block.content.as_str(),
+					block.path.as_str(),
+				])
+			})
+			.collect();
+		let text_blocks: Vec<TextBlock> = self
+			.text_blocks
+			.into_iter()
+			.filter(|block| mentions(&[block.content.as_str(), block.path.as_str()]))
+			.collect();
+
+		let mut code_blocks = Reranker::rerank_code_blocks(code_blocks, query);
+		Reranker::tf_idf_boost(&mut code_blocks, query);
+
+		let mut queries = self.queries;
+		queries.push(query.to_string());
+
+		Self {
+			queries,
+			mode: self.mode,
+			code_blocks,
+			doc_blocks: Reranker::rerank_document_blocks(doc_blocks, query),
+			text_blocks: Reranker::rerank_text_blocks(text_blocks, query),
+		}
+	}
+}
+
+/// Lowercased words of a refinement query; one-letter words match too much
+fn query_terms(query: &str) -> Vec<String> {
+	query
+		.split_whitespace()
+		.map(str::to_lowercase)
+		.filter(|term| term.chars().count() > 1)
+		.collect()
+}
+
+fn cache_path(project_path: &Path) -> Result<PathBuf> {
+	Ok(storage::ensure_project_storage_exists(project_path)?.join(CACHED_RESULTS_FILE))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn code_block(path: &str, content: &str, symbol: &str) -> CodeBlock {
+		CodeBlock {
+			path: path.to_string(),
+			language: "rust".to_string(),
+			content: content.to_string(),
+			symbols: vec![symbol.to_string()],
+			start_line: 0,
+			end_line: 1,
+			hash: path.to_string(),
+			spans: Vec::new(),
+			provenance: None,
+			distance: Some(0.3),
+		}
+	}
+
+	#[test]
+	fn test_refine_keeps_mentioning_hits() {
+		let cached = CachedResults {
+			queries: vec!["error handling".to_string()],
+			mode: "code".to_string(),
+			code_blocks: vec![
+				code_block("src/retry.rs", "fn backoff() {}", "backoff"),
+				code_block("src/parse.rs", "fn parse() { Err(e)? }", "parse"),
+				code_block("src/net.rs", "fn connect() {}", "connect_with_retry"),
+			],
+			..Default::default()
+		};
+
+		let refined = cached.refine("Retry");
+		let paths: Vec<&str> = refined
+			.code_blocks
+			.iter()
+			.map(|block| block.path.as_str())
+			.collect();
+		assert_eq!(paths.len(), 2);
+		assert!(paths.contains(&"src/retry.rs"));
+		assert!(paths.contains(&"src/net.rs"));
+		assert_eq!(refined.queries, vec!["error handling", "Retry"]);
+		assert_eq!(refined.mode, "code");
+	}
+}
@@ -1,6 +1,6 @@
 use super::FileSignature;
 use crate::config::Config;
-use crate::store::{CodeBlock, DocumentBlock, TextBlock};
+use crate::store::{relevance, CodeBlock, DocumentBlock, TextBlock};
 use anyhow::Result;
 
 // Extracted rendering functions:
@@ -119,8 +119,9 @@ pub fn truncate_content_smartly(content: &str, max_characters: usize) -> (String
 	(result, true)
 }
 
-/// Render signatures as markdown string
-pub fn signatures_to_markdown(signatures: &[FileSignature]) -> String {
+/// Render signatures as markdown string; `expand` shows full bodies instead of
+/// the first and last lines of long items
+pub fn signatures_to_markdown(signatures: &[FileSignature], expand: bool) -> String {
 	let mut markdown = String::new();
 
 	if signatures.is_empty() {
@@ -174,7 +175,7 @@ pub fn signatures_to_markdown(signatures: &[FileSignature]) -> String {
 				markdown.push('\n');
 
 				let lines = signature.signature.lines().collect::<Vec<_>>();
-				if lines.len() > 5 {
+				if lines.len() > 5 && !expand {
 					// Show first 2 lines and last 2 lines to maintain context
 					for line in lines.iter().take(2) {
 						markdown.push_str(line.as_ref());
@@ -203,8 +204,8 @@ pub fn signatures_to_markdown(signatures: &[FileSignature]) -> String {
 	markdown
 }
 
-/// Render signatures as text string (token-efficient)
-pub fn render_signatures_text(signatures: &[FileSignature]) -> String {
+/// Render signatures as text string (token-efficient); `expand` shows full bodies
+pub fn render_signatures_text(signatures: &[FileSignature], expand: bool) -> String {
 	let mut output = String::new();
 
 	if signatures.is_empty() {
@@ -246,7 +247,7 @@ pub fn render_signatures_text(signatures: &[FileSignature]) -> String {
 
 				// Add signature content with line numbers (smart truncation showing first and last lines)
 				let lines = signature.signature.lines().collect::<Vec<_>>();
-				let content_with_lines = if lines.len() > 5 {
+				let content_with_lines = if lines.len() > 5 && !expand {
 					// Show first 2 lines, then "...", then last 2 lines to maintain context
 					let first_lines: Vec<String> = lines
 						.iter()
@@ -332,7 +333,7 @@ pub fn code_blocks_to_markdown_with_config(blocks: &[CodeBlock], config: &Config
 
 			// Show similarity score if available
 			if let Some(distance) = block.distance {
-				markdown.push_str(&format!("**Similarity:** {:.4}  ", 1.0 - distance));
+				markdown.push_str(&format!("**Similarity:** {:.4}  ", relevance(distance)));
 			}
 			markdown.push('\n');
 
@@ -425,7 +426,7 @@ pub fn text_blocks_to_markdown_with_config(blocks: &[TextBlock], config: &Config
 
 			// Show relevance score if available
 			if let Some(distance) = block.distance {
-				markdown.push_str(&format!("**Relevance:** {:.4}  ", 1.0 - distance));
+				markdown.push_str(&format!("**Relevance:** {:.4}  ", relevance(distance)));
 			}
 			markdown.push_str("\n\n");
 
@@ -507,7 +508,7 @@ pub fn document_blocks_to_markdown_with_config(
 
 			// Show relevance score if available
 			if let Some(distance) = block.distance {
-				markdown.push_str(&format!("**Relevance:** {:.4}  ", 1.0 - distance));
+				markdown.push_str(&format!("**Relevance:** {:.4}  ", relevance(distance)));
 			}
 			markdown.push_str("\n\n");
 
@@ -537,8 +538,8 @@ pub fn document_blocks_to_markdown_with_config(
 	markdown
 }
 
-/// Render signatures as CLI output with box drawing
-pub fn render_signatures_cli(signatures: &[FileSignature]) {
+/// Render signatures as CLI output with box drawing; `expand` shows full bodies
+pub fn render_signatures_cli(signatures: &[FileSignature], expand: bool) {
 	if signatures.is_empty() {
 		println!("No signatures found.");
 		return;
@@ -591,7 +592,7 @@ pub fn render_signatures_cli(signatures: &[FileSignature]) {
 				let lines = signature.signature.lines().collect::<Vec<_>>();
 				if lines.len() > 1 {
 					println!("║ ┌────────────────────────────────────");
-					if lines.len() > 5 {
+					if lines.len() > 5 && !expand {
 						// Show first 2 lines with line numbers
 						for (i, line) in lines.iter().take(2).enumerate() {
 							let line_num = signature.start_line + 1 + i;
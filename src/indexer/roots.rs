@@ -0,0 +1,114 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Workspaces made of several root directories
+//!
+//! A project spanning sibling directories (`frontend/`, `backend/`,
+//! `shared/`) that don't share a git root is indexed from the directory
+//! containing them, the workspace root, with `index.roots` listing the
+//! directories to walk. Files are stored relative to the workspace root, so
+//! one database covers the whole workspace and paths stay unambiguous.
+
+use anyhow::Result;
+use std::path::{Component, Path};
+
+/// Check `index.roots` and turn it into directories relative to
+/// `workspace_root`, with `/` separators, sorted, and without roots nested
+/// in another one. Empty when the workspace root itself is indexed.
+pub fn resolve(workspace_root: &Path, roots: &[String]) -> Result<Vec<String>> {
+	let canonical_root = workspace_root.canonicalize()?;
+	let mut dirs = Vec::new();
+	for root in roots {
+		let path = workspace_root.join(root);
+		if !path.is_dir() {
+			return Err(anyhow::anyhow!(
+				"index.roots: {} is not a directory under {}",
+				root,
+				workspace_root.display()
+			));
+		}
+		let relative = path
+			.canonicalize()?
+			.strip_prefix(&canonical_root)
+			.map(Path::to_path_buf)
+			.map_err(|_| {
+				anyhow::anyhow!(
+					"index.roots: {} is outside the workspace root {}; index from a directory containing every root",
+					root,
+					workspace_root.display()
+				)
+			})?;
+		let relative: Vec<String> = relative
+			.components()
+			.filter_map(|component| match component {
+				Component::Normal(part) => Some(part.to_string_lossy().to_string()),
+				_ => None,
+			})
+			.collect();
+		if relative.is_empty() {
+			// The workspace root covers every other root
+			return Ok(Vec::new());
+		}
+		dirs.push(relative.join("/"));
+	}
+	dirs.sort();
+	dirs.dedup();
+
+	// Sorting puts a directory right before the ones nested in it
+	let mut resolved: Vec<String> = Vec::new();
+	for dir in dirs {
+		if !super::extra_dirs::contains(&resolved, &dir) {
+			resolved.push(dir);
+		}
+	}
+	Ok(resolved)
+}
+
+/// Whether a file path relative to the workspace root is in one of `roots`;
+/// every path is when there are none
+pub fn contains(roots: &[String], file_path: &str) -> bool {
+	roots.is_empty() || super::extra_dirs::contains(roots, file_path)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_resolve_roots() {
+		let workspace = std::env::temp_dir().join(format!("octocode_roots_{}", std::process::id()));
+		for dir in ["frontend/src", "backend", "shared"] {
+			std::fs::create_dir_all(workspace.join(dir)).unwrap();
+		}
+		let roots = |roots: &[&str]| {
+			let roots: Vec<String> = roots.iter().map(|root| root.to_string()).collect();
+			resolve(&workspace, &roots)
+		};
+
+		assert_eq!(
+			roots(&["shared/", "./backend", "frontend/src", "frontend"]).unwrap(),
+			vec!["backend", "frontend", "shared"]
+		);
+		assert!(roots(&["frontend", "."]).unwrap().is_empty());
+		assert!(roots(&["missing"]).is_err());
+		assert!(roots(&[".."]).is_err());
+
+		let resolved = roots(&["backend", "shared"]).unwrap();
+		assert!(contains(&resolved, "shared/types.ts"));
+		assert!(!contains(&resolved, "frontend/src/app.ts"));
+		assert!(contains(&[], "frontend/src/app.ts"));
+
+		std::fs::remove_dir_all(&workspace).unwrap();
+	}
+}
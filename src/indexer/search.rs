@@ -15,7 +15,11 @@
 // Module for search functionality
 
 use crate::config::Config;
-use crate::store::{CodeBlock, Store};
+use crate::indexer::test_classifier;
+use crate::store::{
+	relevance, Anchor, CodeBlock, ContentFilter, DocumentBlock, PathFilter, Store, SymbolFilter,
+	TextBlock,
+};
 use anyhow::Result;
 use std::collections::HashSet;
 
@@ -46,7 +50,15 @@ pub fn render_code_blocks_with_config(blocks: &[CodeBlock], config: &Config, det
 
 		// Show similarity score if available
 		if let Some(distance) = block.distance {
-			println!("║ Similarity: {:.4}", 1.0 - distance);
+			println!("║ Similarity: {:.4}", relevance(distance));
+		}
+		if let Some(blame) = block.provenance.as_ref().and_then(|p| p.blame.as_ref()) {
+			println!(
+				"║ Last changed: {} by {} ({})",
+				blame.age(chrono::Utc::now()),
+				blame.author,
+				&blame.commit[..blame.commit.len().min(8)]
+			);
 		}
 
 		if !block.symbols.is_empty() {
@@ -170,11 +182,26 @@ pub fn render_code_blocks_with_config(blocks: &[CodeBlock], config: &Config, det
 
 // Render search results as JSON
 pub fn render_results_json(results: &[CodeBlock]) -> Result<(), anyhow::Error> {
-	let json = serde_json::to_string_pretty(results)?;
+	let json = serde_json::to_string_pretty(&scored_json(results)?)?;
 	println!("{}", json);
 	Ok(())
 }
 
+/// Search results as JSON, each with the 0-1 `score` the other formats show
+/// next to its raw `distance`
+pub fn scored_json<T: serde::Serialize>(results: &[T]) -> Result<serde_json::Value> {
+	let mut value = serde_json::to_value(results)?;
+	if let Some(items) = value.as_array_mut() {
+		for item in items.iter_mut().filter_map(|item| item.as_object_mut()) {
+			if let Some(distance) = item.get("distance").and_then(|d| d.as_f64()) {
+				let score = (relevance(distance as f32) as f64 * 10_000.0).round() / 10_000.0;
+				item.insert("score".to_string(), serde_json::json!(score));
+			}
+		}
+	}
+	Ok(value)
+}
+
 // Expand symbols in code blocks to include related code while maintaining relevance order
 pub async fn expand_symbols(
 	store: &Store,
@@ -497,7 +524,7 @@ fn format_code_search_results_with_detail(blocks: &[CodeBlock], detail_level: &s
 
 			// Show similarity score if available
 			if let Some(distance) = block.distance {
-				output.push_str(&format!("- **Similarity**: {:.4}\n", 1.0 - distance));
+				output.push_str(&format!("- **Similarity**: {:.4}\n", relevance(distance)));
 			}
 
 			if !block.symbols.is_empty() {
@@ -596,7 +623,7 @@ fn format_code_search_results_as_markdown(blocks: &[CodeBlock]) -> String {
 
 			// Show similarity score if available
 			if let Some(distance) = block.distance {
-				output.push_str(&format!("- **Similarity**: {:.4}\n", 1.0 - distance));
+				output.push_str(&format!("- **Similarity**: {:.4}\n", relevance(distance)));
 			}
 
 			if !block.symbols.is_empty() {
@@ -671,7 +698,7 @@ fn format_text_search_results_as_markdown(blocks: &[crate::store::TextBlock]) ->
 
 			// Show similarity score if available
 			if let Some(distance) = block.distance {
-				output.push_str(&format!("- **Similarity**: {:.4}\n", 1.0 - distance));
+				output.push_str(&format!("- **Similarity**: {:.4}\n", relevance(distance)));
 			}
 
 			output.push_str("\n**Content:**\n\n");
@@ -728,7 +755,7 @@ fn format_doc_search_results_as_markdown(blocks: &[crate::store::DocumentBlock])
 
 			// Show similarity score if available
 			if let Some(distance) = block.distance {
-				output.push_str(&format!("- **Similarity**: {:.4}\n", 1.0 - distance));
+				output.push_str(&format!("- **Similarity**: {:.4}\n", relevance(distance)));
 			}
 
 			output.push_str("\n**Content:**\n\n");
@@ -756,9 +783,10 @@ pub fn format_code_search_results_as_text(blocks: &[CodeBlock], detail_level: &s
 		// output.push_str(&format!("{}-{}", block.start_line + 1, block.end_line + 1));
 
 		if let Some(distance) = block.distance {
-			output.push_str(&format!(" | Similarity {:.3}", 1.0 - distance));
+			output.push_str(&format!(" | Similarity {:.3}", relevance(distance)));
 		}
 		output.push('\n');
+		output.push_str(&format!("Anchor: {}\n", Anchor::for_code_block(block)));
 
 		// Add symbols if available
 		if !block.symbols.is_empty() {
@@ -836,9 +864,10 @@ pub fn format_text_search_results_as_text(
 		// output.push_str(&format!("{}-{}", block.start_line + 1, block.end_line + 1));
 
 		if let Some(distance) = block.distance {
-			output.push_str(&format!(" | Similarity {:.3}", 1.0 - distance));
+			output.push_str(&format!(" | Similarity {:.3}", relevance(distance)));
 		}
 		output.push('\n');
+		output.push_str(&format!("Anchor: {}\n", Anchor::for_text_block(block)));
 
 		// Add content with line numbers based on detail level
 		match detail_level {
@@ -903,9 +932,10 @@ pub fn format_doc_search_results_as_text(
 		));
 
 		if let Some(distance) = block.distance {
-			output.push_str(&format!(" | Similarity {:.3}", 1.0 - distance));
+			output.push_str(&format!(" | Similarity {:.3}", relevance(distance)));
 		}
 		output.push('\n');
+		output.push_str(&format!("Anchor: {}\n", Anchor::for_document_block(block)));
 
 		// Add content with line numbers based on detail level
 		match detail_level {
@@ -1064,18 +1094,112 @@ fn format_combined_search_results_as_markdown(
 	output
 }
 
+/// Receives each hit of a search, formatted, as soon as it is fetched; the
+/// complete page is returned as usual
+pub type PartialResults = tokio::sync::mpsc::UnboundedSender<String>;
+
+/// Send every hit formatted on its own, in ranking order
+fn stream_hits<T>(partial: Option<&PartialResults>, hits: &[T], format: impl Fn(&[T]) -> String) {
+	if let Some(partial) = partial {
+		for hit in hits {
+			let _ = partial.send(format(std::slice::from_ref(hit)));
+		}
+	}
+}
+
+/// One page of formatted search results
+pub struct SearchPage {
+	pub output: String,
+	/// Offset of the next page when more results follow
+	pub next_offset: Option<usize>,
+}
+
+/// Cursor handed to MCP clients for the page starting at `offset`
+pub fn encode_cursor(offset: usize) -> String {
+	format!("o{}", offset)
+}
+
+/// Page offset from a cursor produced by [`encode_cursor`], at most `max_offset`
+pub fn decode_cursor(cursor: &str, max_offset: usize) -> Result<usize> {
+	let offset = cursor
+		.strip_prefix('o')
+		.and_then(|offset| offset.parse().ok())
+		.ok_or_else(|| {
+			anyhow::anyhow!("Invalid cursor '{}': pass next_cursor unchanged", cursor)
+		})?;
+	check_offset(offset, max_offset)?;
+	Ok(offset)
+}
+
+/// Refuse pages starting beyond `search.max_offset`
+pub fn check_offset(offset: usize, max_offset: usize) -> Result<()> {
+	if offset > max_offset {
+		return Err(anyhow::anyhow!(
+			"Offset {} is beyond the maximum of {} (search.max_offset)",
+			offset,
+			max_offset
+		));
+	}
+	Ok(())
+}
+
+/// Keep the `limit` results starting at `offset`; true when more results follow
+pub fn take_page<T>(results: &mut Vec<T>, offset: usize, limit: usize) -> bool {
+	results.drain(..offset.min(results.len()));
+	let has_more = results.len() > limit;
+	results.truncate(limit);
+	has_more
+}
+
 // Enhanced search function for MCP server with detail level control - returns formatted text results (token-efficient)
+#[allow(clippy::too_many_arguments)]
 pub async fn search_codebase_with_details_text(
 	query: &str,
 	mode: &str,
 	detail_level: &str,
 	max_results: usize,
+	offset: usize,
 	similarity_threshold: f32,
 	language_filter: Option<&str>,
+	symbol_filter: Option<&SymbolFilter>,
+	content_filter: Option<&ContentFilter>,
+	path_filter: Option<&PathFilter>,
+	exclude_tests: bool,
 	config: &Config,
-) -> Result<String> {
-	// Initialize store
-	let store = Store::new().await?;
+	partial: Option<&PartialResults>,
+) -> Result<SearchPage> {
+	// Short queries are rewritten by the LLM and searched together with the
+	// rewrites. Symbol and scoped searches match exact text, so they are not.
+	if config.search.query_expansion && symbol_filter.is_none() && content_filter.is_none() {
+		match crate::indexer::query_expansion::expand_query(query, config).await {
+			Ok(expansions) if !expansions.is_empty() => {
+				let queries: Vec<String> = std::iter::once(query.to_string())
+					.chain(expansions)
+					.collect();
+				return search_queries_text(
+					&queries,
+					mode,
+					detail_level,
+					max_results,
+					offset,
+					similarity_threshold,
+					language_filter,
+					None,
+					None,
+					path_filter,
+					exclude_tests,
+					config,
+					true,
+				)
+				.await;
+			}
+			Ok(_) => {}
+			Err(e) => tracing::warn!("Query expansion failed, searching as typed: {}", e),
+		}
+	}
+
+	// Initialize store, limited to the requested paths
+	let store = Store::new().await?.with_path_filter(path_filter.cloned());
 
 	// Generate embeddings for the query using centralized logic
 	let search_embeddings =
@@ -1084,47 +1208,86 @@ pub async fn search_codebase_with_details_text(
 	// Convert similarity threshold to distance threshold for store operations
 	let distance_threshold = 1.0 - similarity_threshold;
 
+	// The store skips earlier pages; one extra result tells whether another page
+	// follows. Test code is dropped within the page, so pages may come out short.
+	let page_limit = max_results + 1;
+
 	// Perform the search based on mode
 	match mode {
 		"code" => {
 			let embeddings = search_embeddings.code_embeddings.ok_or_else(|| {
 				anyhow::anyhow!("No code embeddings generated for code search mode")
 			})?;
-			let results = store
-				.get_code_blocks_with_language_filter(
+			let mut results = store
+				.get_code_blocks_with_filters(
 					embeddings,
-					Some(max_results),
+					Some(page_limit),
+					offset,
 					Some(distance_threshold),
 					language_filter,
+					symbol_filter,
+					content_filter,
 				)
 				.await?;
-			Ok(format_code_search_results_as_text(&results, detail_level))
+			let has_more = take_page(&mut results, 0, max_results);
+			if exclude_tests {
+				drop_test_results(&mut results, &mut Vec::new(), &mut Vec::new());
+			}
+			stream_hits(partial, &results, |hits| {
+				format_code_search_results_as_text(hits, detail_level)
+			});
+			Ok(SearchPage {
+				output: format_code_search_results_as_text(&results, detail_level),
+				next_offset: has_more.then_some(offset.saturating_add(max_results)),
+			})
 		}
 		"text" => {
 			let embeddings = search_embeddings.text_embeddings.ok_or_else(|| {
 				anyhow::anyhow!("No text embeddings generated for text search mode")
 			})?;
-			let results = store
-				.get_text_blocks_with_config(
+			let mut results = store
+				.get_text_blocks_with_offset(
 					embeddings,
-					Some(max_results),
+					Some(page_limit),
+					offset,
 					Some(distance_threshold),
 				)
 				.await?;
-			Ok(format_text_search_results_as_text(&results, detail_level))
+			let has_more = take_page(&mut results, 0, max_results);
+			if exclude_tests {
+				drop_test_results(&mut Vec::new(), &mut Vec::new(), &mut results);
+			}
+			stream_hits(partial, &results, |hits| {
+				format_text_search_results_as_text(hits, detail_level)
+			});
+			Ok(SearchPage {
+				output: format_text_search_results_as_text(&results, detail_level),
+				next_offset: has_more.then_some(offset.saturating_add(max_results)),
+			})
 		}
 		"docs" => {
 			let embeddings = search_embeddings.text_embeddings.ok_or_else(|| {
 				anyhow::anyhow!("No text embeddings generated for docs search mode")
 			})?;
-			let results = store
-				.get_document_blocks_with_config(
+			let mut results = store
+				.get_document_blocks_with_offset(
 					embeddings,
-					Some(max_results),
+					Some(page_limit),
+					offset,
 					Some(distance_threshold),
 				)
 				.await?;
-			Ok(format_doc_search_results_as_text(&results, detail_level))
+			let has_more = take_page(&mut results, 0, max_results);
+			if exclude_tests {
+				drop_test_results(&mut Vec::new(), &mut results, &mut Vec::new());
+			}
+			stream_hits(partial, &results, |hits| {
+				format_doc_search_results_as_text(hits, detail_level)
+			});
+			Ok(SearchPage {
+				output: format_doc_search_results_as_text(&results, detail_level),
+				next_offset: has_more.then_some(offset.saturating_add(max_results)),
+			})
 		}
 		"all" => {
 			// "all" mode - search across all types with limited results per type
@@ -1135,37 +1298,71 @@ pub async fn search_codebase_with_details_text(
 				anyhow::anyhow!("No text embeddings generated for all search mode")
 			})?;
 
-			let results_per_type = max_results.div_ceil(3); // Distribute results across types
-			let code_results = store
-				.get_code_blocks_with_language_filter(
+			// Distribute results across types; the offset applies to each type.
+			// When streaming, each type's hits are sent as soon as it is fetched.
+			let results_per_type = max_results.div_ceil(3);
+			let mut code_results = store
+				.get_code_blocks_with_filters(
 					code_embeddings,
-					Some(results_per_type),
+					Some(results_per_type + 1),
+					offset,
 					Some(distance_threshold),
 					language_filter,
+					symbol_filter,
+					content_filter,
 				)
 				.await?;
-			let text_results = store
-				.get_text_blocks_with_config(
+			let code_more = take_page(&mut code_results, 0, results_per_type);
+			if exclude_tests {
+				drop_test_results(&mut code_results, &mut Vec::new(), &mut Vec::new());
+			}
+			stream_hits(partial, &code_results, |hits| {
+				format_code_search_results_as_text(hits, detail_level)
+			});
+
+			let mut doc_results = store
+				.get_document_blocks_with_offset(
 					text_embeddings.clone(),
-					Some(results_per_type),
+					Some(results_per_type + 1),
+					offset,
 					Some(distance_threshold),
 				)
 				.await?;
-			let doc_results = store
-				.get_document_blocks_with_config(
+			let doc_more = take_page(&mut doc_results, 0, results_per_type);
+			if exclude_tests {
+				drop_test_results(&mut Vec::new(), &mut doc_results, &mut Vec::new());
+			}
+			stream_hits(partial, &doc_results, |hits| {
+				format_doc_search_results_as_text(hits, detail_level)
+			});
+
+			let mut text_results = store
+				.get_text_blocks_with_offset(
 					text_embeddings,
-					Some(results_per_type),
+					Some(results_per_type + 1),
+					offset,
 					Some(distance_threshold),
 				)
 				.await?;
+			let text_more = take_page(&mut text_results, 0, results_per_type);
+			if exclude_tests {
+				drop_test_results(&mut Vec::new(), &mut Vec::new(), &mut text_results);
+			}
+			stream_hits(partial, &text_results, |hits| {
+				format_text_search_results_as_text(hits, detail_level)
+			});
 
 			// Format combined results with detail level for code
-			Ok(format_combined_search_results_as_text(
-				&code_results,
-				&text_results,
-				&doc_results,
-				detail_level,
-			))
+			Ok(SearchPage {
+				output: format_combined_search_results_as_text(
+					&code_results,
+					&text_results,
+					&doc_results,
+					detail_level,
+				),
+				next_offset: (code_more || text_more || doc_more)
+					.then_some(offset.saturating_add(results_per_type)),
+			})
 		}
 		_ => Err(anyhow::anyhow!(
 			"Invalid search mode '{}'. Use 'all', 'code', 'docs', or 'text'.",
@@ -1175,17 +1372,60 @@ pub async fn search_codebase_with_details_text(
 }
 
 // Enhanced search function for MCP server with multi-query support and detail level control - returns text results
+#[allow(clippy::too_many_arguments)]
 pub async fn search_codebase_with_details_multi_query_text(
 	queries: &[String],
 	mode: &str,
 	detail_level: &str,
 	max_results: usize,
+	offset: usize,
 	similarity_threshold: f32,
 	language_filter: Option<&str>,
+	symbol_filter: Option<&SymbolFilter>,
+	content_filter: Option<&ContentFilter>,
+	path_filter: Option<&PathFilter>,
+	exclude_tests: bool,
 	config: &Config,
-) -> Result<String> {
-	// Initialize store
-	let store = Store::new().await?;
+) -> Result<SearchPage> {
+	search_queries_text(
+		queries,
+		mode,
+		detail_level,
+		max_results,
+		offset,
+		similarity_threshold,
+		language_filter,
+		symbol_filter,
+		content_filter,
+		path_filter,
+		exclude_tests,
+		config,
+		false,
+	)
+	.await
+}
+
+/// Search several queries and merge their rankings: with multi-query bonuses
+/// for queries given by the caller, or with reciprocal rank fusion for a
+/// query and its reformulations (`fuse`)
+#[allow(clippy::too_many_arguments)]
+async fn search_queries_text(
+	queries: &[String],
+	mode: &str,
+	detail_level: &str,
+	max_results: usize,
+	offset: usize,
+	similarity_threshold: f32,
+	language_filter: Option<&str>,
+	symbol_filter: Option<&SymbolFilter>,
+	content_filter: Option<&ContentFilter>,
+	path_filter: Option<&PathFilter>,
+	exclude_tests: bool,
+	config: &Config,
+	fuse: bool,
+) -> Result<SearchPage> {
+	// Initialize store, limited to the requested paths
+	let store = Store::new().await?.with_path_filter(path_filter.cloned());
 
 	// Validate queries (same as CLI)
 	if queries.is_empty() {
@@ -1212,48 +1452,59 @@ pub async fn search_codebase_with_details_multi_query_text(
 	// Convert similarity threshold to distance threshold for store operations
 	let distance_threshold = 1.0 - similarity_threshold;
 
-	// Execute parallel searches - Pass original similarity_threshold, conversion happens inside
+	// Execute parallel searches - Pass original similarity_threshold, conversion happens inside.
+	// Merged rankings differ from per-query ones, so every page is cut from
+	// results fetched from the top, plus one to tell whether another page follows.
 	let search_results = execute_parallel_searches(
 		&store,
 		query_embeddings,
 		mode,
-		max_results,
+		offset.saturating_add(max_results).saturating_add(1),
 		similarity_threshold, // Pass original similarity_threshold
 		language_filter,
+		symbol_filter,
+		content_filter,
+		exclude_tests,
 	)
 	.await?;
 
-	// Deduplicate and merge with multi-query bonuses
-	let (mut code_blocks, mut doc_blocks, mut text_blocks) =
-		deduplicate_and_merge_results(search_results, queries, distance_threshold);
+	let (mut code_blocks, mut doc_blocks, mut text_blocks) = if fuse {
+		fuse_results(search_results, distance_threshold)
+	} else {
+		// Deduplicate and merge with multi-query bonuses
+		deduplicate_and_merge_results(search_results, queries, distance_threshold)
+	};
 
-	// Apply global result limits
-	code_blocks.truncate(max_results);
-	doc_blocks.truncate(max_results);
-	text_blocks.truncate(max_results);
+	// Apply global result limits to the requested page
+	let code_more = take_page(&mut code_blocks, offset, max_results);
+	let doc_more = take_page(&mut doc_blocks, offset, max_results);
+	let text_more = take_page(&mut text_blocks, offset, max_results);
+	let next_offset =
+		(code_more || doc_more || text_more).then_some(offset.saturating_add(max_results));
 
 	// Format results based on mode with detail level control
-	match mode {
-		"code" => Ok(format_code_search_results_as_text(
-			&code_blocks,
-			detail_level,
-		)),
-		"text" => Ok(format_text_search_results_as_text(
-			&text_blocks,
-			detail_level,
-		)),
-		"docs" => Ok(format_doc_search_results_as_text(&doc_blocks, detail_level)),
-		"all" => Ok(format_combined_search_results_as_text(
+	let output = match mode {
+		"code" => format_code_search_results_as_text(&code_blocks, detail_level),
+		"text" => format_text_search_results_as_text(&text_blocks, detail_level),
+		"docs" => format_doc_search_results_as_text(&doc_blocks, detail_level),
+		"all" => format_combined_search_results_as_text(
 			&code_blocks,
 			&text_blocks,
 			&doc_blocks,
 			detail_level,
-		)),
-		_ => Err(anyhow::anyhow!(
-			"Invalid search mode '{}'. Use 'all', 'code', 'docs', or 'text'.",
-			mode
-		)),
-	}
+		),
+		_ => {
+			return Err(anyhow::anyhow!(
+				"Invalid search mode '{}'. Use 'all', 'code', 'docs', or 'text'.",
+				mode
+			))
+		}
+	};
+
+	Ok(SearchPage {
+		output,
+		next_offset,
+	})
 }
 
 // Get a clean preview of code content by skipping comments and showing key parts
@@ -2107,6 +2358,7 @@ pub async fn generate_batch_embeddings_for_queries(
 	}
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn execute_single_search_with_embeddings(
 	store: &Store,
 	embeddings: crate::embedding::SearchModeEmbeddings,
@@ -2115,10 +2367,20 @@ pub async fn execute_single_search_with_embeddings(
 	query_index: usize,
 	similarity_threshold: f32,
 	language_filter: Option<&str>,
+	symbol_filter: Option<&SymbolFilter>,
+	content_filter: Option<&ContentFilter>,
+	exclude_tests: bool,
 ) -> Result<QuerySearchResult> {
 	// Convert similarity threshold to distance threshold for store operations
 	let distance_threshold = 1.0 - similarity_threshold;
 
+	// Over-fetch when test code is dropped below so the limit still fills up
+	let per_query_limit = if exclude_tests {
+		per_query_limit * 2
+	} else {
+		per_query_limit
+	};
+
 	let mut code_blocks = Vec::new();
 	let mut doc_blocks = Vec::new();
 	let mut text_blocks = Vec::new();
@@ -2127,11 +2389,14 @@ pub async fn execute_single_search_with_embeddings(
 		"code" => {
 			if let Some(code_emb) = embeddings.code_embeddings {
 				code_blocks = store
-					.get_code_blocks_with_language_filter(
+					.get_code_blocks_with_filters(
 						code_emb,
 						Some(per_query_limit),
+						0,
 						Some(distance_threshold),
 						language_filter,
+						symbol_filter,
+						content_filter,
 					)
 					.await?;
 			}
@@ -2163,11 +2428,14 @@ pub async fn execute_single_search_with_embeddings(
 
 			if let Some(code_emb) = embeddings.code_embeddings {
 				code_blocks = store
-					.get_code_blocks_with_language_filter(
+					.get_code_blocks_with_filters(
 						code_emb,
 						Some(results_per_type),
+						0,
 						Some(distance_threshold),
 						language_filter,
+						symbol_filter,
+						content_filter,
 					)
 					.await?;
 			}
@@ -2195,6 +2463,10 @@ pub async fn execute_single_search_with_embeddings(
 		_ => return Err(anyhow::anyhow!("Invalid search mode: {}", mode)),
 	}
 
+	if exclude_tests {
+		drop_test_results(&mut code_blocks, &mut doc_blocks, &mut text_blocks);
+	}
+
 	Ok(QuerySearchResult {
 		query_index,
 		code_blocks,
@@ -2203,6 +2475,7 @@ pub async fn execute_single_search_with_embeddings(
 	})
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn execute_parallel_searches(
 	store: &Store,
 	query_embeddings: Vec<(String, crate::embedding::SearchModeEmbeddings)>,
@@ -2210,6 +2483,9 @@ pub async fn execute_parallel_searches(
 	max_results: usize,
 	similarity_threshold: f32,
 	language_filter: Option<&str>,
+	symbol_filter: Option<&SymbolFilter>,
+	content_filter: Option<&ContentFilter>,
+	exclude_tests: bool,
 ) -> Result<Vec<QuerySearchResult>> {
 	let per_query_limit = (max_results * 2) / query_embeddings.len().max(1);
 
@@ -2225,6 +2501,9 @@ pub async fn execute_parallel_searches(
 				index,
 				similarity_threshold,
 				language_filter,
+				symbol_filter,
+				content_filter,
+				exclude_tests,
 			)
 			.await
 		})
@@ -2234,6 +2513,17 @@ pub async fn execute_parallel_searches(
 	futures::future::try_join_all(search_futures).await
 }
 
+/// Drop results that come from test files or inline test code
+pub fn drop_test_results(
+	code_blocks: &mut Vec<CodeBlock>,
+	doc_blocks: &mut Vec<DocumentBlock>,
+	text_blocks: &mut Vec<TextBlock>,
+) {
+	code_blocks.retain(|block| !test_classifier::is_test_block(block));
+	doc_blocks.retain(|block| !test_classifier::is_test_path(&block.path));
+	text_blocks.retain(|block| !test_classifier::is_test_path(&block.path));
+}
+
 pub fn apply_multi_query_bonus_code(
 	block: &mut crate::store::CodeBlock,
 	query_indices: &[usize],
@@ -2422,3 +2712,181 @@ pub fn deduplicate_and_merge_results(
 
 	(final_code_blocks, final_doc_blocks, final_text_blocks)
 }
+
+/// Rank constant of reciprocal rank fusion; larger values flatten the
+/// advantage of top ranks
+const RRF_K: f32 = 60.0;
+
+/// Merge the rankings of a query and its reformulations with reciprocal rank
+/// fusion: a block scores `1 / (RRF_K + rank)` for every query that found it,
+/// so blocks found by several phrasings rise above one-off matches. Blocks
+/// keep their best distance, which the threshold is applied to.
+pub fn fuse_results(
+	search_results: Vec<QuerySearchResult>,
+	distance_threshold: f32,
+) -> (
+	Vec<crate::store::CodeBlock>,
+	Vec<crate::store::DocumentBlock>,
+	Vec<crate::store::TextBlock>,
+) {
+	let code = fuse_rankings(
+		search_results.iter().map(|result| &result.code_blocks[..]),
+		distance_threshold,
+		|block| (&block.hash, block.distance),
+	);
+	let docs = fuse_rankings(
+		search_results.iter().map(|result| &result.doc_blocks[..]),
+		distance_threshold,
+		|block| (&block.hash, block.distance),
+	);
+	let text = fuse_rankings(
+		search_results.iter().map(|result| &result.text_blocks[..]),
+		distance_threshold,
+		|block| (&block.hash, block.distance),
+	);
+	(code, docs, text)
+}
+
+fn fuse_rankings<'a, T: Clone + 'a>(
+	rankings: impl Iterator<Item = &'a [T]>,
+	distance_threshold: f32,
+	key: impl Fn(&T) -> (&String, Option<f32>),
+) -> Vec<T> {
+	use std::collections::HashMap;
+
+	// Fused score and best-distance copy of each block, in first-seen order
+	let mut fused: Vec<(f32, T)> = Vec::new();
+	let mut positions: HashMap<String, usize> = HashMap::new();
+
+	for ranking in rankings {
+		for (rank, block) in ranking.iter().enumerate() {
+			let (hash, distance) = key(block);
+			let score = 1.0 / (RRF_K + rank as f32 + 1.0);
+			match positions.get(hash) {
+				Some(&position) => {
+					let entry = &mut fused[position];
+					entry.0 += score;
+					let best = key(&entry.1).1;
+					if distance.is_some() && (best.is_none() || distance < best) {
+						entry.1 = block.clone();
+					}
+				}
+				None => {
+					positions.insert(hash.clone(), fused.len());
+					fused.push((score, block.clone()));
+				}
+			}
+		}
+	}
+
+	fused.retain(|(_, block)| {
+		key(block)
+			.1
+			.is_none_or(|distance| distance <= distance_threshold)
+	});
+	// Stable sort, so ties keep the order of the original query
+	fused.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+	fused.into_iter().map(|(_, block)| block).collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_take_page_and_cursor() {
+		let mut results: Vec<usize> = (0..7).collect();
+		assert!(take_page(&mut results, 2, 3));
+		assert_eq!(results, vec![2, 3, 4]);
+
+		let mut results: Vec<usize> = (0..5).collect();
+		assert!(!take_page(&mut results, 2, 3));
+		assert_eq!(results, vec![2, 3, 4]);
+
+		let mut results: Vec<usize> = (0..2).collect();
+		assert!(!take_page(&mut results, 4, 3));
+		assert!(results.is_empty());
+
+		assert_eq!(decode_cursor(&encode_cursor(40), 1000).unwrap(), 40);
+		assert_eq!(decode_cursor(&encode_cursor(1000), 1000).unwrap(), 1000);
+
+		// Malformed cursors
+		for cursor in ["40", "o", "o-1", "ox", "o1.5", ""] {
+			assert!(decode_cursor(cursor, 1000).is_err(), "{:?}", cursor);
+		}
+
+		// Oversized cursors, including ones that overflow usize
+		let err = decode_cursor("o1001", 1000).unwrap_err();
+		assert!(err.to_string().contains("search.max_offset"), "{}", err);
+		assert!(decode_cursor(&encode_cursor(usize::MAX), 1000).is_err());
+		assert!(decode_cursor("o18446744073709551616", 1000).is_err());
+	}
+
+	#[test]
+	fn test_stream_hits() {
+		let (partial, mut partial_rx) = tokio::sync::mpsc::unbounded_channel();
+		let hits = vec!["a", "b", "c"];
+		stream_hits(Some(&partial), &hits, |hits| hits.join(","));
+		stream_hits(None, &hits, |hits| hits.join(","));
+		drop(partial);
+
+		let mut sent = Vec::new();
+		while let Ok(message) = partial_rx.try_recv() {
+			sent.push(message);
+		}
+		assert_eq!(sent, vec!["a", "b", "c"]);
+	}
+
+	#[test]
+	fn test_fuse_results() {
+		let block = |hash: &str, distance: f32| CodeBlock {
+			path: format!("src/{}.rs", hash),
+			hash: hash.to_string(),
+			language: "rust".to_string(),
+			content: String::new(),
+			symbols: Vec::new(),
+			start_line: 0,
+			end_line: 1,
+			spans: Vec::new(),
+			provenance: None,
+			distance: Some(distance),
+		};
+		let result = |query_index: usize, code_blocks: Vec<CodeBlock>| QuerySearchResult {
+			query_index,
+			code_blocks,
+			doc_blocks: Vec::new(),
+			text_blocks: Vec::new(),
+		};
+
+		// "b" is second for both queries and beats the two one-off winners
+		let (code, _, _) = fuse_results(
+			vec![
+				result(0, vec![block("a", 0.1), block("b", 0.3), block("far", 0.9)]),
+				result(1, vec![block("c", 0.2), block("b", 0.25)]),
+			],
+			0.5,
+		);
+		let order: Vec<&str> = code.iter().map(|block| block.hash.as_str()).collect();
+		assert_eq!(order, vec!["b", "a", "c"]);
+		assert_eq!(code[0].distance, Some(0.25));
+	}
+
+	#[test]
+	fn test_scored_json() {
+		let blocks = vec![
+			serde_json::json!({"path": "a.md", "distance": 0.25}),
+			serde_json::json!({"path": "b.md", "distance": 1.6}),
+			serde_json::json!({"path": "c.md"}),
+		];
+		let scored = scored_json(&blocks).unwrap();
+		assert_eq!(
+			scored[0],
+			serde_json::json!({"path": "a.md", "distance": 0.25, "score": 0.75})
+		);
+		assert_eq!(
+			scored[1],
+			serde_json::json!({"path": "b.md", "distance": 1.6, "score": 0.0})
+		);
+		assert_eq!(scored[2], serde_json::json!({"path": "c.md"}));
+	}
+}
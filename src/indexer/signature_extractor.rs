@@ -22,7 +22,7 @@ use anyhow::Result;
 use serde::Serialize;
 use std::fs;
 use std::path::{Path, PathBuf};
-use tree_sitter::{Node, Parser};
+use tree_sitter::{Node, Parser, Query, QueryCursor, StreamingIterator};
 
 use crate::indexer::{languages, path_utils};
 
@@ -59,46 +59,10 @@ pub fn extract_file_signatures(files: &[PathBuf]) -> Result<Vec<FileSignature>>
 				// Create a relative path for display using our utility
 				let display_path = path_utils::PathUtils::for_display(file_path, &current_dir);
 
-				// Handle markdown files specially (no tree-sitter parsing)
-				if language == "markdown" {
-					let signatures = extract_markdown_signatures(&contents);
-					let file_comment = extract_markdown_file_comment(&contents);
-
-					all_signatures.push(FileSignature {
-						path: display_path,
-						language: "markdown".to_string(),
-						file_comment,
-						signatures,
-					});
-				} else {
-					// Get the language implementation
-					let lang_impl = match languages::get_language(language) {
-						Some(impl_) => impl_,
-						None => continue, // Skip unsupported languages
-					};
-
-					// Set the parser language
-					parser.set_language(&lang_impl.get_ts_language())?;
-
-					// Parse the file
-					let tree = parser
-						.parse(&contents, None)
-						.unwrap_or_else(|| parser.parse("", None).unwrap());
-
-					// Extract signatures from the file
-					let signatures =
-						extract_signatures(tree.root_node(), &contents, lang_impl.as_ref());
-
-					// Extract file-level comment if present
-					let file_comment = extract_file_comment(tree.root_node(), &contents);
-
-					// Add to our results
-					all_signatures.push(FileSignature {
-						path: display_path,
-						language: lang_impl.name().to_string(),
-						file_comment,
-						signatures,
-					});
+				if let Some(file_signature) =
+					signatures_for_contents(&mut parser, display_path, language, &contents)?
+				{
+					all_signatures.push(file_signature);
 				}
 			}
 		}
@@ -107,6 +71,233 @@ pub fn extract_file_signatures(files: &[PathBuf]) -> Result<Vec<FileSignature>>
 	Ok(all_signatures)
 }
 
+/// Extract signatures from contents that need not be on disk, such as a file
+/// at an older revision. Returns `None` for unsupported languages.
+pub fn extract_signatures_from_contents(
+	path: &str,
+	contents: &str,
+) -> Result<Option<FileSignature>> {
+	match detect_language(Path::new(path)) {
+		Some(language) => {
+			signatures_for_contents(&mut Parser::new(), path.to_string(), language, contents)
+		}
+		None => Ok(None),
+	}
+}
+
+/// A function or method with the names it calls
+#[derive(Debug, Serialize, Clone)]
+pub struct FunctionCalls {
+	pub name: String,
+	/// First line of the declaration
+	pub signature: String,
+	pub start_line: usize,
+	pub end_line: usize,
+	/// Names called in the body, in order of first call. Calls inside a
+	/// nested function belong to that function.
+	pub calls: Vec<String>,
+}
+
+/// Functions of a file with the calls each one makes, found with the
+/// language's tree-sitter call query. Empty for languages without one.
+pub fn extract_function_calls(path: &str, contents: &str) -> Result<Vec<FunctionCalls>> {
+	let Some(lang_impl) = detect_language(Path::new(path)).and_then(languages::get_language) else {
+		return Ok(Vec::new());
+	};
+	let Some(query_source) = lang_impl.call_query() else {
+		return Ok(Vec::new());
+	};
+
+	let ts_language = lang_impl.get_ts_language();
+	let mut parser = Parser::new();
+	parser.set_language(&ts_language)?;
+	let Some(tree) = parser.parse(contents, None) else {
+		return Ok(Vec::new());
+	};
+
+	let mut functions: Vec<FunctionCalls> =
+		extract_signatures(tree.root_node(), contents, lang_impl.as_ref())
+			.into_iter()
+			.filter(|item| item.kind == "function" || item.kind == "method")
+			.map(|item| FunctionCalls {
+				signature: item
+					.signature
+					.lines()
+					.next()
+					.unwrap_or_default()
+					.trim()
+					.trim_end_matches('{')
+					.trim_end()
+					.to_string(),
+				name: item.name,
+				start_line: item.start_line,
+				end_line: item.end_line,
+				calls: Vec::new(),
+			})
+			.collect();
+
+	let query = Query::new(&ts_language, query_source)
+		.map_err(|e| anyhow::anyhow!("Invalid {} call query: {}", lang_impl.name(), e))?;
+	let mut cursor = QueryCursor::new();
+	let mut matches = cursor.matches(&query, tree.root_node(), contents.as_bytes());
+	while let Some(found) = matches.next() {
+		for capture in found.captures {
+			let Ok(name) = capture.node.utf8_text(contents.as_bytes()) else {
+				continue;
+			};
+			let row = capture.node.start_position().row;
+			// The innermost function around the call makes it
+			let caller = functions
+				.iter_mut()
+				.filter(|function| function.start_line <= row && row <= function.end_line)
+				.min_by_key(|function| function.end_line - function.start_line);
+			if let Some(caller) = caller {
+				if !caller.calls.iter().any(|call| call == name) {
+					caller.calls.push(name.to_string());
+				}
+			}
+		}
+	}
+
+	Ok(functions)
+}
+
+/// Narrows extracted signatures down for targeted inspection
+#[derive(Debug, Clone, Default)]
+pub struct SignatureFilter {
+	/// Names to keep: a case-insensitive glob (`parse_*`), or a substring
+	/// when the pattern has no wildcard
+	symbol: Option<globset::GlobMatcher>,
+	/// 1-based inclusive line range items must overlap
+	lines: Option<(usize, usize)>,
+}
+
+impl SignatureFilter {
+	pub fn new(symbol: Option<&str>, lines: Option<(usize, usize)>) -> Result<Self> {
+		let symbol = match symbol {
+			Some(pattern) => {
+				let pattern = if pattern.contains(['*', '?', '[']) {
+					pattern.to_string()
+				} else {
+					format!("*{}*", pattern)
+				};
+				let glob = globset::GlobBuilder::new(&pattern)
+					.case_insensitive(true)
+					.build()
+					.map_err(|e| anyhow::anyhow!("Invalid symbol pattern '{}': {}", pattern, e))?;
+				Some(glob.compile_matcher())
+			}
+			None => None,
+		};
+		Ok(Self { symbol, lines })
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.symbol.is_none() && self.lines.is_none()
+	}
+
+	pub fn matches(&self, item: &SignatureItem) -> bool {
+		if let Some(symbol) = &self.symbol {
+			if !symbol.is_match(&item.name) {
+				return false;
+			}
+		}
+		match self.lines {
+			// Item lines are 0-based
+			Some((first, last)) => item.start_line < last && item.end_line + 1 >= first,
+			None => true,
+		}
+	}
+
+	/// Keep matching signatures; files left without any are dropped
+	pub fn apply(&self, files: Vec<FileSignature>) -> Vec<FileSignature> {
+		if self.is_empty() {
+			return files;
+		}
+		files
+			.into_iter()
+			.filter_map(|mut file| {
+				file.signatures.retain(|item| self.matches(item));
+				(!file.signatures.is_empty()).then_some(file)
+			})
+			.collect()
+	}
+}
+
+/// Parse a 1-based line range: `10:20`, `10:` (to the end), `:20` or `15`
+pub fn parse_line_range(spec: &str) -> Result<(usize, usize), String> {
+	let parse = |value: &str, default: usize| -> Result<usize, String> {
+		if value.trim().is_empty() {
+			return Ok(default);
+		}
+		value
+			.trim()
+			.parse::<usize>()
+			.map_err(|_| format!("'{}' is not a line number", value))
+	};
+	let (first, last) = match spec.split_once(':') {
+		Some((first, last)) => (parse(first, 1)?, parse(last, usize::MAX)?),
+		None => {
+			let line = parse(spec, 0)?;
+			(line, line)
+		}
+	};
+	if first == 0 || first > last {
+		return Err(format!(
+			"invalid line range '{}': expected A:B with 1 <= A <= B",
+			spec
+		));
+	}
+	Ok((first, last))
+}
+
+fn signatures_for_contents(
+	parser: &mut Parser,
+	display_path: String,
+	language: &str,
+	contents: &str,
+) -> Result<Option<FileSignature>> {
+	// Handle markdown files specially (no tree-sitter parsing)
+	if language == "markdown" {
+		let signatures = extract_markdown_signatures(contents);
+		let file_comment = extract_markdown_file_comment(contents);
+
+		return Ok(Some(FileSignature {
+			path: display_path,
+			language: "markdown".to_string(),
+			file_comment,
+			signatures,
+		}));
+	}
+
+	// Get the language implementation
+	let lang_impl = match languages::get_language(language) {
+		Some(impl_) => impl_,
+		None => return Ok(None), // Skip unsupported languages
+	};
+
+	// Set the parser language
+	parser.set_language(&lang_impl.get_ts_language())?;
+
+	// Parse the file
+	let tree = parser
+		.parse(contents, None)
+		.unwrap_or_else(|| parser.parse("", None).unwrap());
+
+	// Extract signatures from the file
+	let signatures = extract_signatures(tree.root_node(), contents, lang_impl.as_ref());
+
+	// Extract file-level comment if present
+	let file_comment = extract_file_comment(tree.root_node(), contents);
+
+	Ok(Some(FileSignature {
+		path: display_path,
+		language: lang_impl.name().to_string(),
+		file_comment,
+		signatures,
+	}))
+}
+
 /// Extract signatures from a parsed file
 fn extract_signatures(
 	node: Node,
@@ -439,3 +630,99 @@ fn extract_markdown_file_comment(contents: &str) -> Option<String> {
 		Some(comment_lines.join(" ").trim().to_string())
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn item(name: &str, start_line: usize, end_line: usize) -> SignatureItem {
+		SignatureItem {
+			kind: "function".to_string(),
+			name: name.to_string(),
+			signature: format!("fn {}() {{}}", name),
+			description: None,
+			start_line,
+			end_line,
+		}
+	}
+
+	#[test]
+	fn test_extract_function_calls() {
+		let rust = "fn main() {\n\tlet config = Config::load();\n\trun(&config);\n\t// skip()\n}\n\nfn run(config: &Config) {\n\tconfig.validate();\n\tparse::<u8>(\"1\");\n\trun(config);\n}\n";
+		let functions = extract_function_calls("src/main.rs", rust).unwrap();
+		let calls: Vec<(&str, Vec<&str>)> = functions
+			.iter()
+			.map(|f| {
+				(
+					f.name.as_str(),
+					f.calls.iter().map(String::as_str).collect(),
+				)
+			})
+			.collect();
+		assert_eq!(
+			calls,
+			vec![
+				("main", vec!["load", "run"]),
+				("run", vec!["validate", "parse", "run"]),
+			]
+		);
+		assert_eq!(functions[1].signature, "fn run(config: &Config)");
+
+		let python =
+			"def handle(request):\n    data = parse(request)\n    return self.render(data)\n";
+		let functions = extract_function_calls("app/views.py", python).unwrap();
+		assert_eq!(functions[0].calls, vec!["parse", "render"]);
+
+		assert!(extract_function_calls("style.css", "a { color: red; }")
+			.unwrap()
+			.is_empty());
+	}
+
+	#[test]
+	fn test_parse_line_range() {
+		assert_eq!(parse_line_range("10:20"), Ok((10, 20)));
+		assert_eq!(parse_line_range("10:"), Ok((10, usize::MAX)));
+		assert_eq!(parse_line_range(":20"), Ok((1, 20)));
+		assert_eq!(parse_line_range("15"), Ok((15, 15)));
+		assert!(parse_line_range("20:10").is_err());
+		assert!(parse_line_range("0:5").is_err());
+		assert!(parse_line_range("a:b").is_err());
+	}
+
+	#[test]
+	fn test_signature_filter() {
+		let file = FileSignature {
+			path: "src/lib.rs".to_string(),
+			language: "rust".to_string(),
+			file_comment: None,
+			signatures: vec![
+				item("parse_config", 0, 9),
+				item("ConfigLoader", 11, 30),
+				item("render", 32, 40),
+			],
+		};
+
+		let names = |filter: SignatureFilter| -> Vec<String> {
+			filter
+				.apply(vec![file.clone()])
+				.iter()
+				.flat_map(|file| file.signatures.iter().map(|item| item.name.clone()))
+				.collect()
+		};
+
+		let by_symbol = SignatureFilter::new(Some("config"), None).unwrap();
+		assert_eq!(names(by_symbol), vec!["parse_config", "ConfigLoader"]);
+
+		let by_glob = SignatureFilter::new(Some("parse_*"), None).unwrap();
+		assert_eq!(names(by_glob), vec!["parse_config"]);
+
+		// Line 10 is the last line of parse_config; lines 12-33 touch the other two
+		let by_lines = SignatureFilter::new(None, Some((10, 10))).unwrap();
+		assert_eq!(names(by_lines), vec!["parse_config"]);
+		let by_lines = SignatureFilter::new(None, Some((12, 33))).unwrap();
+		assert_eq!(names(by_lines), vec!["ConfigLoader", "render"]);
+
+		let none = SignatureFilter::new(Some("missing"), None).unwrap();
+		assert!(none.apply(vec![file.clone()]).is_empty());
+	}
+}
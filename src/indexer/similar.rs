@@ -0,0 +1,174 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Code similar to an existing block
+//!
+//! `octocode search --like src/a.rs:10-40` and the MCP `find_similar` tool
+//! embed the lines as they are on disk, the same way indexed blocks were
+//! embedded, and return the closest code blocks elsewhere in the index:
+//! copies and near-copies that may have drifted apart.
+
+use std::fmt;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use crate::config::Config;
+use crate::embedding::types::InputType;
+use crate::indexer::file_summary::OVERVIEW_PREFIX;
+use crate::store::{CodeBlock, Store};
+
+/// Blocks fetched besides the requested ones, as those overlapping the
+/// referenced lines are dropped
+const OVERLAP_MARGIN: usize = 10;
+
+/// Lines of a file, 1-indexed and inclusive, written `path:start-end`
+#[derive(Debug, Clone, PartialEq)]
+pub struct CodeRange {
+	pub path: String,
+	pub start_line: usize,
+	pub end_line: usize,
+}
+
+impl CodeRange {
+	/// Parse `path:start-end`, or `path:line` for a single line
+	pub fn parse(spec: &str) -> Result<Self> {
+		let invalid = || {
+			anyhow::anyhow!(
+				"Invalid range '{}'. Use <file>:<start>-<end>, e.g. src/main.rs:10-40",
+				spec
+			)
+		};
+		let (path, lines) = spec.rsplit_once(':').ok_or_else(invalid)?;
+		let (start, end) = lines.split_once('-').unwrap_or((lines, lines));
+		let start_line: usize = start.trim().parse().map_err(|_| invalid())?;
+		let end_line: usize = end.trim().parse().map_err(|_| invalid())?;
+		let path = path.trim().trim_start_matches("./");
+		if path.is_empty() || start_line == 0 || end_line < start_line {
+			return Err(invalid());
+		}
+		Ok(Self {
+			path: path.to_string(),
+			start_line,
+			end_line,
+		})
+	}
+
+	/// The lines as they are in the file under `root`
+	pub fn read(&self, root: &Path) -> Result<String> {
+		let contents = std::fs::read_to_string(root.join(&self.path))
+			.with_context(|| format!("Failed to read {}", self.path))?;
+		let lines: Vec<&str> = contents
+			.lines()
+			.skip(self.start_line - 1)
+			.take(self.end_line - self.start_line + 1)
+			.collect();
+		if lines.iter().all(|line| line.trim().is_empty()) {
+			return Err(anyhow::anyhow!(
+				"{} has no code at lines {}-{}",
+				self.path,
+				self.start_line,
+				self.end_line
+			));
+		}
+		Ok(lines.join("\n"))
+	}
+
+	/// Whether an indexed block shares lines with this range
+	fn overlaps(&self, block: &CodeBlock) -> bool {
+		block.path == self.path
+			&& block.start_line < self.end_line
+			&& block.end_line + 1 >= self.start_line
+	}
+}
+
+impl fmt::Display for CodeRange {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}:{}-{}", self.path, self.start_line, self.end_line)
+	}
+}
+
+/// Code blocks most similar to the lines of `range`, closest first, leaving
+/// out the blocks those lines belong to and file overviews
+pub async fn find_similar(
+	store: &Store,
+	range: &CodeRange,
+	root: &Path,
+	limit: usize,
+	similarity_threshold: f32,
+	config: &Config,
+) -> Result<Vec<CodeBlock>> {
+	let content = range.read(root)?;
+	let embedding = crate::embedding::generate_embeddings_batch(
+		vec![content],
+		true,
+		config,
+		InputType::Document,
+	)
+	.await?
+	.into_iter()
+	.next()
+	.ok_or_else(|| anyhow::anyhow!("No embedding generated for {}", range))?;
+
+	let mut blocks = store
+		.get_code_blocks_with_filters(
+			embedding,
+			Some(limit + OVERLAP_MARGIN),
+			0,
+			Some(1.0 - similarity_threshold),
+			None,
+			None,
+			None,
+		)
+		.await?;
+	blocks.retain(|block| !range.overlaps(block) && !block.content.starts_with(OVERVIEW_PREFIX));
+	blocks.truncate(limit);
+	Ok(blocks)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_code_range() {
+		let range = CodeRange::parse("./src/main.rs:10-40").unwrap();
+		assert_eq!(range.path, "src/main.rs");
+		assert_eq!((range.start_line, range.end_line), (10, 40));
+		assert_eq!(range.to_string(), "src/main.rs:10-40");
+		assert_eq!(CodeRange::parse("a.py:7").unwrap().end_line, 7);
+		assert!(CodeRange::parse("src/main.rs").is_err());
+		assert!(CodeRange::parse("src/main.rs:40-10").is_err());
+		assert!(CodeRange::parse("src/main.rs:0-3").is_err());
+
+		// Stored lines are 0-indexed
+		let block = |path: &str, start_line: usize, end_line: usize| CodeBlock {
+			path: path.to_string(),
+			hash: String::new(),
+			language: "rust".to_string(),
+			content: String::new(),
+			symbols: Vec::new(),
+			start_line,
+			end_line,
+			spans: Vec::new(),
+			provenance: None,
+			distance: None,
+		};
+		assert!(range.overlaps(&block("src/main.rs", 39, 60)));
+		assert!(range.overlaps(&block("src/main.rs", 0, 9)));
+		assert!(!range.overlaps(&block("src/main.rs", 40, 60)));
+		assert!(!range.overlaps(&block("src/main.rs", 0, 8)));
+		assert!(!range.overlaps(&block("src/lib.rs", 10, 40)));
+	}
+}
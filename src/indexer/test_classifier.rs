@@ -0,0 +1,157 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test code classification
+//!
+//! Files are recognised by the test conventions of each language
+//! (`*_test.go`, `test_*.py`, `*.spec.ts`, `FooTest.java`, `tests/`
+//! directories, ...). Rust keeps unit tests next to the code, so Rust blocks
+//! are also recognised by their `#[cfg(test)]` / `#[test]` attributes.
+
+use crate::store::CodeBlock;
+
+/// Directories whose contents are test code or test data
+const TEST_DIRS: &[&str] = &[
+	"test",
+	"tests",
+	"__tests__",
+	"spec",
+	"specs",
+	"testdata",
+	"test_data",
+	"fixtures",
+	"__mocks__",
+];
+
+/// Whether a (relative) path holds test code by directory or naming convention
+pub fn is_test_path(path: &str) -> bool {
+	let path = path.replace('\\', "/");
+	let mut segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+	let Some(file_name) = segments.pop() else {
+		return false;
+	};
+
+	if segments
+		.iter()
+		.any(|dir| TEST_DIRS.contains(&dir.to_ascii_lowercase().as_str()))
+	{
+		return true;
+	}
+
+	let (stem, extension) = file_name.rsplit_once('.').unwrap_or((file_name, ""));
+	let lower_stem = stem.to_ascii_lowercase();
+
+	// Shared conventions: foo_test.go, foo_spec.rb, foo.test.ts, foo.spec.js
+	if lower_stem.ends_with("_test")
+		|| lower_stem.ends_with("_tests")
+		|| lower_stem.ends_with("_spec")
+		|| lower_stem.ends_with(".test")
+		|| lower_stem.ends_with(".spec")
+		|| lower_stem.ends_with("_unittest")
+	{
+		return true;
+	}
+
+	match extension {
+		"rs" => lower_stem == "tests",
+		"py" => lower_stem.starts_with("test_") || lower_stem == "conftest",
+		"java" | "kt" | "cs" | "php" | "scala" | "swift" => {
+			stem.ends_with("Test") || stem.ends_with("Tests") || stem.ends_with("IT")
+		}
+		"bats" => true,
+		_ => false,
+	}
+}
+
+/// Whether a block of source is test code living next to production code
+pub fn is_test_code(language: &str, content: &str) -> bool {
+	let mut lines = content.lines().map(str::trim).filter(|l| !l.is_empty());
+	match language {
+		"rust" => lines.any(|line| {
+			line.starts_with("#[cfg(test)]")
+				|| line == "#[test]"
+				|| (line.starts_with("#[") && line.ends_with("::test]"))
+		}),
+		"python" => lines
+			.find(|line| !line.starts_with('@') && !line.starts_with('#'))
+			.is_some_and(|line| {
+				line.starts_with("def test_") || line.starts_with("async def test_")
+			}),
+		"go" => lines
+			.find(|line| !line.starts_with("//"))
+			.is_some_and(|line| {
+				line.starts_with("func Test") || line.starts_with("func Benchmark")
+			}),
+		_ => false,
+	}
+}
+
+pub fn is_test_block(block: &CodeBlock) -> bool {
+	is_test_path(&block.path) || is_test_code(&block.language, &block.content)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_paths_by_language_convention() {
+		for path in [
+			"pkg/server/handler_test.go",
+			"tests/integration.rs",
+			"src/store/tests.rs",
+			"app/test_models.py",
+			"conftest.py",
+			"web/src/Button.test.tsx",
+			"web/src/api.spec.ts",
+			"src/__tests__/utils.js",
+			"src/test/java/com/acme/UserServiceTest.java",
+			"spec/models/user_spec.rb",
+			"testdata/input.json",
+		] {
+			assert!(is_test_path(path), "{} should be a test path", path);
+		}
+
+		for path in [
+			"src/main.rs",
+			"src/testing_utils.rs",
+			"app/models.py",
+			"web/src/Button.tsx",
+			"src/latest.rs",
+			"src/main/java/com/acme/Contest.java",
+			"README.md",
+		] {
+			assert!(!is_test_path(path), "{} should not be a test path", path);
+		}
+	}
+
+	#[test]
+	fn test_inline_test_code() {
+		assert!(is_test_code(
+			"rust",
+			"#[cfg(test)]\nmod tests {\n\tuse super::*;\n}"
+		));
+		assert!(is_test_code(
+			"rust",
+			"#[tokio::test]\nasync fn test_connect() {}"
+		));
+		assert!(!is_test_code("rust", "pub fn parse() -> Result<()> {}"));
+		assert!(is_test_code(
+			"python",
+			"@pytest.mark.slow\ndef test_upload():\n    pass"
+		));
+		assert!(!is_test_code("python", "def upload():\n    pass"));
+		assert!(!is_test_code("javascript", "describe('x', () => {})"));
+	}
+}
@@ -12,6 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::ops::Range;
+
+use crate::config::ChunkStrategy;
+
 /// Text chunk with line information
 #[derive(Debug, Clone)]
 pub struct TextChunkWithLines {
@@ -37,7 +41,7 @@ impl TextProcessor {
 		let mut current_line = 1;
 
 		while start_idx < lines.len() {
-			let mut end_idx = std::cmp::min(start_idx + chunk_size, lines.len());
+			let mut end_idx = std::cmp::min(start_idx + chunk_size.max(1), lines.len());
 			let mut current_content = String::new();
 			let mut char_count = 0;
 
@@ -48,11 +52,11 @@ impl TextProcessor {
 				.skip(start_idx)
 				.take(end_idx - start_idx)
 			{
-				if char_count + line.len() + 1 > chunk_size && !current_content.is_empty() {
-					end_idx = start_idx + idx;
-					break;
-				}
-				if !current_content.is_empty() {
+				if idx > start_idx {
+					if char_count + line.len() + 1 > chunk_size {
+						end_idx = idx;
+						break;
+					}
 					current_content.push('\n');
 					char_count += 1;
 				}
@@ -60,7 +64,7 @@ impl TextProcessor {
 				char_count += line.len();
 			}
 
-			if !current_content.is_empty() {
+			if !current_content.trim().is_empty() {
 				let chunk = TextChunkWithLines {
 					content: current_content,
 					start_line: current_line,
@@ -74,8 +78,10 @@ impl TextProcessor {
 				break;
 			}
 
-			let next_start = if overlap > 0 && end_idx > overlap {
-				end_idx - overlap
+			// Skip the overlap when it would not move past the current chunk's start
+			let next_start = end_idx.saturating_sub(overlap);
+			let next_start = if next_start > start_idx {
+				next_start
 			} else {
 				end_idx
 			};
@@ -86,4 +92,178 @@ impl TextProcessor {
 
 		chunks
 	}
+
+	/// Chunk text with the given strategy
+	///
+	/// `Fixed` is [`TextProcessor::chunk_text`]. The other strategies pack whole
+	/// units (sentences or paragraphs) into chunks of at most `chunk_size`
+	/// characters, or tokens for `Tokens`, and do not overlap.
+	pub fn chunk_text_with_strategy(
+		content: &str,
+		strategy: ChunkStrategy,
+		chunk_size: usize,
+		overlap: usize,
+	) -> Vec<TextChunkWithLines> {
+		let lines: Vec<&str> = content.lines().collect();
+		match strategy {
+			ChunkStrategy::Fixed => Self::chunk_text(content, chunk_size, overlap),
+			ChunkStrategy::Sentence => {
+				let sizes: Vec<usize> = lines.iter().map(|line| line.len() + 1).collect();
+				pack_units(&lines, &sizes, sentence_units(&lines), chunk_size)
+			}
+			ChunkStrategy::Paragraph => {
+				let sizes: Vec<usize> = lines.iter().map(|line| line.len() + 1).collect();
+				pack_units(&lines, &sizes, paragraph_units(&lines), chunk_size)
+			}
+			ChunkStrategy::Tokens => {
+				let bpe = tiktoken_rs::cl100k_base().expect("Failed to load cl100k_base tokenizer");
+				let sizes: Vec<usize> = lines
+					.iter()
+					.map(|line| bpe.encode_with_special_tokens(line).len() + 1)
+					.collect();
+				pack_units(&lines, &sizes, paragraph_units(&lines), chunk_size)
+			}
+		}
+	}
+}
+
+fn is_blank(line: &str) -> bool {
+	line.trim().is_empty()
+}
+
+/// Line ranges that each end a sentence: at terminal punctuation or a blank line
+fn sentence_units(lines: &[&str]) -> Vec<Range<usize>> {
+	let mut units = Vec::new();
+	let mut start = 0;
+	for (idx, line) in lines.iter().enumerate() {
+		let trimmed = line
+			.trim_end()
+			.trim_end_matches(['"', '\'', ')', ']', '*', '_']);
+		if is_blank(line) || trimmed.ends_with(['.', '!', '?']) {
+			units.push(start..idx + 1);
+			start = idx + 1;
+		}
+	}
+	if start < lines.len() {
+		units.push(start..lines.len());
+	}
+	units
+}
+
+/// Line ranges separated by blank lines, or starting a new top-level entry
+///
+/// An unindented line after an indented one opens a new unit, so a YAML key,
+/// TOML table or INI section stays together with its nested lines.
+fn paragraph_units(lines: &[&str]) -> Vec<Range<usize>> {
+	let mut units = Vec::new();
+	let mut start = 0;
+	for idx in 1..lines.len() {
+		let previous = lines[idx - 1];
+		let line = lines[idx];
+		let after_blank = is_blank(previous) && !is_blank(line);
+		let new_section = !is_blank(line)
+			&& !line.starts_with(char::is_whitespace)
+			&& previous.starts_with(char::is_whitespace)
+			&& !is_blank(previous);
+		if after_blank || new_section {
+			units.push(start..idx);
+			start = idx;
+		}
+	}
+	if start < lines.len() {
+		units.push(start..lines.len());
+	}
+	units
+}
+
+/// Pack consecutive units into chunks within `budget`, measured by `sizes` per line
+///
+/// A unit over the budget on its own is split into single lines.
+fn pack_units(
+	lines: &[&str],
+	sizes: &[usize],
+	units: Vec<Range<usize>>,
+	budget: usize,
+) -> Vec<TextChunkWithLines> {
+	let size_of = |range: &Range<usize>| -> usize { sizes[range.clone()].iter().sum() };
+	let units = units.into_iter().flat_map(|unit| {
+		if size_of(&unit) > budget {
+			unit.map(|idx| idx..idx + 1).collect()
+		} else {
+			vec![unit]
+		}
+	});
+
+	let mut chunks = Vec::new();
+	let mut current: Option<Range<usize>> = None;
+	let mut current_size = 0;
+	for unit in units {
+		let unit_size = size_of(&unit);
+		if let Some(range) = current.take_if(|_| current_size + unit_size > budget) {
+			chunks.extend(make_chunk(lines, range));
+			current_size = 0;
+		}
+		current = Some(match current {
+			Some(range) => range.start..unit.end,
+			None => unit,
+		});
+		current_size += unit_size;
+	}
+	if let Some(range) = current {
+		chunks.extend(make_chunk(lines, range));
+	}
+	chunks
+}
+
+/// Chunk for a line range without its leading and trailing blank lines
+fn make_chunk(lines: &[&str], range: Range<usize>) -> Option<TextChunkWithLines> {
+	let start = (range.start..range.end).find(|&idx| !is_blank(lines[idx]))?;
+	let end = (start..range.end)
+		.rev()
+		.find(|&idx| !is_blank(lines[idx]))?
+		+ 1;
+	Some(TextChunkWithLines {
+		content: lines[start..end].join("\n"),
+		start_line: start + 1,
+		end_line: end,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn ranges(chunks: &[TextChunkWithLines]) -> Vec<(usize, usize)> {
+		chunks
+			.iter()
+			.map(|chunk| (chunk.start_line, chunk.end_line))
+			.collect()
+	}
+
+	#[test]
+	fn test_paragraph_strategy_keeps_yaml_entries_whole() {
+		let yaml = "server:\n  host: localhost\n  port: 8080\ndatabase:\n  url: postgres://db\n  pool: 5\n\n# logging\nlogging:\n  level: info\n";
+		let chunks = TextProcessor::chunk_text_with_strategy(yaml, ChunkStrategy::Paragraph, 50, 0);
+		assert_eq!(ranges(&chunks), vec![(1, 3), (4, 6), (8, 10)]);
+		assert_eq!(
+			chunks[1].content,
+			"database:\n  url: postgres://db\n  pool: 5"
+		);
+
+		// Small entries are packed together
+		let chunks =
+			TextProcessor::chunk_text_with_strategy(yaml, ChunkStrategy::Paragraph, 200, 0);
+		assert_eq!(ranges(&chunks), vec![(1, 10)]);
+	}
+
+	#[test]
+	fn test_sentence_strategy_ends_on_sentence_boundaries() {
+		let text = "The first sentence is\nwrapped over two lines.\nThe second sentence\nends here. A third one.\n";
+		let chunks = TextProcessor::chunk_text_with_strategy(text, ChunkStrategy::Sentence, 70, 0);
+		assert_eq!(ranges(&chunks), vec![(1, 2), (3, 4)]);
+
+		// A sentence over the budget falls back to single lines
+		let chunks = TextProcessor::chunk_text_with_strategy(text, ChunkStrategy::Sentence, 30, 0);
+		assert_eq!(ranges(&chunks), vec![(1, 1), (2, 2), (3, 3), (4, 4)]);
+	}
 }
@@ -0,0 +1,233 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Writing embedded blocks to the store during indexing
+//!
+//! Flushes happen every `index.flush_frequency` written batches and, with
+//! `index.flush_interval_ms`, whenever that much time passed since the last
+//! one. With `index.write_queue_size` above zero, embedded batches go to a
+//! background writer through a bounded queue, so the next batch is embedded
+//! while the previous one is written; indexing waits whenever the queue is
+//! full. `index.fsync_on_complete` makes the end of a run wait for every
+//! queued write and fsync the database files it wrote.
+//!
+//! Files are recorded as indexed by a write queued after the batch holding
+//! their blocks, see [`crate::indexer::checkpoint`].
+
+use anyhow::Result;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::config::IndexConfig;
+use crate::store::{CodeBlock, DocumentBlock, Store, TextBlock};
+
+/// Embedded blocks waiting to be written
+pub enum PendingWrite {
+	Code(Vec<CodeBlock>, Vec<Vec<f32>>),
+	Text(Vec<TextBlock>, Vec<Vec<f32>>),
+	Document(Vec<DocumentBlock>, Vec<Vec<f32>>),
+	/// Files whose blocks were written by the previous writes, with their
+	/// modification times, for the run indexing `commit`
+	Files {
+		commit: String,
+		files: Vec<(String, u64)>,
+	},
+}
+
+impl PendingWrite {
+	pub fn is_empty(&self) -> bool {
+		match self {
+			PendingWrite::Code(blocks, _) => blocks.is_empty(),
+			PendingWrite::Text(blocks, _) => blocks.is_empty(),
+			PendingWrite::Document(blocks, _) => blocks.is_empty(),
+			PendingWrite::Files { files, .. } => files.is_empty(),
+		}
+	}
+
+	pub async fn persist(&self, store: &Store) -> Result<()> {
+		match self {
+			PendingWrite::Code(blocks, embeddings) => {
+				store.store_code_blocks(blocks, embeddings).await
+			}
+			PendingWrite::Text(blocks, embeddings) => {
+				store.store_text_blocks(blocks, embeddings).await
+			}
+			PendingWrite::Document(blocks, embeddings) => {
+				store.store_document_blocks(blocks, embeddings).await
+			}
+			PendingWrite::Files { commit, files } => store.store_indexed_files(commit, files).await,
+		}
+	}
+}
+
+/// When written batches are flushed
+#[derive(Debug, Clone)]
+pub struct FlushPolicy {
+	every_batches: usize,
+	interval: Option<Duration>,
+	unflushed: usize,
+	last_flush: Instant,
+}
+
+impl FlushPolicy {
+	pub fn new(config: &IndexConfig) -> Self {
+		Self {
+			every_batches: config.flush_frequency.max(1),
+			interval: (config.flush_interval_ms > 0)
+				.then(|| Duration::from_millis(config.flush_interval_ms)),
+			unflushed: 0,
+			last_flush: Instant::now(),
+		}
+	}
+
+	/// Count a written batch; true when a flush is due
+	pub fn record_batch(&mut self, now: Instant) -> bool {
+		self.unflushed += 1;
+		self.unflushed >= self.every_batches
+			|| self
+				.interval
+				.is_some_and(|interval| now.duration_since(self.last_flush) >= interval)
+	}
+
+	pub fn has_unflushed(&self) -> bool {
+		self.unflushed > 0
+	}
+
+	pub fn flushed(&mut self, now: Instant) {
+		self.unflushed = 0;
+		self.last_flush = now;
+	}
+}
+
+async fn write_and_maybe_flush(
+	store: &Store,
+	policy: &mut FlushPolicy,
+	write: &PendingWrite,
+) -> Result<()> {
+	write.persist(store).await?;
+	// Only batches of blocks count towards a flush
+	if matches!(write, PendingWrite::Files { .. }) {
+		return Ok(());
+	}
+	if policy.record_batch(Instant::now()) {
+		store.flush().await?;
+		policy.flushed(Instant::now());
+	}
+	Ok(())
+}
+
+type Writer = (mpsc::Sender<PendingWrite>, JoinHandle<Result<FlushPolicy>>);
+
+/// Writes embedded batches directly or through the write-behind queue
+pub struct BlockWriter {
+	policy: FlushPolicy,
+	queue: Option<Writer>,
+	fsync_on_complete: bool,
+	started: SystemTime,
+}
+
+impl BlockWriter {
+	pub fn new(store: &Store, config: &IndexConfig) -> Self {
+		let policy = FlushPolicy::new(config);
+		let queue = (config.write_queue_size > 0).then(|| {
+			let (sender, mut receiver) = mpsc::channel::<PendingWrite>(config.write_queue_size);
+			let store = store.clone();
+			let mut policy = policy.clone();
+			let handle = tokio::spawn(async move {
+				while let Some(write) = receiver.recv().await {
+					write_and_maybe_flush(&store, &mut policy, &write).await?;
+				}
+				Ok(policy)
+			});
+			(sender, handle)
+		});
+		Self {
+			policy,
+			queue,
+			fsync_on_complete: config.fsync_on_complete,
+			started: SystemTime::now(),
+		}
+	}
+
+	/// Write a batch, or queue it; waits while the queue is full
+	pub async fn write(&mut self, store: &Store, write: PendingWrite) -> Result<()> {
+		if write.is_empty() {
+			return Ok(());
+		}
+		let Some((sender, _)) = &self.queue else {
+			return write_and_maybe_flush(store, &mut self.policy, &write).await;
+		};
+		if sender.send(write).await.is_ok() {
+			return Ok(());
+		}
+		// The writer stopped, which it only does on an error
+		let (_, handle) = self.queue.take().expect("queue checked above");
+		match handle.await? {
+			Err(e) => Err(e),
+			Ok(_) => Err(anyhow::anyhow!("Index writer stopped unexpectedly")),
+		}
+	}
+
+	/// Wait for queued writes, flush what is left and, with
+	/// `fsync_on_complete`, fsync the files written since the writer started
+	pub async fn finish(mut self, store: &Store) -> Result<()> {
+		if let Some((sender, handle)) = self.queue.take() {
+			drop(sender);
+			self.policy = handle.await??;
+		}
+		if self.policy.has_unflushed() {
+			store.flush().await?;
+			self.policy.flushed(Instant::now());
+		}
+		if self.fsync_on_complete {
+			store.sync_to_disk(self.started).await?;
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_flush_policy() {
+		let config = IndexConfig {
+			flush_frequency: 3,
+			flush_interval_ms: 500,
+			..Default::default()
+		};
+		let mut policy = FlushPolicy::new(&config);
+		let start = policy.last_flush;
+
+		assert!(!policy.record_batch(start));
+		assert!(!policy.record_batch(start + Duration::from_millis(100)));
+		// Batch count reached
+		assert!(policy.record_batch(start + Duration::from_millis(200)));
+		policy.flushed(start + Duration::from_millis(200));
+		assert!(!policy.has_unflushed());
+
+		// Interval elapsed before the batch count
+		assert!(!policy.record_batch(start + Duration::from_millis(300)));
+		assert!(policy.record_batch(start + Duration::from_millis(700)));
+
+		// 0 behaves as 1: flush after every batch
+		let every_batch = FlushPolicy::new(&IndexConfig {
+			flush_frequency: 0,
+			..Default::default()
+		});
+		assert_eq!(every_batch.every_batches, 1);
+	}
+}
@@ -29,10 +29,14 @@
 // Main lib.rs file that exports our modules
 pub mod config;
 pub mod constants;
+pub mod editor;
 pub mod embedding;
+pub mod export;
+pub mod index_cache;
 pub mod indexer;
 pub mod mcp;
 pub mod memory;
+pub mod plugins;
 pub mod reranker;
 pub mod state;
 pub mod storage;
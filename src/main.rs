@@ -16,7 +16,7 @@
 // Copyright (c) 2025 Muvon Un Limited
 
 use clap::{CommandFactory, Parser, Subcommand};
-use clap_complete::{generate, Shell};
+use clap_complete::Shell;
 
 use octocode::config::Config;
 use octocode::store::Store;
@@ -30,6 +30,10 @@ mod commands;
 struct OctocodeArgs {
 	#[command(subcommand)]
 	command: Commands,
+
+	/// Output format for commands that print results (overrides their --format)
+	#[arg(long, global = true, value_enum)]
+	output: Option<commands::OutputFormat>,
 }
 
 #[derive(Subcommand)]
@@ -43,12 +47,25 @@ enum Commands {
 	/// View file signatures (functions, methods, etc.)
 	View(commands::ViewArgs),
 
+	/// Show who calls a function and what it calls
+	Calls(commands::CallsArgs),
+
+	/// Report breaking public API changes between two revisions
+	#[command(name = "api-diff")]
+	ApiDiff(commands::ApiDiffArgs),
+
+	/// Open a hit of the last search in $EDITOR (or VS Code)
+	Open(commands::OpenArgs),
+
 	/// Watch for changes in the codebase and reindex automatically
 	Watch(commands::WatchArgs),
 
 	/// Generate a default configuration file
 	Config(commands::ConfigArgs),
 
+	/// Generate repository reports (code ownership heatmap)
+	Report(commands::ReportArgs),
+
 	/// Query and explore the code relationship graph (GraphRAG)
 	#[command(name = "graphrag")]
 	GraphRAG(commands::GraphRAGArgs),
@@ -56,6 +73,9 @@ enum Commands {
 	/// Start MCP (Model Context Protocol) server
 	Mcp(commands::McpArgs),
 
+	/// Serve search, signatures, GraphRAG and memory as a REST API with JSON responses
+	Serve(commands::ServeArgs),
+
 	/// Start MCP proxy server for multiple repositories
 	#[command(name = "mcp-proxy")]
 	McpProxy(commands::McpProxyArgs),
@@ -66,6 +86,21 @@ enum Commands {
 	/// Clear database tables (useful for debugging)
 	Clear(commands::ClearArgs),
 
+	/// Share the index through a remote cache (push/pull content-addressed chunks)
+	Cache(commands::CacheArgs),
+
+	/// Export the index, or only the changes since a commit, to an archive
+	Export(commands::ExportArgs),
+
+	/// Import an archive written by `export`
+	Import(commands::ImportArgs),
+
+	/// Show index statistics (tables, files, sizes, embeddings, GraphRAG, languages)
+	Stats(commands::StatsArgs),
+
+	/// Maintain the vector store: tune search, build indexes, optimize, migrate
+	Store(commands::StoreArgs),
+
 	/// Generate and create git commit with AI assistance
 	Commit(commands::CommitArgs),
 
@@ -78,9 +113,15 @@ enum Commands {
 	/// Format code according to .editorconfig rules
 	Format(commands::FormatArgs),
 
+	/// Install git hooks running review and format checks before commits and pushes
+	Hooks(commands::HooksArgs),
+
 	/// View MCP server logs
 	Logs(commands::LogsArgs),
 
+	/// Compare embedding models on a sample of the index (speed, cost, retrieval quality)
+	Benchmark(commands::BenchmarkArgs),
+
 	/// Model management and discovery commands
 	Models {
 		#[command(subcommand)]
@@ -93,12 +134,26 @@ enum Commands {
 		#[arg(value_enum)]
 		shell: Shell,
 	},
+
+	/// Print completion candidates from the index (used by completion scripts)
+	#[command(name = "__complete", hide = true)]
+	Complete(commands::CompleteArgs),
 }
 
 #[tokio::main]
 async fn main() -> Result<(), anyhow::Error> {
 	dotenvy::dotenv().ok();
-	let args = OctocodeArgs::parse();
+	let mut args = OctocodeArgs::parse();
+
+	// Apply the global --output flag before dispatching
+	if let Some(output) = args.output.take() {
+		apply_output_format(&mut args.command, output)?;
+	}
+
+	// Completion candidates are printed on every TAB press; keep it minimal
+	if let Commands::Complete(complete_args) = &args.command {
+		return commands::completion::execute(complete_args).await;
+	}
 
 	// Load configuration - ensure .octocode directory exists
 	let config = Config::load()?;
@@ -113,6 +168,11 @@ async fn main() -> Result<(), anyhow::Error> {
 		return commands::mcp::run(mcp_args.clone()).await;
 	}
 
+	// Handle the Serve command separately (doesn't need store)
+	if let Commands::Serve(serve_args) = &args.command {
+		return commands::serve::run(config, serve_args).await;
+	}
+
 	// Handle the MCP Proxy command separately (doesn't need store)
 	if let Commands::McpProxy(mcp_proxy_args) = &args.command {
 		return commands::mcp_proxy::run(mcp_proxy_args.clone()).await;
@@ -138,11 +198,21 @@ async fn main() -> Result<(), anyhow::Error> {
 		return commands::format::execute(format_args).await;
 	}
 
+	// Handle the Hooks command separately (doesn't need store)
+	if let Commands::Hooks(hooks_args) = &args.command {
+		return commands::hooks::execute(&config, hooks_args);
+	}
+
 	// Handle the Memory command separately (doesn't need store)
 	if let Commands::Memory(memory_args) = &args.command {
 		return commands::memory::execute(&config, memory_args).await;
 	}
 
+	// Handle the Open command separately (doesn't need store)
+	if let Commands::Open(open_args) = &args.command {
+		return commands::open::execute(open_args);
+	}
+
 	// Handle the Logs command separately (doesn't need store)
 	if let Commands::Logs(logs_args) = &args.command {
 		return commands::logs::execute(logs_args).await;
@@ -153,14 +223,44 @@ async fn main() -> Result<(), anyhow::Error> {
 		return commands::models::execute_models_command(command.clone()).await;
 	}
 
+	// Handle background watcher management separately (doesn't need store)
+	if let Commands::Watch(watch_args) = &args.command {
+		if watch_args.is_daemon_action() {
+			return commands::watch::execute_daemon_action(watch_args);
+		}
+	}
+
 	// Handle the Completion command separately (doesn't need store)
 	if let Commands::Completion { shell } = &args.command {
-		let mut app = OctocodeArgs::command();
-		let name = app.get_name().to_string();
-		generate(*shell, &mut app, name, &mut std::io::stdout());
+		commands::completion::print_script(*shell, &mut OctocodeArgs::command());
 		return Ok(());
 	}
 
+	// Re-embedding the index runs on the databases directly
+	if let Commands::Store(commands::StoreArgs {
+		command: command @ commands::store::StoreCommand::Migrate { .. },
+	}) = &args.command
+	{
+		return commands::store::migrate(command).await;
+	}
+
+	// Check the index against the configured embedding models before opening
+	// the store, which refuses tables of another dimension
+	match &mut args.command {
+		Commands::Index(index_args) => {
+			index_args.rebuild = commands::index::preflight(&config, index_args).await?;
+			if index_args.rebuild {
+				let db_path =
+					octocode::storage::get_project_database_path(&std::env::current_dir()?)?;
+				octocode::store::preflight::drop_mismatched_tables(&config, &db_path).await?;
+			}
+		}
+		Commands::Watch(_) => {
+			commands::index::check_embedding_drift(&config, false).await?;
+		}
+		_ => {}
+	}
+
 	// Initialize the store
 	let store = Store::new().await?;
 	store.initialize_collections().await?;
@@ -174,25 +274,137 @@ async fn main() -> Result<(), anyhow::Error> {
 			commands::search::execute(&store, search_args, &config).await?
 		}
 		Commands::View(view_args) => commands::view::execute(view_args).await?,
+		Commands::Calls(calls_args) => commands::calls::execute(&config, calls_args).await?,
+		Commands::ApiDiff(api_diff_args) => {
+			commands::api_diff::execute(&config, api_diff_args).await?
+		}
 		Commands::Watch(watch_args) => {
 			commands::watch::execute(&store, &config, watch_args).await?
 		}
 		Commands::GraphRAG(graphrag_args) => {
 			commands::graphrag::execute(&store, graphrag_args, &config).await?
 		}
+		Commands::Report(report_args) => {
+			commands::report::execute(&store, &config, report_args).await?
+		}
 		Commands::Clear(clear_args) => commands::clear::execute(&store, clear_args).await?,
+		Commands::Stats(stats_args) => commands::stats::execute(&store, stats_args).await?,
+		Commands::Store(store_args) => commands::store::execute(&store, store_args).await?,
+		Commands::Export(export_args) => {
+			commands::export::execute(&store, &config, export_args).await?
+		}
+		Commands::Import(import_args) => {
+			commands::import::execute(&store, &config, import_args).await?
+		}
+		Commands::Cache(cache_args) => {
+			commands::cache::execute(&store, &config, cache_args).await?
+		}
+		Commands::Benchmark(benchmark_args) => {
+			commands::benchmark::execute(&store, &config, benchmark_args).await?
+		}
 		Commands::Config(_) => unreachable!(), // Already handled above
 		Commands::Mcp(_) => unreachable!(),    // Already handled above
 		Commands::McpProxy(_) => unreachable!(), // Already handled above
+		Commands::Serve(_) => unreachable!(),  // Already handled above
 		Commands::Commit(_) => unreachable!(), // Already handled above
 		Commands::Review(_) => unreachable!(), // Already handled above
 		Commands::Release(_) => unreachable!(), // Already handled above
 		Commands::Format(_) => unreachable!(), // Already handled above
+		Commands::Hooks(_) => unreachable!(),  // Already handled above
 		Commands::Logs(_) => unreachable!(),   // Already handled above
+		Commands::Open(_) => unreachable!(),   // Already handled above
 		Commands::Models { .. } => unreachable!(), // Already handled above
 		Commands::Memory(_) => unreachable!(), // Already handled above
 		Commands::Completion { .. } => unreachable!(), // Already handled above
+		Commands::Complete(_) => unreachable!(), // Already handled above
 	}
 
 	Ok(())
 }
+
+/// Route the global --output flag to the per-command format options
+fn apply_output_format(
+	command: &mut Commands,
+	output: commands::OutputFormat,
+) -> Result<(), anyhow::Error> {
+	match command {
+		Commands::Search(search_args) => search_args.format = output,
+		Commands::View(view_args) => view_args.format = output,
+		Commands::GraphRAG(graphrag_args) => graphrag_args.format = output,
+		Commands::Stats(stats_args) => stats_args.format = output,
+		Commands::Store(commands::StoreArgs {
+			command:
+				commands::store::StoreCommand::Tune { format, .. }
+				| commands::store::StoreCommand::BuildIndex { format, .. }
+				| commands::store::StoreCommand::Optimize { format, .. }
+				| commands::store::StoreCommand::Migrate { format, .. },
+		}) => *format = output,
+		Commands::Calls(calls_args) => calls_args.format = output,
+		Commands::Benchmark(commands::BenchmarkArgs {
+			command: commands::benchmark::BenchmarkCommand::Embeddings { format, .. },
+		}) => *format = output,
+		Commands::ApiDiff(api_diff_args) => api_diff_args.format = output,
+		Commands::Memory(memory_args) => memory_args.apply_output_format(&output),
+		Commands::Report(report_args) => report_args.apply_output_format(&output),
+		Commands::Review(review_args) if output.is_json() => {
+			review_args.json = true;
+			review_args.format = None;
+		}
+		Commands::Review(_) => {}
+		Commands::Mcp(commands::McpArgs {
+			command: Some(commands::mcp::McpCommand::Status { format }),
+			..
+		}) => *format = output,
+		Commands::McpProxy(commands::McpProxyArgs {
+			command: Some(commands::mcp_proxy::McpProxyCommand::IndexAll { format, .. }),
+			..
+		}) => *format = output,
+		_ => return Err(anyhow::anyhow!("--output is not supported by this command")),
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn parse_with_output(args: &[&str]) -> Result<Commands, anyhow::Error> {
+		let mut args = OctocodeArgs::try_parse_from(args)?;
+		let output = args.output.take().expect("--output was given");
+		apply_output_format(&mut args.command, output)?;
+		Ok(args.command)
+	}
+
+	#[test]
+	fn test_apply_output_format() {
+		// The global flag overrides the per-command --format, wherever it appears
+		match parse_with_output(&["octocode", "--output", "json", "search", "auth flow"]).unwrap() {
+			Commands::Search(search_args) => assert!(search_args.format.is_json()),
+			_ => panic!("expected the search command"),
+		}
+		match parse_with_output(&[
+			"octocode", "view", "src", "--format", "md", "--output", "text",
+		])
+		.unwrap()
+		{
+			Commands::View(view_args) => assert!(view_args.format.is_text()),
+			_ => panic!("expected the view command"),
+		}
+
+		// Review only knows JSON, so other formats leave it untouched
+		match parse_with_output(&["octocode", "review", "--output", "json"]).unwrap() {
+			Commands::Review(review_args) => assert!(review_args.json),
+			_ => panic!("expected the review command"),
+		}
+		match parse_with_output(&["octocode", "review", "--output", "md"]).unwrap() {
+			Commands::Review(review_args) => assert!(!review_args.json),
+			_ => panic!("expected the review command"),
+		}
+
+		// Commands that print nothing structured reject the flag instead of ignoring it
+		let Err(err) = parse_with_output(&["octocode", "--output", "json", "index"]) else {
+			panic!("index accepted --output");
+		};
+		assert!(err.to_string().contains("not supported"));
+	}
+}
@@ -0,0 +1,211 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Bearer-token authentication for the HTTP transports
+//!
+//! `octocode mcp --bind` and `octocode mcp-proxy` accept every request until
+//! `[mcp.auth]` configures a token. From then on each request must carry
+//! `Authorization: Bearer <token>` with one of the global tokens or, through
+//! the proxy, one of the tokens of the repository it is routed to.
+//! Repositories without tokens of their own are then only reachable with a
+//! global token.
+
+use anyhow::Result;
+use std::collections::BTreeMap;
+
+use crate::config::McpAuthConfig;
+
+/// Prefix of tokens read from an environment variable
+const ENV_PREFIX: &str = "env:";
+
+/// `WWW-Authenticate` value sent with `401` responses
+pub const CHALLENGE: &str = "Bearer realm=\"octocode\"";
+
+/// Why a request was turned away
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthRejection {
+	/// No `Authorization: Bearer` header
+	MissingToken,
+	/// A token that is not accepted for the repository
+	InvalidToken,
+}
+
+impl AuthRejection {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			AuthRejection::MissingToken => "missing_token",
+			AuthRejection::InvalidToken => "invalid_token",
+		}
+	}
+
+	/// Body of the `401` response
+	pub fn message(&self) -> &'static str {
+		match self {
+			AuthRejection::MissingToken => "Authorization: Bearer <token> required",
+			AuthRejection::InvalidToken => "Invalid token",
+		}
+	}
+}
+
+/// Tokens of `[mcp.auth]`, with `env:` references resolved
+#[derive(Debug, Clone, Default)]
+pub struct Authenticator {
+	tokens: Vec<String>,
+	repositories: BTreeMap<String, Vec<String>>,
+}
+
+impl Authenticator {
+	/// Resolve the configured tokens; a referenced environment variable that
+	/// is unset or empty is an error, so a typo never leaves a server open
+	pub fn from_config(config: &McpAuthConfig) -> Result<Self> {
+		let resolve = |tokens: &[String]| -> Result<Vec<String>> {
+			tokens.iter().map(|token| resolve_token(token)).collect()
+		};
+		let mut repositories = BTreeMap::new();
+		for (repository, tokens) in &config.repositories {
+			repositories.insert(normalize_repository(repository), resolve(tokens)?);
+		}
+		Ok(Self {
+			tokens: resolve(&config.tokens)?,
+			repositories,
+		})
+	}
+
+	/// Whether requests are checked at all
+	pub fn is_enabled(&self) -> bool {
+		!self.tokens.is_empty() || self.repositories.values().any(|t| !t.is_empty())
+	}
+
+	/// Check the `Authorization` header of a request to `repository` (the
+	/// proxy's route, `None` for `mcp --bind`)
+	pub fn check(
+		&self,
+		repository: Option<&str>,
+		authorization: Option<&str>,
+	) -> Result<(), AuthRejection> {
+		if !self.is_enabled() {
+			return Ok(());
+		}
+		let token = authorization
+			.and_then(bearer_token)
+			.ok_or(AuthRejection::MissingToken)?;
+
+		let repository_tokens = repository
+			.and_then(|repository| self.repositories.get(&normalize_repository(repository)))
+			.into_iter()
+			.flatten();
+		// Compare against every token so the time taken doesn't tell which matched
+		let accepted = self
+			.tokens
+			.iter()
+			.chain(repository_tokens)
+			.fold(false, |accepted, expected| {
+				constant_time_eq(expected.as_bytes(), token.as_bytes()) | accepted
+			});
+		if accepted {
+			Ok(())
+		} else {
+			Err(AuthRejection::InvalidToken)
+		}
+	}
+
+	/// Whether the request's token is valid for every repository rather than
+	/// scoped to some of them (always true without authentication)
+	pub fn is_org_wide(&self, authorization: Option<&str>) -> bool {
+		self.check(None, authorization).is_ok()
+	}
+}
+
+fn resolve_token(token: &str) -> Result<String> {
+	let Some(name) = token.strip_prefix(ENV_PREFIX) else {
+		return Ok(token.to_string());
+	};
+	match std::env::var(name) {
+		Ok(value) if !value.trim().is_empty() => Ok(value.trim().to_string()),
+		_ => Err(anyhow::anyhow!(
+			"[mcp.auth] token references environment variable {}, which is not set",
+			name
+		)),
+	}
+}
+
+/// `org/repo`, whether written with slashes around it or not
+fn normalize_repository(repository: &str) -> String {
+	repository.trim_matches('/').to_string()
+}
+
+/// Token of an `Authorization: Bearer <token>` header value
+fn bearer_token(authorization: &str) -> Option<&str> {
+	let (scheme, token) = authorization.trim().split_once(' ')?;
+	let token = token.trim();
+	(scheme.eq_ignore_ascii_case("bearer") && !token.is_empty()).then_some(token)
+}
+
+/// Equality in time that depends only on the lengths
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+	a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_tokens_by_repository() {
+		let config = McpAuthConfig {
+			tokens: vec!["admin".to_string()],
+			repositories: BTreeMap::from([("/org/api/".to_string(), vec!["api".to_string()])]),
+		};
+		let auth = Authenticator::from_config(&config).unwrap();
+
+		assert_eq!(auth.check(Some("org/api"), Some("Bearer api")), Ok(()));
+		assert_eq!(auth.check(Some("org/web"), Some("bearer admin")), Ok(()));
+		assert_eq!(auth.check(None, Some("Bearer admin")), Ok(()));
+		// A repository's token opens only that repository
+		assert_eq!(
+			auth.check(Some("org/web"), Some("Bearer api")),
+			Err(AuthRejection::InvalidToken)
+		);
+		assert_eq!(
+			auth.check(Some("org/api"), Some("Basic api")),
+			Err(AuthRejection::MissingToken)
+		);
+		assert_eq!(
+			auth.check(Some("org/api"), None),
+			Err(AuthRejection::MissingToken)
+		);
+
+		// Only the tokens valid everywhere are org-wide
+		assert!(auth.is_org_wide(Some("Bearer admin")));
+		assert!(!auth.is_org_wide(Some("Bearer api")));
+		assert!(!auth.is_org_wide(None));
+	}
+
+	#[test]
+	fn test_no_tokens_means_no_checks() {
+		let auth = Authenticator::from_config(&McpAuthConfig::default()).unwrap();
+		assert!(!auth.is_enabled());
+		assert_eq!(auth.check(Some("org/api"), None), Ok(()));
+		assert!(auth.is_org_wide(None));
+	}
+
+	#[test]
+	fn test_missing_environment_token_is_an_error() {
+		let config = McpAuthConfig {
+			tokens: vec!["env:OCTOCODE_TEST_UNSET_MCP_TOKEN".to_string()],
+			repositories: BTreeMap::new(),
+		};
+		assert!(Authenticator::from_config(&config).is_err());
+	}
+}
@@ -0,0 +1,648 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Local control socket for inspecting a running MCP server
+//!
+//! The server records sessions, in-flight tool calls, recent errors and
+//! indexing activity in a shared [`McpActivity`] tracker. A JSON snapshot of
+//! that state is served on a Unix domain socket only its owner may open (a
+//! loopback TCP port guarded by a random token where those don't exist), which
+//! is published in `mcp_control.json` inside the project storage directory, so
+//! that `octocode mcp status` can find the server without touching stdin/stdout.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tracing::debug;
+
+/// File name (inside project storage) that advertises the control endpoint
+const CONTROL_FILE_NAME: &str = "mcp_control.json";
+/// Socket file name (inside project storage) of the control endpoint
+#[cfg(unix)]
+const CONTROL_SOCKET_NAME: &str = "mcp_control.sock";
+/// Maximum number of recent errors kept in memory
+const MAX_RECENT_ERRORS: usize = 20;
+/// HTTP sessions without activity for this long are dropped from the snapshot
+const HTTP_SESSION_IDLE_SECS: u64 = 600;
+/// Timeout for connecting to and reading from the control socket
+const CONTROL_IO_TIMEOUT_MS: u64 = 2_000;
+
+/// Connected client session as seen by the server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionInfo {
+	pub key: String,
+	pub transport: String,
+	pub client_name: Option<String>,
+	pub client_version: Option<String>,
+	pub connected_at: DateTime<Utc>,
+	pub last_activity: DateTime<Utc>,
+	pub requests: u64,
+}
+
+/// Tool call that has started but not yet produced a response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InFlightCall {
+	pub session: String,
+	pub tool: String,
+	pub started_at: DateTime<Utc>,
+	pub elapsed_ms: u64,
+}
+
+/// Error returned to a client, kept for post-mortem inspection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecentError {
+	pub timestamp: DateTime<Utc>,
+	pub method: String,
+	pub tool: Option<String>,
+	pub message: String,
+}
+
+/// Index freshness as observed by the server's background reindexing
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IndexFreshness {
+	pub indexing_in_progress: bool,
+	pub last_indexed_at: Option<DateTime<Utc>>,
+	pub last_duration_ms: Option<u64>,
+	pub last_success: Option<bool>,
+	pub reindex_count: u64,
+}
+
+/// Point-in-time view of the server returned over the control socket
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusSnapshot {
+	pub pid: u32,
+	pub started_at: DateTime<Utc>,
+	pub working_directory: String,
+	pub total_requests: u64,
+	pub sessions: Vec<SessionInfo>,
+	pub in_flight: Vec<InFlightCall>,
+	pub recent_errors: Vec<RecentError>,
+	pub index: IndexFreshness,
+}
+
+/// Where the control server listens
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "transport", rename_all = "snake_case")]
+enum ControlEndpoint {
+	/// Unix domain socket that only the server's user may connect to
+	Unix { path: PathBuf },
+	/// Loopback port; every request must start with the token on its own line
+	Tcp { port: u16, token: String },
+}
+
+/// Contents of the control file that points clients at the control endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ControlFile {
+	pid: u32,
+	started_at: DateTime<Utc>,
+	#[serde(flatten)]
+	endpoint: ControlEndpoint,
+}
+
+struct TrackedCall {
+	session: String,
+	tool: String,
+	started_at: DateTime<Utc>,
+	started: Instant,
+}
+
+struct ActivityState {
+	started_at: DateTime<Utc>,
+	working_directory: PathBuf,
+	total_requests: u64,
+	sessions: HashMap<String, SessionInfo>,
+	in_flight: HashMap<u64, TrackedCall>,
+	next_call_id: u64,
+	recent_errors: VecDeque<RecentError>,
+	index: IndexFreshness,
+	/// Bumped whenever the served index may have changed
+	index_generation: u64,
+}
+
+/// Shared activity tracker updated by the request handlers
+#[derive(Clone)]
+pub struct McpActivity {
+	state: Arc<Mutex<ActivityState>>,
+	indexing_in_progress: Arc<AtomicBool>,
+}
+
+impl McpActivity {
+	pub fn new(working_directory: &Path, indexing_in_progress: Arc<AtomicBool>) -> Self {
+		Self {
+			state: Arc::new(Mutex::new(ActivityState {
+				started_at: Utc::now(),
+				working_directory: working_directory.to_path_buf(),
+				total_requests: 0,
+				sessions: HashMap::new(),
+				in_flight: HashMap::new(),
+				next_call_id: 0,
+				recent_errors: VecDeque::with_capacity(MAX_RECENT_ERRORS),
+				index: IndexFreshness::default(),
+				index_generation: 0,
+			})),
+			indexing_in_progress,
+		}
+	}
+
+	/// Register a request for a session, creating the session on first use
+	pub fn touch_session(&self, key: &str, transport: &str) {
+		let now = Utc::now();
+		let mut state = self.state.lock();
+		state.total_requests += 1;
+		let session = state
+			.sessions
+			.entry(key.to_string())
+			.or_insert_with(|| SessionInfo {
+				key: key.to_string(),
+				transport: transport.to_string(),
+				client_name: None,
+				client_version: None,
+				connected_at: now,
+				last_activity: now,
+				requests: 0,
+			});
+		session.last_activity = now;
+		session.requests += 1;
+	}
+
+	/// Record client identification sent with the `initialize` request
+	pub fn record_initialize(&self, key: &str, params: Option<&Value>) {
+		let client_info = params.and_then(|p| p.get("clientInfo"));
+		let mut state = self.state.lock();
+		if let Some(session) = state.sessions.get_mut(key) {
+			session.client_name = client_info
+				.and_then(|c| c.get("name"))
+				.and_then(|v| v.as_str())
+				.map(String::from);
+			session.client_version = client_info
+				.and_then(|c| c.get("version"))
+				.and_then(|v| v.as_str())
+				.map(String::from);
+		}
+	}
+
+	/// Drop a session once its transport has closed
+	pub fn close_session(&self, key: &str) {
+		self.state.lock().sessions.remove(key);
+	}
+
+	/// Mark a tool call as started and return a handle for `end_call`
+	pub fn begin_call(&self, session: &str, tool: &str) -> u64 {
+		let mut state = self.state.lock();
+		let id = state.next_call_id;
+		state.next_call_id += 1;
+		state.in_flight.insert(
+			id,
+			TrackedCall {
+				session: session.to_string(),
+				tool: tool.to_string(),
+				started_at: Utc::now(),
+				started: Instant::now(),
+			},
+		);
+		id
+	}
+
+	pub fn end_call(&self, call_id: u64) {
+		self.state.lock().in_flight.remove(&call_id);
+	}
+
+	pub fn record_error(&self, method: &str, tool: Option<&str>, message: &str) {
+		let mut state = self.state.lock();
+		if state.recent_errors.len() >= MAX_RECENT_ERRORS {
+			state.recent_errors.pop_front();
+		}
+		state.recent_errors.push_back(RecentError {
+			timestamp: Utc::now(),
+			method: method.to_string(),
+			tool: tool.map(String::from),
+			message: message.to_string(),
+		});
+	}
+
+	/// Point the status at a new project after the server switched workspace roots
+	pub fn set_working_directory(&self, working_directory: &Path) {
+		let mut state = self.state.lock();
+		state.working_directory = working_directory.to_path_buf();
+		state.index = IndexFreshness::default();
+		state.index_generation += 1;
+	}
+
+	/// Record the outcome of a background reindex
+	pub fn record_index(&self, success: bool, duration_ms: u64) {
+		let mut state = self.state.lock();
+		state.index.last_indexed_at = Some(Utc::now());
+		state.index.last_duration_ms = Some(duration_ms);
+		state.index.last_success = Some(success);
+		state.index.reindex_count += 1;
+		state.index_generation += 1;
+	}
+
+	/// Counter that changes whenever the index may have changed
+	pub fn index_generation(&self) -> u64 {
+		self.state.lock().index_generation
+	}
+
+	pub fn snapshot(&self) -> StatusSnapshot {
+		let now = Utc::now();
+		let mut state = self.state.lock();
+
+		// HTTP clients never disconnect explicitly, so expire idle ones here
+		state.sessions.retain(|_, s| {
+			s.transport != "http"
+				|| (now - s.last_activity).num_seconds() < HTTP_SESSION_IDLE_SECS as i64
+		});
+
+		let mut sessions: Vec<SessionInfo> = state.sessions.values().cloned().collect();
+		sessions.sort_by(|a, b| a.connected_at.cmp(&b.connected_at));
+
+		let mut in_flight: Vec<InFlightCall> = state
+			.in_flight
+			.values()
+			.map(|call| InFlightCall {
+				session: call.session.clone(),
+				tool: call.tool.clone(),
+				started_at: call.started_at,
+				elapsed_ms: call.started.elapsed().as_millis() as u64,
+			})
+			.collect();
+		in_flight.sort_by(|a, b| a.started_at.cmp(&b.started_at));
+
+		let mut index = state.index.clone();
+		index.indexing_in_progress = self.indexing_in_progress.load(Ordering::SeqCst);
+
+		StatusSnapshot {
+			pid: std::process::id(),
+			started_at: state.started_at,
+			working_directory: state.working_directory.display().to_string(),
+			total_requests: state.total_requests,
+			sessions,
+			in_flight,
+			recent_errors: state.recent_errors.iter().cloned().collect(),
+			index,
+		}
+	}
+}
+
+/// Path of the control file for a project
+pub fn control_file_path(working_directory: &Path) -> Result<PathBuf> {
+	let project_storage = crate::storage::get_project_storage_path(working_directory)?;
+	Ok(project_storage.join(CONTROL_FILE_NAME))
+}
+
+/// Start serving status snapshots and publish where to find them
+pub async fn start_control_server(
+	activity: McpActivity,
+	working_directory: &Path,
+) -> Result<tokio::task::JoinHandle<()>> {
+	let control_path = control_file_path(working_directory)?;
+	if let Some(parent) = control_path.parent() {
+		std::fs::create_dir_all(parent)?;
+	}
+
+	let (listener, endpoint) = bind_control_listener(&control_path)?;
+	let control_file = ControlFile {
+		pid: std::process::id(),
+		started_at: Utc::now(),
+		endpoint,
+	};
+	write_private_file(&control_path, &serde_json::to_string(&control_file)?)?;
+
+	debug!(endpoint = ?control_file.endpoint, control_file = %control_path.display(), "MCP control socket started");
+
+	Ok(tokio::spawn(listener.serve(activity)))
+}
+
+/// Listener behind the control endpoint
+enum ControlListener {
+	#[cfg(unix)]
+	Unix(tokio::net::UnixListener),
+	#[cfg(not(unix))]
+	Tcp {
+		listener: tokio::net::TcpListener,
+		token: String,
+	},
+}
+
+#[cfg(unix)]
+fn bind_control_listener(control_path: &Path) -> Result<(ControlListener, ControlEndpoint)> {
+	use std::os::unix::fs::PermissionsExt;
+
+	let socket_path = control_path.with_file_name(CONTROL_SOCKET_NAME);
+	// A socket left behind by a server that didn't shut down cleanly blocks bind
+	let _ = std::fs::remove_file(&socket_path);
+	let listener = tokio::net::UnixListener::bind(&socket_path)
+		.map_err(|e| anyhow::anyhow!("Failed to bind control socket: {}", e))?;
+	std::fs::set_permissions(&socket_path, std::fs::Permissions::from_mode(0o600))?;
+	Ok((
+		ControlListener::Unix(listener),
+		ControlEndpoint::Unix { path: socket_path },
+	))
+}
+
+#[cfg(not(unix))]
+fn bind_control_listener(_control_path: &Path) -> Result<(ControlListener, ControlEndpoint)> {
+	let listener = std::net::TcpListener::bind(("127.0.0.1", 0))
+		.map_err(|e| anyhow::anyhow!("Failed to bind control socket: {}", e))?;
+	listener.set_nonblocking(true)?;
+	let listener = tokio::net::TcpListener::from_std(listener)?;
+	let port = listener.local_addr()?.port();
+	let token = uuid::Uuid::new_v4().simple().to_string();
+	Ok((
+		ControlListener::Tcp {
+			listener,
+			token: token.clone(),
+		},
+		ControlEndpoint::Tcp { port, token },
+	))
+}
+
+impl ControlListener {
+	async fn serve(self, activity: McpActivity) {
+		loop {
+			let accepted = match &self {
+				#[cfg(unix)]
+				ControlListener::Unix(listener) => listener.accept().await.map(|(stream, _)| {
+					(Box::new(stream) as Box<dyn ControlStream>, None::<String>)
+				}),
+				#[cfg(not(unix))]
+				ControlListener::Tcp { listener, token } => listener.accept().await.map(|(stream, _)| {
+					(
+						Box::new(stream) as Box<dyn ControlStream>,
+						Some(token.clone()),
+					)
+				}),
+			};
+			match accepted {
+				Ok((stream, token)) => {
+					let activity = activity.clone();
+					tokio::spawn(async move {
+						if let Err(e) = write_snapshot(stream, token.as_deref(), &activity).await {
+							debug!("Control socket write failed: {}", e);
+						}
+					});
+				}
+				Err(e) => {
+					debug!("Control socket accept error: {}", e);
+					break;
+				}
+			}
+		}
+	}
+}
+
+trait ControlStream: AsyncRead + AsyncWrite + Unpin + Send {}
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> ControlStream for T {}
+
+/// Write a file only its owner can read
+fn write_private_file(path: &Path, content: &str) -> Result<()> {
+	#[cfg(unix)]
+	{
+		use std::io::Write;
+		use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+		let mut file = std::fs::OpenOptions::new()
+			.write(true)
+			.create(true)
+			.truncate(true)
+			.mode(0o600)
+			.open(path)?;
+		// An existing file keeps its old mode on open
+		file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+		file.write_all(content.as_bytes())?;
+	}
+	#[cfg(not(unix))]
+	std::fs::write(path, content)?;
+	Ok(())
+}
+
+/// Remove the control file if it still belongs to this process
+pub fn remove_control_file(working_directory: &Path) {
+	let Ok(control_path) = control_file_path(working_directory) else {
+		return;
+	};
+	let ours = std::fs::read_to_string(&control_path)
+		.ok()
+		.and_then(|content| serde_json::from_str::<ControlFile>(&content).ok())
+		.filter(|file| file.pid == std::process::id());
+	if let Some(file) = ours {
+		if let ControlEndpoint::Unix { path } = file.endpoint {
+			let _ = std::fs::remove_file(path);
+		}
+		let _ = std::fs::remove_file(control_path);
+	}
+}
+
+/// Answer one control request; with a token, the request must start with it
+async fn write_snapshot<S>(stream: S, token: Option<&str>, activity: &McpActivity) -> Result<()>
+where
+	S: AsyncRead + AsyncWrite + Unpin,
+{
+	let mut stream = BufReader::new(stream);
+	if let Some(token) = token {
+		let mut line = String::new();
+		let mut request = (&mut stream).take(token.len() as u64 + 2);
+		let read = request.read_line(&mut line);
+		tokio::time::timeout(Duration::from_millis(CONTROL_IO_TIMEOUT_MS), read).await??;
+		if line.trim_end() != token {
+			return Err(anyhow::anyhow!("Control request without a valid token"));
+		}
+	}
+	let payload = serde_json::to_vec(&activity.snapshot())?;
+	stream.write_all(&payload).await?;
+	stream.shutdown().await?;
+	Ok(())
+}
+
+/// Send the request preamble and read the snapshot that comes back
+async fn read_snapshot<S>(mut stream: S, token: Option<&str>) -> Result<StatusSnapshot>
+where
+	S: AsyncRead + AsyncWrite + Unpin,
+{
+	let timeout = Duration::from_millis(CONTROL_IO_TIMEOUT_MS);
+	if let Some(token) = token {
+		stream.write_all(format!("{}\n", token).as_bytes()).await?;
+	}
+	let mut buffer = Vec::new();
+	tokio::time::timeout(timeout, stream.read_to_end(&mut buffer))
+		.await
+		.map_err(|_| anyhow::anyhow!("Timed out reading MCP server status"))??;
+
+	Ok(serde_json::from_slice(&buffer)?)
+}
+
+/// Ask the MCP server running for `working_directory` for its current status
+pub async fn query_status(working_directory: &Path) -> Result<StatusSnapshot> {
+	let control_path = control_file_path(working_directory)?;
+	let content = std::fs::read_to_string(&control_path).map_err(|_| {
+		anyhow::anyhow!(
+			"No running MCP server found for '{}'",
+			working_directory.display()
+		)
+	})?;
+	let control_file: ControlFile = serde_json::from_str(&content)?;
+
+	let not_responding = || {
+		anyhow::anyhow!(
+			"MCP server (pid {}) is not responding; the control file at '{}' may be stale",
+			control_file.pid,
+			control_path.display()
+		)
+	};
+
+	let timeout = Duration::from_millis(CONTROL_IO_TIMEOUT_MS);
+	match &control_file.endpoint {
+		#[cfg(unix)]
+		ControlEndpoint::Unix { path } => {
+			let stream = tokio::time::timeout(timeout, tokio::net::UnixStream::connect(path))
+				.await
+				.map_err(|_| not_responding())?
+				.map_err(|_| not_responding())?;
+			read_snapshot(stream, None).await
+		}
+		ControlEndpoint::Tcp { port, token } => {
+			let stream = tokio::time::timeout(
+				timeout,
+				tokio::net::TcpStream::connect(("127.0.0.1", *port)),
+			)
+			.await
+			.map_err(|_| not_responding())?
+			.map_err(|_| not_responding())?;
+			read_snapshot(stream, Some(token)).await
+		}
+		#[cfg(not(unix))]
+		ControlEndpoint::Unix { .. } => Err(not_responding()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn test_status_snapshot() {
+		let indexing = Arc::new(AtomicBool::new(false));
+		let activity = McpActivity::new(Path::new("/projects/app"), indexing.clone());
+
+		activity.touch_session("stdio", "stdio");
+		activity.record_initialize(
+			"stdio",
+			Some(&serde_json::json!({"clientInfo": {"name": "editor", "version": "1.2"}})),
+		);
+		activity.touch_session("stdio", "stdio");
+		let finished = activity.begin_call("stdio", "semantic_search");
+		let running = activity.begin_call("stdio", "graphrag");
+		activity.end_call(finished);
+		activity.record_error("tools/call", Some("graphrag"), "GraphRAG is not enabled");
+		activity.record_index(true, 120);
+		indexing.store(true, Ordering::SeqCst);
+
+		// The snapshot goes over the control socket as JSON
+		let (client, server) = tokio::io::duplex(64 * 1024);
+		let server = {
+			let activity = activity.clone();
+			tokio::spawn(async move { write_snapshot(server, None, &activity).await.unwrap() })
+		};
+		let snapshot = read_snapshot(client, None).await.unwrap();
+		server.await.unwrap();
+
+		assert_eq!(snapshot.pid, std::process::id());
+		assert_eq!(snapshot.working_directory, "/projects/app");
+		assert_eq!(snapshot.total_requests, 2);
+		assert_eq!(snapshot.sessions.len(), 1);
+		assert_eq!(snapshot.sessions[0].client_name.as_deref(), Some("editor"));
+		assert_eq!(snapshot.sessions[0].client_version.as_deref(), Some("1.2"));
+		assert_eq!(snapshot.sessions[0].requests, 2);
+		assert_eq!(snapshot.in_flight.len(), 1);
+		assert_eq!(snapshot.in_flight[0].tool, "graphrag");
+		assert_eq!(snapshot.recent_errors.len(), 1);
+		assert_eq!(snapshot.recent_errors[0].tool.as_deref(), Some("graphrag"));
+		assert!(snapshot.index.indexing_in_progress);
+		assert_eq!(snapshot.index.last_success, Some(true));
+		assert_eq!(snapshot.index.reindex_count, 1);
+
+		activity.end_call(running);
+		activity.close_session("stdio");
+		let snapshot = activity.snapshot();
+		assert!(snapshot.sessions.is_empty() && snapshot.in_flight.is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_token_guards_tcp_requests() {
+		let activity =
+			McpActivity::new(Path::new("/projects/app"), Arc::new(AtomicBool::new(false)));
+
+		for (sent, answered) in [
+			(Some("secret"), true),
+			(Some("guess"), false),
+			(None, false),
+		] {
+			let (client, server) = tokio::io::duplex(64 * 1024);
+			let server = {
+				let activity = activity.clone();
+				tokio::spawn(async move { write_snapshot(server, Some("secret"), &activity).await })
+			};
+			// Without a token line the server waits for one, so hang up instead
+			let snapshot = match sent {
+				Some(token) => read_snapshot(client, Some(token)).await,
+				None => {
+					drop(client);
+					Err(anyhow::anyhow!("no request sent"))
+				}
+			};
+			assert_eq!(server.await.unwrap().is_ok(), answered);
+			assert_eq!(snapshot.is_ok(), answered);
+		}
+	}
+
+	#[cfg(unix)]
+	#[tokio::test]
+	async fn test_control_socket_is_private() {
+		use std::os::unix::fs::PermissionsExt;
+
+		let project = std::env::temp_dir().join(format!("octocode_control_{}", std::process::id()));
+		std::fs::create_dir_all(&project).unwrap();
+		let activity = McpActivity::new(&project, Arc::new(AtomicBool::new(false)));
+		let handle = start_control_server(activity, &project).await.unwrap();
+
+		let control_path = control_file_path(&project).unwrap();
+		let control_file: ControlFile =
+			serde_json::from_str(&std::fs::read_to_string(&control_path).unwrap()).unwrap();
+		let ControlEndpoint::Unix { path } = &control_file.endpoint else {
+			panic!("expected a Unix socket endpoint");
+		};
+		for file in [path, &control_path] {
+			let mode = std::fs::metadata(file).unwrap().permissions().mode();
+			assert_eq!(mode & 0o777, 0o600, "{}", file.display());
+		}
+
+		let snapshot = query_status(&project).await.unwrap();
+		assert_eq!(snapshot.pid, std::process::id());
+
+		handle.abort();
+		remove_control_file(&project);
+		assert!(!path.exists() && !control_path.exists());
+		if let Some(storage) = control_path.parent() {
+			let _ = std::fs::remove_dir_all(storage);
+		}
+		let _ = std::fs::remove_dir_all(&project);
+	}
+}
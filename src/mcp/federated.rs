@@ -0,0 +1,503 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Federated search across every repository served by the MCP proxy
+//!
+//! The query runs against each indexed repository in turn, and the hits are
+//! merged into one ranked list labelled with their repository.
+
+use anyhow::Result;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+use crate::config::Config;
+use crate::embedding::truncate_output;
+use crate::mcp::proxy::McpProxyServer;
+use crate::mcp::types::{McpError, McpTool};
+use crate::store::{relevance, Anchor, Store};
+
+/// Lines of content shown per hit
+const PREVIEW_LINES: usize = 6;
+
+/// One result of a repository, scored for merging with the others
+#[derive(Debug, Clone)]
+pub struct FederatedHit {
+	/// Repository path relative to the proxy root, as used in proxy URLs
+	pub repository: String,
+	/// "code", "docs" or "text"
+	pub kind: &'static str,
+	pub path: String,
+	/// 1-indexed
+	pub start_line: usize,
+	pub end_line: usize,
+	/// Similarity reported by the repository's own index (0.0-1.0)
+	pub similarity: f32,
+	/// Score used for the merged ranking, set by [`merge_hits`]
+	pub score: f32,
+	pub anchor: Option<String>,
+	pub preview: String,
+}
+
+/// Merge per-repository hits into one ranked list of at most `limit` hits.
+///
+/// A hit's score blends its similarity with how it compares to the best hit
+/// of its own repository, so a repository whose index scores everything
+/// higher doesn't crowd out the best matches of the others.
+pub fn merge_hits(per_repository: Vec<Vec<FederatedHit>>, limit: usize) -> Vec<FederatedHit> {
+	let mut merged = Vec::new();
+	for mut hits in per_repository {
+		let best = hits.iter().map(|hit| hit.similarity).fold(0.0, f32::max);
+		for hit in &mut hits {
+			let relative = if best > 0.0 {
+				hit.similarity / best
+			} else {
+				0.0
+			};
+			hit.score = 0.5 * hit.similarity + 0.5 * relative;
+		}
+		merged.extend(hits);
+	}
+
+	merged.sort_by(|a, b| {
+		b.score
+			.total_cmp(&a.score)
+			.then(b.similarity.total_cmp(&a.similarity))
+			.then(a.repository.cmp(&b.repository))
+			.then(a.path.cmp(&b.path))
+			.then(a.start_line.cmp(&b.start_line))
+	});
+	merged.truncate(limit);
+	merged
+}
+
+fn preview(content: &str, start_line: usize) -> String {
+	content
+		.lines()
+		.take(PREVIEW_LINES)
+		.enumerate()
+		.map(|(index, line)| format!("{}: {}\n", start_line + index, line))
+		.collect()
+}
+
+/// Federated search tool provider for the MCP proxy
+#[derive(Clone)]
+pub struct FederatedSearchProvider {
+	config: Config,
+	root_path: PathBuf,
+}
+
+impl FederatedSearchProvider {
+	pub fn new(config: Config, root_path: PathBuf) -> Self {
+		Self { config, root_path }
+	}
+
+	/// Get the tool definition for federated_search
+	pub fn get_tool_definition() -> McpTool {
+		McpTool {
+			name: "federated_search".to_string(),
+			description: "Semantic search across ALL repositories served by this proxy at once. Returns one ranked list where every result is labelled with its repository, with scores normalized across repositories. Use it to find where something is implemented when you don't know which repository holds it; use semantic_search to dig into a single repository. Only indexed repositories are searched.".to_string(),
+			input_schema: json!({
+				"type": "object",
+				"properties": {
+					"query": {
+						"type": "string",
+						"description": "What to search for, in descriptive terms rather than code syntax",
+						"minLength": 3,
+						"maxLength": 500
+					},
+					"mode": {
+						"type": "string",
+						"description": "Scope of search to limit results to specific content types",
+						"enum": ["code", "text", "docs", "all"],
+						"default": "all"
+					},
+					"max_results": {
+						"type": "integer",
+						"description": "Maximum number of merged results to return (default: 10)",
+						"minimum": 1,
+						"maximum": 50,
+						"default": 10
+					},
+					"repositories": {
+						"type": "array",
+						"items": {"type": "string"},
+						"description": "Only search repositories whose path (relative to the proxy root, e.g. 'org/repo' or 'org') starts with one of these prefixes"
+					},
+					"language": {
+						"type": "string",
+						"description": "Filter code results by programming language"
+					},
+					"max_tokens": {
+						"type": "integer",
+						"description": "Maximum tokens allowed in output before truncation (default: 2000, set to 0 for unlimited)",
+						"minimum": 0,
+						"default": 2000
+					}
+				},
+				"required": ["query"],
+				"additionalProperties": false
+			}),
+		}
+	}
+
+	/// Execute the federated_search tool over the repositories `authorized`
+	/// accepts, by their path relative to the proxy root
+	pub async fn execute(
+		&self,
+		arguments: &Value,
+		authorized: &(dyn Fn(&str) -> bool + Sync),
+	) -> Result<String, McpError> {
+		let query = arguments
+			.get("query")
+			.and_then(|v| v.as_str())
+			.map(str::trim)
+			.ok_or_else(|| {
+				McpError::invalid_params(
+					"Missing required parameter 'query': must be a string describing what to search for",
+					"federated_search",
+				)
+			})?;
+		if !(3..=500).contains(&query.len()) {
+			return Err(McpError::invalid_params(
+				"Invalid query: must be between 3 and 500 characters long",
+				"federated_search",
+			));
+		}
+
+		let mode = arguments
+			.get("mode")
+			.and_then(|v| v.as_str())
+			.unwrap_or("all");
+		if !["code", "text", "docs", "all"].contains(&mode) {
+			return Err(McpError::invalid_params(
+				format!(
+					"Invalid mode '{}': must be one of 'code', 'text', 'docs', or 'all'",
+					mode
+				),
+				"federated_search",
+			));
+		}
+
+		let max_results = arguments
+			.get("max_results")
+			.and_then(|v| v.as_u64())
+			.unwrap_or(10) as usize;
+		if !(1..=50).contains(&max_results) {
+			return Err(McpError::invalid_params(
+				format!(
+					"Invalid max_results '{}': must be between 1 and 50",
+					max_results
+				),
+				"federated_search",
+			));
+		}
+
+		let prefixes: Vec<&str> = arguments
+			.get("repositories")
+			.and_then(|v| v.as_array())
+			.map(|values| values.iter().filter_map(|v| v.as_str()).collect())
+			.unwrap_or_default();
+		let language = arguments.get("language").and_then(|v| v.as_str());
+		let max_tokens = arguments
+			.get("max_tokens")
+			.and_then(|v| v.as_u64())
+			.unwrap_or(2000) as usize;
+
+		let repositories = McpProxyServer::discover_repositories_under(&self.root_path)
+			.map_err(|e| McpError::internal_error(e.to_string(), "federated_search"))?;
+
+		let mut per_repository = Vec::new();
+		let mut skipped = Vec::new();
+		for repository_path in repositories {
+			let repository = repository_path
+				.strip_prefix(&self.root_path)
+				.unwrap_or(&repository_path)
+				.to_string_lossy()
+				.to_string();
+			if !in_scope(&repository, &prefixes, authorized) {
+				continue;
+			}
+
+			let indexed = crate::storage::get_project_database_path(&repository_path)
+				.map(|path| path.exists())
+				.unwrap_or(false);
+			if !indexed {
+				skipped.push(format!("{} (not indexed)", repository));
+				continue;
+			}
+
+			// Repositories are searched one after another: the store is opened
+			// from the current directory, which is shared by the whole process
+			match self
+				.search_repository(
+					&repository_path,
+					&repository,
+					query,
+					mode,
+					max_results,
+					language,
+				)
+				.await
+			{
+				Ok(hits) => per_repository.push(hits),
+				Err(e) => {
+					debug!(repository = %repository, error = %e, "Federated search failed for repository");
+					skipped.push(format!("{} ({})", repository, e));
+				}
+			}
+		}
+
+		let searched = per_repository.len();
+		let hits = merge_hits(per_repository, max_results);
+		Ok(truncate_output(
+			&render_hits(&hits, searched, &skipped),
+			max_tokens,
+		))
+	}
+
+	async fn search_repository(
+		&self,
+		repository_path: &Path,
+		repository: &str,
+		query: &str,
+		mode: &str,
+		limit: usize,
+		language: Option<&str>,
+	) -> Result<Vec<FederatedHit>> {
+		let original_dir = std::env::current_dir()?;
+		std::env::set_current_dir(repository_path)?;
+		let hits = self
+			.query_store(repository, query, mode, limit, language)
+			.await;
+		if let Err(e) = std::env::set_current_dir(&original_dir) {
+			debug!(error = %e, "Failed to restore original directory");
+		}
+		hits
+	}
+
+	async fn query_store(
+		&self,
+		repository: &str,
+		query: &str,
+		mode: &str,
+		limit: usize,
+		language: Option<&str>,
+	) -> Result<Vec<FederatedHit>> {
+		let store = Store::new().await?;
+		let embeddings =
+			crate::embedding::generate_search_embeddings(query, mode, &self.config).await?;
+		let distance_threshold = Some(1.0 - self.config.search.similarity_threshold);
+		let hit = |kind, path: &str, start_line: usize, end_line: usize, distance: Option<f32>| {
+			FederatedHit {
+				repository: repository.to_string(),
+				kind,
+				path: path.to_string(),
+				start_line: start_line + 1,
+				end_line: end_line + 1,
+				similarity: distance.map_or(0.0, relevance),
+				score: 0.0,
+				anchor: None,
+				preview: String::new(),
+			}
+		};
+
+		let mut hits = Vec::new();
+		if let (Some(embedding), "code" | "all") = (embeddings.code_embeddings, mode) {
+			for block in store
+				.get_code_blocks_with_filters(
+					embedding,
+					Some(limit),
+					0,
+					distance_threshold,
+					language,
+					None,
+					None,
+				)
+				.await?
+			{
+				hits.push(FederatedHit {
+					anchor: Some(Anchor::for_code_block(&block).to_string()),
+					preview: preview(&block.content, block.start_line + 1),
+					..hit(
+						"code",
+						&block.path,
+						block.start_line,
+						block.end_line,
+						block.distance,
+					)
+				});
+			}
+		}
+		if let Some(embedding) = embeddings.text_embeddings {
+			if matches!(mode, "docs" | "all") {
+				for block in store
+					.get_document_blocks_with_offset(
+						embedding.clone(),
+						Some(limit),
+						0,
+						distance_threshold,
+					)
+					.await?
+				{
+					hits.push(FederatedHit {
+						preview: format!(
+							"{}\n{}",
+							block.title,
+							preview(&block.content, block.start_line + 1)
+						),
+						..hit(
+							"docs",
+							&block.path,
+							block.start_line,
+							block.end_line,
+							block.distance,
+						)
+					});
+				}
+			}
+			if matches!(mode, "text" | "all") {
+				for block in store
+					.get_text_blocks_with_offset(embedding, Some(limit), 0, distance_threshold)
+					.await?
+				{
+					hits.push(FederatedHit {
+						preview: preview(&block.content, block.start_line + 1),
+						..hit(
+							"text",
+							&block.path,
+							block.start_line,
+							block.end_line,
+							block.distance,
+						)
+					});
+				}
+			}
+		}
+
+		Ok(hits)
+	}
+}
+
+/// Whether a repository matches the requested prefixes, if any, and the
+/// caller may access it
+fn in_scope(
+	repository: &str,
+	prefixes: &[&str],
+	authorized: &(dyn Fn(&str) -> bool + Sync),
+) -> bool {
+	(prefixes.is_empty()
+		|| prefixes
+			.iter()
+			.any(|prefix| repository.starts_with(prefix.trim_matches('/'))))
+		&& authorized(repository)
+}
+
+fn render_hits(hits: &[FederatedHit], searched: usize, skipped: &[String]) -> String {
+	let mut output = format!(
+		"FEDERATED RESULTS ({} from {} repositories)\n",
+		hits.len(),
+		searched
+	);
+	if hits.is_empty() {
+		output.push_str("No results found.\n");
+	}
+
+	for (index, hit) in hits.iter().enumerate() {
+		output.push_str(&format!(
+			"{}. [{}] {}:{}-{} | {} | Score {:.3} (similarity {:.3})\n",
+			index + 1,
+			hit.repository,
+			hit.path,
+			hit.start_line,
+			hit.end_line,
+			hit.kind,
+			hit.score,
+			hit.similarity
+		));
+		if let Some(anchor) = &hit.anchor {
+			output.push_str(&format!("Anchor: {}\n", anchor));
+		}
+		output.push_str(&hit.preview);
+		output.push('\n');
+	}
+
+	if !skipped.is_empty() {
+		output.push_str(&format!("Skipped: {}\n", skipped.join(", ")));
+	}
+	output
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn hit(repository: &str, path: &str, similarity: f32) -> FederatedHit {
+		FederatedHit {
+			repository: repository.to_string(),
+			kind: "code",
+			path: path.to_string(),
+			start_line: 1,
+			end_line: 10,
+			similarity,
+			score: 0.0,
+			anchor: None,
+			preview: String::new(),
+		}
+	}
+
+	#[test]
+	fn test_merge_hits() {
+		let merged = merge_hits(
+			vec![
+				vec![
+					hit("org/api", "auth.rs", 0.9),
+					hit("org/api", "login.rs", 0.45),
+				],
+				vec![hit("org/web", "session.ts", 0.6)],
+				Vec::new(),
+			],
+			2,
+		);
+
+		// The best hit of each repository outranks weaker hits of another
+		let ranked: Vec<(&str, &str)> = merged
+			.iter()
+			.map(|hit| (hit.repository.as_str(), hit.path.as_str()))
+			.collect();
+		assert_eq!(ranked, [("org/api", "auth.rs"), ("org/web", "session.ts")]);
+		assert!((merged[0].score - 0.95).abs() < 1e-6);
+		assert!((merged[1].score - 0.8).abs() < 1e-6);
+	}
+
+	#[test]
+	fn test_in_scope() {
+		let auth = crate::mcp::auth::Authenticator::from_config(&crate::config::McpAuthConfig {
+			tokens: vec!["global".to_string()],
+			repositories: [("org/api".to_string(), vec!["api".to_string()])]
+				.into_iter()
+				.collect(),
+		})
+		.unwrap();
+		let scoped = |repository: &str| auth.check(Some(repository), Some("Bearer api")).is_ok();
+		let global = |repository: &str| auth.check(Some(repository), Some("Bearer global")).is_ok();
+
+		// A repository token only reaches its own repository
+		assert!(in_scope("org/api", &[], &scoped));
+		assert!(!in_scope("org/web", &[], &scoped));
+		assert!(in_scope("org/web", &[], &global));
+		assert!(in_scope("org/web", &["org"], &global));
+		assert!(!in_scope("org/web", &["org/api"], &global));
+	}
+}
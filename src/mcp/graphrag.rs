@@ -18,8 +18,10 @@ use tracing::debug;
 
 use crate::config::Config;
 use crate::embedding::truncate_output;
+use crate::indexer::path_aliases::PathAliases;
 use crate::indexer::{self, graphrag::GraphRAG};
 use crate::mcp::types::{McpError, McpTool};
+use crate::store::Anchor;
 
 #[derive(Debug, Clone)]
 pub enum GraphRAGOperation {
@@ -313,6 +315,17 @@ impl GraphRagProvider {
 			return Err(anyhow::anyhow!("GraphRAG knowledge graph is empty. Run 'octocode index' to build the knowledge graph."));
 		}
 
+		// Nodes are keyed by file path; IDs of renamed files map to their current path
+		let aliases = PathAliases::load(&self.working_directory);
+		let mut args = args.clone();
+		for id in [&mut args.node_id, &mut args.source_id, &mut args.target_id]
+			.into_iter()
+			.flatten()
+		{
+			*id = aliases.resolve_missing(id, |id| graph.nodes.contains_key(id));
+		}
+		let args = &args;
+
 		// Execute the requested operation and capture output
 		match args.operation {
 			GraphRAGOperation::Search => {
@@ -347,9 +360,10 @@ impl GraphRagProvider {
 							},
 							OutputFormat::Md => {
 								Ok(format!(
-									"# Node: {}\n\n**ID:** {}\n**Kind:** {}\n**Path:** {}\n**Description:** {}\n\n**Symbols:**\n{}\n",
+									"# Node: {}\n\n**ID:** {}\n**Anchor:** `{}`\n**Kind:** {}\n**Path:** {}\n**Description:** {}\n\n**Symbols:**\n{}\n",
 									node.name,
 									node.id,
+									Anchor::for_node(&node.path, &node.hash),
 									node.kind,
 									node.path,
 									node.description,
@@ -359,9 +373,10 @@ impl GraphRagProvider {
 							_ => {
 								// Text format for token efficiency
 								Ok(format!(
-									"Node: {}\nID: {}\nKind: {}\nPath: {}\nDescription: {}\nSymbols: {}\n",
+									"Node: {}\nID: {}\nAnchor: {}\nKind: {}\nPath: {}\nDescription: {}\nSymbols: {}\n",
 									node.name,
 									node.id,
+									Anchor::for_node(&node.path, &node.hash),
 									node.kind,
 									node.path,
 									node.description,
@@ -0,0 +1,291 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Minimal HTTP/1.1 framing for the streamable HTTP transport
+//!
+//! Requests are read in full (headers, then exactly `Content-Length` body
+//! bytes) and every response closes the connection. JSON-RPC replies go out
+//! either as `application/json` or as a single-event `text/event-stream`,
+//! depending on what the client accepts.
+
+use anyhow::Result;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// Header carrying the session assigned on `initialize`
+pub const SESSION_HEADER: &str = "Mcp-Session-Id";
+
+const MAX_HEADER_SIZE: usize = 16_384;
+
+const CORS_HEADERS: &str = "Access-Control-Allow-Origin: *\r\nAccess-Control-Allow-Methods: GET, POST, DELETE, OPTIONS\r\nAccess-Control-Allow-Headers: Content-Type, Accept, Authorization, Mcp-Session-Id\r\nAccess-Control-Expose-Headers: Mcp-Session-Id\r\n";
+
+/// A parsed HTTP request
+#[derive(Debug)]
+pub struct HttpRequest {
+	pub method: String,
+	pub path: String,
+	headers: Vec<(String, String)>,
+	pub body: Vec<u8>,
+}
+
+impl HttpRequest {
+	/// Case-insensitive header lookup
+	pub fn header(&self, name: &str) -> Option<&str> {
+		self.headers
+			.iter()
+			.find(|(key, _)| key.eq_ignore_ascii_case(name))
+			.map(|(_, value)| value.as_str())
+	}
+
+	/// Whether the `Accept` header lists the media type (or `*/*`)
+	pub fn accepts(&self, media_type: &str) -> bool {
+		self.header("accept").is_some_and(|accept| {
+			accept.split(',').any(|item| {
+				let item = item.split(';').next().unwrap_or("").trim();
+				item.eq_ignore_ascii_case(media_type) || item == "*/*"
+			})
+		})
+	}
+
+	/// Reply as an event stream only when the client cannot take plain JSON
+	pub fn prefers_event_stream(&self) -> bool {
+		self.accepts("text/event-stream") && !self.accepts("application/json")
+	}
+}
+
+/// Read one request; `Ok(None)` when the peer closed without sending anything
+pub async fn read_request<S: AsyncRead + Unpin + ?Sized>(
+	stream: &mut S,
+	max_body_size: usize,
+) -> Result<Option<HttpRequest>> {
+	let mut buffer = Vec::with_capacity(8192);
+	let mut chunk = [0u8; 8192];
+
+	let header_end = loop {
+		if let Some(pos) = find_header_end(&buffer) {
+			break pos;
+		}
+		if buffer.len() > MAX_HEADER_SIZE {
+			return Err(anyhow::anyhow!(
+				"HTTP headers exceed {} bytes",
+				MAX_HEADER_SIZE
+			));
+		}
+		let read = stream.read(&mut chunk).await?;
+		if read == 0 {
+			if buffer.is_empty() {
+				return Ok(None);
+			}
+			return Err(anyhow::anyhow!("Connection closed before end of headers"));
+		}
+		buffer.extend_from_slice(&chunk[..read]);
+	};
+
+	let head = String::from_utf8_lossy(&buffer[..header_end.0]).to_string();
+	let mut lines = head.lines();
+	let request_line = lines.next().unwrap_or("");
+	let mut parts = request_line.split_whitespace();
+	let method = parts.next().unwrap_or("").to_string();
+	let path = parts.next().unwrap_or("").to_string();
+
+	let headers: Vec<(String, String)> = lines
+		.filter_map(|line| {
+			let (name, value) = line.split_once(':')?;
+			Some((name.trim().to_string(), value.trim().to_string()))
+		})
+		.collect();
+
+	let content_length = headers
+		.iter()
+		.find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+		.and_then(|(_, value)| value.parse::<usize>().ok())
+		.unwrap_or(0);
+	if content_length > max_body_size {
+		return Err(anyhow::anyhow!(
+			"Request body of {} bytes exceeds limit {}",
+			content_length,
+			max_body_size
+		));
+	}
+
+	let mut body = buffer.split_off(header_end.0 + header_end.1);
+	while body.len() < content_length {
+		let read = stream.read(&mut chunk).await?;
+		if read == 0 {
+			return Err(anyhow::anyhow!("Connection closed before end of body"));
+		}
+		body.extend_from_slice(&chunk[..read]);
+	}
+	body.truncate(content_length);
+
+	Ok(Some(HttpRequest {
+		method,
+		path,
+		headers,
+		body,
+	}))
+}
+
+/// Position and length of the blank line ending the headers
+fn find_header_end(buffer: &[u8]) -> Option<(usize, usize)> {
+	if let Some(pos) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+		return Some((pos, 4));
+	}
+	buffer
+		.windows(2)
+		.position(|w| w == b"\n\n")
+		.map(|pos| (pos, 2))
+}
+
+fn status_text(status: u16) -> &'static str {
+	match status {
+		200 => "OK",
+		202 => "Accepted",
+		204 => "No Content",
+		400 => "Bad Request",
+		401 => "Unauthorized",
+		403 => "Forbidden",
+		404 => "Not Found",
+		405 => "Method Not Allowed",
+		406 => "Not Acceptable",
+		413 => "Payload Too Large",
+		500 => "Internal Server Error",
+		_ => "Error",
+	}
+}
+
+/// Write a complete response and flush it
+pub async fn write_response<S: AsyncWrite + Unpin + ?Sized>(
+	stream: &mut S,
+	status: u16,
+	content_type: Option<&str>,
+	extra_headers: &[(&str, &str)],
+	body: &[u8],
+) -> Result<()> {
+	let mut head = format!("HTTP/1.1 {} {}\r\n", status, status_text(status));
+	if let Some(content_type) = content_type {
+		head.push_str(&format!("Content-Type: {}\r\n", content_type));
+	}
+	for (name, value) in extra_headers {
+		head.push_str(&format!("{}: {}\r\n", name, value));
+	}
+	head.push_str(CORS_HEADERS);
+	head.push_str(&format!(
+		"Content-Length: {}\r\nConnection: close\r\n\r\n",
+		body.len()
+	));
+
+	stream.write_all(head.as_bytes()).await?;
+	stream.write_all(body).await?;
+	stream.flush().await?;
+	Ok(())
+}
+
+/// Write a JSON-RPC message as JSON or as a one-event SSE stream
+pub async fn write_message<S: AsyncWrite + Unpin + ?Sized>(
+	stream: &mut S,
+	json: &str,
+	event_stream: bool,
+	extra_headers: &[(&str, &str)],
+) -> Result<()> {
+	if event_stream {
+		let event = format!("event: message\ndata: {}\n\n", json);
+		write_response(
+			stream,
+			200,
+			Some("text/event-stream"),
+			extra_headers,
+			event.as_bytes(),
+		)
+		.await
+	} else {
+		write_response(
+			stream,
+			200,
+			Some("application/json"),
+			extra_headers,
+			json.as_bytes(),
+		)
+		.await
+	}
+}
+
+/// Write a plain-text error
+pub async fn write_error<S: AsyncWrite + Unpin + ?Sized>(
+	stream: &mut S,
+	status: u16,
+	message: &str,
+) -> Result<()> {
+	write_response(stream, status, Some("text/plain"), &[], message.as_bytes()).await
+}
+
+/// Write a `401` asking for a bearer token
+pub async fn write_unauthorized<S: AsyncWrite + Unpin + ?Sized>(
+	stream: &mut S,
+	message: &str,
+) -> Result<()> {
+	write_response(
+		stream,
+		401,
+		Some("text/plain"),
+		&[("WWW-Authenticate", crate::mcp::auth::CHALLENGE)],
+		message.as_bytes(),
+	)
+	.await
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn test_read_request_waits_for_full_body() {
+		let body = r#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#;
+		let raw = format!(
+			"POST /mcp HTTP/1.1\r\nAccept: application/json, text/event-stream\r\nMcp-Session-Id: abc\r\nContent-Length: {}\r\n\r\n{}",
+			body.len(),
+			body
+		);
+		let (mut client, mut server) = tokio::io::duplex(16);
+		let writer = tokio::spawn(async move {
+			client.write_all(raw.as_bytes()).await.unwrap();
+		});
+
+		let request = read_request(&mut server, 1024).await.unwrap().unwrap();
+		writer.await.unwrap();
+
+		assert_eq!(request.method, "POST");
+		assert_eq!(request.path, "/mcp");
+		assert_eq!(request.header("mcp-session-id"), Some("abc"));
+		assert_eq!(request.body, body.as_bytes());
+		assert!(!request.prefers_event_stream());
+	}
+
+	#[tokio::test]
+	async fn test_read_request_rejects_oversized_body() {
+		let raw = "POST /mcp HTTP/1.1\r\nContent-Length: 2048\r\n\r\n";
+		let mut stream = raw.as_bytes();
+		assert!(read_request(&mut stream, 1024).await.is_err());
+	}
+
+	#[test]
+	fn test_event_stream_only_when_json_not_accepted() {
+		let request = HttpRequest {
+			method: "POST".to_string(),
+			path: "/mcp".to_string(),
+			headers: vec![("Accept".to_string(), "text/event-stream".to_string())],
+			body: Vec::new(),
+		};
+		assert!(request.prefers_event_stream());
+	}
+}
@@ -125,6 +125,22 @@ pub fn log_mcp_response(
 	}
 }
 
+/// Log a request turned away by `[mcp.auth]`; the token is never logged
+pub fn log_auth_rejection(
+	transport: &str,
+	peer: &std::net::SocketAddr,
+	repository: Option<&str>,
+	reason: &str,
+) {
+	warn!(
+		transport = transport,
+		peer = %peer,
+		repository = repository,
+		reason = reason,
+		"MCP request rejected: authentication failed"
+	);
+}
+
 /// Log critical errors with context
 pub fn log_critical_error(context: &str, error: &dyn std::error::Error) {
 	error!(
@@ -0,0 +1,117 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Indexed code blocks enclosing LSP locations
+//!
+//! Locations returned by the language server are annotated with the block of
+//! the index they fall in: its lines, symbols and anchor, which the agent can
+//! pass to `resolve_anchor` or use to narrow a `semantic_search`. Without an
+//! index, or for files outside it, locations are returned as the server gave
+//! them.
+
+use std::collections::HashMap;
+
+use crate::store::{Anchor, CodeBlock, Store};
+
+/// Code blocks of the files looked up so far
+pub struct BlockLookup {
+	store: Option<Store>,
+	files: HashMap<String, Vec<CodeBlock>>,
+}
+
+impl BlockLookup {
+	/// Lookup over the project index; empty when the index cannot be opened
+	pub async fn open() -> Self {
+		let store = match Store::new().await {
+			Ok(store) => Some(store),
+			Err(e) => {
+				tracing::debug!("Index unavailable for LSP block lookup: {}", e);
+				None
+			}
+		};
+		Self {
+			store,
+			files: HashMap::new(),
+		}
+	}
+
+	/// Innermost indexed block of `path` containing the 0-indexed `line`
+	pub async fn enclosing(&mut self, path: &str, line: u32) -> Option<&CodeBlock> {
+		if !self.files.contains_key(path) {
+			let blocks = match &self.store {
+				Some(store) => store
+					.get_code_blocks_by_path(path)
+					.await
+					.unwrap_or_default(),
+				None => Vec::new(),
+			};
+			self.files.insert(path.to_string(), blocks);
+		}
+		innermost(self.files.get(path)?, line as usize)
+	}
+}
+
+/// Smallest block whose lines contain `line`
+fn innermost(blocks: &[CodeBlock], line: usize) -> Option<&CodeBlock> {
+	blocks
+		.iter()
+		.filter(|block| block.start_line <= line && line <= block.end_line)
+		.min_by_key(|block| block.end_line - block.start_line)
+}
+
+/// One-line description of a block: its 1-indexed lines, symbols and anchor
+pub fn describe_block(block: &CodeBlock) -> String {
+	let mut description = format!("block {}-{}", block.start_line + 1, block.end_line + 1);
+	let symbols: Vec<&str> = block.symbols.iter().take(5).map(String::as_str).collect();
+	if !symbols.is_empty() {
+		description.push_str(&format!(" [{}]", symbols.join(", ")));
+	}
+	description.push_str(&format!(", anchor {}", Anchor::for_code_block(block)));
+	description
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn block(start_line: usize, end_line: usize, symbols: &[&str]) -> CodeBlock {
+		CodeBlock {
+			path: "src/lib.rs".to_string(),
+			hash: format!("h{}", start_line),
+			language: "rust".to_string(),
+			content: String::new(),
+			symbols: symbols.iter().map(|symbol| symbol.to_string()).collect(),
+			start_line,
+			end_line,
+			spans: Vec::new(),
+			provenance: None,
+			distance: None,
+		}
+	}
+
+	#[test]
+	fn test_innermost() {
+		let blocks = vec![
+			block(0, 100, &["Service"]),
+			block(10, 20, &["charge"]),
+			block(30, 40, &["refund"]),
+		];
+		assert_eq!(innermost(&blocks, 15).unwrap().symbols, vec!["charge"]);
+		assert_eq!(innermost(&blocks, 25).unwrap().symbols, vec!["Service"]);
+		assert!(innermost(&blocks, 200).is_none());
+
+		let description = describe_block(&blocks[1]);
+		assert!(description.starts_with("block 11-21 [charge], anchor src/lib.rs#"));
+	}
+}
@@ -16,7 +16,10 @@
 //!
 //! This module provides integration with external LSP servers, allowing users to
 //! specify any LSP server command and expose its capabilities through MCP tools.
+//! Locations the server returns are annotated with the indexed blocks they
+//! fall in.
 
+pub mod blocks;
 pub mod client;
 pub mod protocol;
 pub mod provider;
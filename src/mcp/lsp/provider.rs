@@ -360,8 +360,8 @@ impl LspProvider {
 	pub fn get_tool_definitions() -> Vec<McpTool> {
 		vec![
             McpTool {
-                name: "lsp_goto_definition".to_string(),
-                description: "Navigate to symbol definition using LSP server. Automatically finds the symbol on the specified line.".to_string(),
+                name: "lsp_definition".to_string(),
+                description: "Navigate to symbol definition using LSP server. Automatically finds the symbol on the specified line. Each definition comes with the indexed block it is in: its lines, symbols and anchor for resolve_anchor.".to_string(),
                 input_schema: json!({
                     "type": "object",
                     "properties": {
@@ -385,7 +385,7 @@ impl LspProvider {
             },
             McpTool {
                 name: "lsp_hover".to_string(),
-                description: "Get symbol information and documentation using LSP server. Automatically finds the symbol on the specified line.".to_string(),
+                description: "Get symbol information and documentation using LSP server. Automatically finds the symbol on the specified line, and names the indexed block the line is in.".to_string(),
                 input_schema: json!({
                     "type": "object",
                     "properties": {
@@ -408,8 +408,8 @@ impl LspProvider {
                 })
             },
             McpTool {
-                name: "lsp_find_references".to_string(),
-                description: "Find all references to a symbol using LSP server. Automatically finds the symbol on the specified line.".to_string(),
+                name: "lsp_references".to_string(),
+                description: "Find all references to a symbol using LSP server. Automatically finds the symbol on the specified line. Each reference comes with the indexed block it is in: its lines, symbols and anchor for resolve_anchor.".to_string(),
                 input_schema: json!({
                     "type": "object",
                     "properties": {
@@ -664,25 +664,19 @@ impl LspProvider {
 			.get("file_path")
 			.and_then(|v| v.as_str())
 			.ok_or_else(|| {
-				McpError::invalid_params(
-					"Missing required parameter: file_path",
-					"lsp_goto_definition",
-				)
+				McpError::invalid_params("Missing required parameter: file_path", "lsp_definition")
 			})?;
 		let line = arguments
 			.get("line")
 			.and_then(|v| v.as_u64())
 			.ok_or_else(|| {
-				McpError::invalid_params("Missing required parameter: line", "lsp_goto_definition")
+				McpError::invalid_params("Missing required parameter: line", "lsp_definition")
 			})? as u32;
 		let symbol = arguments
 			.get("symbol")
 			.and_then(|v| v.as_str())
 			.ok_or_else(|| {
-				McpError::invalid_params(
-					"Missing required parameter: symbol",
-					"lsp_goto_definition",
-				)
+				McpError::invalid_params("Missing required parameter: symbol", "lsp_definition")
 			})?;
 
 		// Clean the file path to handle formatted paths like "[Rust file: main.rs]"
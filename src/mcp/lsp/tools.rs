@@ -19,29 +19,56 @@ use lsp_types::*;
 use serde_json::{json, Value};
 use tracing::{debug, warn};
 
+use super::blocks::{describe_block, BlockLookup};
 use super::protocol::{uri_to_file_path, LspRequest};
 use super::provider::LspProvider;
 
 /// Response formatting utilities for AI-friendly output
 impl LspProvider {
 	/// Format goto definition response as readable text
-	fn format_goto_definition_response(&self, locations: &[Location]) -> String {
+	async fn format_goto_definition_response(&self, locations: &[Location]) -> String {
 		if locations.is_empty() {
 			return "No definition found".to_string();
 		}
 
-		let location = &locations[0];
+		let mut blocks = BlockLookup::open().await;
+		if let [location] = locations {
+			return format!(
+				"Definition found at {}",
+				self.describe_location(location, &mut blocks).await
+			);
+		}
+
+		let mut result = format!("Found {} definition(s):\n", locations.len());
+		for (i, location) in locations.iter().enumerate() {
+			result.push_str(&format!(
+				"{}. {}\n",
+				i + 1,
+				self.describe_location(location, &mut blocks).await
+			));
+		}
+		result.trim_end().to_string()
+	}
+
+	/// `path:line:column` of a location, followed by the indexed block it is in
+	async fn describe_location(&self, location: &Location, blocks: &mut BlockLookup) -> String {
 		let file_path = match uri_to_file_path(&location.uri) {
 			Ok(path) => self.make_path_relative(&path),
 			Err(_) => location.uri.to_string(),
 		};
-
-		format!(
-			"Definition found at {}:{}:{}",
+		let position = format!(
+			"{}:{}:{}",
 			file_path,
 			location.range.start.line + 1,
 			location.range.start.character + 1
-		)
+		);
+		match blocks
+			.enclosing(&file_path, location.range.start.line)
+			.await
+		{
+			Some(block) => format!("{} in {}", position, describe_block(block)),
+			None => position,
+		}
 	}
 
 	/// Format hover response as readable text
@@ -72,25 +99,19 @@ impl LspProvider {
 	}
 
 	/// Format references response as readable text
-	fn format_references_response(&self, locations: &[Location]) -> String {
+	async fn format_references_response(&self, locations: &[Location]) -> String {
 		if locations.is_empty() {
 			return "No references found".to_string();
 		}
 
 		let mut result = format!("Found {} reference(s):\n", locations.len());
+		let mut blocks = BlockLookup::open().await;
 
 		for (i, location) in locations.iter().enumerate() {
-			let file_path = match uri_to_file_path(&location.uri) {
-				Ok(path) => self.make_path_relative(&path),
-				Err(_) => location.uri.to_string(),
-			};
-
 			result.push_str(&format!(
-				"{}. {}:{}:{}\n",
+				"{}. {}\n",
 				i + 1,
-				file_path,
-				location.range.start.line + 1,
-				location.range.start.character + 1
+				self.describe_location(location, &mut blocks).await
 			));
 		}
 
@@ -257,7 +278,7 @@ impl LspProvider {
 		if let Some(result) = response.result {
 			// Handle different response types (Location, Vec<Location>, LocationLink, etc.)
 			let locations = self.parse_goto_definition_response(result)?;
-			Ok(self.format_goto_definition_response(&locations))
+			Ok(self.format_goto_definition_response(&locations).await)
 		} else {
 			Ok("No definition found".to_string())
 		}
@@ -320,7 +341,13 @@ impl LspProvider {
 			let hover: Option<Hover> = serde_json::from_value(result)?;
 
 			if let Some(hover) = hover {
-				Ok(self.format_hover_response(&hover))
+				let mut info = self.format_hover_response(&hover);
+				// The block the hovered symbol is used in
+				let mut blocks = BlockLookup::open().await;
+				if let Some(block) = blocks.enclosing(file_path, line.saturating_sub(1)).await {
+					info.push_str(&format!("\n\nIn indexed {}", describe_block(block)));
+				}
+				Ok(info)
 			} else {
 				Ok("No hover information available".to_string())
 			}
@@ -368,7 +395,7 @@ impl LspProvider {
 			let locations: Option<Vec<Location>> = serde_json::from_value(result)?;
 
 			if let Some(locations) = locations {
-				Ok(self.format_references_response(&locations))
+				Ok(self.format_references_response(&locations).await)
 			} else {
 				Ok("No references found".to_string())
 			}
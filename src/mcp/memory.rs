@@ -14,6 +14,7 @@
 
 use anyhow::Result;
 use serde_json::{json, Value};
+use std::path::Path;
 use std::sync::Arc;
 
 use tokio::sync::Mutex;
@@ -25,21 +26,117 @@ use crate::constants::MAX_QUERIES;
 use crate::embedding::truncate_output;
 use crate::mcp::logging::log_critical_anyhow_error;
 use crate::mcp::types::{McpError, McpTool};
-use crate::memory::{MemoryManager, MemoryQuery, MemoryType};
+use crate::memory::timeline::{self, group_by_period, TimelineFilter, TimelinePeriod};
+use crate::memory::{
+	validate_namespace, Memorized, Memory, MemoryConfig, MemoryManager, MemoryQuery,
+	MemorySearchResult, MemoryType, DEFAULT_NAMESPACE,
+};
+
+/// Org-wide memory namespace shared by every repository behind the MCP proxy.
+/// Only the configured memory types are stored here; everything else stays
+/// in the repository's own store.
+#[derive(Clone)]
+pub struct SharedMemory {
+	manager: Arc<Mutex<MemoryManager>>,
+	memory_types: Vec<MemoryType>,
+}
+
+impl SharedMemory {
+	/// Open the shared store for the proxy root, or `None` when no memory
+	/// types are configured as shared
+	pub async fn open(config: &Config, root_path: &Path) -> Result<Option<Self>> {
+		let mut memory_types: Vec<MemoryType> = Vec::new();
+		for name in &config.mcp.shared_memory_types {
+			let memory_type = MemoryType::from(name.clone());
+			if !memory_types.contains(&memory_type) {
+				memory_types.push(memory_type);
+			}
+		}
+		if memory_types.is_empty() {
+			return Ok(None);
+		}
+
+		let db_path = crate::storage::get_project_storage_path(root_path)?.join("shared_memory");
+		let manager = MemoryManager::open(config, MemoryConfig::default(), &db_path).await?;
+
+		Ok(Some(Self {
+			manager: Arc::new(Mutex::new(manager)),
+			memory_types,
+		}))
+	}
+
+	fn holds(&self, memory_type: &MemoryType) -> bool {
+		self.memory_types.contains(memory_type)
+	}
+
+	/// Narrow a query to the shared memory types, or `None` when the query's
+	/// type filter excludes all of them
+	fn scope_query(&self, query: &MemoryQuery) -> Option<MemoryQuery> {
+		let memory_types = match &query.memory_types {
+			Some(requested) => self
+				.memory_types
+				.iter()
+				.filter(|memory_type| requested.contains(memory_type))
+				.cloned()
+				.collect(),
+			None => self.memory_types.clone(),
+		};
+		if memory_types.is_empty() {
+			return None;
+		}
+
+		Some(MemoryQuery {
+			memory_types: Some(memory_types),
+			..query.clone()
+		})
+	}
+}
+
+/// The `namespace` argument of a memory tool, or the default namespace
+fn namespace_argument(arguments: &Value, tool: &str) -> Result<String, McpError> {
+	let namespace = match arguments.get("namespace") {
+		None | Some(Value::Null) => return Ok(DEFAULT_NAMESPACE.to_string()),
+		Some(value) => value.as_str().ok_or_else(|| {
+			McpError::invalid_params("Invalid namespace parameter: must be a string", tool)
+		})?,
+	};
+	validate_namespace(namespace).map_err(|e| McpError::invalid_params(e.to_string(), tool))?;
+	Ok(namespace.to_string())
+}
 
 /// Memory tools provider
 #[derive(Clone)]
 pub struct MemoryProvider {
 	memory_manager: Arc<Mutex<MemoryManager>>,
+	shared: Option<SharedMemory>,
 	working_directory: std::path::PathBuf,
+	config: Config,
+	read_only: bool,
 }
 
 impl MemoryProvider {
-	pub async fn new(config: &Config, working_directory: std::path::PathBuf) -> Option<Self> {
-		match MemoryManager::new(config).await {
+	/// Memory tools of the repository; a `read_only` server's searches don't
+	/// count retrievals
+	pub async fn new(
+		config: &Config,
+		working_directory: std::path::PathBuf,
+		read_only: bool,
+	) -> Option<Self> {
+		// Open the store of the served repository rather than the process's current directory
+		let manager = match crate::storage::get_project_database_path(&working_directory) {
+			Ok(db_path) => MemoryManager::open(config, MemoryConfig::default(), &db_path)
+				.await
+				.map(|manager| manager.with_read_only(read_only)),
+			Err(e) => Err(e),
+		};
+
+		match manager {
 			Ok(manager) => Some(Self {
 				memory_manager: Arc::new(Mutex::new(manager)),
+				shared: None,
 				working_directory,
+				config: config.clone(),
+				read_only,
 			}),
 			Err(e) => {
 				warn!(
@@ -51,6 +148,12 @@ impl MemoryProvider {
 		}
 	}
 
+	/// Route the shared memory types to an org-wide store
+	pub fn with_shared(mut self, shared: Option<SharedMemory>) -> Self {
+		self.shared = shared;
+		self
+	}
+
 	/// Get all tool definitions for memory operations
 	pub fn get_tool_definitions() -> Vec<McpTool> {
 		vec![
@@ -100,6 +203,17 @@ impl MemoryProvider {
 								"type": "string"
 							},
 							"maxItems": 20
+						},
+						"namespace": {
+							"type": "string",
+							"description": "Namespace to store the memory in, e.g. your agent or session name. Memories in other namespaces are not returned by default searches",
+							"pattern": "^[A-Za-z0-9_.:-]{1,64}$",
+							"default": "default"
+						},
+						"force": {
+							"type": "boolean",
+							"description": "Store the memory even when a near-identical one exists. By default such duplicates are merged into the existing memory or rejected",
+							"default": false
 						}
 					},
 					"required": ["title", "content"],
@@ -156,6 +270,17 @@ impl MemoryProvider {
 								"type": "string"
 							}
 						},
+						"namespace": {
+							"type": "string",
+							"description": "Namespace to search, e.g. your agent or session name",
+							"pattern": "^[A-Za-z0-9_.:-]{1,64}$",
+							"default": "default"
+						},
+						"all_namespaces": {
+							"type": "boolean",
+							"description": "Search the memories of every namespace instead of only 'namespace'",
+							"default": false
+						},
 				"limit": {
 					"type": "integer",
 					"description": "Maximum number of memories to return",
@@ -174,6 +299,60 @@ impl MemoryProvider {
 					"additionalProperties": false
 				}),
 			},
+			McpTool {
+				name: "memory_timeline".to_string(),
+				description: "List stored memories chronologically, grouped by day or week, optionally with an LLM digest per period. Use it to review what was recorded over time, e.g. the decisions of the last sprint.".to_string(),
+				input_schema: json!({
+					"type": "object",
+					"properties": {
+						"memory_types": {
+							"type": "array",
+							"description": "Optional filter by memory types",
+							"items": {
+								"type": "string",
+								"enum": ["code", "architecture", "bug_fix", "feature", "documentation", "user_preference", "decision", "learning", "configuration", "testing", "performance", "security", "insight"]
+							}
+						},
+						"tags": {
+							"type": "array",
+							"description": "Optional filter by tags (any of them)",
+							"items": {
+								"type": "string"
+							}
+						},
+						"scope": {
+							"type": "string",
+							"description": "Only memories with a related file under this path, e.g. 'src/auth'"
+						},
+						"since": {
+							"type": "string",
+							"description": "Start date (YYYY-MM-DD) or span back from now, e.g. '14d' or '2w'"
+						},
+						"until": {
+							"type": "string",
+							"description": "End date (YYYY-MM-DD, exclusive) or span back from now"
+						},
+						"group_by": {
+							"type": "string",
+							"enum": ["day", "week"],
+							"default": "week",
+							"description": "Period to group memories by"
+						},
+						"digest": {
+							"type": "boolean",
+							"default": false,
+							"description": "Summarize each period with the configured LLM"
+						},
+						"max_tokens": {
+							"type": "integer",
+							"description": "Maximum tokens allowed in output before truncation (default: 2000, set to 0 for unlimited)",
+							"minimum": 0,
+							"default": 2000
+						}
+					},
+					"additionalProperties": false
+				}),
+			},
 			McpTool {
 				name: "forget".to_string(),
 				description: "Permanently remove specific memories by ID or forget multiple memories matching certain criteria.".to_string(),
@@ -203,6 +382,17 @@ impl MemoryProvider {
 								"type": "string"
 							}
 						},
+						"namespace": {
+							"type": "string",
+							"description": "Namespace whose memories a query may delete",
+							"pattern": "^[A-Za-z0-9_.:-]{1,64}$",
+							"default": "default"
+						},
+						"shared": {
+							"type": "boolean",
+							"description": "Also forget memories shared with every repository behind the MCP proxy. Needs a token valid for all repositories",
+							"default": false
+						},
 						"confirm": {
 							"type": "boolean",
 							"description": "Must be true to confirm deletion",
@@ -260,6 +450,12 @@ impl MemoryProvider {
 
 		let memory_type = MemoryType::from(memory_type_str.to_string());
 
+		let namespace = namespace_argument(arguments, "memorize")?;
+		let force = arguments
+			.get("force")
+			.and_then(|v| v.as_bool())
+			.unwrap_or(false);
+
 		let importance = arguments
 			.get("importance")
 			.and_then(|v| v.as_f64())
@@ -321,6 +517,7 @@ impl MemoryProvider {
 			title = %title,
 			memory_type = ?memory_type,
 			importance = ?importance,
+			namespace = %namespace,
 			"Memorizing new content"
 		);
 
@@ -340,9 +537,18 @@ impl MemoryProvider {
 			.with_details(format!("Path: {}", self.working_directory.display())));
 		}
 
+		let shared = self
+			.shared
+			.as_ref()
+			.filter(|shared| shared.holds(&memory_type));
+		let target = match shared {
+			Some(shared) => &shared.manager,
+			None => &self.memory_manager,
+		};
+
 		let memory_result = {
 			// Lock memory manager for storing - removed timeout to allow embedding generation to complete
-			let mut manager_guard = self.memory_manager.lock().await;
+			let mut manager_guard = target.lock().await;
 
 			manager_guard
 				.memorize(
@@ -352,6 +558,8 @@ impl MemoryProvider {
 					importance,
 					tags,
 					related_files,
+					&namespace,
+					force,
 				)
 				.await
 				.map_err(|e| {
@@ -367,11 +575,18 @@ impl MemoryProvider {
 			);
 		}
 
-		let memory = memory_result;
-
-		// Return plain text response for MCP protocol compliance
 		// Return minimal response for MCP protocol compliance - just success and ID
-		Ok(format!("Memory stored: {}", memory.id))
+		let mut response = match &memory_result {
+			Memorized::Created(memory) => format!("Memory stored: {}", memory.id),
+			Memorized::Merged { memory, distance } => format!(
+				"Merged into existing memory {} (distance {:.3}); pass force to store it separately",
+				memory.id, distance
+			),
+		};
+		if shared.is_some() {
+			response.push_str(" (shared across repositories)");
+		}
+		Ok(response)
 	}
 
 	/// Execute the remember tool
@@ -492,6 +707,17 @@ impl MemoryProvider {
 				None
 			};
 
+		// Only the requested namespace unless all are asked for
+		let namespaces = if arguments
+			.get("all_namespaces")
+			.and_then(|v| v.as_bool())
+			.unwrap_or(false)
+		{
+			None
+		} else {
+			Some(vec![namespace_argument(arguments, "remember")?])
+		};
+
 		// Set limit
 		let limit = arguments
 			.get("limit")
@@ -506,6 +732,7 @@ impl MemoryProvider {
 			.unwrap_or(2000) as usize;
 
 		let memory_query = MemoryQuery {
+			namespaces,
 			memory_types,
 			tags,
 			related_files,
@@ -521,33 +748,24 @@ impl MemoryProvider {
 			queries.len()
 		);
 
-		let results = {
-			// Lock memory manager for searching - removed timeout to allow operations to complete
-			let manager_guard = self.memory_manager.lock().await;
+		let shared_query = self
+			.shared
+			.as_ref()
+			.and_then(|shared| Some((shared, shared.scope_query(&memory_query)?)));
 
-			// Use multi-query method for comprehensive search
-			if queries.len() == 1 {
-				manager_guard
-					.remember(&queries[0], Some(memory_query))
-					.await
-					.map_err(|e| {
-						McpError::internal_error(
-							format!("Failed to search memories: {}", e),
-							"remember",
-						)
-					})?
-			} else {
-				manager_guard
-					.remember_multi(&queries, Some(memory_query))
-					.await
-					.map_err(|e| {
-						McpError::internal_error(
-							format!("Failed to search memories: {}", e),
-							"remember",
-						)
-					})?
-			}
-		};
+		let mut results =
+			Self::search_manager(&self.memory_manager, &queries, memory_query).await?;
+
+		// Merge org-wide memories with the repository's own by relevance
+		if let Some((shared, query)) = shared_query {
+			results.extend(Self::search_manager(&shared.manager, &queries, query).await?);
+			results.sort_by(|a, b| {
+				b.relevance_score
+					.partial_cmp(&a.relevance_score)
+					.unwrap_or(std::cmp::Ordering::Equal)
+			});
+			results.truncate(limit.min(50));
+		}
 
 		if results.is_empty() {
 			return Ok("No stored memories match your query. Try using different search terms, removing filters, or checking if any memories have been stored yet.".to_string());
@@ -560,8 +778,139 @@ impl MemoryProvider {
 		Ok(truncate_output(&output, max_tokens))
 	}
 
-	/// Execute the forget tool
-	pub async fn execute_forget(&self, arguments: &Value) -> Result<String, McpError> {
+	async fn search_manager(
+		manager: &Mutex<MemoryManager>,
+		queries: &[String],
+		memory_query: MemoryQuery,
+	) -> Result<Vec<MemorySearchResult>, McpError> {
+		// Lock memory manager for searching - removed timeout to allow operations to complete
+		let manager_guard = manager.lock().await;
+
+		// Use multi-query method for comprehensive search
+		let results = if queries.len() == 1 {
+			manager_guard
+				.remember(&queries[0], Some(memory_query))
+				.await
+		} else {
+			manager_guard
+				.remember_multi(queries, Some(memory_query))
+				.await
+		};
+
+		results.map_err(|e| {
+			McpError::internal_error(format!("Failed to search memories: {}", e), "remember")
+		})
+	}
+
+	/// Execute the memory_timeline tool
+	pub async fn execute_timeline(&self, arguments: &Value) -> Result<String, McpError> {
+		let strings = |name: &str| -> Option<Vec<String>> {
+			let list: Vec<String> = arguments
+				.get(name)?
+				.as_array()?
+				.iter()
+				.filter_map(|v| v.as_str().map(|s| s.to_string()))
+				.collect();
+			(!list.is_empty()).then_some(list)
+		};
+		let date = |name: &str| {
+			arguments
+				.get(name)
+				.and_then(|v| v.as_str())
+				.map(|value| timeline::parse_since(value, chrono::Utc::now()))
+				.transpose()
+				.map_err(|e| McpError::invalid_params(e.to_string(), "memory_timeline"))
+		};
+
+		let filter = TimelineFilter {
+			memory_types: strings("memory_types")
+				.map(|types| types.into_iter().map(MemoryType::from).collect()),
+			tags: strings("tags"),
+			scope: arguments
+				.get("scope")
+				.and_then(|v| v.as_str())
+				.map(|s| s.to_string()),
+			since: date("since")?,
+			until: date("until")?,
+		};
+		let period: TimelinePeriod = arguments
+			.get("group_by")
+			.and_then(|v| v.as_str())
+			.unwrap_or("week")
+			.parse()
+			.map_err(|e: anyhow::Error| {
+				McpError::invalid_params(e.to_string(), "memory_timeline")
+			})?;
+		let with_digest = arguments
+			.get("digest")
+			.and_then(|v| v.as_bool())
+			.unwrap_or(false);
+		let max_tokens = arguments
+			.get("max_tokens")
+			.and_then(|v| v.as_u64())
+			.unwrap_or(2000) as usize;
+
+		// Org-wide memories are merged into the same periods
+		let mut memories: Vec<Memory> = Vec::new();
+		let managers = std::iter::once(&self.memory_manager)
+			.chain(self.shared.as_ref().map(|shared| &shared.manager));
+		for manager in managers {
+			let groups = manager
+				.lock()
+				.await
+				.timeline(&filter, period)
+				.await
+				.map_err(|e| {
+					McpError::internal_error(
+						format!("Failed to load memories: {}", e),
+						"memory_timeline",
+					)
+				})?;
+			memories.extend(groups.into_iter().flat_map(|group| group.memories));
+		}
+		memories.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+		memories.dedup_by(|a, b| a.id == b.id);
+
+		let mut groups = group_by_period(memories, period);
+		if with_digest {
+			for group in &mut groups {
+				match timeline::digest(&self.config, group).await {
+					Ok(summary) => group.digest = Some(summary),
+					Err(e) => {
+						warn!(error = %e, period = %group.period, "Memory digest failed");
+					}
+				}
+			}
+		}
+
+		let output = crate::memory::formatting::format_timeline_as_text(&groups, false);
+		Ok(truncate_output(&output, max_tokens))
+	}
+
+	/// Shared store a `forget` call with `shared: true` may delete from;
+	/// `org_wide` tells whether the caller's token is valid for every
+	/// repository behind the proxy
+	fn shared_for_forget(&self, org_wide: bool) -> Result<&SharedMemory, &'static str> {
+		let shared = self
+			.shared
+			.as_ref()
+			.ok_or("No memory types are shared across repositories")?;
+		if self.read_only {
+			return Err("Shared memories can't be forgotten on a read-only server");
+		}
+		if !org_wide {
+			return Err("Forgetting shared memories needs a token valid for every repository");
+		}
+		Ok(shared)
+	}
+
+	/// Execute the forget tool; shared memories are only deleted when asked
+	/// for with `shared: true` by an `org_wide` caller
+	pub async fn execute_forget(
+		&self,
+		arguments: &Value,
+		org_wide: bool,
+	) -> Result<String, McpError> {
 		// Check confirm parameter
 		if !arguments
 			.get("confirm")
@@ -574,6 +923,19 @@ impl MemoryProvider {
 			);
 		}
 
+		let shared = if arguments
+			.get("shared")
+			.and_then(|v| v.as_bool())
+			.unwrap_or(false)
+		{
+			match self.shared_for_forget(org_wide) {
+				Ok(shared) => Some(shared),
+				Err(e) => return Ok(format!("❌ {}", e)),
+			}
+		} else {
+			None
+		};
+
 		// Handle specific memory ID deletion
 		if let Some(memory_id) = arguments.get("memory_id").and_then(|v| v.as_str()) {
 			// Validate memory ID format
@@ -589,7 +951,13 @@ impl MemoryProvider {
 
 			// Execute deletion - removed timeout to allow operation to complete
 			let res = {
-				let mut manager_guard = self.memory_manager.lock().await;
+				let mut target = &self.memory_manager;
+				if let Some(shared) = shared {
+					if let Ok(Some(_)) = shared.manager.lock().await.get_memory(memory_id).await {
+						target = &shared.manager;
+					}
+				}
+				let mut manager_guard = target.lock().await;
 				manager_guard.forget(memory_id).await
 			};
 			match res {
@@ -645,8 +1013,14 @@ impl MemoryProvider {
 				None
 			};
 
+			let namespace = match namespace_argument(arguments, "forget") {
+				Ok(namespace) => namespace,
+				Err(e) => return Ok(format!("❌ {}", e.message)),
+			};
+
 			let memory_query = MemoryQuery {
 				query_text: Some(query.to_string()),
+				namespaces: Some(vec![namespace]),
 				memory_types,
 				tags,
 				..Default::default()
@@ -658,10 +1032,21 @@ impl MemoryProvider {
 				"Forgetting memories matching query"
 			);
 
-			let res = {
+			let shared_query =
+				shared.and_then(|shared| Some((shared, shared.scope_query(&memory_query)?)));
+
+			let mut res = {
 				let mut manager_guard = self.memory_manager.lock().await;
 				manager_guard.forget_matching(memory_query).await
 			};
+			if let (Ok(deleted_count), Some((shared, query))) = (&res, shared_query) {
+				let deleted_count = *deleted_count;
+				let mut manager_guard = shared.manager.lock().await;
+				res = manager_guard
+					.forget_matching(query)
+					.await
+					.map(|shared_count| deleted_count + shared_count);
+			}
 			match res {
 				Ok(deleted_count) => Ok(format!(
 					"✅ {} memories deleted successfully\n\nQuery: \"{}\"",
@@ -674,3 +1059,225 @@ impl MemoryProvider {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::embedding::types::InputType;
+	use crate::embedding::EmbeddingProvider;
+	use std::path::PathBuf;
+
+	/// Embeds words hashed into a few dimensions, so no model is needed
+	struct WordHashEmbedding;
+
+	#[async_trait::async_trait]
+	impl EmbeddingProvider for WordHashEmbedding {
+		async fn generate_embedding(&self, text: &str) -> Result<Vec<f32>> {
+			let mut embedding = vec![0.0; 16];
+			embedding[0] = 1.0;
+			for word in text.split_whitespace() {
+				let hash = word
+					.bytes()
+					.fold(7usize, |hash, byte| hash.wrapping_mul(31) + byte as usize);
+				embedding[1 + hash % 15] += 1.0;
+			}
+			Ok(embedding)
+		}
+
+		async fn generate_embeddings_batch(
+			&self,
+			texts: Vec<String>,
+			_input_type: InputType,
+		) -> Result<Vec<Vec<f32>>> {
+			let mut embeddings = Vec::new();
+			for text in texts {
+				embeddings.push(self.generate_embedding(&text).await?);
+			}
+			Ok(embeddings)
+		}
+
+		fn get_dimension(&self) -> usize {
+			16
+		}
+	}
+
+	async fn manager(config: &Config, storage: &Path) -> Arc<Mutex<MemoryManager>> {
+		let manager = MemoryManager::open_with_provider(
+			config,
+			MemoryConfig::default(),
+			&storage.join("memory"),
+			Box::new(WordHashEmbedding),
+		)
+		.await
+		.unwrap();
+		Arc::new(Mutex::new(manager))
+	}
+
+	/// A proxy root with two repositories sharing decisions
+	struct Proxy {
+		root: PathBuf,
+		api: MemoryProvider,
+		web: MemoryProvider,
+	}
+
+	async fn proxy(name: &str) -> Proxy {
+		let root = std::env::temp_dir().join(format!(
+			"octocode-shared-memory-{}-{}",
+			name,
+			std::process::id()
+		));
+		let _ = std::fs::remove_dir_all(&root);
+		let config = Config::load_from_template().unwrap();
+		let shared = SharedMemory {
+			manager: manager(&config, &root.join("shared")).await,
+			memory_types: vec![MemoryType::Decision],
+		};
+
+		let mut repositories = Vec::new();
+		for repository in ["api", "web"] {
+			let working_directory = root.join(repository);
+			std::fs::create_dir_all(&working_directory).unwrap();
+			repositories.push(MemoryProvider {
+				memory_manager: manager(&config, &working_directory.join(".storage")).await,
+				shared: Some(shared.clone()),
+				working_directory,
+				config: config.clone(),
+				read_only: false,
+			});
+		}
+		let web = repositories.pop().unwrap();
+		let api = repositories.pop().unwrap();
+		Proxy { root, api, web }
+	}
+
+	async fn memorize(provider: &MemoryProvider, memory_type: &str, title: &str) -> String {
+		provider
+			.execute_memorize(&json!({
+				"title": title,
+				"content": format!("{} for the whole service", title),
+				"memory_type": memory_type,
+				"force": true,
+			}))
+			.await
+			.unwrap()
+	}
+
+	async fn remember(provider: &MemoryProvider) -> String {
+		provider
+			.execute_remember(&json!({ "query": "storage decision", "limit": 20 }))
+			.await
+			.unwrap()
+	}
+
+	async fn forget(provider: &MemoryProvider, arguments: Value, org_wide: bool) -> String {
+		let mut arguments = arguments;
+		arguments["confirm"] = json!(true);
+		provider.execute_forget(&arguments, org_wide).await.unwrap()
+	}
+
+	#[tokio::test]
+	async fn test_shared_memory_types_reach_every_repository() {
+		let _lock = crate::mcp::CURRENT_DIR_LOCK.lock().await;
+		let proxy = proxy("share").await;
+
+		let shared = memorize(&proxy.api, "decision", "Use Postgres for storage").await;
+		assert!(
+			shared.ends_with("(shared across repositories)"),
+			"{}",
+			shared
+		);
+		let local = memorize(&proxy.api, "code", "Retry helper for outgoing calls").await;
+		assert!(!local.contains("shared"), "{}", local);
+
+		// Only the configured types leave the repository
+		let web = remember(&proxy.web).await;
+		assert!(web.contains("Use Postgres for storage"), "{}", web);
+		assert!(!web.contains("Retry helper"), "{}", web);
+		let api = remember(&proxy.api).await;
+		assert!(api.contains("Use Postgres for storage") && api.contains("Retry helper"));
+
+		let _ = std::fs::remove_dir_all(&proxy.root);
+	}
+
+	#[tokio::test]
+	async fn test_scope_query_to_shared_types() {
+		let _lock = crate::mcp::CURRENT_DIR_LOCK.lock().await;
+		let proxy = proxy("scope").await;
+		let shared = proxy.api.shared.as_ref().unwrap();
+		let scope = |memory_types: Option<Vec<MemoryType>>| {
+			shared
+				.scope_query(&MemoryQuery {
+					memory_types,
+					..Default::default()
+				})
+				.map(|query| query.memory_types.unwrap())
+		};
+
+		assert_eq!(scope(None), Some(vec![MemoryType::Decision]));
+		assert_eq!(
+			scope(Some(vec![MemoryType::Decision, MemoryType::Code])),
+			Some(vec![MemoryType::Decision])
+		);
+		assert_eq!(scope(Some(vec![MemoryType::Code])), None);
+
+		let _ = std::fs::remove_dir_all(&proxy.root);
+	}
+
+	#[tokio::test]
+	async fn test_forget_leaves_shared_memories_unless_asked() {
+		let _lock = crate::mcp::CURRENT_DIR_LOCK.lock().await;
+		let proxy = proxy("forget").await;
+		memorize(&proxy.api, "decision", "Use Postgres for storage").await;
+		memorize(&proxy.api, "code", "Retry helper for outgoing calls").await;
+		memorize(&proxy.web, "code", "Storage decision cache in the browser").await;
+
+		// A query-based forget in one repository only touches its own store
+		let forgotten = forget(&proxy.api, json!({ "query": "storage decision" }), true).await;
+		assert!(
+			forgotten.starts_with("✅ 1 memories deleted"),
+			"{}",
+			forgotten
+		);
+		let web = remember(&proxy.web).await;
+		assert!(web.contains("Use Postgres for storage"), "{}", web);
+		assert!(web.contains("Storage decision cache"), "{}", web);
+
+		// Shared memories need an org-wide caller on a writable server
+		let scoped = json!({ "query": "storage decision", "shared": true });
+		let refused = forget(&proxy.api, scoped.clone(), false).await;
+		assert!(
+			refused.contains("token valid for every repository"),
+			"{}",
+			refused
+		);
+		let read_only = MemoryProvider {
+			read_only: true,
+			..proxy.api.clone()
+		};
+		let refused = forget(&read_only, scoped.clone(), true).await;
+		assert!(refused.contains("read-only"), "{}", refused);
+		assert!(remember(&proxy.web)
+			.await
+			.contains("Use Postgres for storage"));
+
+		// A type filter outside the shared types leaves them alone
+		let code_only =
+			json!({ "query": "storage decision", "shared": true, "memory_types": ["code"] });
+		forget(&proxy.api, code_only, true).await;
+		assert!(remember(&proxy.web)
+			.await
+			.contains("Use Postgres for storage"));
+
+		let forgotten = forget(&proxy.api, scoped, true).await;
+		assert!(
+			forgotten.starts_with("✅ 1 memories deleted"),
+			"{}",
+			forgotten
+		);
+		let web = remember(&proxy.web).await;
+		assert!(!web.contains("Use Postgres for storage"), "{}", web);
+		assert!(web.contains("Storage decision cache"), "{}", web);
+
+		let _ = std::fs::remove_dir_all(&proxy.root);
+	}
+}
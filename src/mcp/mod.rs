@@ -19,16 +19,32 @@
 //! - GraphRagProvider: GraphRAG relationship-aware search
 //! - MemoryProvider: AI memory storage and retrieval
 //! - LspProvider: Language Server Protocol integration
+//! - FederatedSearchProvider: Search across all repositories of the proxy
 //!
 //! The server automatically enables available tools based on configuration.
 
+pub mod auth;
+pub mod control;
+pub mod federated;
 pub mod graphrag;
+pub mod http;
 pub mod logging;
 pub mod lsp;
 pub mod memory;
+pub mod plugins;
 pub mod proxy;
+pub mod rest;
+pub mod roots;
+pub mod sampling;
 pub mod semantic_code;
 pub mod server;
+pub mod session_cache;
+pub mod tls;
+pub mod tool_overrides;
 pub mod types;
 
 pub use server::McpServer;
+
+/// Held by tests that change the process working directory
+#[cfg(test)]
+pub(crate) static CURRENT_DIR_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
@@ -0,0 +1,172 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! MCP tools offered by `tools` plugins
+//!
+//! Each plugin is asked for its tools once, when the server starts; calls to
+//! them are forwarded with their arguments and answered with the text the
+//! plugin returns. Built-in tools keep their names: a plugin tool with the
+//! same name is never called. A read-only server only offers the tools of
+//! plugins declared `read_only`.
+
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+use crate::config::{Config, PluginHook};
+use crate::mcp::types::{McpError, McpTool};
+use crate::plugins::Plugin;
+
+struct PluginTool {
+	plugin: Plugin,
+	name: String,
+	description: String,
+	input_schema: Value,
+}
+
+/// Tools of the configured plugins
+#[derive(Clone, Default)]
+pub struct PluginToolProvider {
+	tools: Arc<Vec<PluginTool>>,
+}
+
+impl PluginToolProvider {
+	/// Ask every `tools` plugin for its tools; plugins that fail to answer
+	/// are left out, and so are plugins not declared read-only when the
+	/// server is
+	pub async fn new(config: &Config, read_only: bool) -> Self {
+		let mut tools = Vec::new();
+		for plugin in Plugin::with_hook(config, PluginHook::Tools) {
+			if read_only && !plugin.is_read_only() {
+				continue;
+			}
+			let listed = plugin
+				.call_async(json!({ "hook": "list_tools" }))
+				.await
+				.and_then(|reply| {
+					serde_json::from_value::<Vec<McpTool>>(reply["tools"].clone()).map_err(|e| {
+						anyhow::anyhow!("Plugin '{}' listed invalid tools: {}", plugin.name(), e)
+					})
+				});
+			match listed {
+				Ok(listed) => tools.extend(listed.into_iter().map(|tool| PluginTool {
+					plugin: plugin.clone(),
+					name: tool.name,
+					description: tool.description,
+					input_schema: tool.input_schema,
+				})),
+				Err(e) => tracing::warn!("Skipping plugin tools: {}", e),
+			}
+		}
+		Self {
+			tools: Arc::new(tools),
+		}
+	}
+
+	pub fn get_tool_definitions(&self) -> Vec<McpTool> {
+		self.tools
+			.iter()
+			.map(|tool| McpTool {
+				name: tool.name.clone(),
+				description: tool.description.clone(),
+				input_schema: tool.input_schema.clone(),
+			})
+			.collect()
+	}
+
+	pub fn has_tool(&self, name: &str) -> bool {
+		self.tools.iter().any(|tool| tool.name == name)
+	}
+
+	/// Tool names, for error messages listing the available tools
+	pub fn tool_names(&self) -> String {
+		self.tools
+			.iter()
+			.map(|tool| format!(", {}", tool.name))
+			.collect()
+	}
+
+	pub async fn execute(&self, name: &str, arguments: &Value) -> Result<String, McpError> {
+		let tool = self
+			.tools
+			.iter()
+			.find(|tool| tool.name == name)
+			.ok_or_else(|| McpError::method_not_found(format!("Unknown tool '{}'", name), name))?;
+		let reply = tool
+			.plugin
+			.call_async(json!({
+				"hook": "call_tool",
+				"name": name,
+				"arguments": arguments,
+			}))
+			.await
+			.map_err(|e| McpError::internal_error(e.to_string(), name))?;
+		reply
+			.get("content")
+			.and_then(Value::as_str)
+			.map(str::to_string)
+			.ok_or_else(|| {
+				McpError::internal_error(
+					format!("Plugin '{}' replied without content", tool.plugin.name()),
+					name,
+				)
+			})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::config::PluginConfig;
+
+	fn plugin(name: &str, read_only: bool) -> PluginConfig {
+		PluginConfig {
+			name: name.to_string(),
+			command: "sh".to_string(),
+			args: vec![
+				"-c".to_string(),
+				format!(
+					r#"cat > /dev/null; echo '{{"tools": [{{"name": "{}", "description": "", "inputSchema": {{}}}}]}}'"#,
+					name
+				),
+			],
+			hooks: vec![PluginHook::Tools],
+			files: Vec::new(),
+			timeout: 10,
+			read_only,
+		}
+	}
+
+	#[cfg(unix)]
+	#[tokio::test]
+	async fn test_read_only_server_only_offers_read_only_plugins() {
+		let mut config = Config::load_from_template().unwrap();
+		config.plugins = vec![plugin("lookup", true), plugin("deploy", false)];
+
+		let all = PluginToolProvider::new(&config, false).await;
+		assert!(all.has_tool("lookup"));
+		assert!(all.has_tool("deploy"));
+
+		let read_only = PluginToolProvider::new(&config, true).await;
+		assert!(read_only.has_tool("lookup"));
+		assert!(!read_only.has_tool("deploy"));
+		assert!(read_only.execute("deploy", &json!({})).await.is_err());
+		let listed: Vec<String> = read_only
+			.get_tool_definitions()
+			.into_iter()
+			.map(|tool| tool.name)
+			.collect();
+		assert_eq!(listed, ["lookup"]);
+	}
+}
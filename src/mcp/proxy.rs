@@ -19,18 +19,25 @@ use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Instant;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
-use tokio::net::{TcpListener, TcpStream};
-use tokio::sync::Mutex;
+use tokio::net::TcpListener;
+use tokio::sync::{Mutex, OnceCell};
 use tokio::time::Duration;
-use tracing::debug;
+use tokio_rustls::TlsAcceptor;
+use tracing::{debug, warn};
 
-use crate::config::Config;
+use crate::config::{Config, McpConfig};
+use crate::mcp::auth::Authenticator;
+use crate::mcp::federated::FederatedSearchProvider;
 use crate::mcp::graphrag::GraphRagProvider;
+use crate::mcp::http;
 use crate::mcp::logging::{
-	init_mcp_logging, log_critical_anyhow_error, log_mcp_request, log_mcp_response,
+	init_mcp_logging, log_auth_rejection, log_critical_anyhow_error, log_mcp_request,
+	log_mcp_response,
 };
-use crate::mcp::memory::MemoryProvider;
+use crate::mcp::memory::{MemoryProvider, SharedMemory};
 use crate::mcp::semantic_code::SemanticCodeProvider;
+use crate::mcp::tls::{self, HttpStream};
+use crate::mcp::tool_overrides::enforce_token_budget;
 use crate::mcp::types::{JsonRpcError, JsonRpcRequest, JsonRpcResponse, McpError};
 
 // Reuse constants from server.rs for consistency
@@ -45,11 +52,19 @@ struct ProxyMcpInstance {
 	semantic_code: SemanticCodeProvider,
 	graphrag: Option<GraphRagProvider>,
 	memory: Option<MemoryProvider>,
+	federated: FederatedSearchProvider,
+	mcp_config: McpConfig,
 	last_accessed: Arc<Mutex<Instant>>,
 }
 
 impl ProxyMcpInstance {
-	async fn new(config: Config, working_directory: PathBuf, _debug: bool) -> Result<Self> {
+	async fn new(
+		config: Config,
+		working_directory: PathBuf,
+		root_path: PathBuf,
+		shared_memory: Option<SharedMemory>,
+		_debug: bool,
+	) -> Result<Self> {
 		// Skip logging initialization in proxy mode since the main proxy server handles logging
 		// Individual repository instances don't need separate logging as they're part of the same process
 		// The `MCP_LOG_DIR` OnceLock can only be set once per process, so subsequent calls would fail
@@ -57,17 +72,31 @@ impl ProxyMcpInstance {
 		// Reuse exact same provider initialization as McpServer::new
 		let semantic_code = SemanticCodeProvider::new(config.clone(), working_directory.clone());
 		let graphrag = GraphRagProvider::new(config.clone(), working_directory.clone());
-		let memory = MemoryProvider::new(&config, working_directory.clone()).await;
+		let memory = MemoryProvider::new(&config, working_directory.clone(), false)
+			.await
+			.map(|memory| memory.with_shared(shared_memory));
+		let federated = FederatedSearchProvider::new(config.clone(), root_path);
 
 		Ok(Self {
 			semantic_code,
 			graphrag,
 			memory,
+			federated,
+			mcp_config: config.mcp,
 			last_accessed: Arc::new(Mutex::new(Instant::now())),
 		})
 	}
 
-	async fn handle_request(&self, request: &JsonRpcRequest) -> JsonRpcResponse {
+	/// Handle a request; `authorized` tells which other repositories the
+	/// caller's token grants access to, for `federated_search`, and
+	/// `org_wide` whether it is valid for all of them, for forgetting shared
+	/// memories
+	async fn handle_request(
+		&self,
+		request: &JsonRpcRequest,
+		authorized: &(dyn Fn(&str) -> bool + Sync),
+		org_wide: bool,
+	) -> JsonRpcResponse {
 		// Update last accessed time
 		*self.last_accessed.lock().await = Instant::now();
 
@@ -75,7 +104,7 @@ impl ProxyMcpInstance {
 		match request.method.as_str() {
 			"initialize" => self.handle_initialize(request),
 			"tools/list" => self.handle_tools_list(request),
-			"tools/call" => self.handle_tools_call(request).await,
+			"tools/call" => self.handle_tools_call(request, authorized, org_wide).await,
 			"ping" => self.handle_ping(request),
 			_ => JsonRpcResponse {
 				jsonrpc: "2.0".to_string(),
@@ -117,6 +146,10 @@ impl ProxyMcpInstance {
 		let mut tools = vec![
 			SemanticCodeProvider::get_tool_definition(),
 			SemanticCodeProvider::get_view_signatures_tool_definition(),
+			SemanticCodeProvider::get_resolve_anchor_tool_definition(),
+			SemanticCodeProvider::get_find_similar_tool_definition(),
+			SemanticCodeProvider::get_read_range_tool_definition(),
+			FederatedSearchProvider::get_tool_definition(),
 		];
 
 		// Add memory tools if available
@@ -139,7 +172,12 @@ impl ProxyMcpInstance {
 		}
 	}
 
-	async fn handle_tools_call(&self, request: &JsonRpcRequest) -> JsonRpcResponse {
+	async fn handle_tools_call(
+		&self,
+		request: &JsonRpcRequest,
+		authorized: &(dyn Fn(&str) -> bool + Sync),
+		org_wide: bool,
+	) -> JsonRpcResponse {
 		let params = match &request.params {
 			Some(params) => params,
 			None => {
@@ -202,6 +240,10 @@ impl ProxyMcpInstance {
 		let result = match tool_name {
 			"semantic_search" => self.semantic_code.execute_search(arguments).await,
 			"view_signatures" => self.semantic_code.execute_view_signatures(arguments).await,
+			"resolve_anchor" => self.semantic_code.execute_resolve_anchor(arguments).await,
+			"find_similar" => self.semantic_code.execute_find_similar(arguments).await,
+			"read_range" => self.semantic_code.execute_read_range(arguments).await,
+			"federated_search" => self.federated.execute(arguments, authorized).await,
 			"graphrag" => match &self.graphrag {
 				Some(provider) => provider.execute(arguments).await,
 				None => Err(McpError::method_not_found("GraphRAG is not enabled in the current configuration. Please enable GraphRAG in octocode.toml to use relationship-aware search.", "graphrag")),
@@ -214,18 +256,24 @@ impl ProxyMcpInstance {
 				Some(provider) => provider.execute_remember(arguments).await,
 				None => Err(McpError::method_not_found("Memory system is not available", "remember")),
 			},
+			"memory_timeline" => match &self.memory {
+				Some(provider) => provider.execute_timeline(arguments).await,
+				None => Err(McpError::method_not_found("Memory system is not available", "memory_timeline")),
+			},
 			"forget" => match &self.memory {
-				Some(provider) => provider.execute_forget(arguments).await,
+				Some(provider) => provider.execute_forget(arguments, org_wide).await,
 				None => Err(McpError::method_not_found("Memory system is not available", "forget")),
 			},
 			_ => {
-				let available_tools = format!("semantic_search, view_signatures{}{}",
+				let available_tools = format!("semantic_search, view_signatures, resolve_anchor, find_similar, read_range, federated_search{}{}",
 				if self.graphrag.is_some() { ", graphrag" } else { "" },
-					if self.memory.is_some() { ", memorize, remember, forget" } else { "" }
+					if self.memory.is_some() { ", memorize, remember, memory_timeline, forget" } else { "" }
 				);
 				Err(McpError::method_not_found(format!("Unknown tool '{}'. Available tools: {}", tool_name, available_tools), "proxy_call"))
 			}
 		};
+		let result =
+			result.map(|content| enforce_token_budget(tool_name, content, &self.mcp_config));
 
 		match result {
 			Ok(content) => JsonRpcResponse {
@@ -294,6 +342,9 @@ pub struct McpProxyServer {
 	root_path: PathBuf,
 	debug: bool,
 	instances: Arc<Mutex<HashMap<String, ProxyMcpInstance>>>,
+	shared_memory: Arc<OnceCell<Option<SharedMemory>>>,
+	tls_acceptor: Option<TlsAcceptor>,
+	auth: Arc<Authenticator>,
 }
 
 impl McpProxyServer {
@@ -311,10 +362,18 @@ impl McpProxyServer {
 			root_path,
 			debug: debug_mode,
 			instances: Arc::new(Mutex::new(HashMap::new())),
+			shared_memory: Arc::new(OnceCell::new()),
+			tls_acceptor: None,
+			auth: Arc::new(Authenticator::default()),
 		})
 	}
 
 	pub async fn run(&mut self) -> Result<()> {
+		// Fail before binding if the TLS material or a token is unusable
+		let mcp_config = Config::load()?.mcp;
+		self.tls_acceptor = tls::build_acceptor(&mcp_config.tls)?;
+		self.auth = Arc::new(Authenticator::from_config(&mcp_config.auth)?);
+
 		let listener = TcpListener::bind(&self.bind_addr)
 			.await
 			.map_err(|e| anyhow::anyhow!("Failed to bind to {}: {}", self.bind_addr, e))?;
@@ -342,7 +401,8 @@ impl McpProxyServer {
 		});
 
 		println!(
-			"✅ Proxy server ready! Send requests to http://{}/org/repo",
+			"✅ Proxy server ready! Send requests to {}://{}/org/repo",
+			self.scheme(),
 			self.bind_addr
 		);
 
@@ -351,13 +411,29 @@ impl McpProxyServer {
 			match listener.accept().await {
 				Ok((stream, addr)) => {
 					let instances = self.instances.clone();
+					let shared_memory = self.shared_memory.clone();
 					let root_path = self.root_path.clone();
+					let tls_acceptor = self.tls_acceptor.clone();
+					let auth = self.auth.clone();
 					let debug = self.debug;
 
 					tokio::spawn(async move {
-						if let Err(e) =
-							Self::handle_connection(stream, instances, root_path, debug).await
-						{
+						let result = match tls::accept(tls_acceptor.as_ref(), stream).await {
+							Ok(stream) => {
+								Self::handle_connection(
+									stream,
+									instances,
+									shared_memory,
+									root_path,
+									&auth,
+									addr,
+									debug,
+								)
+								.await
+							}
+							Err(e) => Err(e),
+						};
+						if let Err(e) = result {
 							debug!("Connection error from {}: {}", addr, e);
 						}
 					});
@@ -393,8 +469,9 @@ impl McpProxyServer {
 					.unwrap_or(repo_path)
 					.to_string_lossy();
 				println!(
-					"   📂 {} → http://{}/{}",
+					"   📂 {} → {}://{}/{}",
 					repo_path.display(),
+					self.scheme(),
 					self.bind_addr,
 					relative_path
 				);
@@ -405,11 +482,24 @@ impl McpProxyServer {
 		Ok(())
 	}
 
+	fn scheme(&self) -> &'static str {
+		if self.tls_acceptor.is_some() {
+			"https"
+		} else {
+			"http"
+		}
+	}
+
 	async fn discover_repositories(&self) -> Result<Vec<PathBuf>> {
+		Self::discover_repositories_under(&self.root_path)
+	}
+
+	/// Git repositories under `root_path` that the proxy would serve, sorted by path
+	pub fn discover_repositories_under(root_path: &Path) -> Result<Vec<PathBuf>> {
 		let mut repositories = Vec::new();
 
 		// Use std::fs for sync operations since we're doing simple directory traversal
-		Self::find_git_repos_recursive(&self.root_path, &mut repositories)?;
+		Self::find_git_repos_recursive(root_path, &mut repositories)?;
 
 		repositories.sort();
 		Ok(repositories)
@@ -445,9 +535,12 @@ impl McpProxyServer {
 	}
 
 	async fn handle_connection(
-		mut stream: TcpStream,
+		mut stream: Box<dyn HttpStream>,
 		instances: Arc<Mutex<HashMap<String, ProxyMcpInstance>>>,
+		shared_memory: Arc<OnceCell<Option<SharedMemory>>>,
 		root_path: PathBuf,
+		auth: &Authenticator,
+		peer: SocketAddr,
 		debug: bool,
 	) -> Result<()> {
 		let mut buffer = vec![0; 8192];
@@ -496,6 +589,7 @@ impl McpProxyServer {
 		let mut content_length = 0;
 		let mut body_start = 0;
 		let mut header_end_found = false;
+		let mut authorization = None;
 
 		// Find headers and body boundary
 		let header_body_split = request_str
@@ -503,13 +597,17 @@ impl McpProxyServer {
 			.or_else(|| request_str.find("\n\n"));
 
 		if let Some(split_pos) = header_body_split {
-			// Parse headers to find content-length
+			// Parse headers to find content-length and authorization
 			let headers_part = &request_str[..split_pos];
 			for line in headers_part.lines() {
 				if line.to_lowercase().starts_with("content-length:") {
 					if let Some(len_str) = line.split(':').nth(1) {
 						content_length = len_str.trim().parse().unwrap_or(0);
 					}
+				} else if let Some((name, value)) = line.split_once(':') {
+					if name.trim().eq_ignore_ascii_case("authorization") {
+						authorization = Some(value.trim());
+					}
 				}
 			}
 
@@ -527,6 +625,11 @@ impl McpProxyServer {
 			return Ok(());
 		}
 
+		if let Err(rejection) = auth.check(Some(&repo_path), authorization) {
+			log_auth_rejection("proxy", &peer, Some(&repo_path), rejection.as_str());
+			return http::write_unauthorized(&mut stream, rejection.message()).await;
+		}
+
 		let json_body = if content_length > 0 && body_start < bytes_read {
 			let body_bytes =
 				&buffer[body_start..std::cmp::min(body_start + content_length, bytes_read)];
@@ -564,23 +667,36 @@ impl McpProxyServer {
 		let request_method = request.method.clone();
 
 		// Get or create MCP instance for this repository
-		let instance =
-			match Self::get_or_create_instance(&instances, &repo_path, &root_path, debug).await {
-				Ok(instance) => instance,
-				Err(e) => {
-					debug!("Failed to get MCP instance for {}: {}", repo_path, e);
-					Self::send_http_error(
-						&mut stream,
-						404,
-						&format!("Repository not found: {}", repo_path),
-					)
-					.await?;
-					return Ok(());
-				}
-			};
+		let instance = match Self::get_or_create_instance(
+			&instances,
+			&shared_memory,
+			&repo_path,
+			&root_path,
+			debug,
+		)
+		.await
+		{
+			Ok(instance) => instance,
+			Err(e) => {
+				debug!("Failed to get MCP instance for {}: {}", repo_path, e);
+				Self::send_http_error(
+					&mut stream,
+					404,
+					&format!("Repository not found: {}", repo_path),
+				)
+				.await?;
+				return Ok(());
+			}
+		};
 
 		// Handle the request
-		let response = instance.handle_request(&request).await;
+		// A token scoped to this repository doesn't reach the others through
+		// federated_search
+		let authorized = |repository: &str| auth.check(Some(repository), authorization).is_ok();
+		let org_wide = auth.is_org_wide(authorization);
+		let response = instance
+			.handle_request(&request, &authorized, org_wide)
+			.await;
 
 		// Log the response
 		let duration_ms = start_time.elapsed().as_millis() as u64;
@@ -610,6 +726,7 @@ impl McpProxyServer {
 
 	async fn get_or_create_instance(
 		instances: &Arc<Mutex<HashMap<String, ProxyMcpInstance>>>,
+		shared_memory: &OnceCell<Option<SharedMemory>>,
 		repo_path: &str,
 		root_path: &Path,
 		debug: bool,
@@ -646,7 +763,22 @@ impl McpProxyServer {
 		println!("   📂 Path: {}", full_path.display());
 
 		let config = Config::load()?;
-		let instance = ProxyMcpInstance::new(config, full_path, debug).await?;
+
+		// The org-wide memory store is opened once and shared by every instance
+		let shared = match shared_memory
+			.get_or_try_init(|| SharedMemory::open(&config, root_path))
+			.await
+		{
+			Ok(shared) => shared.clone(),
+			Err(e) => {
+				warn!("Failed to open shared memory store: {}", e);
+				None
+			}
+		};
+
+		let instance =
+			ProxyMcpInstance::new(config, full_path, root_path.to_path_buf(), shared, debug)
+				.await?;
 
 		// Store and return
 		instances_guard.insert(repo_path.to_string(), instance.clone());
@@ -675,7 +807,11 @@ impl McpProxyServer {
 		}
 	}
 
-	async fn send_http_error(stream: &mut TcpStream, status: u16, message: &str) -> Result<()> {
+	async fn send_http_error(
+		stream: &mut dyn HttpStream,
+		status: u16,
+		message: &str,
+	) -> Result<()> {
 		let status_text = match status {
 			400 => "Bad Request",
 			404 => "Not Found",
@@ -689,19 +825,24 @@ impl McpProxyServer {
 		);
 
 		stream.write_all(response.as_bytes()).await?;
+		stream.flush().await?;
 		Ok(())
 	}
 
-	async fn send_http_response(stream: &mut TcpStream, response: &JsonRpcResponse) -> Result<()> {
+	async fn send_http_response(
+		stream: &mut dyn HttpStream,
+		response: &JsonRpcResponse,
+	) -> Result<()> {
 		let json_response = serde_json::to_string(response)?;
 
 		let http_response = format!(
-			"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nAccess-Control-Allow-Origin: *\r\nAccess-Control-Allow-Methods: POST, OPTIONS\r\nAccess-Control-Allow-Headers: Content-Type\r\n\r\n{}",
+			"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nAccess-Control-Allow-Origin: *\r\nAccess-Control-Allow-Methods: POST, OPTIONS\r\nAccess-Control-Allow-Headers: Content-Type, Authorization\r\n\r\n{}",
 			json_response.len(),
 			json_response
 		);
 
 		stream.write_all(http_response.as_bytes()).await?;
+		stream.flush().await?;
 		Ok(())
 	}
 }
@@ -0,0 +1,459 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! REST API over the MCP tool providers
+//!
+//! Each endpoint maps onto one MCP tool: arguments come from the query string
+//! (GET) or a JSON body (POST/DELETE) and are passed to the provider as-is.
+//! Responses are `{"result": ...}` on success and `{"error": ...}` otherwise;
+//! tool output that is itself JSON is embedded as a JSON value.
+//!
+//! With `[mcp.auth]` tokens configured, every request needs one of them as a
+//! bearer token. Without tokens, memories can only be changed over loopback.
+
+use anyhow::Result;
+use serde_json::{json, Map, Value};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tracing::{debug, info};
+
+use crate::config::Config;
+use crate::mcp::auth::Authenticator;
+use crate::mcp::graphrag::GraphRagProvider;
+use crate::mcp::http::{self, HttpRequest};
+use crate::mcp::logging::{
+	log_auth_rejection, log_critical_anyhow_error, log_mcp_request, log_mcp_response,
+};
+use crate::mcp::memory::MemoryProvider;
+use crate::mcp::semantic_code::SemanticCodeProvider;
+use crate::mcp::server::request_limit;
+use crate::mcp::tls::{self, HttpStream};
+use crate::mcp::types::McpError;
+
+const REST_MAX_REQUEST_SIZE: usize = 1_048_576; // 1MB maximum request size
+
+/// Query string parameters always passed to tools as arrays
+const ARRAY_PARAMS: &[&str] = &["files", "tags", "related_files", "memory_types"];
+
+/// Query string parameters passed to tools as numbers
+const NUMBER_PARAMS: &[&str] = &[
+	"max_results",
+	"max_tokens",
+	"threshold",
+	"limit",
+	"importance",
+	"max_depth",
+];
+
+/// REST API server exposing search, signatures, GraphRAG and memory
+pub struct RestServer {
+	semantic_code: SemanticCodeProvider,
+	graphrag: Option<GraphRagProvider>,
+	memory: Option<MemoryProvider>,
+	config: Config,
+	read_only: bool,
+	/// Tokens of `[mcp.auth]`
+	auth: Authenticator,
+	/// Whether memories may be changed: with tokens configured, or when
+	/// listening on loopback only
+	writable: bool,
+	/// Tool calls allowed to run at once, from `mcp.max_concurrent_requests`
+	request_limit: Option<Arc<tokio::sync::Semaphore>>,
+}
+
+impl RestServer {
+	pub async fn new(config: Config, working_directory: PathBuf, read_only: bool) -> Self {
+		let semantic_code = SemanticCodeProvider::new(config.clone(), working_directory.clone());
+		let graphrag = GraphRagProvider::new(config.clone(), working_directory.clone());
+		let memory = MemoryProvider::new(&config, working_directory, read_only).await;
+		let request_limit = request_limit(config.mcp.max_concurrent_requests);
+
+		Self {
+			semantic_code,
+			graphrag,
+			memory,
+			config,
+			read_only,
+			auth: Authenticator::default(),
+			writable: false,
+			request_limit,
+		}
+	}
+
+	pub async fn run(mut self, bind_addr: SocketAddr) -> Result<()> {
+		// Fail before binding if the TLS material or a token is unusable
+		let tls_acceptor = tls::build_acceptor(&self.config.mcp.tls)?;
+		self.auth = Authenticator::from_config(&self.config.mcp.auth)?;
+		self.writable = self.auth.is_enabled() || bind_addr.ip().is_loopback();
+		let listener = TcpListener::bind(&bind_addr)
+			.await
+			.map_err(|e| anyhow::anyhow!("Failed to bind to {}: {}", bind_addr, e))?;
+
+		info!(
+			tls = tls_acceptor.is_some(),
+			read_only = self.read_only,
+			auth = self.auth.is_enabled(),
+			"REST API server listening on {}",
+			bind_addr
+		);
+		println!(
+			"REST API listening on {}://{} (endpoints: /search, /view, /graphrag/nodes, /memory)",
+			if tls_acceptor.is_some() {
+				"https"
+			} else {
+				"http"
+			},
+			bind_addr
+		);
+
+		let server = Arc::new(self);
+		loop {
+			match listener.accept().await {
+				Ok((stream, addr)) => {
+					let server = server.clone();
+					let tls_acceptor = tls_acceptor.clone();
+					tokio::spawn(async move {
+						let result = match tls::accept(tls_acceptor.as_ref(), stream).await {
+							Ok(stream) => server.handle_connection(stream, addr).await,
+							Err(e) => Err(e),
+						};
+						if let Err(e) = result {
+							debug!("REST connection error from {}: {}", addr, e);
+						}
+					});
+				}
+				Err(e) => {
+					log_critical_anyhow_error(
+						"REST server accept error",
+						&anyhow::anyhow!("{}", e),
+					);
+					break;
+				}
+			}
+		}
+
+		Ok(())
+	}
+
+	async fn handle_connection(
+		&self,
+		mut stream: Box<dyn HttpStream>,
+		peer: SocketAddr,
+	) -> Result<()> {
+		let request = match http::read_request(&mut stream, REST_MAX_REQUEST_SIZE).await {
+			Ok(Some(request)) => request,
+			Ok(None) => return Ok(()),
+			Err(e) => {
+				debug!("Failed to read HTTP request: {}", e);
+				return write_json(&mut stream, 400, &json!({"error": "Invalid HTTP request"}))
+					.await;
+			}
+		};
+
+		if request.method == "OPTIONS" {
+			return http::write_response(&mut stream, 204, None, &[], b"").await;
+		}
+
+		if let Err(rejection) = self.auth.check(None, request.header("authorization")) {
+			log_auth_rejection("rest", &peer, None, rejection.as_str());
+			return http::write_unauthorized(&mut stream, rejection.message()).await;
+		}
+
+		let (status, body) = self.route(&request).await;
+		write_json(&mut stream, status, &body).await
+	}
+
+	/// Dispatch a request to its tool; returns the status and response body
+	async fn route(&self, request: &HttpRequest) -> (u16, Value) {
+		let (path, query) = request
+			.path
+			.split_once('?')
+			.unwrap_or((request.path.as_str(), ""));
+
+		let mut arguments = match request_arguments(query, &request.body) {
+			Ok(arguments) => arguments,
+			Err(message) => return (400, json!({ "error": message })),
+		};
+
+		let tool = match (request.method.as_str(), path.trim_end_matches('/')) {
+			("GET", "/health") => return (200, json!({"result": {"status": "ok"}})),
+			("GET" | "POST", "/search") => "semantic_search",
+			("GET" | "POST", "/view") => "view_signatures",
+			("GET" | "POST", "/graphrag/nodes") => {
+				// A node id looks one node up; otherwise nodes are searched
+				let operation = if arguments.contains_key("node_id") {
+					"get-node"
+				} else {
+					"search"
+				};
+				arguments.insert("operation".to_string(), json!(operation));
+				arguments.entry("format").or_insert_with(|| json!("json"));
+				"graphrag"
+			}
+			("GET", "/memory") => "remember",
+			("POST", "/memory") => "memorize",
+			("DELETE", "/memory") => "forget",
+			("GET" | "POST", "/memory/timeline") => "memory_timeline",
+			(
+				_,
+				"/health" | "/search" | "/view" | "/graphrag/nodes" | "/memory"
+				| "/memory/timeline",
+			) => {
+				return (405, json!({"error": "Method not allowed"}));
+			}
+			_ => return (404, json!({"error": "Not found"})),
+		};
+
+		if matches!(tool, "memorize" | "forget") {
+			if self.read_only {
+				return (
+					403,
+					json!({"error": "Server runs in read-only mode: memories cannot be changed"}),
+				);
+			}
+			if !self.writable {
+				return (
+					403,
+					json!({"error": "Memories can only be changed with [mcp.auth] tokens configured or over loopback"}),
+				);
+			}
+		}
+
+		let arguments = Value::Object(arguments);
+		log_mcp_request(tool, Some(&arguments), None);
+		// Calls over the limit wait here for a running one to finish
+		let _permit = match &self.request_limit {
+			Some(limit) => limit.clone().acquire_owned().await.ok(),
+			None => None,
+		};
+		let start_time = std::time::Instant::now();
+		let result = self.call_tool(tool, &arguments).await;
+		log_mcp_response(
+			tool,
+			result.is_ok(),
+			None,
+			Some(start_time.elapsed().as_millis() as u64),
+		);
+
+		match result {
+			Ok(output) => {
+				// Tools answering in JSON are embedded as values, not strings
+				let result = serde_json::from_str::<Value>(&output)
+					.ok()
+					.filter(|value| value.is_object() || value.is_array())
+					.unwrap_or(Value::String(output));
+				(200, json!({ "result": result }))
+			}
+			Err(e) => (status_for(&e), json!({ "error": e.to_string() })),
+		}
+	}
+
+	async fn call_tool(&self, tool: &str, arguments: &Value) -> Result<String, McpError> {
+		match tool {
+			"semantic_search" => self.semantic_code.execute_search(arguments).await,
+			"view_signatures" => self.semantic_code.execute_view_signatures(arguments).await,
+			"graphrag" => match &self.graphrag {
+				Some(provider) => provider.execute(arguments).await,
+				None => Err(McpError::method_not_found(
+					"GraphRAG is not enabled in the current configuration",
+					"graphrag",
+				)),
+			},
+			_ => {
+				let Some(memory) = &self.memory else {
+					return Err(McpError::method_not_found(
+						"Memory system is not available",
+						tool,
+					));
+				};
+				match tool {
+					"memorize" => memory.execute_memorize(arguments).await,
+					"forget" => memory.execute_forget(arguments, false).await,
+					"memory_timeline" => memory.execute_timeline(arguments).await,
+					_ => memory.execute_remember(arguments).await,
+				}
+			}
+		}
+	}
+}
+
+/// HTTP status for a tool error
+fn status_for(error: &McpError) -> u16 {
+	match error.code {
+		-32602 => 400,
+		-32601 => 404,
+		_ => 500,
+	}
+}
+
+async fn write_json(stream: &mut dyn HttpStream, status: u16, body: &Value) -> Result<()> {
+	let body = serde_json::to_vec(body)?;
+	http::write_response(stream, status, Some("application/json"), &[], &body).await
+}
+
+/// Tool arguments from the query string, overlaid with a JSON object body
+pub fn request_arguments(query: &str, body: &[u8]) -> Result<Map<String, Value>, String> {
+	let mut arguments = Map::new();
+
+	for (key, value) in url::form_urlencoded::parse(query.as_bytes()) {
+		let value = if NUMBER_PARAMS.contains(&key.as_ref()) {
+			value
+				.parse::<f64>()
+				.ok()
+				.and_then(|number| {
+					if number.fract() == 0.0 && number >= 0.0 {
+						Some(json!(number as u64))
+					} else {
+						serde_json::Number::from_f64(number).map(Value::Number)
+					}
+				})
+				.ok_or_else(|| format!("Parameter '{}' must be a number", key))?
+		} else {
+			match value.as_ref() {
+				"true" => Value::Bool(true),
+				"false" => Value::Bool(false),
+				_ => Value::String(value.into_owned()),
+			}
+		};
+
+		// Repeated parameters become arrays, e.g. ?query=a&query=b
+		match arguments.get_mut(key.as_ref()) {
+			Some(Value::Array(values)) => values.push(value),
+			Some(existing) => *existing = json!([existing.take(), value]),
+			None if ARRAY_PARAMS.contains(&key.as_ref()) => {
+				arguments.insert(key.into_owned(), json!([value]));
+			}
+			None => {
+				arguments.insert(key.into_owned(), value);
+			}
+		}
+	}
+
+	if !body.iter().all(u8::is_ascii_whitespace) {
+		match serde_json::from_slice::<Value>(body) {
+			Ok(Value::Object(fields)) => arguments.extend(fields),
+			_ => return Err("Request body must be a JSON object".to_string()),
+		}
+	}
+
+	Ok(arguments)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_request_arguments() {
+		let arguments = request_arguments(
+			"query=auth+flow&query=login%20checks&max_results=5&threshold=0.4&files=src%2Fmain.rs&exclude_tests=true",
+			b"",
+		)
+		.unwrap();
+		assert_eq!(arguments["query"], json!(["auth flow", "login checks"]));
+		assert_eq!(arguments["max_results"], json!(5));
+		assert_eq!(arguments["threshold"], json!(0.4));
+		assert_eq!(arguments["files"], json!(["src/main.rs"]));
+		assert_eq!(arguments["exclude_tests"], json!(true));
+
+		let arguments = request_arguments(
+			"mode=code",
+			br#"{"query": "error handling", "mode": "all"}"#,
+		)
+		.unwrap();
+		assert_eq!(arguments["query"], json!("error handling"));
+		assert_eq!(arguments["mode"], json!("all"));
+
+		assert!(request_arguments("max_results=many", b"").is_err());
+		assert!(request_arguments("", b"[1, 2]").is_err());
+	}
+	fn server(auth: Authenticator, writable: bool) -> RestServer {
+		let config = Config::load_from_template().unwrap();
+		RestServer {
+			semantic_code: SemanticCodeProvider::new(config.clone(), std::env::temp_dir()),
+			graphrag: None,
+			memory: None,
+			config,
+			read_only: false,
+			auth,
+			writable,
+			request_limit: None,
+		}
+	}
+
+	/// Status line of the response to a raw request
+	async fn status(server: &RestServer, raw: &str) -> String {
+		use tokio::io::{AsyncReadExt, AsyncWriteExt};
+		let (mut client, stream) = tokio::io::duplex(8192);
+		client.write_all(raw.as_bytes()).await.unwrap();
+		let peer: SocketAddr = "127.0.0.1:9".parse().unwrap();
+		server
+			.handle_connection(Box::new(stream), peer)
+			.await
+			.unwrap();
+		let mut response = String::new();
+		client.read_to_string(&mut response).await.unwrap();
+		response.lines().next().unwrap_or("").to_string()
+	}
+
+	#[tokio::test]
+	async fn test_auth_and_memory_writes() {
+		let memorize = "POST /memory HTTP/1.1\r\nContent-Length: 2\r\n\r\n{}";
+		let health = "GET /health HTTP/1.1\r\n\r\n";
+
+		// Without tokens, beyond loopback: reads work, memory writes don't
+		let open = server(Authenticator::default(), false);
+		assert!(status(&open, health).await.contains("200"));
+		assert!(status(&open, memorize).await.contains("403"));
+
+		let auth = Authenticator::from_config(&crate::config::McpAuthConfig {
+			tokens: vec!["secret".to_string()],
+			..Default::default()
+		})
+		.unwrap();
+		let protected = server(auth, true);
+		assert!(status(&protected, health).await.contains("401"));
+		let authorized = "GET /health HTTP/1.1\r\nAuthorization: Bearer secret\r\n\r\n";
+		assert!(status(&protected, authorized).await.contains("200"));
+	}
+
+	#[tokio::test]
+	async fn test_tool_calls_beyond_the_limit_wait() {
+		let mut limited = server(Authenticator::default(), true);
+		limited.request_limit = request_limit(1);
+		let remember = "GET /memory?query=auth HTTP/1.1\r\n\r\n";
+		let health = "GET /health HTTP/1.1\r\n\r\n";
+		let wait = std::time::Duration::from_millis(200);
+
+		// With the only permit taken, tool calls wait; health checks don't
+		let held = limited
+			.request_limit
+			.clone()
+			.unwrap()
+			.acquire_owned()
+			.await
+			.unwrap();
+		assert!(tokio::time::timeout(wait, status(&limited, remember))
+			.await
+			.is_err());
+		assert!(status(&limited, health).await.contains("200"));
+
+		drop(held);
+		assert!(tokio::time::timeout(wait, status(&limited, remember))
+			.await
+			.is_ok());
+	}
+}
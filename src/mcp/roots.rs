@@ -0,0 +1,119 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! MCP workspace roots
+//!
+//! Clients that declare the `roots` capability (typically IDEs) tell the
+//! server which workspace folders are open. The server asks for them after
+//! initialization and again on `notifications/roots/list_changed`, and
+//! retargets its project store when the workspace moves away from the
+//! directory it is currently serving.
+
+use anyhow::Result;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+
+use crate::mcp::sampling;
+
+/// Extract the `roots` capability from `initialize` params
+pub fn client_supports_roots(params: Option<&Value>) -> bool {
+	params
+		.and_then(|p| p.get("capabilities"))
+		.and_then(|c| c.get("roots"))
+		.is_some()
+}
+
+/// Ask the client for its current workspace roots
+pub async fn request_roots() -> Result<Vec<PathBuf>> {
+	let result = sampling::send_request("roots/list", json!({})).await?;
+	Ok(parse_roots(&result))
+}
+
+/// Local directories from a `roots/list` result; non-`file://` roots are skipped
+fn parse_roots(result: &Value) -> Vec<PathBuf> {
+	result
+		.get("roots")
+		.and_then(|roots| roots.as_array())
+		.map(|roots| {
+			roots
+				.iter()
+				.filter_map(|root| root.get("uri").and_then(|uri| uri.as_str()))
+				.filter_map(root_uri_to_path)
+				.collect()
+		})
+		.unwrap_or_default()
+}
+
+/// Convert a `file://` root URI into a local path
+fn root_uri_to_path(uri: &str) -> Option<PathBuf> {
+	let url = url::Url::parse(uri).ok()?;
+	if url.scheme() != "file" {
+		return None;
+	}
+	url.to_file_path().ok()
+}
+
+/// Pick the directory to serve from the client's roots.
+///
+/// Returns `None` when the current directory is still one of the roots (or
+/// inside one), so an unchanged workspace never triggers a retarget.
+pub fn select_root(roots: &[PathBuf], current: &Path) -> Option<PathBuf> {
+	if roots
+		.iter()
+		.any(|root| current == root || current.starts_with(root))
+	{
+		return None;
+	}
+	roots.iter().find(|root| root.is_dir()).cloned()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse_roots_keeps_file_uris_only() {
+		let result = json!({
+			"roots": [
+				{ "uri": "file:///home/user/project", "name": "project" },
+				{ "uri": "https://example.com/repo" },
+				{ "uri": "file:///home/user/with%20space" }
+			]
+		});
+		assert_eq!(
+			parse_roots(&result),
+			vec![
+				PathBuf::from("/home/user/project"),
+				PathBuf::from("/home/user/with space")
+			]
+		);
+	}
+
+	#[test]
+	fn test_select_root_keeps_current_workspace() {
+		let roots = vec![PathBuf::from("/home/user/project")];
+		assert_eq!(select_root(&roots, Path::new("/home/user/project")), None);
+		assert_eq!(
+			select_root(&roots, Path::new("/home/user/project/crate")),
+			None
+		);
+	}
+
+	#[test]
+	fn test_select_root_switches_to_existing_directory() {
+		let dir = std::env::temp_dir();
+		let roots = vec![PathBuf::from("/nonexistent/root"), dir.clone()];
+		assert_eq!(select_root(&roots, Path::new("/somewhere/else")), Some(dir));
+	}
+}
@@ -0,0 +1,297 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! MCP sampling bridge
+//!
+//! When the connected client advertises the `sampling` capability, LLM
+//! requests made by the server (GraphRAG descriptions, relationship
+//! discovery, ...) are sent to the client as `sampling/createMessage`
+//! requests instead of calling a provider API. This makes the MCP server
+//! usable without any LLM API key configured on the server side.
+//!
+//! The bridge is process-wide: the stdio server installs it with the shared
+//! stdout writer, and the stdin reader task hands responses back through
+//! [`route_response`]. Other server-initiated requests (such as `roots/list`)
+//! go through [`send_request`] on the same bridge, and notifications (such as
+//! streamed search results) through [`send_notification`].
+
+use anyhow::Result;
+use parking_lot::Mutex;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::oneshot;
+use tokio::time::Duration;
+use tracing::debug;
+
+/// Prefix for ids of server-initiated requests, so they never clash with client ids
+const SAMPLING_ID_PREFIX: &str = "octocode-sampling-";
+/// How long to wait for the client to answer a sampling request
+const SAMPLING_TIMEOUT_SECS: u64 = 120;
+/// How long to wait for the client to answer any other server-initiated request
+const REQUEST_TIMEOUT_SECS: u64 = 10;
+/// Default completion budget requested from the client
+pub const SAMPLING_DEFAULT_MAX_TOKENS: u32 = 2048;
+
+/// Stdout shared between protocol responses and server-initiated requests
+pub type SharedWriter = Arc<tokio::sync::Mutex<tokio::io::Stdout>>;
+
+type PendingResponse = oneshot::Sender<std::result::Result<Value, String>>;
+
+struct SamplingBridge {
+	writer: SharedWriter,
+	pending: Mutex<HashMap<String, PendingResponse>>,
+	next_id: AtomicU64,
+	client_supported: AtomicBool,
+}
+
+static BRIDGE: OnceLock<SamplingBridge> = OnceLock::new();
+
+/// Install the bridge for the stdio transport. Only the first call has an effect.
+pub fn install(writer: SharedWriter) {
+	let _ = BRIDGE.set(SamplingBridge {
+		writer,
+		pending: Mutex::new(HashMap::new()),
+		next_id: AtomicU64::new(1),
+		client_supported: AtomicBool::new(false),
+	});
+}
+
+/// Record whether the client declared the `sampling` capability in `initialize`
+pub fn set_client_supported(supported: bool) {
+	if let Some(bridge) = BRIDGE.get() {
+		bridge.client_supported.store(supported, Ordering::SeqCst);
+		debug!(supported = supported, "MCP client sampling capability");
+	}
+}
+
+/// Whether LLM requests can currently be routed through the client
+pub fn is_available() -> bool {
+	BRIDGE
+		.get()
+		.is_some_and(|bridge| bridge.client_supported.load(Ordering::SeqCst))
+}
+
+/// Extract the `sampling` capability from `initialize` params
+pub fn client_supports_sampling(params: Option<&Value>) -> bool {
+	params
+		.and_then(|p| p.get("capabilities"))
+		.and_then(|c| c.get("sampling"))
+		.is_some()
+}
+
+/// Deliver a client response to a pending sampling request.
+///
+/// Returns `true` when the message was consumed and must not be handled as a request.
+pub fn route_response(line: &str) -> bool {
+	let Some(bridge) = BRIDGE.get() else {
+		return false;
+	};
+	let Some((id, outcome)) = parse_response(line) else {
+		return false;
+	};
+
+	let Some(sender) = bridge.pending.lock().remove(&id) else {
+		debug!(
+			id = id,
+			"Dropping response for unknown or expired sampling request"
+		);
+		return true;
+	};
+	let _ = sender.send(outcome);
+	true
+}
+
+/// Id and outcome of a client response to a server-initiated request; `None`
+/// for anything else the client sends
+fn parse_response(line: &str) -> Option<(String, std::result::Result<Value, String>)> {
+	let message = serde_json::from_str::<Value>(line.trim()).ok()?;
+	if message.get("method").is_some() {
+		return None;
+	}
+	let id = message
+		.get("id")
+		.and_then(|v| v.as_str())
+		.filter(|id| id.starts_with(SAMPLING_ID_PREFIX))?;
+
+	let outcome = match message.get("error") {
+		Some(error) => Err(error
+			.get("message")
+			.and_then(|m| m.as_str())
+			.unwrap_or("unknown sampling error")
+			.to_string()),
+		None => Ok(message.get("result").cloned().unwrap_or(Value::Null)),
+	};
+	Some((id.to_string(), outcome))
+}
+
+/// Ask the client's model to complete a prompt and return the text response
+pub async fn create_message(
+	system: &str,
+	prompt: &str,
+	model_hint: Option<&str>,
+	max_tokens: u32,
+) -> Result<String> {
+	let bridge = BRIDGE
+		.get()
+		.filter(|bridge| bridge.client_supported.load(Ordering::SeqCst))
+		.ok_or_else(|| anyhow::anyhow!("MCP client does not support sampling"))?;
+
+	let mut params = json!({
+		"messages": [{
+			"role": "user",
+			"content": {
+				"type": "text",
+				"text": prompt
+			}
+		}],
+		"systemPrompt": system,
+		"maxTokens": max_tokens,
+		"includeContext": "none"
+	});
+	if let Some(model) = model_hint {
+		params["modelPreferences"] = json!({ "hints": [{ "name": model }] });
+	}
+
+	let result = request_with_timeout(
+		bridge,
+		"sampling/createMessage",
+		params,
+		SAMPLING_TIMEOUT_SECS,
+	)
+	.await
+	.map_err(|e| anyhow::anyhow!("Sampling failed: {}", e))?;
+	result
+		.get("content")
+		.and_then(|c| c.get("text"))
+		.and_then(|t| t.as_str())
+		.map(String::from)
+		.ok_or_else(|| anyhow::anyhow!("Sampling response has no text content: {}", result))
+}
+
+/// Send a server-initiated request to the client and wait for its result
+pub async fn send_request(method: &str, params: Value) -> Result<Value> {
+	let bridge = BRIDGE
+		.get()
+		.ok_or_else(|| anyhow::anyhow!("No MCP client connected over stdio"))?;
+
+	request_with_timeout(bridge, method, params, REQUEST_TIMEOUT_SECS)
+		.await
+		.map_err(|e| anyhow::anyhow!("{} failed: {}", method, e))
+}
+
+/// Send a server-initiated notification to the client; no answer is expected
+pub async fn send_notification(method: &str, params: Value) -> Result<()> {
+	let bridge = BRIDGE
+		.get()
+		.ok_or_else(|| anyhow::anyhow!("No MCP client connected over stdio"))?;
+
+	let notification = json!({
+		"jsonrpc": "2.0",
+		"method": method,
+		"params": params
+	});
+	let payload = serde_json::to_string(&notification)?;
+	let mut writer = bridge.writer.lock().await;
+	writer.write_all(payload.as_bytes()).await?;
+	writer.write_all(b"\n").await?;
+	writer.flush().await?;
+	Ok(())
+}
+
+async fn request_with_timeout(
+	bridge: &SamplingBridge,
+	method: &str,
+	params: Value,
+	timeout_secs: u64,
+) -> std::result::Result<Value, String> {
+	let id = format!(
+		"{}{}",
+		SAMPLING_ID_PREFIX,
+		bridge.next_id.fetch_add(1, Ordering::SeqCst)
+	);
+	let request = json!({
+		"jsonrpc": "2.0",
+		"id": id,
+		"method": method,
+		"params": params
+	});
+
+	let (tx, rx) = oneshot::channel();
+	bridge.pending.lock().insert(id.clone(), tx);
+
+	let sent: Result<()> = async {
+		let payload = serde_json::to_string(&request)?;
+		let mut writer = bridge.writer.lock().await;
+		writer.write_all(payload.as_bytes()).await?;
+		writer.write_all(b"\n").await?;
+		writer.flush().await?;
+		Ok(())
+	}
+	.await;
+	if let Err(e) = sent {
+		bridge.pending.lock().remove(&id);
+		return Err(e.to_string());
+	}
+
+	match tokio::time::timeout(Duration::from_secs(timeout_secs), rx).await {
+		Ok(Ok(outcome)) => outcome,
+		Ok(Err(_)) => Err("request was cancelled".to_string()),
+		Err(_) => {
+			bridge.pending.lock().remove(&id);
+			Err(format!("client did not answer within {}s", timeout_secs))
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_client_supports_sampling() {
+		let params = json!({"capabilities": {"sampling": {}, "roots": {}}});
+		assert!(client_supports_sampling(Some(&params)));
+		let params = json!({"capabilities": {"roots": {}}});
+		assert!(!client_supports_sampling(Some(&params)));
+		assert!(!client_supports_sampling(None));
+	}
+
+	#[test]
+	fn test_parse_response() {
+		let (id, outcome) = parse_response(
+			r#"{"jsonrpc":"2.0","id":"octocode-sampling-3","result":{"content":{"type":"text","text":"ok"}}}"#,
+		)
+		.unwrap();
+		assert_eq!(id, "octocode-sampling-3");
+		assert_eq!(outcome.unwrap()["content"]["text"], "ok");
+
+		let (_, outcome) = parse_response(
+			r#"{"jsonrpc":"2.0","id":"octocode-sampling-4","error":{"code":-1,"message":"User rejected sampling"}}"#,
+		)
+		.unwrap();
+		assert_eq!(outcome.unwrap_err(), "User rejected sampling");
+
+		// Client requests and responses to ids the server didn't hand out are
+		// left to the request handler
+		assert!(parse_response(
+			r#"{"jsonrpc":"2.0","id":"octocode-sampling-5","method":"tools/call"}"#
+		)
+		.is_none());
+		assert!(parse_response(r#"{"jsonrpc":"2.0","id":7,"result":{}}"#).is_none());
+		assert!(parse_response("not json").is_none());
+	}
+}
@@ -19,11 +19,21 @@ use tracing::debug;
 use crate::config::Config;
 use crate::constants::MAX_QUERIES;
 use crate::embedding::truncate_output;
+use crate::indexer::path_aliases::PathAliases;
 use crate::indexer::search::{
+	decode_cursor, encode_cursor, format_code_search_results_as_text,
 	search_codebase_with_details_multi_query_text, search_codebase_with_details_text,
+	PartialResults,
 };
+use crate::indexer::similar::{find_similar, CodeRange};
 use crate::indexer::{extract_file_signatures, render_signatures_text, NoindexWalker, PathUtils};
 use crate::mcp::types::{McpError, McpTool};
+use crate::store::{
+	Anchor, AnchorKey, ContentFilter, ContentScope, PathFilter, ResolvedAnchor, Store, SymbolFilter,
+};
+
+/// Most context lines read_range adds on either side of a range
+const MAX_READ_CONTEXT_LINES: usize = 100;
 
 /// Semantic code search tool provider
 #[derive(Clone)]
@@ -108,7 +118,33 @@ impl SemanticCodeProvider {
 					},
 					"language": {
 						"type": "string",
-						"description": "Filter by programming language (only affects code blocks). Supported languages: rust, javascript, typescript, python, go, cpp, php, bash, ruby, json, svelte, css"
+						"description": "Filter by programming language (only affects code blocks). Supported languages: rust, javascript, typescript, python, go, cpp, php, bash, ruby, json, svelte, css, hcl, sql, zig, proto, haskell, lua, dart"
+					},
+					"symbol": {
+						"type": "string",
+						"description": "Only return code blocks that declare this exact symbol name, ranked by the query. End with '*' for prefix matching, e.g. 'parse_*'. Use when you know the function/class name; implies mode 'code' and disables the similarity threshold unless one is given",
+						"minLength": 1,
+						"maxLength": 200
+					},
+					"in": {
+						"type": "string",
+						"description": "Only return code blocks where the query text literally appears in comments, string literals, or the code itself (outside comments and strings). Use to find TODOs, user-facing messages or real usages of a term; implies mode 'code' and disables the similarity threshold unless one is given",
+						"enum": ["comments", "strings", "code"]
+					},
+					"paths": {
+						"type": "array",
+						"description": "Only return results from files at or under these paths, relative to the project root, e.g. ['packages/api', 'src/indexer']. Narrows the search to the relevant part of a large repository",
+						"items": {"type": "string", "minLength": 1},
+						"minItems": 1,
+						"maxItems": 20
+					},
+					"cursor": {
+						"type": "string",
+						"description": "Continue a previous search: pass the next_cursor from its response together with the same query and options to get the next page of results"
+					},
+					"exclude_tests": {
+						"type": "boolean",
+						"description": "Leave test files and inline test code (e.g. Rust #[cfg(test)] modules) out of the results. Defaults to config.search.exclude_tests"
 					},
 					"max_tokens": {
 						"type": "integer",
@@ -127,7 +163,7 @@ impl SemanticCodeProvider {
 	pub fn get_view_signatures_tool_definition() -> McpTool {
 		McpTool {
 			name: "view_signatures".to_string(),
-			description: "Extract and view function signatures, class definitions, and other meaningful code structures from files. Shows method signatures, class definitions, interfaces, and other declarations without full implementation details. Perfect for getting an overview of code structure and available APIs. Output includes 1-indexed line ranges and signature code with 1-indexed line numbers prefixed to each line.\nSupported Languages:\nRust, JavaScript, TypeScript, Python, Go, C++, PHP, Ruby, Bash, JSON, CSS, Svelte, Markdown.\nSignatures are the structural definitions of code elements without their implementation details. They include function declarations, method headers, class definitions, interfaces, types, and other high-level constructs that define the API and architecture of code. Signatures provide a concise overview of what functionality exists and how it can be accessed, without showing the actual implementation logic.".to_string(),
+			description: "Extract and view function signatures, class definitions, and other meaningful code structures from files. Shows method signatures, class definitions, interfaces, and other declarations without full implementation details. Perfect for getting an overview of code structure and available APIs. Output includes 1-indexed line ranges and signature code with 1-indexed line numbers prefixed to each line.\nSupported Languages:\nRust, JavaScript, TypeScript, Python, Go, C++, PHP, Ruby, Bash, JSON, CSS, Svelte, Markdown, Terraform/HCL, SQL, Zig, Protobuf, Haskell, Lua, Dart.\nSignatures are the structural definitions of code elements without their implementation details. They include function declarations, method headers, class definitions, interfaces, types, and other high-level constructs that define the API and architecture of code. Signatures provide a concise overview of what functionality exists and how it can be accessed, without showing the actual implementation logic.".to_string(),
 			input_schema: json!({
 				"type": "object",
 				"properties": {
@@ -154,8 +190,140 @@ impl SemanticCodeProvider {
 		}
 	}
 
+	/// Get the tool definition for resolve_anchor
+	pub fn get_resolve_anchor_tool_definition() -> McpTool {
+		McpTool {
+			name: "resolve_anchor".to_string(),
+			description: "Fetch the exact snippet behind an anchor from earlier semantic_search or graphrag results. Every result carries an 'Anchor: path#symbol@hash' line (or 'path@hash' for graph nodes); cite it, and pass it here later to get the current content. If the cited content changed after reindexing, the block is found again by its symbol or line and returned with a fresh anchor.".to_string(),
+			input_schema: json!({
+				"type": "object",
+				"properties": {
+					"anchor": {
+						"type": "string",
+						"description": "Anchor exactly as shown in a result, e.g. 'src/config.rs#parse_config@3fa9c1d2e4b5'",
+						"minLength": 3,
+						"maxLength": 1000
+					},
+					"max_tokens": {
+						"type": "integer",
+						"description": "Maximum tokens allowed in output before truncation (default: 2000, set to 0 for unlimited)",
+						"minimum": 0,
+						"default": 2000
+					}
+				},
+				"required": ["anchor"],
+				"additionalProperties": false
+			}),
+		}
+	}
+
+	/// Get the tool definition for find_similar
+	pub fn get_find_similar_tool_definition() -> McpTool {
+		McpTool {
+			name: "find_similar".to_string(),
+			description: "Find code similar to an existing block: duplicates and near-duplicates elsewhere in the repository, e.g. to spot copy-paste drift before fixing a bug in one copy. The given lines are read from disk and compared with every indexed code block; the blocks they belong to are left out. Output has the same format as semantic_search in code mode.".to_string(),
+			input_schema: json!({
+				"type": "object",
+				"properties": {
+					"file": {
+						"type": "string",
+						"description": "Path of the file relative to the project root, e.g. 'src/payments/charge.rs'"
+					},
+					"start_line": {
+						"type": "integer",
+						"description": "First line of the block, 1-indexed",
+						"minimum": 1
+					},
+					"end_line": {
+						"type": "integer",
+						"description": "Last line of the block, 1-indexed and inclusive",
+						"minimum": 1
+					},
+					"max_results": {
+						"type": "integer",
+						"description": "Maximum number of similar blocks to return",
+						"minimum": 1,
+						"maximum": 20,
+						"default": 5
+					},
+					"threshold": {
+						"type": "number",
+						"description": "Minimum similarity (0.0-1.0); raise it to only see near-copies (default: search.similarity_threshold)",
+						"minimum": 0.0,
+						"maximum": 1.0
+					},
+					"detail_level": {
+						"type": "string",
+						"description": "Detail level: 'signatures', 'partial' or 'full'",
+						"enum": ["signatures", "partial", "full"],
+						"default": "partial"
+					},
+					"max_tokens": {
+						"type": "integer",
+						"description": "Maximum tokens allowed in output before truncation (default: 2000, set to 0 for unlimited)",
+						"minimum": 0,
+						"default": 2000
+					}
+				},
+				"required": ["file", "start_line", "end_line"],
+				"additionalProperties": false
+			}),
+		}
+	}
+
+	/// Get the tool definition for read_range
+	pub fn get_read_range_tool_definition() -> McpTool {
+		McpTool {
+			name: "read_range".to_string(),
+			description: "Read the exact lines of a project file, e.g. the code around a semantic_search hit, with surrounding context lines. Paths are relative to the project root; files outside it, hidden files and files excluded by .gitignore or .noindex can't be read. Output starts with a 'path:start-end' header followed by 'N: line' lines; requested lines are marked with '>'.".to_string(),
+			input_schema: json!({
+				"type": "object",
+				"properties": {
+					"file": {
+						"type": "string",
+						"description": "Path of the file relative to the project root, e.g. 'src/payments/charge.rs'"
+					},
+					"start_line": {
+						"type": "integer",
+						"description": "First line to read, 1-indexed",
+						"minimum": 1
+					},
+					"end_line": {
+						"type": "integer",
+						"description": "Last line to read, 1-indexed and inclusive (default: start_line)",
+						"minimum": 1
+					},
+					"context_lines": {
+						"type": "integer",
+						"description": "Lines of context before and after the range (default: search.context_lines)",
+						"minimum": 0,
+						"maximum": 100
+					},
+					"max_tokens": {
+						"type": "integer",
+						"description": "Maximum tokens allowed in output before truncation (default: 2000, set to 0 for unlimited)",
+						"minimum": 0,
+						"default": 2000
+					}
+				},
+				"required": ["file", "start_line"],
+				"additionalProperties": false
+			}),
+		}
+	}
+
 	/// Execute the semantic_search tool
 	pub async fn execute_search(&self, arguments: &Value) -> Result<String, McpError> {
+		self.execute_search_streaming(arguments, None).await
+	}
+
+	/// Execute the semantic_search tool, sending the top hits of single-query
+	/// searches to `partial` before the complete result is returned
+	pub async fn execute_search_streaming(
+		&self,
+		arguments: &Value,
+		partial: Option<&PartialResults>,
+	) -> Result<String, McpError> {
 		// Parse queries - handle both string and array inputs
 		let queries: Vec<String> = match arguments.get("query") {
 			Some(Value::String(s)) => vec![s.clone()],
@@ -224,7 +392,7 @@ impl SemanticCodeProvider {
 			}
 		}
 
-		let mode = arguments
+		let mut mode = arguments
 			.get("mode")
 			.and_then(|v| v.as_str())
 			.unwrap_or("all");
@@ -272,11 +440,98 @@ impl SemanticCodeProvider {
 			));
 		}
 
+		// Parse symbol filter; symbols only exist on code blocks
+		let symbol_filter = match arguments.get("symbol") {
+			Some(symbol_value) => {
+				let symbol = symbol_value.as_str().ok_or_else(|| {
+					McpError::invalid_params(
+						"Invalid symbol parameter: must be a string",
+						"semantic_search",
+					)
+				})?;
+				let filter = SymbolFilter::parse(symbol)
+					.map_err(|e| McpError::invalid_params(e.to_string(), "semantic_search"))?;
+				match mode {
+					"docs" | "text" => {
+						return Err(McpError::invalid_params(
+							format!(
+								"Invalid mode '{}': 'symbol' only applies to code search",
+								mode
+							),
+							"semantic_search",
+						));
+					}
+					_ => mode = "code",
+				}
+				Some(filter)
+			}
+			None => None,
+		};
+
+		// Parse comment/string scope; spans only exist on code blocks
+		let content_filter = match arguments.get("in") {
+			Some(scope_value) => {
+				let scope = scope_value
+					.as_str()
+					.ok_or_else(|| {
+						McpError::invalid_params(
+							"Invalid in parameter: must be a string",
+							"semantic_search",
+						)
+					})
+					.and_then(|scope| {
+						ContentScope::parse(scope)
+							.map_err(|e| McpError::invalid_params(e.to_string(), "semantic_search"))
+					})?;
+				let filter = ContentFilter::new(scope, &queries)
+					.map_err(|e| McpError::invalid_params(e.to_string(), "semantic_search"))?;
+				match mode {
+					"docs" | "text" => {
+						return Err(McpError::invalid_params(
+							format!("Invalid mode '{}': 'in' only applies to code search", mode),
+							"semantic_search",
+						));
+					}
+					_ => mode = "code",
+				}
+				Some(filter)
+			}
+			None => None,
+		};
+
+		let path_filter = match arguments.get("paths") {
+			Some(paths_value) => {
+				let paths = paths_value
+					.as_array()
+					.and_then(|paths| {
+						paths
+							.iter()
+							.map(|path| path.as_str().map(str::to_string))
+							.collect::<Option<Vec<String>>>()
+					})
+					.ok_or_else(|| {
+						McpError::invalid_params(
+							"Invalid paths parameter: must be an array of strings",
+							"semantic_search",
+						)
+					})?;
+				Some(
+					PathFilter::parse(&paths)
+						.map_err(|e| McpError::invalid_params(e.to_string(), "semantic_search"))?,
+				)
+			}
+			None => None,
+		};
+
 		let similarity_threshold = arguments
 			.get("threshold")
 			.and_then(|v| v.as_f64())
 			.map(|v| v as f32)
-			.unwrap_or(self.config.search.similarity_threshold);
+			.unwrap_or(if symbol_filter.is_some() || content_filter.is_some() {
+				0.0
+			} else {
+				self.config.search.similarity_threshold
+			});
 
 		// Validate similarity threshold
 		if !(0.0..=1.0).contains(&similarity_threshold) {
@@ -302,7 +557,7 @@ impl SemanticCodeProvider {
 			use crate::indexer::languages;
 			if languages::get_language(language).is_none() {
 				return Err(McpError::invalid_params(
-					format!("Invalid language '{}': supported languages are rust, javascript, typescript, python, go, cpp, php, bash, ruby, json, svelte, css", language),
+					format!("Invalid language '{}': supported languages are rust, javascript, typescript, python, go, cpp, php, bash, ruby, json, svelte, css, hcl, sql, zig, proto, haskell, lua, dart", language),
 					"semantic_search"
 				));
 			}
@@ -312,6 +567,25 @@ impl SemanticCodeProvider {
 			None
 		};
 
+		let offset = match arguments.get("cursor") {
+			Some(cursor_value) => {
+				let cursor = cursor_value.as_str().ok_or_else(|| {
+					McpError::invalid_params(
+						"Invalid cursor parameter: must be a string",
+						"semantic_search",
+					)
+				})?;
+				decode_cursor(cursor, self.config.search.max_offset)
+					.map_err(|e| McpError::invalid_params(e.to_string(), "semantic_search"))?
+			}
+			None => 0,
+		};
+
+		let exclude_tests = arguments
+			.get("exclude_tests")
+			.and_then(|v| v.as_bool())
+			.unwrap_or(self.config.search.exclude_tests);
+
 		// Parse max_tokens parameter
 		let max_tokens = arguments
 			.get("max_tokens")
@@ -324,8 +598,13 @@ impl SemanticCodeProvider {
 			mode = %mode,
 			detail_level = %detail_level,
 			max_results = %max_results,
+			offset = %offset,
 			similarity_threshold = %similarity_threshold,
 			language_filter = ?language_filter,
+			symbol_filter = ?symbol_filter,
+			content_filter = ?content_filter,
+			path_filter = ?path_filter,
+			exclude_tests = %exclude_tests,
 			working_directory = %self.working_directory.display(),
 			"Executing semantic code search with {} queries",
 			queries.len()
@@ -362,20 +641,32 @@ impl SemanticCodeProvider {
 				mode,
 				detail_level,
 				max_results,
+				offset,
 				similarity_threshold,
 				language_filter.as_deref(),
+				symbol_filter.as_ref(),
+				content_filter.as_ref(),
+				path_filter.as_ref(),
+				exclude_tests,
 				&self.config,
+				partial,
 			)
 			.await
 		} else {
-			// Multi-query - use text function for token efficiency
+			// Multi-query - use text function for token efficiency. Rankings are
+			// merged across queries, so nothing is streamed ahead of the result.
 			search_codebase_with_details_multi_query_text(
 				&queries,
 				mode,
 				detail_level,
 				max_results,
+				offset,
 				similarity_threshold,
 				language_filter.as_deref(),
+				symbol_filter.as_ref(),
+				content_filter.as_ref(),
+				path_filter.as_ref(),
+				exclude_tests,
 				&self.config,
 			)
 			.await
@@ -391,9 +682,15 @@ impl SemanticCodeProvider {
 			);
 		}
 
-		// Apply token truncation if needed
+		// Apply token truncation if needed; the cursor goes last so it survives truncation
 		match results {
-			Ok(output) => Ok(truncate_output(&output, max_tokens)),
+			Ok(page) => {
+				let mut output = truncate_output(&page.output, max_tokens);
+				if let Some(next_offset) = page.next_offset {
+					output.push_str(&format!("\n\nnext_cursor: {}", encode_cursor(next_offset)));
+				}
+				Ok(output)
+			}
 			Err(e) => Err(McpError::internal_error(
 				format!("Search operation failed: {}", e),
 				"semantic_search",
@@ -544,9 +841,531 @@ impl SemanticCodeProvider {
 		};
 
 		// Return text format for token efficiency
-		let text_output = render_signatures_text(&signatures);
+		let text_output = render_signatures_text(&signatures, false);
 
 		// Apply token truncation if needed
 		Ok(truncate_output(&text_output, max_tokens))
 	}
+
+	/// Execute the find_similar tool
+	pub async fn execute_find_similar(&self, arguments: &Value) -> Result<String, McpError> {
+		let file = arguments
+			.get("file")
+			.and_then(|v| v.as_str())
+			.filter(|file| !file.trim().is_empty())
+			.ok_or_else(|| {
+				McpError::invalid_params(
+					"Missing required parameter 'file': must be a path relative to the project root",
+					"find_similar",
+				)
+			})?;
+		let line = |name: &str| {
+			arguments.get(name).and_then(|v| v.as_u64()).ok_or_else(|| {
+				McpError::invalid_params(
+					format!(
+						"Missing required parameter '{}': must be a 1-indexed line",
+						name
+					),
+					"find_similar",
+				)
+			})
+		};
+		let range = CodeRange::parse(&format!(
+			"{}:{}-{}",
+			file,
+			line("start_line")?,
+			line("end_line")?
+		))
+		.map_err(|e| McpError::invalid_params(e.to_string(), "find_similar"))?;
+
+		let max_results = arguments
+			.get("max_results")
+			.and_then(|v| v.as_u64())
+			.unwrap_or(5)
+			.clamp(1, 20) as usize;
+		let threshold = arguments
+			.get("threshold")
+			.and_then(|v| v.as_f64())
+			.map(|v| v as f32)
+			.unwrap_or(self.config.search.similarity_threshold);
+		if !(0.0..=1.0).contains(&threshold) {
+			return Err(McpError::invalid_params(
+				"Invalid threshold: must be between 0.0 and 1.0",
+				"find_similar",
+			));
+		}
+		let detail_level = match arguments.get("detail_level").and_then(|v| v.as_str()) {
+			None => "partial",
+			Some(level @ ("signatures" | "partial" | "full")) => level,
+			Some(level) => {
+				return Err(McpError::invalid_params(
+					format!(
+						"Invalid detail_level '{}': use 'signatures', 'partial' or 'full'",
+						level
+					),
+					"find_similar",
+				))
+			}
+		};
+		let max_tokens = arguments
+			.get("max_tokens")
+			.and_then(|v| v.as_u64())
+			.unwrap_or(2000) as usize;
+
+		debug!(
+			range = %range,
+			max_results = %max_results,
+			threshold = %threshold,
+			working_directory = %self.working_directory.display(),
+			"Finding similar code"
+		);
+
+		let original_dir = std::env::current_dir().map_err(|e| {
+			McpError::internal_error(
+				format!("Failed to get current directory: {}", e),
+				"find_similar",
+			)
+		})?;
+		std::env::set_current_dir(&self.working_directory).map_err(|e| {
+			McpError::internal_error(
+				format!(
+					"Failed to change to working directory '{}': {}",
+					self.working_directory.display(),
+					e
+				),
+				"find_similar",
+			)
+		})?;
+
+		let result = async {
+			let store = Store::new().await?;
+			find_similar(
+				&store,
+				&range,
+				&self.working_directory,
+				max_results,
+				threshold,
+				&self.config,
+			)
+			.await
+		}
+		.await;
+
+		if let Err(e) = std::env::set_current_dir(&original_dir) {
+			debug!(
+				error = %e,
+				original_dir = %original_dir.display(),
+				"Failed to restore original directory"
+			);
+		}
+
+		match result {
+			Ok(blocks) if blocks.is_empty() => Ok(format!(
+				"No code similar to {} found above similarity {}.",
+				range, threshold
+			)),
+			Ok(blocks) => Ok(truncate_output(
+				&format_code_search_results_as_text(&blocks, detail_level),
+				max_tokens,
+			)),
+			Err(e) => Err(McpError::internal_error(
+				format!("Similar code search failed: {}", e),
+				"find_similar",
+			)),
+		}
+	}
+
+	/// Execute the read_range tool
+	pub async fn execute_read_range(&self, arguments: &Value) -> Result<String, McpError> {
+		let file = arguments
+			.get("file")
+			.and_then(|v| v.as_str())
+			.filter(|file| !file.trim().is_empty())
+			.ok_or_else(|| {
+				McpError::invalid_params(
+					"Missing required parameter 'file': must be a path relative to the project root",
+					"read_range",
+				)
+			})?;
+		let start_line = arguments
+			.get("start_line")
+			.and_then(|v| v.as_u64())
+			.filter(|line| *line >= 1)
+			.ok_or_else(|| {
+				McpError::invalid_params(
+					"Missing required parameter 'start_line': must be a 1-indexed line",
+					"read_range",
+				)
+			})? as usize;
+		let end_line = arguments
+			.get("end_line")
+			.and_then(|v| v.as_u64())
+			.map_or(start_line, |line| line as usize);
+		if end_line < start_line {
+			return Err(McpError::invalid_params(
+				format!(
+					"Invalid range: end_line {} is before start_line {}",
+					end_line, start_line
+				),
+				"read_range",
+			));
+		}
+		let context_lines = arguments
+			.get("context_lines")
+			.and_then(|v| v.as_u64())
+			.map_or(self.config.search.context_lines, |lines| lines as usize)
+			.min(MAX_READ_CONTEXT_LINES);
+		let max_tokens = arguments
+			.get("max_tokens")
+			.and_then(|v| v.as_u64())
+			.unwrap_or(2000) as usize;
+
+		debug!(
+			file = %file,
+			start_line = %start_line,
+			end_line = %end_line,
+			context_lines = %context_lines,
+			working_directory = %self.working_directory.display(),
+			"Reading file range"
+		);
+
+		let (path, relative) = self
+			.resolve_project_file(file)
+			.map_err(|e| McpError::invalid_params(e.to_string(), "read_range"))?;
+		let contents = std::fs::read_to_string(&path).map_err(|e| {
+			McpError::internal_error(
+				format!("Failed to read '{}': {}", relative, e),
+				"read_range",
+			)
+		})?;
+		// Redact credentials the same way indexing does, so they never reach the agent
+		let contents = if self.config.index.skip_secrets {
+			match crate::indexer::secret_scanner::scan(&contents) {
+				Some(scan) => scan.redacted,
+				None => contents,
+			}
+		} else {
+			contents
+		};
+
+		let output = render_line_range(&relative, &contents, start_line, end_line, context_lines)
+			.map_err(|e| McpError::invalid_params(e.to_string(), "read_range"))?;
+		Ok(truncate_output(&output, max_tokens))
+	}
+
+	/// Canonical path of a project file and its path relative to the root,
+	/// refusing files indexing would never read
+	fn resolve_project_file(&self, file: &str) -> Result<(std::path::PathBuf, String)> {
+		let root = self.working_directory.canonicalize()?;
+		let path = root
+			.join(file)
+			.canonicalize()
+			.map_err(|_| anyhow::anyhow!("File '{}' not found in the project", file))?;
+		// Canonical paths also catch `..` and symlinks leading out of the project
+		let relative = path
+			.strip_prefix(&root)
+			.map_err(|_| anyhow::anyhow!("'{}' is outside the project root", file))?;
+		if !path.is_file() {
+			return Err(anyhow::anyhow!("'{}' is not a file", file));
+		}
+		if relative
+			.components()
+			.any(|component| component.as_os_str().to_string_lossy().starts_with('.'))
+		{
+			return Err(anyhow::anyhow!("'{}' is a hidden file", file));
+		}
+		let matcher = NoindexWalker::create_matcher(&root, true)?;
+		if matcher
+			.matched_path_or_any_parents(&path, false)
+			.is_ignore()
+		{
+			return Err(anyhow::anyhow!(
+				"'{}' is excluded by .gitignore or .noindex",
+				file
+			));
+		}
+		let relative = PathUtils::to_relative_string(&path, &root);
+		Ok((path, relative))
+	}
+
+	/// Execute the resolve_anchor tool
+	pub async fn execute_resolve_anchor(&self, arguments: &Value) -> Result<String, McpError> {
+		let anchor = arguments
+			.get("anchor")
+			.and_then(|v| v.as_str())
+			.ok_or_else(|| {
+				McpError::invalid_params(
+					"Missing required parameter 'anchor': must be an anchor from a search or graph result",
+					"resolve_anchor",
+				)
+			})?;
+		let anchor = Anchor::parse(anchor)
+			.map_err(|e| McpError::invalid_params(e.to_string(), "resolve_anchor"))?;
+
+		let max_tokens = arguments
+			.get("max_tokens")
+			.and_then(|v| v.as_u64())
+			.unwrap_or(2000) as usize;
+
+		debug!(
+			anchor = %anchor,
+			working_directory = %self.working_directory.display(),
+			"Resolving anchor"
+		);
+
+		let original_dir = std::env::current_dir().map_err(|e| {
+			McpError::internal_error(
+				format!("Failed to get current directory: {}", e),
+				"resolve_anchor",
+			)
+		})?;
+		std::env::set_current_dir(&self.working_directory).map_err(|e| {
+			McpError::internal_error(
+				format!(
+					"Failed to change to working directory '{}': {}",
+					self.working_directory.display(),
+					e
+				),
+				"resolve_anchor",
+			)
+		})?;
+
+		let result = self.resolve_anchor(&anchor).await;
+
+		if let Err(e) = std::env::set_current_dir(&original_dir) {
+			debug!(
+				error = %e,
+				original_dir = %original_dir.display(),
+				"Failed to restore original directory"
+			);
+		}
+
+		match result {
+			Ok(output) => Ok(truncate_output(&output, max_tokens)),
+			Err(e) => Err(McpError::internal_error(
+				format!("Anchor resolution failed: {}", e),
+				"resolve_anchor",
+			)),
+		}
+	}
+
+	async fn resolve_anchor(&self, anchor: &Anchor) -> Result<String> {
+		// Graph node anchors carry no key and cite the node's file summary
+		if anchor.key().is_none() {
+			return self.resolve_node_anchor(anchor).await;
+		}
+
+		let store = Store::new().await?;
+		let mut resolved = store.resolve_anchor(anchor).await?;
+		if resolved.is_none() {
+			// The cited file may have been renamed since the anchor was issued
+			let aliases = PathAliases::load(&self.working_directory);
+			if let Some(current_path) = aliases.renamed(anchor.path()) {
+				resolved = store
+					.resolve_anchor(&anchor.with_path(current_path))
+					.await?;
+			}
+		}
+		Ok(match resolved {
+			Some(resolved) => render_resolved_anchor(anchor, &resolved),
+			None => format!(
+				"Anchor {} no longer resolves: no block indexed for {} matches it. Search again for a fresh anchor.\n",
+				anchor,
+				anchor.path()
+			),
+		})
+	}
+
+	async fn resolve_node_anchor(&self, anchor: &Anchor) -> Result<String> {
+		if !self.config.graphrag.enabled {
+			return Err(anyhow::anyhow!(
+				"Anchor {} cites a GraphRAG node, but GraphRAG is not enabled",
+				anchor
+			));
+		}
+
+		let graph = crate::indexer::GraphBuilder::new_with_quiet(self.config.clone(), true)
+			.await?
+			.get_graph()
+			.await?;
+		let aliases = PathAliases::load(&self.working_directory);
+		let node = match graph
+			.nodes
+			.get(anchor.path())
+			.or_else(|| graph.nodes.get(aliases.renamed(anchor.path())?))
+		{
+			Some(node) => node,
+			None => {
+				return Ok(format!(
+					"Anchor {} no longer resolves: the graph has no node for {}. Search again for a fresh anchor.\n",
+					anchor,
+					anchor.path()
+				))
+			}
+		};
+
+		let current = Anchor::for_node(&node.path, &node.hash);
+		let mut output = format!("Anchor: {}\n", current);
+		if !anchor.matches_hash(&node.hash) {
+			output.push_str(&format!("Status: changed since {}\n", anchor));
+		}
+		output.push_str(&format!(
+			"Node: {} ({})\nDescription: {}\nSymbols: {}\n",
+			node.name,
+			node.kind,
+			node.description,
+			node.symbols.join(", ")
+		));
+		Ok(output)
+	}
+}
+
+/// Render lines `start_line..=end_line` (1-indexed) with `context_lines`
+/// around them, marking the requested lines
+fn render_line_range(
+	path: &str,
+	contents: &str,
+	start_line: usize,
+	end_line: usize,
+	context_lines: usize,
+) -> Result<String> {
+	let lines: Vec<&str> = contents.lines().collect();
+	if start_line > lines.len() {
+		return Err(anyhow::anyhow!(
+			"Line {} is past the end of {} ({} lines)",
+			start_line,
+			path,
+			lines.len()
+		));
+	}
+	let first = start_line.saturating_sub(context_lines).max(1);
+	let last = (end_line + context_lines).min(lines.len());
+
+	let mut output = format!("{}:{}-{}\n", path, first, last);
+	for (number, line) in (first..=last).zip(&lines[first - 1..last]) {
+		let marker = if (start_line..=end_line).contains(&number) {
+			'>'
+		} else {
+			' '
+		};
+		output.push_str(&format!("{}{}: {}\n", marker, number, line));
+	}
+	Ok(output)
+}
+
+/// Render a resolved block with 1-indexed line numbers, noting when it moved
+fn render_resolved_anchor(requested: &Anchor, resolved: &ResolvedAnchor) -> String {
+	let (start_line, end_line) = resolved.block.lines();
+	let current = resolved.block.anchor();
+	let mut output = format!("Anchor: {}\n", current);
+	if current.path() != requested.path() {
+		output.push_str(&format!("Status: file renamed from {}\n", requested.path()));
+	}
+	if !resolved.exact {
+		output.push_str(&format!(
+			"Status: content changed since {}; resolved by {}\n",
+			requested,
+			match requested.key() {
+				Some(AnchorKey::Line(_)) => "line",
+				_ => "symbol",
+			}
+		));
+	}
+	output.push_str(&format!(
+		"{}:{}-{}\n",
+		current.path(),
+		start_line + 1,
+		end_line + 1
+	));
+	for (i, line) in resolved.block.content().lines().enumerate() {
+		output.push_str(&format!("{}: {}\n", start_line + 1 + i, line));
+	}
+	output
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Project with a source file, a hidden file and an excluded file, next to
+	/// a file outside of it
+	fn project(name: &str) -> (std::path::PathBuf, SemanticCodeProvider) {
+		let base = std::env::temp_dir().join(format!(
+			"octocode-read-range-{}-{}",
+			name,
+			std::process::id()
+		));
+		let _ = std::fs::remove_dir_all(&base);
+		let root = base.join("project");
+		std::fs::create_dir_all(root.join("src")).unwrap();
+		std::fs::create_dir_all(root.join(".secrets")).unwrap();
+		std::fs::create_dir_all(root.join("generated")).unwrap();
+		let source: String = (1..=10).map(|i| format!("line {}\n", i)).collect();
+		std::fs::write(root.join("src/lib.rs"), source).unwrap();
+		std::fs::write(root.join(".secrets/key.txt"), "hidden\n").unwrap();
+		std::fs::write(root.join("generated/out.rs"), "generated\n").unwrap();
+		std::fs::write(root.join(".noindex"), "generated/\n").unwrap();
+		std::fs::write(base.join("outside.txt"), "outside\n").unwrap();
+
+		let config = Config::load_from_template().unwrap();
+		(base, SemanticCodeProvider::new(config, root))
+	}
+
+	#[test]
+	fn test_resolve_project_file() {
+		let (base, provider) = project("resolve");
+
+		let (path, relative) = provider.resolve_project_file("src/lib.rs").unwrap();
+		assert_eq!(relative, "src/lib.rs");
+		assert!(path.is_absolute() && path.ends_with("src/lib.rs"));
+		// Paths are normalised before they are checked
+		assert_eq!(
+			provider
+				.resolve_project_file("src/../src/lib.rs")
+				.unwrap()
+				.1,
+			"src/lib.rs"
+		);
+
+		let error = |file: &str| provider.resolve_project_file(file).unwrap_err().to_string();
+		assert!(error("../outside.txt").contains("outside the project root"));
+		let absolute = base.join("outside.txt");
+		assert!(error(absolute.to_str().unwrap()).contains("outside the project root"));
+		assert!(error("src/missing.rs").contains("not found"));
+		assert!(error("src").contains("not a file"));
+		assert!(error(".secrets/key.txt").contains("hidden file"));
+		assert!(error("generated/out.rs").contains("excluded"));
+
+		let _ = std::fs::remove_dir_all(base);
+	}
+
+	#[tokio::test]
+	async fn test_read_range() {
+		let (base, provider) = project("read");
+
+		let output = provider
+			.execute_read_range(&serde_json::json!({
+				"file": "src/lib.rs",
+				"start_line": 4,
+				"end_line": 5,
+				"context_lines": 1
+			}))
+			.await
+			.unwrap();
+		assert_eq!(
+			output,
+			"src/lib.rs:3-6\n 3: line 3\n>4: line 4\n>5: line 5\n 6: line 6\n"
+		);
+
+		let escaping = provider
+			.execute_read_range(&serde_json::json!({"file": "../outside.txt", "start_line": 1}))
+			.await;
+		assert!(escaping.is_err());
+		let past_end = provider
+			.execute_read_range(&serde_json::json!({"file": "src/lib.rs", "start_line": 11}))
+			.await;
+		assert!(past_end.is_err());
+
+		let _ = std::fs::remove_dir_all(base);
+	}
 }
@@ -14,11 +14,12 @@
 
 use anyhow::Result;
 use serde_json::json;
+use std::collections::HashSet;
 use std::panic;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
-use tokio::net::{TcpListener, TcpStream};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
 use tokio::sync::mpsc;
 use tokio::sync::Mutex;
 use tokio::time::{sleep, Duration, Instant};
@@ -26,13 +27,24 @@ use tracing::{debug, info, trace, warn};
 
 use crate::config::Config;
 use crate::indexer;
+use crate::mcp::auth::Authenticator;
+use crate::mcp::control::{self, McpActivity};
 use crate::mcp::graphrag::GraphRagProvider;
+use crate::mcp::http;
 use crate::mcp::logging::{
-	init_mcp_logging, log_critical_anyhow_error, log_critical_error, log_indexing_operation,
-	log_mcp_request, log_mcp_response, log_watcher_event,
+	init_mcp_logging, log_auth_rejection, log_critical_anyhow_error, log_critical_error,
+	log_indexing_operation, log_mcp_request, log_mcp_response, log_watcher_event,
 };
 use crate::mcp::memory::MemoryProvider;
+use crate::mcp::plugins::PluginToolProvider;
+use crate::mcp::roots;
+use crate::mcp::sampling::{self, SharedWriter};
 use crate::mcp::semantic_code::SemanticCodeProvider;
+use crate::mcp::session_cache::{CachedCall, SessionCache};
+use crate::mcp::tls::{self, HttpStream};
+use crate::mcp::tool_overrides::{
+	apply_tool_overrides, enforce_token_budget, resolve_tool_name, StreamBudget,
+};
 use crate::mcp::types::{parse_mcp_error, JsonRpcError, JsonRpcRequest, JsonRpcResponse, McpError};
 use crate::state;
 use crate::store::Store;
@@ -63,6 +75,7 @@ pub struct McpServer {
 	graphrag: Option<GraphRagProvider>,
 	memory: Option<MemoryProvider>,
 	lsp: Option<Arc<Mutex<crate::mcp::lsp::LspProvider>>>,
+	plugins: PluginToolProvider,
 	debug: bool,
 	working_directory: std::path::PathBuf,
 	no_git: bool,
@@ -72,6 +85,56 @@ pub struct McpServer {
 	store: Store,
 	config: Config,
 	index_rx: Option<mpsc::Receiver<()>>,
+	activity: McpActivity,
+	control_handle: Option<tokio::task::JoinHandle<()>>,
+	read_only: bool,
+	lsp_command: Option<String>,
+	client_roots_supported: bool,
+	session_cache: SessionCache,
+}
+
+/// Session key used for the single stdin/stdout client
+const STDIO_SESSION: &str = "stdio";
+
+/// Tools that write to the store and are hidden in read-only mode
+const MUTATING_TOOLS: &[&str] = &["memorize", "forget"];
+
+fn is_mutating_tool(name: &str) -> bool {
+	MUTATING_TOOLS.contains(&name)
+}
+
+/// Successful tool result carrying a single text block
+fn tool_text_response(request: &JsonRpcRequest, content: String) -> JsonRpcResponse {
+	JsonRpcResponse {
+		jsonrpc: "2.0".to_string(),
+		id: request.id.clone(),
+		result: Some(json!({
+			"content": [{
+				"type": "text",
+				"text": content
+			}]
+		})),
+		error: None,
+	}
+}
+
+/// Error returned when a client calls a mutating tool on a read-only server
+fn read_only_error(request: &JsonRpcRequest, tool_name: &str) -> JsonRpcResponse {
+	JsonRpcResponse {
+		jsonrpc: "2.0".to_string(),
+		id: request.id.clone(),
+		result: None,
+		error: Some(
+			McpError::method_not_found(
+				format!(
+					"Tool '{}' is not available: the server is running in read-only mode",
+					tool_name
+				),
+				tool_name,
+			)
+			.into_jsonrpc(),
+		),
+	}
 }
 
 impl McpServer {
@@ -81,6 +144,7 @@ impl McpServer {
 		working_directory: std::path::PathBuf,
 		no_git: bool,
 		lsp_command: Option<String>,
+		read_only: bool,
 	) -> Result<Self> {
 		// Change to the working directory at server startup
 		std::env::set_current_dir(&working_directory).map_err(|e| {
@@ -100,50 +164,69 @@ impl McpServer {
 
 		let semantic_code = SemanticCodeProvider::new(config.clone(), working_directory.clone());
 		let graphrag = GraphRagProvider::new(config.clone(), working_directory.clone());
-		let memory = MemoryProvider::new(&config, working_directory.clone()).await;
+		let memory = MemoryProvider::new(&config, working_directory.clone(), read_only).await;
+		let plugins = PluginToolProvider::new(&config, read_only).await;
 
 		// Initialize LSP provider if command is provided (lazy initialization)
-		let lsp = if let Some(command) = lsp_command {
-			tracing::info!(
-				"LSP provider will be initialized lazily with command: {}",
-				command
-			);
-			let provider = Arc::new(Mutex::new(crate::mcp::lsp::LspProvider::new(
-				working_directory.clone(),
-				command,
-			)));
-
-			// Start LSP initialization in background (non-blocking)
-			let provider_clone = provider.clone();
-			tokio::spawn(async move {
-				let mut provider_guard = provider_clone.lock().await;
-				if let Err(e) = provider_guard.start_initialization().await {
-					tracing::warn!("LSP initialization failed: {}", e);
-				}
-			});
+		let lsp = lsp_command
+			.clone()
+			.map(|command| Self::spawn_lsp(working_directory.clone(), command));
 
-			Some(provider)
-		} else {
-			None
-		};
+		let indexing_in_progress = Arc::new(AtomicBool::new(false));
+		let activity = McpActivity::new(&working_directory, indexing_in_progress.clone());
+
+		let session_cache = SessionCache::new(config.mcp.session_cache_size);
 
 		Ok(Self {
 			semantic_code,
 			graphrag,
 			memory,
 			lsp,
+			plugins,
 			debug,
 			working_directory,
 			no_git,
 			watcher_handle: None,
 			index_handle: None,
-			indexing_in_progress: Arc::new(AtomicBool::new(false)),
+			indexing_in_progress,
 			store,
 			config,
 			index_rx: None,
+			activity,
+			control_handle: None,
+			read_only,
+			lsp_command,
+			client_roots_supported: false,
+			session_cache,
 		})
 	}
 
+	/// Create an LSP provider and start its initialization in the background
+	fn spawn_lsp(
+		working_directory: std::path::PathBuf,
+		command: String,
+	) -> Arc<Mutex<crate::mcp::lsp::LspProvider>> {
+		tracing::info!(
+			"LSP provider will be initialized lazily with command: {}",
+			command
+		);
+		let provider = Arc::new(Mutex::new(crate::mcp::lsp::LspProvider::new(
+			working_directory,
+			command,
+		)));
+
+		// Start LSP initialization in background (non-blocking)
+		let provider_clone = provider.clone();
+		tokio::spawn(async move {
+			let mut provider_guard = provider_clone.lock().await;
+			if let Err(e) = provider_guard.start_initialization().await {
+				tracing::warn!("LSP initialization failed: {}", e);
+			}
+		});
+
+		provider
+	}
+
 	pub async fn run(&mut self) -> Result<()> {
 		// Set up panic handler to prevent server crashes from tool execution
 		let original_hook = panic::take_hook();
@@ -156,6 +239,9 @@ impl McpServer {
 		// Start the file watcher as a completely independent background task
 		self.start_watcher().await?;
 
+		// Expose live status to `octocode mcp status`
+		self.start_control().await;
+
 		// Log server startup details using structured logging (no console output for MCP protocol compliance)
 		info!(
 			debug_mode = self.debug,
@@ -171,122 +257,101 @@ impl McpServer {
 		let mut index_rx = self.index_rx.take().unwrap();
 
 		// Handle MCP protocol communication (stdin/stdout) with error resilience
-		// This runs independently of file watching and indexing
-		let stdin = tokio::io::stdin();
-		let stdout = tokio::io::stdout();
-		let mut reader = BufReader::new(stdin);
-		let mut writer = stdout;
+		// This runs independently of file watching and indexing.
+		// Stdin is read on its own task so that client responses to server-initiated
+		// requests (sampling) are delivered even while a tool call or reindex is running.
+		let writer: SharedWriter = Arc::new(Mutex::new(tokio::io::stdout()));
+		sampling::install(writer.clone());
+
+		let (line_tx, mut line_rx) = mpsc::channel::<String>(MCP_MAX_PENDING_EVENTS);
+		let reader_handle = tokio::spawn(read_stdin_lines(line_tx));
 
-		let mut line = String::with_capacity(1024); // Pre-allocate reasonable buffer
 		let mut consecutive_errors = 0u32;
 		const MAX_CONSECUTIVE_ERRORS: u32 = 10;
 
 		loop {
-			line.clear();
-
 			tokio::select! {
-				// Handle MCP protocol messages from stdin with timeout and error recovery
-				result = tokio::time::timeout(
-					Duration::from_millis(MCP_IO_TIMEOUT_MS),
-					reader.read_line(&mut line)
-				) => {
-					match result {
-						Ok(Ok(0)) => {
-							// EOF reached - normal shutdown
-							debug!("MCP Server: EOF received, shutting down gracefully");
-							break;
+				// Handle MCP protocol messages forwarded by the stdin reader
+				maybe_line = line_rx.recv() => {
+					let Some(line) = maybe_line else {
+						// Reader finished - EOF or unrecoverable stdin error
+						debug!("MCP Server: stdin closed, shutting down gracefully");
+						break;
+					};
+					let bytes_read = line.len();
+
+					// Check for oversized requests to prevent memory exhaustion
+					if bytes_read > MCP_MAX_REQUEST_SIZE {
+						log_critical_anyhow_error(
+							"Request size limit exceeded",
+							&anyhow::anyhow!("Request size {} exceeds limit {}", bytes_read, MCP_MAX_REQUEST_SIZE)
+						);
+
+						// Send error response for oversized request
+						if let Err(e) = self.send_error_response(
+							&writer,
+							None,
+							-32700,
+							"Request too large",
+							Some(json!({"max_size": MCP_MAX_REQUEST_SIZE}))
+						).await {
+							log_critical_anyhow_error("Failed to send error response", &e);
 						}
-						Ok(Ok(bytes_read)) => {
-							// Check for oversized requests to prevent memory exhaustion
-							if bytes_read > MCP_MAX_REQUEST_SIZE {
-								log_critical_anyhow_error(
-									"Request size limit exceeded",
-									&anyhow::anyhow!("Request size {} exceeds limit {}", bytes_read, MCP_MAX_REQUEST_SIZE)
-								);
+						continue;
+					}
 
-								// Send error response for oversized request
-								if let Err(e) = self.send_error_response(
-									&mut writer,
-									None,
-									-32700,
-									"Request too large",
-									Some(json!({"max_size": MCP_MAX_REQUEST_SIZE}))
-								).await {
-									log_critical_anyhow_error("Failed to send error response", &e);
-								}
-								continue;
-							}
+					// Process the request with panic recovery
+					let handled = self.handle_request_safe(&line).await;
 
-							// Process the request with panic recovery
-							match self.handle_request_safe(&line).await {
-								Ok(Some(response)) => {
-									// Send response with error handling
-									if let Err(e) = self.send_response(&mut writer, &response).await {
-										log_critical_anyhow_error("Failed to send response", &e);
-										consecutive_errors += 1;
-										if consecutive_errors >= MAX_CONSECUTIVE_ERRORS {
-											log_critical_anyhow_error(
-												"Too many consecutive errors",
-												&anyhow::anyhow!("Shutting down after {} consecutive errors", consecutive_errors)
-											);
-											break;
-										}
-									} else {
-										consecutive_errors = 0; // Reset on successful send
-									}
-								}
-								Ok(None) => {
-									// No response needed (e.g., empty request)
-									consecutive_errors = 0;
-								}
-								Err(e) => {
-									log_critical_anyhow_error("Request handling failed", &e);
-									consecutive_errors += 1;
-
-									// Try to send error response
-									if let Err(send_err) = self.send_error_response(
-										&mut writer,
-										None,
-										-32603,
-										"Internal server error",
-										Some(json!({"error": e.to_string()}))
-									).await {
-										log_critical_anyhow_error("Failed to send error response", &send_err);
-									}
+					// A workspace root change restarts the watcher with a new index channel
+					if let Some(rx) = self.index_rx.take() {
+						index_rx = rx;
+					}
 
-									if consecutive_errors >= MAX_CONSECUTIVE_ERRORS {
-										log_critical_anyhow_error(
-											"Too many consecutive errors",
-											&anyhow::anyhow!("Shutting down after {} consecutive errors", consecutive_errors)
-										);
-										break;
-									}
-								}
-							}
-						}
-						Ok(Err(e)) => {
-							// I/O error reading from stdin
-							if self.is_broken_pipe_error(&e) {
-								debug!("MCP Server: Broken pipe detected, shutting down gracefully");
-								break;
-							} else {
-								log_critical_error("Error reading from stdin", &e);
+					match handled {
+						Ok(Some(response)) => {
+							// Send response with error handling
+							if let Err(e) = self.send_response(&writer, &response).await {
+								log_critical_anyhow_error("Failed to send response", &e);
 								consecutive_errors += 1;
 								if consecutive_errors >= MAX_CONSECUTIVE_ERRORS {
+									log_critical_anyhow_error(
+										"Too many consecutive errors",
+										&anyhow::anyhow!("Shutting down after {} consecutive errors", consecutive_errors)
+									);
 									break;
 								}
-								// Brief delay before retrying
-								tokio::time::sleep(Duration::from_millis(100)).await;
+							} else {
+								consecutive_errors = 0; // Reset on successful send
+							}
+						}
+						Ok(None) => {
+							// No response needed (e.g., empty request or notification)
+							consecutive_errors = 0;
+						}
+						Err(e) => {
+							log_critical_anyhow_error("Request handling failed", &e);
+							consecutive_errors += 1;
+
+							// Try to send error response
+							if let Err(send_err) = self.send_error_response(
+								&writer,
+								None,
+								-32603,
+								"Internal server error",
+								Some(json!({"error": e.to_string()}))
+							).await {
+								log_critical_anyhow_error("Failed to send error response", &send_err);
+							}
+
+							if consecutive_errors >= MAX_CONSECUTIVE_ERRORS {
+								log_critical_anyhow_error(
+									"Too many consecutive errors",
+									&anyhow::anyhow!("Shutting down after {} consecutive errors", consecutive_errors)
+								);
+								break;
 							}
 						}
-				Err(_) => {
-					// Timeout on stdin read - this is normal when no client requests are pending
-					// MCP servers should wait indefinitely for client requests, not terminate on timeouts
-					trace!("MCP Server: Timeout reading from stdin (normal - waiting for client requests)");
-					// Do NOT increment consecutive_errors for timeouts - this is expected behavior
-					// Reset consecutive_errors since timeout is not an actual error
-					consecutive_errors = 0;
-				}
 					}
 				}
 
@@ -298,10 +363,15 @@ impl McpServer {
 					sleep(Duration::from_millis(DEFAULT_ADDITIONAL_DELAY_MS)).await;
 
 					// Perform direct indexing with timeout protection
+					let index_start = Instant::now();
 					let indexing_result = tokio::time::timeout(
 						Duration::from_millis(MCP_INDEX_TIMEOUT_MS),
 						perform_indexing(&self.store, &self.config, &self.working_directory, self.no_git)
 					).await;
+					self.activity.record_index(
+						matches!(indexing_result, Ok(Ok(()))),
+						index_start.elapsed().as_millis() as u64,
+					);
 
 					match indexing_result {
 						Ok(Ok(())) => {
@@ -339,6 +409,11 @@ impl McpServer {
 		if let Some(handle) = self.index_handle.take() {
 			handle.abort();
 		}
+		reader_handle.abort();
+		sampling::set_client_supported(false);
+
+		self.activity.close_session(STDIO_SESSION);
+		self.stop_control();
 
 		debug!("MCP Server stopped");
 
@@ -358,6 +433,9 @@ impl McpServer {
 		// Start the file watcher as a completely independent background task
 		self.start_watcher().await?;
 
+		// Expose live status to `octocode mcp status`
+		self.start_control().await;
+
 		// Parse bind address
 		let addr = bind_addr
 			.parse::<std::net::SocketAddr>()
@@ -378,20 +456,41 @@ impl McpServer {
 		// Get the index receiver for handling indexing requests
 		let mut index_rx = self.index_rx.take().unwrap();
 
+		// Fail before binding if a configured token can't be resolved
+		let auth = Arc::new(Authenticator::from_config(&self.config.mcp.auth)?);
+		info!(
+			auth = auth.is_enabled(),
+			"MCP HTTP bearer-token authentication"
+		);
+
 		// Create shared server state for HTTP handlers
-		let server_state = Arc::new(Mutex::new(HttpServerState {
+		let server_state = Arc::new(HttpServerState {
 			semantic_code: self.semantic_code.clone(),
 			graphrag: self.graphrag.clone(),
 			memory: self.memory.clone(),
 			lsp: self.lsp.clone(),
-		}));
+			plugins: self.plugins.clone(),
+			activity: self.activity.clone(),
+			mcp_config: self.config.mcp.clone(),
+			auth,
+			read_only: self.read_only,
+			sessions: Mutex::new(HashSet::new()),
+			session_cache: self.session_cache.clone(),
+			request_limit: request_limit(self.config.mcp.max_concurrent_requests),
+		});
+
+		// Fail before binding if the TLS material is unusable
+		let tls_acceptor = tls::build_acceptor(&self.config.mcp.tls)?;
 
 		// Start HTTP server
 		let listener = TcpListener::bind(&addr)
 			.await
 			.map_err(|e| anyhow::anyhow!("Failed to bind to {}: {}", addr, e))?;
 
-		info!("MCP HTTP server listening on {}", addr);
+		info!(
+			tls = tls_acceptor.is_some(),
+			"MCP HTTP server listening on {}", addr
+		);
 
 		// Clone state for the server task
 		let state_for_server = server_state.clone();
@@ -400,8 +499,13 @@ impl McpServer {
 				match listener.accept().await {
 					Ok((stream, addr)) => {
 						let state = state_for_server.clone();
+						let tls_acceptor = tls_acceptor.clone();
 						tokio::spawn(async move {
-							if let Err(e) = handle_http_connection(stream, state).await {
+							let result = match tls::accept(tls_acceptor.as_ref(), stream).await {
+								Ok(stream) => handle_http_connection(stream, addr, state).await,
+								Err(e) => Err(e),
+							};
+							if let Err(e) = result {
 								debug!("HTTP connection error from {}: {}", addr, e);
 							}
 						});
@@ -427,10 +531,15 @@ impl McpServer {
 					sleep(Duration::from_millis(DEFAULT_ADDITIONAL_DELAY_MS)).await;
 
 					// Perform direct indexing with timeout protection
+					let index_start = Instant::now();
 					let indexing_result = tokio::time::timeout(
 						Duration::from_millis(MCP_INDEX_TIMEOUT_MS),
 						perform_indexing(&self.store, &self.config, &self.working_directory, self.no_git)
 					).await;
+					self.activity.record_index(
+						matches!(indexing_result, Ok(Ok(()))),
+						index_start.elapsed().as_millis() as u64,
+					);
 
 					match indexing_result {
 						Ok(Ok(())) => {
@@ -475,12 +584,89 @@ impl McpServer {
 			handle.abort();
 		}
 		server_handle.abort();
+		self.stop_control();
 
 		debug!("MCP HTTP Server stopped");
 
 		Ok(())
 	}
 
+	/// Ask the client for its workspace roots and follow them if the workspace moved
+	async fn sync_workspace_root(&mut self) {
+		let roots = match roots::request_roots().await {
+			Ok(roots) => roots,
+			Err(e) => {
+				warn!("Failed to fetch workspace roots from client: {}", e);
+				return;
+			}
+		};
+
+		let Some(root) = roots::select_root(&roots, &self.working_directory) else {
+			debug!("Client workspace roots include the current directory");
+			return;
+		};
+
+		if let Err(e) = self.retarget(root).await {
+			log_critical_anyhow_error("Failed to switch to client workspace root", &e);
+		}
+	}
+
+	/// Rebind the store, providers, watcher and control socket to another project
+	async fn retarget(&mut self, working_directory: std::path::PathBuf) -> Result<()> {
+		info!(
+			from = %self.working_directory.display(),
+			to = %working_directory.display(),
+			"Switching MCP server to client workspace root"
+		);
+
+		// Open the new project first so a failure leaves the current one intact
+		let (config, store) = open_project(&working_directory).await?;
+
+		// Stop everything bound to the old project
+		if let Some(handle) = self.watcher_handle.take() {
+			handle.abort();
+		}
+		if let Some(handle) = self.index_handle.take() {
+			handle.abort();
+		}
+		self.stop_control();
+		self.indexing_in_progress.store(false, Ordering::SeqCst);
+
+		self.store = store;
+		self.semantic_code = SemanticCodeProvider::new(config.clone(), working_directory.clone());
+		self.graphrag = GraphRagProvider::new(config.clone(), working_directory.clone());
+		self.memory = MemoryProvider::new(&config, working_directory.clone(), self.read_only).await;
+		self.plugins = PluginToolProvider::new(&config, self.read_only).await;
+		self.lsp = self
+			.lsp_command
+			.clone()
+			.map(|command| Self::spawn_lsp(working_directory.clone(), command));
+		self.activity.set_working_directory(&working_directory);
+		self.session_cache = SessionCache::new(config.mcp.session_cache_size);
+		self.config = config;
+		self.working_directory = working_directory;
+
+		self.start_watcher().await?;
+		self.start_control().await;
+
+		Ok(())
+	}
+
+	/// Start the local control socket; failures are logged but never fatal
+	async fn start_control(&mut self) {
+		match control::start_control_server(self.activity.clone(), &self.working_directory).await {
+			Ok(handle) => self.control_handle = Some(handle),
+			Err(e) => warn!("Failed to start MCP control socket: {}", e),
+		}
+	}
+
+	fn stop_control(&mut self) {
+		if let Some(handle) = self.control_handle.take() {
+			handle.abort();
+			control::remove_control_file(&self.working_directory);
+		}
+	}
+
 	async fn start_watcher(&mut self) -> Result<()> {
 		let (file_tx, file_rx) = mpsc::channel(MCP_MAX_PENDING_EVENTS);
 		let (index_tx, index_rx) = mpsc::channel(10);
@@ -597,6 +783,7 @@ impl McpServer {
 			Ok(req) => {
 				// Log the request with proper method and ID
 				log_mcp_request(&req.method, req.params.as_ref(), req.id.as_ref());
+				self.activity.touch_session(STDIO_SESSION, "stdio");
 				req
 			}
 			Err(e) => {
@@ -614,11 +801,33 @@ impl McpServer {
 			}
 		};
 
+		// Workspace root notifications: follow the client's workspace, nothing to answer
+		if matches!(
+			request.method.as_str(),
+			"notifications/initialized" | "notifications/roots/list_changed"
+		) {
+			if self.client_roots_supported {
+				self.sync_workspace_root().await;
+			}
+			return Ok(None);
+		}
+
 		let start_time = std::time::Instant::now();
 		let request_id = request.id.clone();
 		let request_id_for_error = request_id.clone(); // Clone for error handling
 		let request_method = request.method.clone(); // Clone for error handling
 
+		// Track the request so `octocode mcp status` can report it while it runs
+		if request.method == "initialize" {
+			self.activity
+				.record_initialize(STDIO_SESSION, request.params.as_ref());
+			self.client_roots_supported = roots::client_supports_roots(request.params.as_ref());
+		}
+		let tool_name = tool_call_name(&request);
+		let call_id = tool_name
+			.as_deref()
+			.map(|tool| self.activity.begin_call(STDIO_SESSION, tool));
+
 		// Execute request with comprehensive panic recovery (timeout control left to external MCP client)
 		let response = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
 			// Create a new async runtime for the panic-safe execution
@@ -627,7 +836,12 @@ impl McpServer {
 				tokio::runtime::Handle::current().block_on(async {
 					// Execute request without internal timeout - let external MCP client control timeouts
 					match request.method.as_str() {
-						"initialize" => self.handle_initialize(&request).await,
+						"initialize" => {
+							sampling::set_client_supported(sampling::client_supports_sampling(
+								request.params.as_ref(),
+							));
+							self.handle_initialize(&request).await
+						}
 						"tools/list" => self.handle_tools_list(&request).await,
 						"tools/call" => self.handle_tools_call(&request).await,
 						"ping" => self.handle_ping(&request).await,
@@ -667,6 +881,14 @@ impl McpServer {
 			}
 		};
 
+		if let Some(call_id) = call_id {
+			self.activity.end_call(call_id);
+		}
+		if let Some(ref error) = response.error {
+			self.activity
+				.record_error(&request_method, tool_name.as_deref(), &error.message);
+		}
+
 		// Log the response with timing
 		let duration_ms = start_time.elapsed().as_millis() as u64;
 		log_mcp_response(
@@ -680,7 +902,7 @@ impl McpServer {
 	}
 
 	/// Helper method to detect broken pipe errors
-	fn is_broken_pipe_error(&self, error: &std::io::Error) -> bool {
+	fn is_broken_pipe_error(error: &std::io::Error) -> bool {
 		use std::io::ErrorKind;
 		matches!(
 			error.kind(),
@@ -692,11 +914,7 @@ impl McpServer {
 	}
 
 	/// Safe response sending with error handling and size limits
-	async fn send_response(
-		&self,
-		writer: &mut tokio::io::Stdout,
-		response: &JsonRpcResponse,
-	) -> Result<()> {
+	async fn send_response(&self, writer: &SharedWriter, response: &JsonRpcResponse) -> Result<()> {
 		// Serialize response with panic recovery and size checking
 		let response_json = match panic::catch_unwind(|| serde_json::to_string(response)) {
 			Ok(Ok(json)) => {
@@ -732,6 +950,7 @@ impl McpServer {
 
 		// Send with timeout to prevent hanging on broken pipes
 		tokio::time::timeout(Duration::from_millis(MCP_IO_TIMEOUT_MS), async {
+			let mut writer = writer.lock().await;
 			writer.write_all(response_json.as_bytes()).await?;
 			writer.write_all(b"\n").await?;
 			writer.flush().await
@@ -745,7 +964,7 @@ impl McpServer {
 	/// Helper method to send error responses
 	async fn send_error_response(
 		&self,
-		writer: &mut tokio::io::Stdout,
+		writer: &SharedWriter,
 		id: Option<&serde_json::Value>,
 		code: i32,
 		message: &str,
@@ -791,6 +1010,9 @@ impl McpServer {
 		let mut tools = vec![
 			SemanticCodeProvider::get_tool_definition(),
 			SemanticCodeProvider::get_view_signatures_tool_definition(),
+			SemanticCodeProvider::get_resolve_anchor_tool_definition(),
+			SemanticCodeProvider::get_find_similar_tool_definition(),
+			SemanticCodeProvider::get_read_range_tool_definition(),
 		];
 
 		// Add memory tools if available
@@ -808,6 +1030,14 @@ impl McpServer {
 			tools.extend(crate::mcp::lsp::LspProvider::get_tool_definitions());
 		}
 
+		tools.extend(self.plugins.get_tool_definitions());
+
+		if self.read_only {
+			tools.retain(|tool| !is_mutating_tool(&tool.name));
+		}
+		self.session_cache.add_refresh_parameter(&mut tools);
+		let tools = apply_tool_overrides(tools, &self.config.mcp);
+
 		JsonRpcResponse {
 			jsonrpc: "2.0".to_string(),
 			id: request.id.clone(),
@@ -855,6 +1085,33 @@ impl McpServer {
 			}
 		};
 
+		// Map configured tool names back to the built-in ones
+		let tool_name = match resolve_tool_name(tool_name, &self.config.mcp) {
+			Some(name) => name.to_string(),
+			None => {
+				return JsonRpcResponse {
+					jsonrpc: "2.0".to_string(),
+					id: request.id.clone(),
+					result: None,
+					error: Some(
+						McpError::method_not_found(
+							format!(
+								"Tool '{}' is disabled in the current configuration",
+								tool_name
+							),
+							tool_name,
+						)
+						.into_jsonrpc(),
+					),
+				};
+			}
+		};
+		let tool_name = tool_name.as_str();
+
+		if self.read_only && is_mutating_tool(tool_name) {
+			return read_only_error(request, tool_name);
+		}
+
 		let default_args = json!({});
 		let arguments = params.get("arguments").unwrap_or(&default_args);
 
@@ -877,9 +1134,24 @@ impl McpServer {
 			}
 		}
 
+		// Repeated read-only calls may be answered from the session cache
+		let cached_call = CachedCall::new(tool_name, arguments);
+		let generation = self.activity.index_generation();
+		if let Some(call) = &cached_call {
+			if let Some(content) = self.session_cache.lookup(STDIO_SESSION, call, generation) {
+				return tool_text_response(request, content);
+			}
+		}
+
 		let result = match tool_name {
-			"semantic_search" => self.semantic_code.execute_search(arguments).await,
+			"semantic_search" => match progress_token(params) {
+				Some(token) => self.execute_search_streamed(arguments, token).await,
+				None => self.semantic_code.execute_search(arguments).await,
+			},
 			"view_signatures" => self.semantic_code.execute_view_signatures(arguments).await,
+			"resolve_anchor" => self.semantic_code.execute_resolve_anchor(arguments).await,
+			"find_similar" => self.semantic_code.execute_find_similar(arguments).await,
+			"read_range" => self.semantic_code.execute_read_range(arguments).await,
 			"graphrag" => match &self.graphrag {
 				Some(provider) => provider.execute(arguments).await,
 				None => Err(McpError::method_not_found("GraphRAG is not enabled in the current configuration. Please enable GraphRAG in octocode.toml to use relationship-aware search.", "graphrag")),
@@ -892,17 +1164,22 @@ impl McpServer {
 				Some(provider) => provider.execute_remember(arguments).await,
 				None => Err(McpError::method_not_found("Memory system is not available", "remember")),
 			},
+			"memory_timeline" => match &self.memory {
+				Some(provider) => provider.execute_timeline(arguments).await,
+				None => Err(McpError::method_not_found("Memory system is not available", "memory_timeline")),
+			},
 			"forget" => match &self.memory {
-				Some(provider) => provider.execute_forget(arguments).await,
+				Some(provider) => provider.execute_forget(arguments, false).await,
 				None => Err(McpError::method_not_found("Memory system is not available", "forget")),
 			},
 			// LSP tools
-			"lsp_goto_definition" => match &self.lsp {
+			// Renamed tools keep their old names for existing clients
+			"lsp_definition" | "lsp_goto_definition" => match &self.lsp {
 				Some(provider) => {
 					let mut lsp_guard = provider.lock().await;
 					lsp_guard.execute_goto_definition(arguments).await
 				},
-				None => Err(McpError::method_not_found("LSP server is not available. Start MCP server with --with-lsp=\"<command>\" to enable LSP features.", "lsp_goto_definition")),
+				None => Err(McpError::method_not_found("LSP server is not available. Start MCP server with --with-lsp=\"<command>\" to enable LSP features.", "lsp_definition")),
 			},
 			"lsp_hover" => match &self.lsp {
 				Some(provider) => {
@@ -911,12 +1188,12 @@ impl McpServer {
 				},
 				None => Err(McpError::method_not_found("LSP server is not available. Start MCP server with --with-lsp=\"<command>\" to enable LSP features.", "lsp_hover")),
 			},
-			"lsp_find_references" => match &self.lsp {
+			"lsp_references" | "lsp_find_references" => match &self.lsp {
 				Some(provider) => {
 					let mut lsp_guard = provider.lock().await;
 					lsp_guard.execute_find_references(arguments).await
 				},
-				None => Err(McpError::method_not_found("LSP server is not available. Start MCP server with --with-lsp=\"<command>\" to enable LSP features.", "lsp_find_references")),
+				None => Err(McpError::method_not_found("LSP server is not available. Start MCP server with --with-lsp=\"<command>\" to enable LSP features.", "lsp_references")),
 			},
 			"lsp_document_symbols" => match &self.lsp {
 				Some(provider) => {
@@ -939,16 +1216,29 @@ impl McpServer {
 				},
 				None => Err(McpError::method_not_found("LSP server is not available. Start MCP server with --with-lsp=\"<command>\" to enable LSP features.", "lsp_completion")),
 			},
+			name if self.plugins.has_tool(name) => self.plugins.execute(name, arguments).await,
 			_ => {
-				let available_tools = format!("semantic_search, view_signatures{}{}{}",
+				let available_tools = format!("semantic_search, view_signatures, resolve_anchor, find_similar, read_range{}{}{}{}",
 				if self.graphrag.is_some() { ", graphrag" } else { "" },
-					if self.memory.is_some() { ", memorize, remember, forget" } else { "" },
-					if self.lsp.is_some() { ", lsp_goto_definition, lsp_hover, lsp_find_references, lsp_document_symbols, lsp_workspace_symbols, lsp_completion" } else { "" }
+					if self.memory.is_some() { ", memorize, remember, memory_timeline, forget" } else { "" },
+					if self.lsp.is_some() { ", lsp_definition, lsp_hover, lsp_references, lsp_document_symbols, lsp_workspace_symbols, lsp_completion" } else { "" },
+					self.plugins.tool_names()
 				);
 				Err(McpError::method_not_found(format!("Unknown tool '{}'. Available tools: {}", tool_name, available_tools), tool_name))
 			}
 		};
 
+		let result =
+			result.map(|content| enforce_token_budget(tool_name, content, &self.config.mcp));
+		let result = match (result, &cached_call) {
+			(Ok(content), Some(call)) => {
+				Ok(self
+					.session_cache
+					.record(STDIO_SESSION, call, content, generation))
+			}
+			(result, _) => result,
+		};
+
 		match result {
 			Ok(content) => JsonRpcResponse {
 				jsonrpc: "2.0".to_string(),
@@ -984,6 +1274,49 @@ impl McpServer {
 		}
 	}
 
+	/// Run semantic_search, sending the hits found ahead of the complete result
+	/// to the client as `notifications/progress` messages for the call's token
+	async fn execute_search_streamed(
+		&self,
+		arguments: &serde_json::Value,
+		token: serde_json::Value,
+	) -> Result<String, McpError> {
+		// Streamed hits count against the same token budget as the response
+		let requested = arguments
+			.get("max_tokens")
+			.and_then(|v| v.as_u64())
+			.unwrap_or(2000) as usize;
+		let mut budget = StreamBudget::new("semantic_search", requested, &self.config.mcp);
+		let (partial, mut partial_rx) = mpsc::unbounded_channel::<String>();
+		let forward = tokio::spawn(async move {
+			let mut progress = 0u64;
+			while let Some(message) = partial_rx.recv().await {
+				let Some(message) = budget.take(message) else {
+					continue;
+				};
+				progress += 1;
+				let params = json!({
+					"progressToken": token,
+					"progress": progress,
+					"message": message
+				});
+				if let Err(e) = sampling::send_notification("notifications/progress", params).await
+				{
+					debug!("Failed to stream partial search results: {}", e);
+				}
+			}
+		});
+
+		let result = self
+			.semantic_code
+			.execute_search_streaming(arguments, Some(&partial))
+			.await;
+		// Partial results go out before the response that completes the call
+		drop(partial);
+		let _ = forward.await;
+		result
+	}
+
 	async fn handle_ping(&self, request: &JsonRpcRequest) -> JsonRpcResponse {
 		JsonRpcResponse {
 			jsonrpc: "2.0".to_string(),
@@ -1044,6 +1377,71 @@ impl Drop for McpServer {
 		if let Some(handle) = self.index_handle.take() {
 			handle.abort();
 		}
+		self.stop_control();
+	}
+}
+
+/// Name of the tool invoked by a `tools/call` request, if any
+fn tool_call_name(request: &JsonRpcRequest) -> Option<String> {
+	if request.method != "tools/call" {
+		return None;
+	}
+	request
+		.params
+		.as_ref()
+		.and_then(|p| p.get("name"))
+		.and_then(|v| v.as_str())
+		.map(String::from)
+}
+
+/// Progress token a client attached to a request to receive progress notifications
+fn progress_token(params: &serde_json::Value) -> Option<serde_json::Value> {
+	params
+		.get("_meta")
+		.and_then(|meta| meta.get("progressToken"))
+		.filter(|token| token.is_string() || token.is_number())
+		.cloned()
+}
+
+/// Read stdin line by line, handing sampling responses to the bridge and
+/// forwarding everything else to the request loop
+async fn read_stdin_lines(tx: mpsc::Sender<String>) {
+	let mut reader = BufReader::new(tokio::io::stdin());
+	let mut consecutive_errors = 0u32;
+	const MAX_READ_ERRORS: u32 = 10;
+
+	loop {
+		let mut line = String::with_capacity(1024); // Pre-allocate reasonable buffer
+		match reader.read_line(&mut line).await {
+			Ok(0) => {
+				// EOF reached - normal shutdown
+				debug!("MCP Server: EOF received on stdin");
+				break;
+			}
+			Ok(_) => {
+				consecutive_errors = 0;
+				if sampling::route_response(&line) {
+					continue;
+				}
+				if tx.send(line).await.is_err() {
+					break;
+				}
+			}
+			Err(e) => {
+				// I/O error reading from stdin
+				if McpServer::is_broken_pipe_error(&e) {
+					debug!("MCP Server: Broken pipe detected, shutting down gracefully");
+					break;
+				}
+				log_critical_error("Error reading from stdin", &e);
+				consecutive_errors += 1;
+				if consecutive_errors >= MAX_READ_ERRORS {
+					break;
+				}
+				// Brief delay before retrying
+				tokio::time::sleep(Duration::from_millis(100)).await;
+			}
+		}
 	}
 }
 
@@ -1083,6 +1481,20 @@ async fn perform_indexing(
 	match indexing_result {
 		Ok(()) => {
 			log_indexing_operation("direct_reindex_complete", None, Some(duration_ms), true);
+			let state_guard = state.read();
+			if !state_guard.secret_findings.is_empty() {
+				warn!(
+					findings = ?state_guard.secret_findings,
+					skipped_blocks = state_guard.secret_skipped_blocks,
+					"Secrets kept out of the index"
+				);
+			}
+			if !state_guard.policy_violations.is_empty() {
+				warn!(
+					violations = ?state_guard.policy_violations,
+					"Blocks from local-only paths not sent to remote providers"
+				);
+			}
 			Ok(())
 		}
 		Err(e) => {
@@ -1210,74 +1622,117 @@ async fn run_watcher(
 
 // HTTP server types and handlers for MCP over HTTP
 
-/// Shared state for HTTP handlers
-#[derive(Clone)]
+/// State shared by all HTTP connections; only the session set is locked, so
+/// tool calls run side by side
 struct HttpServerState {
 	semantic_code: SemanticCodeProvider,
 	graphrag: Option<GraphRagProvider>,
 	memory: Option<MemoryProvider>,
 	lsp: Option<Arc<Mutex<crate::mcp::lsp::LspProvider>>>,
+	plugins: PluginToolProvider,
+	activity: McpActivity,
+	mcp_config: crate::config::McpConfig,
+	/// Tokens of `[mcp.auth]`
+	auth: Arc<Authenticator>,
+	read_only: bool,
+	/// Session ids handed out on `initialize` and not yet deleted
+	sessions: Mutex<HashSet<String>>,
+	session_cache: SessionCache,
+	/// Tool calls allowed to run at once, from `mcp.max_concurrent_requests`
+	request_limit: Option<Arc<tokio::sync::Semaphore>>,
 }
 
-/// Handle a single HTTP connection
+/// Semaphore for `mcp.max_concurrent_requests`; 0 removes the limit
+pub(crate) fn request_limit(max_concurrent_requests: usize) -> Option<Arc<tokio::sync::Semaphore>> {
+	match max_concurrent_requests {
+		0 => None,
+		limit => Some(Arc::new(tokio::sync::Semaphore::new(limit))),
+	}
+}
+
+/// Handle a single HTTP connection using the streamable HTTP transport:
+/// POST carries JSON-RPC messages, DELETE ends a session and GET (server-initiated
+/// streams) is not offered
 async fn handle_http_connection(
-	mut stream: TcpStream,
-	state: Arc<Mutex<HttpServerState>>,
+	mut stream: Box<dyn HttpStream>,
+	peer: std::net::SocketAddr,
+	state: Arc<HttpServerState>,
 ) -> Result<()> {
-	let mut buffer = vec![0; 8192];
-	let bytes_read = stream.read(&mut buffer).await?;
+	let http_request = match http::read_request(&mut stream, MCP_MAX_REQUEST_SIZE).await {
+		Ok(Some(request)) => request,
+		Ok(None) => return Ok(()),
+		Err(e) => {
+			debug!("Failed to read HTTP request: {}", e);
+			return http::write_error(&mut stream, 400, "Invalid HTTP request").await;
+		}
+	};
 
-	if bytes_read == 0 {
-		return Ok(());
+	let path = http_request.path.split('?').next().unwrap_or("");
+	if path != "/mcp" && path != "/" {
+		return http::write_error(&mut stream, 404, "Not Found").await;
 	}
 
-	let request_str = String::from_utf8_lossy(&buffer[..bytes_read]);
-
-	// Parse HTTP request - simple implementation for MCP over HTTP
-	let mut lines = request_str.lines();
-	let request_line = lines.next().unwrap_or("");
-
-	// Check if it's a POST request to /mcp
-	if !request_line.starts_with("POST /mcp") && !request_line.starts_with("POST / ") {
-		// Send 404 for non-MCP endpoints
-		let response = "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n";
-		stream.write_all(response.as_bytes()).await?;
-		return Ok(());
+	// CORS preflights carry no credentials
+	if http_request.method != "OPTIONS" {
+		if let Err(rejection) = state.auth.check(None, http_request.header("authorization")) {
+			log_auth_rejection("http", &peer, None, rejection.as_str());
+			return http::write_unauthorized(&mut stream, rejection.message()).await;
+		}
 	}
 
-	// Find content length
-	let mut content_length = 0;
-	let mut body_start = 0;
-
-	for (i, line) in lines.enumerate() {
-		if line.is_empty() {
-			// Calculate where body starts in the original buffer
-			let lines_before_body: Vec<&str> = request_str.lines().take(i + 2).collect();
-			body_start = lines_before_body.join("\n").len() + 1; // +1 for the final \n
-			break;
+	let session_id = http_request
+		.header(http::SESSION_HEADER)
+		.map(str::to_string);
+
+	match http_request.method.as_str() {
+		"POST" => {}
+		"OPTIONS" => return http::write_response(&mut stream, 204, None, &[], b"").await,
+		"DELETE" => {
+			let removed = match &session_id {
+				Some(id) => state.sessions.lock().await.remove(id),
+				None => false,
+			};
+			return match session_id {
+				Some(id) if removed => {
+					let session_key = format!("http:{}", id);
+					state.activity.close_session(&session_key);
+					state.session_cache.remove_session(&session_key);
+					http::write_response(&mut stream, 200, None, &[], b"").await
+				}
+				_ => http::write_error(&mut stream, 404, "Session not found").await,
+			};
 		}
-		if line.to_lowercase().starts_with("content-length:") {
-			if let Some(len_str) = line.split(':').nth(1) {
-				content_length = len_str.trim().parse().unwrap_or(0);
-			}
+		_ => {
+			return http::write_response(
+				&mut stream,
+				405,
+				None,
+				&[("Allow", "POST, DELETE, OPTIONS")],
+				b"",
+			)
+			.await;
 		}
 	}
 
-	// Extract JSON body
-	let json_body = if content_length > 0 && body_start < bytes_read {
-		let body_bytes =
-			&buffer[body_start..std::cmp::min(body_start + content_length, bytes_read)];
-		String::from_utf8_lossy(body_bytes).to_string()
-	} else {
-		return send_http_error(&mut stream, 400, "Missing or invalid request body").await;
-	};
+	// Clients that send no Accept header get JSON, as before
+	if http_request.header("accept").is_some()
+		&& !http_request.accepts("application/json")
+		&& !http_request.accepts("text/event-stream")
+	{
+		return http::write_error(
+			&mut stream,
+			406,
+			"Accept must include application/json or text/event-stream",
+		)
+		.await;
+	}
 
 	// Parse JSON-RPC request
-	let request: JsonRpcRequest = match serde_json::from_str(&json_body) {
+	let request: JsonRpcRequest = match serde_json::from_slice(&http_request.body) {
 		Ok(req) => req,
 		Err(e) => {
 			debug!("Failed to parse JSON-RPC request: {}", e);
-			return send_http_error(&mut stream, 400, "Invalid JSON-RPC request").await;
+			return http::write_error(&mut stream, 400, "Invalid JSON-RPC request").await;
 		}
 	};
 
@@ -1292,14 +1747,57 @@ async fn handle_http_connection(
 	let request_id = request.id.clone();
 	let request_method = request.method.clone();
 
-	// Get server state
-	let server_state = state.lock().await;
+	// Sessions are assigned on initialize; clients that never send the header
+	// are keyed by their address, as before
+	let new_session = if request.method == "initialize" {
+		let id = uuid::Uuid::new_v4().to_string();
+		state.sessions.lock().await.insert(id.clone());
+		Some(id)
+	} else {
+		None
+	};
+	let known_session = match &session_id {
+		Some(id) => state.sessions.lock().await.contains(id),
+		None => false,
+	};
+	let session_key = match (&new_session, &session_id) {
+		(Some(id), _) => format!("http:{}", id),
+		(None, Some(id)) if known_session => format!("http:{}", id),
+		(None, Some(_)) => {
+			return http::write_error(&mut stream, 404, "Session not found").await;
+		}
+		(None, None) => format!("http:{}", peer.ip()),
+	};
+
+	state.activity.touch_session(&session_key, "http");
+	if request.method == "initialize" {
+		state
+			.activity
+			.record_initialize(&session_key, request.params.as_ref());
+	}
+
+	// Notifications and client responses get no JSON-RPC reply
+	if request.id.is_none() {
+		return http::write_response(&mut stream, 202, None, &[], b"").await;
+	}
+
+	let tool_name = tool_call_name(&request);
+	let call_id = tool_name
+		.as_deref()
+		.map(|tool| state.activity.begin_call(&session_key, tool));
 
 	// Handle the request
 	let response = match request.method.as_str() {
 		"initialize" => handle_initialize_http(&request),
-		"tools/list" => handle_tools_list_http(&request, &server_state),
-		"tools/call" => handle_tools_call_http(&request, &server_state).await,
+		"tools/list" => handle_tools_list_http(&request, &state),
+		"tools/call" => {
+			// Calls over the limit wait here for a running one to finish
+			let _permit = match &state.request_limit {
+				Some(limit) => limit.clone().acquire_owned().await.ok(),
+				None => None,
+			};
+			handle_tools_call_http(&request, &state, &session_key).await
+		}
 		"ping" => handle_ping_http(&request),
 		_ => JsonRpcResponse {
 			jsonrpc: "2.0".to_string(),
@@ -1313,6 +1811,15 @@ async fn handle_http_connection(
 		},
 	};
 
+	if let Some(call_id) = call_id {
+		state.activity.end_call(call_id);
+	}
+	if let Some(ref error) = response.error {
+		state
+			.activity
+			.record_error(&request_method, tool_name.as_deref(), &error.message);
+	}
+
 	// Log the response
 	let duration_ms = start_time.elapsed().as_millis() as u64;
 	log_mcp_response(
@@ -1323,39 +1830,18 @@ async fn handle_http_connection(
 	);
 
 	// Send HTTP response
-	send_http_response(&mut stream, &response).await
-}
-
-/// Send HTTP error response
-async fn send_http_error(stream: &mut TcpStream, status: u16, message: &str) -> Result<()> {
-	let status_text = match status {
-		400 => "Bad Request",
-		404 => "Not Found",
-		500 => "Internal Server Error",
-		_ => "Error",
-	};
-
-	let response = format!(
-		"HTTP/1.1 {} {}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nAccess-Control-Allow-Origin: *\r\n\r\n{}",
-		status, status_text, message.len(), message
-	);
-
-	stream.write_all(response.as_bytes()).await?;
-	Ok(())
-}
-
-/// Send HTTP JSON-RPC response
-async fn send_http_response(stream: &mut TcpStream, response: &JsonRpcResponse) -> Result<()> {
-	let json_response = serde_json::to_string(response)?;
-
-	let http_response = format!(
-		"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nAccess-Control-Allow-Origin: *\r\nAccess-Control-Allow-Methods: POST, OPTIONS\r\nAccess-Control-Allow-Headers: Content-Type\r\n\r\n{}",
-		json_response.len(),
-		json_response
-	);
-
-	stream.write_all(http_response.as_bytes()).await?;
-	Ok(())
+	let json_response = serde_json::to_string(&response)?;
+	let session_header: Vec<(&str, &str)> = new_session
+		.as_deref()
+		.map(|id| vec![(http::SESSION_HEADER, id)])
+		.unwrap_or_default();
+	http::write_message(
+		&mut stream,
+		&json_response,
+		http_request.prefers_event_stream(),
+		&session_header,
+	)
+	.await
 }
 
 fn handle_initialize_http(request: &JsonRpcRequest) -> JsonRpcResponse {
@@ -1384,6 +1870,9 @@ fn handle_tools_list_http(request: &JsonRpcRequest, state: &HttpServerState) ->
 	let mut tools = vec![
 		SemanticCodeProvider::get_tool_definition(),
 		SemanticCodeProvider::get_view_signatures_tool_definition(),
+		SemanticCodeProvider::get_resolve_anchor_tool_definition(),
+		SemanticCodeProvider::get_find_similar_tool_definition(),
+		SemanticCodeProvider::get_read_range_tool_definition(),
 	];
 
 	// Add memory tools if available
@@ -1401,6 +1890,14 @@ fn handle_tools_list_http(request: &JsonRpcRequest, state: &HttpServerState) ->
 		tools.extend(crate::mcp::lsp::LspProvider::get_tool_definitions());
 	}
 
+	tools.extend(state.plugins.get_tool_definitions());
+
+	if state.read_only {
+		tools.retain(|tool| !is_mutating_tool(&tool.name));
+	}
+	state.session_cache.add_refresh_parameter(&mut tools);
+	let tools = apply_tool_overrides(tools, &state.mcp_config);
+
 	JsonRpcResponse {
 		jsonrpc: "2.0".to_string(),
 		id: request.id.clone(),
@@ -1414,6 +1911,7 @@ fn handle_tools_list_http(request: &JsonRpcRequest, state: &HttpServerState) ->
 async fn handle_tools_call_http(
 	request: &JsonRpcRequest,
 	state: &HttpServerState,
+	session: &str,
 ) -> JsonRpcResponse {
 	let params = match &request.params {
 		Some(params) => params,
@@ -1451,6 +1949,33 @@ async fn handle_tools_call_http(
 		}
 	};
 
+	// Map configured tool names back to the built-in ones
+	let tool_name = match resolve_tool_name(tool_name, &state.mcp_config) {
+		Some(name) => name.to_string(),
+		None => {
+			return JsonRpcResponse {
+				jsonrpc: "2.0".to_string(),
+				id: request.id.clone(),
+				result: None,
+				error: Some(
+					McpError::method_not_found(
+						format!(
+							"Tool '{}' is disabled in the current configuration",
+							tool_name
+						),
+						tool_name,
+					)
+					.into_jsonrpc(),
+				),
+			};
+		}
+	};
+	let tool_name = tool_name.as_str();
+
+	if state.read_only && is_mutating_tool(tool_name) {
+		return read_only_error(request, tool_name);
+	}
+
 	let default_args = json!({});
 	let arguments = params.get("arguments").unwrap_or(&default_args);
 
@@ -1473,9 +1998,21 @@ async fn handle_tools_call_http(
 		}
 	}
 
+	// Repeated read-only calls may be answered from the session cache
+	let cached_call = CachedCall::new(tool_name, arguments);
+	let generation = state.activity.index_generation();
+	if let Some(call) = &cached_call {
+		if let Some(content) = state.session_cache.lookup(session, call, generation) {
+			return tool_text_response(request, content);
+		}
+	}
+
 	let result = match tool_name {
 		"semantic_search" => state.semantic_code.execute_search(arguments).await,
 		"view_signatures" => state.semantic_code.execute_view_signatures(arguments).await,
+		"resolve_anchor" => state.semantic_code.execute_resolve_anchor(arguments).await,
+		"find_similar" => state.semantic_code.execute_find_similar(arguments).await,
+		"read_range" => state.semantic_code.execute_read_range(arguments).await,
 		"graphrag" => match &state.graphrag {
 			Some(provider) => provider.execute(arguments).await,
 			None => Err(McpError::method_not_found("GraphRAG is not enabled in the current configuration. Please enable GraphRAG in octocode.toml to use relationship-aware search.", "graphrag")),
@@ -1488,17 +2025,22 @@ async fn handle_tools_call_http(
 			Some(provider) => provider.execute_remember(arguments).await,
 			None => Err(McpError::method_not_found("Memory system is not available", "remember")),
 		},
+		"memory_timeline" => match &state.memory {
+			Some(provider) => provider.execute_timeline(arguments).await,
+			None => Err(McpError::method_not_found("Memory system is not available", "memory_timeline")),
+		},
 		"forget" => match &state.memory {
-			Some(provider) => provider.execute_forget(arguments).await,
+			Some(provider) => provider.execute_forget(arguments, false).await,
 			None => Err(McpError::method_not_found("Memory system is not available", "forget")),
 		},
 		// LSP tools
-		"lsp_goto_definition" => match &state.lsp {
+		// Renamed tools keep their old names for existing clients
+		"lsp_definition" | "lsp_goto_definition" => match &state.lsp {
 			Some(provider) => {
 				let mut lsp_guard = provider.lock().await;
 				lsp_guard.execute_goto_definition(arguments).await
 			},
-			None => Err(McpError::method_not_found("LSP server is not available. Start MCP server with --with-lsp=\"<command>\" to enable LSP features.", "lsp_goto_definition")),
+			None => Err(McpError::method_not_found("LSP server is not available. Start MCP server with --with-lsp=\"<command>\" to enable LSP features.", "lsp_definition")),
 		},
 		"lsp_hover" => match &state.lsp {
 			Some(provider) => {
@@ -1507,12 +2049,12 @@ async fn handle_tools_call_http(
 			},
 			None => Err(McpError::method_not_found("LSP server is not available. Start MCP server with --with-lsp=\"<command>\" to enable LSP features.", "lsp_hover")),
 		},
-		"lsp_find_references" => match &state.lsp {
+		"lsp_references" | "lsp_find_references" => match &state.lsp {
 			Some(provider) => {
 				let mut lsp_guard = provider.lock().await;
 				lsp_guard.execute_find_references(arguments).await
 			},
-			None => Err(McpError::method_not_found("LSP server is not available. Start MCP server with --with-lsp=\"<command>\" to enable LSP features.", "lsp_find_references")),
+			None => Err(McpError::method_not_found("LSP server is not available. Start MCP server with --with-lsp=\"<command>\" to enable LSP features.", "lsp_references")),
 		},
 		"lsp_document_symbols" => match &state.lsp {
 			Some(provider) => {
@@ -1535,16 +2077,26 @@ async fn handle_tools_call_http(
 			},
 			None => Err(McpError::method_not_found("LSP server is not available. Start MCP server with --with-lsp=\"<command>\" to enable LSP features.", "lsp_completion")),
 		},
+		name if state.plugins.has_tool(name) => state.plugins.execute(name, arguments).await,
 		_ => {
-			let available_tools = format!("semantic_search, view_signatures{}{}{}",
+			let available_tools = format!("semantic_search, view_signatures, resolve_anchor, find_similar, read_range{}{}{}{}",
 			if state.graphrag.is_some() { ", graphrag" } else { "" },
-				if state.memory.is_some() { ", memorize, remember, forget" } else { "" },
-				if state.lsp.is_some() { ", lsp_goto_definition, lsp_hover, lsp_find_references, lsp_document_symbols, lsp_workspace_symbols, lsp_completion" } else { "" }
+				if state.memory.is_some() { ", memorize, remember, memory_timeline, forget" } else { "" },
+				if state.lsp.is_some() { ", lsp_definition, lsp_hover, lsp_references, lsp_document_symbols, lsp_workspace_symbols, lsp_completion" } else { "" },
+				state.plugins.tool_names()
 			);
 			Err(McpError::method_not_found(format!("Unknown tool '{}'. Available tools: {}", tool_name, available_tools), tool_name))
 		}
 	};
 
+	let result = result.map(|content| enforce_token_budget(tool_name, content, &state.mcp_config));
+	let result = match (result, &cached_call) {
+		(Ok(content), Some(call)) => Ok(state
+			.session_cache
+			.record(session, call, content, generation)),
+		(result, _) => result,
+	};
+
 	match result {
 		Ok(content) => JsonRpcResponse {
 			jsonrpc: "2.0".to_string(),
@@ -1581,6 +2133,58 @@ async fn handle_tools_call_http(
 	}
 }
 
+/// Switches back to the directory it was created in when dropped, unless kept
+struct CurrentDirGuard {
+	previous: Option<std::path::PathBuf>,
+}
+
+impl CurrentDirGuard {
+	/// Change to `directory`, remembering the current one
+	fn change_to(directory: &std::path::Path) -> Result<Self> {
+		let previous = std::env::current_dir()?;
+		std::env::set_current_dir(directory).map_err(|e| {
+			anyhow::anyhow!(
+				"Failed to change to working directory '{}': {}",
+				directory.display(),
+				e
+			)
+		})?;
+		Ok(Self {
+			previous: Some(previous),
+		})
+	}
+
+	/// Stay in the new directory
+	fn keep(mut self) {
+		self.previous = None;
+	}
+}
+
+impl Drop for CurrentDirGuard {
+	fn drop(&mut self) {
+		if let Some(previous) = self.previous.take() {
+			if let Err(e) = std::env::set_current_dir(&previous) {
+				warn!(
+					"Failed to restore working directory '{}': {}",
+					previous.display(),
+					e
+				);
+			}
+		}
+	}
+}
+
+/// Change into a project and load its config and store; on any failure the
+/// previous working directory is restored
+async fn open_project(working_directory: &std::path::Path) -> Result<(Config, Store)> {
+	let guard = CurrentDirGuard::change_to(working_directory)?;
+	let config = Config::load()?;
+	let store = Store::new().await?;
+	store.initialize_collections().await?;
+	guard.keep();
+	Ok((config, store))
+}
+
 fn handle_ping_http(request: &JsonRpcRequest) -> JsonRpcResponse {
 	JsonRpcResponse {
 		jsonrpc: "2.0".to_string(),
@@ -1589,3 +2193,133 @@ fn handle_ping_http(request: &JsonRpcRequest) -> JsonRpcResponse {
 		error: None,
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	async fn http_state(max_concurrent_requests: usize, read_only: bool) -> Arc<HttpServerState> {
+		let config = Config::load_from_template().unwrap();
+		let working_directory = std::env::temp_dir();
+		Arc::new(HttpServerState {
+			semantic_code: SemanticCodeProvider::new(config.clone(), working_directory.clone()),
+			graphrag: None,
+			memory: None,
+			lsp: None,
+			plugins: PluginToolProvider::new(&config, false).await,
+			activity: McpActivity::new(&working_directory, Arc::new(AtomicBool::new(false))),
+			mcp_config: config.mcp.clone(),
+			auth: Arc::new(Authenticator::default()),
+			read_only,
+			sessions: Mutex::new(HashSet::new()),
+			session_cache: SessionCache::new(0),
+			request_limit: request_limit(max_concurrent_requests),
+		})
+	}
+
+	/// Raw response to a JSON-RPC message posted to `/mcp`
+	async fn post(state: Arc<HttpServerState>, body: &str) -> String {
+		use tokio::io::AsyncReadExt;
+		let (mut client, stream) = tokio::io::duplex(64 * 1024);
+		let raw = format!(
+			"POST /mcp HTTP/1.1\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+			body.len(),
+			body
+		);
+		client.write_all(raw.as_bytes()).await.unwrap();
+		let peer: std::net::SocketAddr = "127.0.0.1:9".parse().unwrap();
+		handle_http_connection(Box::new(stream), peer, state)
+			.await
+			.unwrap();
+		let mut response = String::new();
+		client.read_to_string(&mut response).await.unwrap();
+		response
+	}
+
+	#[tokio::test]
+	async fn test_http_tool_calls_beyond_the_limit_wait() {
+		let call = r#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"missing_tool","arguments":{}}}"#;
+		let ping = r#"{"jsonrpc":"2.0","id":2,"method":"ping"}"#;
+
+		let state = http_state(1, false).await;
+		let running = state
+			.request_limit
+			.clone()
+			.unwrap()
+			.acquire_owned()
+			.await
+			.unwrap();
+
+		let queued = tokio::spawn(post(state.clone(), call));
+		sleep(Duration::from_millis(200)).await;
+		assert!(
+			!queued.is_finished(),
+			"a tool call over the limit should wait for a running one"
+		);
+
+		// Requests other than tool calls are not limited
+		let response = post(state.clone(), ping).await;
+		assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+
+		drop(running);
+		let response = tokio::time::timeout(Duration::from_secs(5), queued)
+			.await
+			.expect("the queued call should run once a slot is free")
+			.unwrap();
+		assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+		assert!(response.contains("missing_tool"), "{}", response);
+
+		// Without a limit calls never wait
+		let state = http_state(0, false).await;
+		assert!(state.request_limit.is_none());
+		let response = tokio::time::timeout(Duration::from_secs(5), post(state, call))
+			.await
+			.unwrap();
+		assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+	}
+
+	#[tokio::test]
+	async fn test_read_only_refuses_mutating_tools() {
+		let memorize = r#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"memorize","arguments":{"title":"t","content":"c","memory_type":"code"}}}"#;
+
+		let response = post(http_state(0, true).await, memorize).await;
+		assert!(response.contains("read-only mode"), "{}", response);
+
+		let response = post(http_state(0, false).await, memorize).await;
+		assert!(!response.contains("read-only mode"), "{}", response);
+
+		assert!(is_mutating_tool("memorize") && is_mutating_tool("forget"));
+		assert!(!is_mutating_tool("remember") && !is_mutating_tool("semantic_search"));
+	}
+
+	#[tokio::test]
+	async fn test_retarget_loads_the_new_project_or_stays_put() {
+		let _lock = crate::mcp::CURRENT_DIR_LOCK.lock().await;
+		let root = std::env::temp_dir().join(format!("octocode_retarget_{}", std::process::id()));
+		let write_config = |project: &str, content: &str| {
+			let dir = root.join(project).join(".octocode");
+			std::fs::create_dir_all(&dir).unwrap();
+			std::fs::write(dir.join(crate::config::PROJECT_CONFIG_FILE), content).unwrap();
+			root.join(project)
+		};
+		let broken = write_config("broken", "[search\nmax_offset = ");
+		let healthy = write_config("healthy", "[search]\nmax_offset = 7\n");
+		let before = std::env::current_dir().unwrap();
+
+		for target in [broken, root.join("missing")] {
+			assert!(open_project(&target).await.is_err());
+			assert_eq!(std::env::current_dir().unwrap(), before);
+		}
+
+		let (config, _store) = open_project(&healthy).await.unwrap();
+		let moved_to = std::env::current_dir().unwrap();
+		std::env::set_current_dir(&before).unwrap();
+		assert_eq!(moved_to, healthy.canonicalize().unwrap());
+		assert_eq!(config.search.max_offset, 7);
+
+		if let Ok(storage) = crate::storage::get_project_storage_path(&healthy) {
+			let _ = std::fs::remove_dir_all(storage);
+		}
+		let _ = std::fs::remove_dir_all(&root);
+	}
+}
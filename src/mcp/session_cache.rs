@@ -0,0 +1,319 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Per-session cache of rendered tool results
+//!
+//! Agents often repeat the same read-only call within one session. Each
+//! session keeps a small LRU of rendered results keyed by tool and arguments.
+//! When a repeated call would return exactly what the client already has, the
+//! server answers with a short "not modified" notice carrying the result's
+//! checksum instead of the full payload. Index-backed tools are not even
+//! re-executed until the next reindex. Passing `"refresh": true` always
+//! returns the full result.
+
+use parking_lot::Mutex;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+
+use crate::mcp::types::McpTool;
+
+/// Read-only tools whose results are cached
+const CACHEABLE_TOOLS: &[&str] = &[
+	"semantic_search",
+	"view_signatures",
+	"resolve_anchor",
+	"find_similar",
+	"read_range",
+	"graphrag",
+	"remember",
+	"lsp_definition",
+	"lsp_goto_definition",
+	"lsp_hover",
+	"lsp_references",
+	"lsp_find_references",
+	"lsp_document_symbols",
+	"lsp_workspace_symbols",
+];
+
+/// Tools that only read the index, so their output cannot change until the next reindex
+const INDEX_BACKED_TOOLS: &[&str] = &["semantic_search", "graphrag"];
+
+/// Argument that bypasses the "not modified" notice
+pub const REFRESH_ARGUMENT: &str = "refresh";
+
+/// Upper bound on tracked sessions; the least recently used one is dropped
+const MAX_SESSIONS: usize = 64;
+
+struct CachedResult {
+	key: String,
+	checksum: String,
+	output: String,
+	generation: u64,
+}
+
+struct SessionEntries {
+	session: String,
+	results: Vec<CachedResult>,
+}
+
+/// Shared cache of rendered results, one LRU per session
+#[derive(Clone)]
+pub struct SessionCache {
+	capacity: usize,
+	sessions: Arc<Mutex<Vec<SessionEntries>>>,
+}
+
+/// A cacheable tool call
+pub struct CachedCall {
+	tool: String,
+	key: String,
+	refresh: bool,
+}
+
+impl CachedCall {
+	/// Identify a call for caching; `None` for tools that are never cached
+	pub fn new(tool: &str, arguments: &Value) -> Option<Self> {
+		if !CACHEABLE_TOOLS.contains(&tool) {
+			return None;
+		}
+
+		let refresh = arguments
+			.get(REFRESH_ARGUMENT)
+			.and_then(|v| v.as_bool())
+			.unwrap_or(false);
+		let mut arguments = arguments.clone();
+		if let Some(map) = arguments.as_object_mut() {
+			map.remove(REFRESH_ARGUMENT);
+		}
+
+		Some(Self {
+			tool: tool.to_string(),
+			key: format!("{}:{}", tool, arguments),
+			refresh,
+		})
+	}
+}
+
+impl SessionCache {
+	/// Create a cache holding up to `capacity` results per session; 0 disables it
+	pub fn new(capacity: usize) -> Self {
+		Self {
+			capacity,
+			sessions: Arc::new(Mutex::new(Vec::new())),
+		}
+	}
+
+	pub fn is_enabled(&self) -> bool {
+		self.capacity > 0
+	}
+
+	/// Answer a call from the cache without executing it.
+	///
+	/// Only index-backed tools qualify, and only while the index has not changed
+	/// since the result was rendered.
+	pub fn lookup(&self, session: &str, call: &CachedCall, generation: u64) -> Option<String> {
+		if !self.is_enabled() || !INDEX_BACKED_TOOLS.contains(&call.tool.as_str()) {
+			return None;
+		}
+
+		let mut sessions = self.sessions.lock();
+		let entries = touch_session(&mut sessions, session)?;
+		let position = entries
+			.results
+			.iter()
+			.position(|result| result.key == call.key && result.generation == generation)?;
+		let cached = entries.results.remove(position);
+
+		let response = if call.refresh {
+			cached.output.clone()
+		} else {
+			not_modified(&call.tool, &cached.checksum)
+		};
+		entries.results.push(cached);
+		Some(response)
+	}
+
+	/// Store a freshly rendered result and return what to send: the notice when
+	/// it matches the previous result for the same call, otherwise the output
+	pub fn record(
+		&self,
+		session: &str,
+		call: &CachedCall,
+		output: String,
+		generation: u64,
+	) -> String {
+		if !self.is_enabled() {
+			return output;
+		}
+
+		let checksum = checksum(&output);
+		let mut sessions = self.sessions.lock();
+		if touch_session(&mut sessions, session).is_none() {
+			if sessions.len() >= MAX_SESSIONS {
+				sessions.remove(0);
+			}
+			sessions.push(SessionEntries {
+				session: session.to_string(),
+				results: Vec::new(),
+			});
+		}
+		let entries = sessions
+			.last_mut()
+			.expect("session was just touched or inserted");
+
+		let previous = entries
+			.results
+			.iter()
+			.position(|result| result.key == call.key)
+			.map(|position| entries.results.remove(position));
+		let unchanged = previous.is_some_and(|previous| previous.checksum == checksum);
+
+		if entries.results.len() >= self.capacity {
+			entries.results.remove(0);
+		}
+		let response = if unchanged && !call.refresh {
+			not_modified(&call.tool, &checksum)
+		} else {
+			output.clone()
+		};
+		entries.results.push(CachedResult {
+			key: call.key.clone(),
+			checksum,
+			output,
+			generation,
+		});
+
+		response
+	}
+
+	/// Forget everything cached for a session
+	pub fn remove_session(&self, session: &str) {
+		self.sessions
+			.lock()
+			.retain(|entries| entries.session != session);
+	}
+
+	/// Forget every session, e.g. after switching to another project
+	pub fn clear(&self) {
+		self.sessions.lock().clear();
+	}
+
+	/// Advertise the `refresh` argument on cacheable tools
+	pub fn add_refresh_parameter(&self, tools: &mut [McpTool]) {
+		if !self.is_enabled() {
+			return;
+		}
+		for tool in tools
+			.iter_mut()
+			.filter(|tool| CACHEABLE_TOOLS.contains(&tool.name.as_str()))
+		{
+			if let Some(properties) = tool
+				.input_schema
+				.get_mut("properties")
+				.and_then(|props| props.as_object_mut())
+			{
+				properties.insert(
+					REFRESH_ARGUMENT.to_string(),
+					json!({
+						"type": "boolean",
+						"description": "Return the full result even if an identical result was already sent in this session",
+						"default": false
+					}),
+				);
+			}
+		}
+	}
+}
+
+/// Move a session to the most recently used position and return it
+fn touch_session<'a>(
+	sessions: &'a mut Vec<SessionEntries>,
+	session: &str,
+) -> Option<&'a mut SessionEntries> {
+	let position = sessions
+		.iter()
+		.position(|entries| entries.session == session)?;
+	let entries = sessions.remove(position);
+	sessions.push(entries);
+	sessions.last_mut()
+}
+
+fn checksum(output: &str) -> String {
+	let digest = Sha256::digest(output.as_bytes());
+	format!("{:x}", digest)[..16].to_string()
+}
+
+fn not_modified(tool: &str, checksum: &str) -> String {
+	format!(
+		"Not modified: this {} call returned the same result earlier in this session (checksum {}). Reuse that result, or call again with \"{}\": true to receive it in full.",
+		tool, checksum, REFRESH_ARGUMENT
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn call(tool: &str, arguments: Value) -> CachedCall {
+		CachedCall::new(tool, &arguments).unwrap()
+	}
+
+	#[test]
+	fn test_repeated_result_becomes_not_modified() {
+		let cache = SessionCache::new(8);
+		let first = call("view_signatures", json!({"files": ["src/main.rs"]}));
+		assert_eq!(cache.record("s1", &first, "sigs".to_string(), 0), "sigs");
+
+		let again = call("view_signatures", json!({"files": ["src/main.rs"]}));
+		assert!(cache
+			.record("s1", &again, "sigs".to_string(), 0)
+			.starts_with("Not modified"));
+
+		// Changed output and other sessions get the full result
+		assert_eq!(cache.record("s1", &again, "new".to_string(), 0), "new");
+		assert_eq!(cache.record("s2", &again, "new".to_string(), 0), "new");
+	}
+
+	#[test]
+	fn test_lookup_only_within_same_index_generation() {
+		let cache = SessionCache::new(8);
+		let search = call("semantic_search", json!({"query": "auth"}));
+		assert!(cache.lookup("s1", &search, 0).is_none());
+		cache.record("s1", &search, "results".to_string(), 0);
+
+		assert!(cache
+			.lookup("s1", &search, 0)
+			.unwrap()
+			.starts_with("Not modified"));
+		assert!(cache.lookup("s1", &search, 1).is_none());
+
+		let refresh = call("semantic_search", json!({"query": "auth", "refresh": true}));
+		assert_eq!(cache.lookup("s1", &refresh, 0).as_deref(), Some("results"));
+	}
+
+	#[test]
+	fn test_uncacheable_tools_and_disabled_cache() {
+		assert!(CachedCall::new("memorize", &json!({})).is_none());
+
+		let cache = SessionCache::new(0);
+		let search = call("semantic_search", json!({"query": "auth"}));
+		cache.record("s1", &search, "results".to_string(), 0);
+		assert_eq!(
+			cache.record("s1", &search, "results".to_string(), 0),
+			"results"
+		);
+		assert!(cache.lookup("s1", &search, 0).is_none());
+	}
+}
@@ -0,0 +1,149 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! TLS for the HTTP transports
+//!
+//! `octocode mcp --bind` and `octocode mcp-proxy` accept plain TCP by default.
+//! When `[mcp.tls]` names a certificate and key, every accepted connection is
+//! wrapped in a rustls server session first; `client_ca_path` additionally
+//! requires clients to present a certificate signed by that CA (mutual TLS).
+
+use anyhow::{Context, Result};
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::server::WebPkiClientVerifier;
+use rustls::{RootCertStore, ServerConfig};
+use std::fs::File;
+use std::io::BufReader;
+use std::sync::Arc;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpStream;
+use tokio_rustls::TlsAcceptor;
+
+use crate::config::McpTlsConfig;
+
+/// Byte stream an HTTP handler reads requests from and writes responses to
+pub trait HttpStream: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> HttpStream for T {}
+
+/// Build the TLS acceptor from config, or `None` when TLS is not configured
+pub fn build_acceptor(config: &McpTlsConfig) -> Result<Option<TlsAcceptor>> {
+	let (cert_path, key_path) = match (&config.cert_path, &config.key_path) {
+		(Some(cert), Some(key)) => (cert, key),
+		(None, None) => {
+			if config.client_ca_path.is_some() {
+				return Err(anyhow::anyhow!(
+					"[mcp.tls] client_ca_path requires cert_path and key_path"
+				));
+			}
+			return Ok(None);
+		}
+		_ => {
+			return Err(anyhow::anyhow!(
+				"[mcp.tls] cert_path and key_path must be set together"
+			))
+		}
+	};
+
+	let certs = load_certs(cert_path)?;
+	let key = load_private_key(key_path)?;
+
+	let provider = Arc::new(rustls::crypto::ring::default_provider());
+	let builder = ServerConfig::builder_with_provider(provider.clone())
+		.with_safe_default_protocol_versions()
+		.context("Failed to select TLS protocol versions")?;
+
+	let builder = match &config.client_ca_path {
+		Some(ca_path) => {
+			let mut roots = RootCertStore::empty();
+			for cert in load_certs(ca_path)? {
+				roots
+					.add(cert)
+					.with_context(|| format!("Invalid CA certificate in {}", ca_path))?;
+			}
+			let verifier = WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider)
+				.build()
+				.context("Failed to build client certificate verifier")?;
+			builder.with_client_cert_verifier(verifier)
+		}
+		None => builder.with_no_client_auth(),
+	};
+
+	let mut server_config = builder
+		.with_single_cert(certs, key)
+		.context("TLS certificate and private key do not match")?;
+	server_config.alpn_protocols = vec![b"http/1.1".to_vec()];
+
+	Ok(Some(TlsAcceptor::from(Arc::new(server_config))))
+}
+
+/// Complete the TLS handshake when an acceptor is configured
+pub async fn accept(
+	acceptor: Option<&TlsAcceptor>,
+	stream: TcpStream,
+) -> Result<Box<dyn HttpStream>> {
+	match acceptor {
+		Some(acceptor) => {
+			let tls_stream = acceptor
+				.accept(stream)
+				.await
+				.context("TLS handshake failed")?;
+			Ok(Box::new(tls_stream))
+		}
+		None => Ok(Box::new(stream)),
+	}
+}
+
+fn load_certs(path: &str) -> Result<Vec<CertificateDer<'static>>> {
+	let file = File::open(path).with_context(|| format!("Failed to open {}", path))?;
+	let certs = rustls_pemfile::certs(&mut BufReader::new(file))
+		.collect::<Result<Vec<_>, _>>()
+		.with_context(|| format!("Failed to parse certificates in {}", path))?;
+	if certs.is_empty() {
+		return Err(anyhow::anyhow!("No PEM certificates found in {}", path));
+	}
+	Ok(certs)
+}
+
+fn load_private_key(path: &str) -> Result<PrivateKeyDer<'static>> {
+	let file = File::open(path).with_context(|| format!("Failed to open {}", path))?;
+	rustls_pemfile::private_key(&mut BufReader::new(file))
+		.with_context(|| format!("Failed to parse private key in {}", path))?
+		.ok_or_else(|| anyhow::anyhow!("No PEM private key found in {}", path))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_tls_disabled_without_paths() {
+		assert!(build_acceptor(&McpTlsConfig::default()).unwrap().is_none());
+	}
+
+	#[test]
+	fn test_tls_requires_cert_and_key_together() {
+		let config = McpTlsConfig {
+			cert_path: Some("server.crt".to_string()),
+			..Default::default()
+		};
+		assert!(build_acceptor(&config).is_err());
+
+		let config = McpTlsConfig {
+			client_ca_path: Some("ca.crt".to_string()),
+			..Default::default()
+		};
+		assert!(build_acceptor(&config).is_err());
+	}
+}
@@ -0,0 +1,294 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Deployment-specific tool customization from the `[mcp.tools]` config section
+//!
+//! Tools can be disabled, exposed under a different name, or given new
+//! descriptions for the tool itself and its parameters. Overrides are keyed
+//! by the built-in tool name; incoming calls are mapped back to it before
+//! dispatch. A `max_tokens` budget caps what a tool may return.
+
+use crate::config::McpConfig;
+use crate::embedding::{count_tokens, truncate_output};
+use crate::mcp::types::McpTool;
+
+/// Apply configured overrides to the built-in tool definitions
+pub fn apply_tool_overrides(tools: Vec<McpTool>, config: &McpConfig) -> Vec<McpTool> {
+	tools
+		.into_iter()
+		.filter_map(|mut tool| {
+			let Some(overrides) = config.tools.get(&tool.name) else {
+				return Some(tool);
+			};
+			if !overrides.enabled {
+				return None;
+			}
+
+			if let Some(ref name) = overrides.name {
+				tool.name = name.clone();
+			}
+			if let Some(ref description) = overrides.description {
+				tool.description = description.clone();
+			}
+			// Advertise the budget as the upper bound of the tool's own limit
+			if let Some(max_tokens) = overrides.max_tokens.filter(|&max| max > 0) {
+				if let Some(property) = tool
+					.input_schema
+					.get_mut("properties")
+					.and_then(|props| props.get_mut("max_tokens"))
+					.and_then(|prop| prop.as_object_mut())
+				{
+					property.insert("maximum".to_string(), max_tokens.into());
+					let default = property
+						.get("default")
+						.and_then(|v| v.as_u64())
+						.map_or(max_tokens, |d| (d as usize).min(max_tokens));
+					property.insert("default".to_string(), default.into());
+				}
+			}
+			for (param, description) in &overrides.parameters {
+				if let Some(property) = tool
+					.input_schema
+					.get_mut("properties")
+					.and_then(|props| props.get_mut(param))
+					.and_then(|prop| prop.as_object_mut())
+				{
+					property.insert(
+						"description".to_string(),
+						serde_json::Value::String(description.clone()),
+					);
+				}
+			}
+			Some(tool)
+		})
+		.collect()
+}
+
+/// Map a tool name sent by the client back to the built-in tool name.
+///
+/// Returns `None` when the requested tool has been disabled.
+pub fn resolve_tool_name<'a>(requested: &'a str, config: &'a McpConfig) -> Option<&'a str> {
+	// Renamed tools are looked up by their exposed name first
+	if let Some((builtin, overrides)) = config
+		.tools
+		.iter()
+		.find(|(_, overrides)| overrides.name.as_deref() == Some(requested))
+	{
+		return overrides.enabled.then_some(builtin.as_str());
+	}
+
+	match config.tools.get(requested) {
+		Some(overrides) if !overrides.enabled => None,
+		_ => Some(requested),
+	}
+}
+
+/// Cut a tool response down to the configured token budget, if any.
+///
+/// Tokens are counted with the same tokenizer as the tools' own `max_tokens`
+/// parameter, so a budget of 3000 holds even when a call asks for 0 (unlimited).
+pub fn enforce_token_budget(builtin: &str, output: String, config: &McpConfig) -> String {
+	match config
+		.tools
+		.get(builtin)
+		.and_then(|overrides| overrides.max_tokens)
+	{
+		Some(max_tokens) if max_tokens > 0 => truncate_output(&output, max_tokens),
+		_ => output,
+	}
+}
+
+/// Token budget shared by the partial results a tool streams ahead of its
+/// response: the smaller of the call's `max_tokens` and the configured cap
+pub struct StreamBudget {
+	remaining: Option<usize>,
+}
+
+impl StreamBudget {
+	/// A `requested` budget of 0 means unlimited, as in the tools' parameter
+	pub fn new(builtin: &str, requested: usize, config: &McpConfig) -> Self {
+		let configured = config
+			.tools
+			.get(builtin)
+			.and_then(|overrides| overrides.max_tokens)
+			.unwrap_or(0);
+		Self {
+			remaining: [requested, configured].into_iter().filter(|&t| t > 0).min(),
+		}
+	}
+
+	/// The message cut to what is left of the budget, or `None` once it is spent
+	pub fn take(&mut self, message: String) -> Option<String> {
+		let Some(remaining) = self.remaining.as_mut() else {
+			return Some(message);
+		};
+		if *remaining == 0 {
+			return None;
+		}
+		let tokens = count_tokens(&message);
+		if tokens <= *remaining {
+			*remaining -= tokens;
+			return Some(message);
+		}
+		let message = truncate_output(&message, *remaining);
+		*remaining = 0;
+		Some(message)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::config::McpToolConfig;
+
+	fn config_with_budget(builtin: &str, max_tokens: usize) -> McpConfig {
+		let mut config = McpConfig::default();
+		config.tools.insert(
+			builtin.to_string(),
+			McpToolConfig {
+				max_tokens: Some(max_tokens),
+				..Default::default()
+			},
+		);
+		config
+	}
+
+	#[test]
+	fn test_tool_overrides() {
+		let tool = |name: &str| McpTool {
+			name: name.to_string(),
+			description: format!("{} description", name),
+			input_schema: serde_json::json!({
+				"type": "object",
+				"properties": {
+					"query": {"type": "string", "description": "What to look for"},
+					"max_tokens": {"type": "integer", "default": 2000}
+				}
+			}),
+		};
+		let mut config = McpConfig::default();
+		config.tools.insert(
+			"semantic_search".to_string(),
+			McpToolConfig {
+				name: Some("code_search".to_string()),
+				description: Some("Search this monorepo".to_string()),
+				parameters: [("query".to_string(), "A service name".to_string())].into(),
+				max_tokens: Some(1500),
+				..Default::default()
+			},
+		);
+		config.tools.insert(
+			"forget".to_string(),
+			McpToolConfig {
+				enabled: false,
+				..Default::default()
+			},
+		);
+
+		let tools = apply_tool_overrides(
+			vec![tool("semantic_search"), tool("forget"), tool("graphrag")],
+			&config,
+		);
+		let names: Vec<&str> = tools.iter().map(|tool| tool.name.as_str()).collect();
+		assert_eq!(names, vec!["code_search", "graphrag"]);
+
+		let search = &tools[0];
+		assert_eq!(search.description, "Search this monorepo");
+		let properties = &search.input_schema["properties"];
+		assert_eq!(properties["query"]["description"], "A service name");
+		assert_eq!(properties["max_tokens"]["maximum"], 1500);
+		assert_eq!(properties["max_tokens"]["default"], 1500);
+		assert_eq!(tools[1].description, "graphrag description");
+
+		// Calls are mapped back to the built-in name; disabled tools are refused
+		assert_eq!(
+			resolve_tool_name("code_search", &config),
+			Some("semantic_search")
+		);
+		assert_eq!(resolve_tool_name("graphrag", &config), Some("graphrag"));
+		assert_eq!(resolve_tool_name("forget", &config), None);
+	}
+
+	#[test]
+	fn test_enforce_token_budget() {
+		let output = (0..200)
+			.map(|i| format!("src/file_{}.rs:{} fn handler_{}()", i, i, i))
+			.collect::<Vec<_>>()
+			.join("\n");
+		let tokens = count_tokens(&output);
+
+		// Cut at the budget, on a line boundary, with the truncation marker
+		let config = config_with_budget("semantic_search", 100);
+		let cut = enforce_token_budget("semantic_search", output.clone(), &config);
+		let (kept, marker) = cut.split_once("\n\n[Output truncated").unwrap();
+		assert!(!kept.is_empty() && count_tokens(kept) < tokens);
+		assert!(output.starts_with(kept));
+		assert!(output[kept.len()..].starts_with('\n'));
+		assert!(marker.contains(&format!("{} tokens estimated, max 100 allowed", tokens)));
+
+		// A result under the budget is returned as is
+		let config = config_with_budget("semantic_search", tokens);
+		assert_eq!(
+			enforce_token_budget("semantic_search", output.clone(), &config),
+			output
+		);
+
+		// Other tools, no budget and a budget of 0 leave the result alone
+		let config = config_with_budget("semantic_search", 100);
+		assert_eq!(
+			enforce_token_budget("graphrag", output.clone(), &config),
+			output
+		);
+		assert_eq!(
+			enforce_token_budget("semantic_search", output.clone(), &McpConfig::default()),
+			output
+		);
+		let config = config_with_budget("semantic_search", 0);
+		assert_eq!(
+			enforce_token_budget("semantic_search", output.clone(), &config),
+			output
+		);
+	}
+
+	#[test]
+	fn test_stream_budget() {
+		let hit = "src/main.rs:1-3 fn main()\n".repeat(4);
+		let hit_tokens = count_tokens(&hit);
+
+		// Unlimited when neither the call nor the config sets a budget
+		let mut budget = StreamBudget::new("semantic_search", 0, &McpConfig::default());
+		for _ in 0..10 {
+			assert_eq!(budget.take(hit.clone()).as_deref(), Some(hit.as_str()));
+		}
+
+		// Hits pass whole while they fit, the one crossing the budget is cut,
+		// and nothing is streamed after it
+		let mut budget = StreamBudget::new(
+			"semantic_search",
+			hit_tokens * 2 + hit_tokens / 2,
+			&McpConfig::default(),
+		);
+		assert_eq!(budget.take(hit.clone()).as_deref(), Some(hit.as_str()));
+		assert_eq!(budget.take(hit.clone()).as_deref(), Some(hit.as_str()));
+		let cut = budget.take(hit.clone()).unwrap();
+		assert!(cut.contains("[Output truncated"));
+		assert_eq!(budget.take(hit.clone()), None);
+
+		// The configured cap wins over a larger requested budget
+		let config = config_with_budget("semantic_search", hit_tokens);
+		let mut budget = StreamBudget::new("semantic_search", 2000, &config);
+		assert_eq!(budget.take(hit.clone()).as_deref(), Some(hit.as_str()));
+		assert_eq!(budget.take(hit), None);
+	}
+}
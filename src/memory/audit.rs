@@ -0,0 +1,254 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Append-only audit log of memory changes
+//!
+//! Every create, update, delete, redaction, archive and restore appends one
+//! JSON line to `memory_audit.jsonl` in the project storage directory. The
+//! log never holds memory content: entries carry a SHA-256 of the title and
+//! content instead, so a change can be verified against an export without the
+//! log itself becoming a copy of redacted data. Nothing in octocode rewrites
+//! or truncates the file, `memory clear-all` included.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use super::git_utils::GitUtils;
+use super::types::Memory;
+
+/// Audit log file name inside the project storage directory
+pub const AUDIT_LOG_FILE: &str = "memory_audit.jsonl";
+
+/// What happened to a memory
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+	Created,
+	Updated,
+	Deleted,
+	Redacted,
+	Imported,
+	Archived,
+	Restored,
+	/// Memories removed in bulk by `memory cleanup` or `memory clear-all`;
+	/// the memory id is `*`
+	Cleared,
+}
+
+impl std::fmt::Display for AuditAction {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let name = match self {
+			AuditAction::Created => "created",
+			AuditAction::Updated => "updated",
+			AuditAction::Deleted => "deleted",
+			AuditAction::Redacted => "redacted",
+			AuditAction::Imported => "imported",
+			AuditAction::Archived => "archived",
+			AuditAction::Restored => "restored",
+			AuditAction::Cleared => "cleared",
+		};
+		write!(f, "{}", name)
+	}
+}
+
+/// One line of the audit log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+	pub timestamp: DateTime<Utc>,
+	pub action: AuditAction,
+	pub memory_id: String,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub memory_type: Option<String>,
+	/// SHA-256 of the title and content after the change
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub content_hash: Option<String>,
+	/// Git `user.email`, or the OS user when git has none
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub actor: Option<String>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub git_commit: Option<String>,
+	/// Free-form context such as a redaction reason
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub details: Option<String>,
+}
+
+impl AuditEntry {
+	pub fn new(action: AuditAction, memory_id: &str) -> Self {
+		Self {
+			timestamp: Utc::now(),
+			action,
+			memory_id: memory_id.to_string(),
+			memory_type: None,
+			content_hash: None,
+			actor: None,
+			git_commit: None,
+			details: None,
+		}
+	}
+
+	/// Entry describing `memory` as it is after the change
+	pub fn for_memory(action: AuditAction, memory: &Memory) -> Self {
+		Self {
+			memory_type: Some(memory.memory_type.to_string()),
+			content_hash: Some(content_hash(memory)),
+			..Self::new(action, &memory.id)
+		}
+	}
+
+	pub fn with_details(mut self, details: impl Into<String>) -> Self {
+		self.details = Some(details.into());
+		self
+	}
+}
+
+/// SHA-256 of a memory's title and content
+pub fn content_hash(memory: &Memory) -> String {
+	let mut hasher = Sha256::new();
+	hasher.update(memory.title.as_bytes());
+	hasher.update([0u8]);
+	hasher.update(memory.content.as_bytes());
+	format!("{:x}", hasher.finalize())
+}
+
+fn current_actor() -> Option<String> {
+	std::process::Command::new("git")
+		.args(["config", "user.email"])
+		.output()
+		.ok()
+		.filter(|output| output.status.success())
+		.and_then(|output| String::from_utf8(output.stdout).ok())
+		.map(|email| email.trim().to_string())
+		.filter(|email| !email.is_empty())
+		.or_else(|| std::env::var("USER").ok())
+		.or_else(|| std::env::var("USERNAME").ok())
+}
+
+/// The audit log of one project's memories
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+	path: PathBuf,
+}
+
+impl AuditLog {
+	/// Audit log kept in a project storage directory
+	pub fn in_dir(storage_dir: &Path) -> Self {
+		Self {
+			path: storage_dir.join(AUDIT_LOG_FILE),
+		}
+	}
+
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	/// Append entries, one JSON line each, stamped with the current actor and commit
+	pub fn append(&self, entries: Vec<AuditEntry>) -> Result<()> {
+		if entries.is_empty() {
+			return Ok(());
+		}
+		let actor = current_actor();
+		let git_commit = GitUtils::get_current_commit();
+
+		let mut lines = String::new();
+		for mut entry in entries {
+			entry.actor = entry.actor.or_else(|| actor.clone());
+			entry.git_commit = entry.git_commit.or_else(|| git_commit.clone());
+			lines.push_str(&serde_json::to_string(&entry)?);
+			lines.push('\n');
+		}
+
+		let mut file = std::fs::OpenOptions::new()
+			.create(true)
+			.append(true)
+			.open(&self.path)?;
+		file.write_all(lines.as_bytes())?;
+		file.sync_data()?;
+		Ok(())
+	}
+
+	/// Entries oldest first, optionally only those of one memory
+	pub fn read(&self, memory_id: Option<&str>) -> Result<Vec<AuditEntry>> {
+		let file = match std::fs::File::open(&self.path) {
+			Ok(file) => file,
+			Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+			Err(e) => return Err(e.into()),
+		};
+
+		let mut entries = Vec::new();
+		for (number, line) in std::io::BufReader::new(file).lines().enumerate() {
+			let line = line?;
+			if line.trim().is_empty() {
+				continue;
+			}
+			let entry: AuditEntry = serde_json::from_str(&line).map_err(|e| {
+				anyhow::anyhow!(
+					"Invalid audit log line {} in {}: {}",
+					number + 1,
+					self.path.display(),
+					e
+				)
+			})?;
+			if memory_id.is_none_or(|id| entry.memory_id == id) {
+				entries.push(entry);
+			}
+		}
+		Ok(entries)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::memory::types::MemoryType;
+
+	#[test]
+	fn test_append_and_read() {
+		let dir = std::env::temp_dir().join(format!("octocode_audit_{}", std::process::id()));
+		let _ = std::fs::remove_dir_all(&dir);
+		std::fs::create_dir_all(&dir).unwrap();
+		let log = AuditLog::in_dir(&dir);
+		assert!(log.read(None).unwrap().is_empty());
+
+		let memory = Memory::new(
+			MemoryType::Decision,
+			"Use LanceDB".to_string(),
+			"Embedded, no server".to_string(),
+			None,
+		);
+		log.append(vec![AuditEntry::for_memory(AuditAction::Created, &memory)])
+			.unwrap();
+		log.append(vec![
+			AuditEntry::new(AuditAction::Deleted, "other"),
+			AuditEntry::for_memory(AuditAction::Redacted, &memory).with_details("PII"),
+		])
+		.unwrap();
+
+		let entries = log.read(Some(&memory.id)).unwrap();
+		assert_eq!(entries.len(), 2);
+		assert_eq!(entries[1].action, AuditAction::Redacted);
+		assert_eq!(entries[1].details.as_deref(), Some("PII"));
+		assert_eq!(entries[0].content_hash, Some(content_hash(&memory)));
+
+		// The log holds no memory content
+		let raw = std::fs::read_to_string(log.path()).unwrap();
+		assert!(!raw.contains("LanceDB"));
+		assert_eq!(log.read(None).unwrap().len(), 3);
+
+		let _ = std::fs::remove_dir_all(&dir);
+	}
+}
@@ -14,7 +14,7 @@
 
 // Shared memory formatting functions for CLI and MCP
 
-use crate::memory::MemorySearchResult;
+use crate::memory::{MemorySearchResult, TimelineGroup, DEFAULT_NAMESPACE};
 
 /// Format memory search results as text (token-efficient, for MCP)
 pub fn format_memories_as_text(results: &[MemorySearchResult]) -> String {
@@ -58,6 +58,10 @@ pub fn format_memories_as_text(results: &[MemorySearchResult]) -> String {
 			output.push_str(&format!("Git: {}\n", git_commit));
 		}
 
+		if result.memory.namespace != DEFAULT_NAMESPACE {
+			output.push_str(&format!("Namespace: {}\n", result.memory.namespace));
+		}
+
 		output.push_str(&format!("ID: {}\n", result.memory.id));
 
 		// Add content as-is without any modification
@@ -235,3 +239,52 @@ pub fn format_memories_for_cli(results: &[MemorySearchResult], format: &str) {
 		}
 	}
 }
+
+/// Format a memory timeline as text, one heading per period (for CLI and MCP)
+pub fn format_timeline_as_text(groups: &[TimelineGroup], compact: bool) -> String {
+	if groups.is_empty() {
+		return "No memories recorded in the selected range.".to_string();
+	}
+
+	let total: usize = groups.iter().map(|group| group.memories.len()).sum();
+	let mut output = format!(
+		"MEMORY TIMELINE ({} memories, {} periods)\n",
+		total,
+		groups.len()
+	);
+
+	for group in groups {
+		output.push_str(&format!(
+			"\n{} ({} memories)\n",
+			group.period,
+			group.memories.len()
+		));
+		if let Some(digest) = &group.digest {
+			output.push_str(digest.trim_end());
+			output.push_str("\n\n");
+		}
+		for memory in &group.memories {
+			output.push_str(&format!(
+				"  {} [{}] {} - {}\n",
+				memory.created_at.format("%Y-%m-%d %H:%M"),
+				memory.memory_type,
+				memory.title,
+				memory.id
+			));
+			if compact {
+				continue;
+			}
+			if !memory.metadata.tags.is_empty() {
+				output.push_str(&format!("    Tags: {}\n", memory.metadata.tags.join(", ")));
+			}
+			if !memory.metadata.related_files.is_empty() {
+				output.push_str(&format!(
+					"    Files: {}\n",
+					memory.metadata.related_files.join(", ")
+				));
+			}
+		}
+	}
+
+	output
+}
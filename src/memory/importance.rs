@@ -0,0 +1,89 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Effective importance of memories
+//!
+//! The importance a memory was stored with is only a starting point: memories
+//! that `remember` keeps returning become more important, and ones nobody
+//! retrieves fade. The effective importance ranks search results and picks
+//! the memories archived when the store outgrows `memory.max_memories`.
+
+use chrono::{DateTime, Utc};
+
+use super::types::Memory;
+
+const BASE_WEIGHT: f32 = 0.5;
+const FREQUENCY_WEIGHT: f32 = 0.3;
+const RECENCY_WEIGHT: f32 = 0.2;
+
+/// Retrievals at which the frequency part reaches half its weight
+const FREQUENCY_HALF_POINT: f32 = 5.0;
+
+/// Effective importance between 0.0 and 1.0 from the stored importance, how
+/// often the memory was retrieved and how long ago (or created, if never)
+pub fn effective_importance(memory: &Memory, now: DateTime<Utc>, half_life_days: f32) -> f32 {
+	let count = memory.metadata.access_count as f32;
+	let frequency = count / (count + FREQUENCY_HALF_POINT);
+
+	let last_used = memory.metadata.last_accessed.unwrap_or(memory.created_at);
+	let age_days = (now - last_used).num_seconds().max(0) as f32 / 86_400.0;
+	let recency = if half_life_days > 0.0 {
+		0.5f32.powf(age_days / half_life_days)
+	} else {
+		0.0
+	};
+
+	(BASE_WEIGHT * memory.metadata.importance.clamp(0.0, 1.0)
+		+ FREQUENCY_WEIGHT * frequency
+		+ RECENCY_WEIGHT * recency)
+		.clamp(0.0, 1.0)
+}
+
+/// Search ranking score: relevance, scaled by up to ±25% for effective importance
+pub fn rank_score(relevance: f32, effective_importance: f32) -> f32 {
+	relevance * (0.75 + 0.5 * effective_importance)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::memory::types::{MemoryMetadata, MemoryType};
+	use chrono::Duration;
+
+	#[test]
+	fn test_access_and_recency_raise_importance() {
+		let now = Utc::now();
+		let mut memory = Memory::new(
+			MemoryType::Insight,
+			"title".to_string(),
+			"content".to_string(),
+			Some(MemoryMetadata {
+				importance: 0.5,
+				..Default::default()
+			}),
+		);
+		memory.created_at = now - Duration::days(90);
+		let stale = effective_importance(&memory, now, 30.0);
+		// Base 0.25 plus an eighth of the recency weight after three half-lives
+		assert!((stale - 0.275).abs() < 1e-3);
+
+		memory.metadata.access_count = 5;
+		memory.metadata.last_accessed = Some(now);
+		let used = effective_importance(&memory, now, 30.0);
+		assert!((used - 0.6).abs() < 1e-3);
+
+		assert!(rank_score(0.8, used) > rank_score(0.8, stale));
+		assert!(rank_score(0.9, stale) > rank_score(0.6, used));
+	}
+}
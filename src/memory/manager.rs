@@ -15,62 +15,102 @@
 use anyhow::Result;
 use chrono::Utc;
 
+use super::audit::{AuditAction, AuditEntry, AuditLog};
 use super::git_utils::GitUtils;
+use super::importance::{effective_importance, rank_score};
 use super::store::MemoryStore;
+use super::timeline::{group_by_period, TimelineFilter, TimelineGroup, TimelinePeriod};
+use super::transfer::{ExportedMemory, MemoryExport, EXPORT_VERSION};
 use super::types::{
-	Memory, MemoryConfig, MemoryMetadata, MemoryQuery, MemoryRelationship, MemorySearchResult,
-	MemoryType, RelationshipType,
+	validate_namespace, Memory, MemoryConfig, MemoryMetadata, MemoryQuery, MemoryRelationship,
+	MemorySearchResult, MemoryType, RelationshipType,
 };
-use crate::config::Config;
-use crate::embedding::{create_embedding_provider_from_parts, parse_provider_model};
+use crate::config::{Config, DuplicatePolicy, MemoryRetentionConfig};
+use crate::embedding::{
+	create_embedding_provider_from_parts, parse_provider_model, EmbeddingProvider,
+};
+
+/// Title of redacted memories
+pub const REDACTED_TITLE: &str = "[REDACTED]";
+
+/// Tag redacted memories carry instead of their own
+pub const REDACTED_TAG: &str = "redacted";
+
+/// What memorizing stored
+#[derive(Debug, Clone)]
+pub enum Memorized {
+	Created(Memory),
+	/// A duplicate, merged into the existing memory it is this close to
+	Merged {
+		memory: Memory,
+		distance: f32,
+	},
+}
+
+impl Memorized {
+	pub fn memory(&self) -> &Memory {
+		match self {
+			Memorized::Created(memory) | Memorized::Merged { memory, .. } => memory,
+		}
+	}
+}
 
 /// High-level memory management interface
 pub struct MemoryManager {
 	store: MemoryStore,
 	config: MemoryConfig,
+	retention: MemoryRetentionConfig,
+	/// Append-only log of changes, next to the database
+	audit: Option<AuditLog>,
+	/// Searches leave the store untouched, retrievals are not counted
+	read_only: bool,
+}
+
+/// Store size at which adding a memory archives down to `max_memories`.
+/// Archiving reads every memory, so the store may outgrow the limit by a
+/// tenth before it runs rather than on every addition.
+fn prune_threshold(max_memories: usize) -> usize {
+	max_memories + (max_memories / 10).max(1)
 }
 
 impl MemoryManager {
 	/// Create a new memory manager
 	pub async fn new(config: &Config) -> Result<Self> {
-		let memory_config = MemoryConfig::default();
+		Self::with_config(config, MemoryConfig::default()).await
+	}
 
+	/// Create a new memory manager with custom config
+	pub async fn with_config(config: &Config, memory_config: MemoryConfig) -> Result<Self> {
 		// Use the same storage system as the main application
 		let current_dir = std::env::current_dir()?;
 		let db_path = crate::storage::get_project_database_path(&current_dir)?;
-		let db_path_str = db_path.to_string_lossy().to_string();
+		Self::open(config, memory_config, &db_path).await
+	}
 
+	/// Create a memory manager backed by an explicit database directory
+	pub async fn open(
+		config: &Config,
+		memory_config: MemoryConfig,
+		db_path: &std::path::Path,
+	) -> Result<Self> {
 		// Create embedding provider using text model from config
 		let model_string = &config.embedding.text_model;
 		let (provider, model) = parse_provider_model(model_string);
 		let embedding_provider = create_embedding_provider_from_parts(&provider, &model)?;
 
-		let store = MemoryStore::new(
-			&db_path_str,
-			embedding_provider,
-			memory_config.clone(),
-			config.clone(),
-		)
-		.await?;
-
-		Ok(Self {
-			store,
-			config: memory_config,
-		})
+		Self::open_with_provider(config, memory_config, db_path, embedding_provider).await
 	}
 
-	/// Create a new memory manager with custom config
-	pub async fn with_config(config: &Config, memory_config: MemoryConfig) -> Result<Self> {
-		// Use the same storage system as the main application
-		let current_dir = std::env::current_dir()?;
-		let db_path = crate::storage::get_project_database_path(&current_dir)?;
+	/// Like [`Self::open`], embedding with the given provider instead of the
+	/// configured text model
+	pub async fn open_with_provider(
+		config: &Config,
+		memory_config: MemoryConfig,
+		db_path: &std::path::Path,
+		embedding_provider: Box<dyn EmbeddingProvider>,
+	) -> Result<Self> {
 		let db_path_str = db_path.to_string_lossy().to_string();
 
-		// Create embedding provider using text model from config
-		let model_string = &config.embedding.text_model;
-		let (provider, model) = parse_provider_model(model_string);
-		let embedding_provider = create_embedding_provider_from_parts(&provider, &model)?;
-
 		let store = MemoryStore::new(
 			&db_path_str,
 			embedding_provider,
@@ -82,10 +122,41 @@ impl MemoryManager {
 		Ok(Self {
 			store,
 			config: memory_config,
+			retention: config.memory.clone(),
+			audit: db_path.parent().map(AuditLog::in_dir),
+			read_only: false,
 		})
 	}
 
+	/// For servers that must not change the store: searches no longer count
+	/// retrievals of the memories they return
+	pub fn with_read_only(mut self, read_only: bool) -> Self {
+		self.read_only = read_only;
+		self
+	}
+
+	/// Record changes in the audit log
+	fn audit(&self, entries: Vec<AuditEntry>) -> Result<()> {
+		match &self.audit {
+			Some(log) => log.append(entries),
+			None => Ok(()),
+		}
+	}
+
+	/// Audit log entries, oldest first, optionally only those of one memory
+	pub fn audit_log(&self, memory_id: Option<&str>) -> Result<Vec<AuditEntry>> {
+		match &self.audit {
+			Some(log) => log.read(memory_id),
+			None => Ok(Vec::new()),
+		}
+	}
+
 	/// Memorize new information with automatic Git context
+	///
+	/// A memory within `memory.duplicate_distance` of one already in the
+	/// namespace is merged into it or rejected, per `memory.on_duplicate`,
+	/// unless `force` is set.
+	#[allow(clippy::too_many_arguments)]
 	pub async fn memorize(
 		&mut self,
 		memory_type: MemoryType,
@@ -94,7 +165,11 @@ impl MemoryManager {
 		importance: Option<f32>,
 		tags: Option<Vec<String>>,
 		related_files: Option<Vec<String>>,
-	) -> Result<Memory> {
+		namespace: &str,
+		force: bool,
+	) -> Result<Memorized> {
+		validate_namespace(namespace)?;
+
 		// Initialize metadata with all values at once to satisfy clippy
 		let mut metadata = MemoryMetadata {
 			git_commit: GitUtils::get_current_commit(),
@@ -120,17 +195,53 @@ impl MemoryManager {
 			}
 		}
 
-		let memory = Memory::new(memory_type, title, content, Some(metadata));
+		let mut memory = Memory::new(memory_type, title, content, Some(metadata));
+		memory.namespace = namespace.to_string();
+
+		let embedding = self.store.memory_embedding(&memory).await?;
+		if !force && self.retention.duplicate_distance > 0.0 {
+			if let Some((existing, distance)) = self
+				.store
+				.find_duplicate(&embedding, namespace, self.retention.duplicate_distance)
+				.await?
+			{
+				let merged = merge_duplicate(
+					self.retention.on_duplicate,
+					existing,
+					distance,
+					&memory.metadata,
+				)?;
+				self.store.update_memory(merged.memory()).await?;
+				self.audit(vec![AuditEntry::for_memory(
+					AuditAction::Updated,
+					merged.memory(),
+				)])?;
+				return Ok(merged);
+			}
+		}
 
 		// Store the memory
-		self.store.store_memory(&memory).await?;
+		self.store
+			.store_memory_with_embedding(&memory, embedding)
+			.await?;
+		self.audit(vec![AuditEntry::for_memory(AuditAction::Created, &memory)])?;
 
 		// Auto-create relationships if enabled
 		if self.config.auto_relationships {
 			self.create_automatic_relationships(&memory).await?;
 		}
 
-		Ok(memory)
+		let max_memories = self.retention.max_memories;
+		if max_memories > 0 {
+			let count = self.store.get_memory_count().await.unwrap_or(0);
+			if count >= prune_threshold(max_memories) {
+				if let Err(e) = self.prune().await {
+					tracing::warn!("Failed to prune memories: {}", e);
+				}
+			}
+		}
+
+		Ok(Memorized::Created(memory))
 	}
 
 	/// Remember (search) memories based on query
@@ -142,7 +253,9 @@ impl MemoryManager {
 		let mut search_query = filters.unwrap_or_default();
 		search_query.query_text = Some(query.to_string());
 
-		self.store.search_memories(&search_query).await
+		let results = self.store.search_memories(&search_query).await?;
+		self.record_access(&results).await;
+		Ok(results)
 	}
 
 	/// Remember (search) memories based on multiple queries with relevance-based merging
@@ -217,10 +330,17 @@ impl MemoryManager {
 			})
 			.collect();
 
-		// Sort by relevance score (highest first)
+		// Sort by relevance weighted by effective importance (highest first)
+		let now = Utc::now();
+		let score = |result: &MemorySearchResult| {
+			rank_score(
+				result.relevance_score,
+				effective_importance(&result.memory, now, self.retention.recency_half_life_days),
+			)
+		};
 		final_results.sort_by(|a, b| {
-			b.relevance_score
-				.partial_cmp(&a.relevance_score)
+			score(b)
+				.partial_cmp(&score(a))
 				.unwrap_or(std::cmp::Ordering::Equal)
 		});
 
@@ -229,9 +349,24 @@ impl MemoryManager {
 			final_results.truncate(limit);
 		}
 
+		self.record_access(&final_results).await;
 		Ok(final_results)
 	}
 
+	/// Count a retrieval of every returned memory; failing to do so never fails the search
+	async fn record_access(&self, results: &[MemorySearchResult]) {
+		if self.read_only {
+			return;
+		}
+		let ids: Vec<String> = results
+			.iter()
+			.map(|result| result.memory.id.clone())
+			.collect();
+		if let Err(e) = self.store.record_access(&ids).await {
+			tracing::warn!("Failed to record memory access: {}", e);
+		}
+	}
+
 	/// Remember memories with advanced filtering
 	pub async fn remember_advanced(&self, query: MemoryQuery) -> Result<Vec<MemorySearchResult>> {
 		self.store.search_memories(&query).await
@@ -239,7 +374,8 @@ impl MemoryManager {
 
 	/// Forget (delete) a memory by ID
 	pub async fn forget(&mut self, memory_id: &str) -> Result<()> {
-		self.store.delete_memory(memory_id).await
+		self.store.delete_memory(memory_id).await?;
+		self.audit(vec![AuditEntry::new(AuditAction::Deleted, memory_id)])
 	}
 
 	/// Forget memories matching criteria
@@ -247,11 +383,15 @@ impl MemoryManager {
 		let search_results = self.store.search_memories(&query).await?;
 		let mut deleted_count = 0;
 
+		let mut entries = Vec::new();
+
 		for result in search_results {
 			self.store.delete_memory(&result.memory.id).await?;
+			entries.push(AuditEntry::new(AuditAction::Deleted, &result.memory.id));
 			deleted_count += 1;
 		}
 
+		self.audit(entries)?;
 		Ok(deleted_count)
 	}
 
@@ -277,6 +417,7 @@ impl MemoryManager {
 			}
 
 			self.store.update_memory(&memory).await?;
+			self.audit(vec![AuditEntry::for_memory(AuditAction::Updated, &memory)])?;
 
 			// Update relationships if auto-relationships is enabled
 			if self.config.auto_relationships {
@@ -294,9 +435,14 @@ impl MemoryManager {
 		self.store.get_memory(memory_id).await
 	}
 
-	/// Get recent memories
-	pub async fn get_recent_memories(&self, limit: usize) -> Result<Vec<Memory>> {
+	/// Get recent memories, of one namespace or all of them
+	pub async fn get_recent_memories(
+		&self,
+		limit: usize,
+		namespace: Option<&str>,
+	) -> Result<Vec<Memory>> {
 		let query = MemoryQuery {
+			namespaces: namespace.map(|namespace| vec![namespace.to_string()]),
 			limit: Some(limit),
 			sort_by: Some(super::types::MemorySortBy::CreatedAt),
 			sort_order: Some(super::types::SortOrder::Descending),
@@ -307,13 +453,15 @@ impl MemoryManager {
 		Ok(results.into_iter().map(|r| r.memory).collect())
 	}
 
-	/// Get memories by type
+	/// Get memories by type, of one namespace or all of them
 	pub async fn get_memories_by_type(
 		&self,
 		memory_type: MemoryType,
 		limit: Option<usize>,
+		namespace: Option<&str>,
 	) -> Result<Vec<Memory>> {
 		let query = MemoryQuery {
+			namespaces: namespace.map(|namespace| vec![namespace.to_string()]),
 			memory_types: Some(vec![memory_type]),
 			limit,
 			sort_by: Some(super::types::MemorySortBy::CreatedAt),
@@ -380,7 +528,7 @@ impl MemoryManager {
 		let total_count = self.store.get_memory_count().await?;
 
 		// Get count by type (simplified - would need custom queries for exact counts)
-		let recent_memories = self.get_recent_memories(100).await?;
+		let recent_memories = self.get_recent_memories(100, None).await?;
 		let mut type_counts = std::collections::HashMap::new();
 
 		for memory in &recent_memories {
@@ -391,6 +539,7 @@ impl MemoryManager {
 
 		Ok(MemoryStats {
 			total_memories: total_count,
+			archived_memories: self.store.get_archived_count().await?,
 			type_counts,
 			recent_count: recent_memories.len().min(10),
 			git_commit: GitUtils::get_current_commit(),
@@ -445,21 +594,223 @@ impl MemoryManager {
 		Ok(related_memories)
 	}
 
+	/// Export all memories and relationships, optionally with their embeddings
+	pub async fn export(&self, include_embeddings: bool) -> Result<MemoryExport> {
+		let count = self.store.get_memory_count().await?;
+		let mut memories = self.store.get_all_memories(0, count).await?;
+		// Oldest first so exports diff cleanly as memories are added
+		memories.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+
+		let mut embeddings = if include_embeddings {
+			self.store.get_memory_embeddings().await?
+		} else {
+			Default::default()
+		};
+		let memories = memories
+			.into_iter()
+			.map(|memory| {
+				let embedding = embeddings.remove(&memory.id);
+				ExportedMemory { memory, embedding }
+			})
+			.collect();
+
+		let mut relationships = self.store.get_all_relationships().await?;
+		relationships.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+
+		Ok(MemoryExport {
+			version: EXPORT_VERSION,
+			exported_at: Utc::now(),
+			embedding_model: include_embeddings.then(|| self.store.embedding_model().to_string()),
+			memories,
+			relationships,
+		})
+	}
+
+	/// Import exported memories and relationships
+	///
+	/// Memories keep their ids, so importing the same file twice overwrites
+	/// instead of duplicating. Exported embeddings are reused only when they
+	/// were computed with the configured model and dimension, unless `reembed`
+	/// is set; everything else is embedded again.
+	pub async fn import(&mut self, export: MemoryExport, reembed: bool) -> Result<ImportSummary> {
+		let reuse =
+			!reembed && export.embedding_model.as_deref() == Some(self.store.embedding_model());
+		let vector_dim = self.store.vector_dim();
+
+		let mut summary = ImportSummary::default();
+		let mut imported_ids = std::collections::HashSet::new();
+		let mut to_embed = Vec::new();
+		for exported in export.memories {
+			imported_ids.insert(exported.memory.id.clone());
+			match exported.embedding {
+				Some(embedding) if reuse && embedding.len() == vector_dim => {
+					self.store
+						.store_memory_with_embedding(&exported.memory, embedding)
+						.await?;
+					summary.reused_embeddings += 1;
+				}
+				_ => to_embed.push(exported.memory),
+			}
+		}
+		self.store.store_memories(&to_embed).await?;
+		summary.memories = imported_ids.len();
+		self.audit(
+			imported_ids
+				.iter()
+				.map(|id| AuditEntry::new(AuditAction::Imported, id))
+				.collect(),
+		)?;
+		summary.archived = self.prune().await?;
+
+		// Relationships need both ends, either imported now or already stored
+		for relationship in export.relationships {
+			let mut connected = true;
+			for id in [&relationship.source_id, &relationship.target_id] {
+				if !imported_ids.contains(id) && self.store.get_memory(id).await?.is_none() {
+					connected = false;
+				}
+			}
+			if connected {
+				self.store.store_relationship(&relationship).await?;
+				summary.relationships += 1;
+			} else {
+				summary.skipped_relationships += 1;
+			}
+		}
+
+		Ok(summary)
+	}
+
+	/// Memories matching `filter`, oldest first, grouped by day or week
+	pub async fn timeline(
+		&self,
+		filter: &TimelineFilter,
+		period: TimelinePeriod,
+	) -> Result<Vec<TimelineGroup>> {
+		let count = self.store.get_memory_count().await?;
+		let mut memories: Vec<Memory> = self
+			.store
+			.get_all_memories(0, count)
+			.await?
+			.into_iter()
+			.filter(|memory| filter.matches(memory))
+			.collect();
+		memories.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+		Ok(group_by_period(memories, period))
+	}
+
+	/// Archive rarely used memories while the store holds more than
+	/// `memory.max_memories`
+	///
+	/// Only memories whose effective importance is below
+	/// `memory.archive_below_importance` are archived, least important first,
+	/// so the store may stay above the limit. Returns the number archived.
+	pub async fn prune(&mut self) -> Result<usize> {
+		let max_memories = self.retention.max_memories;
+		if max_memories == 0 {
+			return Ok(0);
+		}
+		let count = self.store.get_memory_count().await?;
+		if count <= max_memories {
+			return Ok(0);
+		}
+
+		let now = Utc::now();
+		let mut candidates: Vec<(f32, String)> = self
+			.store
+			.get_all_memories(0, count)
+			.await?
+			.into_iter()
+			.map(|memory| {
+				let importance =
+					effective_importance(&memory, now, self.retention.recency_half_life_days);
+				(importance, memory.id)
+			})
+			.filter(|(importance, _)| *importance < self.retention.archive_below_importance)
+			.collect();
+		candidates.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+		candidates.truncate(count - max_memories);
+
+		let ids: Vec<String> = candidates.into_iter().map(|(_, id)| id).collect();
+		let archived = self.store.archive_memories(&ids).await?;
+		self.audit(
+			ids.iter()
+				.map(|id| AuditEntry::new(AuditAction::Archived, id))
+				.collect(),
+		)?;
+		Ok(archived)
+	}
+
+	/// Bring an archived memory back; false if no archived memory has this id
+	pub async fn restore(&mut self, memory_id: &str) -> Result<bool> {
+		if !self.store.restore_memory(memory_id).await? {
+			return Ok(false);
+		}
+		// Restoring counts as a use, so the next prune does not archive it again
+		self.store.record_access(&[memory_id.to_string()]).await?;
+		self.audit(vec![AuditEntry::new(AuditAction::Restored, memory_id)])?;
+		Ok(true)
+	}
+
 	/// Clean up old memories
 	pub async fn cleanup(&mut self) -> Result<usize> {
-		self.store.cleanup_old_memories().await
+		let cleaned = self.store.cleanup_old_memories().await?;
+		if cleaned > 0 {
+			self.audit(vec![AuditEntry::new(AuditAction::Cleared, "*")
+				.with_details(format!("cleanup removed {} old memories", cleaned))])?;
+		}
+		Ok(cleaned)
 	}
 
 	/// Clear all memory data (DANGEROUS: deletes all memories and relationships)
 	pub async fn clear_all(&mut self) -> Result<usize> {
-		self.store.clear_all_memory_data().await
+		let deleted = self.store.clear_all_memory_data().await?;
+		self.audit(vec![AuditEntry::new(AuditAction::Cleared, "*")
+			.with_details(format!("clear-all deleted {} records", deleted))])?;
+		Ok(deleted)
+	}
+
+	/// Scrub a memory's title, content, tags and files, keeping a tombstone
+	///
+	/// The tombstone keeps the id, type, dates and relationships, so links
+	/// to it stay valid; it is re-embedded from the tombstone text and the
+	/// table's old versions are pruned, so the original text and vector do
+	/// not remain on disk. Returns None when no memory has this id.
+	pub async fn redact(
+		&mut self,
+		memory_id: &str,
+		reason: Option<String>,
+	) -> Result<Option<Memory>> {
+		let Some(mut memory) = self.store.get_memory(memory_id).await? else {
+			return Ok(None);
+		};
+
+		let now = Utc::now();
+		memory.title = REDACTED_TITLE.to_string();
+		memory.content = format!("Content redacted on {}.", now.format("%Y-%m-%d"));
+		memory.metadata.tags = vec![REDACTED_TAG.to_string()];
+		memory.metadata.related_files.clear();
+		memory.metadata.created_by = None;
+		memory.metadata.custom_fields.clear();
+		memory.updated_at = now;
+		self.store.update_memory(&memory).await?;
+
+		let mut entry = AuditEntry::for_memory(AuditAction::Redacted, &memory);
+		if let Some(reason) = reason {
+			entry = entry.with_details(reason);
+		}
+		self.audit(vec![entry])?;
+
+		self.store.purge_history().await?;
+		Ok(Some(memory))
 	}
 
 	/// Auto-create relationships for a new memory
 	async fn create_automatic_relationships(&mut self, memory: &Memory) -> Result<()> {
-		// Find similar memories based on content similarity
+		// Find similar memories of the same namespace based on content similarity
 		let similar_query = MemoryQuery {
 			query_text: Some(memory.get_searchable_text()),
+			namespaces: Some(vec![memory.namespace.clone()]),
 			memory_types: Some(vec![memory.memory_type.clone()]),
 			limit: Some(5),
 			min_relevance: Some(self.config.relationship_threshold),
@@ -497,6 +848,7 @@ impl MemoryManager {
 		if !memory.metadata.related_files.is_empty() {
 			let file_query = MemoryQuery {
 				related_files: Some(memory.metadata.related_files.clone()),
+				namespaces: Some(vec![memory.namespace.clone()]),
 				limit: Some(10),
 				..Default::default()
 			};
@@ -542,6 +894,7 @@ impl MemoryManager {
 		if let Some(mut memory) = self.store.get_memory(memory_id).await? {
 			memory.add_tag(tag);
 			self.store.update_memory(&memory).await?;
+			self.audit(vec![AuditEntry::for_memory(AuditAction::Updated, &memory)])?;
 			Ok(true)
 		} else {
 			Ok(false)
@@ -553,6 +906,7 @@ impl MemoryManager {
 		if let Some(mut memory) = self.store.get_memory(memory_id).await? {
 			memory.remove_tag(tag);
 			self.store.update_memory(&memory).await?;
+			self.audit(vec![AuditEntry::for_memory(AuditAction::Updated, &memory)])?;
 			Ok(true)
 		} else {
 			Ok(false)
@@ -565,6 +919,7 @@ impl MemoryManager {
 			let relative_path = GitUtils::get_relative_path(&file_path).unwrap_or(file_path);
 			memory.add_related_file(relative_path);
 			self.store.update_memory(&memory).await?;
+			self.audit(vec![AuditEntry::for_memory(AuditAction::Updated, &memory)])?;
 			Ok(true)
 		} else {
 			Ok(false)
@@ -576,6 +931,7 @@ impl MemoryManager {
 		if let Some(mut memory) = self.store.get_memory(memory_id).await? {
 			memory.remove_related_file(file_path);
 			self.store.update_memory(&memory).await?;
+			self.audit(vec![AuditEntry::for_memory(AuditAction::Updated, &memory)])?;
 			Ok(true)
 		} else {
 			Ok(false)
@@ -583,10 +939,24 @@ impl MemoryManager {
 	}
 }
 
+/// Result of a memory import
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct ImportSummary {
+	pub memories: usize,
+	/// Memories stored with their exported embedding instead of re-embedding
+	pub reused_embeddings: usize,
+	pub relationships: usize,
+	/// Relationships dropped because one of their memories does not exist
+	pub skipped_relationships: usize,
+	/// Memories archived because the import took the store past `max_memories`
+	pub archived: usize,
+}
+
 /// Memory statistics
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct MemoryStats {
 	pub total_memories: usize,
+	pub archived_memories: usize,
 	pub type_counts: std::collections::HashMap<String, usize>,
 	pub recent_count: usize,
 	pub git_commit: Option<String>,
@@ -597,6 +967,12 @@ impl MemoryStats {
 	pub fn format(&self) -> String {
 		let mut output = "Memory Statistics:\n".to_string();
 		output.push_str(&format!("  Total memories: {}\n", self.total_memories));
+		if self.archived_memories > 0 {
+			output.push_str(&format!(
+				"  Archived memories: {}\n",
+				self.archived_memories
+			));
+		}
 		output.push_str(&format!("  Recent memories: {}\n", self.recent_count));
 
 		if let Some(ref commit) = self.git_commit {
@@ -613,3 +989,83 @@ impl MemoryStats {
 		output
 	}
 }
+
+/// Fold a new memory's metadata into the existing memory it duplicates, or
+/// reject it, per `memory.on_duplicate`
+fn merge_duplicate(
+	policy: DuplicatePolicy,
+	mut existing: Memory,
+	distance: f32,
+	duplicate: &MemoryMetadata,
+) -> Result<Memorized> {
+	if policy == DuplicatePolicy::Reject {
+		return Err(anyhow::anyhow!(
+			"Memory duplicates {} '{}' (distance {:.3}); store it anyway with force",
+			existing.id,
+			existing.title,
+			distance
+		));
+	}
+	existing.absorb(duplicate);
+	Ok(Memorized::Merged {
+		memory: existing,
+		distance,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_prune_threshold() {
+		assert_eq!(prune_threshold(10000), 11000);
+		// Small stores still get room for one addition before archiving
+		assert_eq!(prune_threshold(5), 6);
+	}
+
+	#[test]
+	fn test_merge_duplicate() {
+		let existing = Memory::new(
+			MemoryType::Architecture,
+			"Store layout".to_string(),
+			"One table per block kind".to_string(),
+			Some(MemoryMetadata {
+				tags: vec!["store".to_string()],
+				related_files: vec!["src/store/mod.rs".to_string()],
+				importance: 0.4,
+				git_commit: Some("abc123".to_string()),
+				..Default::default()
+			}),
+		);
+		let duplicate = MemoryMetadata {
+			tags: vec!["store".to_string(), "lance".to_string()],
+			related_files: vec!["src/store/table_ops.rs".to_string()],
+			importance: 0.8,
+			git_commit: Some("def456".to_string()),
+			..Default::default()
+		};
+
+		let merged =
+			merge_duplicate(DuplicatePolicy::Merge, existing.clone(), 0.05, &duplicate).unwrap();
+		let Memorized::Merged { memory, distance } = merged else {
+			panic!("duplicate should be merged");
+		};
+		assert_eq!(distance, 0.05);
+		assert_eq!(memory.id, existing.id);
+		assert_eq!(memory.content, existing.content);
+		assert_eq!(memory.metadata.tags, vec!["store", "lance"]);
+		assert_eq!(
+			memory.metadata.related_files,
+			vec!["src/store/mod.rs", "src/store/table_ops.rs"]
+		);
+		assert_eq!(memory.metadata.importance, 0.8);
+		assert_eq!(memory.metadata.git_commit.as_deref(), Some("def456"));
+
+		let rejected = merge_duplicate(DuplicatePolicy::Reject, existing.clone(), 0.05, &duplicate)
+			.unwrap_err()
+			.to_string();
+		assert!(rejected.contains(&existing.id), "{}", rejected);
+		assert!(rejected.contains("force"), "{}", rejected);
+	}
+}
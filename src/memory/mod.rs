@@ -15,18 +15,25 @@
 // Memory module for AI context and conversation state management
 // Uses LanceDB for vector storage and semantic search capabilities
 
+pub mod audit;
 pub mod formatting;
 pub mod git_utils;
+pub mod importance;
 pub mod manager;
 pub mod store;
+pub mod timeline;
+pub mod transfer;
 pub mod types;
 
 // Re-export the main types and interfaces
+pub use audit::{AuditAction, AuditEntry, AuditLog};
 pub use formatting::{format_memories_as_text, format_memories_for_cli};
 pub use git_utils::{CommitInfo, GitUtils};
-pub use manager::{MemoryManager, MemoryStats};
+pub use manager::{ImportSummary, Memorized, MemoryManager, MemoryStats};
 pub use store::MemoryStore;
+pub use timeline::{TimelineFilter, TimelineGroup, TimelinePeriod};
+pub use transfer::{ExportFormat, MemoryExport};
 pub use types::{
-	Memory, MemoryConfig, MemoryMetadata, MemoryQuery, MemoryRelationship, MemorySearchResult,
-	MemorySortBy, MemoryType, RelationshipType, SortOrder,
+	validate_namespace, Memory, MemoryConfig, MemoryMetadata, MemoryQuery, MemoryRelationship,
+	MemorySearchResult, MemorySortBy, MemoryType, RelationshipType, SortOrder, DEFAULT_NAMESPACE,
 };
@@ -17,7 +17,7 @@ use chrono::Utc;
 use std::sync::Arc;
 
 // Arrow imports
-use arrow::array::{Array, FixedSizeListArray, Float32Array, StringArray};
+use arrow::array::{Array, FixedSizeListArray, Float32Array, StringArray, UInt32Array};
 use arrow::datatypes::{DataType, Field, Schema};
 use arrow::record_batch::RecordBatch;
 
@@ -27,10 +27,55 @@ use lancedb::{
 	connect,
 	index::Index,
 	query::{ExecutableQuery, QueryBase},
+	table::{NewColumnTransform, OptimizeAction},
 	Connection, DistanceType,
 };
 
-use super::types::{Memory, MemoryConfig, MemoryQuery, MemoryRelationship, MemorySearchResult};
+use super::types::{
+	Memory, MemoryConfig, MemoryQuery, MemoryRelationship, MemorySearchResult, DEFAULT_NAMESPACE,
+};
+use crate::indexer::path_aliases::PathAliases;
+
+/// Table memories are moved to when pruned, with their embeddings
+const ARCHIVE_TABLE: &str = "memories_archive";
+
+/// Columns tracking how often and when `remember` returned a memory
+fn access_fields() -> Vec<Field> {
+	vec![
+		Field::new("access_count", DataType::UInt32, true),
+		Field::new("last_accessed", DataType::Utf8, true),
+	]
+}
+
+/// Column holding the namespace; null for memories stored before namespaces
+fn namespace_field() -> Field {
+	Field::new("namespace", DataType::Utf8, true)
+}
+
+/// Filter on the namespace column; rows without one belong to the default namespace
+fn namespace_filter(namespaces: &[String]) -> String {
+	let names: Vec<String> = namespaces
+		.iter()
+		.map(|namespace| format!("'{}'", namespace.replace('\'', "''")))
+		.collect();
+	let mut filter = format!("namespace IN ({})", names.join(", "));
+	if namespaces
+		.iter()
+		.any(|namespace| namespace == DEFAULT_NAMESPACE)
+	{
+		filter = format!("({} OR namespace IS NULL)", filter);
+	}
+	filter
+}
+
+/// `id IN (...)` filter for a set of memory ids
+fn id_filter(memory_ids: &[String]) -> String {
+	let ids: Vec<String> = memory_ids
+		.iter()
+		.map(|id| format!("'{}'", id.replace('\'', "''")))
+		.collect();
+	format!("id IN ({})", ids.join(", "))
+}
 
 /// LanceDB-based storage for memories with vector search capabilities
 pub struct MemoryStore {
@@ -39,6 +84,8 @@ pub struct MemoryStore {
 	config: MemoryConfig,
 	main_config: crate::config::Config,
 	vector_dim: usize,
+	/// Project storage directory, where renames of related files are recorded
+	storage_dir: Option<std::path::PathBuf>,
 }
 
 impl MemoryStore {
@@ -62,6 +109,9 @@ impl MemoryStore {
 			config,
 			main_config,
 			vector_dim,
+			storage_dir: std::path::Path::new(db_path)
+				.parent()
+				.map(|dir| dir.to_path_buf()),
 		};
 
 		// Initialize tables
@@ -76,32 +126,15 @@ impl MemoryStore {
 
 		// Create memories table if it doesn't exist
 		if !table_names.contains(&"memories".to_string()) {
-			let schema = Arc::new(Schema::new(vec![
-				Field::new("id", DataType::Utf8, false),
-				Field::new("memory_type", DataType::Utf8, false),
-				Field::new("title", DataType::Utf8, false),
-				Field::new("content", DataType::Utf8, false),
-				Field::new("created_at", DataType::Utf8, false),
-				Field::new("updated_at", DataType::Utf8, false),
-				Field::new("importance", DataType::Float32, false),
-				Field::new("confidence", DataType::Float32, false),
-				Field::new("tags", DataType::Utf8, true), // JSON serialized
-				Field::new("related_files", DataType::Utf8, true), // JSON serialized
-				Field::new("git_commit", DataType::Utf8, true),
-				Field::new(
-					"embedding",
-					DataType::FixedSizeList(
-						Arc::new(Field::new("item", DataType::Float32, true)),
-						self.vector_dim as i32,
-					),
-					true,
-				),
-			]));
-
 			self.db
-				.create_empty_table("memories", schema)
+				.create_empty_table("memories", self.memories_schema())
 				.execute()
 				.await?;
+		} else {
+			self.add_missing_columns("memories").await?;
+		}
+		if table_names.contains(&ARCHIVE_TABLE.to_string()) {
+			self.add_missing_columns(ARCHIVE_TABLE).await?;
 		}
 
 		// Create relationships table if it doesn't exist
@@ -125,27 +158,32 @@ impl MemoryStore {
 		Ok(())
 	}
 
-	/// Store a memory
-	pub async fn store_memory(&mut self, memory: &Memory) -> Result<()> {
-		// Generate embedding using the optimized single embedding function for better performance
-		let embedding = crate::embedding::generate_embeddings(
-			&memory.get_searchable_text(),
-			false,
-			&self.main_config,
-		)
-		.await?;
-
-		self.store_memory_with_embedding(memory, embedding).await
+	/// Tables created before access tracking or namespaces lack their columns
+	async fn add_missing_columns(&self, table_name: &str) -> Result<()> {
+		let table = self.db.open_table(table_name).execute().await?;
+		let schema = table.schema().await?;
+		let missing: Vec<Field> = access_fields()
+			.into_iter()
+			.chain(std::iter::once(namespace_field()))
+			.filter(|field| schema.field_with_name(field.name()).is_err())
+			.collect();
+		if !missing.is_empty() {
+			if let Err(e) = table
+				.add_columns(
+					NewColumnTransform::AllNulls(Arc::new(Schema::new(missing))),
+					None,
+				)
+				.await
+			{
+				tracing::warn!("Failed to add columns to {}: {}", table_name, e);
+			}
+		}
+		Ok(())
 	}
 
-	/// Store a memory with a pre-computed embedding (for batch operations)
-	async fn store_memory_with_embedding(
-		&mut self,
-		memory: &Memory,
-		embedding: Vec<f32>,
-	) -> Result<()> {
-		// Create record batch
-		let schema = Arc::new(Schema::new(vec![
+	/// Schema of the memories table (and its archive)
+	fn memories_schema(&self) -> Arc<Schema> {
+		let mut fields = vec![
 			Field::new("id", DataType::Utf8, false),
 			Field::new("memory_type", DataType::Utf8, false),
 			Field::new("title", DataType::Utf8, false),
@@ -154,8 +192,8 @@ impl MemoryStore {
 			Field::new("updated_at", DataType::Utf8, false),
 			Field::new("importance", DataType::Float32, false),
 			Field::new("confidence", DataType::Float32, false),
-			Field::new("tags", DataType::Utf8, true),
-			Field::new("related_files", DataType::Utf8, true),
+			Field::new("tags", DataType::Utf8, true), // JSON serialized
+			Field::new("related_files", DataType::Utf8, true), // JSON serialized
 			Field::new("git_commit", DataType::Utf8, true),
 			Field::new(
 				"embedding",
@@ -165,7 +203,71 @@ impl MemoryStore {
 				),
 				true,
 			),
-		]));
+		];
+		fields.extend(access_fields());
+		fields.push(namespace_field());
+		Arc::new(Schema::new(fields))
+	}
+
+	/// Store a memory
+	pub async fn store_memory(&mut self, memory: &Memory) -> Result<()> {
+		let embedding = self.memory_embedding(memory).await?;
+		self.store_memory_with_embedding(memory, embedding).await
+	}
+
+	/// Embedding a memory is stored with
+	pub async fn memory_embedding(&self, memory: &Memory) -> Result<Vec<f32>> {
+		self.embedding_provider
+			.generate_embedding(&memory.get_searchable_text())
+			.await
+	}
+
+	/// The memory of `namespace` closest to `embedding`, with its cosine
+	/// distance, when it is within `max_distance`
+	pub async fn find_duplicate(
+		&self,
+		embedding: &[f32],
+		namespace: &str,
+		max_distance: f32,
+	) -> Result<Option<(Memory, f32)>> {
+		let table = self.db.open_table("memories").execute().await?;
+		let mut results = table
+			.vector_search(embedding)?
+			.distance_type(DistanceType::Cosine)
+			.only_if(namespace_filter(&[namespace.to_string()]))
+			.limit(1)
+			.execute()
+			.await?;
+
+		while let Some(batch) = results.try_next().await? {
+			if batch.num_rows() == 0 {
+				continue;
+			}
+			let distance = batch
+				.column_by_name("_distance")
+				.and_then(|col| col.as_any().downcast_ref::<Float32Array>())
+				.map(|arr| arr.value(0))
+				.unwrap_or(f32::MAX);
+			if distance > max_distance {
+				return Ok(None);
+			}
+			return Ok(self
+				.batch_to_memories(&batch)?
+				.into_iter()
+				.next()
+				.map(|memory| (memory, distance)));
+		}
+		Ok(None)
+	}
+
+	/// Store a memory with a pre-computed embedding (for batch operations and imports)
+	pub async fn store_memory_with_embedding(
+		&mut self,
+		memory: &Memory,
+		embedding: Vec<f32>,
+	) -> Result<()> {
+		// Create record batch
+		let schema = self.memories_schema();
 
 		// Prepare data
 		let tags_json = serde_json::to_string(&memory.metadata.tags)?;
@@ -195,6 +297,12 @@ impl MemoryStore {
 				Arc::new(StringArray::from(vec![files_json])),
 				Arc::new(StringArray::from(vec![memory.metadata.git_commit.clone()])),
 				Arc::new(embedding_array),
+				Arc::new(UInt32Array::from(vec![memory.metadata.access_count])),
+				Arc::new(StringArray::from(vec![memory
+					.metadata
+					.last_accessed
+					.map(|at| at.to_rfc3339())])),
+				Arc::new(StringArray::from(vec![memory.namespace.clone()])),
 			],
 		)?;
 
@@ -300,15 +408,20 @@ impl MemoryStore {
 			.map(|memory| memory.get_searchable_text())
 			.collect();
 
-		// Generate ALL embeddings in ONE API request using the same high-level function as indexer
-		// This includes token-aware batching and respects config limits
-		let embeddings = crate::embedding::generate_embeddings_batch(
+		// Token-aware batches within the configured limits, like the indexer
+		let batches = crate::embedding::split_texts_into_token_limited_batches(
 			texts,
-			false,
-			&self.main_config,
-			crate::embedding::types::InputType::Query,
-		)
-		.await?;
+			self.main_config.index.embeddings_batch_size,
+			self.main_config.index.embeddings_max_tokens_per_batch,
+		);
+		let mut embeddings = Vec::new();
+		for batch in batches {
+			embeddings.extend(
+				self.embedding_provider
+					.generate_embeddings_batch(batch, crate::embedding::types::InputType::Query)
+					.await?,
+			);
+		}
 
 		if embeddings.len() != memories.len() {
 			return Err(anyhow::anyhow!(
@@ -381,6 +494,12 @@ impl MemoryStore {
 			.min(self.config.max_search_results);
 		let min_relevance = query.min_relevance.unwrap_or(0.0);
 
+		// Memories keep the paths their files had when they were created
+		let aliases = match (&query.related_files, &self.storage_dir) {
+			(Some(_), Some(storage_dir)) => PathAliases::load_from_dir(storage_dir),
+			_ => PathAliases::default(),
+		};
+
 		let mut results = Vec::new();
 
 		// If we have a text query, use semantic search
@@ -396,6 +515,11 @@ impl MemoryStore {
 				.distance_type(DistanceType::Cosine)
 				.limit(limit * 2); // Get more results to filter
 
+			// Other namespaces must not crowd out the requested ones
+			if let Some(namespaces) = &query.namespaces {
+				db_query = db_query.only_if(namespace_filter(namespaces));
+			}
+
 			// Apply intelligent search optimization
 			db_query = crate::store::vector_optimizer::VectorOptimizer::optimize_query(
 				db_query, &table, "memories",
@@ -403,7 +527,11 @@ impl MemoryStore {
 			.await
 			.map_err(|e| anyhow::anyhow!("Failed to optimize query: {}", e))?;
 
-			let mut db_results = db_query.execute().await?;
+			let mut db_results =
+				crate::store::vector_optimizer::VectorOptimizer::execute_with_fallback(
+					db_query, "memories",
+				)
+				.await?;
 
 			while let Some(batch) = db_results.try_next().await? {
 				if batch.num_rows() == 0 {
@@ -421,7 +549,7 @@ impl MemoryStore {
 
 				for (memory, distance) in memories.into_iter().zip(distance_array.into_iter()) {
 					// Apply filters
-					if !self.matches_filters(&memory, query) {
+					if !self.matches_filters(&memory, query, &aliases) {
 						continue;
 					}
 
@@ -438,7 +566,12 @@ impl MemoryStore {
 			}
 		} else {
 			// No text query, just apply filters
-			let mut db_results = table.query().execute().await?;
+			let mut db_query = table.query();
+			if let Some(namespaces) = &query.namespaces {
+				db_query = db_query.only_if(namespace_filter(namespaces));
+			}
+			// A filter-only scan reads no vector index
+			let mut db_results = db_query.execute().await?;
 
 			while let Some(batch) = db_results.try_next().await? {
 				if batch.num_rows() == 0 {
@@ -448,7 +581,7 @@ impl MemoryStore {
 				let memories = self.batch_to_memories(&batch)?;
 
 				for memory in memories {
-					if self.matches_filters(&memory, query) {
+					if self.matches_filters(&memory, query, &aliases) {
 						let relevance_score = memory.metadata.importance;
 						if relevance_score >= min_relevance {
 							results.push(MemorySearchResult {
@@ -502,10 +635,18 @@ impl MemoryStore {
 				}
 			});
 		} else {
-			// Default: Sort by relevance score (highest first)
+			// Default: relevance weighted by effective importance (highest first)
+			let now = Utc::now();
+			let half_life = self.main_config.memory.recency_half_life_days;
+			let score = |result: &MemorySearchResult| {
+				super::importance::rank_score(
+					result.relevance_score,
+					super::importance::effective_importance(&result.memory, now, half_life),
+				)
+			};
 			results.sort_by(|a, b| {
-				b.relevance_score
-					.partial_cmp(&a.relevance_score)
+				score(b)
+					.partial_cmp(&score(a))
 					.unwrap_or(std::cmp::Ordering::Equal)
 			});
 		}
@@ -623,12 +764,191 @@ impl MemoryStore {
 		Ok(relationships)
 	}
 
+	/// Get every stored relationship
+	pub async fn get_all_relationships(&self) -> Result<Vec<MemoryRelationship>> {
+		let table = self.db.open_table("memory_relationships").execute().await?;
+		let mut results = table.query().execute().await?;
+
+		let mut relationships = Vec::new();
+		while let Some(batch) = results.try_next().await? {
+			if batch.num_rows() == 0 {
+				continue;
+			}
+			relationships.append(&mut self.batch_to_relationships(&batch)?);
+		}
+
+		Ok(relationships)
+	}
+
+	/// Get the stored embedding vector of every memory, keyed by memory id
+	pub async fn get_memory_embeddings(
+		&self,
+	) -> Result<std::collections::HashMap<String, Vec<f32>>> {
+		let table = self.db.open_table("memories").execute().await?;
+		let mut results = table
+			.query()
+			.select(lancedb::query::Select::Columns(vec![
+				"id".to_string(),
+				"embedding".to_string(),
+			]))
+			.execute()
+			.await?;
+
+		let mut embeddings = std::collections::HashMap::new();
+		while let Some(batch) = results.try_next().await? {
+			if batch.num_rows() == 0 {
+				continue;
+			}
+			let ids = batch
+				.column_by_name("id")
+				.and_then(|column| column.as_any().downcast_ref::<StringArray>())
+				.ok_or_else(|| anyhow::anyhow!("id column not found"))?;
+			let vectors = batch
+				.column_by_name("embedding")
+				.and_then(|column| column.as_any().downcast_ref::<FixedSizeListArray>())
+				.ok_or_else(|| anyhow::anyhow!("embedding column not found"))?;
+
+			for row in 0..batch.num_rows() {
+				if vectors.is_null(row) {
+					continue;
+				}
+				if let Some(values) = vectors.value(row).as_any().downcast_ref::<Float32Array>() {
+					embeddings.insert(ids.value(row).to_string(), values.values().to_vec());
+				}
+			}
+		}
+
+		Ok(embeddings)
+	}
+
+	/// Dimension of the stored embedding vectors
+	pub fn vector_dim(&self) -> usize {
+		self.vector_dim
+	}
+
+	/// Embedding model memories are embedded with (`provider:model`)
+	pub fn embedding_model(&self) -> &str {
+		&self.main_config.embedding.text_model
+	}
+
 	/// Get total count of memories
 	pub async fn get_memory_count(&self) -> Result<usize> {
 		let table = self.db.open_table("memories").execute().await?;
 		Ok(table.count_rows(None).await?)
 	}
 
+	/// Count a retrieval of each memory and stamp it as last accessed now
+	pub async fn record_access(&self, memory_ids: &[String]) -> Result<()> {
+		if memory_ids.is_empty() {
+			return Ok(());
+		}
+
+		let table = self.db.open_table("memories").execute().await?;
+		let now = Utc::now().to_rfc3339();
+		for chunk in memory_ids.chunks(100) {
+			table
+				.update()
+				.only_if(id_filter(chunk))
+				.column("access_count", "COALESCE(access_count, 0) + 1")
+				.column("last_accessed", format!("'{}'", now))
+				.execute()
+				.await?;
+		}
+
+		Ok(())
+	}
+
+	/// Move memories, embeddings included, to the archive table
+	pub async fn archive_memories(&mut self, memory_ids: &[String]) -> Result<usize> {
+		self.move_memories("memories", ARCHIVE_TABLE, memory_ids)
+			.await
+	}
+
+	/// Move an archived memory back; false if no archived memory has this id
+	pub async fn restore_memory(&mut self, memory_id: &str) -> Result<bool> {
+		let table_names = self.db.table_names().execute().await?;
+		if !table_names.contains(&ARCHIVE_TABLE.to_string()) {
+			return Ok(false);
+		}
+
+		let moved = self
+			.move_memories(ARCHIVE_TABLE, "memories", &[memory_id.to_string()])
+			.await?;
+		Ok(moved > 0)
+	}
+
+	/// Get count of archived memories
+	pub async fn get_archived_count(&self) -> Result<usize> {
+		let table_names = self.db.table_names().execute().await?;
+		if !table_names.contains(&ARCHIVE_TABLE.to_string()) {
+			return Ok(0);
+		}
+
+		let table = self.db.open_table(ARCHIVE_TABLE).execute().await?;
+		Ok(table.count_rows(None).await?)
+	}
+
+	/// Copy rows between the memories table and its archive, then delete the originals
+	async fn move_memories(&self, from: &str, to: &str, memory_ids: &[String]) -> Result<usize> {
+		let source = self.db.open_table(from).execute().await?;
+		let mut moved = 0;
+
+		for chunk in memory_ids.chunks(100) {
+			let filter = id_filter(chunk);
+			let batches: Vec<RecordBatch> = source
+				.query()
+				.only_if(filter.clone())
+				.execute()
+				.await?
+				.try_collect::<Vec<_>>()
+				.await?
+				.into_iter()
+				.filter(|batch| batch.num_rows() > 0)
+				.collect();
+			if batches.is_empty() {
+				continue;
+			}
+
+			let rows: usize = batches.iter().map(|batch| batch.num_rows()).sum();
+			let schema = batches[0].schema();
+			let batch_reader =
+				arrow::record_batch::RecordBatchIterator::new(batches.into_iter().map(Ok), schema);
+
+			let table_names = self.db.table_names().execute().await?;
+			if table_names.contains(&to.to_string()) {
+				let target = self.db.open_table(to).execute().await?;
+				target.add(batch_reader).execute().await?;
+			} else {
+				self.db.create_table(to, batch_reader).execute().await?;
+			}
+
+			source.delete(&filter).await?;
+			moved += rows;
+		}
+
+		Ok(moved)
+	}
+
+	/// Rewrite the memories table and drop its old versions, so deleted and
+	/// replaced rows no longer exist on disk
+	pub async fn purge_history(&self) -> Result<()> {
+		let table = self.db.open_table("memories").execute().await?;
+		table
+			.optimize(OptimizeAction::Compact {
+				options: Default::default(),
+				remap_options: None,
+			})
+			.await?;
+		table
+			.optimize(OptimizeAction::Prune {
+				older_than: Some(chrono::Duration::zero()),
+				delete_unverified: Some(true),
+				error_if_tagged_old_versions: Some(false),
+			})
+			.await?;
+		Ok(())
+	}
+
 	/// Clean up old memories based on configuration
 	pub async fn cleanup_old_memories(&mut self) -> Result<usize> {
 		if let Some(cleanup_days) = self.config.auto_cleanup_days {
@@ -729,6 +1049,19 @@ impl MemoryStore {
 			.and_then(|col| col.as_any().downcast_ref::<StringArray>())
 			.ok_or_else(|| anyhow::anyhow!("git_commit column not found or wrong type"))?;
 
+		// Access tracking columns are null for memories stored before it existed
+		let access_count_array = batch
+			.column_by_name("access_count")
+			.and_then(|col| col.as_any().downcast_ref::<UInt32Array>());
+
+		let last_accessed_array = batch
+			.column_by_name("last_accessed")
+			.and_then(|col| col.as_any().downcast_ref::<StringArray>());
+
+		let namespace_array = batch
+			.column_by_name("namespace")
+			.and_then(|col| col.as_any().downcast_ref::<StringArray>());
+
 		for i in 0..num_rows {
 			let memory_type =
 				super::types::MemoryType::from(memory_type_array.value(i).to_string());
@@ -751,12 +1084,29 @@ impl MemoryStore {
 				Some(git_array.value(i).to_string())
 			};
 
+			let access_count = access_count_array
+				.filter(|array| !array.is_null(i))
+				.map(|array| array.value(i))
+				.unwrap_or(0);
+
+			let last_accessed = last_accessed_array
+				.filter(|array| !array.is_null(i))
+				.and_then(|array| DateTime::parse_from_rfc3339(array.value(i)).ok())
+				.map(|at| at.with_timezone(&Utc));
+
+			let namespace = namespace_array
+				.filter(|array| !array.is_null(i))
+				.map(|array| array.value(i).to_string())
+				.unwrap_or_else(|| DEFAULT_NAMESPACE.to_string());
+
 			let metadata = super::types::MemoryMetadata {
 				git_commit,
 				importance: importance_array.value(i),
 				confidence: confidence_array.value(i),
 				tags,
 				related_files,
+				access_count,
+				last_accessed,
 				..Default::default()
 			};
 
@@ -770,6 +1120,7 @@ impl MemoryStore {
 				updated_at: DateTime::parse_from_rfc3339(updated_at_array.value(i))?
 					.with_timezone(&Utc),
 				metadata,
+				namespace,
 				relevance_score: None,
 			};
 
@@ -852,7 +1203,14 @@ impl MemoryStore {
 	}
 
 	/// Check if memory matches the query filters
-	fn matches_filters(&self, memory: &Memory, query: &MemoryQuery) -> bool {
+	fn matches_filters(&self, memory: &Memory, query: &MemoryQuery, aliases: &PathAliases) -> bool {
+		// Filter by namespaces
+		if let Some(ref namespaces) = query.namespaces {
+			if !namespaces.contains(&memory.namespace) {
+				return false;
+			}
+		}
+
 		// Filter by memory types
 		if let Some(ref memory_types) = query.memory_types {
 			if !memory_types.contains(&memory.memory_type) {
@@ -869,10 +1227,13 @@ impl MemoryStore {
 
 		// Filter by related files
 		if let Some(ref files) = query.related_files {
-			if !files
-				.iter()
-				.any(|file| memory.metadata.related_files.contains(file))
-			{
+			if !files.iter().any(|file| {
+				memory
+					.metadata
+					.related_files
+					.iter()
+					.any(|related| aliases.same_file(related, file))
+			}) {
 				return false;
 			}
 		}
@@ -947,6 +1308,17 @@ impl MemoryStore {
 			self.db.drop_table("memory_relationships").await?;
 		}
 
+		// Archived memories go with them
+		if self
+			.db
+			.table_names()
+			.execute()
+			.await?
+			.contains(&ARCHIVE_TABLE.to_string())
+		{
+			self.db.drop_table(ARCHIVE_TABLE).await?;
+		}
+
 		// Recreate tables
 		self.initialize_tables().await?;
 
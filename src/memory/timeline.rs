@@ -0,0 +1,305 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Chronological memory views
+//!
+//! Memories are listed oldest first and grouped by day or ISO week; a week
+//! can be summarized by the configured LLM ("what decisions were recorded
+//! this sprint"). Unlike search, the timeline is not capped by
+//! `max_search_results`: every memory matching the filter is included.
+
+use anyhow::Result;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use serde::Serialize;
+
+use super::types::{Memory, MemoryType};
+use crate::config::Config;
+
+/// Which memories a timeline shows
+#[derive(Debug, Clone, Default)]
+pub struct TimelineFilter {
+	pub memory_types: Option<Vec<MemoryType>>,
+	/// Any of these tags
+	pub tags: Option<Vec<String>>,
+	/// Only memories with a related file under this path
+	pub scope: Option<String>,
+	pub since: Option<DateTime<Utc>>,
+	pub until: Option<DateTime<Utc>>,
+}
+
+impl TimelineFilter {
+	pub fn matches(&self, memory: &Memory) -> bool {
+		if let Some(memory_types) = &self.memory_types {
+			if !memory_types.contains(&memory.memory_type) {
+				return false;
+			}
+		}
+		if let Some(tags) = &self.tags {
+			if !tags.iter().any(|tag| memory.metadata.tags.contains(tag)) {
+				return false;
+			}
+		}
+		if let Some(scope) = &self.scope {
+			let scope = scope.trim_start_matches("./").trim_end_matches('/');
+			if !memory.metadata.related_files.iter().any(|file| {
+				let file = file.trim_start_matches("./");
+				scope.is_empty()
+					|| file == scope
+					|| file
+						.strip_prefix(scope)
+						.is_some_and(|rest| rest.starts_with('/'))
+			}) {
+				return false;
+			}
+		}
+		if self.since.is_some_and(|since| memory.created_at < since) {
+			return false;
+		}
+		if self.until.is_some_and(|until| memory.created_at >= until) {
+			return false;
+		}
+		true
+	}
+}
+
+/// Length of the periods a timeline is grouped into
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimelinePeriod {
+	Day,
+	#[default]
+	Week,
+}
+
+impl std::str::FromStr for TimelinePeriod {
+	type Err = anyhow::Error;
+
+	fn from_str(s: &str) -> Result<Self> {
+		match s.to_lowercase().as_str() {
+			"day" | "daily" => Ok(Self::Day),
+			"week" | "weekly" => Ok(Self::Week),
+			_ => Err(anyhow::anyhow!("Unknown period '{}': use day or week", s)),
+		}
+	}
+}
+
+/// Memories created within one day or week
+#[derive(Debug, Clone, Serialize)]
+pub struct TimelineGroup {
+	/// "2025-06-14" for days, "2025-W24" for weeks
+	pub period: String,
+	/// First day of the period
+	pub start: NaiveDate,
+	pub memories: Vec<Memory>,
+	/// LLM summary of the period, when requested
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub digest: Option<String>,
+}
+
+/// Group memories sorted oldest first into consecutive periods
+pub fn group_by_period(memories: Vec<Memory>, period: TimelinePeriod) -> Vec<TimelineGroup> {
+	let mut groups: Vec<TimelineGroup> = Vec::new();
+	for memory in memories {
+		let date = memory.created_at.date_naive();
+		let start = match period {
+			TimelinePeriod::Day => date,
+			TimelinePeriod::Week => {
+				date - Duration::days(date.weekday().num_days_from_monday() as i64)
+			}
+		};
+		match groups.last_mut() {
+			Some(group) if group.start == start => group.memories.push(memory),
+			_ => {
+				let label = match period {
+					TimelinePeriod::Day => start.format("%Y-%m-%d").to_string(),
+					TimelinePeriod::Week => {
+						let week = start.iso_week();
+						format!("{}-W{:02}", week.year(), week.week())
+					}
+				};
+				groups.push(TimelineGroup {
+					period: label,
+					start,
+					memories: vec![memory],
+					digest: None,
+				});
+			}
+		}
+	}
+	groups
+}
+
+/// Parse `--since`/`--until` values: a date (`2025-06-01`) or a span back from `now`
+/// (`14d`, `2w`)
+pub fn parse_since(value: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+	let value = value.trim();
+	if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+		return Ok(date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc());
+	}
+
+	let invalid = || {
+		anyhow::anyhow!(
+			"Invalid date '{}': use YYYY-MM-DD or a span like 14d or 2w",
+			value
+		)
+	};
+	let (count, unit) = value.split_at(value.len().saturating_sub(1));
+	let count: i64 = count.parse().map_err(|_| invalid())?;
+	let span = match unit {
+		"d" => Duration::days(count),
+		"w" => Duration::weeks(count),
+		_ => return Err(invalid()),
+	};
+	Ok(now - span)
+}
+
+const DIGEST_SYSTEM_PROMPT: &str = "You summarize a software team's recorded project memories for one period. \
+Write a short digest in Markdown: lead with the decisions made, then notable bug fixes, features and other insights. \
+Mention memory titles where useful, do not invent anything that is not in the memories, and keep it under 250 words.";
+
+/// Summarize a period's memories with the configured LLM
+pub async fn digest(config: &Config, group: &TimelineGroup) -> Result<String> {
+	let mut prompt = format!(
+		"Period: {} ({} memories)\n\n",
+		group.period,
+		group.memories.len()
+	);
+	for memory in &group.memories {
+		prompt.push_str(&format!(
+			"## [{}] {} ({})\n",
+			memory.memory_type,
+			memory.title,
+			memory.created_at.format("%Y-%m-%d")
+		));
+		if !memory.metadata.tags.is_empty() {
+			prompt.push_str(&format!("Tags: {}\n", memory.metadata.tags.join(", ")));
+		}
+		prompt.push_str(&memory.content);
+		prompt.push_str("\n\n");
+	}
+
+	call_llm(config, DIGEST_SYSTEM_PROMPT, &prompt).await
+}
+
+async fn call_llm(config: &Config, system: &str, prompt: &str) -> Result<String> {
+	use serde_json::{json, Value};
+
+	// Without an API key, fall back to the MCP client's model when serving
+	let api_key = match &config.openrouter.api_key {
+		Some(key) => key.clone(),
+		None => match std::env::var("OPENROUTER_API_KEY") {
+			Ok(key) => key,
+			Err(_) if crate::mcp::sampling::is_available() => {
+				return crate::mcp::sampling::create_message(
+					system,
+					prompt,
+					Some(&config.openrouter.model),
+					crate::mcp::sampling::SAMPLING_DEFAULT_MAX_TOKENS,
+				)
+				.await;
+			}
+			Err(_) => return Err(anyhow::anyhow!("No OpenRouter API key found")),
+		},
+	};
+
+	let payload = json!({
+		"model": config.openrouter.model,
+		"messages": [
+			{ "role": "system", "content": system },
+			{ "role": "user", "content": prompt }
+		],
+		"temperature": 0.2,
+		"max_tokens": 600
+	});
+
+	let response = reqwest::Client::new()
+		.post(format!(
+			"{}/chat/completions",
+			config.openrouter.base_url.trim_end_matches('/')
+		))
+		.header("Authorization", format!("Bearer {}", api_key))
+		.header("HTTP-Referer", "https://github.com/muvon/octocode")
+		.header("X-Title", "Octocode")
+		.json(&payload)
+		.timeout(std::time::Duration::from_secs(config.openrouter.timeout))
+		.send()
+		.await?;
+
+	if !response.status().is_success() {
+		let error_text = response.text().await?;
+		return Err(anyhow::anyhow!("LLM API error: {}", error_text));
+	}
+
+	let response_json: Value = response.json().await?;
+	response_json["choices"][0]["message"]["content"]
+		.as_str()
+		.map(|content| content.trim().to_string())
+		.ok_or_else(|| anyhow::anyhow!("Invalid response format from LLM"))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use chrono::TimeZone;
+
+	fn memory_at(title: &str, memory_type: MemoryType, day: u32) -> Memory {
+		let mut memory = Memory::new(memory_type, title.to_string(), "content".to_string(), None);
+		memory.created_at = Utc.with_ymd_and_hms(2025, 6, day, 12, 0, 0).unwrap();
+		memory
+	}
+
+	#[test]
+	fn test_group_by_week() {
+		// June 9 2025 is a Monday
+		let memories = vec![
+			memory_at("a", MemoryType::Decision, 9),
+			memory_at("b", MemoryType::BugFix, 15),
+			memory_at("c", MemoryType::Decision, 16),
+		];
+		let weeks = group_by_period(memories.clone(), TimelinePeriod::Week);
+		let labels: Vec<(&str, usize)> = weeks
+			.iter()
+			.map(|w| (w.period.as_str(), w.memories.len()))
+			.collect();
+		assert_eq!(labels, vec![("2025-W24", 2), ("2025-W25", 1)]);
+		assert_eq!(
+			weeks[1].start,
+			NaiveDate::from_ymd_opt(2025, 6, 16).unwrap()
+		);
+
+		assert_eq!(group_by_period(memories, TimelinePeriod::Day).len(), 3);
+	}
+
+	#[test]
+	fn test_filter_and_since() {
+		let mut memory = memory_at("a", MemoryType::Decision, 10);
+		memory.metadata.related_files = vec!["src/auth/login.rs".to_string()];
+
+		let mut filter = TimelineFilter {
+			memory_types: Some(vec![MemoryType::Decision]),
+			scope: Some("src/auth/".to_string()),
+			..Default::default()
+		};
+		assert!(filter.matches(&memory));
+		filter.scope = Some("src/au".to_string());
+		assert!(!filter.matches(&memory));
+		filter.scope = None;
+
+		let now = Utc.with_ymd_and_hms(2025, 6, 20, 0, 0, 0).unwrap();
+		filter.since = Some(parse_since("1w", now).unwrap());
+		assert!(!filter.matches(&memory));
+		filter.since = Some(parse_since("2025-06-10", now).unwrap());
+		assert!(filter.matches(&memory));
+		assert!(parse_since("soon", now).is_err());
+	}
+}
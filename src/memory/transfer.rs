@@ -0,0 +1,336 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Memory export and import files
+//!
+//! JSON files carry everything, optionally including embedding vectors so an
+//! import with the same model does not have to re-embed. Markdown files are
+//! meant to be read and reviewed (e.g. checked into a repository): each memory
+//! is a section whose metadata sits in an HTML comment under its heading, so
+//! the file imports back without loss except for the vectors.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use super::types::{Memory, MemoryRelationship};
+
+/// Current export file version; newer files are rejected on import
+pub const EXPORT_VERSION: u32 = 1;
+
+const MEMORY_MARKER: &str = "<!-- octocode-memory ";
+const RELATIONSHIPS_MARKER: &str = "<!-- octocode-relationships ";
+const MARKER_END: &str = " -->";
+
+/// All memories and relationships of a project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryExport {
+	pub version: u32,
+	pub exported_at: DateTime<Utc>,
+	/// Model the embeddings were computed with, when they are included
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub embedding_model: Option<String>,
+	pub memories: Vec<ExportedMemory>,
+	#[serde(default)]
+	pub relationships: Vec<MemoryRelationship>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedMemory {
+	#[serde(flatten)]
+	pub memory: Memory,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub embedding: Option<Vec<f32>>,
+}
+
+/// Export file format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+	Json,
+	Md,
+}
+
+impl std::str::FromStr for ExportFormat {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.to_lowercase().as_str() {
+			"json" => Ok(Self::Json),
+			"md" | "markdown" => Ok(Self::Md),
+			other => Err(format!("unknown format '{}' (expected json or md)", other)),
+		}
+	}
+}
+
+impl ExportFormat {
+	/// Markdown for `.md`/`.markdown` files, JSON otherwise
+	pub fn from_path(path: &Path) -> Self {
+		match path.extension().and_then(|ext| ext.to_str()) {
+			Some(ext) if ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown") => {
+				Self::Md
+			}
+			_ => Self::Json,
+		}
+	}
+}
+
+impl MemoryExport {
+	pub fn render(&self, format: ExportFormat) -> Result<String> {
+		match format {
+			ExportFormat::Json => Ok(serde_json::to_string_pretty(self)?),
+			ExportFormat::Md => self.to_markdown(),
+		}
+	}
+
+	pub fn parse(contents: &str, format: ExportFormat) -> Result<Self> {
+		let export: Self = match format {
+			ExportFormat::Json => serde_json::from_str(contents)
+				.map_err(|e| anyhow::anyhow!("Invalid memory export file: {}", e))?,
+			ExportFormat::Md => Self::from_markdown(contents)?,
+		};
+		if export.version > EXPORT_VERSION {
+			return Err(anyhow::anyhow!(
+				"Memory export version {} is newer than supported version {}; upgrade octocode",
+				export.version,
+				EXPORT_VERSION
+			));
+		}
+		Ok(export)
+	}
+
+	fn to_markdown(&self) -> Result<String> {
+		let mut markdown = format!(
+			"# Octocode memories\n\nExported {}: {} memories, {} relationships. \
+			 Import with `octocode memory import --file <this file>`.\n",
+			self.exported_at.format("%Y-%m-%d %H:%M:%S UTC"),
+			self.memories.len(),
+			self.relationships.len()
+		);
+
+		for exported in &self.memories {
+			let memory = &exported.memory;
+			// Everything but the content goes into the marker comment
+			let mut metadata = serde_json::to_value(memory)?;
+			if let Some(fields) = metadata.as_object_mut() {
+				fields.remove("content");
+				fields.remove("relevance_score");
+			}
+			markdown.push_str(&format!(
+				"\n## {}\n{}{}{}\n\n{}\n",
+				memory.title.replace('\n', " "),
+				MEMORY_MARKER,
+				comment_safe(&serde_json::to_string(&metadata)?),
+				MARKER_END,
+				memory.content.trim_matches('\n')
+			));
+		}
+
+		if !self.relationships.is_empty() {
+			markdown.push_str(&format!(
+				"\n{}{}{}\n",
+				RELATIONSHIPS_MARKER,
+				comment_safe(&serde_json::to_string(&self.relationships)?),
+				MARKER_END
+			));
+		}
+		Ok(markdown)
+	}
+
+	fn from_markdown(contents: &str) -> Result<Self> {
+		let lines: Vec<&str> = contents.lines().collect();
+		let marker_json = |line: &str, marker: &str| -> Option<String> {
+			line.strip_prefix(marker)
+				.and_then(|rest| rest.strip_suffix(MARKER_END))
+				.map(str::to_string)
+		};
+
+		let markers: Vec<usize> = (0..lines.len())
+			.filter(|&idx| lines[idx].starts_with(MEMORY_MARKER))
+			.collect();
+		let relationships_line = lines
+			.iter()
+			.position(|line| line.starts_with(RELATIONSHIPS_MARKER));
+
+		let mut memories = Vec::with_capacity(markers.len());
+		for (position, &marker) in markers.iter().enumerate() {
+			let json = marker_json(lines[marker], MEMORY_MARKER)
+				.ok_or_else(|| anyhow::anyhow!("Malformed memory marker on line {}", marker + 1))?;
+			let mut metadata: serde_json::Value = serde_json::from_str(&json).map_err(|e| {
+				anyhow::anyhow!("Invalid memory metadata on line {}: {}", marker + 1, e)
+			})?;
+
+			// The content runs up to the next memory's heading or the relationships
+			let mut end = markers
+				.get(position + 1)
+				.copied()
+				.or(relationships_line)
+				.unwrap_or(lines.len());
+			if markers.get(position + 1).is_some() && lines[end - 1].starts_with("## ") {
+				end -= 1;
+			}
+			let content = lines[marker + 1..end].join("\n");
+
+			if let Some(fields) = metadata.as_object_mut() {
+				fields.insert(
+					"content".to_string(),
+					serde_json::Value::String(content.trim_matches('\n').to_string()),
+				);
+			}
+			let memory: Memory = serde_json::from_value(metadata).map_err(|e| {
+				anyhow::anyhow!("Invalid memory metadata on line {}: {}", marker + 1, e)
+			})?;
+			memories.push(ExportedMemory {
+				memory,
+				embedding: None,
+			});
+		}
+
+		let relationships = match relationships_line {
+			Some(line) => {
+				let json = marker_json(lines[line], RELATIONSHIPS_MARKER).ok_or_else(|| {
+					anyhow::anyhow!("Malformed relationships marker on line {}", line + 1)
+				})?;
+				serde_json::from_str(&json).map_err(|e| {
+					anyhow::anyhow!("Invalid relationships on line {}: {}", line + 1, e)
+				})?
+			}
+			None => Vec::new(),
+		};
+
+		Ok(Self {
+			version: EXPORT_VERSION,
+			exported_at: Utc::now(),
+			embedding_model: None,
+			memories,
+			relationships,
+		})
+	}
+}
+
+/// `-->` would end the HTML comment early; escape it inside JSON strings
+fn comment_safe(json: &str) -> String {
+	json.replace("-->", "--\\u003e")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::memory::types::{
+		validate_namespace, MemoryMetadata, MemoryType, RelationshipType, DEFAULT_NAMESPACE,
+	};
+
+	fn sample() -> MemoryExport {
+		let first = Memory::new(
+			MemoryType::Architecture,
+			"Store opens tables lazily".to_string(),
+			"Tables are created on first use.\n\n## Not a heading of the export\nSee `Store::new` --> details."
+				.to_string(),
+			Some(MemoryMetadata {
+				tags: vec!["storage".to_string()],
+				related_files: vec!["src/store/mod.rs".to_string()],
+				importance: 0.8,
+				..Default::default()
+			}),
+		);
+		let second = Memory::new(
+			MemoryType::BugFix,
+			"Flush before exit".to_string(),
+			"Pending batches were lost on Ctrl-C.".to_string(),
+			None,
+		);
+		let relationship = MemoryRelationship {
+			id: "rel-1".to_string(),
+			source_id: second.id.clone(),
+			target_id: first.id.clone(),
+			relationship_type: RelationshipType::DependsOn,
+			strength: 0.7,
+			description: "flush relies on table layout".to_string(),
+			created_at: Utc::now(),
+		};
+		MemoryExport {
+			version: EXPORT_VERSION,
+			exported_at: Utc::now(),
+			embedding_model: None,
+			memories: vec![first, second]
+				.into_iter()
+				.map(|memory| ExportedMemory {
+					memory,
+					embedding: None,
+				})
+				.collect(),
+			relationships: vec![relationship],
+		}
+	}
+
+	#[test]
+	fn test_markdown_round_trip() {
+		let export = sample();
+		let markdown = export.render(ExportFormat::Md).unwrap();
+		assert!(markdown.contains("\n## Store opens tables lazily\n"));
+
+		let imported = MemoryExport::parse(&markdown, ExportFormat::Md).unwrap();
+		assert_eq!(imported.memories.len(), 2);
+		for (original, imported) in export.memories.iter().zip(&imported.memories) {
+			assert_eq!(
+				serde_json::to_value(&original.memory).unwrap(),
+				serde_json::to_value(&imported.memory).unwrap()
+			);
+		}
+		assert_eq!(imported.relationships.len(), 1);
+		assert_eq!(
+			imported.relationships[0].target_id,
+			export.memories[0].memory.id
+		);
+	}
+
+	#[test]
+	fn test_json_round_trip_keeps_embeddings() {
+		let mut export = sample();
+		export.embedding_model = Some("voyage:voyage-3.5-lite".to_string());
+		export.memories[0].embedding = Some(vec![0.25, -0.5]);
+
+		let json = export.render(ExportFormat::Json).unwrap();
+		let imported = MemoryExport::parse(&json, ExportFormat::Json).unwrap();
+		assert_eq!(imported.embedding_model, export.embedding_model);
+		assert_eq!(imported.memories[0].embedding, Some(vec![0.25, -0.5]));
+		assert_eq!(imported.memories[1].embedding, None);
+
+		export.version = EXPORT_VERSION + 1;
+		let json = export.render(ExportFormat::Json).unwrap();
+		assert!(MemoryExport::parse(&json, ExportFormat::Json).is_err());
+	}
+
+	#[test]
+	fn test_namespaces_survive_export() {
+		let mut export = sample();
+		export.memories[1].memory.namespace = "agent-a".to_string();
+		let markdown = export.render(ExportFormat::Md).unwrap();
+		let imported = MemoryExport::parse(&markdown, ExportFormat::Md).unwrap();
+		assert_eq!(imported.memories[1].memory.namespace, "agent-a");
+
+		// Exports written before namespaces land in the default one
+		let mut json = serde_json::to_value(&export).unwrap();
+		json["memories"][1]
+			.as_object_mut()
+			.unwrap()
+			.remove("namespace");
+		let imported: MemoryExport = serde_json::from_value(json).unwrap();
+		assert_eq!(imported.memories[1].memory.namespace, DEFAULT_NAMESPACE);
+
+		assert!(validate_namespace("session:42").is_ok());
+		assert!(validate_namespace("").is_err());
+		assert!(validate_namespace("a' OR 1=1").is_err());
+	}
+}
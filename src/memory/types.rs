@@ -16,6 +16,30 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// Namespace of memories stored without one, including all stored before
+/// namespaces existed
+pub const DEFAULT_NAMESPACE: &str = "default";
+
+fn default_namespace() -> String {
+	DEFAULT_NAMESPACE.to_string()
+}
+
+/// Check a namespace name: letters, digits, `-`, `_`, `.` and `:`, at most 64
+pub fn validate_namespace(namespace: &str) -> anyhow::Result<()> {
+	if namespace.is_empty()
+		|| namespace.len() > 64
+		|| !namespace
+			.chars()
+			.all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'))
+	{
+		return Err(anyhow::anyhow!(
+			"Invalid namespace '{}': use up to 64 letters, digits, '-', '_', '.' or ':'",
+			namespace
+		));
+	}
+	Ok(())
+}
+
 /// Types of memories that can be stored - unified for comprehensive coverage
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum MemoryType {
@@ -104,6 +128,12 @@ pub struct MemoryMetadata {
 	pub created_by: Option<String>,
 	/// Additional key-value metadata
 	pub custom_fields: HashMap<String, String>,
+	/// How many times `remember` returned this memory
+	#[serde(default)]
+	pub access_count: u32,
+	/// When `remember` last returned this memory
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub last_accessed: Option<DateTime<Utc>>,
 }
 
 impl Default for MemoryMetadata {
@@ -116,6 +146,8 @@ impl Default for MemoryMetadata {
 			confidence: 1.0,
 			created_by: None,
 			custom_fields: HashMap::new(),
+			access_count: 0,
+			last_accessed: None,
 		}
 	}
 }
@@ -133,6 +165,10 @@ pub struct Memory {
 	pub content: String,
 	/// Associated metadata
 	pub metadata: MemoryMetadata,
+	/// Agent or session the memory belongs to; searches stay within one
+	/// namespace unless they ask for more
+	#[serde(default = "default_namespace")]
+	pub namespace: String,
 	/// Creation timestamp
 	pub created_at: DateTime<Utc>,
 	/// Last update timestamp
@@ -157,6 +193,7 @@ impl Memory {
 			title,
 			content,
 			metadata: metadata.unwrap_or_default(),
+			namespace: default_namespace(),
 			created_at: now,
 			updated_at: now,
 			relevance_score: None,
@@ -209,6 +246,26 @@ impl Memory {
 		}
 	}
 
+	/// Fold the metadata of a duplicate into this memory: its tags and files
+	/// are added, the higher importance is kept and the commit is the newest
+	pub fn absorb(&mut self, duplicate: &MemoryMetadata) {
+		for tag in &duplicate.tags {
+			if !self.metadata.tags.contains(tag) {
+				self.metadata.tags.push(tag.clone());
+			}
+		}
+		for file in &duplicate.related_files {
+			if !self.metadata.related_files.contains(file) {
+				self.metadata.related_files.push(file.clone());
+			}
+		}
+		self.metadata.importance = self.metadata.importance.max(duplicate.importance);
+		if duplicate.git_commit.is_some() {
+			self.metadata.git_commit = duplicate.git_commit.clone();
+		}
+		self.updated_at = Utc::now();
+	}
+
 	/// Add a related file if it doesn't exist
 	pub fn add_related_file(&mut self, file_path: String) {
 		if !self.metadata.related_files.contains(&file_path) {
@@ -236,6 +293,8 @@ impl Memory {
 pub struct MemoryQuery {
 	/// Text query for semantic search
 	pub query_text: Option<String>,
+	/// Filter by namespaces; all namespaces when unset
+	pub namespaces: Option<Vec<String>>,
 	/// Filter by memory types
 	pub memory_types: Option<Vec<MemoryType>>,
 	/// Filter by tags (any of these tags)
@@ -0,0 +1,300 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! External plugins
+//!
+//! Executables listed under `[[plugins]]` extend indexing, search and the MCP
+//! server without changes to the crate. Each call starts the plugin, writes
+//! one JSON request to its stdin and reads one JSON reply from its stdout:
+//!
+//! - `process_chunk`: `{"hook", "path", "content"}`, answered with
+//!   `{"content"}` to index other content for the block, or `{}`
+//! - `filter_results`: `{"hook", "kind", "results"}`, answered with
+//!   `{"keep": [indices]}` listing the results to keep, best first, or `{}`
+//! - `list_tools` / `call_tool`: `{"hook"}` answered with `{"tools": [...]}`
+//!   MCP tool definitions, then `{"hook", "name", "arguments"}` answered with
+//!   `{"content"}` text
+//!
+//! A reply `{"error": "..."}` reports a failure. Failing plugins are skipped
+//! with a warning: blocks are indexed and results returned as if the plugin
+//! were not configured.
+
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::config::{Config, PluginConfig, PluginHook};
+use crate::indexer::chunk_processors::{ChunkProcessor, ProcessedChunk};
+
+/// A configured plugin executable
+#[derive(Debug, Clone)]
+pub struct Plugin {
+	config: PluginConfig,
+}
+
+impl Plugin {
+	pub fn new(config: PluginConfig) -> Self {
+		Self { config }
+	}
+
+	/// Configured plugins answering `hook`, in configuration order
+	pub fn with_hook(config: &Config, hook: PluginHook) -> Vec<Self> {
+		config
+			.plugins
+			.iter()
+			.filter(|plugin| plugin.hooks.contains(&hook))
+			.cloned()
+			.map(Self::new)
+			.collect()
+	}
+
+	pub fn name(&self) -> &str {
+		&self.config.name
+	}
+
+	/// Whether its tools are declared not to change anything
+	pub fn is_read_only(&self) -> bool {
+		self.config.read_only
+	}
+
+	/// Send `request` and wait for the reply, stopping the plugin when it
+	/// takes longer than its timeout
+	pub fn call(&self, request: &Value) -> Result<Value> {
+		let body = serde_json::to_vec(request)?;
+		let mut child = Command::new(&self.config.command)
+			.args(&self.config.args)
+			.stdin(Stdio::piped())
+			.stdout(Stdio::piped())
+			.stderr(Stdio::inherit())
+			.spawn()
+			.with_context(|| {
+				format!(
+					"Failed to start plugin '{}' ({})",
+					self.config.name, self.config.command
+				)
+			})?;
+
+		// Written and read on their own threads so a large request or reply
+		// cannot fill a pipe while we wait for the other
+		let mut stdin = child.stdin.take().expect("piped stdin");
+		let writer = std::thread::spawn(move || {
+			// A plugin that ignores its input may exit before reading it
+			let _ = stdin.write_all(&body);
+		});
+		let mut stdout = child.stdout.take().expect("piped stdout");
+		let reader = std::thread::spawn(move || {
+			let mut output = Vec::new();
+			let _ = stdout.read_to_end(&mut output);
+			output
+		});
+
+		let deadline = Instant::now() + Duration::from_secs(self.config.timeout);
+		let status = loop {
+			if let Some(status) = child.try_wait()? {
+				break status;
+			}
+			if Instant::now() >= deadline {
+				let _ = child.kill();
+				let _ = child.wait();
+				return Err(anyhow::anyhow!(
+					"Plugin '{}' did not reply within {}s",
+					self.config.name,
+					self.config.timeout
+				));
+			}
+			std::thread::sleep(Duration::from_millis(5));
+		};
+		let _ = writer.join();
+		let output = reader.join().unwrap_or_default();
+
+		if !status.success() {
+			return Err(anyhow::anyhow!(
+				"Plugin '{}' exited with {}",
+				self.config.name,
+				status
+			));
+		}
+		let reply: Value = serde_json::from_slice(&output)
+			.with_context(|| format!("Plugin '{}' replied with invalid JSON", self.config.name))?;
+		if let Some(error) = reply.get("error").and_then(Value::as_str) {
+			return Err(anyhow::anyhow!("Plugin '{}': {}", self.config.name, error));
+		}
+		Ok(reply)
+	}
+
+	/// [`Self::call`] on a blocking thread
+	pub async fn call_async(&self, request: Value) -> Result<Value> {
+		let plugin = self.clone();
+		tokio::task::spawn_blocking(move || plugin.call(&request))
+			.await
+			.map_err(|e| anyhow::anyhow!("Plugin '{}' call failed: {}", self.config.name, e))?
+	}
+}
+
+/// Chunk processor handing blocks of matching files to a `process_chunk`
+/// plugin
+pub struct PluginChunkProcessor {
+	plugin: Plugin,
+	/// Files whose blocks are processed; all when `None`
+	files: Option<GlobSet>,
+	/// Whether a failure was already reported
+	failed: AtomicBool,
+}
+
+impl PluginChunkProcessor {
+	pub fn new(plugin: Plugin) -> Result<Self> {
+		let files = if plugin.config.files.is_empty() {
+			None
+		} else {
+			let mut builder = GlobSetBuilder::new();
+			for pattern in &plugin.config.files {
+				let glob = Glob::new(pattern.trim_start_matches("./")).map_err(|e| {
+					anyhow::anyhow!(
+						"Invalid pattern '{}' in files of plugin '{}': {}",
+						pattern,
+						plugin.name(),
+						e
+					)
+				})?;
+				builder.add(glob);
+			}
+			Some(builder.build()?)
+		};
+		Ok(Self {
+			plugin,
+			files,
+			failed: AtomicBool::new(false),
+		})
+	}
+}
+
+impl ChunkProcessor for PluginChunkProcessor {
+	fn name(&self) -> &str {
+		self.plugin.name()
+	}
+
+	fn process(&self, content: &str) -> Option<ProcessedChunk> {
+		self.process_in("", content)
+	}
+
+	fn process_in(&self, path: &str, content: &str) -> Option<ProcessedChunk> {
+		if self
+			.files
+			.as_ref()
+			.is_some_and(|files| !files.is_match(path))
+		{
+			return None;
+		}
+
+		let request = json!({
+			"hook": "process_chunk",
+			"path": path,
+			"content": content,
+		});
+		match self.plugin.call(&request) {
+			Ok(reply) => reply
+				.get("content")
+				.and_then(Value::as_str)
+				.filter(|processed| *processed != content)
+				.map(|processed| ProcessedChunk {
+					content: processed.to_string(),
+					// Not a redaction; nothing to report
+					lines: Vec::new(),
+				}),
+			Err(e) => {
+				if !self.failed.swap(true, Ordering::Relaxed) {
+					eprintln!("Warning: {}; blocks are indexed unprocessed", e);
+				}
+				tracing::debug!("Plugin failed on a block of {}: {}", path, e);
+				None
+			}
+		}
+	}
+}
+
+/// Results kept by the `filter_results` plugins, in the order they give.
+/// `kind` is "code", "text" or "docs".
+pub async fn filter_results<T: Serialize>(
+	plugins: &[Plugin],
+	kind: &str,
+	mut results: Vec<T>,
+) -> Vec<T> {
+	for plugin in plugins {
+		if results.is_empty() {
+			break;
+		}
+		let request = json!({
+			"hook": "filter_results",
+			"kind": kind,
+			"results": results,
+		});
+		let keep = plugin
+			.call_async(request)
+			.await
+			.and_then(|reply| kept_indices(&reply, results.len()));
+		match keep {
+			Ok(Some(indices)) => {
+				let mut slots: Vec<Option<T>> = results.into_iter().map(Some).collect();
+				results = indices
+					.into_iter()
+					.filter_map(|index| slots[index].take())
+					.collect();
+			}
+			Ok(None) => {}
+			Err(e) => eprintln!("Warning: {}; results are returned unfiltered", e),
+		}
+	}
+	results
+}
+
+/// Indices listed in the `keep` field of a `filter_results` reply, checked
+/// against the number of results sent
+fn kept_indices(reply: &Value, len: usize) -> Result<Option<Vec<usize>>> {
+	let Some(keep) = reply.get("keep") else {
+		return Ok(None);
+	};
+	let indices: Vec<usize> = serde_json::from_value(keep.clone())
+		.context("Plugin reply 'keep' must be a list of result indices")?;
+	if let Some(index) = indices.iter().find(|&&index| index >= len) {
+		return Err(anyhow::anyhow!(
+			"Plugin kept result {} of only {}",
+			index,
+			len
+		));
+	}
+	Ok(Some(indices))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_kept_indices() {
+		assert_eq!(kept_indices(&json!({}), 3).unwrap(), None);
+		assert_eq!(
+			kept_indices(&json!({"keep": [2, 0]}), 3).unwrap(),
+			Some(vec![2, 0])
+		);
+		assert_eq!(kept_indices(&json!({"keep": []}), 3).unwrap(), Some(vec![]));
+		assert!(kept_indices(&json!({"keep": [3]}), 3).is_err());
+		assert!(kept_indices(&json!({"keep": "all"}), 3).is_err());
+	}
+}
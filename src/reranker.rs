@@ -29,6 +29,10 @@
 use crate::store::{CodeBlock, DocumentBlock, TextBlock};
 use std::collections::HashMap;
 
+pub mod cross_encoder;
+
+pub use cross_encoder::CrossEncoder;
+
 /// Reranking strategies for improving search results
 pub struct Reranker;
 
@@ -0,0 +1,207 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Offline reranking with an ONNX cross-encoder run by FastEmbed
+//!
+//! A cross-encoder reads the query and a result together, which ranks far
+//! better than comparing their embeddings, at the cost of one model pass per
+//! result. Models such as `BAAI/bge-reranker-base` are downloaded on first
+//! use into the FastEmbed cache shared with local embedding models, so
+//! reranking needs no API key.
+
+use anyhow::Result;
+
+use crate::store::{CodeBlock, DocumentBlock, TextBlock};
+
+/// Provider prefix of cross-encoder models in `search.rerank_model`
+const PROVIDER: &str = "fastembed";
+
+/// Results scored per model pass
+#[cfg(feature = "fastembed")]
+const BATCH_SIZE: usize = 32;
+
+/// Longest part of a result handed to the model; cross-encoders only read
+/// the first few hundred tokens anyway
+const MAX_DOCUMENT_CHARS: usize = 4000;
+
+pub struct CrossEncoder {
+	#[cfg(feature = "fastembed")]
+	model: fastembed::TextRerank,
+}
+
+impl CrossEncoder {
+	/// Load `fastembed:<model>`, downloading it on first use
+	pub fn load(model: &str) -> Result<Self> {
+		let model = match model.split_once(':') {
+			Some((PROVIDER, model)) => model,
+			Some((provider, _)) => {
+				return Err(anyhow::anyhow!(
+					"Unsupported reranker provider '{}': cross-encoders run locally with '{}:<model>'",
+					provider,
+					PROVIDER
+				))
+			}
+			None => model,
+		};
+		Self::load_fastembed(model)
+	}
+
+	#[cfg(feature = "fastembed")]
+	fn load_fastembed(model: &str) -> Result<Self> {
+		use anyhow::Context;
+		use fastembed::{RerankInitOptions, TextRerank};
+
+		let supported = TextRerank::list_supported_models();
+		let info = supported
+			.iter()
+			.find(|info| info.model_code.eq_ignore_ascii_case(model))
+			.ok_or_else(|| {
+				let codes: Vec<&str> = supported
+					.iter()
+					.map(|info| info.model_code.as_str())
+					.collect();
+				anyhow::anyhow!(
+					"Unsupported cross-encoder '{}'. Supported models: {}",
+					model,
+					codes.join(", ")
+				)
+			})?;
+
+		// Same system-wide cache as FastEmbed embedding models
+		let cache_dir = crate::storage::get_fastembed_cache_dir()
+			.context("Failed to get FastEmbed cache directory")?;
+		let model = TextRerank::try_new(
+			RerankInitOptions::new(info.model.clone())
+				.with_show_download_progress(true)
+				.with_cache_dir(cache_dir),
+		)
+		.context("Failed to initialize cross-encoder")?;
+
+		Ok(Self { model })
+	}
+
+	#[cfg(not(feature = "fastembed"))]
+	fn load_fastembed(_model: &str) -> Result<Self> {
+		Err(anyhow::anyhow!(
+			"Cross-encoder reranking needs FastEmbed support. Please rebuild with --features fastembed"
+		))
+	}
+
+	pub fn rerank_code_blocks(
+		&self,
+		blocks: Vec<CodeBlock>,
+		query: &str,
+	) -> Result<Vec<CodeBlock>> {
+		self.rerank(
+			blocks,
+			query,
+			|block| format!("{}\n{}", block.path, block.content),
+			|block, distance| block.distance = Some(distance),
+		)
+	}
+
+	pub fn rerank_document_blocks(
+		&self,
+		blocks: Vec<DocumentBlock>,
+		query: &str,
+	) -> Result<Vec<DocumentBlock>> {
+		self.rerank(
+			blocks,
+			query,
+			|block| format!("{}\n{}", block.title, block.content),
+			|block, distance| block.distance = Some(distance),
+		)
+	}
+
+	pub fn rerank_text_blocks(
+		&self,
+		blocks: Vec<TextBlock>,
+		query: &str,
+	) -> Result<Vec<TextBlock>> {
+		self.rerank(
+			blocks,
+			query,
+			|block| format!("{}\n{}", block.path, block.content),
+			|block, distance| block.distance = Some(distance),
+		)
+	}
+
+	/// Order `blocks` by the model's score for `query`, closest first. The
+	/// score replaces the distance of each block, as `1 - sigmoid(score)`.
+	fn rerank<T>(
+		&self,
+		blocks: Vec<T>,
+		query: &str,
+		text: impl Fn(&T) -> String,
+		set_distance: impl Fn(&mut T, f32),
+	) -> Result<Vec<T>> {
+		if blocks.is_empty() {
+			return Ok(blocks);
+		}
+		let documents: Vec<String> = blocks
+			.iter()
+			.map(|block| text(block).chars().take(MAX_DOCUMENT_CHARS).collect())
+			.collect();
+		let scores = self.scores(query, &documents)?;
+
+		let mut scored: Vec<(f32, T)> = scores.into_iter().zip(blocks).collect();
+		scored.sort_by(|(a, _), (b, _)| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
+		Ok(scored
+			.into_iter()
+			.map(|(score, mut block)| {
+				set_distance(&mut block, score_to_distance(score));
+				block
+			})
+			.collect())
+	}
+
+	/// Score of every document, in document order
+	#[cfg(feature = "fastembed")]
+	fn scores(&self, query: &str, documents: &[String]) -> Result<Vec<f32>> {
+		let documents: Vec<&str> = documents.iter().map(String::as_str).collect();
+		let results = self
+			.model
+			.rerank(query, documents, false, Some(BATCH_SIZE))?;
+		let mut scores = vec![f32::MIN; results.len()];
+		for result in results {
+			if let Some(score) = scores.get_mut(result.index) {
+				*score = result.score;
+			}
+		}
+		Ok(scores)
+	}
+
+	#[cfg(not(feature = "fastembed"))]
+	fn scores(&self, _query: &str, _documents: &[String]) -> Result<Vec<f32>> {
+		Self::load_fastembed("").map(|_| Vec::new())
+	}
+}
+
+/// Cross-encoders output logits; distances are between 0 and 1, lower first
+fn score_to_distance(score: f32) -> f32 {
+	1.0 - 1.0 / (1.0 + (-score).exp())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_score_to_distance() {
+		assert!((score_to_distance(0.0) - 0.5).abs() < 1e-6);
+		assert!(score_to_distance(8.0) < 0.01);
+		assert!(score_to_distance(-8.0) > 0.99);
+		assert!(score_to_distance(2.0) < score_to_distance(1.0));
+	}
+}
@@ -30,6 +30,8 @@ use parking_lot::RwLock;
 use std::path::PathBuf;
 use std::sync::Arc;
 
+use crate::indexer::file_policy::SkippedFiles;
+
 #[derive(Default)]
 pub struct IndexState {
 	pub current_directory: PathBuf,
@@ -40,6 +42,9 @@ pub struct IndexState {
 	pub indexing_complete: bool,
 	pub status_message: String,
 	pub force_reindex: bool,
+	// Current phase and file, as reported in the progress file
+	pub phase: String,
+	pub current_file: Option<String>,
 	// GraphRAG state tracking
 	pub graphrag_enabled: bool,
 	pub graphrag_blocks: usize,
@@ -47,6 +52,15 @@ pub struct IndexState {
 	pub counting_files: bool,
 	// Quiet mode for MCP server (no console output)
 	pub quiet_mode: bool,
+	// Secret scanning report: "path:line (kind)" per finding, and blocks left out
+	pub secret_findings: Vec<String>,
+	pub secret_skipped_blocks: usize,
+	// Blocks kept out by embedding.path_policies: "path (destination)" per block
+	pub policy_violations: Vec<String>,
+	// Files kept out by index.max_file_size, skip_binary and the extension lists
+	pub file_policy_skips: SkippedFiles,
+	// Directories indexed despite .gitignore and .noindex (watch --include-extra)
+	pub extra_dirs: Vec<String>,
 }
 
 pub type SharedState = Arc<RwLock<IndexState>>;
@@ -121,7 +121,7 @@ fn get_git_remote_url(project_path: &Path) -> Result<String> {
 /// Normalize git URL to be consistent regardless of protocol
 /// e.g., https://github.com/user/repo.git and git@github.com:user/repo.git
 /// both become github.com/user/repo
-fn normalize_git_url(url: &str) -> String {
+pub fn normalize_git_url(url: &str) -> String {
 	let url = url.trim();
 
 	// Remove .git suffix if present
@@ -0,0 +1,338 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::Result;
+
+use super::{CodeBlock, DocumentBlock, TextBlock};
+
+/// Number of content hash characters kept in an anchor
+pub const ANCHOR_HASH_LEN: usize = 12;
+
+/// Stable citation for an indexed block or graph node.
+///
+/// Rendered as `path#key@hash`: the block ID (`path#key`) stays the same
+/// across reindexing while the block keeps its primary symbol, and the
+/// content hash tells whether the cited content is still current. `key` is the
+/// primary symbol when the block declares one, `L<line>` otherwise, and absent
+/// for GraphRAG nodes (`path@hash`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Anchor {
+	path: String,
+	key: Option<String>,
+	hash: String,
+}
+
+/// Which part of a file an anchor's key points at
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnchorKey<'a> {
+	Symbol(&'a str),
+	Line(usize),
+}
+
+impl Anchor {
+	pub fn for_code_block(block: &CodeBlock) -> Self {
+		let key = block
+			.symbols
+			.iter()
+			.find(|symbol| is_valid_key(symbol))
+			.cloned()
+			.unwrap_or_else(|| line_key(block.start_line));
+		Self::new(&block.path, Some(key), &block.hash)
+	}
+
+	pub fn for_text_block(block: &TextBlock) -> Self {
+		Self::new(&block.path, Some(line_key(block.start_line)), &block.hash)
+	}
+
+	pub fn for_document_block(block: &DocumentBlock) -> Self {
+		Self::new(&block.path, Some(line_key(block.start_line)), &block.hash)
+	}
+
+	pub fn for_node(path: &str, hash: &str) -> Self {
+		Self::new(path, None, hash)
+	}
+
+	fn new(path: &str, key: Option<String>, hash: &str) -> Self {
+		Self {
+			path: path.to_string(),
+			key,
+			hash: hash.chars().take(ANCHOR_HASH_LEN).collect(),
+		}
+	}
+
+	pub fn parse(anchor: &str) -> Result<Self> {
+		let invalid = || {
+			anyhow::anyhow!(
+				"Invalid anchor '{}': expected 'path#symbol@hash', 'path#L<line>@hash' or 'path@hash'",
+				anchor
+			)
+		};
+
+		let (id, hash) = anchor.trim().rsplit_once('@').ok_or_else(invalid)?;
+		if hash.is_empty() || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+			return Err(invalid());
+		}
+
+		let (path, key) = match id.rsplit_once('#') {
+			Some((path, key)) if is_valid_key(key) => (path, Some(key.to_string())),
+			Some(_) => return Err(invalid()),
+			None => (id, None),
+		};
+		if path.is_empty() || path.contains('\'') {
+			return Err(invalid());
+		}
+
+		Ok(Self {
+			path: path.to_string(),
+			key,
+			hash: hash.to_ascii_lowercase(),
+		})
+	}
+
+	pub fn path(&self) -> &str {
+		&self.path
+	}
+
+	/// The same anchor for a file that was renamed to `path`
+	pub fn with_path(&self, path: &str) -> Self {
+		Self {
+			path: path.to_string(),
+			..self.clone()
+		}
+	}
+
+	pub fn key(&self) -> Option<AnchorKey<'_>> {
+		let key = self.key.as_deref()?;
+		match key.strip_prefix('L').and_then(|line| line.parse().ok()) {
+			Some(line) if line > 0 => Some(AnchorKey::Line(line)),
+			_ => Some(AnchorKey::Symbol(key)),
+		}
+	}
+
+	/// Whether a full stored hash is the one this anchor was issued for
+	pub fn matches_hash(&self, hash: &str) -> bool {
+		hash.starts_with(&self.hash)
+	}
+}
+
+impl std::fmt::Display for Anchor {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match &self.key {
+			Some(key) => write!(f, "{}#{}@{}", self.path, key, self.hash),
+			None => write!(f, "{}@{}", self.path, self.hash),
+		}
+	}
+}
+
+/// Indexed block of any kind an anchor can point at
+#[derive(Debug, Clone)]
+pub enum AnchoredBlock {
+	Code(CodeBlock),
+	Text(TextBlock),
+	Document(DocumentBlock),
+}
+
+impl AnchoredBlock {
+	pub fn anchor(&self) -> Anchor {
+		match self {
+			Self::Code(block) => Anchor::for_code_block(block),
+			Self::Text(block) => Anchor::for_text_block(block),
+			Self::Document(block) => Anchor::for_document_block(block),
+		}
+	}
+
+	pub fn content(&self) -> &str {
+		match self {
+			Self::Code(block) => &block.content,
+			Self::Text(block) => &block.content,
+			Self::Document(block) => &block.content,
+		}
+	}
+
+	/// 0-indexed inclusive line range
+	pub fn lines(&self) -> (usize, usize) {
+		match self {
+			Self::Code(block) => (block.start_line, block.end_line),
+			Self::Text(block) => (block.start_line, block.end_line),
+			Self::Document(block) => (block.start_line, block.end_line),
+		}
+	}
+
+	pub fn hash(&self) -> &str {
+		match self {
+			Self::Code(block) => &block.hash,
+			Self::Text(block) => &block.hash,
+			Self::Document(block) => &block.hash,
+		}
+	}
+
+	fn declares(&self, symbol: &str) -> bool {
+		matches!(self, Self::Code(block) if block.symbols.iter().any(|s| s == symbol))
+	}
+}
+
+/// Outcome of resolving an anchor against the current index
+#[derive(Debug, Clone)]
+pub struct ResolvedAnchor {
+	pub block: AnchoredBlock,
+	/// `false` when the cited content changed and the block was found by its key
+	pub exact: bool,
+}
+
+/// Pick the block an anchor refers to among the blocks currently indexed for its path.
+///
+/// The content hash wins; otherwise the block declaring the anchor's symbol, or
+/// the block covering (or starting nearest to) the anchor's line.
+pub fn resolve(anchor: &Anchor, candidates: Vec<AnchoredBlock>) -> Option<ResolvedAnchor> {
+	if let Some(block) = candidates
+		.iter()
+		.find(|block| anchor.matches_hash(block.hash()))
+	{
+		return Some(ResolvedAnchor {
+			block: block.clone(),
+			exact: true,
+		});
+	}
+
+	let block = match anchor.key()? {
+		AnchorKey::Symbol(symbol) => candidates.into_iter().find(|block| block.declares(symbol)),
+		AnchorKey::Line(line) => {
+			let line = line - 1;
+			candidates.into_iter().min_by_key(|block| {
+				let (start, end) = block.lines();
+				if (start..=end).contains(&line) {
+					0
+				} else {
+					start.abs_diff(line)
+				}
+			})
+		}
+	}?;
+
+	Some(ResolvedAnchor {
+		block,
+		exact: false,
+	})
+}
+
+/// 1-indexed line key for blocks without a symbol
+fn line_key(start_line: usize) -> String {
+	format!("L{}", start_line + 1)
+}
+
+fn is_valid_key(key: &str) -> bool {
+	!key.is_empty()
+		&& !key
+			.chars()
+			.any(|c| c.is_whitespace() || matches!(c, '#' | '@' | '\''))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn code_block(symbols: &[&str], start_line: usize) -> CodeBlock {
+		CodeBlock {
+			path: "src/config.rs".to_string(),
+			language: "rust".to_string(),
+			content: String::new(),
+			symbols: symbols.iter().map(|s| s.to_string()).collect(),
+			start_line,
+			end_line: start_line + 10,
+			hash: "3fa9c1d2e4b5a6978877".to_string(),
+			spans: Vec::new(),
+			provenance: None,
+			distance: None,
+		}
+	}
+
+	#[test]
+	fn test_code_block_anchor_uses_primary_symbol() {
+		let anchor = Anchor::for_code_block(&code_block(&["parse_config", "load"], 41));
+		assert_eq!(
+			anchor.to_string(),
+			"src/config.rs#parse_config@3fa9c1d2e4b5"
+		);
+		assert_eq!(anchor.key(), Some(AnchorKey::Symbol("parse_config")));
+
+		let anchor = Anchor::for_code_block(&code_block(&[], 41));
+		assert_eq!(anchor.to_string(), "src/config.rs#L42@3fa9c1d2e4b5");
+		assert_eq!(anchor.key(), Some(AnchorKey::Line(42)));
+	}
+
+	#[test]
+	fn test_parse_round_trip() {
+		for text in [
+			"src/config.rs#parse_config@3fa9c1d2e4b5",
+			"docs/a#b.md#L7@abc123",
+			"src/main.rs@0123456789ab",
+		] {
+			let anchor = Anchor::parse(text).unwrap();
+			assert_eq!(anchor.to_string(), text);
+		}
+
+		let anchor = Anchor::parse("src/main.rs@0123456789ab").unwrap();
+		assert_eq!(anchor.path(), "src/main.rs");
+		assert!(anchor.key().is_none());
+		assert!(anchor.matches_hash("0123456789abcdef"));
+		assert!(!anchor.matches_hash("1123456789abcdef"));
+	}
+
+	#[test]
+	fn test_resolve_falls_back_to_symbol_and_line() {
+		let anchor = Anchor::for_code_block(&code_block(&["parse_config"], 41));
+
+		let mut moved = code_block(&["parse_config"], 60);
+		moved.hash = "ffff".to_string();
+		let mut other = code_block(&["load"], 10);
+		other.hash = "eeee".to_string();
+		let resolved = resolve(
+			&anchor,
+			vec![AnchoredBlock::Code(other), AnchoredBlock::Code(moved)],
+		)
+		.unwrap();
+		assert!(!resolved.exact);
+		assert_eq!(resolved.block.lines().0, 60);
+
+		let resolved = resolve(
+			&anchor,
+			vec![AnchoredBlock::Code(code_block(&["parse_config"], 41))],
+		)
+		.unwrap();
+		assert!(resolved.exact);
+
+		let anchor = Anchor::parse("src/config.rs#L45@0000").unwrap();
+		let mut near = code_block(&[], 41);
+		near.hash = "aaaa".to_string();
+		let mut far = code_block(&[], 100);
+		far.hash = "bbbb".to_string();
+		let resolved = resolve(
+			&anchor,
+			vec![AnchoredBlock::Code(far), AnchoredBlock::Code(near)],
+		)
+		.unwrap();
+		assert_eq!(resolved.block.lines().0, 41);
+
+		assert!(resolve(&Anchor::parse("src/config.rs@0000").unwrap(), Vec::new()).is_none());
+	}
+
+	#[test]
+	fn test_parse_rejects_malformed_anchors() {
+		assert!(Anchor::parse("src/main.rs").is_err());
+		assert!(Anchor::parse("src/main.rs@xyz").is_err());
+		assert!(Anchor::parse("@0123").is_err());
+		assert!(Anchor::parse("src/main.rs#@0123").is_err());
+		assert!(Anchor::parse("it's.rs@0123").is_err());
+	}
+}
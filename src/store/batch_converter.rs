@@ -14,16 +14,30 @@
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::sync::Arc;
-use uuid::Uuid;
 
 // Arrow imports
-use arrow::array::{Array, FixedSizeListArray, Float32Array, ListArray, StringArray, UInt32Array};
+use arrow::array::{
+	Array, ArrayRef, FixedSizeListArray, Float32Array, ListArray, StringArray, UInt32Array,
+};
 use arrow::datatypes::{DataType, Field, Schema};
 use arrow::record_batch::RecordBatch;
 
+use crate::store::provenance::{provenance_arrays, provenance_fields, ProvenanceColumns};
 use crate::store::{CodeBlock, DocumentBlock, TextBlock};
 
+/// Row id derived from the row's identifying fields rather than random, so
+/// indexing the same content yields the same ids on every machine
+pub(crate) fn row_id(parts: &[&str]) -> String {
+	let mut hasher = Sha256::new();
+	for part in parts {
+		hasher.update(part.as_bytes());
+		hasher.update([0]);
+	}
+	format!("{:x}", hasher.finalize())[..32].to_string()
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct BatchConverter {
 	vector_dim: usize,
@@ -64,27 +78,36 @@ impl BatchConverter {
 		}
 
 		// Create schema
-		let schema = Arc::new(Schema::new(vec![
-			Field::new("id", DataType::Utf8, false),
-			Field::new("path", DataType::Utf8, false),
-			Field::new("language", DataType::Utf8, false),
-			Field::new("content", DataType::Utf8, false),
-			Field::new("symbols", DataType::Utf8, true), // Storing serialized JSON of symbols
-			Field::new("start_line", DataType::UInt32, false),
-			Field::new("end_line", DataType::UInt32, false),
-			Field::new("hash", DataType::Utf8, false),
-			Field::new(
-				"embedding",
-				DataType::FixedSizeList(
-					Arc::new(Field::new("item", DataType::Float32, true)),
-					self.vector_dim as i32,
+		let schema = Arc::new(Schema::new(
+			vec![
+				Field::new("id", DataType::Utf8, false),
+				Field::new("path", DataType::Utf8, false),
+				Field::new("language", DataType::Utf8, false),
+				Field::new("content", DataType::Utf8, false),
+				Field::new("symbols", DataType::Utf8, true), // Storing serialized JSON of symbols
+				Field::new("start_line", DataType::UInt32, false),
+				Field::new("end_line", DataType::UInt32, false),
+				Field::new("hash", DataType::Utf8, false),
+				Field::new(
+					"embedding",
+					DataType::FixedSizeList(
+						Arc::new(Field::new("item", DataType::Float32, true)),
+						self.vector_dim as i32,
+					),
+					true,
 				),
-				true,
-			),
-		]));
+				Field::new("spans", DataType::Utf8, true), // Storing serialized JSON of comment/string spans
+			]
+			.into_iter()
+			.chain(provenance_fields())
+			.collect::<Vec<_>>(),
+		));
 
 		// Create arrays
-		let ids: Vec<String> = blocks.iter().map(|_| Uuid::new_v4().to_string()).collect();
+		let ids: Vec<String> = blocks
+			.iter()
+			.map(|b| row_id(&[&b.path, &b.start_line.to_string(), &b.hash]))
+			.collect();
 		let paths: Vec<&str> = blocks.iter().map(|b| b.path.as_str()).collect();
 		let languages: Vec<&str> = blocks.iter().map(|b| b.language.as_str()).collect();
 		let contents: Vec<&str> = blocks.iter().map(|b| b.content.as_str()).collect();
@@ -92,6 +115,10 @@ impl BatchConverter {
 			.iter()
 			.map(|b| serde_json::to_string(&b.symbols).unwrap_or_default())
 			.collect();
+		let spans: Vec<String> = blocks
+			.iter()
+			.map(|b| serde_json::to_string(&b.spans).unwrap_or_default())
+			.collect();
 		let start_lines: Vec<u32> = blocks.iter().map(|b| b.start_line as u32).collect();
 		let end_lines: Vec<u32> = blocks.iter().map(|b| b.end_line as u32).collect();
 		let hashes: Vec<&str> = blocks.iter().map(|b| b.hash.as_str()).collect();
@@ -147,7 +174,7 @@ impl BatchConverter {
 		let batch = RecordBatch::try_new(
 			schema,
 			vec![
-				Arc::new(StringArray::from(ids)),
+				Arc::new(StringArray::from(ids)) as ArrayRef,
 				Arc::new(StringArray::from(paths)),
 				Arc::new(StringArray::from(languages)),
 				Arc::new(StringArray::from(contents)),
@@ -156,7 +183,13 @@ impl BatchConverter {
 				Arc::new(UInt32Array::from(end_lines)),
 				Arc::new(StringArray::from(hashes)),
 				Arc::new(embedding_array),
-			],
+				Arc::new(StringArray::from(spans)),
+			]
+			.into_iter()
+			.chain(provenance_arrays(
+				blocks.iter().map(|b| b.provenance.as_ref()),
+			))
+			.collect(),
 		)?;
 
 		Ok(batch)
@@ -192,26 +225,34 @@ impl BatchConverter {
 		}
 
 		// Create schema matching the actual TextBlock structure
-		let schema = Arc::new(Schema::new(vec![
-			Field::new("id", DataType::Utf8, false),
-			Field::new("path", DataType::Utf8, false),
-			Field::new("language", DataType::Utf8, false),
-			Field::new("content", DataType::Utf8, false),
-			Field::new("start_line", DataType::UInt32, false),
-			Field::new("end_line", DataType::UInt32, false),
-			Field::new("hash", DataType::Utf8, false),
-			Field::new(
-				"embedding",
-				DataType::FixedSizeList(
-					Arc::new(Field::new("item", DataType::Float32, true)),
-					self.vector_dim as i32,
+		let schema = Arc::new(Schema::new(
+			vec![
+				Field::new("id", DataType::Utf8, false),
+				Field::new("path", DataType::Utf8, false),
+				Field::new("language", DataType::Utf8, false),
+				Field::new("content", DataType::Utf8, false),
+				Field::new("start_line", DataType::UInt32, false),
+				Field::new("end_line", DataType::UInt32, false),
+				Field::new("hash", DataType::Utf8, false),
+				Field::new(
+					"embedding",
+					DataType::FixedSizeList(
+						Arc::new(Field::new("item", DataType::Float32, true)),
+						self.vector_dim as i32,
+					),
+					true,
 				),
-				true,
-			),
-		]));
+			]
+			.into_iter()
+			.chain(provenance_fields())
+			.collect::<Vec<_>>(),
+		));
 
 		// Create arrays
-		let ids: Vec<String> = blocks.iter().map(|_| Uuid::new_v4().to_string()).collect();
+		let ids: Vec<String> = blocks
+			.iter()
+			.map(|b| row_id(&[&b.path, &b.start_line.to_string(), &b.hash]))
+			.collect();
 		let paths: Vec<&str> = blocks.iter().map(|b| b.path.as_str()).collect();
 		let languages: Vec<&str> = blocks.iter().map(|b| b.language.as_str()).collect();
 		let contents: Vec<&str> = blocks.iter().map(|b| b.content.as_str()).collect();
@@ -269,7 +310,7 @@ impl BatchConverter {
 		let batch = RecordBatch::try_new(
 			schema,
 			vec![
-				Arc::new(StringArray::from(ids)),
+				Arc::new(StringArray::from(ids)) as ArrayRef,
 				Arc::new(StringArray::from(paths)),
 				Arc::new(StringArray::from(languages)),
 				Arc::new(StringArray::from(contents)),
@@ -277,7 +318,12 @@ impl BatchConverter {
 				Arc::new(UInt32Array::from(end_lines)),
 				Arc::new(StringArray::from(hashes)),
 				Arc::new(embedding_array),
-			],
+			]
+			.into_iter()
+			.chain(provenance_arrays(
+				blocks.iter().map(|b| b.provenance.as_ref()),
+			))
+			.collect(),
 		)?;
 
 		Ok(batch)
@@ -313,32 +359,40 @@ impl BatchConverter {
 		}
 
 		// Create schema matching the actual DocumentBlock structure
-		let schema = Arc::new(Schema::new(vec![
-			Field::new("id", DataType::Utf8, false),
-			Field::new("path", DataType::Utf8, false),
-			Field::new("title", DataType::Utf8, false),
-			Field::new("content", DataType::Utf8, false),
-			Field::new(
-				"context",
-				DataType::List(Arc::new(Field::new("item", DataType::Utf8, true))),
-				true,
-			),
-			Field::new("level", DataType::UInt32, false),
-			Field::new("start_line", DataType::UInt32, false),
-			Field::new("end_line", DataType::UInt32, false),
-			Field::new("hash", DataType::Utf8, false),
-			Field::new(
-				"embedding",
-				DataType::FixedSizeList(
-					Arc::new(Field::new("item", DataType::Float32, true)),
-					self.vector_dim as i32,
+		let schema = Arc::new(Schema::new(
+			vec![
+				Field::new("id", DataType::Utf8, false),
+				Field::new("path", DataType::Utf8, false),
+				Field::new("title", DataType::Utf8, false),
+				Field::new("content", DataType::Utf8, false),
+				Field::new(
+					"context",
+					DataType::List(Arc::new(Field::new("item", DataType::Utf8, true))),
+					true,
 				),
-				true,
-			),
-		]));
+				Field::new("level", DataType::UInt32, false),
+				Field::new("start_line", DataType::UInt32, false),
+				Field::new("end_line", DataType::UInt32, false),
+				Field::new("hash", DataType::Utf8, false),
+				Field::new(
+					"embedding",
+					DataType::FixedSizeList(
+						Arc::new(Field::new("item", DataType::Float32, true)),
+						self.vector_dim as i32,
+					),
+					true,
+				),
+			]
+			.into_iter()
+			.chain(provenance_fields())
+			.collect::<Vec<_>>(),
+		));
 
 		// Create arrays
-		let ids: Vec<String> = blocks.iter().map(|_| Uuid::new_v4().to_string()).collect();
+		let ids: Vec<String> = blocks
+			.iter()
+			.map(|b| row_id(&[&b.path, &b.start_line.to_string(), &b.hash]))
+			.collect();
 		let paths: Vec<&str> = blocks.iter().map(|b| b.path.as_str()).collect();
 		let titles: Vec<&str> = blocks.iter().map(|b| b.title.as_str()).collect();
 		let contents: Vec<&str> = blocks.iter().map(|b| b.content.as_str()).collect();
@@ -416,7 +470,7 @@ impl BatchConverter {
 		let batch = RecordBatch::try_new(
 			schema,
 			vec![
-				Arc::new(StringArray::from(ids)),
+				Arc::new(StringArray::from(ids)) as ArrayRef,
 				Arc::new(StringArray::from(paths)),
 				Arc::new(StringArray::from(titles)),
 				Arc::new(StringArray::from(contents)),
@@ -426,12 +480,46 @@ impl BatchConverter {
 				Arc::new(UInt32Array::from(end_lines)),
 				Arc::new(StringArray::from(hashes)),
 				Arc::new(embedding_array),
-			],
+			]
+			.into_iter()
+			.chain(provenance_arrays(
+				blocks.iter().map(|b| b.provenance.as_ref()),
+			))
+			.collect(),
 		)?;
 
 		Ok(batch)
 	}
 
+	// Extract the stored embedding vectors of a RecordBatch, one per row
+	pub fn batch_to_embeddings(&self, batch: &RecordBatch) -> Result<Vec<Vec<f32>>> {
+		let embedding_array = batch
+			.column_by_name("embedding")
+			.ok_or_else(|| anyhow::anyhow!("embedding column not found"))?
+			.as_any()
+			.downcast_ref::<FixedSizeListArray>()
+			.ok_or_else(|| anyhow::anyhow!("embedding column is not a FixedSizeListArray"))?;
+		let dim = embedding_array.value_length() as usize;
+		if dim != self.vector_dim {
+			return Err(anyhow::anyhow!(
+				"Stored embeddings have dimension {} but expected {}",
+				dim,
+				self.vector_dim
+			));
+		}
+
+		(0..batch.num_rows())
+			.map(|row| {
+				embedding_array
+					.value(row)
+					.as_any()
+					.downcast_ref::<Float32Array>()
+					.map(|values| values.values().to_vec())
+					.ok_or_else(|| anyhow::anyhow!("embedding values are not Float32"))
+			})
+			.collect()
+	}
+
 	// Convert a RecordBatch to a Vec of CodeBlocks
 	pub fn batch_to_code_blocks(
 		&self,
@@ -502,6 +590,11 @@ impl BatchConverter {
 			.and_then(|col| col.as_any().downcast_ref::<Float32Array>())
 			.map(|arr| (0..arr.len()).map(|i| arr.value(i)).collect::<Vec<f32>>())
 			.unwrap_or_default();
+		let provenance = ProvenanceColumns::from_batch(batch);
+		// Blocks indexed before spans were recorded have no value (or no column)
+		let spans_array = batch
+			.column_by_name("spans")
+			.and_then(|col| col.as_any().downcast_ref::<StringArray>());
 		for i in 0..batch.num_rows() {
 			// Parse symbols JSON
 			let symbols_json = symbols_array.value(i);
@@ -510,6 +603,10 @@ impl BatchConverter {
 			} else {
 				serde_json::from_str(symbols_json).unwrap_or_default()
 			};
+			let spans = spans_array
+				.filter(|array| !array.is_null(i))
+				.and_then(|array| serde_json::from_str(array.value(i)).ok())
+				.unwrap_or_default();
 
 			let code_block = CodeBlock {
 				path: path_array.value(i).to_string(),
@@ -519,6 +616,8 @@ impl BatchConverter {
 				start_line: start_line_array.value(i) as usize,
 				end_line: end_line_array.value(i) as usize,
 				hash: hash_array.value(i).to_string(),
+				spans,
+				provenance: provenance.as_ref().and_then(|p| p.get(i)),
 				distance: distance_array.get(i).copied(),
 			};
 
@@ -586,6 +685,8 @@ impl BatchConverter {
 			.map(|arr| (0..arr.len()).map(|i| arr.value(i)).collect::<Vec<f32>>())
 			.unwrap_or_default();
 
+		let provenance = ProvenanceColumns::from_batch(batch);
+
 		// Process each row
 		for i in 0..batch.num_rows() {
 			let text_block = TextBlock {
@@ -595,6 +696,7 @@ impl BatchConverter {
 				start_line: start_line_array.value(i) as usize,
 				end_line: end_line_array.value(i) as usize,
 				hash: hash_array.value(i).to_string(),
+				provenance: provenance.as_ref().and_then(|p| p.get(i)),
 				distance: distance_array.get(i).copied(),
 			};
 
@@ -667,6 +769,7 @@ impl BatchConverter {
 			.and_then(|col| col.as_any().downcast_ref::<Float32Array>())
 			.map(|arr| (0..arr.len()).map(|i| arr.value(i)).collect::<Vec<f32>>())
 			.unwrap_or_default();
+		let provenance = ProvenanceColumns::from_batch(batch);
 		for i in 0..batch.num_rows() {
 			let context = if context_array.is_null(i) {
 				Vec::new()
@@ -707,6 +810,7 @@ impl BatchConverter {
 				start_line: start_line_array.value(i) as usize,
 				end_line: end_line_array.value(i) as usize,
 				hash: hash_array.value(i).to_string(),
+				provenance: provenance.as_ref().and_then(|p| p.get(i)),
 				distance: distance_array.get(i).copied(),
 			};
 
@@ -716,3 +820,199 @@ impl BatchConverter {
 		Ok(document_blocks)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::store::provenance::Blame;
+	use crate::store::{ContentSpan, Provenance, SpanKind};
+	use proptest::prelude::*;
+
+	const DIM: usize = 4;
+
+	fn text() -> impl Strategy<Value = String> {
+		prop_oneof![
+			"\\PC{0,40}",
+			any::<String>(),
+			Just(String::new()),
+			(1usize..5000).prop_map(|count| "é🚀{".repeat(count)),
+		]
+	}
+
+	fn line() -> impl Strategy<Value = usize> {
+		(0..=u32::MAX).prop_map(|line| line as usize)
+	}
+
+	fn provenance() -> impl Strategy<Value = Option<Provenance>> {
+		prop::option::of(
+			(
+				text(),
+				any::<u32>(),
+				text(),
+				(0i64..4_102_444_800, 0u32..1_000_000_000),
+				prop::option::of((text(), text(), 0i64..4_102_444_800)),
+			)
+				.prop_map(
+					|(octocode_version, chunker_version, embedding_model, (secs, nanos), blame)| {
+						Provenance {
+							octocode_version,
+							chunker_version,
+							embedding_model,
+							indexed_at: chrono::DateTime::from_timestamp(secs, nanos).unwrap(),
+							blame: blame.map(|(commit, author, secs)| Blame {
+								commit,
+								author,
+								committed_at: chrono::DateTime::from_timestamp(secs, 0).unwrap(),
+							}),
+						}
+					},
+				),
+		)
+	}
+
+	fn embeddings(count: usize) -> impl Strategy<Value = Vec<Vec<f32>>> {
+		prop::collection::vec(prop::collection::vec(-1.0f32..1.0, DIM), count)
+	}
+
+	fn code_block() -> impl Strategy<Value = CodeBlock> {
+		let span = (any::<bool>(), 0usize..10_000, 0usize..10_000).prop_map(|(comment, a, b)| {
+			ContentSpan {
+				kind: if comment {
+					SpanKind::Comment
+				} else {
+					SpanKind::String
+				},
+				start: a.min(b),
+				end: a.max(b),
+			}
+		});
+		(
+			(text(), text(), text()),
+			prop::collection::vec(text(), 0..5),
+			(line(), line()),
+			(text(), provenance()),
+			prop::collection::vec(span, 0..5),
+		)
+			.prop_map(
+				|(
+					(path, language, content),
+					symbols,
+					(start_line, end_line),
+					(hash, provenance),
+					spans,
+				)| {
+					CodeBlock {
+						path,
+						language,
+						content,
+						symbols,
+						start_line,
+						end_line,
+						hash,
+						spans,
+						provenance,
+						distance: None,
+					}
+				},
+			)
+	}
+
+	fn text_block() -> impl Strategy<Value = TextBlock> {
+		(
+			(text(), text(), text()),
+			(line(), line()),
+			(text(), provenance()),
+		)
+			.prop_map(
+				|((path, language, content), (start_line, end_line), (hash, provenance))| {
+					TextBlock {
+						path,
+						language,
+						content,
+						start_line,
+						end_line,
+						hash,
+						provenance,
+						distance: None,
+					}
+				},
+			)
+	}
+
+	fn document_block() -> impl Strategy<Value = DocumentBlock> {
+		(
+			(text(), text(), text()),
+			prop::collection::vec(text(), 0..5),
+			(0usize..7, line(), line()),
+			(text(), provenance()),
+		)
+			.prop_map(
+				|(
+					(path, title, content),
+					context,
+					(level, start_line, end_line),
+					(hash, provenance),
+				)| {
+					DocumentBlock {
+						path,
+						title,
+						content,
+						context,
+						level,
+						start_line,
+						end_line,
+						hash,
+						provenance,
+						distance: None,
+					}
+				},
+			)
+	}
+
+	/// Blocks with their embeddings, one embedding per block
+	fn with_embeddings<T: Clone + std::fmt::Debug>(
+		block: impl Strategy<Value = T>,
+	) -> impl Strategy<Value = (Vec<T>, Vec<Vec<f32>>)> {
+		prop::collection::vec(block, 1..8).prop_flat_map(|blocks| {
+			let count = blocks.len();
+			(Just(blocks), embeddings(count))
+		})
+	}
+
+	fn as_json<T: Serialize>(blocks: &[T]) -> serde_json::Value {
+		serde_json::to_value(blocks).unwrap()
+	}
+
+	proptest! {
+		#![proptest_config(ProptestConfig::with_cases(64))]
+
+		#[test]
+		fn prop_code_blocks_round_trip((blocks, embeddings) in with_embeddings(code_block())) {
+			let converter = BatchConverter::new(DIM);
+			let batch = converter.code_block_to_batch(&blocks, &embeddings).unwrap();
+			let restored = converter.batch_to_code_blocks(&batch, None).unwrap();
+			prop_assert_eq!(as_json(&restored), as_json(&blocks));
+			prop_assert_eq!(converter.batch_to_embeddings(&batch).unwrap(), embeddings);
+		}
+
+		#[test]
+		fn prop_text_blocks_round_trip((blocks, embeddings) in with_embeddings(text_block())) {
+			let converter = BatchConverter::new(DIM);
+			let batch = converter.text_block_to_batch(&blocks, &embeddings).unwrap();
+			let restored = converter.batch_to_text_blocks(&batch, None).unwrap();
+			prop_assert_eq!(as_json(&restored), as_json(&blocks));
+			prop_assert_eq!(converter.batch_to_embeddings(&batch).unwrap(), embeddings);
+		}
+
+		#[test]
+		fn prop_document_blocks_round_trip(
+			(blocks, embeddings) in with_embeddings(document_block()),
+		) {
+			let converter = BatchConverter::new(DIM);
+			let batch = converter.document_block_to_batch(&blocks, &embeddings).unwrap();
+			let restored = converter.batch_to_document_blocks(&batch, None).unwrap();
+			prop_assert_eq!(as_json(&restored), as_json(&blocks));
+			prop_assert_eq!(converter.batch_to_embeddings(&batch).unwrap(), embeddings);
+		}
+	}
+}
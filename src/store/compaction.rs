@@ -0,0 +1,76 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Compaction of LanceDB tables
+//!
+//! Every write adds fragments and a new table version; deletes only mark
+//! rows. After many incremental updates (watch mode in particular) tables
+//! are spread over many small fragments and old versions keep the replaced
+//! data on disk. `octocode store optimize` merges the fragments, dropping
+//! deleted rows, then removes the versions older than the retention window.
+
+use anyhow::Result;
+use serde::Serialize;
+
+use lancedb::table::{CompactionOptions, OptimizeAction};
+use lancedb::Table;
+
+/// What `octocode store optimize` did to a table
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TableCompaction {
+	pub table: String,
+	/// Fragments merged away, and the fragments that replaced them
+	pub fragments_removed: usize,
+	pub fragments_added: usize,
+	pub versions_pruned: u64,
+	/// Disk space freed by pruning
+	pub bytes_reclaimed: u64,
+}
+
+/// Compact `table`, then prune versions older than `keep`
+pub async fn compact_table(
+	table: &Table,
+	table_name: &str,
+	keep: chrono::Duration,
+) -> Result<TableCompaction> {
+	let mut compaction = TableCompaction {
+		table: table_name.to_string(),
+		..Default::default()
+	};
+
+	let stats = table
+		.optimize(OptimizeAction::Compact {
+			options: CompactionOptions::default(),
+			remap_options: None,
+		})
+		.await?;
+	if let Some(metrics) = stats.compaction {
+		compaction.fragments_removed = metrics.fragments_removed;
+		compaction.fragments_added = metrics.fragments_added;
+	}
+
+	let stats = table
+		.optimize(OptimizeAction::Prune {
+			older_than: Some(keep),
+			delete_unverified: None,
+			error_if_tagged_old_versions: Some(false),
+		})
+		.await?;
+	if let Some(removal) = stats.prune {
+		compaction.versions_pruned = removal.old_versions;
+		compaction.bytes_reclaimed = removal.bytes_removed;
+	}
+
+	Ok(compaction)
+}
@@ -0,0 +1,151 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Values of the live index for shell completion
+//!
+//! Completion runs on every TAB press, so the database is opened read-only
+//! without the table creation and migrations of [`super::Store::new`], and a
+//! project that was never indexed simply has nothing to complete.
+
+use anyhow::Result;
+use arrow::array::{Array, StringArray};
+use futures::TryStreamExt;
+use lancedb::query::{ExecutableQuery, QueryBase, Select};
+use lancedb::{connect, Connection};
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use super::shards;
+use crate::memory::DEFAULT_NAMESPACE;
+
+/// What a completion lists
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompletionSource {
+	/// Symbols declared by indexed code blocks
+	Symbols,
+	/// Languages of indexed code blocks
+	Languages,
+	/// Tags of stored memories
+	Tags,
+	/// Namespaces of stored memories
+	Namespaces,
+}
+
+/// Distinct values of `source` starting with `prefix`, sorted
+pub async fn values(db_path: &Path, source: CompletionSource, prefix: &str) -> Result<Vec<String>> {
+	if !db_path.exists() {
+		return Ok(Vec::new());
+	}
+	let storage_path = db_path
+		.to_str()
+		.ok_or_else(|| anyhow::anyhow!("Invalid database path"))?;
+	let db = connect(storage_path).execute().await?;
+
+	let mut values = BTreeSet::new();
+	match source {
+		CompletionSource::Symbols => {
+			// Symbols are stored as JSON arrays, so the prefix can narrow the scan
+			let filter =
+				is_plain_identifier(prefix).then(|| format!("symbols LIKE '%\"{}%'", prefix));
+			for table in shards::block_tables(&db, "code_blocks").await? {
+				collect(&db, &table, "symbols", filter.as_deref(), true, &mut values).await?;
+			}
+		}
+		CompletionSource::Languages => {
+			for table in shards::block_tables(&db, "code_blocks").await? {
+				collect(&db, &table, "language", None, false, &mut values).await?;
+			}
+		}
+		CompletionSource::Tags => {
+			if has_table(&db, "memories").await? {
+				collect(&db, "memories", "tags", None, true, &mut values).await?;
+			}
+		}
+		CompletionSource::Namespaces => {
+			if has_table(&db, "memories").await? {
+				// Memories stored before namespaces existed belong to the default one
+				values.insert(DEFAULT_NAMESPACE.to_string());
+				collect(&db, "memories", "namespace", None, false, &mut values).await?;
+			}
+		}
+	}
+
+	Ok(values
+		.into_iter()
+		.filter(|value| value.starts_with(prefix))
+		.collect())
+}
+
+fn is_plain_identifier(prefix: &str) -> bool {
+	!prefix.is_empty()
+		&& prefix
+			.chars()
+			.all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
+
+async fn has_table(db: &Connection, name: &str) -> Result<bool> {
+	Ok(db
+		.table_names()
+		.execute()
+		.await?
+		.iter()
+		.any(|table| table == name))
+}
+
+/// Add the values of a string column, or of the JSON string arrays it holds
+async fn collect(
+	db: &Connection,
+	table_name: &str,
+	column_name: &str,
+	filter: Option<&str>,
+	json_list: bool,
+	values: &mut BTreeSet<String>,
+) -> Result<()> {
+	let table = db.open_table(table_name).execute().await?;
+	let schema = table.schema().await?;
+	if schema.field_with_name(column_name).is_err() {
+		return Ok(());
+	}
+
+	let mut query = table
+		.query()
+		.select(Select::Columns(vec![column_name.to_string()]));
+	if let Some(filter) = filter {
+		query = query.only_if(filter);
+	}
+	let mut results = query.execute().await?;
+
+	while let Some(batch) = results.try_next().await? {
+		let Some(column) = batch
+			.column_by_name(column_name)
+			.and_then(|c| c.as_any().downcast_ref::<StringArray>())
+		else {
+			continue;
+		};
+		for i in 0..column.len() {
+			if column.is_null(i) {
+				continue;
+			}
+			let value = column.value(i);
+			if json_list {
+				if let Ok(items) = serde_json::from_str::<Vec<String>>(value) {
+					values.extend(items.into_iter().filter(|item| !item.is_empty()));
+				}
+			} else if !value.is_empty() {
+				values.insert(value.to_string());
+			}
+		}
+	}
+	Ok(())
+}
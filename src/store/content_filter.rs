@@ -0,0 +1,184 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// Kind of a syntax span recorded for a code block at index time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SpanKind {
+	Comment,
+	String,
+}
+
+/// Byte range of a comment or string literal within a block's content
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContentSpan {
+	pub kind: SpanKind,
+	pub start: usize,
+	pub end: usize,
+}
+
+/// Part of a code block a search term has to appear in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentScope {
+	Comments,
+	Strings,
+	/// Anywhere outside comments and string literals
+	Code,
+}
+
+impl ContentScope {
+	pub fn parse(scope: &str) -> Result<Self> {
+		match scope {
+			"comments" => Ok(Self::Comments),
+			"strings" => Ok(Self::Strings),
+			"code" => Ok(Self::Code),
+			_ => Err(anyhow::anyhow!(
+				"Invalid scope '{}'. Use 'comments', 'strings', or 'code'.",
+				scope
+			)),
+		}
+	}
+
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::Comments => "comments",
+			Self::Strings => "strings",
+			Self::Code => "code",
+		}
+	}
+}
+
+/// Keeps code blocks that mention one of the search terms (case-insensitive)
+/// inside comments, inside string literals, or in the code itself.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContentFilter {
+	scope: ContentScope,
+	terms: Vec<String>,
+}
+
+impl ContentFilter {
+	pub fn new(scope: ContentScope, terms: &[String]) -> Result<Self> {
+		let terms: Vec<String> = terms
+			.iter()
+			.map(|term| term.trim().to_ascii_lowercase())
+			.filter(|term| !term.is_empty())
+			.collect();
+		if terms.is_empty() {
+			return Err(anyhow::anyhow!(
+				"A search term is required to search in {}",
+				scope.as_str()
+			));
+		}
+
+		Ok(Self { scope, terms })
+	}
+
+	pub fn scope(&self) -> ContentScope {
+		self.scope
+	}
+
+	/// Whether any term occurs in the requested part of the block
+	pub fn matches(&self, content: &str, spans: &[ContentSpan]) -> bool {
+		// ASCII lowercasing keeps byte offsets aligned with the recorded spans
+		let content = content.to_ascii_lowercase();
+		self.terms.iter().any(|term| {
+			content.match_indices(term.as_str()).any(|(start, found)| {
+				let end = start + found.len();
+				match self.scope {
+					ContentScope::Comments => in_span(spans, SpanKind::Comment, start, end),
+					ContentScope::Strings => in_span(spans, SpanKind::String, start, end),
+					ContentScope::Code => !spans
+						.iter()
+						.any(|span| start < span.end && span.start < end),
+				}
+			})
+		})
+	}
+
+	/// Coarse SQL prefilter on the block content; narrowed with [`Self::matches`]
+	pub(crate) fn sql_predicate(&self) -> String {
+		let clauses: Vec<String> = self
+			.terms
+			.iter()
+			.map(|term| format!("lower(content) LIKE '%{}%'", term.replace('\'', "''")))
+			.collect();
+		format!("({})", clauses.join(" OR "))
+	}
+}
+
+fn in_span(spans: &[ContentSpan], kind: SpanKind, start: usize, end: usize) -> bool {
+	spans
+		.iter()
+		.any(|span| span.kind == kind && span.start <= start && end <= span.end)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// `// retry later` is a comment, `"retry failed"` a string literal
+	const CONTENT: &str = "fn retry() {\n\t// retry later\n\tlog(\"Retry failed\");\n}";
+
+	fn spans() -> Vec<ContentSpan> {
+		let comment = CONTENT.find("// retry").unwrap();
+		let string = CONTENT.find("\"Retry").unwrap();
+		vec![
+			ContentSpan {
+				kind: SpanKind::Comment,
+				start: comment,
+				end: comment + "// retry later".len(),
+			},
+			ContentSpan {
+				kind: SpanKind::String,
+				start: string,
+				end: string + "\"Retry failed\"".len(),
+			},
+		]
+	}
+
+	fn filter(scope: ContentScope, term: &str) -> ContentFilter {
+		ContentFilter::new(scope, &[term.to_string()]).unwrap()
+	}
+
+	#[test]
+	fn test_scopes_match_only_their_spans() {
+		let spans = spans();
+		assert!(filter(ContentScope::Comments, "later").matches(CONTENT, &spans));
+		assert!(!filter(ContentScope::Comments, "failed").matches(CONTENT, &spans));
+		assert!(filter(ContentScope::Strings, "FAILED").matches(CONTENT, &spans));
+		assert!(!filter(ContentScope::Strings, "later").matches(CONTENT, &spans));
+		assert!(filter(ContentScope::Code, "log(").matches(CONTENT, &spans));
+		assert!(!filter(ContentScope::Code, "later").matches(CONTENT, &spans));
+		// `retry` appears in the function name as well as in the comment
+		assert!(filter(ContentScope::Code, "retry").matches(CONTENT, &spans));
+	}
+
+	#[test]
+	fn test_sql_predicate_escapes_quotes() {
+		let filter = ContentFilter::new(
+			ContentScope::Strings,
+			&["can't".to_string(), "  ".to_string(), "Retry".to_string()],
+		)
+		.unwrap();
+		assert_eq!(
+			filter.sql_predicate(),
+			"(lower(content) LIKE '%can''t%' OR lower(content) LIKE '%retry%')"
+		);
+		assert!(ContentFilter::new(ContentScope::Code, &[" ".to_string()]).is_err());
+		assert!(ContentScope::parse("docs").is_err());
+	}
+}
@@ -24,11 +24,41 @@ use lancedb::{
 	Connection,
 };
 
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
 use crate::store::{
-	batch_converter::BatchConverter, table_ops::TableOperations, CodeBlock, DocumentBlock,
+	batch_converter::BatchConverter, shards, table_ops::TableOperations, CodeBlock, DocumentBlock,
 	TextBlock,
 };
 
+/// Row count and on-disk size of a single table
+#[derive(Debug, Clone, Serialize)]
+pub struct TableStatistics {
+	pub name: String,
+	pub rows: usize,
+	/// Distinct file paths, for tables that store file content
+	pub unique_files: Option<usize>,
+	pub size_bytes: u64,
+}
+
+/// Snapshot of index health for `octocode stats`
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexStatistics {
+	pub tables: Vec<TableStatistics>,
+	pub unique_files: usize,
+	pub total_size_bytes: u64,
+	pub code_vector_dim: usize,
+	pub text_vector_dim: usize,
+	pub last_indexed_commit: Option<String>,
+	pub graphrag_last_commit: Option<String>,
+	pub graphrag_nodes: usize,
+	pub graphrag_relationships: usize,
+	/// Code block counts per language
+	pub languages: BTreeMap<String, usize>,
+}
+
 /// Debug and inspection operations for the database
 pub struct DebugOperations<'a> {
 	pub db: &'a Connection,
@@ -47,36 +77,41 @@ impl<'a> DebugOperations<'a> {
 
 	/// List all indexed files (moved from debug command)
 	pub async fn list_indexed_files(&self) -> Result<()> {
-		let table_names = self.db.table_names().execute().await?;
 		let mut total_files = 0;
 
-		for table_name in &["code_blocks", "text_blocks", "document_blocks"] {
-			if table_names.contains(&table_name.to_string()) {
+		for table_name in shards::BLOCK_TABLES {
+			let tables = shards::block_tables(self.db, table_name).await?;
+			if !tables.is_empty() {
 				println!("\n📁 Files in {} table:", table_name);
-				let table = self.db.open_table(*table_name).execute().await?;
-				let mut results = table
-					.query()
-					.select(Select::Columns(vec!["path".to_string()]))
-					.execute()
-					.await?;
-
 				let mut unique_paths = std::collections::HashSet::new();
 
-				// Process all result batches
-				while let Some(batch) = results.try_next().await? {
-					if batch.num_rows() > 0 {
-						if let Some(column) = batch.column_by_name("path") {
-							if let Some(path_array) = column.as_any().downcast_ref::<StringArray>()
-							{
-								for i in 0..path_array.len() {
-									let path = path_array.value(i).to_string();
-									unique_paths.insert(path);
+				for shard in &tables {
+					let table = self.db.open_table(shard).execute().await?;
+					let mut results = table
+						.query()
+						.select(Select::Columns(vec!["path".to_string()]))
+						.execute()
+						.await?;
+
+					// Process all result batches
+					while let Some(batch) = results.try_next().await? {
+						if batch.num_rows() > 0 {
+							if let Some(column) = batch.column_by_name("path") {
+								if let Some(path_array) =
+									column.as_any().downcast_ref::<StringArray>()
+								{
+									for i in 0..path_array.len() {
+										let path = path_array.value(i).to_string();
+										unique_paths.insert(path);
+									}
+								} else {
+									return Err(anyhow::anyhow!(
+										"Path column is not a StringArray"
+									));
 								}
 							} else {
-								return Err(anyhow::anyhow!("Path column is not a StringArray"));
+								return Err(anyhow::anyhow!("Path column not found"));
 							}
-						} else {
-							return Err(anyhow::anyhow!("Path column not found"));
 						}
 					}
 				}
@@ -100,9 +135,108 @@ impl<'a> DebugOperations<'a> {
 		Ok(())
 	}
 
+	/// Row counts, unique files and on-disk sizes for every table in the database
+	pub async fn table_statistics(&self, database_path: &Path) -> Result<Vec<TableStatistics>> {
+		let mut table_names = self.db.table_names().execute().await?;
+		table_names.sort();
+
+		let mut tables = Vec::with_capacity(table_names.len());
+		for name in table_names {
+			let table = self.db.open_table(&name).execute().await?;
+			let rows = table.count_rows(None).await?;
+			let unique_files = if shards::base_of(&name).is_some() {
+				Some(self.distinct_values(&name, "path").await?.len())
+			} else {
+				None
+			};
+			let size_bytes = directory_size(&database_path.join(format!("{}.lance", name)));
+
+			tables.push(TableStatistics {
+				name,
+				rows,
+				unique_files,
+				size_bytes,
+			});
+		}
+
+		Ok(tables)
+	}
+
+	/// Number of distinct files across all content tables
+	pub async fn unique_indexed_files(&self) -> Result<usize> {
+		let mut tables = Vec::new();
+		for base in shards::BLOCK_TABLES {
+			tables.extend(shards::block_tables(self.db, base).await?);
+		}
+		let tables: Vec<&str> = tables.iter().map(String::as_str).collect();
+		Ok(self
+			.table_ops
+			.get_all_indexed_file_paths(&tables)
+			.await?
+			.len())
+	}
+
+	/// Code block counts grouped by language
+	pub async fn language_distribution(&self) -> Result<BTreeMap<String, usize>> {
+		let mut languages = BTreeMap::new();
+		for table_name in shards::block_tables(self.db, "code_blocks").await? {
+			let table = self.db.open_table(&table_name).execute().await?;
+			let mut results = table
+				.query()
+				.select(Select::Columns(vec!["language".to_string()]))
+				.execute()
+				.await?;
+
+			while let Some(batch) = results.try_next().await? {
+				if let Some(column) = batch
+					.column_by_name("language")
+					.and_then(|c| c.as_any().downcast_ref::<StringArray>())
+				{
+					for i in 0..column.len() {
+						*languages.entry(column.value(i).to_string()).or_insert(0) += 1;
+					}
+				}
+			}
+		}
+
+		Ok(languages)
+	}
+
+	async fn distinct_values(
+		&self,
+		table_name: &str,
+		column_name: &str,
+	) -> Result<HashSet<String>> {
+		let table = self.db.open_table(table_name).execute().await?;
+		let mut results = table
+			.query()
+			.select(Select::Columns(vec![column_name.to_string()]))
+			.execute()
+			.await?;
+
+		let mut values = HashSet::new();
+		while let Some(batch) = results.try_next().await? {
+			if let Some(column) = batch
+				.column_by_name(column_name)
+				.and_then(|c| c.as_any().downcast_ref::<StringArray>())
+			{
+				for i in 0..column.len() {
+					values.insert(column.value(i).to_string());
+				}
+			}
+		}
+
+		Ok(values)
+	}
+
 	/// Show all chunks for a specific file path across all tables
 	pub async fn show_file_chunks(&self, file_path: &str) -> Result<()> {
 		let table_names = self.db.table_names().execute().await?;
+		let has_table = |base: &str| {
+			table_names
+				.iter()
+				.any(|name| shards::base_of(name) == Some(base))
+		};
 		let mut total_chunks = 0;
 		let mut found_in_any_table = false;
 
@@ -110,7 +244,7 @@ impl<'a> DebugOperations<'a> {
 		println!("{}", "=".repeat(80));
 
 		// Check code_blocks table
-		if table_names.contains(&"code_blocks".to_string()) {
+		if has_table("code_blocks") {
 			if let Ok(chunks) = self.get_file_code_blocks(file_path).await {
 				if !chunks.is_empty() {
 					found_in_any_table = true;
@@ -133,7 +267,7 @@ impl<'a> DebugOperations<'a> {
 		}
 
 		// Check text_blocks table
-		if table_names.contains(&"text_blocks".to_string()) {
+		if has_table("text_blocks") {
 			if let Ok(chunks) = self.get_file_text_blocks(file_path).await {
 				if !chunks.is_empty() {
 					found_in_any_table = true;
@@ -154,7 +288,7 @@ impl<'a> DebugOperations<'a> {
 		}
 
 		// Check document_blocks table
-		if table_names.contains(&"document_blocks".to_string()) {
+		if has_table("document_blocks") {
 			if let Ok(chunks) = self.get_file_document_blocks(file_path).await {
 				if !chunks.is_empty() {
 					found_in_any_table = true;
@@ -194,22 +328,27 @@ impl<'a> DebugOperations<'a> {
 
 	/// Get all code blocks for a specific file
 	async fn get_file_code_blocks(&self, file_path: &str) -> Result<Vec<CodeBlock>> {
-		let table = self.db.open_table("code_blocks").execute().await?;
+		let mut blocks = Vec::new();
+		for table_name in shards::tables_for_path("code_blocks", file_path) {
+			if !self.table_ops.table_exists(&table_name).await? {
+				continue;
+			}
+			let table = self.db.open_table(&table_name).execute().await?;
 
-		let mut results = table
-			.query()
-			.only_if(format!("path = '{}'", file_path))
-			.execute()
-			.await?;
+			let mut results = table
+				.query()
+				.only_if(format!("path = '{}'", file_path))
+				.execute()
+				.await?;
 
-		let mut blocks = Vec::new();
-		let converter = BatchConverter::new(self.code_vector_dim);
+			let converter = BatchConverter::new(self.code_vector_dim);
 
-		// Process all result batches
-		while let Some(batch) = results.try_next().await? {
-			if batch.num_rows() > 0 {
-				let mut batch_blocks = converter.batch_to_code_blocks(&batch, None)?;
-				blocks.append(&mut batch_blocks);
+			// Process all result batches
+			while let Some(batch) = results.try_next().await? {
+				if batch.num_rows() > 0 {
+					let mut batch_blocks = converter.batch_to_code_blocks(&batch, None)?;
+					blocks.append(&mut batch_blocks);
+				}
 			}
 		}
 
@@ -218,22 +357,27 @@ impl<'a> DebugOperations<'a> {
 
 	/// Get all text blocks for a specific file
 	async fn get_file_text_blocks(&self, file_path: &str) -> Result<Vec<TextBlock>> {
-		let table = self.db.open_table("text_blocks").execute().await?;
+		let mut blocks = Vec::new();
+		for table_name in shards::tables_for_path("text_blocks", file_path) {
+			if !self.table_ops.table_exists(&table_name).await? {
+				continue;
+			}
+			let table = self.db.open_table(&table_name).execute().await?;
 
-		let mut results = table
-			.query()
-			.only_if(format!("path = '{}'", file_path))
-			.execute()
-			.await?;
+			let mut results = table
+				.query()
+				.only_if(format!("path = '{}'", file_path))
+				.execute()
+				.await?;
 
-		let mut blocks = Vec::new();
-		let converter = BatchConverter::new(self.code_vector_dim);
+			let converter = BatchConverter::new(self.code_vector_dim);
 
-		// Process all result batches
-		while let Some(batch) = results.try_next().await? {
-			if batch.num_rows() > 0 {
-				let mut batch_blocks = converter.batch_to_text_blocks(&batch, None)?;
-				blocks.append(&mut batch_blocks);
+			// Process all result batches
+			while let Some(batch) = results.try_next().await? {
+				if batch.num_rows() > 0 {
+					let mut batch_blocks = converter.batch_to_text_blocks(&batch, None)?;
+					blocks.append(&mut batch_blocks);
+				}
 			}
 		}
 
@@ -242,22 +386,27 @@ impl<'a> DebugOperations<'a> {
 
 	/// Get all document blocks for a specific file
 	async fn get_file_document_blocks(&self, file_path: &str) -> Result<Vec<DocumentBlock>> {
-		let table = self.db.open_table("document_blocks").execute().await?;
+		let mut blocks = Vec::new();
+		for table_name in shards::tables_for_path("document_blocks", file_path) {
+			if !self.table_ops.table_exists(&table_name).await? {
+				continue;
+			}
+			let table = self.db.open_table(&table_name).execute().await?;
 
-		let mut results = table
-			.query()
-			.only_if(format!("path = '{}'", file_path))
-			.execute()
-			.await?;
+			let mut results = table
+				.query()
+				.only_if(format!("path = '{}'", file_path))
+				.execute()
+				.await?;
 
-		let mut blocks = Vec::new();
-		let converter = BatchConverter::new(self.code_vector_dim);
+			let converter = BatchConverter::new(self.code_vector_dim);
 
-		// Process all result batches
-		while let Some(batch) = results.try_next().await? {
-			if batch.num_rows() > 0 {
-				let mut batch_blocks = converter.batch_to_document_blocks(&batch, None)?;
-				blocks.append(&mut batch_blocks);
+			// Process all result batches
+			while let Some(batch) = results.try_next().await? {
+				if batch.num_rows() > 0 {
+					let mut batch_blocks = converter.batch_to_document_blocks(&batch, None)?;
+					blocks.append(&mut batch_blocks);
+				}
 			}
 		}
 
@@ -282,3 +431,95 @@ impl<'a> DebugOperations<'a> {
 		}
 	}
 }
+
+/// Total size of all files below a directory (0 if it does not exist)
+fn directory_size(path: &Path) -> u64 {
+	let Ok(entries) = std::fs::read_dir(path) else {
+		return 0;
+	};
+	entries
+		.flatten()
+		.map(|entry| match entry.metadata() {
+			Ok(metadata) if metadata.is_dir() => directory_size(&entry.path()),
+			Ok(metadata) => metadata.len(),
+			Err(_) => 0,
+		})
+		.sum()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use arrow::array::RecordBatchIterator;
+	use arrow::datatypes::{DataType, Field, Schema};
+	use arrow::record_batch::RecordBatch;
+	use std::sync::Arc;
+
+	async fn create_table(db: &Connection, name: &str, columns: &[(&str, Vec<&str>)]) {
+		let schema = Arc::new(Schema::new(
+			columns
+				.iter()
+				.map(|(column, _)| Field::new(*column, DataType::Utf8, false))
+				.collect::<Vec<_>>(),
+		));
+		let arrays = columns
+			.iter()
+			.map(|(_, values)| Arc::new(StringArray::from(values.clone())) as _)
+			.collect();
+		let batch = RecordBatch::try_new(schema.clone(), arrays).unwrap();
+		let reader = RecordBatchIterator::new(std::iter::once(Ok(batch)), schema);
+		db.create_table(name, reader).execute().await.unwrap();
+	}
+
+	#[tokio::test]
+	async fn test_index_statistics() {
+		let path = std::env::temp_dir().join(format!("octocode-stats-{}", std::process::id()));
+		let _ = std::fs::remove_dir_all(&path);
+		let db = lancedb::connect(path.to_str().unwrap())
+			.execute()
+			.await
+			.unwrap();
+
+		create_table(
+			&db,
+			"code_blocks",
+			&[
+				("path", vec!["src/a.rs", "src/a.rs", "src/b.py"]),
+				("language", vec!["rust", "rust", "python"]),
+			],
+		)
+		.await;
+		create_table(
+			&db,
+			"text_blocks",
+			&[("path", vec!["README.md", "src/a.rs"])],
+		)
+		.await;
+		create_table(&db, "graphrag_nodes", &[("id", vec!["a", "b", "c", "d"])]).await;
+
+		let debug_ops = DebugOperations::new(&db, 0);
+		let tables = debug_ops.table_statistics(&path).await.unwrap();
+		let summary: Vec<_> = tables
+			.iter()
+			.map(|table| (table.name.as_str(), table.rows, table.unique_files))
+			.collect();
+		assert_eq!(
+			summary,
+			vec![
+				("code_blocks", 3, Some(2)),
+				("graphrag_nodes", 4, None),
+				("text_blocks", 2, Some(2)),
+			]
+		);
+		assert!(tables.iter().all(|table| table.size_bytes > 0));
+
+		// Files shared between tables are counted once
+		assert_eq!(debug_ops.unique_indexed_files().await.unwrap(), 3);
+		assert_eq!(
+			debug_ops.language_distribution().await.unwrap(),
+			BTreeMap::from([("python".to_string(), 1), ("rust".to_string(), 2)])
+		);
+
+		let _ = std::fs::remove_dir_all(&path);
+	}
+}
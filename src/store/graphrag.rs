@@ -78,30 +78,28 @@ impl<'a> GraphRagOperations<'a> {
 	pub async fn get_all_code_blocks_for_graphrag(&self) -> Result<Vec<CodeBlock>> {
 		let mut all_blocks = Vec::new();
 
-		if !self.table_ops.table_exists("code_blocks").await? {
-			return Ok(all_blocks);
-		}
-
-		let table = self.db.open_table("code_blocks").execute().await?;
-
-		// Get all code blocks in batches to avoid memory issues
-		let mut results = table.query().execute().await?;
-
-		// Process all result batches
-		while let Some(batch) = results.try_next().await? {
-			if batch.num_rows() > 0 {
-				// Convert batch to CodeBlocks
-				let converter =
-					crate::store::batch_converter::BatchConverter::new(self.code_vector_dim);
-				let mut code_blocks = converter.batch_to_code_blocks(&batch, None)?;
-				all_blocks.append(&mut code_blocks);
-
-				// Log progress for large datasets
-				if cfg!(debug_assertions) && all_blocks.len() % 1000 == 0 {
-					tracing::debug!(
-						"Loaded {} code blocks for GraphRAG processing...",
-						all_blocks.len()
-					);
+		for table_name in crate::store::shards::block_tables(self.db, "code_blocks").await? {
+			let table = self.db.open_table(&table_name).execute().await?;
+
+			// Get all code blocks in batches to avoid memory issues
+			let mut results = table.query().execute().await?;
+
+			// Process all result batches
+			while let Some(batch) = results.try_next().await? {
+				if batch.num_rows() > 0 {
+					// Convert batch to CodeBlocks
+					let converter =
+						crate::store::batch_converter::BatchConverter::new(self.code_vector_dim);
+					let mut code_blocks = converter.batch_to_code_blocks(&batch, None)?;
+					all_blocks.append(&mut code_blocks);
+
+					// Log progress for large datasets
+					if cfg!(debug_assertions) && all_blocks.len() % 1000 == 0 {
+						tracing::debug!(
+							"Loaded {} code blocks for GraphRAG processing...",
+							all_blocks.len()
+						);
+					}
 				}
 			}
 		}
@@ -226,6 +224,33 @@ impl<'a> GraphRagOperations<'a> {
 		Ok(deleted_count)
 	}
 
+	/// Remove only the outgoing GraphRAG relationships of a node
+	pub async fn remove_graph_relationships_by_source(&self, node_id: &str) -> Result<usize> {
+		if !self
+			.table_ops
+			.table_exists("graphrag_relationships")
+			.await?
+		{
+			return Ok(0);
+		}
+
+		let table = self
+			.db
+			.open_table("graphrag_relationships")
+			.execute()
+			.await?;
+
+		let before_count = table.count_rows(None).await?;
+
+		table
+			.delete(&format!("source = '{}'", node_id))
+			.await
+			.map_err(|e| anyhow::anyhow!("Failed to delete from graphrag_relationships: {}", e))?;
+
+		let after_count = table.count_rows(None).await?;
+		Ok(before_count.saturating_sub(after_count))
+	}
+
 	/// Search for graph nodes by vector similarity
 	pub async fn search_graph_nodes(&self, embedding: &[f32], limit: usize) -> Result<RecordBatch> {
 		// Check embedding dimension
@@ -273,7 +298,11 @@ impl<'a> GraphRagOperations<'a> {
 		.await
 		.map_err(|e| anyhow::anyhow!("Failed to optimize query: {}", e))?;
 
-		let mut results = optimized_query.execute().await?;
+		let mut results = crate::store::vector_optimizer::VectorOptimizer::execute_with_fallback(
+			optimized_query,
+			"graphrag_nodes",
+		)
+		.await?;
 
 		// Collect all results into a single batch
 		let mut all_batches = Vec::new();
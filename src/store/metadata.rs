@@ -16,7 +16,7 @@ use anyhow::Result;
 use std::sync::Arc;
 
 // Arrow imports
-use arrow::array::{Array, Int64Array, StringArray};
+use arrow::array::{Array, Int64Array, StringArray, UInt32Array};
 use arrow::datatypes::{DataType, Field, Schema};
 use arrow::record_batch::RecordBatch;
 
@@ -27,8 +27,15 @@ use lancedb::{
 	Connection,
 };
 
+use crate::store::preflight::EmbeddingSettings;
 use crate::store::table_ops::TableOperations;
 
+/// Table recording the embedding models the index was built with
+pub(crate) const EMBEDDING_SETTINGS_TABLE: &str = "index_embedding";
+
+/// Table listing the files an unfinished index run has written
+const CHECKPOINT_TABLE: &str = "index_checkpoint";
+
 /// Handles git and file metadata operations
 pub struct MetadataOperations<'a> {
 	pub db: &'a Connection,
@@ -379,4 +386,216 @@ impl<'a> MetadataOperations<'a> {
 			.create_table_with_schema("graphrag_git_metadata", schema)
 			.await
 	}
+
+	/// Add files whose blocks are written to the checkpoint of the run
+	/// indexing `commit_hash` (empty outside git repositories)
+	pub async fn store_checkpoint_files(
+		&self,
+		commit_hash: &str,
+		files: &[(String, u64)],
+	) -> Result<()> {
+		let schema = Arc::new(Schema::new(vec![
+			Field::new("path", DataType::Utf8, false),
+			Field::new("mtime", DataType::Int64, false),
+			Field::new("commit_hash", DataType::Utf8, false),
+		]));
+
+		if !self.table_ops.table_exists(CHECKPOINT_TABLE).await? {
+			self.table_ops
+				.create_table_with_schema(CHECKPOINT_TABLE, schema.clone())
+				.await?;
+		}
+
+		let batch = RecordBatch::try_new(
+			schema,
+			vec![
+				Arc::new(StringArray::from_iter_values(
+					files.iter().map(|(path, _)| path.as_str()),
+				)),
+				Arc::new(Int64Array::from_iter_values(
+					files.iter().map(|(_, mtime)| *mtime as i64),
+				)),
+				Arc::new(StringArray::from(vec![commit_hash; files.len()])),
+			],
+		)?;
+		self.table_ops.store_batch(CHECKPOINT_TABLE, batch).await
+	}
+
+	/// Files in the checkpoint of an unfinished run indexing `commit_hash`
+	pub async fn get_checkpoint_files(
+		&self,
+		commit_hash: &str,
+	) -> Result<std::collections::HashSet<String>> {
+		let mut files = std::collections::HashSet::new();
+		if !self.table_ops.table_exists(CHECKPOINT_TABLE).await? {
+			return Ok(files);
+		}
+
+		let table = self.db.open_table(CHECKPOINT_TABLE).execute().await?;
+		let mut results = table
+			.query()
+			.only_if(format!(
+				"commit_hash = '{}'",
+				commit_hash.replace('\'', "''")
+			))
+			.select(Select::Columns(vec!["path".to_string()]))
+			.execute()
+			.await?;
+
+		while let Some(batch) = results.try_next().await? {
+			if let Some(paths) = batch
+				.column_by_name("path")
+				.and_then(|column| column.as_any().downcast_ref::<StringArray>())
+			{
+				files.extend(paths.iter().flatten().map(String::from));
+			}
+		}
+
+		Ok(files)
+	}
+
+	/// Forget the checkpoint once a run completes or another one starts over
+	pub async fn clear_checkpoint(&self) -> Result<()> {
+		self.table_ops.clear_table(CHECKPOINT_TABLE).await
+	}
+
+	/// Record the manifest hash of the index as of the last deterministic run
+	pub async fn store_index_manifest_hash(&self, manifest_hash: &str) -> Result<()> {
+		let schema = Arc::new(Schema::new(vec![
+			Field::new("manifest_hash", DataType::Utf8, false),
+			Field::new("indexed_at", DataType::Int64, false),
+		]));
+
+		if !self.table_ops.table_exists("index_manifest").await? {
+			self.table_ops
+				.create_table_with_schema("index_manifest", schema.clone())
+				.await?;
+		}
+
+		let batch = RecordBatch::try_new(
+			schema,
+			vec![
+				Arc::new(StringArray::from(vec![manifest_hash])),
+				Arc::new(Int64Array::from(vec![chrono::Utc::now().timestamp()])),
+			],
+		)?;
+
+		self.table_ops.clear_table("index_manifest").await?;
+		self.table_ops.store_batch("index_manifest", batch).await?;
+
+		Ok(())
+	}
+
+	/// Manifest hash recorded by the last deterministic run
+	pub async fn get_index_manifest_hash(&self) -> Result<Option<String>> {
+		if !self.table_ops.table_exists("index_manifest").await? {
+			return Ok(None);
+		}
+
+		let table = self.db.open_table("index_manifest").execute().await?;
+		let mut results = table
+			.query()
+			.select(Select::Columns(vec!["manifest_hash".to_string()]))
+			.limit(1)
+			.execute()
+			.await?;
+
+		while let Some(batch) = results.try_next().await? {
+			if let Some(hashes) = batch
+				.column_by_name("manifest_hash")
+				.and_then(|column| column.as_any().downcast_ref::<StringArray>())
+			{
+				if let Some(hash) = hashes.iter().next() {
+					return Ok(hash.map(|s| s.to_string()));
+				}
+			}
+		}
+
+		Ok(None)
+	}
+
+	/// Record the embedding models and dimensions the index is built with
+	pub async fn store_embedding_settings(&self, settings: &EmbeddingSettings) -> Result<()> {
+		let schema = Arc::new(Schema::new(vec![
+			Field::new("code_model", DataType::Utf8, false),
+			Field::new("text_model", DataType::Utf8, false),
+			Field::new("code_dim", DataType::UInt32, false),
+			Field::new("text_dim", DataType::UInt32, false),
+			Field::new("recorded_at", DataType::Int64, false),
+		]));
+
+		if !self
+			.table_ops
+			.table_exists(EMBEDDING_SETTINGS_TABLE)
+			.await?
+		{
+			self.table_ops
+				.create_table_with_schema(EMBEDDING_SETTINGS_TABLE, schema.clone())
+				.await?;
+		}
+
+		let batch = RecordBatch::try_new(
+			schema,
+			vec![
+				Arc::new(StringArray::from(vec![settings.code_model.as_str()])),
+				Arc::new(StringArray::from(vec![settings.text_model.as_str()])),
+				Arc::new(UInt32Array::from(vec![settings.code_dim as u32])),
+				Arc::new(UInt32Array::from(vec![settings.text_dim as u32])),
+				Arc::new(Int64Array::from(vec![chrono::Utc::now().timestamp()])),
+			],
+		)?;
+
+		self.table_ops.clear_table(EMBEDDING_SETTINGS_TABLE).await?;
+		self.table_ops
+			.store_batch(EMBEDDING_SETTINGS_TABLE, batch)
+			.await?;
+
+		Ok(())
+	}
+
+	/// Embedding models and dimensions recorded for the index, if any
+	pub async fn get_embedding_settings(&self) -> Result<Option<EmbeddingSettings>> {
+		if !self
+			.table_ops
+			.table_exists(EMBEDDING_SETTINGS_TABLE)
+			.await?
+		{
+			return Ok(None);
+		}
+
+		let table = self
+			.db
+			.open_table(EMBEDDING_SETTINGS_TABLE)
+			.execute()
+			.await?;
+		let mut results = table.query().limit(1).execute().await?;
+
+		while let Some(batch) = results.try_next().await? {
+			if batch.num_rows() == 0 {
+				continue;
+			}
+			let text = |name: &str| {
+				batch
+					.column_by_name(name)
+					.and_then(|column| column.as_any().downcast_ref::<StringArray>())
+					.map(|values| values.value(0).to_string())
+					.ok_or_else(|| anyhow::anyhow!("{} column not found", name))
+			};
+			let dimension = |name: &str| {
+				batch
+					.column_by_name(name)
+					.and_then(|column| column.as_any().downcast_ref::<UInt32Array>())
+					.map(|values| values.value(0) as usize)
+					.ok_or_else(|| anyhow::anyhow!("{} column not found", name))
+			};
+			return Ok(Some(EmbeddingSettings {
+				code_model: text("code_model")?,
+				text_model: text("text_model")?,
+				code_dim: dimension("code_dim")?,
+				text_dim: dimension("text_dim")?,
+			}));
+		}
+
+		Ok(None)
+	}
 }
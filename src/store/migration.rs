@@ -0,0 +1,672 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Re-embedding an index with other embedding models
+//!
+//! `octocode store migrate` moves an index to the configured embedding models
+//! without taking it offline. The rows are re-embedded into a staging
+//! database next to the index, while the index itself stays as it is: until
+//! the migration completes, [`crate::config::Config::load`] reports the models
+//! the index was built with, so searches and index runs keep using them.
+//! Rows are matched by hash (memories by id), so an interrupted migration
+//! resumes where it stopped and rows indexed meanwhile are picked up by the
+//! next pass. Once every row is re-embedded, the remaining tables are copied
+//! and the staging database replaces the index in one directory rename.
+
+use anyhow::Result;
+use arrow::array::{Array, ArrayRef, BooleanArray, FixedSizeListArray, Float32Array, ListArray};
+use arrow::array::{RecordBatchIterator, StringArray};
+use arrow::compute::filter_record_batch;
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
+use lancedb::query::{ExecutableQuery, QueryBase, Select};
+use lancedb::{connect, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use super::table_ops::TableOperations;
+use super::{shards, EmbeddingSettings, MetadataOperations};
+use crate::embedding::provider::EmbeddingProvider;
+use crate::embedding::types::InputType;
+use crate::embedding::{
+	create_embedding_provider_from_parts, parse_provider_model,
+	split_texts_into_token_limited_batches,
+};
+
+/// Migration record, in the project's storage directory
+const STATE_FILE: &str = "migration.json";
+/// Database the rows are re-embedded into, next to the index
+const STAGING_DIR: &str = "storage.migrating";
+/// Where the index is moved while the staging database takes its place
+const PREVIOUS_DIR: &str = "storage.previous";
+
+/// A migration in progress
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Migration {
+	/// Models the index is built with, used until the switch
+	pub from: EmbeddingSettings,
+	/// Models the rows are re-embedded with
+	pub to: EmbeddingSettings,
+	pub started_at: DateTime<Utc>,
+}
+
+/// Rows of a table and how many of them are re-embedded
+#[derive(Debug, Clone, Serialize)]
+pub struct TableProgress {
+	pub table: String,
+	pub rows: usize,
+	pub migrated: usize,
+}
+
+/// How the rows of a table reach the migrated index
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Carry {
+	/// Re-embedded from their text with the code or the text model
+	Reembed {
+		code: bool,
+		key: &'static str,
+		text: TextOf,
+	},
+	/// Copied as they are at the switch
+	Copy,
+	/// Left behind; the next index run rebuilds it
+	Skip,
+}
+
+/// What a row's embedding is computed from, matching the indexer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TextOf {
+	Content,
+	Document,
+	Memory,
+}
+
+fn carry(table: &str, has_embedding: bool) -> Carry {
+	let reembed = |code, key, text| Carry::Reembed { code, key, text };
+	match shards::base_of(table) {
+		Some("code_blocks" | "file_summaries") => reembed(true, "hash", TextOf::Content),
+		Some("text_blocks") => reembed(false, "hash", TextOf::Content),
+		Some("document_blocks") => reembed(false, "hash", TextOf::Document),
+		_ => match table {
+			"memories" | "memories_archive" => reembed(false, "id", TextOf::Memory),
+			// Recorded for the new models at the switch; calibrated for the old vectors
+			super::metadata::EMBEDDING_SETTINGS_TABLE | super::tuning::TUNING_TABLE => Carry::Skip,
+			// The graph is rebuilt by the next index run
+			_ if table.starts_with("graphrag_") => Carry::Skip,
+			// Vectors that can't be computed again from the row
+			_ if has_embedding => Carry::Skip,
+			_ => Carry::Copy,
+		},
+	}
+}
+
+/// Storage directory of the project, holding the index and the migration
+fn storage_dir(project_dir: &Path) -> Result<PathBuf> {
+	crate::storage::get_project_storage_path(project_dir)
+}
+
+/// The migration in progress for the project, if any
+pub fn pending(project_dir: &Path) -> Result<Option<Migration>> {
+	let path = storage_dir(project_dir)?.join(STATE_FILE);
+	if !path.exists() {
+		return Ok(None);
+	}
+	let migration = serde_json::from_str(&std::fs::read_to_string(&path)?)
+		.map_err(|e| anyhow::anyhow!("Invalid migration record {}: {}", path.display(), e))?;
+	Ok(Some(migration))
+}
+
+/// Record a migration from the models the index is built with to `to`;
+/// from now on the index is searched with the `from` models
+pub fn start(
+	project_dir: &Path,
+	from: EmbeddingSettings,
+	to: EmbeddingSettings,
+) -> Result<Migration> {
+	let migration = Migration {
+		from,
+		to,
+		started_at: Utc::now(),
+	};
+	let dir = crate::storage::ensure_project_storage_exists(project_dir)?;
+	std::fs::write(
+		dir.join(STATE_FILE),
+		serde_json::to_string_pretty(&migration)?,
+	)?;
+	Ok(migration)
+}
+
+/// Discard the migration and the rows re-embedded so far. Returns whether
+/// there was one.
+pub fn abort(project_dir: &Path) -> Result<bool> {
+	let dir = storage_dir(project_dir)?;
+	let staging = dir.join(STAGING_DIR);
+	if staging.exists() {
+		std::fs::remove_dir_all(&staging)?;
+	}
+	let state = dir.join(STATE_FILE);
+	let pending = state.exists();
+	if pending {
+		std::fs::remove_file(state)?;
+	}
+	Ok(pending)
+}
+
+/// Rows re-embedded so far, per table
+pub async fn status(project_dir: &Path, migration: &Migration) -> Result<Vec<TableProgress>> {
+	let dir = storage_dir(project_dir)?;
+	let (index, staging) = open(&dir).await?;
+	let staging_ops = TableOperations::new(&staging);
+
+	let mut progress = Vec::new();
+	for (table, _) in reembedded_tables(&index, migration).await? {
+		let rows = index
+			.open_table(&table)
+			.execute()
+			.await?
+			.count_rows(None)
+			.await?;
+		let migrated = if staging_ops.table_exists(&table).await? {
+			staging
+				.open_table(&table)
+				.execute()
+				.await?
+				.count_rows(None)
+				.await?
+		} else {
+			0
+		};
+		progress.push(TableProgress {
+			table,
+			rows,
+			migrated,
+		});
+	}
+	Ok(progress)
+}
+
+/// Embedding models of a migration, created only for the kinds of rows
+/// that are re-embedded
+struct Embedder {
+	provider: Box<dyn EmbeddingProvider>,
+	model: String,
+	dimension: usize,
+	batch_size: usize,
+	max_tokens_per_batch: usize,
+}
+
+impl Embedder {
+	fn new(
+		model: &str,
+		dimension: usize,
+		batch_size: usize,
+		max_tokens_per_batch: usize,
+	) -> Result<Self> {
+		let (provider, name) = parse_provider_model(model);
+		Ok(Self {
+			provider: create_embedding_provider_from_parts(&provider, &name)?,
+			model: model.to_string(),
+			dimension,
+			batch_size: batch_size.max(1),
+			max_tokens_per_batch,
+		})
+	}
+
+	async fn embed(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+		let mut embeddings = Vec::with_capacity(texts.len());
+		for batch in split_texts_into_token_limited_batches(
+			texts,
+			self.batch_size,
+			self.max_tokens_per_batch,
+		) {
+			embeddings.extend(
+				self.provider
+					.generate_embeddings_batch(batch, InputType::Document)
+					.await?,
+			);
+		}
+		Ok(embeddings)
+	}
+}
+
+/// Re-embed the rows of the index not migrated yet and remove migrated rows
+/// the index no longer has. Runs until the staging database matches the
+/// index as it was when each table was read; `on_progress` is called after
+/// every stored batch.
+pub async fn run(
+	project_dir: &Path,
+	migration: &Migration,
+	batch_size: usize,
+	max_tokens_per_batch: usize,
+	mut on_progress: impl FnMut(&TableProgress),
+) -> Result<Vec<TableProgress>> {
+	let dir = storage_dir(project_dir)?;
+	let (index, staging) = open(&dir).await?;
+	let tables = reembedded_tables(&index, migration).await?;
+
+	let needs = |code: bool| {
+		tables
+			.iter()
+			.any(|(_, carry)| matches!(carry, Carry::Reembed { code: c, .. } if *c == code))
+	};
+	let embedder = |code: bool| -> Result<Option<Embedder>> {
+		if !needs(code) {
+			return Ok(None);
+		}
+		let (model, dimension) = if code {
+			(&migration.to.code_model, migration.to.code_dim)
+		} else {
+			(&migration.to.text_model, migration.to.text_dim)
+		};
+		Embedder::new(model, dimension, batch_size, max_tokens_per_batch).map(Some)
+	};
+	let code_embedder = embedder(true)?;
+	let text_embedder = embedder(false)?;
+
+	let mut progress = Vec::new();
+	for (table, carry) in tables {
+		let Carry::Reembed { code, key, text } = carry else {
+			continue;
+		};
+		let embedder = if code { &code_embedder } else { &text_embedder };
+		let embedder = embedder
+			.as_ref()
+			.ok_or_else(|| anyhow::anyhow!("No embedding model for {}", table))?;
+		progress.push(
+			reembed_table(
+				&index,
+				&staging,
+				&table,
+				key,
+				text,
+				embedder,
+				&mut on_progress,
+			)
+			.await?,
+		);
+	}
+	Ok(progress)
+}
+
+/// Finish a migration whose rows are re-embedded: copy the other tables, pick
+/// up rows indexed since the last pass, record the new models and replace
+/// the index with the staging database
+pub async fn switch(
+	project_dir: &Path,
+	migration: &Migration,
+	batch_size: usize,
+	max_tokens_per_batch: usize,
+) -> Result<()> {
+	let dir = storage_dir(project_dir)?;
+	{
+		let (index, staging) = open(&dir).await?;
+		// Metadata first: files indexed after the copy are reindexed next time
+		// rather than lost
+		for (table, carry) in plan(&index, migration).await? {
+			if carry == Carry::Copy {
+				copy_table(&index, &staging, &table).await?;
+			}
+		}
+	}
+	run(
+		project_dir,
+		migration,
+		batch_size,
+		max_tokens_per_batch,
+		|_| {},
+	)
+	.await?;
+	{
+		let (_, staging) = open(&dir).await?;
+		MetadataOperations::new(&staging)
+			.store_embedding_settings(&migration.to)
+			.await?;
+	}
+
+	let index = dir.join("storage");
+	let staging = dir.join(STAGING_DIR);
+	let previous = dir.join(PREVIOUS_DIR);
+	if previous.exists() {
+		std::fs::remove_dir_all(&previous)?;
+	}
+	std::fs::rename(&index, &previous)?;
+	if let Err(e) = std::fs::rename(&staging, &index) {
+		// Put the index back rather than leave the project without one
+		std::fs::rename(&previous, &index)?;
+		return Err(e.into());
+	}
+	std::fs::remove_file(dir.join(STATE_FILE))?;
+	std::fs::remove_dir_all(&previous)?;
+	Ok(())
+}
+
+async fn open(dir: &Path) -> Result<(Connection, Connection)> {
+	let path = |name: &str| {
+		dir.join(name)
+			.to_str()
+			.map(str::to_string)
+			.ok_or_else(|| anyhow::anyhow!("Invalid database path"))
+	};
+	let index = connect(&path("storage")?).execute().await?;
+	let staging = connect(&path(STAGING_DIR)?).execute().await?;
+	Ok((index, staging))
+}
+
+/// How each table of the index reaches the migrated index
+async fn plan(index: &Connection, migration: &Migration) -> Result<Vec<(String, Carry)>> {
+	let code_changed = migration.from.code_model != migration.to.code_model
+		|| migration.from.code_dim != migration.to.code_dim;
+	let text_changed = migration.from.text_model != migration.to.text_model
+		|| migration.from.text_dim != migration.to.text_dim;
+
+	let mut plan = Vec::new();
+	for table in index.table_names().execute().await? {
+		let schema = index.open_table(&table).execute().await?.schema().await?;
+		let carry = match carry(&table, schema.field_with_name("embedding").is_ok()) {
+			// Vectors of an unchanged model are kept
+			Carry::Reembed { code, .. } if !(if code { code_changed } else { text_changed }) => {
+				Carry::Copy
+			}
+			carry => carry,
+		};
+		plan.push((table, carry));
+	}
+	Ok(plan)
+}
+
+/// Tables whose rows are re-embedded
+async fn reembedded_tables(
+	index: &Connection,
+	migration: &Migration,
+) -> Result<Vec<(String, Carry)>> {
+	let mut plan = plan(index, migration).await?;
+	plan.retain(|(_, carry)| matches!(carry, Carry::Reembed { .. }));
+	Ok(plan)
+}
+
+async fn reembed_table(
+	index: &Connection,
+	staging: &Connection,
+	table: &str,
+	key: &str,
+	text: TextOf,
+	embedder: &Embedder,
+	on_progress: &mut impl FnMut(&TableProgress),
+) -> Result<TableProgress> {
+	let source = index.open_table(table).execute().await?;
+	let staging_ops = TableOperations::new(staging);
+	let mut migrated = if staging_ops.table_exists(table).await? {
+		keys(staging, table, key).await?
+	} else {
+		HashSet::new()
+	};
+	let mut progress = TableProgress {
+		table: table.to_string(),
+		rows: source.count_rows(None).await?,
+		migrated: 0,
+	};
+
+	let mut present = HashSet::new();
+	let mut results = source.query().execute().await?;
+	while let Some(batch) = results.try_next().await? {
+		let keys = string_column(&batch, key)?;
+		let mut new_keys = Vec::new();
+		let mask: BooleanArray = (0..keys.len())
+			.map(|i| {
+				let key = keys.value(i);
+				present.insert(key.to_string());
+				let new = !migrated.contains(key);
+				if new {
+					new_keys.push(key.to_string());
+				}
+				Some(new)
+			})
+			.collect();
+		let batch = filter_record_batch(&batch, &mask)?;
+		if batch.num_rows() > 0 {
+			let embeddings = embedder.embed(texts(&batch, text)?).await?;
+			staging_ops
+				.store_batch(table, with_embeddings(&batch, &embeddings, embedder)?)
+				.await?;
+			migrated.extend(new_keys);
+		}
+		progress.migrated = present.len();
+		on_progress(&progress);
+	}
+
+	// Rows removed from the index since they were re-embedded
+	let removed: Vec<String> = migrated.difference(&present).cloned().collect();
+	if !removed.is_empty() {
+		let table = staging.open_table(table).execute().await?;
+		for chunk in removed.chunks(500) {
+			let values: Vec<String> = chunk
+				.iter()
+				.map(|value| format!("'{}'", value.replace('\'', "''")))
+				.collect();
+			table
+				.delete(&format!("{} IN ({})", key, values.join(", ")))
+				.await?;
+		}
+	}
+	Ok(progress)
+}
+
+/// Replace a staging table with the index's current rows
+async fn copy_table(index: &Connection, staging: &Connection, table: &str) -> Result<()> {
+	let source = index.open_table(table).execute().await?;
+	let schema = source.schema().await?;
+	let batches: Vec<RecordBatch> = source.query().execute().await?.try_collect().await?;
+
+	if TableOperations::new(staging).table_exists(table).await? {
+		staging.drop_table(table).await?;
+	}
+	if batches.is_empty() {
+		staging.create_empty_table(table, schema).execute().await?;
+	} else {
+		let reader = RecordBatchIterator::new(batches.into_iter().map(Ok), schema);
+		staging.create_table(table, reader).execute().await?;
+	}
+	Ok(())
+}
+
+async fn keys(db: &Connection, table: &str, key: &str) -> Result<HashSet<String>> {
+	let mut keys = HashSet::new();
+	let mut results = db
+		.open_table(table)
+		.execute()
+		.await?
+		.query()
+		.select(Select::Columns(vec![key.to_string()]))
+		.execute()
+		.await?;
+	while let Some(batch) = results.try_next().await? {
+		let column = string_column(&batch, key)?;
+		keys.extend((0..column.len()).map(|i| column.value(i).to_string()));
+	}
+	Ok(keys)
+}
+
+fn string_column<'a>(batch: &'a RecordBatch, name: &str) -> Result<&'a StringArray> {
+	batch
+		.column_by_name(name)
+		.and_then(|column| column.as_any().downcast_ref::<StringArray>())
+		.ok_or_else(|| anyhow::anyhow!("{} column not found", name))
+}
+
+/// Text each row is embedded from, as the indexer and the memory store embed it
+fn texts(batch: &RecordBatch, text: TextOf) -> Result<Vec<String>> {
+	let content = string_column(batch, "content")?;
+	let rows = 0..batch.num_rows();
+	match text {
+		TextOf::Content => Ok(rows.map(|i| content.value(i).to_string()).collect()),
+		TextOf::Document => {
+			let context = batch
+				.column_by_name("context")
+				.and_then(|column| column.as_any().downcast_ref::<ListArray>())
+				.ok_or_else(|| anyhow::anyhow!("context column not found"))?;
+			Ok(rows
+				.map(|i| {
+					let headings = context.value(i);
+					let headings: Vec<&str> = headings
+						.as_any()
+						.downcast_ref::<StringArray>()
+						.map(|values| values.iter().flatten().collect())
+						.unwrap_or_default();
+					if headings.is_empty() {
+						content.value(i).to_string()
+					} else {
+						format!("{}\n\n{}", headings.join("\n"), content.value(i))
+					}
+				})
+				.collect())
+		}
+		TextOf::Memory => {
+			let title = string_column(batch, "title")?;
+			// Serialized as JSON lists, possibly null
+			let list = |name: &str, i: usize| -> String {
+				batch
+					.column_by_name(name)
+					.and_then(|column| column.as_any().downcast_ref::<StringArray>())
+					.filter(|values| values.is_valid(i))
+					.and_then(|values| serde_json::from_str::<Vec<String>>(values.value(i)).ok())
+					.unwrap_or_default()
+					.join(" ")
+			};
+			Ok(rows
+				.map(|i| {
+					format!(
+						"{} {} {} {}",
+						title.value(i),
+						content.value(i),
+						list("tags", i),
+						list("related_files", i)
+					)
+				})
+				.collect())
+		}
+	}
+}
+
+/// `batch` with its `embedding` column replaced by `embeddings` and the
+/// recorded embedding model updated
+fn with_embeddings(
+	batch: &RecordBatch,
+	embeddings: &[Vec<f32>],
+	embedder: &Embedder,
+) -> Result<RecordBatch> {
+	if embeddings.len() != batch.num_rows() {
+		return Err(anyhow::anyhow!(
+			"{} returned {} embeddings for {} rows",
+			embedder.model,
+			embeddings.len(),
+			batch.num_rows()
+		));
+	}
+	if let Some(embedding) = embeddings.iter().find(|e| e.len() != embedder.dimension) {
+		return Err(anyhow::anyhow!(
+			"{} returned {}-dimensional embeddings, expected {}",
+			embedder.model,
+			embedding.len(),
+			embedder.dimension
+		));
+	}
+
+	let item = Arc::new(Field::new("item", DataType::Float32, true));
+	let values = Float32Array::from(embeddings.concat());
+	let embedding: ArrayRef = Arc::new(FixedSizeListArray::new(
+		item.clone(),
+		embedder.dimension as i32,
+		Arc::new(values),
+		None,
+	));
+
+	let schema = batch.schema();
+	let mut fields = Vec::with_capacity(schema.fields().len());
+	let mut columns = Vec::with_capacity(schema.fields().len());
+	for (field, column) in schema.fields().iter().zip(batch.columns()) {
+		match field.name().as_str() {
+			"embedding" => {
+				fields.push(Field::new(
+					"embedding",
+					DataType::FixedSizeList(item.clone(), embedder.dimension as i32),
+					field.is_nullable(),
+				));
+				columns.push(embedding.clone());
+			}
+			"embedding_model" => {
+				fields.push(field.as_ref().clone());
+				columns.push(Arc::new(StringArray::from(vec![
+					embedder.model.as_str();
+					batch.num_rows()
+				])) as ArrayRef);
+			}
+			_ => {
+				fields.push(field.as_ref().clone());
+				columns.push(column.clone());
+			}
+		}
+	}
+	Ok(RecordBatch::try_new(
+		Arc::new(Schema::new(fields)),
+		columns,
+	)?)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_tables_carried_over() {
+		let reembed = |table| matches!(carry(table, true), Carry::Reembed { .. });
+		assert!(reembed("code_blocks"));
+		assert!(reembed("code_blocks.src"));
+		assert!(reembed("document_blocks"));
+		assert!(reembed("memories"));
+		assert_eq!(carry("file_metadata", false), Carry::Copy);
+		assert_eq!(carry("memory_relationships", false), Carry::Copy);
+		assert_eq!(carry("graphrag_nodes", true), Carry::Skip);
+		assert_eq!(carry("graphrag_git_metadata", false), Carry::Skip);
+		assert_eq!(carry("index_embedding", false), Carry::Skip);
+	}
+
+	#[test]
+	fn test_document_and_memory_texts() {
+		let schema = Arc::new(Schema::new(vec![
+			Field::new("content", DataType::Utf8, false),
+			Field::new("title", DataType::Utf8, false),
+			Field::new("tags", DataType::Utf8, true),
+			Field::new("related_files", DataType::Utf8, true),
+		]));
+		let batch = RecordBatch::try_new(
+			schema,
+			vec![
+				Arc::new(StringArray::from(vec!["Use anyhow"])),
+				Arc::new(StringArray::from(vec!["Errors"])),
+				Arc::new(StringArray::from(vec![Some("[\"rust\",\"style\"]")])),
+				Arc::new(StringArray::from(vec![None::<&str>])),
+			],
+		)
+		.unwrap();
+		assert_eq!(
+			texts(&batch, TextOf::Memory).unwrap(),
+			vec!["Errors Use anyhow rust style "]
+		);
+		assert_eq!(texts(&batch, TextOf::Content).unwrap(), vec!["Use anyhow"]);
+	}
+}
@@ -39,6 +39,7 @@ use futures::TryStreamExt;
 use lancedb::{
 	connect,
 	query::{ExecutableQuery, QueryBase},
+	table::NewColumnTransform,
 	Connection, DistanceType,
 };
 
@@ -48,13 +49,32 @@ use self::{
 	metadata::MetadataOperations, table_ops::TableOperations, vector_optimizer::VectorOptimizer,
 };
 
+pub mod anchor;
 pub mod batch_converter;
+pub mod compaction;
+pub mod completion;
+pub mod content_filter;
 pub mod debug;
 pub mod graphrag;
 pub mod metadata;
+pub mod migration;
+pub mod path_filter;
+pub mod preflight;
+pub mod provenance;
+pub mod shards;
+pub mod symbol_filter;
 pub mod table_ops;
+pub mod tuning;
 pub mod vector_optimizer;
 
+pub use anchor::{Anchor, AnchorKey, AnchoredBlock, ResolvedAnchor};
+pub use content_filter::{ContentFilter, ContentScope, ContentSpan, SpanKind};
+pub use debug::{IndexStatistics, TableStatistics};
+pub use path_filter::PathFilter;
+pub use preflight::EmbeddingSettings;
+pub use provenance::{Blame, Provenance, CHUNKER_VERSION};
+pub use symbol_filter::SymbolFilter;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CodeBlock {
 	pub path: String,
@@ -64,6 +84,12 @@ pub struct CodeBlock {
 	pub start_line: usize,
 	pub end_line: usize,
 	pub hash: String,
+	/// Comments and string literals in `content`, recorded at index time
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub spans: Vec<ContentSpan>,
+	/// How and when the block was indexed; absent for blocks indexed before it was recorded
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub provenance: Option<Provenance>,
 	// Optional distance field for relevance sorting (higher is more relevant)
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub distance: Option<f32>,
@@ -77,6 +103,9 @@ pub struct TextBlock {
 	pub start_line: usize,
 	pub end_line: usize,
 	pub hash: String,
+	/// How and when the block was indexed; absent for blocks indexed before it was recorded
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub provenance: Option<Provenance>,
 	// Optional distance field for relevance sorting (higher is more relevant)
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub distance: Option<f32>,
@@ -92,15 +121,34 @@ pub struct DocumentBlock {
 	pub start_line: usize,
 	pub end_line: usize,
 	pub hash: String,
+	/// How and when the block was indexed; absent for blocks indexed before it was recorded
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub provenance: Option<Provenance>,
 	// Optional distance field for relevance sorting (higher is more relevant)
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub distance: Option<f32>,
 }
 
+/// Relevance of a result from 0.0 to 1.0, the scale of the similarity
+/// threshold, whichever table it comes from. Cosine distances range from 0.0
+/// to 2.0; opposite vectors score 0.0 like unrelated ones.
+pub fn relevance(distance: f32) -> f32 {
+	(1.0 - distance).clamp(0.0, 1.0)
+}
+
+#[derive(Clone)]
 pub struct Store {
 	db: Connection,
 	code_vector_dim: usize, // Size of code embedding vectors
 	text_vector_dim: usize, // Size of text embedding vectors
+	/// Write blocks to one table per top-level directory, see [`shards`]
+	shard_by_directory: bool,
+	/// Limits block searches to some paths, see [`Store::with_path_filter`]
+	path_filter: Option<PathFilter>,
+	/// Search parameters recorded by `octocode store tune`, by table name
+	search_tuning: Arc<std::collections::HashMap<String, tuning::TunedSearch>>,
+	/// `filter_results` plugins applied to block searches
+	result_filters: Arc<Vec<crate::plugins::Plugin>>,
 }
 
 // Implementing Drop for the Store
@@ -155,36 +203,42 @@ impl Store {
 		// Check if tables exist and if their schema matches the current configuration
 		let table_names = db.table_names().execute().await?;
 
-		// Check for schema mismatches and recreate tables if necessary
-		for table_name in [
-			"code_blocks",
-			"text_blocks",
-			"document_blocks",
-			"graphrag_nodes",
-		] {
-			if table_names.contains(&table_name.to_string()) {
-				if let Ok(table) = db.open_table(table_name).execute().await {
-					if let Ok(schema) = table.schema().await {
-						// Check if embedding field has the right dimension
-						if let Ok(field) = schema.field_with_name("embedding") {
-							if let DataType::FixedSizeList(_, size) = field.data_type() {
-								let expected_dim = match table_name {
-									"code_blocks" | "graphrag_nodes" => code_vector_dim as i32,
+		// Check for schema mismatches: empty tables and the derived graph are
+		// recreated, indexed blocks are kept for `octocode store migrate`
+		for table_name in table_names
+			.iter()
+			.map(String::as_str)
+			.filter(|name| shards::base_of(name).is_some() || *name == "graphrag_nodes")
+		{
+			if let Ok(table) = db.open_table(table_name).execute().await {
+				if let Ok(schema) = table.schema().await {
+					// Check if embedding field has the right dimension
+					if let Ok(field) = schema.field_with_name("embedding") {
+						if let DataType::FixedSizeList(_, size) = field.data_type() {
+							let expected_dim =
+								match shards::base_of(table_name).unwrap_or(table_name) {
+									"code_blocks" | "file_summaries" | "graphrag_nodes" => {
+										code_vector_dim as i32
+									}
 									"text_blocks" | "document_blocks" => text_vector_dim as i32,
 									_ => continue,
 								};
 
-								if size != &expected_dim {
-									tracing::warn!("Schema mismatch detected for table '{}': expected dimension {}, found {}. Dropping table for recreation.",
+							if size != &expected_dim {
+								let rows = table.count_rows(None).await.unwrap_or(0);
+								if rows > 0 && table_name != "graphrag_nodes" {
+									return Err(anyhow::anyhow!(
+										"Table '{}' holds {} vectors of dimension {}, the configured embedding models produce {}. \
+										 Run `octocode store migrate` to re-embed the index with the configured models while it stays searchable, \
+										 or `octocode index --rebuild` to drop and rebuild it.",
+										table_name, rows, size, expected_dim
+									));
+								}
+								tracing::warn!("Schema mismatch detected for table '{}': expected dimension {}, found {}. Dropping table for recreation.",
 										table_name, expected_dim, size);
-									drop(table); // Release table handle before dropping
-									if let Err(e) = db.drop_table(table_name).await {
-										tracing::warn!(
-											"Failed to drop table {}: {}",
-											table_name,
-											e
-										);
-									}
+								drop(table); // Release table handle before dropping
+								if let Err(e) = db.drop_table(table_name).await {
+									tracing::warn!("Failed to drop table {}: {}", table_name, e);
 								}
 							}
 						}
@@ -193,20 +247,101 @@ impl Store {
 			}
 		}
 
+		// Columns added after a table was first created: existing tables get them
+		// as empty columns, in the order new tables declare them; reindexing fills them in.
+		// Listed again, as mismatched tables were dropped above
+		let table_names = db.table_names().execute().await?;
+		for table_name in &table_names {
+			let added = match shards::base_of(table_name) {
+				Some("code_blocks" | "file_summaries") => {
+					std::iter::once(Field::new("spans", DataType::Utf8, true))
+						.chain(provenance::provenance_fields())
+						.collect::<Vec<_>>()
+				}
+				Some(_) => provenance::provenance_fields(),
+				None => continue,
+			};
+			let Ok(table) = db.open_table(table_name).execute().await else {
+				continue;
+			};
+			let Ok(schema) = table.schema().await else {
+				continue;
+			};
+			let missing: Vec<Field> = added
+				.into_iter()
+				.filter(|field| schema.field_with_name(field.name()).is_err())
+				.collect();
+			if missing.is_empty() {
+				continue;
+			}
+			let names: Vec<String> = missing.iter().map(|field| field.name().clone()).collect();
+			if let Err(e) = table
+				.add_columns(
+					NewColumnTransform::AllNulls(Arc::new(Schema::new(missing))),
+					None,
+				)
+				.await
+			{
+				tracing::warn!(
+					"Failed to add {} columns to {}: {}",
+					names.join(", "),
+					table_name,
+					e
+				);
+			}
+		}
+
+		// Searches fall back to the size-based heuristics without a calibration
+		let search_tuning = tuning::load(&db).await.unwrap_or_else(|e| {
+			tracing::warn!("Failed to read search tuning: {}", e);
+			Default::default()
+		});
+
 		Ok(Self {
 			db,
 			code_vector_dim,
 			text_vector_dim,
+			shard_by_directory: config.index.shard_by_directory,
+			path_filter: None,
+			search_tuning: Arc::new(search_tuning),
+			result_filters: Arc::new(crate::plugins::Plugin::with_hook(
+				&config,
+				crate::config::PluginHook::FilterResults,
+			)),
 		})
 	}
 
-	pub async fn initialize_collections(&self) -> Result<()> {
-		// Check if tables exist, if not create them
-		let table_names = self.db.table_names().execute().await?;
+	/// The same store with block searches limited to files matching `filter`.
+	/// With sharded tables, only the shards of the filter's paths are opened.
+	pub fn with_path_filter(&self, filter: Option<PathFilter>) -> Self {
+		// Store implements Drop, so fields can't be moved out of a clone
+		let mut store = self.clone();
+		store.path_filter = filter;
+		store
+	}
+
+	/// Tables a search of a block table reads: all of its tables, or with a
+	/// path filter the unsharded table and the shards of the filter's paths
+	async fn search_tables(&self, base: &str) -> Result<Vec<String>> {
+		let tables = shards::block_tables(&self.db, base).await?;
+		let Some(filter) = &self.path_filter else {
+			return Ok(tables);
+		};
+		let wanted: Vec<String> = filter
+			.shards()
+			.into_iter()
+			.map(|shard| shards::shard_table(base, shard))
+			.collect();
+		Ok(tables
+			.into_iter()
+			.filter(|table| table == base || wanted.contains(table))
+			.collect())
+	}
 
-		// Create code_blocks table if it doesn't exist
-		if !table_names.contains(&"code_blocks".to_string()) {
-			let schema = Arc::new(Schema::new(vec![
+	/// Columns of code blocks and file overviews
+	fn code_block_schema(&self) -> Arc<Schema> {
+		Arc::new(Schema::new(
+			vec![
 				Field::new("id", DataType::Utf8, false),
 				Field::new("path", DataType::Utf8, false),
 				Field::new("language", DataType::Utf8, false),
@@ -223,34 +358,55 @@ impl Store {
 					),
 					true,
 				),
-			]));
+				// Appended last, matching tables migrated by `Store::new`
+				Field::new("spans", DataType::Utf8, true),
+			]
+			.into_iter()
+			.chain(provenance::provenance_fields())
+			.collect::<Vec<_>>(),
+		))
+	}
 
-			let _table = self
-				.db
-				.create_empty_table("code_blocks", schema)
-				.execute()
-				.await?;
+	pub async fn initialize_collections(&self) -> Result<()> {
+		// Check if tables exist, if not create them
+		let table_names = self.db.table_names().execute().await?;
+
+		// Create code_blocks and file_summaries tables if they don't exist,
+		// overviews being stored like code blocks
+		for table_name in ["code_blocks", "file_summaries"] {
+			if !table_names.contains(&table_name.to_string()) {
+				let _table = self
+					.db
+					.create_empty_table(table_name, self.code_block_schema())
+					.execute()
+					.await?;
+			}
 		}
 
 		// Create text_blocks table if it doesn't exist
 		if !table_names.contains(&"text_blocks".to_string()) {
-			let schema = Arc::new(Schema::new(vec![
-				Field::new("id", DataType::Utf8, false),
-				Field::new("path", DataType::Utf8, false),
-				Field::new("language", DataType::Utf8, false),
-				Field::new("content", DataType::Utf8, false),
-				Field::new("start_line", DataType::UInt32, false),
-				Field::new("end_line", DataType::UInt32, false),
-				Field::new("hash", DataType::Utf8, false),
-				Field::new(
-					"embedding",
-					DataType::FixedSizeList(
-						Arc::new(Field::new("item", DataType::Float32, true)),
-						self.text_vector_dim as i32,
+			let schema = Arc::new(Schema::new(
+				vec![
+					Field::new("id", DataType::Utf8, false),
+					Field::new("path", DataType::Utf8, false),
+					Field::new("language", DataType::Utf8, false),
+					Field::new("content", DataType::Utf8, false),
+					Field::new("start_line", DataType::UInt32, false),
+					Field::new("end_line", DataType::UInt32, false),
+					Field::new("hash", DataType::Utf8, false),
+					Field::new(
+						"embedding",
+						DataType::FixedSizeList(
+							Arc::new(Field::new("item", DataType::Float32, true)),
+							self.text_vector_dim as i32,
+						),
+						true,
 					),
-					true,
-				),
-			]));
+				]
+				.into_iter()
+				.chain(provenance::provenance_fields())
+				.collect::<Vec<_>>(),
+			));
 
 			let _table = self
 				.db
@@ -261,29 +417,34 @@ impl Store {
 
 		// Create document_blocks table if it doesn't exist
 		if !table_names.contains(&"document_blocks".to_string()) {
-			let schema = Arc::new(Schema::new(vec![
-				Field::new("id", DataType::Utf8, false),
-				Field::new("path", DataType::Utf8, false),
-				Field::new("title", DataType::Utf8, false),
-				Field::new("content", DataType::Utf8, false),
-				Field::new(
-					"context",
-					DataType::List(Arc::new(Field::new("item", DataType::Utf8, true))),
-					true,
-				),
-				Field::new("level", DataType::UInt32, false),
-				Field::new("start_line", DataType::UInt32, false),
-				Field::new("end_line", DataType::UInt32, false),
-				Field::new("hash", DataType::Utf8, false),
-				Field::new(
-					"embedding",
-					DataType::FixedSizeList(
-						Arc::new(Field::new("item", DataType::Float32, true)),
-						self.text_vector_dim as i32,
+			let schema = Arc::new(Schema::new(
+				vec![
+					Field::new("id", DataType::Utf8, false),
+					Field::new("path", DataType::Utf8, false),
+					Field::new("title", DataType::Utf8, false),
+					Field::new("content", DataType::Utf8, false),
+					Field::new(
+						"context",
+						DataType::List(Arc::new(Field::new("item", DataType::Utf8, true))),
+						true,
 					),
-					true,
-				),
-			]));
+					Field::new("level", DataType::UInt32, false),
+					Field::new("start_line", DataType::UInt32, false),
+					Field::new("end_line", DataType::UInt32, false),
+					Field::new("hash", DataType::Utf8, false),
+					Field::new(
+						"embedding",
+						DataType::FixedSizeList(
+							Arc::new(Field::new("item", DataType::Float32, true)),
+							self.text_vector_dim as i32,
+						),
+						true,
+					),
+				]
+				.into_iter()
+				.chain(provenance::provenance_fields())
+				.collect::<Vec<_>>(),
+			));
 
 			let _table = self
 				.db
@@ -298,7 +459,12 @@ impl Store {
 	// Delegate operations to modular components
 	pub async fn content_exists(&self, hash: &str, collection: &str) -> Result<bool> {
 		let table_ops = TableOperations::new(&self.db);
-		table_ops.content_exists(hash, collection).await
+		for table_name in shards::block_tables(&self.db, collection).await? {
+			if table_ops.content_exists(hash, &table_name).await? {
+				return Ok(true);
+			}
+		}
+		Ok(false)
 	}
 
 	pub async fn store_code_blocks(
@@ -307,48 +473,31 @@ impl Store {
 		embeddings: &[Vec<f32>],
 	) -> Result<()> {
 		let converter = BatchConverter::new(self.code_vector_dim);
-		let batch = converter.code_block_to_batch(blocks, embeddings)?;
-
-		let table_ops = TableOperations::new(&self.db);
-		table_ops.store_batch("code_blocks", batch).await?;
-
-		// Create or optimize vector index based on dataset growth
-		if let Ok(table) = self.db.open_table("code_blocks").execute().await {
-			let row_count = table.count_rows(None).await?;
-			let indices = table.list_indices().await?;
-			let has_index = indices.iter().any(|idx| idx.columns == vec!["embedding"]);
-
-			if !has_index {
-				// Create initial index
-				if let Err(e) = table_ops
-					.create_vector_index_optimized("code_blocks", "embedding", self.code_vector_dim)
-					.await
-				{
-					tracing::warn!("Failed to create optimized vector index: {}", e);
-				}
-			} else {
-				// Check if we should optimize existing index due to growth
-				if VectorOptimizer::should_optimize_for_growth(
-					row_count,
-					self.code_vector_dim,
-					true,
-				) {
-					tracing::info!("Dataset growth detected, optimizing code_blocks index");
-					if let Err(e) = table_ops
-						.recreate_vector_index_optimized(
-							"code_blocks",
-							"embedding",
-							self.code_vector_dim,
-						)
-						.await
-					{
-						tracing::warn!("Failed to recreate optimized vector index: {}", e);
-					}
-				}
-			}
-		}
+		self.store_partitioned(
+			"code_blocks",
+			blocks,
+			embeddings,
+			|block| block.path.as_str(),
+			|blocks, embeddings| converter.code_block_to_batch(blocks, embeddings),
+		)
+		.await
+	}
 
-		Ok(())
+	/// Store overview blocks of long code files, embedded with the code model
+	pub async fn store_file_summaries(
+		&self,
+		blocks: &[CodeBlock],
+		embeddings: &[Vec<f32>],
+	) -> Result<()> {
+		let converter = BatchConverter::new(self.code_vector_dim);
+		self.store_partitioned(
+			"file_summaries",
+			blocks,
+			embeddings,
+			|block| block.path.as_str(),
+			|blocks, embeddings| converter.code_block_to_batch(blocks, embeddings),
+		)
+		.await
 	}
 
 	pub async fn store_text_blocks(
@@ -357,48 +506,14 @@ impl Store {
 		embeddings: &[Vec<f32>],
 	) -> Result<()> {
 		let converter = BatchConverter::new(self.text_vector_dim);
-		let batch = converter.text_block_to_batch(blocks, embeddings)?;
-
-		let table_ops = TableOperations::new(&self.db);
-		table_ops.store_batch("text_blocks", batch).await?;
-
-		// Create or optimize vector index based on dataset growth
-		if let Ok(table) = self.db.open_table("text_blocks").execute().await {
-			let row_count = table.count_rows(None).await?;
-			let indices = table.list_indices().await?;
-			let has_index = indices.iter().any(|idx| idx.columns == vec!["embedding"]);
-
-			if !has_index {
-				// Create initial index
-				if let Err(e) = table_ops
-					.create_vector_index_optimized("text_blocks", "embedding", self.text_vector_dim)
-					.await
-				{
-					tracing::warn!("Failed to create optimized vector index: {}", e);
-				}
-			} else {
-				// Check if we should optimize existing index due to growth
-				if VectorOptimizer::should_optimize_for_growth(
-					row_count,
-					self.text_vector_dim,
-					true,
-				) {
-					tracing::info!("Dataset growth detected, optimizing text_blocks index");
-					if let Err(e) = table_ops
-						.recreate_vector_index_optimized(
-							"text_blocks",
-							"embedding",
-							self.text_vector_dim,
-						)
-						.await
-					{
-						tracing::warn!("Failed to recreate optimized vector index: {}", e);
-					}
-				}
-			}
-		}
-
-		Ok(())
+		self.store_partitioned(
+			"text_blocks",
+			blocks,
+			embeddings,
+			|block| block.path.as_str(),
+			|blocks, embeddings| converter.text_block_to_batch(blocks, embeddings),
+		)
+		.await
 	}
 
 	pub async fn store_document_blocks(
@@ -407,13 +522,54 @@ impl Store {
 		embeddings: &[Vec<f32>],
 	) -> Result<()> {
 		let converter = BatchConverter::new(self.text_vector_dim);
-		let batch = converter.document_block_to_batch(blocks, embeddings)?;
+		self.store_partitioned(
+			"document_blocks",
+			blocks,
+			embeddings,
+			|block| block.path.as_str(),
+			|blocks, embeddings| converter.document_block_to_batch(blocks, embeddings),
+		)
+		.await
+	}
+
+	/// Store blocks in a block table, or in its shards when sharding is enabled
+	async fn store_partitioned<T: Clone>(
+		&self,
+		base: &str,
+		blocks: &[T],
+		embeddings: &[Vec<f32>],
+		path: fn(&T) -> &str,
+		to_batch: impl Fn(&[T], &[Vec<f32>]) -> Result<RecordBatch>,
+	) -> Result<()> {
+		if !self.shard_by_directory {
+			return self.store_rows(base, to_batch(blocks, embeddings)?).await;
+		}
+		for (table_name, (blocks, embeddings)) in shards::partition(base, blocks, embeddings, path)
+		{
+			self.store_rows(&table_name, to_batch(&blocks, &embeddings)?)
+				.await?;
+		}
+		Ok(())
+	}
+
+	/// Embedding dimension of a block or GraphRAG table
+	fn vector_dim_of(&self, table_name: &str) -> usize {
+		match shards::base_of(table_name) {
+			Some("code_blocks" | "file_summaries") | None => self.code_vector_dim,
+			_ => self.text_vector_dim,
+		}
+	}
+
+	/// Append rows to a block table, creating or optimizing its vector index
+	/// as it grows
+	async fn store_rows(&self, table_name: &str, batch: RecordBatch) -> Result<()> {
+		let vector_dim = self.vector_dim_of(table_name);
 
 		let table_ops = TableOperations::new(&self.db);
-		table_ops.store_batch("document_blocks", batch).await?;
+		table_ops.store_batch(table_name, batch).await?;
 
 		// Create or optimize vector index based on dataset growth
-		if let Ok(table) = self.db.open_table("document_blocks").execute().await {
+		if let Ok(table) = self.db.open_table(table_name).execute().await {
 			let row_count = table.count_rows(None).await?;
 			let indices = table.list_indices().await?;
 			let has_index = indices.iter().any(|idx| idx.columns == vec!["embedding"]);
@@ -421,29 +577,17 @@ impl Store {
 			if !has_index {
 				// Create initial index
 				if let Err(e) = table_ops
-					.create_vector_index_optimized(
-						"document_blocks",
-						"embedding",
-						self.text_vector_dim,
-					)
+					.create_vector_index_optimized(table_name, "embedding", vector_dim)
 					.await
 				{
 					tracing::warn!("Failed to create optimized vector index: {}", e);
 				}
 			} else {
 				// Check if we should optimize existing index due to growth
-				if VectorOptimizer::should_optimize_for_growth(
-					row_count,
-					self.text_vector_dim,
-					true,
-				) {
-					tracing::info!("Dataset growth detected, optimizing document_blocks index");
+				if VectorOptimizer::should_optimize_for_growth(row_count, vector_dim, true) {
+					tracing::info!("Dataset growth detected, optimizing {} index", table_name);
 					if let Err(e) = table_ops
-						.recreate_vector_index_optimized(
-							"document_blocks",
-							"embedding",
-							self.text_vector_dim,
-						)
+						.recreate_vector_index_optimized(table_name, "embedding", vector_dim)
 						.await
 					{
 						tracing::warn!("Failed to recreate optimized vector index: {}", e);
@@ -478,43 +622,104 @@ impl Store {
 		distance_threshold: Option<f32>,
 		language_filter: Option<&str>,
 	) -> Result<Vec<CodeBlock>> {
-		let table_ops = TableOperations::new(&self.db);
-		if !table_ops.table_exists("code_blocks").await? {
-			return Ok(Vec::new());
+		self.get_code_blocks_with_filters(
+			embedding,
+			limit,
+			0,
+			distance_threshold,
+			language_filter,
+			None,
+			None,
+		)
+		.await
+	}
+
+	/// Vector search over code blocks, restricted by language, symbol and/or
+	/// where the search terms appear before ranking. `offset` skips that many
+	/// ranked results (after filtering) for paging. File overviews are ranked
+	/// with the code blocks, unless symbols or search terms are required.
+	#[allow(clippy::too_many_arguments)]
+	pub async fn get_code_blocks_with_filters(
+		&self,
+		embedding: Vec<f32>,
+		limit: Option<usize>,
+		offset: usize,
+		distance_threshold: Option<f32>,
+		language_filter: Option<&str>,
+		symbol_filter: Option<&SymbolFilter>,
+		content_filter: Option<&ContentFilter>,
+	) -> Result<Vec<CodeBlock>> {
+		// Apply language, symbol, content and path filters if specified
+		let mut predicates = Vec::new();
+		if let Some(language) = language_filter {
+			predicates.push(format!("language = '{}'", language));
+		}
+		if let Some(symbol) = symbol_filter {
+			predicates.push(symbol.sql_predicate());
+		}
+		if let Some(content) = content_filter {
+			predicates.push(content.sql_predicate());
+		}
+		if let Some(path) = &self.path_filter {
+			predicates.push(path.sql_predicate());
 		}
 
-		let table = self.db.open_table("code_blocks").execute().await?;
+		let mut all_code_blocks = Vec::new();
+		let converter = BatchConverter::new(self.code_vector_dim);
 
-		let mut query = table
-			.vector_search(embedding)?
-			.distance_type(DistanceType::Cosine) // Always use Cosine for consistency
-			.limit(limit.unwrap_or(10));
-		// Apply language filter if specified
-		if let Some(language) = language_filter {
-			query = query.only_if(format!("language = '{}'", language));
+		// Overviews declare no symbols and only quote the first line of blocks
+		let mut tables = self.search_tables("code_blocks").await?;
+		if symbol_filter.is_none() && content_filter.is_none() {
+			tables.extend(self.search_tables("file_summaries").await?);
 		}
 
-		// Apply intelligent search optimization
-		query = VectorOptimizer::optimize_query(query, &table, "code_blocks")
+		// Each shard returns its own best results; they are merged below
+		for table_name in tables {
+			let table = self.db.open_table(&table_name).execute().await?;
+
+			let mut query = table
+				.vector_search(embedding.clone())?
+				.distance_type(DistanceType::Cosine) // Always use Cosine for consistency
+				.limit(limit.unwrap_or(10).saturating_add(offset));
+			if !predicates.is_empty() {
+				query = query.only_if(predicates.join(" AND "));
+			}
+
+			// Apply intelligent search optimization
+			query = VectorOptimizer::optimize_query_tuned(
+				query,
+				&table,
+				&table_name,
+				self.search_tuning.get(&table_name),
+			)
 			.await
 			.map_err(|e| anyhow::anyhow!("Failed to optimize query: {}", e))?;
 
-		let mut results = query.execute().await?;
-		let mut all_code_blocks = Vec::new();
-		let converter = BatchConverter::new(self.code_vector_dim);
+			let mut results = VectorOptimizer::execute_with_fallback(query, &table_name).await?;
+			while let Some(batch) = results.try_next().await? {
+				if batch.num_rows() > 0 {
+					let mut code_blocks = converter.batch_to_code_blocks(&batch, None)?;
 
-		while let Some(batch) = results.try_next().await? {
-			if batch.num_rows() > 0 {
-				let mut code_blocks = converter.batch_to_code_blocks(&batch, None)?;
+					// Apply distance threshold if specified
+					if let Some(distance_threshold_value) = distance_threshold {
+						code_blocks.retain(|block| {
+							block.distance.is_none_or(|d| d <= distance_threshold_value)
+						});
+					}
 
-				// Apply distance threshold if specified
-				if let Some(distance_threshold_value) = distance_threshold {
-					code_blocks.retain(|block| {
-						block.distance.is_none_or(|d| d <= distance_threshold_value)
-					});
-				}
+					// The SQL prefilter is coarse; keep only exact symbol matches
+					if let Some(symbol) = symbol_filter {
+						code_blocks.retain(|block| symbol.matches(&block.symbols));
+					}
+					if let Some(content) = content_filter {
+						code_blocks.retain(|block| content.matches(&block.content, &block.spans));
+					}
+					if let Some(path) = &self.path_filter {
+						code_blocks.retain(|block| path.matches(&block.path));
+					}
 
-				all_code_blocks.append(&mut code_blocks);
+					all_code_blocks.append(&mut code_blocks);
+				}
 			}
 		}
 
@@ -530,6 +735,13 @@ impl Store {
 			}
 		});
 
+		let mut all_code_blocks =
+			crate::plugins::filter_results(&self.result_filters, "code", all_code_blocks).await;
+
+		// Skip earlier pages; with several shards there may be more than a page left
+		all_code_blocks.drain(..offset.min(all_code_blocks.len()));
+		all_code_blocks.truncate(limit.unwrap_or(10));
+
 		Ok(all_code_blocks)
 	}
 
@@ -545,39 +757,61 @@ impl Store {
 		limit: Option<usize>,
 		distance_threshold: Option<f32>,
 	) -> Result<Vec<TextBlock>> {
-		let table_ops = TableOperations::new(&self.db);
-		if !table_ops.table_exists("text_blocks").await? {
-			return Ok(Vec::new());
-		}
+		self.get_text_blocks_with_offset(embedding, limit, 0, distance_threshold)
+			.await
+	}
 
-		let table = self.db.open_table("text_blocks").execute().await?;
+	/// Like [`Self::get_text_blocks_with_config`], skipping the first `offset`
+	/// ranked results for paging
+	pub async fn get_text_blocks_with_offset(
+		&self,
+		embedding: Vec<f32>,
+		limit: Option<usize>,
+		offset: usize,
+		distance_threshold: Option<f32>,
+	) -> Result<Vec<TextBlock>> {
+		let mut all_text_blocks = Vec::new();
+		let converter = BatchConverter::new(self.text_vector_dim);
+
+		// Each shard returns its own best results; they are merged below
+		for table_name in self.search_tables("text_blocks").await? {
+			let table = self.db.open_table(&table_name).execute().await?;
 
-		let mut query = table
-			.vector_search(embedding)?
-			.distance_type(DistanceType::Cosine) // Always use Cosine for consistency
-			.limit(limit.unwrap_or(10));
+			let mut query = table
+				.vector_search(embedding.clone())?
+				.distance_type(DistanceType::Cosine) // Always use Cosine for consistency
+				.limit(limit.unwrap_or(10).saturating_add(offset));
+			if let Some(path) = &self.path_filter {
+				query = query.only_if(path.sql_predicate());
+			}
 
-		// Apply intelligent search optimization
-		query = VectorOptimizer::optimize_query(query, &table, "text_blocks")
+			// Apply intelligent search optimization
+			query = VectorOptimizer::optimize_query_tuned(
+				query,
+				&table,
+				&table_name,
+				self.search_tuning.get(&table_name),
+			)
 			.await
 			.map_err(|e| anyhow::anyhow!("Failed to optimize query: {}", e))?;
 
-		let mut results = query.execute().await?;
-		let mut all_text_blocks = Vec::new();
-		let converter = BatchConverter::new(self.text_vector_dim);
+			let mut results = VectorOptimizer::execute_with_fallback(query, &table_name).await?;
+			while let Some(batch) = results.try_next().await? {
+				if batch.num_rows() > 0 {
+					let mut text_blocks = converter.batch_to_text_blocks(&batch, None)?;
 
-		while let Some(batch) = results.try_next().await? {
-			if batch.num_rows() > 0 {
-				let mut text_blocks = converter.batch_to_text_blocks(&batch, None)?;
+					// Apply distance threshold if specified
+					if let Some(distance_threshold_value) = distance_threshold {
+						text_blocks.retain(|block| {
+							block.distance.is_none_or(|d| d <= distance_threshold_value)
+						});
+					}
+					if let Some(path) = &self.path_filter {
+						text_blocks.retain(|block| path.matches(&block.path));
+					}
 
-				// Apply distance threshold if specified
-				if let Some(distance_threshold_value) = distance_threshold {
-					text_blocks.retain(|block| {
-						block.distance.is_none_or(|d| d <= distance_threshold_value)
-					});
+					all_text_blocks.append(&mut text_blocks);
 				}
-
-				all_text_blocks.append(&mut text_blocks);
 			}
 		}
 
@@ -593,6 +827,13 @@ impl Store {
 			}
 		});
 
+		let mut all_text_blocks =
+			crate::plugins::filter_results(&self.result_filters, "text", all_text_blocks).await;
+
+		// Skip earlier pages; with several shards there may be more than a page left
+		all_text_blocks.drain(..offset.min(all_text_blocks.len()));
+		all_text_blocks.truncate(limit.unwrap_or(10));
+
 		Ok(all_text_blocks)
 	}
 
@@ -607,39 +848,61 @@ impl Store {
 		limit: Option<usize>,
 		distance_threshold: Option<f32>,
 	) -> Result<Vec<DocumentBlock>> {
-		let table_ops = TableOperations::new(&self.db);
-		if !table_ops.table_exists("document_blocks").await? {
-			return Ok(Vec::new());
-		}
+		self.get_document_blocks_with_offset(embedding, limit, 0, distance_threshold)
+			.await
+	}
+
+	/// Like [`Self::get_document_blocks_with_config`], skipping the first `offset`
+	/// ranked results for paging
+	pub async fn get_document_blocks_with_offset(
+		&self,
+		embedding: Vec<f32>,
+		limit: Option<usize>,
+		offset: usize,
+		distance_threshold: Option<f32>,
+	) -> Result<Vec<DocumentBlock>> {
+		let mut all_document_blocks = Vec::new();
+		let converter = BatchConverter::new(self.text_vector_dim);
 
-		let table = self.db.open_table("document_blocks").execute().await?;
+		// Each shard returns its own best results; they are merged below
+		for table_name in self.search_tables("document_blocks").await? {
+			let table = self.db.open_table(&table_name).execute().await?;
 
-		let mut query = table
-			.vector_search(embedding)?
-			.distance_type(DistanceType::Cosine) // Always use Cosine for consistency
-			.limit(limit.unwrap_or(10));
+			let mut query = table
+				.vector_search(embedding.clone())?
+				.distance_type(DistanceType::Cosine) // Always use Cosine for consistency
+				.limit(limit.unwrap_or(10).saturating_add(offset));
+			if let Some(path) = &self.path_filter {
+				query = query.only_if(path.sql_predicate());
+			}
 
-		// Apply intelligent search optimization
-		query = VectorOptimizer::optimize_query(query, &table, "document_blocks")
+			// Apply intelligent search optimization
+			query = VectorOptimizer::optimize_query_tuned(
+				query,
+				&table,
+				&table_name,
+				self.search_tuning.get(&table_name),
+			)
 			.await
 			.map_err(|e| anyhow::anyhow!("Failed to optimize query: {}", e))?;
 
-		let mut results = query.execute().await?;
-		let mut all_document_blocks = Vec::new();
-		let converter = BatchConverter::new(self.text_vector_dim);
+			let mut results = VectorOptimizer::execute_with_fallback(query, &table_name).await?;
+			while let Some(batch) = results.try_next().await? {
+				if batch.num_rows() > 0 {
+					let mut document_blocks = converter.batch_to_document_blocks(&batch, None)?;
 
-		while let Some(batch) = results.try_next().await? {
-			if batch.num_rows() > 0 {
-				let mut document_blocks = converter.batch_to_document_blocks(&batch, None)?;
+					// Apply distance threshold if specified
+					if let Some(distance_threshold_value) = distance_threshold {
+						document_blocks.retain(|block| {
+							block.distance.is_none_or(|d| d <= distance_threshold_value)
+						});
+					}
+					if let Some(path) = &self.path_filter {
+						document_blocks.retain(|block| path.matches(&block.path));
+					}
 
-				// Apply distance threshold if specified
-				if let Some(distance_threshold_value) = distance_threshold {
-					document_blocks.retain(|block| {
-						block.distance.is_none_or(|d| d <= distance_threshold_value)
-					});
+					all_document_blocks.append(&mut document_blocks);
 				}
-
-				all_document_blocks.append(&mut document_blocks);
 			}
 		}
 
@@ -655,36 +918,46 @@ impl Store {
 			}
 		});
 
+		let mut all_document_blocks =
+			crate::plugins::filter_results(&self.result_filters, "docs", all_document_blocks).await;
+
+		// Skip earlier pages; with several shards there may be more than a page left
+		all_document_blocks.drain(..offset.min(all_document_blocks.len()));
+		all_document_blocks.truncate(limit.unwrap_or(10));
+
 		Ok(all_document_blocks)
 	}
 
 	// Delegate other operations to modular components
 	pub async fn remove_blocks_by_path(&self, file_path: &str) -> Result<()> {
-		let table_ops = TableOperations::new(&self.db);
-		table_ops
-			.remove_blocks_by_path(file_path, "code_blocks")
-			.await?;
-		table_ops
-			.remove_blocks_by_path(file_path, "text_blocks")
-			.await?;
-		table_ops
-			.remove_blocks_by_path(file_path, "document_blocks")
-			.await?;
+		self.remove_content_blocks_by_path(file_path).await?;
 		// Clean up GraphRAG data for the file
-		table_ops
-			.remove_blocks_by_path(file_path, "graphrag_nodes")
-			.await?;
-		table_ops
-			.remove_blocks_by_path(file_path, "graphrag_relationships")
-			.await?;
+		self.remove_graph_nodes_by_path(file_path).await?;
+		self.remove_graph_relationships_by_path(file_path).await?;
+		Ok(())
+	}
+
+	/// Remove code, text and document blocks for a file, leaving GraphRAG data untouched
+	pub async fn remove_content_blocks_by_path(&self, file_path: &str) -> Result<()> {
+		let table_ops = TableOperations::new(&self.db);
+		for base in shards::BLOCK_TABLES {
+			for table_name in shards::tables_for_path(base, file_path) {
+				table_ops
+					.remove_blocks_by_path(file_path, &table_name)
+					.await?;
+			}
+		}
 		Ok(())
 	}
 
 	pub async fn get_all_indexed_file_paths(&self) -> Result<std::collections::HashSet<String>> {
 		let table_ops = TableOperations::new(&self.db);
-		table_ops
-			.get_all_indexed_file_paths(&["code_blocks", "text_blocks", "document_blocks"])
-			.await
+		let mut tables = Vec::new();
+		for base in shards::BLOCK_TABLES {
+			tables.extend(shards::block_tables(&self.db, base).await?);
+		}
+		let tables: Vec<&str> = tables.iter().map(String::as_str).collect();
+		table_ops.get_all_indexed_file_paths(&tables).await
 	}
 
 	pub async fn flush(&self) -> Result<()> {
@@ -692,6 +965,46 @@ impl Store {
 		table_ops.flush_all_tables().await
 	}
 
+	/// fsync the database files modified since `since`, and their directories,
+	/// so that completed writes survive a crash or power loss. Returns the
+	/// number of files synced.
+	pub async fn sync_to_disk(&self, since: std::time::SystemTime) -> Result<usize> {
+		let root = std::path::PathBuf::from(self.db.uri());
+		tokio::task::spawn_blocking(move || {
+			fn sync_dir(
+				dir: &std::path::Path,
+				since: std::time::SystemTime,
+				synced: &mut usize,
+			) -> std::io::Result<bool> {
+				let mut changed = false;
+				for entry in std::fs::read_dir(dir)? {
+					let entry = entry?;
+					let metadata = entry.metadata()?;
+					if metadata.is_dir() {
+						changed |= sync_dir(&entry.path(), since, synced)?;
+					} else if metadata.modified().is_ok_and(|modified| modified >= since) {
+						std::fs::File::open(entry.path())?.sync_all()?;
+						*synced += 1;
+						changed = true;
+					}
+				}
+				// New directory entries are only durable once the directory is synced
+				#[cfg(unix)]
+				if changed {
+					std::fs::File::open(dir)?.sync_all()?;
+				}
+				Ok(changed)
+			}
+
+			let mut synced = 0;
+			if root.is_dir() {
+				sync_dir(&root, since, &mut synced)?;
+			}
+			Ok(synced)
+		})
+		.await?
+	}
+
 	pub async fn close(self) -> Result<()> {
 		// The database connection is closed automatically when the Store is dropped
 		Ok(())
@@ -708,18 +1021,25 @@ impl Store {
 	}
 
 	pub async fn clear_code_table(&self) -> Result<()> {
-		let table_ops = TableOperations::new(&self.db);
-		table_ops.clear_table("code_blocks").await
+		self.clear_block_tables("code_blocks").await?;
+		self.clear_block_tables("file_summaries").await
 	}
 
 	pub async fn clear_docs_table(&self) -> Result<()> {
-		let table_ops = TableOperations::new(&self.db);
-		table_ops.clear_table("document_blocks").await
+		self.clear_block_tables("document_blocks").await
 	}
 
 	pub async fn clear_text_table(&self) -> Result<()> {
+		self.clear_block_tables("text_blocks").await
+	}
+
+	/// Drop a block table together with its shards
+	async fn clear_block_tables(&self, base: &str) -> Result<()> {
 		let table_ops = TableOperations::new(&self.db);
-		table_ops.clear_table("text_blocks").await
+		for table_name in shards::block_tables(&self.db, base).await? {
+			table_ops.clear_table(&table_name).await?;
+		}
+		Ok(())
 	}
 
 	pub fn get_code_vector_dim(&self) -> usize {
@@ -757,6 +1077,56 @@ impl Store {
 		metadata_ops.clear_git_metadata().await
 	}
 
+	/// Record the modification times of files whose blocks are written, and
+	/// add them to the checkpoint of the run indexing `commit_hash`
+	pub async fn store_indexed_files(
+		&self,
+		commit_hash: &str,
+		files: &[(String, u64)],
+	) -> Result<()> {
+		let metadata_ops = MetadataOperations::new(&self.db);
+		for (path, mtime) in files {
+			metadata_ops.store_file_metadata(path, *mtime).await?;
+		}
+		metadata_ops
+			.store_checkpoint_files(commit_hash, files)
+			.await
+	}
+
+	pub async fn get_checkpoint_files(
+		&self,
+		commit_hash: &str,
+	) -> Result<std::collections::HashSet<String>> {
+		let metadata_ops = MetadataOperations::new(&self.db);
+		metadata_ops.get_checkpoint_files(commit_hash).await
+	}
+
+	pub async fn clear_checkpoint(&self) -> Result<()> {
+		let metadata_ops = MetadataOperations::new(&self.db);
+		metadata_ops.clear_checkpoint().await
+	}
+
+	pub async fn store_index_manifest_hash(&self, manifest_hash: &str) -> Result<()> {
+		let metadata_ops = MetadataOperations::new(&self.db);
+		metadata_ops.store_index_manifest_hash(manifest_hash).await
+	}
+
+	/// Record the embedding models and dimensions the index is built with
+	pub async fn store_embedding_settings(&self, settings: &EmbeddingSettings) -> Result<()> {
+		let metadata_ops = MetadataOperations::new(&self.db);
+		metadata_ops.store_embedding_settings(settings).await
+	}
+
+	pub async fn get_embedding_settings(&self) -> Result<Option<EmbeddingSettings>> {
+		let metadata_ops = MetadataOperations::new(&self.db);
+		metadata_ops.get_embedding_settings().await
+	}
+
+	pub async fn get_index_manifest_hash(&self) -> Result<Option<String>> {
+		let metadata_ops = MetadataOperations::new(&self.db);
+		metadata_ops.get_index_manifest_hash().await
+	}
+
 	pub async fn get_graphrag_last_commit_hash(&self) -> Result<Option<String>> {
 		let metadata_ops = MetadataOperations::new(&self.db);
 		metadata_ops.get_graphrag_last_commit_hash().await
@@ -810,6 +1180,13 @@ impl Store {
 			.await
 	}
 
+	pub async fn remove_graph_relationships_by_source(&self, node_id: &str) -> Result<usize> {
+		let graphrag_ops = GraphRagOperations::new(&self.db, self.code_vector_dim);
+		graphrag_ops
+			.remove_graph_relationships_by_source(node_id)
+			.await
+	}
+
 	pub async fn search_graph_nodes(&self, embedding: &[f32], limit: usize) -> Result<RecordBatch> {
 		let graphrag_ops = GraphRagOperations::new(&self.db, self.code_vector_dim);
 		graphrag_ops.search_graph_nodes(embedding, limit).await
@@ -831,58 +1208,374 @@ impl Store {
 		debug_ops.show_file_chunks(file_path).await
 	}
 
+	/// Collect row counts, sizes and metadata describing the health of the index
+	pub async fn get_index_statistics(&self) -> Result<IndexStatistics> {
+		let current_dir = std::env::current_dir()?;
+		let database_path = crate::storage::get_project_database_path(&current_dir)?;
+
+		let debug_ops = DebugOperations::new(&self.db, self.code_vector_dim);
+		let tables = debug_ops.table_statistics(&database_path).await?;
+		let table_rows = |name: &str| {
+			tables
+				.iter()
+				.find(|table| table.name == name)
+				.map_or(0, |table| table.rows)
+		};
+
+		Ok(IndexStatistics {
+			unique_files: debug_ops.unique_indexed_files().await?,
+			total_size_bytes: tables.iter().map(|table| table.size_bytes).sum(),
+			code_vector_dim: self.code_vector_dim,
+			text_vector_dim: self.text_vector_dim,
+			last_indexed_commit: self.get_last_commit_hash().await?,
+			graphrag_last_commit: self.get_graphrag_last_commit_hash().await?,
+			graphrag_nodes: table_rows("graphrag_nodes"),
+			graphrag_relationships: table_rows("graphrag_relationships"),
+			languages: debug_ops.language_distribution().await?,
+			tables,
+		})
+	}
+
 	// Additional methods for backward compatibility
 	pub async fn get_code_block_by_symbol(&self, symbol: &str) -> Result<Option<CodeBlock>> {
-		let table_ops = TableOperations::new(&self.db);
-		if !table_ops.table_exists("code_blocks").await? {
-			return Ok(None);
-		}
-
-		let table = self.db.open_table("code_blocks").execute().await?;
-		let mut results = table
-			.query()
-			.only_if(format!("symbols LIKE '%{}%'", symbol))
-			.limit(1)
-			.execute()
-			.await?;
-
-		while let Some(batch) = results.try_next().await? {
-			if batch.num_rows() > 0 {
-				let converter = BatchConverter::new(self.code_vector_dim);
-				let code_blocks = converter.batch_to_code_blocks(&batch, None)?;
-				return Ok(code_blocks.into_iter().next());
+		self.first_code_block(&format!("symbols LIKE '%{}%'", symbol))
+			.await
+	}
+
+	pub async fn get_code_block_by_hash(&self, hash: &str) -> Result<CodeBlock> {
+		let tables = shards::block_tables(&self.db, "code_blocks").await?;
+		if tables.is_empty() {
+			return Err(anyhow::anyhow!("Code blocks table does not exist"));
+		}
+
+		self.first_code_block(&format!("hash = '{}'", hash))
+			.await?
+			.ok_or_else(|| anyhow::anyhow!("Code block with hash {} not found", hash))
+	}
+
+	/// First code block matching a predicate in any code table
+	async fn first_code_block(&self, predicate: &str) -> Result<Option<CodeBlock>> {
+		let converter = BatchConverter::new(self.code_vector_dim);
+		for table_name in shards::block_tables(&self.db, "code_blocks").await? {
+			let table = self.db.open_table(&table_name).execute().await?;
+			let mut results = table.query().only_if(predicate).limit(1).execute().await?;
+
+			while let Some(batch) = results.try_next().await? {
+				if batch.num_rows() > 0 {
+					let code_blocks = converter.batch_to_code_blocks(&batch, None)?;
+					return Ok(code_blocks.into_iter().next());
+				}
 			}
 		}
 
 		Ok(None)
 	}
 
-	pub async fn get_code_block_by_hash(&self, hash: &str) -> Result<CodeBlock> {
+	/// Code blocks of one file, ordered by their lines
+	pub async fn get_code_blocks_by_path(&self, file_path: &str) -> Result<Vec<CodeBlock>> {
 		let table_ops = TableOperations::new(&self.db);
-		if !table_ops.table_exists("code_blocks").await? {
-			return Err(anyhow::anyhow!("Code blocks table does not exist"));
+		let converter = BatchConverter::new(self.code_vector_dim);
+		let predicate = format!("path = '{}'", file_path.replace('\'', "''"));
+		let mut blocks = Vec::new();
+
+		for table_name in shards::tables_for_path("code_blocks", file_path) {
+			if !table_ops.table_exists(&table_name).await? {
+				continue;
+			}
+			let table = self.db.open_table(&table_name).execute().await?;
+			let mut results = table.query().only_if(predicate.clone()).execute().await?;
+			while let Some(batch) = results.try_next().await? {
+				if batch.num_rows() > 0 {
+					blocks.extend(converter.batch_to_code_blocks(&batch, None)?);
+				}
+			}
+		}
+
+		blocks.sort_by_key(|block| (block.start_line, block.end_line));
+		Ok(blocks)
+	}
+
+	/// Find the block an anchor cites, falling back to its key when the content changed
+	pub async fn resolve_anchor(&self, anchor: &Anchor) -> Result<Option<ResolvedAnchor>> {
+		let table_ops = TableOperations::new(&self.db);
+		let predicate = format!("path = '{}'", anchor.path());
+		let mut candidates = Vec::new();
+
+		for (base, table_name) in shards::BLOCK_TABLES.into_iter().flat_map(|base| {
+			shards::tables_for_path(base, anchor.path()).map(|table_name| (base, table_name))
+		}) {
+			if !table_ops.table_exists(&table_name).await? {
+				continue;
+			}
+
+			let table = self.db.open_table(&table_name).execute().await?;
+			let mut results = table.query().only_if(predicate.clone()).execute().await?;
+
+			while let Some(batch) = results.try_next().await? {
+				if batch.num_rows() == 0 {
+					continue;
+				}
+				match base {
+					"code_blocks" | "file_summaries" => {
+						let converter = BatchConverter::new(self.code_vector_dim);
+						candidates.extend(
+							converter
+								.batch_to_code_blocks(&batch, None)?
+								.into_iter()
+								.map(AnchoredBlock::Code),
+						);
+					}
+					"text_blocks" => {
+						let converter = BatchConverter::new(self.text_vector_dim);
+						candidates.extend(
+							converter
+								.batch_to_text_blocks(&batch, None)?
+								.into_iter()
+								.map(AnchoredBlock::Text),
+						);
+					}
+					_ => {
+						let converter = BatchConverter::new(self.text_vector_dim);
+						candidates.extend(
+							converter
+								.batch_to_document_blocks(&batch, None)?
+								.into_iter()
+								.map(AnchoredBlock::Document),
+						);
+					}
+				}
+			}
 		}
 
-		let table = self.db.open_table("code_blocks").execute().await?;
-		let mut results = table
-			.query()
-			.only_if(format!("hash = '{}'", hash))
-			.limit(1)
-			.execute()
-			.await?;
-
-		while let Some(batch) = results.try_next().await? {
-			if batch.num_rows() > 0 {
-				let converter = BatchConverter::new(self.code_vector_dim);
-				let code_blocks = converter.batch_to_code_blocks(&batch, None)?;
-				return code_blocks
-					.into_iter()
-					.next()
-					.ok_or_else(|| anyhow::anyhow!("Failed to convert result to CodeBlock"));
+		Ok(anchor::resolve(anchor, candidates))
+	}
+
+	/// Hashes of every block in one of the block tables, shards included
+	pub async fn get_all_block_hashes(&self, table_name: &str) -> Result<Vec<String>> {
+		let table_ops = TableOperations::new(&self.db);
+		let mut hashes = Vec::new();
+		for shard in shards::block_tables(&self.db, table_name).await? {
+			hashes.extend(table_ops.get_all_hashes(&shard).await?);
+		}
+		Ok(hashes)
+	}
+
+	/// Read blocks together with their stored embeddings, e.g. to share them
+	/// through the remote index cache
+	pub async fn get_blocks_with_embeddings(
+		&self,
+		table_name: &str,
+		hashes: &[String],
+	) -> Result<Vec<(AnchoredBlock, Vec<f32>)>> {
+		let mut blocks = Vec::new();
+		if hashes.is_empty() {
+			return Ok(blocks);
+		}
+
+		let vector_dim = match table_name {
+			"code_blocks" => self.code_vector_dim,
+			_ => self.text_vector_dim,
+		};
+		let converter = BatchConverter::new(vector_dim);
+
+		for shard in shards::block_tables(&self.db, table_name).await? {
+			let table = self.db.open_table(&shard).execute().await?;
+			for group in hashes.chunks(100) {
+				let filter = group
+					.iter()
+					.map(|hash| format!("'{}'", hash))
+					.collect::<Vec<_>>()
+					.join(", ");
+				let mut results = table
+					.query()
+					.only_if(format!("hash IN ({})", filter))
+					.execute()
+					.await?;
+
+				while let Some(batch) = results.try_next().await? {
+					if batch.num_rows() == 0 {
+						continue;
+					}
+					let embeddings = converter.batch_to_embeddings(&batch)?;
+					let rows: Vec<AnchoredBlock> = match table_name {
+						"code_blocks" => converter
+							.batch_to_code_blocks(&batch, None)?
+							.into_iter()
+							.map(AnchoredBlock::Code)
+							.collect(),
+						"text_blocks" => converter
+							.batch_to_text_blocks(&batch, None)?
+							.into_iter()
+							.map(AnchoredBlock::Text)
+							.collect(),
+						_ => converter
+							.batch_to_document_blocks(&batch, None)?
+							.into_iter()
+							.map(AnchoredBlock::Document)
+							.collect(),
+					};
+					blocks.extend(rows.into_iter().zip(embeddings));
+				}
 			}
 		}
 
-		Err(anyhow::anyhow!("Code block with hash {} not found", hash))
+		Ok(blocks)
+	}
+
+	/// Store blocks of any kind with embeddings computed elsewhere
+	pub async fn store_blocks_with_embeddings(
+		&self,
+		blocks: Vec<(AnchoredBlock, Vec<f32>)>,
+	) -> Result<()> {
+		let (mut code, mut code_embeddings) = (Vec::new(), Vec::new());
+		let (mut text, mut text_embeddings) = (Vec::new(), Vec::new());
+		let (mut docs, mut doc_embeddings) = (Vec::new(), Vec::new());
+
+		for (block, embedding) in blocks {
+			match block {
+				AnchoredBlock::Code(block) => {
+					code.push(block);
+					code_embeddings.push(embedding);
+				}
+				AnchoredBlock::Text(block) => {
+					text.push(block);
+					text_embeddings.push(embedding);
+				}
+				AnchoredBlock::Document(block) => {
+					docs.push(block);
+					doc_embeddings.push(embedding);
+				}
+			}
+		}
+
+		if !code.is_empty() {
+			self.store_code_blocks(&code, &code_embeddings).await?;
+		}
+		if !text.is_empty() {
+			self.store_text_blocks(&text, &text_embeddings).await?;
+		}
+		if !docs.is_empty() {
+			self.store_document_blocks(&docs, &doc_embeddings).await?;
+		}
+		Ok(())
+	}
+
+	/// Calibrate nprobes and refine factor of every block table with a vector
+	/// index against exact search, and record the results for later searches.
+	/// Tables without an index are searched exactly and left out.
+	pub async fn tune_search(
+		&self,
+		options: &tuning::TuneOptions,
+	) -> Result<Vec<tuning::TableTuning>> {
+		let mut tunings = Vec::new();
+		for base in shards::BLOCK_TABLES {
+			for table_name in shards::block_tables(&self.db, base).await? {
+				let table = self.db.open_table(&table_name).execute().await?;
+				if let Some(tuning) = tuning::tune_table(&table, &table_name, options).await? {
+					tunings.push(tuning);
+				}
+			}
+		}
+		tuning::save(&self.db, &tunings).await?;
+		Ok(tunings)
+	}
+
+	/// Forget recorded search parameters, going back to the size-based heuristics
+	pub async fn reset_search_tuning(&self) -> Result<()> {
+		tuning::save(&self.db, &[]).await
+	}
+
+	/// Build the vector indexes missing from the block and GraphRAG tables,
+	/// and bring existing ones up to date with the rows stored since. With
+	/// `wait`, return only once the indexes cover every row, or fail when
+	/// the timeout passes first.
+	pub async fn build_indexes(
+		&self,
+		wait: Option<std::time::Duration>,
+	) -> Result<Vec<vector_optimizer::IndexBuild>> {
+		use lancedb::table::{OptimizeAction, OptimizeOptions};
+		use vector_optimizer::{IndexBuild, IndexHealth};
+
+		let table_ops = TableOperations::new(&self.db);
+		let mut tables = Vec::new();
+		for base in shards::BLOCK_TABLES {
+			tables.extend(shards::block_tables(&self.db, base).await?);
+		}
+		if table_ops.table_exists("graphrag_nodes").await? {
+			tables.push("graphrag_nodes".to_string());
+		}
+
+		let mut builds = Vec::new();
+		for table_name in tables {
+			let vector_dim = self.vector_dim_of(&table_name);
+			let table = self.db.open_table(&table_name).execute().await?;
+			let rows = table.count_rows(None).await?;
+
+			let action = match VectorOptimizer::index_health(&table, rows).await? {
+				IndexHealth::NotNeeded => "skipped",
+				IndexHealth::Missing => {
+					table_ops
+						.create_vector_index_optimized(&table_name, "embedding", vector_dim)
+						.await?;
+					"created"
+				}
+				// Clusters trained on a fraction of the rows would fit the rest poorly
+				IndexHealth::Partial { .. } => {
+					table_ops
+						.recreate_vector_index_optimized(&table_name, "embedding", vector_dim)
+						.await?;
+					"rebuilt"
+				}
+				IndexHealth::Ready { unindexed: 0, .. } => "unchanged",
+				IndexHealth::Ready { .. } => {
+					table
+						.optimize(OptimizeAction::Index(OptimizeOptions::default()))
+						.await?;
+					"updated"
+				}
+			};
+
+			// Index operations above went through their own handle
+			let table = self.db.open_table(&table_name).execute().await?;
+			if let Some(timeout) = wait {
+				let indices = table.list_indices().await?;
+				if let Some(index) = indices.iter().find(|idx| idx.columns == vec!["embedding"]) {
+					table
+						.wait_for_index(&[index.name.as_str()], timeout)
+						.await
+						.map_err(|e| {
+							anyhow::anyhow!("Index of {} is not ready: {}", table_name, e)
+						})?;
+				}
+			}
+
+			builds.push(IndexBuild {
+				health: VectorOptimizer::index_health(&table, rows).await?,
+				table: table_name,
+				rows,
+				action,
+			});
+		}
+		Ok(builds)
+	}
+
+	/// Compact every table, dropping deleted rows, and prune the versions
+	/// older than `keep`; then bring the vector indexes up to date with the
+	/// compacted tables, as [`Store::build_indexes`] does
+	pub async fn optimize(
+		&self,
+		keep: chrono::Duration,
+	) -> Result<(
+		Vec<compaction::TableCompaction>,
+		Vec<vector_optimizer::IndexBuild>,
+	)> {
+		let mut compactions = Vec::new();
+		for table_name in self.db.table_names().execute().await? {
+			let table = self.db.open_table(&table_name).execute().await?;
+			compactions.push(compaction::compact_table(&table, &table_name, keep).await?);
+		}
+		let builds = self.build_indexes(None).await?;
+		Ok((compactions, builds))
 	}
 
 	pub async fn tables_exist(&self, table_names: &[&str]) -> Result<bool> {
@@ -897,13 +1590,22 @@ impl Store {
 		table_name: &str,
 	) -> Result<Vec<String>> {
 		let table_ops = TableOperations::new(&self.db);
-		table_ops
-			.get_file_blocks_metadata(file_path, table_name)
-			.await
+		let mut hashes = Vec::new();
+		for shard in shards::tables_for_path(table_name, file_path) {
+			hashes.extend(
+				table_ops
+					.get_file_blocks_metadata(file_path, &shard)
+					.await?,
+			);
+		}
+		Ok(hashes)
 	}
 
 	pub async fn remove_blocks_by_hashes(&self, hashes: &[String], table_name: &str) -> Result<()> {
 		let table_ops = TableOperations::new(&self.db);
-		table_ops.remove_blocks_by_hashes(hashes, table_name).await
+		for shard in shards::block_tables(&self.db, table_name).await? {
+			table_ops.remove_blocks_by_hashes(hashes, &shard).await?;
+		}
+		Ok(())
 	}
 }
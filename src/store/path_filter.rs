@@ -0,0 +1,139 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::Result;
+use std::collections::BTreeSet;
+
+use super::shards;
+
+/// Restricts searches to files at or under some project-relative paths.
+///
+/// `src/indexer` matches `src/indexer/mod.rs` but not `src/indexer_old.rs`.
+/// With sharded block tables only the shards of the paths are searched.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathFilter {
+	prefixes: Vec<String>,
+}
+
+impl PathFilter {
+	pub fn parse(paths: &[String]) -> Result<Self> {
+		let mut prefixes = Vec::new();
+		for path in paths {
+			let prefix = path.trim();
+			let prefix = prefix.strip_prefix("./").unwrap_or(prefix);
+			let prefix = prefix.trim_end_matches('/');
+			if prefix.is_empty() || prefix == "." {
+				return Err(anyhow::anyhow!("Path filter cannot be empty"));
+			}
+			if prefix.starts_with('/')
+				|| prefix
+					.split('/')
+					.any(|part| part == ".." || part.is_empty())
+				|| prefix
+					.chars()
+					.any(|c| matches!(c, '\'' | '"' | '\\' | '%' | '*'))
+			{
+				return Err(anyhow::anyhow!(
+					"Invalid path filter '{}': use a file or directory relative to the project root",
+					path
+				));
+			}
+			prefixes.push(prefix.to_string());
+		}
+		if prefixes.is_empty() {
+			return Err(anyhow::anyhow!("Path filter cannot be empty"));
+		}
+		prefixes.sort();
+		prefixes.dedup();
+		Ok(Self { prefixes })
+	}
+
+	pub fn matches(&self, path: &str) -> bool {
+		self.prefixes.iter().any(|prefix| {
+			path.strip_prefix(prefix.as_str())
+				.is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+		})
+	}
+
+	/// Shards that can hold matching files. A single-component path may be a
+	/// top-level directory or a file at the root, so both shards are included.
+	pub fn shards(&self) -> BTreeSet<&str> {
+		let mut names = BTreeSet::new();
+		for prefix in &self.prefixes {
+			match prefix.split_once('/') {
+				Some((directory, _)) => {
+					names.insert(directory);
+				}
+				None => {
+					names.insert(prefix.as_str());
+					names.insert(shards::ROOT_SHARD);
+				}
+			}
+		}
+		names
+	}
+
+	/// SQL prefilter on the `path` column. `_` acts as a LIKE wildcard, so
+	/// results are narrowed with [`Self::matches`].
+	pub(crate) fn sql_predicate(&self) -> String {
+		let conditions: Vec<String> = self
+			.prefixes
+			.iter()
+			.map(|prefix| format!("path = '{0}' OR path LIKE '{0}/%'", prefix))
+			.collect();
+		format!("({})", conditions.join(" OR "))
+	}
+}
+
+impl std::fmt::Display for PathFilter {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", self.prefixes.join(", "))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn filter(paths: &[&str]) -> Result<PathFilter> {
+		PathFilter::parse(&paths.iter().map(|p| p.to_string()).collect::<Vec<_>>())
+	}
+
+	#[test]
+	fn test_matches_and_shards() {
+		let filter = filter(&["./src/indexer/", "Cargo.toml"]).unwrap();
+		assert!(filter.matches("src/indexer/mod.rs"));
+		assert!(filter.matches("Cargo.toml"));
+		assert!(!filter.matches("src/indexer_old.rs"));
+		assert!(!filter.matches("src/main.rs"));
+		assert_eq!(
+			filter.shards().into_iter().collect::<Vec<_>>(),
+			vec!["Cargo.toml", "_root", "src"]
+		);
+		assert_eq!(
+			filter.sql_predicate(),
+			"(path = 'Cargo.toml' OR path LIKE 'Cargo.toml/%' OR path = 'src/indexer' OR path LIKE 'src/indexer/%')"
+		);
+	}
+
+	#[test]
+	fn test_rejects_invalid_paths() {
+		assert!(filter(&[]).is_err());
+		assert!(filter(&["/etc"]).is_err());
+		assert!(filter(&["../other"]).is_err());
+		assert!(filter(&["src//lib"]).is_err());
+		assert!(filter(&["src' OR 1=1"]).is_err());
+		assert!(filter(&["."]).is_err());
+	}
+}
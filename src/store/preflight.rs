@@ -0,0 +1,250 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Embedding drift checks before indexing
+//!
+//! The embedding models an index was built with are recorded in the store.
+//! Before an index run, the configured models and vector dimensions are
+//! compared with that record and with the dimensions of the existing tables,
+//! so a changed model is reported up front. Opening the [`super::Store`] would
+//! otherwise fail on tables of another dimension, and a model of the same
+//! dimension would silently mix incompatible vectors in one index.
+
+use anyhow::Result;
+use arrow::datatypes::DataType;
+use lancedb::{connect, Connection};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use super::{shards, MetadataOperations};
+use crate::config::Config;
+
+/// Embedding models and vector dimensions of an index
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmbeddingSettings {
+	pub code_model: String,
+	pub text_model: String,
+	pub code_dim: usize,
+	pub text_dim: usize,
+}
+
+impl EmbeddingSettings {
+	pub fn from_config(config: &Config) -> Self {
+		let dimension = |model_string: &str| {
+			let (provider, model) = crate::embedding::parse_provider_model(model_string);
+			config.embedding.get_vector_dimension(&provider, &model)
+		};
+		Self {
+			code_model: config.embedding.code_model.clone(),
+			text_model: config.embedding.text_model.clone(),
+			code_dim: dimension(&config.embedding.code_model),
+			text_dim: dimension(&config.embedding.text_model),
+		}
+	}
+}
+
+/// A difference between the existing index and the configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Drift {
+	/// The index was built with another model
+	Model {
+		kind: &'static str,
+		indexed: String,
+		configured: String,
+	},
+	/// A table holds vectors of another dimension
+	Dimension {
+		table: String,
+		indexed: usize,
+		configured: usize,
+	},
+}
+
+impl std::fmt::Display for Drift {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Drift::Model {
+				kind,
+				indexed,
+				configured,
+			} => write!(
+				f,
+				"{} embeddings were built with {}, the configured model is {}",
+				kind, indexed, configured
+			),
+			Drift::Dimension {
+				table,
+				indexed,
+				configured,
+			} => write!(
+				f,
+				"table {} holds {}-dimensional vectors, the configured model produces {}",
+				table, indexed, configured
+			),
+		}
+	}
+}
+
+/// Compare the recorded settings and the dimensions of existing tables with
+/// the configured settings
+pub fn compare(
+	recorded: Option<&EmbeddingSettings>,
+	table_dims: &[(String, usize)],
+	configured: &EmbeddingSettings,
+) -> Vec<Drift> {
+	let mut drift = Vec::new();
+	if let Some(recorded) = recorded {
+		for (kind, indexed, configured) in [
+			("code", &recorded.code_model, &configured.code_model),
+			("text", &recorded.text_model, &configured.text_model),
+		] {
+			if indexed != configured {
+				drift.push(Drift::Model {
+					kind,
+					indexed: indexed.clone(),
+					configured: configured.clone(),
+				});
+			}
+		}
+	}
+	for (table, indexed) in table_dims {
+		let expected = match shards::base_of(table).unwrap_or(table.as_str()) {
+			"code_blocks" | "file_summaries" | "graphrag_nodes" => configured.code_dim,
+			"text_blocks" | "document_blocks" => configured.text_dim,
+			_ => continue,
+		};
+		if *indexed != expected {
+			drift.push(Drift::Dimension {
+				table: table.clone(),
+				indexed: *indexed,
+				configured: expected,
+			});
+		}
+	}
+	drift
+}
+
+/// Check the index at `db_path` against the configuration without changing it
+pub async fn check(config: &Config, db_path: &Path) -> Result<Vec<Drift>> {
+	if !db_path.exists() {
+		return Ok(Vec::new());
+	}
+	let storage_path = db_path
+		.to_str()
+		.ok_or_else(|| anyhow::anyhow!("Invalid database path"))?;
+	let db = connect(storage_path).execute().await?;
+
+	let table_dims = table_dims(&db).await?;
+	let recorded = MetadataOperations::new(&db)
+		.get_embedding_settings()
+		.await?;
+	Ok(compare(
+		recorded.as_ref(),
+		&table_dims,
+		&EmbeddingSettings::from_config(config),
+	))
+}
+
+/// Embedding models recorded for the index at `db_path`, if any
+pub async fn recorded(db_path: &Path) -> Result<Option<EmbeddingSettings>> {
+	if !db_path.exists() {
+		return Ok(None);
+	}
+	let storage_path = db_path
+		.to_str()
+		.ok_or_else(|| anyhow::anyhow!("Invalid database path"))?;
+	let db = connect(storage_path).execute().await?;
+	MetadataOperations::new(&db).get_embedding_settings().await
+}
+
+/// Drop the tables of the index at `db_path` holding vectors of another
+/// dimension than the configured models produce, before rebuilding it
+pub async fn drop_mismatched_tables(config: &Config, db_path: &Path) -> Result<()> {
+	if !db_path.exists() {
+		return Ok(());
+	}
+	let storage_path = db_path
+		.to_str()
+		.ok_or_else(|| anyhow::anyhow!("Invalid database path"))?;
+	let db = connect(storage_path).execute().await?;
+
+	let configured = EmbeddingSettings::from_config(config);
+	for drift in compare(None, &table_dims(&db).await?, &configured) {
+		if let Drift::Dimension { table, .. } = drift {
+			db.drop_table(&table).await?;
+		}
+	}
+	Ok(())
+}
+
+/// Vector dimension of each table holding indexed blocks
+async fn table_dims(db: &Connection) -> Result<Vec<(String, usize)>> {
+	let mut table_dims = Vec::new();
+	for table_name in db.table_names().execute().await? {
+		if shards::base_of(&table_name).is_none() && table_name != "graphrag_nodes" {
+			continue;
+		}
+		let schema = db.open_table(&table_name).execute().await?.schema().await?;
+		if let Ok(field) = schema.field_with_name("embedding") {
+			if let DataType::FixedSizeList(_, size) = field.data_type() {
+				table_dims.push((table_name, *size as usize));
+			}
+		}
+	}
+	Ok(table_dims)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_compare() {
+		let configured = EmbeddingSettings {
+			code_model: "voyage:voyage-code-3".to_string(),
+			text_model: "voyage:voyage-3.5-lite".to_string(),
+			code_dim: 1024,
+			text_dim: 1024,
+		};
+		assert!(compare(Some(&configured), &[], &configured).is_empty());
+
+		let recorded = EmbeddingSettings {
+			code_model: "jina:jina-embeddings-v2-base-code".to_string(),
+			..configured.clone()
+		};
+		let tables = vec![
+			("code_blocks.src".to_string(), 768),
+			("text_blocks".to_string(), 1024),
+			("git_metadata".to_string(), 1),
+		];
+		assert_eq!(
+			compare(Some(&recorded), &tables, &configured),
+			vec![
+				Drift::Model {
+					kind: "code",
+					indexed: "jina:jina-embeddings-v2-base-code".to_string(),
+					configured: "voyage:voyage-code-3".to_string(),
+				},
+				Drift::Dimension {
+					table: "code_blocks.src".to_string(),
+					indexed: 768,
+					configured: 1024,
+				},
+			]
+		);
+
+		// Indexes from before the settings were recorded are checked by dimension only
+		assert_eq!(compare(None, &tables, &configured).len(), 1);
+	}
+}
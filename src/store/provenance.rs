@@ -0,0 +1,341 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Per-block provenance
+//!
+//! Every stored block records which octocode and chunker produced it, which
+//! model embedded it and when, so consumers can tell stale blocks apart and
+//! reproduce results. The values live in four nullable columns appended to
+//! each block table; blocks indexed before they existed have no provenance.
+//! With `index.blame`, three more columns record the last commit that changed
+//! the block's lines.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, StringArray, UInt32Array};
+use arrow::datatypes::{DataType, Field};
+use arrow::record_batch::RecordBatch;
+
+/// Version of the chunking rules; bump whenever the same file would be split
+/// into different blocks
+pub const CHUNKER_VERSION: u32 = 2;
+
+/// How and when a block was indexed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Provenance {
+	pub octocode_version: String,
+	pub chunker_version: u32,
+	/// Embedding model as configured ("provider:model")
+	pub embedding_model: String,
+	pub indexed_at: DateTime<Utc>,
+	/// Last commit changing the block, when indexed with `index.blame`
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub blame: Option<Blame>,
+}
+
+/// The last commit that changed one of a block's lines, from `git blame`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Blame {
+	pub commit: String,
+	pub author: String,
+	pub committed_at: DateTime<Utc>,
+}
+
+impl Blame {
+	/// Age of the change, e.g. "3 months ago"
+	pub fn age(&self, now: DateTime<Utc>) -> String {
+		let days = (now - self.committed_at).num_days().max(0);
+		let (count, unit) = match days {
+			0 => return "today".to_string(),
+			1..=13 => (days, "day"),
+			14..=59 => (days / 7, "week"),
+			60..=729 => (days / 30, "month"),
+			_ => (days / 365, "year"),
+		};
+		format!(
+			"{} {}{} ago",
+			count,
+			unit,
+			if count == 1 { "" } else { "s" }
+		)
+	}
+}
+
+impl Provenance {
+	/// Provenance for blocks indexed at `indexed_at` with the given embedding model
+	pub fn new(embedding_model: &str, indexed_at: DateTime<Utc>) -> Self {
+		Self {
+			octocode_version: env!("CARGO_PKG_VERSION").to_string(),
+			chunker_version: CHUNKER_VERSION,
+			embedding_model: embedding_model.to_string(),
+			indexed_at,
+			blame: None,
+		}
+	}
+}
+
+/// Provenance columns, appended after the other fields of every block table
+pub(crate) fn provenance_fields() -> Vec<Field> {
+	vec![
+		Field::new("octocode_version", DataType::Utf8, true),
+		Field::new("chunker_version", DataType::UInt32, true),
+		Field::new("embedding_model", DataType::Utf8, true),
+		Field::new("indexed_at", DataType::Utf8, true),
+		Field::new("blame_commit", DataType::Utf8, true),
+		Field::new("blame_author", DataType::Utf8, true),
+		Field::new("blame_committed_at", DataType::Utf8, true),
+	]
+}
+
+/// Column arrays for [`provenance_fields`], one row per block
+pub(crate) fn provenance_arrays<'a>(
+	provenance: impl Iterator<Item = Option<&'a Provenance>>,
+) -> Vec<ArrayRef> {
+	let provenance: Vec<Option<&Provenance>> = provenance.collect();
+	vec![
+		Arc::new(StringArray::from_iter(
+			provenance
+				.iter()
+				.map(|p| p.map(|p| p.octocode_version.as_str())),
+		)),
+		Arc::new(UInt32Array::from_iter(
+			provenance.iter().map(|p| p.map(|p| p.chunker_version)),
+		)),
+		Arc::new(StringArray::from_iter(
+			provenance
+				.iter()
+				.map(|p| p.map(|p| p.embedding_model.as_str())),
+		)),
+		Arc::new(StringArray::from_iter(
+			provenance
+				.iter()
+				.map(|p| p.map(|p| p.indexed_at.to_rfc3339())),
+		)),
+		Arc::new(StringArray::from_iter(
+			blame(&provenance).map(|b| b.map(|b| b.commit.as_str())),
+		)),
+		Arc::new(StringArray::from_iter(
+			blame(&provenance).map(|b| b.map(|b| b.author.as_str())),
+		)),
+		Arc::new(StringArray::from_iter(
+			blame(&provenance).map(|b| b.map(|b| b.committed_at.to_rfc3339())),
+		)),
+	]
+}
+
+fn blame<'a>(
+	provenance: &'a [Option<&'a Provenance>],
+) -> impl Iterator<Item = Option<&'a Blame>> + 'a {
+	provenance.iter().map(|p| p.and_then(|p| p.blame.as_ref()))
+}
+
+/// Reads provenance back from a block batch
+pub(crate) struct ProvenanceColumns<'a> {
+	octocode_version: &'a StringArray,
+	chunker_version: &'a UInt32Array,
+	embedding_model: &'a StringArray,
+	indexed_at: &'a StringArray,
+	/// Absent from tables written before blame was recorded
+	blame: Option<[&'a StringArray; 3]>,
+}
+
+impl<'a> ProvenanceColumns<'a> {
+	/// `None` for batches from tables or queries without the columns
+	pub fn from_batch(batch: &'a RecordBatch) -> Option<Self> {
+		let strings = |name: &str| {
+			batch
+				.column_by_name(name)
+				.and_then(|col| col.as_any().downcast_ref::<StringArray>())
+		};
+		Some(Self {
+			octocode_version: strings("octocode_version")?,
+			chunker_version: batch
+				.column_by_name("chunker_version")
+				.and_then(|col| col.as_any().downcast_ref::<UInt32Array>())?,
+			embedding_model: strings("embedding_model")?,
+			indexed_at: strings("indexed_at")?,
+			blame: strings("blame_commit")
+				.zip(strings("blame_author"))
+				.zip(strings("blame_committed_at"))
+				.map(|((commit, author), committed_at)| [commit, author, committed_at]),
+		})
+	}
+
+	pub fn get(&self, row: usize) -> Option<Provenance> {
+		if self.octocode_version.is_null(row)
+			|| self.chunker_version.is_null(row)
+			|| self.embedding_model.is_null(row)
+			|| self.indexed_at.is_null(row)
+		{
+			return None;
+		}
+		Some(Provenance {
+			octocode_version: self.octocode_version.value(row).to_string(),
+			chunker_version: self.chunker_version.value(row),
+			embedding_model: self.embedding_model.value(row).to_string(),
+			indexed_at: DateTime::parse_from_rfc3339(self.indexed_at.value(row))
+				.ok()?
+				.with_timezone(&Utc),
+			blame: self.blame(row),
+		})
+	}
+
+	fn blame(&self, row: usize) -> Option<Blame> {
+		let [commit, author, committed_at] = self.blame?;
+		if commit.is_null(row) || author.is_null(row) || committed_at.is_null(row) {
+			return None;
+		}
+		Some(Blame {
+			commit: commit.value(row).to_string(),
+			author: author.value(row).to_string(),
+			committed_at: DateTime::parse_from_rfc3339(committed_at.value(row))
+				.ok()?
+				.with_timezone(&Utc),
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::store::{CodeBlock, Store};
+	use arrow::datatypes::Schema;
+
+	fn provenance(model: &str, blame: Option<Blame>) -> Provenance {
+		let indexed_at = DateTime::parse_from_rfc3339("2025-06-01T12:00:00Z")
+			.unwrap()
+			.with_timezone(&Utc);
+		Provenance {
+			blame,
+			..Provenance::new(model, indexed_at)
+		}
+	}
+
+	fn blame() -> Blame {
+		Blame {
+			commit: "4f2a9c1".to_string(),
+			author: "Dana".to_string(),
+			committed_at: DateTime::parse_from_rfc3339("2025-03-01T08:30:00Z")
+				.unwrap()
+				.with_timezone(&Utc),
+		}
+	}
+
+	fn batch(fields: Vec<Field>, columns: Vec<ArrayRef>) -> RecordBatch {
+		RecordBatch::try_new(Arc::new(Schema::new(fields)), columns).unwrap()
+	}
+
+	#[test]
+	fn test_provenance_round_trips_through_columns() {
+		let rows = [
+			Some(provenance("voyage:voyage-code-3", Some(blame()))),
+			Some(provenance("jina:jina-embeddings-v3", None)),
+			None,
+		];
+		let batch = batch(
+			provenance_fields(),
+			provenance_arrays(rows.iter().map(Option::as_ref)),
+		);
+
+		let columns = ProvenanceColumns::from_batch(&batch).unwrap();
+		for (row, expected) in rows.iter().enumerate() {
+			assert_eq!(&columns.get(row), expected);
+		}
+		assert_eq!(columns.get(0).unwrap().chunker_version, CHUNKER_VERSION);
+	}
+
+	#[test]
+	fn test_tables_without_provenance_columns() {
+		// Indexed before provenance was recorded
+		let path = Field::new("path", DataType::Utf8, false);
+		let paths: ArrayRef = Arc::new(StringArray::from(vec!["src/lib.rs"]));
+		assert!(ProvenanceColumns::from_batch(&batch(vec![path], vec![paths])).is_none());
+
+		// Indexed before blame was recorded
+		let rows = [Some(provenance("voyage:voyage-code-3", Some(blame())))];
+		let batch = batch(
+			provenance_fields()[..4].to_vec(),
+			provenance_arrays(rows.iter().map(Option::as_ref))[..4].to_vec(),
+		);
+		let columns = ProvenanceColumns::from_batch(&batch).unwrap();
+		assert_eq!(
+			columns.get(0),
+			Some(provenance("voyage:voyage-code-3", None))
+		);
+	}
+
+	#[tokio::test]
+	async fn test_stored_blocks_keep_their_provenance() {
+		let _lock = crate::mcp::CURRENT_DIR_LOCK.lock().await;
+		let project =
+			std::env::temp_dir().join(format!("octocode_provenance_{}", std::process::id()));
+		std::fs::create_dir_all(&project).unwrap();
+		let before = std::env::current_dir().unwrap();
+		std::env::set_current_dir(&project).unwrap();
+		let store = Store::new().await;
+		std::env::set_current_dir(&before).unwrap();
+		let store = store.unwrap();
+		store.initialize_collections().await.unwrap();
+
+		let block = |path: &str, start_line: usize, provenance: Option<Provenance>| CodeBlock {
+			path: path.to_string(),
+			language: "rust".to_string(),
+			content: format!("fn block_{}() {{}}", start_line),
+			symbols: vec![format!("block_{}", start_line)],
+			start_line,
+			end_line: start_line,
+			hash: format!("{}:{}", path, start_line),
+			spans: Vec::new(),
+			provenance,
+			distance: None,
+		};
+		let recorded = provenance("voyage:voyage-code-3", Some(blame()));
+		let blocks = vec![
+			block("src/lib.rs", 1, Some(recorded.clone())),
+			block("src/lib.rs", 3, None),
+			block("src/main.rs", 1, Some(recorded.clone())),
+		];
+		let embeddings = vec![vec![0.1; store.code_vector_dim]; blocks.len()];
+		store.store_code_blocks(&blocks, &embeddings).await.unwrap();
+
+		let mut stored = store.get_code_blocks_by_path("src/lib.rs").await.unwrap();
+		stored.sort_by_key(|block| block.start_line);
+		let provenance: Vec<Option<Provenance>> =
+			stored.into_iter().map(|block| block.provenance).collect();
+		assert_eq!(provenance, vec![Some(recorded.clone()), None]);
+
+		// A file that was never indexed, or was deleted since, has no blocks
+		assert!(store
+			.get_code_blocks_by_path("src/missing.rs")
+			.await
+			.unwrap()
+			.is_empty());
+		store.remove_blocks_by_path("src/lib.rs").await.unwrap();
+		assert!(store
+			.get_code_blocks_by_path("src/lib.rs")
+			.await
+			.unwrap()
+			.is_empty());
+		let remaining = store.get_code_blocks_by_path("src/main.rs").await.unwrap();
+		assert_eq!(remaining.len(), 1);
+		assert_eq!(remaining[0].provenance, Some(recorded));
+
+		if let Ok(storage) = crate::storage::get_project_storage_path(&project) {
+			let _ = std::fs::remove_dir_all(storage);
+		}
+		let _ = std::fs::remove_dir_all(&project);
+	}
+}
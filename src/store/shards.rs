@@ -0,0 +1,145 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Block tables split by top-level directory
+//!
+//! With `index.shard_by_directory`, code, text and document blocks and file
+//! overviews are written to one table per top-level directory of their file:
+//! blocks of `packages/ui/button.ts` go to `code_blocks.packages`, files at the
+//! project root to `code_blocks._root`. Every shard keeps its own vector index, so
+//! indexes stay small, and searches limited to some paths only open the shards
+//! those paths live in. The unsharded tables are always read as well, so an
+//! index built before sharding was turned on keeps working until it is rebuilt.
+
+use anyhow::Result;
+use lancedb::Connection;
+use std::collections::BTreeMap;
+
+/// Tables holding blocks of indexed files, each possibly split into shards.
+/// `file_summaries` holds one overview block per long code file, with the
+/// columns of `code_blocks`
+pub const BLOCK_TABLES: [&str; 4] = [
+	"code_blocks",
+	"text_blocks",
+	"document_blocks",
+	"file_summaries",
+];
+
+/// Shard of files directly in the project directory
+pub const ROOT_SHARD: &str = "_root";
+
+/// Shard a file belongs to: its top-level directory
+pub fn shard_of(path: &str) -> &str {
+	match path.split_once('/') {
+		Some((directory, _)) if !directory.is_empty() => directory,
+		_ => ROOT_SHARD,
+	}
+}
+
+/// Table of one shard of a block table. LanceDB table names only allow
+/// letters, digits, `_` and `-` besides the separating `.`
+pub fn shard_table(base: &str, shard: &str) -> String {
+	let shard: String = shard
+		.chars()
+		.map(|c| {
+			if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+				c
+			} else {
+				'_'
+			}
+		})
+		.collect();
+	format!("{}.{}", base, shard)
+}
+
+/// Block table a table belongs to, whether unsharded or one of its shards
+pub fn base_of(table_name: &str) -> Option<&'static str> {
+	BLOCK_TABLES.into_iter().find(|base| {
+		table_name == *base
+			|| table_name
+				.strip_prefix(base)
+				.is_some_and(|rest| rest.starts_with('.'))
+	})
+}
+
+/// Existing tables of a block table: the unsharded one first, then the shards
+pub async fn block_tables(db: &Connection, base: &str) -> Result<Vec<String>> {
+	let mut tables: Vec<String> = db
+		.table_names()
+		.execute()
+		.await?
+		.into_iter()
+		.filter(|name| base_of(name) == Some(base))
+		.collect();
+	tables.sort();
+	Ok(tables)
+}
+
+/// Tables that may hold blocks of one file: the unsharded table and the
+/// file's shard, whether or not they exist
+pub fn tables_for_path(base: &str, path: &str) -> [String; 2] {
+	[base.to_string(), shard_table(base, shard_of(path))]
+}
+
+/// Split blocks and their embeddings by the shard table of each block's file
+pub fn partition<T: Clone>(
+	base: &str,
+	blocks: &[T],
+	embeddings: &[Vec<f32>],
+	path: fn(&T) -> &str,
+) -> BTreeMap<String, (Vec<T>, Vec<Vec<f32>>)> {
+	let mut shards: BTreeMap<String, (Vec<T>, Vec<Vec<f32>>)> = BTreeMap::new();
+	for (block, embedding) in blocks.iter().zip(embeddings) {
+		let (shard_blocks, shard_embeddings) = shards
+			.entry(shard_table(base, shard_of(path(block))))
+			.or_default();
+		shard_blocks.push(block.clone());
+		shard_embeddings.push(embedding.clone());
+	}
+	shards
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_shard_tables() {
+		assert_eq!(shard_of("packages/ui/button.ts"), "packages");
+		assert_eq!(shard_of("Cargo.toml"), ROOT_SHARD);
+		assert_eq!(shard_table("code_blocks", ".github"), "code_blocks._github");
+		assert_eq!(
+			tables_for_path("text_blocks", "README.md"),
+			["text_blocks".to_string(), "text_blocks._root".to_string()]
+		);
+
+		assert_eq!(base_of("code_blocks"), Some("code_blocks"));
+		assert_eq!(base_of("code_blocks.src"), Some("code_blocks"));
+		assert_eq!(base_of("code_blocks_old"), None);
+		assert_eq!(base_of("file_summaries.src"), Some("file_summaries"));
+		assert_eq!(base_of("graphrag_nodes"), None);
+
+		let paths = ["src/a.rs", "docs/b.md", "src/c.rs"];
+		let embeddings = vec![vec![1.0], vec![2.0], vec![3.0]];
+		let shards = partition("code_blocks", &paths, &embeddings, |path| *path);
+		assert_eq!(
+			shards.keys().collect::<Vec<_>>(),
+			vec!["code_blocks.docs", "code_blocks.src"]
+		);
+		assert_eq!(
+			shards["code_blocks.src"],
+			(vec!["src/a.rs", "src/c.rs"], vec![vec![1.0], vec![3.0]])
+		);
+	}
+}
@@ -0,0 +1,122 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::Result;
+
+/// Exact or prefix filter on the `symbols` column of `code_blocks`.
+///
+/// `parse_config` matches only blocks declaring that exact symbol;
+/// `parse_*` matches any symbol starting with `parse_`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SymbolFilter {
+	name: String,
+	prefix: bool,
+}
+
+impl SymbolFilter {
+	pub fn parse(pattern: &str) -> Result<Self> {
+		let pattern = pattern.trim();
+		let (name, prefix) = match pattern.strip_suffix('*') {
+			Some(name) => (name, true),
+			None => (pattern, false),
+		};
+
+		if name.is_empty() {
+			return Err(anyhow::anyhow!("Symbol name cannot be empty"));
+		}
+		if name
+			.chars()
+			.any(|c| c.is_whitespace() || matches!(c, '\'' | '"' | '\\' | '%' | '*'))
+		{
+			return Err(anyhow::anyhow!(
+				"Invalid symbol '{}': use a plain identifier, optionally ending with '*' for prefix matching",
+				pattern
+			));
+		}
+
+		Ok(Self {
+			name: name.to_string(),
+			prefix,
+		})
+	}
+
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	/// Whether any of a block's symbols satisfies the filter
+	pub fn matches(&self, symbols: &[String]) -> bool {
+		symbols.iter().any(|symbol| {
+			if self.prefix {
+				symbol.starts_with(&self.name)
+			} else {
+				symbol == &self.name
+			}
+		})
+	}
+
+	/// Coarse SQL prefilter over the JSON-encoded `symbols` column.
+	/// `_` acts as a LIKE wildcard, so results are narrowed with [`Self::matches`].
+	pub(crate) fn sql_predicate(&self) -> String {
+		if self.prefix {
+			format!("symbols LIKE '%\"{}%'", self.name)
+		} else {
+			format!("symbols LIKE '%\"{}\"%'", self.name)
+		}
+	}
+}
+
+impl std::fmt::Display for SymbolFilter {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		if self.prefix {
+			write!(f, "{}*", self.name)
+		} else {
+			write!(f, "{}", self.name)
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn symbols(names: &[&str]) -> Vec<String> {
+		names.iter().map(|name| name.to_string()).collect()
+	}
+
+	#[test]
+	fn test_exact_match_ignores_similar_symbols() {
+		let filter = SymbolFilter::parse("parse_config").unwrap();
+		assert!(filter.matches(&symbols(&["load", "parse_config"])));
+		assert!(!filter.matches(&symbols(&["parse_config_file", "parse"])));
+		assert_eq!(filter.sql_predicate(), "symbols LIKE '%\"parse_config\"%'");
+	}
+
+	#[test]
+	fn test_prefix_match() {
+		let filter = SymbolFilter::parse("parse_*").unwrap();
+		assert!(filter.matches(&symbols(&["parse_config_file"])));
+		assert!(!filter.matches(&symbols(&["reparse_config"])));
+		assert_eq!(filter.sql_predicate(), "symbols LIKE '%\"parse_%'");
+		assert_eq!(filter.to_string(), "parse_*");
+	}
+
+	#[test]
+	fn test_rejects_invalid_patterns() {
+		assert!(SymbolFilter::parse("*").is_err());
+		assert!(SymbolFilter::parse("  ").is_err());
+		assert!(SymbolFilter::parse("foo' OR 1=1").is_err());
+		assert!(SymbolFilter::parse("a*b").is_err());
+	}
+}
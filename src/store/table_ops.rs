@@ -227,6 +227,35 @@ impl<'a> TableOperations<'a> {
 		Ok(())
 	}
 
+	/// Get the hashes of every block in a table
+	pub async fn get_all_hashes(&self, table_name: &str) -> Result<Vec<String>> {
+		let mut hashes = Vec::new();
+
+		if !self.table_exists(table_name).await? {
+			return Ok(hashes);
+		}
+
+		let table = self.db.open_table(table_name).execute().await?;
+		let mut results = table
+			.query()
+			.select(Select::Columns(vec!["hash".to_string()]))
+			.execute()
+			.await?;
+
+		while let Some(batch) = results.try_next().await? {
+			if let Some(hash_array) = batch
+				.column_by_name("hash")
+				.and_then(|column| column.as_any().downcast_ref::<arrow::array::StringArray>())
+			{
+				for i in 0..hash_array.len() {
+					hashes.push(hash_array.value(i).to_string());
+				}
+			}
+		}
+
+		Ok(hashes)
+	}
+
 	/// Get file metadata (hashes) for a specific file path from a table
 	pub async fn get_file_blocks_metadata(
 		&self,
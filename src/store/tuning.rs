@@ -0,0 +1,397 @@
+// Copyright 2025 Muvon Un Limited
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Search parameters calibrated against exact search
+//!
+//! `VectorOptimizer` picks nprobes and refine factor from the row count alone.
+//! `octocode store tune` measures instead: stored vectors are used as queries,
+//! their exact nearest neighbours (found without the index) are compared with
+//! what the index returns at several settings, and the fastest setting that
+//! reaches the target recall is recorded per table. Searches use the recorded
+//! setting until the table grows or shrinks by more than half.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Instant;
+
+use arrow::array::{Array, Float32Array, Int64Array, StringArray, UInt32Array};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use futures::TryStreamExt;
+use lancedb::{
+	query::{ExecutableQuery, QueryBase, Select},
+	Connection, DistanceType, Table,
+};
+
+use crate::store::batch_converter::BatchConverter;
+use crate::store::table_ops::TableOperations;
+use crate::store::vector_optimizer::SearchParams;
+
+/// Table recording the calibrated search parameters
+pub(crate) const TUNING_TABLE: &str = "search_tuning";
+
+/// Rows scanned to pick sample vectors from, spread evenly over the scan
+const SAMPLE_SCAN_ROWS: usize = 2000;
+
+/// How a calibration is run
+#[derive(Debug, Clone)]
+pub struct TuneOptions {
+	/// Stored vectors used as queries
+	pub samples: usize,
+	/// Neighbours compared per query
+	pub top_k: usize,
+	/// Smallest acceptable share of exact neighbours found, 0.0-1.0
+	pub target_recall: f32,
+}
+
+impl Default for TuneOptions {
+	fn default() -> Self {
+		Self {
+			samples: 20,
+			top_k: 10,
+			target_recall: 0.95,
+		}
+	}
+}
+
+/// Recall and latency of one setting
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Probe {
+	pub nprobes: usize,
+	pub refine_factor: Option<u32>,
+	pub recall: f32,
+	/// Mean latency of a query, in milliseconds
+	pub latency_ms: f32,
+}
+
+/// Setting recorded for a table
+#[derive(Debug, Clone, Serialize)]
+pub struct TableTuning {
+	pub table: String,
+	pub rows: usize,
+	pub chosen: Probe,
+	/// Every setting measured, cheapest first
+	pub probes: Vec<Probe>,
+}
+
+/// Calibrated parameters as read back for searches
+#[derive(Debug, Clone)]
+pub struct TunedSearch {
+	pub params: SearchParams,
+	/// Rows when the table was calibrated
+	pub rows: usize,
+}
+
+impl TunedSearch {
+	/// The calibration still describes a table of `rows` rows
+	pub fn applies_to(&self, rows: usize) -> bool {
+		rows * 2 >= self.rows && rows <= self.rows * 2
+	}
+}
+
+/// Settings measured for a table: nprobes from 1% of the estimated
+/// partitions to all of them, each without and with refinement
+fn candidate_settings(rows: usize) -> Vec<(usize, Option<u32>)> {
+	let partitions = ((rows as f64).sqrt() as usize).max(2);
+	let mut nprobes: Vec<usize> = [100, 40, 20, 10, 5, 2, 1]
+		.iter()
+		.map(|divisor| (partitions / divisor).max(1))
+		.collect();
+	nprobes.dedup();
+
+	nprobes
+		.into_iter()
+		.flat_map(|n| [None, Some(5), Some(20)].map(|refine| (n, refine)))
+		.collect()
+}
+
+/// Fastest setting reaching `target_recall`, or the one with the best recall
+/// when none does
+fn choose(probes: &[Probe], target_recall: f32) -> Option<&Probe> {
+	let by_latency = |a: &&Probe, b: &&Probe| a.latency_ms.total_cmp(&b.latency_ms);
+	probes
+		.iter()
+		.filter(|probe| probe.recall >= target_recall)
+		.min_by(by_latency)
+		.or_else(|| {
+			probes
+				.iter()
+				.max_by(|a, b| a.recall.total_cmp(&b.recall).then(by_latency(b, a)))
+		})
+}
+
+/// Calibrate one table; None when it has no vector index, as searches are
+/// exact then
+pub async fn tune_table(
+	table: &Table,
+	table_name: &str,
+	options: &TuneOptions,
+) -> Result<Option<TableTuning>> {
+	let indices = table.list_indices().await?;
+	if !indices
+		.iter()
+		.any(|index| index.columns == vec!["embedding"])
+	{
+		return Ok(None);
+	}
+	let rows = table.count_rows(None).await?;
+
+	let samples = sample_vectors(table, options.samples).await?;
+	if samples.is_empty() {
+		return Ok(None);
+	}
+
+	// Exact neighbours of every sample
+	let mut exact = Vec::with_capacity(samples.len());
+	for vector in &samples {
+		let query = table
+			.vector_search(vector.clone())?
+			.distance_type(DistanceType::Cosine)
+			.limit(options.top_k)
+			.bypass_vector_index();
+		exact.push(hashes(query).await?);
+	}
+
+	let mut probes = Vec::new();
+	for (nprobes, refine_factor) in candidate_settings(rows) {
+		let mut found = 0;
+		let mut expected = 0;
+		let started = Instant::now();
+		for (vector, neighbours) in samples.iter().zip(&exact) {
+			let mut query = table
+				.vector_search(vector.clone())?
+				.distance_type(DistanceType::Cosine)
+				.limit(options.top_k)
+				.nprobes(nprobes);
+			if let Some(refine_factor) = refine_factor {
+				query = query.refine_factor(refine_factor);
+			}
+			let approximate = hashes(query).await?;
+			found += neighbours.intersection(&approximate).count();
+			expected += neighbours.len();
+		}
+		let latency_ms = started.elapsed().as_secs_f32() * 1000.0 / samples.len() as f32;
+		probes.push(Probe {
+			nprobes,
+			refine_factor,
+			recall: if expected == 0 {
+				1.0
+			} else {
+				found as f32 / expected as f32
+			},
+			latency_ms,
+		});
+	}
+	probes.sort_by(|a, b| a.latency_ms.total_cmp(&b.latency_ms));
+
+	let Some(chosen) = choose(&probes, options.target_recall).cloned() else {
+		return Ok(None);
+	};
+	tracing::debug!(
+		"Tuned {}: nprobes={}, refine_factor={:?}, recall={:.3}",
+		table_name,
+		chosen.nprobes,
+		chosen.refine_factor,
+		chosen.recall
+	);
+
+	Ok(Some(TableTuning {
+		table: table_name.to_string(),
+		rows,
+		chosen,
+		probes,
+	}))
+}
+
+/// Stored vectors spread over the first rows of the table
+async fn sample_vectors(table: &Table, samples: usize) -> Result<Vec<Vec<f32>>> {
+	let schema = table.schema().await?;
+	let dim = match schema.field_with_name("embedding")?.data_type() {
+		DataType::FixedSizeList(_, size) => *size as usize,
+		_ => return Ok(Vec::new()),
+	};
+	let converter = BatchConverter::new(dim);
+
+	let mut vectors = Vec::new();
+	let mut results = table
+		.query()
+		.select(Select::Columns(vec!["embedding".to_string()]))
+		.limit(SAMPLE_SCAN_ROWS)
+		.execute()
+		.await?;
+	while let Some(batch) = results.try_next().await? {
+		if batch.num_rows() > 0 {
+			vectors.extend(converter.batch_to_embeddings(&batch)?);
+		}
+	}
+
+	let step = (vectors.len() / samples.max(1)).max(1);
+	Ok(vectors.into_iter().step_by(step).take(samples).collect())
+}
+
+/// Hashes of the blocks a query returns
+async fn hashes(query: lancedb::query::VectorQuery) -> Result<HashSet<String>> {
+	let mut found = HashSet::new();
+	let mut results = query
+		.select(Select::Columns(vec!["hash".to_string()]))
+		.execute()
+		.await?;
+	while let Some(batch) = results.try_next().await? {
+		if let Some(column) = batch
+			.column_by_name("hash")
+			.and_then(|column| column.as_any().downcast_ref::<StringArray>())
+		{
+			found.extend(column.iter().flatten().map(String::from));
+		}
+	}
+	Ok(found)
+}
+
+/// Replace the recorded parameters with those of `tunings`
+pub async fn save(db: &Connection, tunings: &[TableTuning]) -> Result<()> {
+	let table_ops = TableOperations::new(db);
+	let schema = Arc::new(Schema::new(vec![
+		Field::new("table_name", DataType::Utf8, false),
+		Field::new("nprobes", DataType::UInt32, false),
+		Field::new("refine_factor", DataType::UInt32, true),
+		Field::new("recall", DataType::Float32, false),
+		Field::new("rows", DataType::Int64, false),
+		Field::new("tuned_at", DataType::Int64, false),
+	]));
+
+	if !table_ops.table_exists(TUNING_TABLE).await? {
+		table_ops
+			.create_table_with_schema(TUNING_TABLE, schema.clone())
+			.await?;
+	}
+	table_ops.clear_table(TUNING_TABLE).await?;
+	if tunings.is_empty() {
+		return Ok(());
+	}
+
+	let now = chrono::Utc::now().timestamp();
+	let batch = RecordBatch::try_new(
+		schema,
+		vec![
+			Arc::new(StringArray::from_iter_values(
+				tunings.iter().map(|tuning| tuning.table.as_str()),
+			)),
+			Arc::new(UInt32Array::from_iter_values(
+				tunings.iter().map(|tuning| tuning.chosen.nprobes as u32),
+			)),
+			Arc::new(UInt32Array::from_iter(
+				tunings.iter().map(|tuning| tuning.chosen.refine_factor),
+			)),
+			Arc::new(Float32Array::from_iter_values(
+				tunings.iter().map(|tuning| tuning.chosen.recall),
+			)),
+			Arc::new(Int64Array::from_iter_values(
+				tunings.iter().map(|tuning| tuning.rows as i64),
+			)),
+			Arc::new(Int64Array::from_iter_values(tunings.iter().map(|_| now))),
+		],
+	)?;
+	table_ops.store_batch(TUNING_TABLE, batch).await
+}
+
+/// Recorded parameters by table name; empty when `store tune` never ran
+pub async fn load(db: &Connection) -> Result<HashMap<String, TunedSearch>> {
+	let mut tuned = HashMap::new();
+	if !TableOperations::new(db).table_exists(TUNING_TABLE).await? {
+		return Ok(tuned);
+	}
+
+	let table = db.open_table(TUNING_TABLE).execute().await?;
+	let mut results = table.query().execute().await?;
+	while let Some(batch) = results.try_next().await? {
+		let column = |name: &str| {
+			batch
+				.column_by_name(name)
+				.ok_or_else(|| anyhow::anyhow!("{} column not found", name))
+		};
+		let names = column("table_name")?
+			.as_any()
+			.downcast_ref::<StringArray>()
+			.ok_or_else(|| anyhow::anyhow!("table_name is not a string column"))?;
+		let nprobes = column("nprobes")?
+			.as_any()
+			.downcast_ref::<UInt32Array>()
+			.ok_or_else(|| anyhow::anyhow!("nprobes is not a UInt32 column"))?;
+		let refine_factors = column("refine_factor")?
+			.as_any()
+			.downcast_ref::<UInt32Array>()
+			.ok_or_else(|| anyhow::anyhow!("refine_factor is not a UInt32 column"))?;
+		let rows = column("rows")?
+			.as_any()
+			.downcast_ref::<Int64Array>()
+			.ok_or_else(|| anyhow::anyhow!("rows is not an Int64 column"))?;
+
+		for row in 0..batch.num_rows() {
+			tuned.insert(
+				names.value(row).to_string(),
+				TunedSearch {
+					params: SearchParams {
+						nprobes: nprobes.value(row) as usize,
+						refine_factor: (!refine_factors.is_null(row))
+							.then(|| refine_factors.value(row)),
+					},
+					rows: rows.value(row) as usize,
+				},
+			);
+		}
+	}
+	Ok(tuned)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn probe(nprobes: usize, recall: f32, latency_ms: f32) -> Probe {
+		Probe {
+			nprobes,
+			refine_factor: None,
+			recall,
+			latency_ms,
+		}
+	}
+
+	#[test]
+	fn test_choose() {
+		let probes = vec![probe(1, 0.7, 1.0), probe(4, 0.96, 2.0), probe(16, 1.0, 5.0)];
+		assert_eq!(choose(&probes, 0.95).unwrap().nprobes, 4);
+		assert_eq!(choose(&probes, 0.99).unwrap().nprobes, 16);
+		// Nothing reaches the target: best recall wins
+		let probes = vec![probe(1, 0.5, 1.0), probe(4, 0.8, 2.0)];
+		assert_eq!(choose(&probes, 0.95).unwrap().nprobes, 4);
+		assert!(choose(&[], 0.95).is_none());
+
+		let settings = candidate_settings(10_000);
+		assert_eq!(settings.first(), Some(&(1, None)));
+		assert_eq!(settings.last(), Some(&(100, Some(20))));
+
+		let tuned = TunedSearch {
+			params: SearchParams {
+				nprobes: 4,
+				refine_factor: None,
+			},
+			rows: 1000,
+		};
+		assert!(tuned.applies_to(1800));
+		assert!(!tuned.applies_to(2500));
+		assert!(!tuned.applies_to(400));
+	}
+}
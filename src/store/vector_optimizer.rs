@@ -20,8 +20,24 @@
 //! - System capabilities
 //! - LanceDB best practices
 
+use std::collections::HashSet;
+use std::sync::{LazyLock, Mutex};
+
+use lancedb::arrow::SendableRecordBatchStream;
+use lancedb::query::ExecutableQuery;
 use lancedb::Table;
 use lancedb::{query::VectorQuery, DistanceType};
+use serde::Serialize;
+
+use crate::store::tuning::TunedSearch;
+
+/// Row count from which a table gets a vector index; smaller tables are
+/// searched exactly
+pub const MIN_INDEXED_ROWS: usize = 1000;
+
+/// Tables already reported as searched exactly, so each is reported once
+static EXACT_SEARCH_WARNED: LazyLock<Mutex<HashSet<String>>> =
+	LazyLock::new(|| Mutex::new(HashSet::new()));
 
 /// Vector index optimization parameters automatically calculated from dataset characteristics
 #[derive(Debug, Clone)]
@@ -40,6 +56,46 @@ pub struct SearchParams {
 	pub refine_factor: Option<u32>,
 }
 
+/// State of the vector index of a table, as far as searches are concerned
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum IndexHealth {
+	/// Too small to need an index; searched exactly
+	NotNeeded,
+	/// Large enough for an index that has not been built
+	Missing,
+	/// Built, but most rows were added after it and are not in it
+	Partial { indexed: usize, unindexed: usize },
+	/// Built and covering most rows
+	Ready { indexed: usize, unindexed: usize },
+}
+
+impl IndexHealth {
+	/// Classify from the table size and the statistics of its embedding
+	/// index, if it has one
+	pub fn classify(row_count: usize, index: Option<(usize, usize)>) -> Self {
+		match index {
+			Some((indexed, unindexed)) if unindexed > indexed => {
+				Self::Partial { indexed, unindexed }
+			}
+			Some((indexed, unindexed)) => Self::Ready { indexed, unindexed },
+			None if row_count < MIN_INDEXED_ROWS => Self::NotNeeded,
+			None => Self::Missing,
+		}
+	}
+}
+
+/// What `octocode store build-index` did to the vector index of a table
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexBuild {
+	pub table: String,
+	pub rows: usize,
+	/// "created", "rebuilt", "updated", "unchanged" or "skipped" (too small)
+	pub action: &'static str,
+	/// State once done
+	pub health: IndexHealth,
+}
+
 /// Intelligent vector index optimizer
 pub struct VectorOptimizer;
 
@@ -60,51 +116,131 @@ impl VectorOptimizer {
 	/// # Returns
 	/// The optimized query with applied search parameters
 	pub async fn optimize_query(
+		query: VectorQuery,
+		table: &Table,
+		table_name: &str,
+	) -> Result<VectorQuery, lancedb::Error> {
+		Self::optimize_query_tuned(query, table, table_name, None).await
+	}
+
+	/// Like [`Self::optimize_query`], preferring parameters recorded by
+	/// `octocode store tune` while they still match the table's size
+	pub async fn optimize_query_tuned(
 		mut query: VectorQuery,
 		table: &Table,
 		table_name: &str,
+		tuned: Option<&TunedSearch>,
 	) -> Result<VectorQuery, lancedb::Error> {
-		// Get table statistics
 		let row_count = table.count_rows(None).await?;
-		let indices = table.list_indices().await?;
-		let has_index = indices.iter().any(|idx| idx.columns == vec!["embedding"]);
-
-		// Apply intelligent search optimization if index exists
-		if has_index {
-			// Estimate partition count from row count
-			let estimated_partitions = if row_count < 1000 {
-				2
-			} else {
-				(row_count as f64).sqrt() as u32
-			};
+		let health = match Self::index_health(table, row_count).await {
+			Ok(health) => health,
+			Err(e) => {
+				warn_exact_search(
+					table_name,
+					&format!("its vector index could not be read ({})", e),
+				);
+				return Ok(query.bypass_vector_index());
+			}
+		};
 
-			let search_params = Self::calculate_search_params(estimated_partitions, row_count);
+		match health {
+			IndexHealth::Ready { .. } => {
+				// Estimate partition count from row count
+				let estimated_partitions = if row_count < MIN_INDEXED_ROWS {
+					2
+				} else {
+					(row_count as f64).sqrt() as u32
+				};
+
+				let search_params = match tuned.filter(|tuned| tuned.applies_to(row_count)) {
+					Some(tuned) => tuned.params.clone(),
+					None => Self::calculate_search_params(estimated_partitions, row_count),
+				};
+
+				query = query.nprobes(search_params.nprobes);
+				if let Some(refine_factor) = search_params.refine_factor {
+					query = query.refine_factor(refine_factor);
+				}
 
-			query = query.nprobes(search_params.nprobes);
-			if let Some(refine_factor) = search_params.refine_factor {
-				query = query.refine_factor(refine_factor);
+				tracing::debug!(
+					"Applied search optimization to {}: nprobes={}, refine_factor={:?}, rows={}, health={:?}",
+					table_name,
+					search_params.nprobes,
+					search_params.refine_factor,
+					row_count,
+					health
+				);
+			}
+			IndexHealth::Partial { indexed, unindexed } => {
+				// Probing an index trained on a fraction of the rows misses
+				// neighbours; the exact scan is what the index would return
+				warn_exact_search(
+					table_name,
+					&format!(
+						"its vector index covers only {} of {} rows",
+						indexed,
+						indexed + unindexed
+					),
+				);
+				query = query.bypass_vector_index();
+			}
+			IndexHealth::Missing => {
+				// Without an index LanceDB scans all rows anyway
+				warn_exact_search(
+					table_name,
+					&format!("it has {} rows but no vector index", row_count),
+				);
+			}
+			IndexHealth::NotNeeded => {
+				tracing::debug!(
+					"No index needed for {}, using exact search (rows={})",
+					table_name,
+					row_count
+				);
 			}
-
-			tracing::debug!(
-				"Applied search optimization to {}: nprobes={}, refine_factor={:?}, rows={}, has_index={}",
-				table_name,
-				search_params.nprobes,
-				search_params.refine_factor,
-				row_count,
-				has_index
-			);
-		} else {
-			tracing::debug!(
-				"No index found for {}, using default search (rows={}, has_index={})",
-				table_name,
-				row_count,
-				has_index
-			);
 		}
 
 		Ok(query)
 	}
 
+	/// State of the embedding index of `table`, which has `row_count` rows
+	pub async fn index_health(
+		table: &Table,
+		row_count: usize,
+	) -> Result<IndexHealth, lancedb::Error> {
+		let indices = table.list_indices().await?;
+		let Some(index) = indices.iter().find(|idx| idx.columns == vec!["embedding"]) else {
+			return Ok(IndexHealth::classify(row_count, None));
+		};
+		// An index listed without statistics is still being built
+		let stats = table
+			.index_stats(&index.name)
+			.await?
+			.map(|stats| (stats.num_indexed_rows, stats.num_unindexed_rows));
+		Ok(IndexHealth::classify(
+			row_count,
+			Some(stats.unwrap_or((0, row_count))),
+		))
+	}
+
+	/// Run a vector query, retrying it as an exact search when the index
+	/// cannot serve it
+	pub async fn execute_with_fallback(
+		query: VectorQuery,
+		table_name: &str,
+	) -> Result<SendableRecordBatchStream, lancedb::Error> {
+		match query.clone().execute().await {
+			Ok(results) => Ok(results),
+			Err(e) => {
+				warn_exact_search(
+					table_name,
+					&format!("searching its vector index failed ({})", e),
+				);
+				query.bypass_vector_index().execute().await
+			}
+		}
+	}
+
 	/// Calculate optimal index parameters based on dataset characteristics
 	///
 	/// Based on LanceDB documentation and best practices:
@@ -121,7 +257,7 @@ impl VectorOptimizer {
 	pub fn calculate_index_params(row_count: usize, vector_dimension: usize) -> VectorIndexParams {
 		// LanceDB performs excellently with brute force search up to ~100K rows
 		// For smaller datasets, indexing overhead outweighs benefits
-		if row_count < 1000 {
+		if row_count < MIN_INDEXED_ROWS {
 			tracing::debug!(
 				"Dataset size {} is small, skipping index creation (brute force will be faster)",
 				row_count
@@ -347,6 +483,23 @@ impl VectorOptimizer {
 	}
 }
 
+/// Report once per table that it is searched exactly, and why
+fn warn_exact_search(table_name: &str, reason: &str) {
+	let first = EXACT_SEARCH_WARNED
+		.lock()
+		.map(|mut warned| warned.insert(table_name.to_string()))
+		.unwrap_or(true);
+	if first {
+		tracing::warn!("Searching {} exactly, as {}", table_name, reason);
+		eprintln!(
+			"Warning: searching {} exactly, as {}. Results are complete but slower until it is indexed.",
+			table_name, reason
+		);
+	} else {
+		tracing::debug!("Searching {} exactly, as {}", table_name, reason);
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -391,6 +544,26 @@ mod tests {
 		assert!(search_params.refine_factor.is_some());
 	}
 
+	#[test]
+	fn test_index_health() {
+		assert_eq!(IndexHealth::classify(500, None), IndexHealth::NotNeeded);
+		assert_eq!(IndexHealth::classify(5000, None), IndexHealth::Missing);
+		assert_eq!(
+			IndexHealth::classify(5000, Some((1000, 4000))),
+			IndexHealth::Partial {
+				indexed: 1000,
+				unindexed: 4000
+			}
+		);
+		assert_eq!(
+			IndexHealth::classify(5000, Some((4000, 1000))),
+			IndexHealth::Ready {
+				indexed: 4000,
+				unindexed: 1000
+			}
+		);
+	}
+
 	#[test]
 	fn test_growth_optimization() {
 		// Should optimize at growth milestones when index exists
@@ -46,6 +46,8 @@ pub struct IgnorePatterns {
 	gitignore_patterns: HashSet<String>,
 	noindex_patterns: HashSet<String>,
 	working_directory: PathBuf,
+	/// Directories watched despite the patterns (watch --include-extra)
+	extra_dirs: Vec<String>,
 }
 
 impl IgnorePatterns {
@@ -55,6 +57,7 @@ impl IgnorePatterns {
 			gitignore_patterns: HashSet::new(),
 			noindex_patterns: HashSet::new(),
 			working_directory,
+			extra_dirs: Vec::new(),
 		};
 
 		ignore_patterns.load_gitignore();
@@ -62,6 +65,12 @@ impl IgnorePatterns {
 		ignore_patterns
 	}
 
+	/// Never ignore paths in these directories, relative to the working directory
+	pub fn with_extra_dirs(mut self, extra_dirs: Vec<String>) -> Self {
+		self.extra_dirs = extra_dirs;
+		self
+	}
+
 	/// Load patterns from .gitignore file
 	fn load_gitignore(&mut self) {
 		let gitignore_path = self.working_directory.join(".gitignore");
@@ -103,6 +112,10 @@ impl IgnorePatterns {
 			path_str.to_string()
 		};
 
+		if crate::indexer::extra_dirs::contains(&self.extra_dirs, &relative_path) {
+			return false;
+		}
+
 		// Check gitignore patterns
 		if self.matches_patterns(&relative_path, &self.gitignore_patterns) {
 			return true;
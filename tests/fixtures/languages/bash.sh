@@ -0,0 +1,31 @@
+#!/usr/bin/env bash
+set -euo pipefail
+
+BACKUP_DIR="${BACKUP_DIR:-/var/backups}"
+RETENTION_DAYS=14
+
+# Prints a timestamped message
+log() {
+	echo "[$(date +%H:%M:%S)] $*"
+}
+
+create_backup() {
+	local source="$1"
+	local target="$BACKUP_DIR/$(basename "$source")-$(date +%F).tar.gz"
+	tar -czf "$target" "$source"
+	log "created $target"
+}
+
+prune_backups() {
+	find "$BACKUP_DIR" -name '*.tar.gz' -mtime +"$RETENTION_DAYS" -delete
+	log "pruned backups older than $RETENTION_DAYS days"
+}
+
+main() {
+	for dir in "$@"; do
+		create_backup "$dir"
+	done
+	prune_backups
+}
+
+main "$@"
@@ -0,0 +1,41 @@
+#include <string>
+#include <vector>
+
+namespace geometry {
+
+constexpr double kEpsilon = 1e-9;
+
+// A point in the plane
+struct Point {
+	double x;
+	double y;
+};
+
+class Polygon {
+public:
+	explicit Polygon(std::vector<Point> points) : points_(std::move(points)) {}
+
+	double area() const {
+		double sum = 0.0;
+		for (size_t i = 0; i < points_.size(); ++i) {
+			const Point& a = points_[i];
+			const Point& b = points_[(i + 1) % points_.size()];
+			sum += a.x * b.y - b.x * a.y;
+		}
+		return sum / 2.0;
+	}
+
+private:
+	std::vector<Point> points_;
+};
+
+template <typename T>
+T clamp(T value, T low, T high) {
+	return value < low ? low : (value > high ? high : value);
+}
+
+bool nearly_equal(double a, double b) {
+	return (a - b) < kEpsilon && (b - a) < kEpsilon;
+}
+
+}  // namespace geometry
@@ -0,0 +1,56 @@
+import 'package:flutter/material.dart';
+import 'package:counter/src/storage.dart';
+import '../theme.dart' show AppTheme;
+
+typedef CounterCallback = void Function(int value);
+
+enum CounterMode { increment, decrement }
+
+/// Keeps the count in storage
+mixin Persisted on ChangeNotifier {
+  Future<void> save(int value) => Storage.instance.write('count', value);
+}
+
+extension Clamp on int {
+  int clampTo(int max) => this > max ? max : this;
+}
+
+class CounterModel extends ChangeNotifier with Persisted {
+  int _value = 0;
+
+  int get value => _value;
+
+  void change(CounterMode mode) {
+    _value += mode == CounterMode.increment ? 1 : -1;
+    save(_value);
+    notifyListeners();
+  }
+}
+
+class CounterPage extends StatefulWidget {
+  const CounterPage({super.key, required this.onChanged});
+
+  final CounterCallback onChanged;
+
+  @override
+  State<CounterPage> createState() => _CounterPageState();
+}
+
+class _CounterPageState extends State<CounterPage> {
+  final CounterModel _model = CounterModel();
+
+  @override
+  Widget build(BuildContext context) {
+    return Scaffold(
+      body: Center(child: Text('${_model.value.clampTo(99)}')),
+      floatingActionButton: FloatingActionButton(
+        onPressed: () => _model.change(CounterMode.increment),
+        child: const Icon(Icons.add),
+      ),
+    );
+  }
+}
+
+void main() {
+  runApp(MaterialApp(theme: AppTheme.light, home: CounterPage(onChanged: print)));
+}
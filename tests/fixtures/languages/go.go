@@ -0,0 +1,52 @@
+package inventory
+
+import (
+	"errors"
+	"sync"
+)
+
+// ErrNotFound is returned for unknown SKUs
+var ErrNotFound = errors.New("item not found")
+
+const defaultCapacity = 64
+
+// Item is a stocked product
+type Item struct {
+	SKU      string
+	Quantity int
+}
+
+// Store keeps stock levels safe for concurrent use
+type Store struct {
+	mu    sync.Mutex
+	items map[string]*Item
+}
+
+type Reader interface {
+	Get(sku string) (*Item, error)
+}
+
+// NewStore creates an empty store
+func NewStore() *Store {
+	return &Store{items: make(map[string]*Item, defaultCapacity)}
+}
+
+func (s *Store) Get(sku string) (*Item, error) {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+	item, ok := s.items[sku]
+	if !ok {
+		return nil, ErrNotFound
+	}
+	return item, nil
+}
+
+func (s *Store) Add(sku string, quantity int) {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+	if item, ok := s.items[sku]; ok {
+		item.Quantity += quantity
+		return
+	}
+	s.items[sku] = &Item{SKU: sku, Quantity: quantity}
+}
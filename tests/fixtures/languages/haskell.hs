@@ -0,0 +1,52 @@
+-- | Shapes and their geometry
+module Geometry.Shape
+  ( Shape (..)
+  , Point (..)
+  , HasArea (..)
+  , area
+  , scale
+  ) where
+
+import qualified Data.Map.Strict as Map
+import Data.List (sortOn)
+
+data Point = Point
+  { px :: Double
+  , py :: Double
+  } deriving (Show, Eq)
+
+data Shape
+  = Circle Point Double
+  | Rectangle Point Point
+  deriving (Show)
+
+newtype Name = Name String
+
+type Registry = Map.Map String Shape
+
+class HasArea a where
+  areaOf :: a -> Double
+  perimeter :: a -> Double
+
+instance HasArea Shape where
+  areaOf = area
+  perimeter (Circle _ r) = 2 * pi * r
+  perimeter (Rectangle (Point x1 y1) (Point x2 y2)) = 2 * (abs (x2 - x1) + abs (y2 - y1))
+
+-- | Area of a shape
+area :: Shape -> Double
+area (Circle _ r) = pi * r * r
+area (Rectangle (Point x1 y1) (Point x2 y2)) = abs (x2 - x1) * abs (y2 - y1)
+
+scale :: Double -> Shape -> Shape
+scale k (Circle c r) = Circle c (k * r)
+scale k (Rectangle a b) = Rectangle a (stretch b)
+  where
+    stretch (Point x y) = Point (k * x) (k * y)
+
+largest :: [Shape] -> Maybe Shape
+largest [] = Nothing
+largest shapes = Just (last (sortOn area shapes))
+
+defaultRegistry :: Registry
+defaultRegistry = Map.fromList [("unit", Circle (Point 0 0) 1)]
@@ -0,0 +1,40 @@
+terraform {
+  required_version = ">= 1.5"
+}
+
+provider "aws" {
+  region = var.region
+}
+
+variable "region" {
+  type    = string
+  default = "eu-west-1"
+}
+
+locals {
+  bucket_name = "assets-${var.region}"
+}
+
+resource "aws_s3_bucket" "assets" {
+  bucket = local.bucket_name
+
+  tags = {
+    Team = "platform"
+  }
+}
+
+data "aws_iam_policy_document" "read" {
+  statement {
+    actions   = ["s3:GetObject"]
+    resources = ["${aws_s3_bucket.assets.arn}/*"]
+  }
+}
+
+module "cdn" {
+  source = "./modules/cdn"
+  origin = aws_s3_bucket.assets.bucket_regional_domain_name
+}
+
+output "bucket_arn" {
+  value = aws_s3_bucket.assets.arn
+}
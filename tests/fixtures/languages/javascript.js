@@ -0,0 +1,41 @@
+import { EventEmitter } from 'events';
+import fetch from 'node-fetch';
+
+const DEFAULT_TIMEOUT = 5000;
+const RETRIES = 3;
+
+/**
+ * HTTP client with retries
+ */
+export class Client extends EventEmitter {
+	constructor(baseUrl, options = {}) {
+		super();
+		this.baseUrl = baseUrl;
+		this.timeout = options.timeout || DEFAULT_TIMEOUT;
+	}
+
+	async get(path) {
+		for (let attempt = 0; attempt < RETRIES; attempt++) {
+			try {
+				const response = await fetch(this.baseUrl + path);
+				this.emit('response', response.status);
+				return await response.json();
+			} catch (error) {
+				this.emit('retry', attempt);
+			}
+		}
+		throw new Error(`GET ${path} failed`);
+	}
+}
+
+// Builds a query string from an object
+export function toQuery(params) {
+	return Object.entries(params)
+		.map(([key, value]) => `${encodeURIComponent(key)}=${encodeURIComponent(value)}`)
+		.join('&');
+}
+
+export const withTimeout = (promise, ms) =>
+	Promise.race([promise, new Promise((_, reject) => setTimeout(() => reject(new Error('timeout')), ms))]);
+
+export default Client;
@@ -0,0 +1,36 @@
+-- Neovim plugin module
+local config = require("plugin.config")
+local util = require "plugin.util"
+
+local M = {
+  enabled = true,
+  version = "1.0.0",
+}
+
+local namespace = vim.api.nvim_create_namespace("plugin")
+
+--- Merge user options into the defaults
+function M.setup(opts)
+  M.options = vim.tbl_deep_extend("force", config.defaults, opts or {})
+  M.enabled = true
+end
+
+local function highlight(buf, line)
+  vim.api.nvim_buf_add_highlight(buf, namespace, "Search", line, 0, -1)
+end
+
+function M:render(buf)
+  for i, line in ipairs(self.lines) do
+    if util.matches(line) then
+      highlight(buf, i - 1)
+    end
+  end
+end
+
+M.toggle = function()
+  M.enabled = not M.enabled
+end
+
+PluginVersion = M.version
+
+return M
@@ -0,0 +1,49 @@
+<?php
+
+namespace App\Billing;
+
+use App\Models\Customer;
+use InvalidArgumentException;
+
+interface Gateway
+{
+    public function charge(int $amount, string $token): bool;
+}
+
+/**
+ * Issues invoices and charges customers
+ */
+class InvoiceService
+{
+    private const CURRENCY = 'EUR';
+
+    public function __construct(private Gateway $gateway)
+    {
+    }
+
+    public function bill(Customer $customer, int $amount): bool
+    {
+        if ($amount <= 0) {
+            throw new InvalidArgumentException('Amount must be positive');
+        }
+        return $this->gateway->charge($amount, $customer->paymentToken());
+    }
+
+    private function format(int $amount): string
+    {
+        return sprintf('%.2f %s', $amount / 100, self::CURRENCY);
+    }
+}
+
+trait Discountable
+{
+    public function discount(int $amount, int $percent): int
+    {
+        return intdiv($amount * (100 - $percent), 100);
+    }
+}
+
+function cents(float $value): int
+{
+    return (int) round($value * 100);
+}
@@ -0,0 +1,46 @@
+"""Order processing helpers."""
+
+import json
+from dataclasses import dataclass
+from typing import List, Optional
+
+TAX_RATE = 0.2
+
+
+@dataclass
+class Item:
+    name: str
+    price: float
+    quantity: int = 1
+
+
+class Order:
+    """A customer order."""
+
+    def __init__(self, customer: str, items: Optional[List[Item]] = None):
+        self.customer = customer
+        self.items = items or []
+
+    def add(self, item: Item) -> None:
+        self.items.append(item)
+
+    @property
+    def total(self) -> float:
+        subtotal = sum(item.price * item.quantity for item in self.items)
+        return round(subtotal * (1 + TAX_RATE), 2)
+
+
+# Serializes an order for the API
+def to_json(order: Order) -> str:
+    return json.dumps(
+        {
+            "customer": order.customer,
+            "items": [item.__dict__ for item in order.items],
+            "total": order.total,
+        }
+    )
+
+
+async def submit(client, order: Order) -> bool:
+    response = await client.post("/orders", data=to_json(order))
+    return response.status == 201
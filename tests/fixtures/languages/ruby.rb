@@ -0,0 +1,34 @@
+require 'json'
+
+module Shipping
+  FREE_THRESHOLD = 50
+
+  # Calculates shipping costs per region
+  class Calculator
+    attr_reader :rates
+
+    def initialize(rates)
+      @rates = rates
+    end
+
+    def cost(region, subtotal)
+      return 0 if subtotal >= FREE_THRESHOLD
+
+      rates.fetch(region) { raise ArgumentError, "unknown region #{region}" }
+    end
+
+    def self.from_json(path)
+      new(JSON.parse(File.read(path)))
+    end
+
+    private
+
+    def round_up(value)
+      (value * 100).ceil / 100.0
+    end
+  end
+
+  def self.regions
+    %w[eu us asia]
+  end
+end
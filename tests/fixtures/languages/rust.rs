@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+use std::fmt;
+
+/// Maximum number of cached entries
+const MAX_ENTRIES: usize = 128;
+static DEFAULT_NAME: &str = "cache";
+
+/// Least-recently-used cache keyed by string
+pub struct Cache {
+	entries: HashMap<String, String>,
+	order: Vec<String>,
+}
+
+pub enum Lookup {
+	Hit(String),
+	Miss,
+}
+
+pub trait Store {
+	fn get(&self, key: &str) -> Option<&String>;
+}
+
+impl Cache {
+	pub fn new() -> Self {
+		Self {
+			entries: HashMap::new(),
+			order: Vec::new(),
+		}
+	}
+
+	// Evicts the oldest entry when full
+	pub fn insert(&mut self, key: String, value: String) {
+		if self.order.len() >= MAX_ENTRIES {
+			let oldest = self.order.remove(0);
+			self.entries.remove(&oldest);
+		}
+		self.order.push(key.clone());
+		self.entries.insert(key, value);
+	}
+}
+
+impl Store for Cache {
+	fn get(&self, key: &str) -> Option<&String> {
+		self.entries.get(key)
+	}
+}
+
+impl fmt::Display for Cache {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{} ({} entries)", DEFAULT_NAME, self.entries.len())
+	}
+}
+
+macro_rules! cached {
+	($cache:expr, $key:expr) => {
+		$cache.get($key)
+	};
+}
+
+mod tests {
+	fn helper() -> usize {
+		super::MAX_ENTRIES
+	}
+}
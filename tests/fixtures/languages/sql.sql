@@ -0,0 +1,27 @@
+-- Accounts and their orders
+CREATE TABLE accounts (
+    id BIGSERIAL PRIMARY KEY,
+    email TEXT NOT NULL UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE TABLE orders (
+    id BIGSERIAL PRIMARY KEY,
+    account_id BIGINT NOT NULL REFERENCES accounts (id),
+    total NUMERIC(10, 2) NOT NULL,
+    placed_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX orders_account_idx ON orders (account_id);
+
+CREATE VIEW account_totals AS
+SELECT a.id, a.email, SUM(o.total) AS lifetime_value
+FROM accounts a
+JOIN orders o ON o.account_id = a.id
+GROUP BY a.id, a.email;
+
+ALTER TABLE orders ADD COLUMN status TEXT NOT NULL DEFAULT 'pending';
+
+CREATE FUNCTION order_count(account BIGINT) RETURNS BIGINT AS $$
+    SELECT COUNT(*) FROM orders WHERE account_id = account;
+$$ LANGUAGE sql;
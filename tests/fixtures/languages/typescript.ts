@@ -0,0 +1,42 @@
+import { Logger } from './logger';
+
+export interface User {
+	id: number;
+	name: string;
+	email?: string;
+}
+
+export type UserId = User['id'];
+
+export enum Role {
+	Admin = 'admin',
+	Member = 'member',
+}
+
+/** Repository of users backed by a map */
+export class UserRepository {
+	private users = new Map<UserId, User>();
+
+	constructor(private readonly logger: Logger) {}
+
+	add(user: User): void {
+		this.users.set(user.id, user);
+		this.logger.info(`added user ${user.id}`);
+	}
+
+	find(id: UserId): User | undefined {
+		return this.users.get(id);
+	}
+}
+
+export function isAdmin(user: User, roles: Map<UserId, Role>): boolean {
+	return roles.get(user.id) === Role.Admin;
+}
+
+export const byName = (a: User, b: User): number => a.name.localeCompare(b.name);
+
+namespace Validation {
+	export function isEmail(value: string): boolean {
+		return /^[^@]+@[^@]+$/.test(value);
+	}
+}
@@ -0,0 +1,44 @@
+const std = @import("std");
+const math = @import("math.zig");
+
+/// A point on the plane
+pub const Point = struct {
+    x: f32,
+    y: f32,
+
+    pub fn init(x: f32, y: f32) Point {
+        return .{ .x = x, .y = y };
+    }
+
+    pub fn distance(self: Point, other: Point) f32 {
+        return math.hypot(self.x - other.x, self.y - other.y);
+    }
+};
+
+pub const Shape = enum {
+    circle,
+    square,
+};
+
+const ParseError = error{
+    InvalidCharacter,
+    Overflow,
+};
+
+const max_points = 64;
+
+comptime {
+    std.debug.assert(max_points > 0);
+}
+
+pub fn parseCount(text: []const u8) ParseError!u32 {
+    return std.fmt.parseInt(u32, text, 10) catch ParseError.InvalidCharacter;
+}
+
+fn helper() void {}
+
+test "points are apart" {
+    const a = Point.init(0, 0);
+    const b = Point.init(3, 4);
+    try std.testing.expectEqual(@as(f32, 5), a.distance(b));
+}